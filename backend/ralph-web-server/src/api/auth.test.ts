@@ -0,0 +1,164 @@
+/**
+ * Token Authentication Tests
+ *
+ * Tests for the optional dashboard token:
+ * - Requests without a token are rejected with 401
+ * - Bearer header, cookie and query parameter are accepted
+ * - /health stays reachable
+ * - Reads and mutations can be protected independently
+ * - Rejections don't reveal whether a loop exists
+ * - No token configured means no auth
+ */
+
+import { describe, it, beforeEach } from "node:test";
+import assert from "node:assert/strict";
+import { createServer } from "./server.js";
+import { TOKEN_COOKIE, isMutating, type AuthScope } from "./auth.js";
+import { initializeDatabase, getDatabase } from "../db/connection.js";
+import { LoopsManager } from "../services/LoopsManager.js";
+import type { FastifyInstance } from "fastify";
+
+const TOKEN = "s3cret-token";
+
+async function setupServer(
+  authToken?: string,
+  authScope?: AuthScope,
+  loopsManager?: LoopsManager
+): Promise<FastifyInstance> {
+  initializeDatabase(getDatabase(":memory:"));
+  return createServer({ db: getDatabase(), logger: false, authToken, authScope, loopsManager });
+}
+
+/** A LoopsManager that knows a single loop, "loop-1" */
+function singleLoopManager(): LoopsManager {
+  const manager = new LoopsManager();
+  manager.stopLoop = async (id: string) => {
+    if (id !== "loop-1") {
+      throw new Error(`Loop not found: ${id}`);
+    }
+  };
+  return manager;
+}
+
+function stopLoop(server: FastifyInstance, id: string, token?: string) {
+  return server.inject({
+    method: "POST",
+    url: "/trpc/loops.stop",
+    headers: {
+      "content-type": "application/json",
+      ...(token ? { authorization: `Bearer ${token}` } : {}),
+    },
+    payload: JSON.stringify({ id }),
+  });
+}
+
+describe("token authentication", () => {
+  let server: FastifyInstance;
+
+  beforeEach(async () => {
+    server = await setupServer(TOKEN);
+  });
+
+  it("rejects requests without a token", async () => {
+    const res = await server.inject({ method: "GET", url: "/api/v1/tasks" });
+    assert.equal(res.statusCode, 401);
+    assert.deepEqual(res.json(), { error: "Unauthorized" });
+  });
+
+  it("rejects a wrong token", async () => {
+    const res = await server.inject({
+      method: "GET",
+      url: "/api/v1/tasks",
+      headers: { authorization: "Bearer nope" },
+    });
+    assert.equal(res.statusCode, 401);
+  });
+
+  it("accepts a bearer token", async () => {
+    const res = await server.inject({
+      method: "GET",
+      url: "/api/v1/tasks",
+      headers: { authorization: `Bearer ${TOKEN}` },
+    });
+    assert.equal(res.statusCode, 200);
+  });
+
+  it("accepts the token cookie", async () => {
+    const res = await server.inject({
+      method: "GET",
+      url: "/api/v1/tasks",
+      headers: { cookie: `theme=dark; ${TOKEN_COOKIE}=${TOKEN}` },
+    });
+    assert.equal(res.statusCode, 200);
+  });
+
+  it("accepts the token query parameter", async () => {
+    const res = await server.inject({ method: "GET", url: `/api/v1/tasks?token=${TOKEN}` });
+    assert.equal(res.statusCode, 200);
+  });
+
+  it("leaves /health open", async () => {
+    const res = await server.inject({ method: "GET", url: "/health" });
+    assert.equal(res.statusCode, 200);
+  });
+});
+
+describe("per-route scope", () => {
+  it("classifies methods", () => {
+    assert.equal(isMutating("GET"), false);
+    assert.equal(isMutating("head"), false);
+    assert.equal(isMutating("OPTIONS"), false);
+    assert.equal(isMutating("POST"), true);
+    assert.equal(isMutating("PATCH"), true);
+    assert.equal(isMutating("DELETE"), true);
+  });
+
+  it("lets reads through when only mutations are protected", async () => {
+    const server = await setupServer(TOKEN, { reads: false }, singleLoopManager());
+
+    const read = await server.inject({ method: "GET", url: "/api/v1/tasks" });
+    assert.equal(read.statusCode, 200);
+
+    const stop = await stopLoop(server, "loop-1");
+    assert.equal(stop.statusCode, 401);
+    assert.deepEqual(stop.json(), { error: "Unauthorized" });
+  });
+
+  it("runs mutations with the token", async () => {
+    const server = await setupServer(TOKEN, { reads: false }, singleLoopManager());
+    const res = await stopLoop(server, "loop-1", TOKEN);
+    assert.equal(res.statusCode, 200);
+  });
+
+  it("lets mutations through when only reads are protected", async () => {
+    const server = await setupServer(TOKEN, { mutations: false }, singleLoopManager());
+
+    const read = await server.inject({ method: "GET", url: "/api/v1/tasks" });
+    assert.equal(read.statusCode, 401);
+
+    const stop = await stopLoop(server, "loop-1");
+    assert.equal(stop.statusCode, 200);
+  });
+
+  it("rejects known and unknown loops identically", async () => {
+    const server = await setupServer(TOKEN, {}, singleLoopManager());
+
+    const known = await stopLoop(server, "loop-1");
+    const unknown = await stopLoop(server, "no-such-loop");
+    assert.equal(known.statusCode, 401);
+    assert.equal(unknown.statusCode, 401);
+    assert.equal(known.body, unknown.body);
+
+    // With the token, the difference is visible
+    const authorized = await stopLoop(server, "no-such-loop", TOKEN);
+    assert.notEqual(authorized.statusCode, 401);
+  });
+});
+
+describe("without a token", () => {
+  it("does not require auth", async () => {
+    const server = await setupServer();
+    const res = await server.inject({ method: "GET", url: "/api/v1/tasks" });
+    assert.equal(res.statusCode, 200);
+  });
+});
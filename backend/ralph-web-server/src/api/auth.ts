@@ -0,0 +1,111 @@
+/**
+ * Token Authentication
+ *
+ * Optional shared-token auth for exposing the dashboard beyond localhost.
+ * When a token is configured, every request must present it as:
+ * - an `Authorization: Bearer <token>` header,
+ * - a `ralph_web_token` cookie (set by the dashboard dev server), or
+ * - a `token` query parameter.
+ *
+ * Reads (GET/HEAD/OPTIONS, which covers tRPC queries and WebSocket upgrades)
+ * and mutations (every other method, e.g. tRPC mutations such as `loops.stop`)
+ * can be protected independently. Rejected requests get the same 401 whatever
+ * the route, so an unauthenticated client can't tell which loops exist.
+ * `/health` stays open for probes.
+ */
+
+import { timingSafeEqual } from "crypto";
+import type { FastifyInstance, FastifyRequest } from "fastify";
+
+/** Cookie carrying the dashboard token */
+export const TOKEN_COOKIE = "ralph_web_token";
+
+/** Paths that never require a token */
+const PUBLIC_PATHS = new Set(["/health"]);
+
+/** Methods that only read state */
+const READ_METHODS = new Set(["GET", "HEAD", "OPTIONS"]);
+
+/** Which kinds of request must carry the token */
+export interface AuthScope {
+  /** Protect read-only requests (default: true) */
+  reads?: boolean;
+  /** Protect mutating requests (default: true) */
+  mutations?: boolean;
+}
+
+function tokensEqual(candidate: string, expected: string): boolean {
+  const a = Buffer.from(candidate);
+  const b = Buffer.from(expected);
+  return a.length === b.length && timingSafeEqual(a, b);
+}
+
+function cookieValue(header: string | undefined, name: string): string | undefined {
+  if (!header) return undefined;
+  for (const part of header.split(";")) {
+    const [key, ...rest] = part.trim().split("=");
+    if (key === name) {
+      return decodeURIComponent(rest.join("="));
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Extract every token candidate a request presents.
+ */
+export function requestTokens(request: Pick<FastifyRequest, "headers" | "url">): string[] {
+  const candidates: string[] = [];
+
+  const authorization = request.headers.authorization;
+  if (authorization?.startsWith("Bearer ")) {
+    candidates.push(authorization.slice("Bearer ".length).trim());
+  }
+
+  const cookie = cookieValue(request.headers.cookie, TOKEN_COOKIE);
+  if (cookie) candidates.push(cookie);
+
+  const query = new URL(request.url, "http://localhost").searchParams.get("token");
+  if (query) candidates.push(query);
+
+  return candidates;
+}
+
+/**
+ * Check whether a request carries the expected token.
+ */
+export function isAuthorized(
+  request: Pick<FastifyRequest, "headers" | "url">,
+  token: string
+): boolean {
+  return requestTokens(request).some((candidate) => tokensEqual(candidate, token));
+}
+
+/**
+ * Whether a request with this method changes state.
+ */
+export function isMutating(method: string): boolean {
+  return !READ_METHODS.has(method.toUpperCase());
+}
+
+/**
+ * Reject requests (including WebSocket upgrades) in `scope` that lack the token.
+ *
+ * Runs before routing, so the response never depends on whether the
+ * requested resource exists.
+ */
+export function registerTokenAuth(
+  server: FastifyInstance,
+  token: string,
+  scope: AuthScope = {}
+): void {
+  const { reads = true, mutations = true } = scope;
+  server.addHook("onRequest", async (request, reply) => {
+    const path = request.url.split("?")[0];
+    const protectedRequest = isMutating(request.method) ? mutations : reads;
+    if (!protectedRequest || PUBLIC_PATHS.has(path) || isAuthorized(request, token)) {
+      return;
+    }
+    return reply.code(401).send({ error: "Unauthorized" });
+  });
+}
@@ -0,0 +1,151 @@
+/**
+ * Event Stream Tests
+ *
+ * Tests for the /events/stream server-sent events endpoint:
+ * - The follower reports only complete appended lines
+ * - Malformed lines are skipped
+ * - A new run (marker change) is followed from its start
+ * - The endpoint streams appended records
+ */
+
+import { describe, it, beforeEach, afterEach } from "node:test";
+import assert from "node:assert/strict";
+import fs from "fs";
+import http from "http";
+import os from "os";
+import path from "path";
+import { createServer } from "./server.js";
+import { EventsFileFollower, StreamedEvent, formatSseEvent, resolveActiveEventsFile } from "./eventStream.js";
+import { initializeDatabase, getDatabase } from "../db/connection.js";
+import type { FastifyInstance } from "fastify";
+import type { AddressInfo } from "net";
+
+function record(topic: string): string {
+  return JSON.stringify({ topic, payload: `${topic} payload`, ts: "2026-01-01T00:00:00Z" }) + "\n";
+}
+
+describe("EventsFileFollower", () => {
+  let workspace: string;
+  let events: StreamedEvent[];
+
+  beforeEach(() => {
+    workspace = fs.mkdtempSync(path.join(os.tmpdir(), "ralph-events-"));
+    fs.mkdirSync(path.join(workspace, ".ralph"));
+    fs.writeFileSync(path.join(workspace, ".ralph/current-events"), ".ralph/events-1.jsonl");
+    events = [];
+  });
+
+  afterEach(() => {
+    fs.rmSync(workspace, { recursive: true, force: true });
+  });
+
+  it("resolves the marker, falling back to events.jsonl", () => {
+    assert.equal(resolveActiveEventsFile(workspace), ".ralph/events-1.jsonl");
+    fs.rmSync(path.join(workspace, ".ralph/current-events"));
+    assert.equal(resolveActiveEventsFile(workspace), path.join(".ralph", "events.jsonl"));
+  });
+
+  it("starts at the end and reports complete appended lines", () => {
+    const file = path.join(workspace, ".ralph/events-1.jsonl");
+    fs.writeFileSync(file, record("old.event"));
+
+    const follower = new EventsFileFollower(workspace, (event) => events.push(event));
+    fs.appendFileSync(file, record("build.done") + "not json\n" + '{"topic":"partial"');
+    follower.check();
+
+    assert.deepEqual(
+      events.map((event) => event.record.topic),
+      ["build.done"]
+    );
+    assert.equal(events[0].file, ".ralph/events-1.jsonl");
+
+    fs.appendFileSync(file, ',"payload":"x"}\n');
+    follower.check();
+    assert.deepEqual(
+      events.map((event) => event.record.topic),
+      ["build.done", "partial"]
+    );
+    assert.equal(events[1].offset, fs.statSync(file).size);
+  });
+
+  it("replays from the start and follows a new run's file", () => {
+    fs.writeFileSync(path.join(workspace, ".ralph/events-1.jsonl"), record("task.start"));
+    const follower = new EventsFileFollower(workspace, (event) => events.push(event), {
+      fromStart: true,
+    });
+    follower.check();
+
+    fs.writeFileSync(path.join(workspace, ".ralph/events-2.jsonl"), record("task.resume"));
+    fs.writeFileSync(path.join(workspace, ".ralph/current-events"), ".ralph/events-2.jsonl");
+    follower.check();
+
+    assert.deepEqual(
+      events.map((event) => [event.file, event.record.topic]),
+      [
+        [".ralph/events-1.jsonl", "task.start"],
+        [".ralph/events-2.jsonl", "task.resume"],
+      ]
+    );
+  });
+
+  it("formats records as ralph-event messages keyed by offset", () => {
+    const message = formatSseEvent({
+      file: ".ralph/events-1.jsonl",
+      offset: 42,
+      record: { topic: "build.done" },
+    });
+    assert.equal(
+      message,
+      'id: 42\nevent: ralph-event\ndata: {"file":".ralph/events-1.jsonl","offset":42,"record":{"topic":"build.done"}}\n\n'
+    );
+  });
+});
+
+describe("GET /events/stream", () => {
+  let workspace: string;
+  let server: FastifyInstance;
+
+  beforeEach(async () => {
+    workspace = fs.mkdtempSync(path.join(os.tmpdir(), "ralph-stream-"));
+    fs.mkdirSync(path.join(workspace, ".ralph"));
+    fs.writeFileSync(path.join(workspace, ".ralph/events.jsonl"), "");
+    initializeDatabase(getDatabase(":memory:"));
+    server = await createServer({ db: getDatabase(), logger: false, workspaceRoot: workspace });
+    await server.listen({ port: 0, host: "127.0.0.1" });
+  });
+
+  afterEach(async () => {
+    await server.close();
+    fs.rmSync(workspace, { recursive: true, force: true });
+  });
+
+  it("streams appended records", async () => {
+    const { port } = server.server.address() as AddressInfo;
+
+    const body = await new Promise<string>((resolve, reject) => {
+      const req = http.get(`http://127.0.0.1:${port}/events/stream`, (res) => {
+        assert.equal(res.statusCode, 200);
+        assert.equal(res.headers["content-type"], "text/event-stream");
+
+        let received = "";
+        res.setEncoding("utf8");
+        res.on("data", (chunk: string) => {
+          received += chunk;
+          if (received.includes("\n\n") && !received.includes("event: ralph-event")) {
+            fs.appendFileSync(path.join(workspace, ".ralph/events.jsonl"), record("build.done"));
+          }
+          if (received.includes('"topic":"build.done"')) {
+            req.destroy();
+            resolve(received);
+          }
+        });
+      });
+      req.on("error", (err) => {
+        if (!(err as NodeJS.ErrnoException).code?.startsWith("ECONNRESET")) reject(err);
+      });
+    });
+
+    assert.match(body, /^: connected\n\n/);
+    assert.match(body, /event: ralph-event\ndata: \{"file":"\.ralph\/events\.jsonl"/);
+  });
+});
@@ -0,0 +1,212 @@
+/**
+ * Event Stream (Server-Sent Events)
+ *
+ * GET /events/stream pushes records from the workspace's active events file
+ * (`.ralph/events-*.jsonl`, named by the `.ralph/current-events` marker) as
+ * the loop appends them.
+ *
+ * Each record is sent as:
+ *
+ *   id: <byte offset just past the record's line>
+ *   event: ralph-event
+ *   data: {"file": ".ralph/events-20250101-120000.jsonl", "offset": 1234,
+ *          "record": {"topic": "build.done", "payload": "...", "ts": "..."}}
+ *
+ * `record` is the JSONL line exactly as written (`topic`, `payload`, `ts`, plus
+ * any extra fields); malformed lines are skipped. A `: heartbeat` comment goes
+ * out every 15 seconds to keep proxies from closing idle connections.
+ *
+ * Streams start at the end of the file (new records only). Pass `?from=start`
+ * to replay the whole file; a reconnecting `EventSource` sends `Last-Event-ID`
+ * and resumes from that offset. When a new run switches the marker to a new
+ * file, the stream follows it from the start.
+ */
+
+import fs from "fs";
+import path from "path";
+import type { FastifyInstance } from "fastify";
+
+/** Marker file naming the active events file, relative to the workspace */
+const CURRENT_EVENTS_MARKER = path.join(".ralph", "current-events");
+/** Events file used when no marker exists */
+const DEFAULT_EVENTS_FILE = path.join(".ralph", "events.jsonl");
+
+/** Payload of each `ralph-event` message */
+export interface StreamedEvent {
+  /** Events file the record came from, relative to the workspace */
+  file: string;
+  /** Byte offset just past the record's line (also the SSE id) */
+  offset: number;
+  /** The parsed JSONL record */
+  record: Record<string, unknown>;
+}
+
+export interface EventStreamOptions {
+  /** Workspace whose `.ralph/` directory is followed */
+  workspaceRoot: string;
+  /** Interval between heartbeat comments (default: 15000ms) */
+  heartbeatMs?: number;
+  /** Interval between file checks (default: 250ms) */
+  pollIntervalMs?: number;
+}
+
+interface FollowerOptions {
+  /** Start at the beginning of the current file instead of its end */
+  fromStart?: boolean;
+  /** Resume the current file at this byte offset */
+  startOffset?: number;
+  /** Interval between file checks (default: 250ms) */
+  pollIntervalMs?: number;
+}
+
+/**
+ * Resolve the active events file, relative to the workspace.
+ */
+export function resolveActiveEventsFile(workspaceRoot: string): string {
+  try {
+    const marker = fs.readFileSync(path.join(workspaceRoot, CURRENT_EVENTS_MARKER), "utf8").trim();
+    if (marker) return marker;
+  } catch {
+    // No marker yet: fall back to the default file
+  }
+  return DEFAULT_EVENTS_FILE;
+}
+
+/**
+ * Format one server-sent event.
+ */
+export function formatSseEvent(event: StreamedEvent): string {
+  return `id: ${event.offset}\nevent: ralph-event\ndata: ${JSON.stringify(event)}\n\n`;
+}
+
+/**
+ * Tails the active events file, reporting each complete appended line.
+ *
+ * Only lines ending in a newline are read, so a record being written is
+ * picked up on the next check. A file that shrinks (truncated or replaced)
+ * is re-read from the start.
+ */
+export class EventsFileFollower {
+  private file: string;
+  private offset: number;
+  private timer?: NodeJS.Timeout;
+  private readonly pollIntervalMs: number;
+
+  constructor(
+    private readonly workspaceRoot: string,
+    private readonly onEvent: (event: StreamedEvent) => void,
+    options: FollowerOptions = {}
+  ) {
+    this.pollIntervalMs = options.pollIntervalMs ?? 250;
+    this.file = resolveActiveEventsFile(workspaceRoot);
+    if (options.startOffset !== undefined) {
+      this.offset = options.startOffset;
+    } else if (options.fromStart) {
+      this.offset = 0;
+    } else {
+      this.offset = this.fileSize();
+    }
+  }
+
+  /** Start checking the file periodically */
+  start(): void {
+    if (this.timer) return;
+    this.timer = setInterval(() => this.check(), this.pollIntervalMs);
+    this.check();
+  }
+
+  /** Stop checking; safe to call more than once */
+  stop(): void {
+    if (this.timer) {
+      clearInterval(this.timer);
+      this.timer = undefined;
+    }
+  }
+
+  /** Read and report any complete lines appended since the last check */
+  check(): void {
+    const active = resolveActiveEventsFile(this.workspaceRoot);
+    if (active !== this.file) {
+      this.file = active;
+      this.offset = 0;
+    }
+
+    const size = this.fileSize();
+    if (size < this.offset) {
+      this.offset = 0;
+    }
+    if (size === this.offset) return;
+
+    const buffer = Buffer.alloc(size - this.offset);
+    const fd = fs.openSync(path.join(this.workspaceRoot, this.file), "r");
+    try {
+      fs.readSync(fd, buffer, 0, buffer.length, this.offset);
+    } finally {
+      fs.closeSync(fd);
+    }
+
+    let start = 0;
+    let newline = buffer.indexOf(0x0a, start);
+    while (newline !== -1) {
+      const line = buffer.subarray(start, newline).toString("utf8").trim();
+      const offset = this.offset + newline + 1;
+      if (line) {
+        try {
+          this.onEvent({ file: this.file, offset, record: JSON.parse(line) });
+        } catch {
+          // Skip malformed lines, as the loop does
+        }
+      }
+      start = newline + 1;
+      newline = buffer.indexOf(0x0a, start);
+    }
+    this.offset += start;
+  }
+
+  private fileSize(): number {
+    try {
+      return fs.statSync(path.join(this.workspaceRoot, this.file)).size;
+    } catch {
+      return 0;
+    }
+  }
+}
+
+/**
+ * Register GET /events/stream on the server.
+ */
+export function registerEventStream(server: FastifyInstance, options: EventStreamOptions): void {
+  const heartbeatMs = options.heartbeatMs ?? 15_000;
+
+  server.get<{ Querystring: { from?: string } }>("/events/stream", (request, reply) => {
+    const lastEventId = Number.parseInt(String(request.headers["last-event-id"] ?? ""), 10);
+
+    reply.hijack();
+    const res = reply.raw;
+    res.writeHead(200, {
+      "Content-Type": "text/event-stream",
+      "Cache-Control": "no-cache",
+      Connection: "keep-alive",
+      "X-Accel-Buffering": "no",
+    });
+    res.write(": connected\n\n");
+
+    const follower = new EventsFileFollower(
+      options.workspaceRoot,
+      (event) => res.write(formatSseEvent(event)),
+      {
+        fromStart: request.query.from === "start",
+        startOffset: Number.isNaN(lastEventId) ? undefined : lastEventId,
+        pollIntervalMs: options.pollIntervalMs,
+      }
+    );
+    follower.start();
+
+    const heartbeat = setInterval(() => res.write(": heartbeat\n\n"), heartbeatMs);
+
+    res.on("close", () => {
+      clearInterval(heartbeat);
+      follower.stop();
+    });
+  });
+}
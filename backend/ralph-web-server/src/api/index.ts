@@ -15,6 +15,18 @@ export type { AppRouter, Context } from "./trpc";
 // REST API exports
 export { registerRestRoutes } from "./rest";
 
+// Token authentication exports
+export { registerTokenAuth, isAuthorized, TOKEN_COOKIE } from "./auth";
+
+// Server-sent event stream exports
+export {
+  registerEventStream,
+  EventsFileFollower,
+  resolveActiveEventsFile,
+  formatSseEvent,
+} from "./eventStream";
+export type { StreamedEvent, EventStreamOptions } from "./eventStream";
+
 // WebSocket log streaming exports
 export {
   LogBroadcaster,
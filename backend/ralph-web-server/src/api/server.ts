@@ -5,7 +5,9 @@
  * - /health endpoint for health checks
  * - /trpc/* endpoints for TRPC API
  * - /ws/logs WebSocket endpoint for real-time log streaming
+ * - /events/stream server-sent events for the workspace's events file (see ./eventStream)
  * - CORS support for cross-origin requests
+ * - Optional token authentication (see ./auth)
  */
 
 import Fastify, { FastifyInstance } from "fastify";
@@ -18,6 +20,8 @@ import { BetterSQLite3Database } from "drizzle-orm/better-sqlite3";
 import * as schema from "../db/schema";
 import { getLogBroadcaster } from "./LogBroadcaster";
 import { registerRestRoutes } from "./rest";
+import { registerTokenAuth, type AuthScope } from "./auth";
+import { registerEventStream } from "./eventStream";
 import { TaskBridge } from "../services/TaskBridge";
 import { LoopsManager } from "../services/LoopsManager";
 import { PlanningService } from "../services/PlanningService";
@@ -37,13 +41,19 @@ export interface ServerOptions {
   loopsManager?: LoopsManager;
   /** PlanningService for planning sessions (optional) */
   planningService?: PlanningService;
+  /** Require this token on every request except /health (default: no auth) */
+  authToken?: string;
+  /** Which requests need `authToken` (default: reads and mutations) */
+  authScope?: AuthScope;
+  /** Workspace whose events file /events/stream follows (default: no stream) */
+  workspaceRoot?: string;
 }
 
 /**
  * Create and configure a Fastify server with TRPC
  */
 export async function createServer(options: ServerOptions = {}): Promise<FastifyInstance> {
-  const { port = 3000, host = "0.0.0.0", db = getDatabase(), logger = true, taskBridge, loopsManager, planningService } = options;
+  const { port = 3000, host = "0.0.0.0", db = getDatabase(), logger = true, taskBridge, loopsManager, planningService, authToken, authScope, workspaceRoot } = options;
 
   const server = Fastify({ logger });
 
@@ -54,6 +64,10 @@ export async function createServer(options: ServerOptions = {}): Promise<Fastify
     credentials: true,
   });
 
+  if (authToken) {
+    registerTokenAuth(server, authToken, authScope);
+  }
+
   // Register WebSocket plugin
   await server.register(websocket);
 
@@ -102,6 +116,11 @@ export async function createServer(options: ServerOptions = {}): Promise<Fastify
     );
   });
 
+  // Server-sent events for the active events file
+  if (workspaceRoot) {
+    registerEventStream(server, { workspaceRoot });
+  }
+
   // Register TRPC plugin
   await server.register(fastifyTRPCPlugin, {
     prefix: "/trpc",
@@ -505,6 +505,32 @@ export const loopsRouter = router({
       return enrichedLoops;
     }),
 
+  /**
+   * List agent tasks from .ralph/agent/tasks.jsonl (same store as `ralph tools task`)
+   */
+  tasks: publicProcedure
+    .input(
+      z
+        .object({
+          includeClosed: z.boolean().default(false).optional(),
+          tag: z.string().optional(),
+        })
+        .optional()
+    )
+    .query(async ({ ctx, input }) => {
+      if (!ctx.loopsManager) {
+        throw new TRPCError({
+          code: "INTERNAL_SERVER_ERROR",
+          message: "LoopsManager is not configured",
+        });
+      }
+
+      return ctx.loopsManager.listAgentTasks({
+        includeClosed: input?.includeClosed,
+        tag: input?.tag,
+      });
+    }),
+
   /**
    * Get manager status (running state, interval, last processed time)
    */
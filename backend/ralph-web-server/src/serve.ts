@@ -19,6 +19,13 @@ import { FileOutputStreamer } from "./runner/FileOutputStreamer";
 
 const PORT = parseInt(process.env.PORT || "3000", 10);
 const HOST = process.env.HOST || "0.0.0.0";
+// RALPH_WEB_TOKEN: when set, every request except /health must present this token
+const AUTH_TOKEN = process.env.RALPH_WEB_TOKEN || undefined;
+// RALPH_WEB_AUTH_READ / RALPH_WEB_AUTH_MUTATE: "none" exempts reads or mutations from the token
+const AUTH_SCOPE = {
+  reads: process.env.RALPH_WEB_AUTH_READ !== "none",
+  mutations: process.env.RALPH_WEB_AUTH_MUTATE !== "none",
+};
 
 // Resolve workspace root:
 // 1. RALPH_WORKSPACE_ROOT env var (explicit override)
@@ -167,7 +174,7 @@ async function gracefulShutdown(signal: string, timeoutMs: number): Promise<void
 process.on("SIGTERM", () => gracefulShutdown("SIGTERM", 30000));
 process.on("SIGINT", () => gracefulShutdown("SIGINT", 10000));
 
-startServer({ port: PORT, host: HOST, db, taskBridge, loopsManager, planningService })
+startServer({ port: PORT, host: HOST, db, taskBridge, loopsManager, planningService, authToken: AUTH_TOKEN, authScope: AUTH_SCOPE, workspaceRoot: CWD })
   .then(() => {
     // Restore pending tasks from database
     const restoredCount = taskQueue.hydrate();
@@ -197,6 +204,7 @@ startServer({ port: PORT, host: HOST, db, taskBridge, loopsManager, planningServ
     console.log(`Health check: http://${HOST}:${PORT}/health`);
     console.log(`TRPC endpoint: http://${HOST}:${PORT}/trpc`);
     console.log(`REST API: http://${HOST}:${PORT}/api/v1`);
+    console.log(`Event stream: http://${HOST}:${PORT}/events/stream`);
     console.log(`Dispatcher started (polling for tasks, maxConcurrent=${maxConcurrent})`);
     console.log(`LoopsManager active (processing every ${loopsProcessIntervalMs}ms)`);
     console.log(`TaskBridge active (DB tasks → execution queue)`);
@@ -246,6 +246,43 @@ describe("LoopsManager.retryMerge with steering input", () => {
   });
 });
 
+describe("LoopsManager.listAgentTasks", () => {
+  test("reads tasks from the CLI's JSON output", async () => {
+    const manager = new LoopsManager({ ralphPath: "ralph" });
+    let calledWith: string[] = [];
+    (manager as any).runRalphCommand = async (args: string[]) => {
+      calledWith = args;
+      return JSON.stringify([
+        {
+          id: "task-1-abcd",
+          title: "Migrate schema",
+          status: "open",
+          priority: 1,
+          blocked_by: [],
+          tags: ["db"],
+          created: "2025-01-01T00:00:00Z",
+        },
+      ]);
+    };
+
+    const tasks = await manager.listAgentTasks({ includeClosed: true, tag: "db" });
+
+    assert.deepStrictEqual(calledWith, [
+      "tools",
+      "task",
+      "list",
+      "--format",
+      "json",
+      "--all",
+      "--tag",
+      "db",
+    ]);
+    assert.strictEqual(tasks.length, 1);
+    assert.strictEqual(tasks[0].title, "Migrate schema");
+    assert.deepStrictEqual(tasks[0].tags, ["db"]);
+  });
+});
+
 describe("MergeButtonState type", () => {
   test("MergeButtonState interface exists and is exported", async () => {
     // This test verifies the type is properly exported
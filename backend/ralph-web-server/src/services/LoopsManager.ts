@@ -32,6 +32,25 @@ export interface MergeButtonState {
   reason?: string;
 }
 
+/**
+ * An agent work item from `.ralph/agent/tasks.jsonl`, as emitted by
+ * `ralph tools task list --format json`.
+ */
+export interface AgentTask {
+  id: string;
+  title: string;
+  description?: string;
+  status: "open" | "in_progress" | "closed" | "failed";
+  priority: number;
+  blocked_by: string[];
+  tags?: string[];
+  parent?: string;
+  loop_id?: string;
+  created: string;
+  updated?: string;
+  closed?: string;
+}
+
 export interface LoopsManagerOptions {
   /** How often to process merge queue (default: 30s) */
   processIntervalMs?: number;
@@ -134,6 +153,24 @@ export class LoopsManager extends EventEmitter {
     }
   }
 
+  /**
+   * List agent tasks through the same task store the CLI and Telegram bot use.
+   * Closed and failed tasks are included only when `includeClosed` is set.
+   */
+  async listAgentTasks(
+    options: { includeClosed?: boolean; tag?: string } = {}
+  ): Promise<AgentTask[]> {
+    const args = ["tools", "task", "list", "--format", "json"];
+    if (options.includeClosed) {
+      args.push("--all");
+    }
+    if (options.tag) {
+      args.push("--tag", options.tag);
+    }
+    const output = await this.runRalphCommand(args);
+    return JSON.parse(output) as AgentTask[];
+  }
+
   /**
    * Prune stale loops from crashed processes
    */
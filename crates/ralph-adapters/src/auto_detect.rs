@@ -3,6 +3,7 @@
 //! When config specifies `agent: auto`, this module handles detecting
 //! which backends are available in the system PATH.
 
+use ralph_core::{CustomBackendConfig, RalphConfig};
 use std::process::Command;
 use std::sync::OnceLock;
 use tracing::debug;
@@ -72,8 +73,28 @@ impl std::error::Error for NoBackendError {}
 /// for exit code 0. The command may differ from the backend name (e.g.,
 /// "kiro" backend uses "kiro-cli" command).
 pub fn is_backend_available(backend: &str) -> bool {
-    let command = detection_command(backend);
-    let result = Command::new(command).arg("--version").output();
+    run_probe(
+        backend,
+        detection_command(backend),
+        &["--version".to_string()],
+    )
+}
+
+/// Checks if a custom backend is available by running its probe command.
+///
+/// The probe defaults to `<command> --version`; see
+/// [`CustomBackendConfig::probe_command`].
+pub fn is_custom_backend_available(backend: &str, config: &CustomBackendConfig) -> bool {
+    let probe = config.probe_command();
+    match probe.split_first() {
+        Some((command, args)) => run_probe(backend, command, args),
+        None => false,
+    }
+}
+
+/// Runs a probe command and reports whether it exited successfully.
+fn run_probe(backend: &str, command: &str, args: &[String]) -> bool {
+    let result = Command::new(command).args(args).output();
 
     match result {
         Ok(output) => {
@@ -110,6 +131,36 @@ pub fn is_backend_available(backend: &str) -> bool {
 pub fn detect_backend<F>(priority: &[&str], adapter_enabled: F) -> Result<String, NoBackendError>
 where
     F: Fn(&str) -> bool,
+{
+    detect_backend_with(priority, adapter_enabled, is_backend_available)
+}
+
+/// Detects the first available backend using the priority list, enabled
+/// adapters, and custom backends from `config`.
+///
+/// Backends declared in `adapters.custom_backends` are checked with their
+/// probe command instead of `<name> --version`.
+pub fn detect_configured_backend(config: &RalphConfig) -> Result<String, NoBackendError> {
+    let priority = config.get_agent_priority();
+    detect_backend_with(
+        &priority,
+        |backend| config.adapter_settings(backend).enabled,
+        |backend| match config.adapters.custom_backends.get(backend) {
+            Some(custom) => is_custom_backend_available(backend, custom),
+            None => is_backend_available(backend),
+        },
+    )
+}
+
+/// Detects the first available backend, checking availability with `is_available`.
+fn detect_backend_with<F, A>(
+    priority: &[&str],
+    adapter_enabled: F,
+    is_available: A,
+) -> Result<String, NoBackendError>
+where
+    F: Fn(&str) -> bool,
+    A: Fn(&str) -> bool,
 {
     debug!(priority = ?priority, "Starting backend auto-detection");
 
@@ -132,7 +183,7 @@ where
 
         checked.push(backend.to_string());
 
-        if is_backend_available(backend) {
+        if is_available(backend) {
             debug!(backend = backend, "Backend detected and selected");
             // Cache the result (ignore if already set)
             let _ = DETECTED_BACKEND.set(Some(backend.to_string()));
@@ -172,6 +223,32 @@ mod tests {
         ));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_is_custom_backend_available_runs_probe() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let script = temp.path().join("fake-agent");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n[ \"$1\" = \"--healthcheck\" ] && exit 0\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let command = script.display().to_string();
+
+        // Default probe (`--version`) fails for this script
+        let mut config = CustomBackendConfig::new(&command);
+        assert!(!is_custom_backend_available("fake", &config));
+
+        config.probe = Some(vec![command, "--healthcheck".to_string()]);
+        assert!(is_custom_backend_available("fake", &config));
+
+        config.probe = Some(vec!["definitely_not_a_real_command_xyz123".to_string()]);
+        assert!(!is_custom_backend_available("fake", &config));
+    }
+
     #[test]
     fn test_detect_backend_with_disabled_adapters() {
         // All adapters disabled should fail
@@ -0,0 +1,697 @@
+//! Typed events parsed from a backend's streaming output.
+//!
+//! [`StreamJsonParser`] turns Claude's `--output-format stream-json` NDJSON
+//! into [`BackendEvent`]s: message boundaries, text, tool calls and their
+//! results, and token usage. It reads both the message-level lines
+//! (`system`, `assistant`, `user`, `result`) and the raw API events Claude
+//! adds with `--include-partial-messages` (`stream_event` lines carrying
+//! `message_start`, `content_block_delta`, `message_stop`, ...). Anything
+//! that is not stream-json comes through as [`BackendEvent::Raw`], so the
+//! output of any backend can be fed to the parser.
+
+use ralph_core::{CliConfig, ModelPricing, truncate_with_ellipsis};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Input fields shown after the tool name in [`BackendEvent::summary`], in
+/// order of preference.
+const TOOL_SUMMARY_FIELDS: [&str; 7] = [
+    "file_path",
+    "path",
+    "command",
+    "pattern",
+    "url",
+    "query",
+    "description",
+];
+
+/// An event from a backend's output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackendEvent {
+    /// The backend session started.
+    SessionStart {
+        session_id: String,
+        model: Option<String>,
+    },
+    /// An assistant message started.
+    MessageStart {
+        id: Option<String>,
+        model: Option<String>,
+    },
+    /// Assistant text: a streamed delta, or a whole block when the backend
+    /// doesn't stream partial messages.
+    Text { text: String },
+    /// A tool call with its complete input.
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    /// The result of a tool call.
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    /// Token usage of the current message, reported once per message.
+    Usage { usage: TokenUsage },
+    /// The current assistant message ended.
+    MessageStop,
+    /// The session finished, with its totals.
+    SessionEnd {
+        is_error: bool,
+        duration_ms: u64,
+        num_turns: u32,
+        total_cost_usd: f64,
+        usage: Option<TokenUsage>,
+    },
+    /// A line that is not stream-json, passed through unchanged.
+    Raw { text: String },
+}
+
+impl BackendEvent {
+    /// Text the agent produced, for scanning output as it arrives (e.g.
+    /// for the completion promise).
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text { text } | Self::Raw { text } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// One-line description of a tool call, like `tool: Read src/main.rs`.
+    pub fn summary(&self) -> Option<String> {
+        let Self::ToolUse { name, input, .. } = self else {
+            return None;
+        };
+        let argument = TOOL_SUMMARY_FIELDS
+            .iter()
+            .find_map(|field| input.get(field).and_then(Value::as_str))
+            .and_then(|value| value.lines().next());
+        Some(match argument {
+            Some(argument) => format!("tool: {name} {}", truncate_with_ellipsis(argument, 80)),
+            None => format!("tool: {name}"),
+        })
+    }
+}
+
+/// Token counts reported by the backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl TokenUsage {
+    /// All tokens, cached input included.
+    pub fn total(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_creation_input_tokens
+            + self.cache_read_input_tokens
+    }
+
+    /// Cost of these tokens at `pricing`, in USD.
+    pub fn cost(&self, pricing: &ModelPricing) -> f64 {
+        pricing.cost(
+            self.input_tokens,
+            self.output_tokens,
+            self.cache_creation_input_tokens,
+            self.cache_read_input_tokens,
+        )
+    }
+
+    /// Takes each count from `later` unless it is zero. API events report
+    /// input tokens at the start of a message and output tokens at the end.
+    fn merged(self, later: Self) -> Self {
+        let pick = |early: u64, late: u64| if late > 0 { late } else { early };
+        Self {
+            input_tokens: pick(self.input_tokens, later.input_tokens),
+            output_tokens: pick(self.output_tokens, later.output_tokens),
+            cache_creation_input_tokens: pick(
+                self.cache_creation_input_tokens,
+                later.cache_creation_input_tokens,
+            ),
+            cache_read_input_tokens: pick(
+                self.cache_read_input_tokens,
+                later.cache_read_input_tokens,
+            ),
+        }
+    }
+}
+
+/// Estimated spend of a backend session from its events, in USD.
+///
+/// Prices the session's token usage with `cli.pricing` when the reported
+/// model has an entry, and otherwise falls back to the cost the backend
+/// reported itself. Returns `None` when the events carry neither.
+pub fn estimate_cost(events: &[BackendEvent], cli: &CliConfig) -> Option<f64> {
+    let mut model = None;
+    let mut message_usage: Option<TokenUsage> = None;
+    let mut session_end = None;
+    for event in events {
+        match event {
+            BackendEvent::SessionStart { model: Some(m), .. }
+            | BackendEvent::MessageStart { model: Some(m), .. } => {
+                model.get_or_insert(m.as_str());
+            }
+            BackendEvent::Usage { usage } => {
+                let total = message_usage.get_or_insert_default();
+                total.input_tokens += usage.input_tokens;
+                total.output_tokens += usage.output_tokens;
+                total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+                total.cache_read_input_tokens += usage.cache_read_input_tokens;
+            }
+            BackendEvent::SessionEnd {
+                total_cost_usd,
+                usage,
+                ..
+            } => session_end = Some((*total_cost_usd, *usage)),
+            _ => {}
+        }
+    }
+
+    // The session totals cover every turn, per-message usage is a fallback
+    // for output that was cut off before the result line
+    let usage = session_end.and_then(|(_, usage)| usage).or(message_usage);
+    let priced = model
+        .and_then(|model| cli.pricing_for(model))
+        .zip(usage)
+        .map(|(pricing, usage)| usage.cost(pricing));
+    priced.or(session_end.map(|(reported, _)| reported))
+}
+
+/// A tool call whose input is still streaming.
+#[derive(Debug)]
+struct PendingToolUse {
+    id: String,
+    name: String,
+    input: Value,
+    input_json: String,
+}
+
+/// Incremental parser for Claude's stream-json output.
+///
+/// Feed it one line at a time; call [`finish`](Self::finish) at the end of
+/// the output.
+#[derive(Debug, Default)]
+pub struct StreamJsonParser {
+    /// Whether API events are present. The message-level `assistant` lines
+    /// then repeat content that was already streamed, and are skipped.
+    partial: bool,
+    /// Id of the open assistant message, when reading message-level lines.
+    open_message: Option<String>,
+    /// Usage from the open message's `message_start`.
+    message_usage: TokenUsage,
+    /// Tool calls whose input is still streaming, by content block index.
+    pending_tools: HashMap<u64, PendingToolUse>,
+}
+
+impl StreamJsonParser {
+    /// Creates a parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses one line of output.
+    ///
+    /// Lines that are not stream-json objects, including malformed JSON,
+    /// become a single [`BackendEvent::Raw`]. Recognized lines that carry
+    /// nothing of interest yield no events.
+    pub fn parse_line(&mut self, line: &str) -> Vec<BackendEvent> {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            return vec![raw(line)];
+        };
+        let Some(kind) = value.get("type").and_then(Value::as_str) else {
+            return vec![raw(line)];
+        };
+
+        match kind {
+            "system" => session_start(&value).into_iter().collect(),
+            "stream_event" => {
+                self.partial = true;
+                value
+                    .get("event")
+                    .map(|event| self.api_event(event))
+                    .unwrap_or_default()
+            }
+            "message_start"
+            | "content_block_start"
+            | "content_block_delta"
+            | "content_block_stop"
+            | "message_delta"
+            | "message_stop" => {
+                self.partial = true;
+                self.api_event(&value)
+            }
+            "assistant" => self.assistant(&value),
+            "user" => self.user(&value),
+            "result" => self.result(&value),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Events implied by the end of the output, such as the end of a message
+    /// that was cut off.
+    pub fn finish(&mut self) -> Vec<BackendEvent> {
+        self.pending_tools.clear();
+        self.close_message().into_iter().collect()
+    }
+
+    /// Handles an Anthropic API streaming event.
+    fn api_event(&mut self, event: &Value) -> Vec<BackendEvent> {
+        let index = event.get("index").and_then(Value::as_u64).unwrap_or(0);
+        match event.get("type").and_then(Value::as_str) {
+            Some("message_start") => {
+                let message = event.get("message");
+                self.message_usage = message
+                    .and_then(|message| message.get("usage"))
+                    .and_then(usage)
+                    .unwrap_or_default();
+                vec![BackendEvent::MessageStart {
+                    id: message.and_then(|message| string(message, "id")),
+                    model: message.and_then(|message| string(message, "model")),
+                }]
+            }
+            Some("content_block_start") => {
+                let Some(block) = event.get("content_block") else {
+                    return Vec::new();
+                };
+                match block.get("type").and_then(Value::as_str) {
+                    Some("tool_use") => {
+                        self.pending_tools.insert(
+                            index,
+                            PendingToolUse {
+                                id: string(block, "id").unwrap_or_default(),
+                                name: string(block, "name").unwrap_or_default(),
+                                input: block.get("input").cloned().unwrap_or(Value::Null),
+                                input_json: String::new(),
+                            },
+                        );
+                        Vec::new()
+                    }
+                    Some("text") => text_event(block.get("text")),
+                    _ => Vec::new(),
+                }
+            }
+            Some("content_block_delta") => {
+                let Some(delta) = event.get("delta") else {
+                    return Vec::new();
+                };
+                match delta.get("type").and_then(Value::as_str) {
+                    Some("text_delta") => text_event(delta.get("text")),
+                    Some("input_json_delta") => {
+                        if let Some(tool) = self.pending_tools.get_mut(&index)
+                            && let Some(json) = delta.get("partial_json").and_then(Value::as_str)
+                        {
+                            tool.input_json.push_str(json);
+                        }
+                        Vec::new()
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            Some("content_block_stop") => {
+                let Some(tool) = self.pending_tools.remove(&index) else {
+                    return Vec::new();
+                };
+                let input = if tool.input_json.trim().is_empty() {
+                    tool.input
+                } else {
+                    serde_json::from_str(&tool.input_json).unwrap_or_else(|e| {
+                        tracing::debug!(tool = %tool.name, "Unparseable tool input: {}", e);
+                        Value::String(tool.input_json)
+                    })
+                };
+                vec![BackendEvent::ToolUse {
+                    id: tool.id,
+                    name: tool.name,
+                    input,
+                }]
+            }
+            Some("message_delta") => match event.get("usage").and_then(usage) {
+                Some(final_usage) => vec![BackendEvent::Usage {
+                    usage: self.message_usage.merged(final_usage),
+                }],
+                None => Vec::new(),
+            },
+            Some("message_stop") => vec![BackendEvent::MessageStop],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Handles a message-level `assistant` line.
+    fn assistant(&mut self, value: &Value) -> Vec<BackendEvent> {
+        let Some(message) = value.get("message") else {
+            return Vec::new();
+        };
+        if self.partial {
+            return Vec::new();
+        }
+
+        let mut events = Vec::new();
+        // Claude writes one line per content block, repeating the message
+        let id = string(message, "id").unwrap_or_default();
+        if self.open_message.as_ref() != Some(&id) {
+            events.extend(self.close_message());
+            events.push(BackendEvent::MessageStart {
+                id: Some(id.clone()).filter(|id| !id.is_empty()),
+                model: string(message, "model"),
+            });
+            if let Some(usage) = message.get("usage").and_then(usage) {
+                events.push(BackendEvent::Usage { usage });
+            }
+            self.open_message = Some(id);
+        }
+
+        for block in content_blocks(message) {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => events.extend(text_event(block.get("text"))),
+                Some("tool_use") => events.push(BackendEvent::ToolUse {
+                    id: string(block, "id").unwrap_or_default(),
+                    name: string(block, "name").unwrap_or_default(),
+                    input: block.get("input").cloned().unwrap_or(Value::Null),
+                }),
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// Handles a `user` line, which carries tool results.
+    fn user(&mut self, value: &Value) -> Vec<BackendEvent> {
+        let mut events: Vec<BackendEvent> = self.close_message().into_iter().collect();
+        let Some(message) = value.get("message") else {
+            return events;
+        };
+        for block in content_blocks(message) {
+            if block.get("type").and_then(Value::as_str) == Some("tool_result") {
+                events.push(BackendEvent::ToolResult {
+                    tool_use_id: string(block, "tool_use_id").unwrap_or_default(),
+                    content: tool_result_text(block.get("content")),
+                    is_error: block
+                        .get("is_error")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                });
+            }
+        }
+        events
+    }
+
+    /// Handles the final `result` line.
+    fn result(&mut self, value: &Value) -> Vec<BackendEvent> {
+        let mut events: Vec<BackendEvent> = self.close_message().into_iter().collect();
+        events.push(BackendEvent::SessionEnd {
+            is_error: value
+                .get("is_error")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            duration_ms: value
+                .get("duration_ms")
+                .and_then(Value::as_u64)
+                .unwrap_or(0),
+            num_turns: value
+                .get("num_turns")
+                .and_then(Value::as_u64)
+                .and_then(|turns| u32::try_from(turns).ok())
+                .unwrap_or(0),
+            total_cost_usd: value
+                .get("total_cost_usd")
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0),
+            usage: value.get("usage").and_then(usage),
+        });
+        events
+    }
+
+    /// Ends the open message-level assistant message, if any.
+    fn close_message(&mut self) -> Option<BackendEvent> {
+        self.open_message.take().map(|_| BackendEvent::MessageStop)
+    }
+}
+
+fn raw(line: &str) -> BackendEvent {
+    BackendEvent::Raw {
+        text: line.to_string(),
+    }
+}
+
+fn string(value: &Value, field: &str) -> Option<String> {
+    value.get(field).and_then(Value::as_str).map(String::from)
+}
+
+fn usage(value: &Value) -> Option<TokenUsage> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+fn text_event(text: Option<&Value>) -> Vec<BackendEvent> {
+    match text.and_then(Value::as_str) {
+        Some(text) if !text.is_empty() => vec![BackendEvent::Text {
+            text: text.to_string(),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+fn content_blocks(message: &Value) -> &[Value] {
+    message
+        .get("content")
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn session_start(value: &Value) -> Option<BackendEvent> {
+    Some(BackendEvent::SessionStart {
+        session_id: string(value, "session_id")?,
+        model: string(value, "model"),
+    })
+}
+
+/// Tool result content is either a string or a list of content blocks.
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_fixture(fixture: &str) -> Vec<BackendEvent> {
+        let mut parser = StreamJsonParser::new();
+        let mut events: Vec<BackendEvent> = fixture
+            .lines()
+            .flat_map(|line| parser.parse_line(line))
+            .collect();
+        events.extend(parser.finish());
+        events
+    }
+
+    fn summaries(events: &[BackendEvent]) -> Vec<String> {
+        events.iter().filter_map(BackendEvent::summary).collect()
+    }
+
+    fn text(events: &[BackendEvent]) -> String {
+        events.iter().filter_map(BackendEvent::text).collect()
+    }
+
+    #[test]
+    fn test_message_level_session() {
+        let events = parse_fixture(include_str!(
+            "../tests/fixtures/claude-stream-json/tool-session.jsonl"
+        ));
+
+        assert_eq!(
+            events[0],
+            BackendEvent::SessionStart {
+                session_id: "5f0c6c1e-2a51-4d0c-9d0a-0c5e8f1b2a77".to_string(),
+                model: Some("claude-sonnet-4-5-20250929".to_string()),
+            }
+        );
+        assert_eq!(
+            summaries(&events),
+            vec!["tool: Read src/main.rs", "tool: Bash cargo test"]
+        );
+        assert!(text(&events).ends_with("LOOP_COMPLETE"));
+
+        // Three messages, each opened and closed once despite repeated lines
+        let starts = events
+            .iter()
+            .filter(|e| matches!(e, BackendEvent::MessageStart { .. }))
+            .count();
+        let stops = events
+            .iter()
+            .filter(|e| matches!(e, BackendEvent::MessageStop))
+            .count();
+        assert_eq!((starts, stops), (3, 3));
+
+        assert!(events.contains(&BackendEvent::ToolResult {
+            tool_use_id: "toolu_01Hk4c9L8zUh5dP7Nn2qX3bB".to_string(),
+            content: "error: could not find `Cargo.toml` in `/work/demo`".to_string(),
+            is_error: true,
+        }));
+
+        let Some(BackendEvent::SessionEnd {
+            is_error,
+            num_turns,
+            usage: Some(usage),
+            ..
+        }) = events.last()
+        else {
+            panic!("expected SessionEnd last, got {:?}", events.last());
+        };
+        assert!(!is_error);
+        assert_eq!(*num_turns, 5);
+        assert_eq!(usage.output_tokens, 47);
+        assert_eq!(usage.total(), 15 + 47 + 2586 + 39246);
+    }
+
+    #[test]
+    fn test_estimate_cost_prices_usage_or_falls_back_to_reported_cost() {
+        let events = parse_fixture(include_str!(
+            "../tests/fixtures/claude-stream-json/tool-session.jsonl"
+        ));
+        let mut cli = CliConfig::default();
+
+        let reported = estimate_cost(&events, &cli).unwrap();
+        assert!((reported - 0.029_173_8).abs() < 1e-9);
+
+        cli.pricing.insert(
+            "claude-sonnet-4-5".to_string(),
+            ModelPricing {
+                input: 1_000_000.0,
+                output: 2_000_000.0,
+                cache_write: 0.0,
+                cache_read: 0.0,
+            },
+        );
+        let priced = estimate_cost(&events, &cli).unwrap();
+        assert!((priced - (15.0 + 2.0 * 47.0)).abs() < 1e-9);
+
+        // Cut off before the result line: per-message usage is summed
+        let truncated: Vec<_> = events
+            .iter()
+            .filter(|e| !matches!(e, BackendEvent::SessionEnd { .. }))
+            .cloned()
+            .collect();
+        assert!(estimate_cost(&truncated, &cli).unwrap() > 0.0);
+        assert_eq!(estimate_cost(&[raw("plain text")], &cli), None);
+    }
+
+    #[test]
+    fn test_partial_messages_stream_deltas_and_assemble_tool_input() {
+        let events = parse_fixture(include_str!(
+            "../tests/fixtures/claude-stream-json/partial-messages.jsonl"
+        ));
+
+        let texts: Vec<_> = events.iter().filter_map(BackendEvent::text).collect();
+        assert_eq!(texts, vec!["Let me check", " the config."]);
+
+        let tool_uses: Vec<_> = events
+            .iter()
+            .filter(|e| matches!(e, BackendEvent::ToolUse { .. }))
+            .collect();
+        assert_eq!(
+            tool_uses,
+            vec![&BackendEvent::ToolUse {
+                id: "toolu_01Pq2rS3tU4vW5xY6zA7bC8d".to_string(),
+                name: "Read".to_string(),
+                input: json!({ "file_path": "ralph.yml" }),
+            }]
+        );
+
+        assert!(events.contains(&BackendEvent::Usage {
+            usage: TokenUsage {
+                input_tokens: 4,
+                output_tokens: 58,
+                cache_creation_input_tokens: 1024,
+                cache_read_input_tokens: 8192,
+            }
+        }));
+        assert!(events.contains(&BackendEvent::ToolResult {
+            tool_use_id: "toolu_01Pq2rS3tU4vW5xY6zA7bC8d".to_string(),
+            content: "event_loop:\n  max_iterations: 10\n".to_string(),
+            is_error: false,
+        }));
+
+        let boundaries: Vec<_> = events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    BackendEvent::MessageStart { .. } | BackendEvent::MessageStop
+                )
+            })
+            .collect();
+        assert_eq!(boundaries.len(), 2);
+    }
+
+    #[test]
+    fn test_malformed_lines_degrade_to_raw_text() {
+        let events = parse_fixture(include_str!(
+            "../tests/fixtures/claude-stream-json/malformed-lines.jsonl"
+        ));
+
+        let raw: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                BackendEvent::Raw { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(raw.len(), 4);
+        assert_eq!(
+            raw[0],
+            "Warning: claude is running in a non-interactive shell"
+        );
+        assert!(raw[1].starts_with(r#"{"type":"assistant""#));
+        assert_eq!(raw[2], "[1, 2, 3]");
+        assert_eq!(raw[3], "");
+
+        // Parsing carries on after the bad lines
+        assert!(events.contains(&BackendEvent::Text {
+            text: "Still here.".to_string()
+        }));
+        assert_eq!(events.last(), Some(&BackendEvent::MessageStop));
+    }
+
+    #[test]
+    fn test_plain_text_backends_pass_through() {
+        let mut parser = StreamJsonParser::new();
+
+        assert_eq!(
+            parser.parse_line("Working on it..."),
+            vec![BackendEvent::Raw {
+                text: "Working on it...".to_string()
+            }]
+        );
+        assert!(parser.finish().is_empty());
+    }
+
+    #[test]
+    fn test_summary_without_known_argument() {
+        let event = BackendEvent::ToolUse {
+            id: "toolu_1".to_string(),
+            name: "TodoWrite".to_string(),
+            input: json!({ "todos": [] }),
+        };
+
+        assert_eq!(event.summary().as_deref(), Some("tool: TodoWrite"));
+        assert_eq!(BackendEvent::MessageStop.summary(), None);
+    }
+}
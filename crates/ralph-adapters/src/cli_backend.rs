@@ -1,6 +1,10 @@
 //! CLI backend definitions for different AI tools.
 
-use ralph_core::{CliConfig, HatBackend};
+use ralph_core::{
+    CliConfig, CustomBackendConfig, CustomOutputFormat, CustomPromptMode, HatBackend, KeyringStore,
+    RalphConfig, SecretStore, resolve_env_value,
+};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -41,6 +45,58 @@ pub enum PromptMode {
     Stdin,
 }
 
+/// Prompts above this many bytes leave the command line by default. Linux
+/// caps a single argument at 128 KiB.
+pub const DEFAULT_LARGE_PROMPT_THRESHOLD: usize = 100_000;
+
+/// Where Claude prompts leave the command line; longer `-p` arguments have
+/// caused trouble well below the OS limit.
+const CLAUDE_LARGE_PROMPT_THRESHOLD: usize = 7000;
+
+/// How a prompt too long for the command line reaches the backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LargePromptDelivery {
+    /// Pass a short instruction to read the prompt from a temp file. Suits
+    /// agents that can read files.
+    ReadInstruction,
+    /// Pass the path of a temp file holding the prompt after this flag.
+    FileFlag(String),
+    /// Write the prompt to stdin instead of passing it as an argument.
+    Stdin,
+}
+
+/// Handling of long prompts for backends that take the prompt as an
+/// argument ([`PromptMode::Arg`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargePrompt {
+    /// Prompts longer than this many bytes use `delivery` (0 = all prompts).
+    pub threshold: usize,
+    /// How those prompts are delivered.
+    pub delivery: LargePromptDelivery,
+}
+
+impl Default for LargePrompt {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_LARGE_PROMPT_THRESHOLD,
+            delivery: LargePromptDelivery::ReadInstruction,
+        }
+    }
+}
+
+impl LargePrompt {
+    fn claude() -> Self {
+        Self {
+            threshold: CLAUDE_LARGE_PROMPT_THRESHOLD,
+            ..Self::default()
+        }
+    }
+
+    fn applies_to(&self, prompt: &str) -> bool {
+        prompt.len() > self.threshold
+    }
+}
+
 /// A CLI backend configuration for executing prompts.
 #[derive(Debug, Clone)]
 pub struct CliBackend {
@@ -56,6 +112,20 @@ pub struct CliBackend {
     pub output_format: OutputFormat,
     /// Environment variables to set when spawning the process.
     pub env_vars: Vec<(String, String)>,
+    /// How prompts too long for the command line are passed.
+    pub large_prompt: LargePrompt,
+    /// Flag resuming the most recent session in the working directory
+    /// (Claude's `--continue`), or `None` if the backend cannot resume.
+    pub continue_flag: Option<String>,
+}
+
+/// What an iteration sends to the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IterationPrompt {
+    /// The full prompt, in a fresh session.
+    Full,
+    /// Only what changed since the previous iteration, resuming its session.
+    Continuation,
 }
 
 impl CliBackend {
@@ -73,7 +143,12 @@ impl CliBackend {
             "copilot" => Self::copilot(),
             "opencode" => Self::opencode(),
             "pi" => Self::pi(),
-            "custom" => return Self::custom(config),
+            "custom" => {
+                let mut backend = Self::custom(config)?;
+                backend.merge_env(&config.env);
+                backend.set_large_prompt_threshold(config.large_prompt_threshold);
+                return Ok(backend);
+            }
             _ => Self::claude(), // Default to claude
         };
 
@@ -89,9 +164,184 @@ impl CliBackend {
             backend.command = cmd.clone();
         }
 
+        backend.merge_env(&config.env);
+        backend.set_large_prompt_threshold(config.large_prompt_threshold);
+        Ok(backend)
+    }
+
+    /// Creates the backend selected by `cli.backend`, including backends
+    /// declared in `adapters.custom_backends`.
+    ///
+    /// `cli.args` and `cli.env` apply to custom backends as well.
+    ///
+    /// # Errors
+    /// Returns `CustomBackendError` if backend is "custom" but no command is specified.
+    pub fn from_ralph_config(config: &RalphConfig) -> Result<Self, CustomBackendError> {
+        let Some(custom) = config.adapters.custom_backends.get(&config.cli.backend) else {
+            return Self::from_config(&config.cli);
+        };
+        let mut backend = Self::from_custom_backend(custom);
+        backend.args.extend(config.cli.args.iter().cloned());
+        backend.merge_env(&config.cli.env);
+        backend.set_large_prompt_threshold(config.cli.large_prompt_threshold);
         Ok(backend)
     }
 
+    /// Creates a backend from an `adapters.custom_backends` entry.
+    pub fn from_custom_backend(config: &CustomBackendConfig) -> Self {
+        let (prompt_mode, prompt_flag) = match config.prompt_mode {
+            CustomPromptMode::Arg => (PromptMode::Arg, None),
+            CustomPromptMode::Flag => (PromptMode::Arg, config.prompt_flag.clone()),
+            CustomPromptMode::Stdin => (PromptMode::Stdin, None),
+        };
+        let output_format = match config.output_format {
+            CustomOutputFormat::Text => OutputFormat::Text,
+            CustomOutputFormat::StreamJson => OutputFormat::StreamJson,
+            CustomOutputFormat::PiStreamJson => OutputFormat::PiStreamJson,
+        };
+
+        // A custom command may not be an agent that can read files
+        let delivery = match &config.prompt_file_flag {
+            Some(flag) => LargePromptDelivery::FileFlag(flag.clone()),
+            None => LargePromptDelivery::Stdin,
+        };
+
+        Self {
+            command: config.command.clone(),
+            args: config.args.clone(),
+            prompt_mode,
+            prompt_flag,
+            output_format,
+            env_vars: vec![],
+            large_prompt: LargePrompt {
+                delivery,
+                ..LargePrompt::default()
+            },
+            continue_flag: None,
+        }
+    }
+
+    /// Applies `cli.large_prompt_threshold`, if set.
+    pub fn set_large_prompt_threshold(&mut self, threshold: Option<usize>) {
+        if let Some(threshold) = threshold {
+            self.large_prompt.threshold = threshold;
+        }
+    }
+
+    /// Whether this backend can resume its previous session.
+    pub fn supports_continuation(&self) -> bool {
+        self.continue_flag.is_some()
+    }
+
+    /// Chooses what to send for an iteration.
+    ///
+    /// Continuation needs `continue_session` enabled, a backend that can
+    /// resume, and a previous iteration run by the same command, whose
+    /// session is the one resumed; otherwise the full prompt is sent.
+    pub fn iteration_prompt(
+        &self,
+        continue_session: bool,
+        previous: Option<&CliBackend>,
+    ) -> IterationPrompt {
+        let same_session = previous.is_some_and(|prev| prev.command == self.command);
+        if continue_session && self.supports_continuation() && same_session {
+            IterationPrompt::Continuation
+        } else {
+            IterationPrompt::Full
+        }
+    }
+
+    /// Adds the continue flag so the next run resumes the previous session.
+    pub fn continue_session(&mut self) {
+        if let Some(flag) = self.continue_flag.clone()
+            && !self.args.contains(&flag)
+        {
+            self.args.push(flag);
+        }
+    }
+
+    /// Merges configured environment variables into `env_vars`.
+    ///
+    /// `${VAR}` references in values are expanded from Ralph's own environment
+    /// and `keychain:<entry>` values are read from the OS keychain (unresolved
+    /// sources become an empty string). An entry replaces any existing
+    /// variable with the same name, so later merges take precedence.
+    pub fn merge_env(&mut self, env: &BTreeMap<String, String>) {
+        self.merge_env_from(env, &KeyringStore);
+    }
+
+    /// Like [`merge_env`](Self::merge_env), reading `keychain:` values from `store`.
+    pub fn merge_env_from(&mut self, env: &BTreeMap<String, String>, store: &dyn SecretStore) {
+        for (key, value) in env {
+            self.set_env(key, resolve_env_value(value, store).value);
+        }
+    }
+
+    /// Sets one environment variable, replacing any existing value.
+    pub fn set_env(&mut self, key: &str, value: String) {
+        match self.env_vars.iter_mut().find(|(k, _)| k == key) {
+            Some(existing) => existing.1 = value,
+            None => self.env_vars.push((key.to_string(), value)),
+        }
+    }
+
+    /// Restricts which tools the agent may use, using this backend's own flags.
+    ///
+    /// - Claude: `--allowedTools=...` and `--disallowedTools=...` (merged into
+    ///   the `--disallowedTools` list Ralph already passes)
+    /// - Copilot: `--allow-tool`/`--deny-tool` per tool; an allowlist replaces
+    ///   `--allow-all-tools`
+    ///
+    /// Returns `false` if restrictions were requested but this backend has no
+    /// way to enforce them; the command is left unchanged in that case.
+    pub fn restrict_tools(&mut self, allowed: &[String], disallowed: &[String]) -> bool {
+        if allowed.is_empty() && disallowed.is_empty() {
+            return true;
+        }
+
+        match self.command.as_str() {
+            "claude" => {
+                if !allowed.is_empty() {
+                    self.args
+                        .push(format!("--allowedTools={}", allowed.join(",")));
+                }
+                if !disallowed.is_empty() {
+                    match self
+                        .args
+                        .iter_mut()
+                        .find(|arg| arg.starts_with("--disallowedTools="))
+                    {
+                        Some(existing) => {
+                            for tool in disallowed {
+                                existing.push(',');
+                                existing.push_str(tool);
+                            }
+                        }
+                        None => self
+                            .args
+                            .push(format!("--disallowedTools={}", disallowed.join(","))),
+                    }
+                }
+                true
+            }
+            "copilot" => {
+                if !allowed.is_empty() {
+                    self.args.retain(|arg| arg != "--allow-all-tools");
+                    for tool in allowed {
+                        self.args.push("--allow-tool".to_string());
+                        self.args.push(tool.clone());
+                    }
+                }
+                for tool in disallowed {
+                    self.args.push("--deny-tool".to_string());
+                    self.args.push(tool.clone());
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Creates the Claude backend.
     ///
     /// Uses `-p` flag for headless/print mode execution. This runs Claude
@@ -114,6 +364,8 @@ impl CliBackend {
             prompt_flag: Some("-p".to_string()),
             output_format: OutputFormat::StreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::claude(),
+            continue_flag: Some("--continue".to_string()),
         }
     }
 
@@ -137,6 +389,8 @@ impl CliBackend {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::claude(),
+            continue_flag: None,
         }
     }
 
@@ -155,6 +409,8 @@ impl CliBackend {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -175,6 +431,8 @@ impl CliBackend {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         backend.args.extend(extra_args.iter().cloned());
         backend
@@ -234,10 +492,36 @@ impl CliBackend {
                 prompt_flag: None,
                 output_format: OutputFormat::Text,
                 env_vars: vec![],
+                large_prompt: LargePrompt::default(),
+                continue_flag: None,
             }),
         }
     }
 
+    /// Creates a backend from a HatBackend configuration, resolving named
+    /// backends against `custom_backends` before the built-in ones.
+    ///
+    /// # Errors
+    /// Returns error if the backend configuration is invalid.
+    pub fn from_hat_backend_with_custom(
+        hat_backend: &HatBackend,
+        custom_backends: &BTreeMap<String, CustomBackendConfig>,
+    ) -> Result<Self, CustomBackendError> {
+        let (name, extra_args) = match hat_backend {
+            HatBackend::Named(name) => (name, &[][..]),
+            HatBackend::NamedWithArgs { backend_type, args } => (backend_type, args.as_slice()),
+            _ => return Self::from_hat_backend(hat_backend),
+        };
+        match custom_backends.get(name) {
+            Some(custom) => {
+                let mut backend = Self::from_custom_backend(custom);
+                backend.args.extend(extra_args.iter().cloned());
+                Ok(backend)
+            }
+            None => Self::from_hat_backend(hat_backend),
+        }
+    }
+
     /// Creates the Gemini backend.
     pub fn gemini() -> Self {
         Self {
@@ -247,6 +531,8 @@ impl CliBackend {
             prompt_flag: Some("-p".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -259,6 +545,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -271,6 +559,8 @@ impl CliBackend {
             prompt_flag: Some("-x".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -286,6 +576,8 @@ impl CliBackend {
             prompt_flag: Some("-p".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -302,6 +594,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -323,6 +617,8 @@ impl CliBackend {
                 "CLAUDE_CODE_EXPERIMENTAL_AGENT_TEAMS".to_string(),
                 "1".to_string(),
             )],
+            large_prompt: LargePrompt::claude(),
+            continue_flag: None,
         }
     }
 
@@ -358,6 +654,24 @@ impl CliBackend {
         }
     }
 
+    /// Creates a headless backend whose output is plain text, for running a
+    /// single prompt and capturing what the agent printed.
+    ///
+    /// Same as [`Self::from_name`] except Claude, which runs with `-p` but
+    /// without `--output-format stream-json`.
+    ///
+    /// # Errors
+    /// Returns `CustomBackendError` if the backend name is not recognized.
+    pub fn for_print_prompt(backend_name: &str) -> Result<Self, CustomBackendError> {
+        match backend_name {
+            "claude" => Ok(Self {
+                prompt_flag: Some("-p".to_string()),
+                ..Self::claude_interactive()
+            }),
+            _ => Self::from_name(backend_name),
+        }
+    }
+
     /// Kiro in interactive mode (removes --no-interactive).
     ///
     /// Unlike headless `kiro()`, this allows the user to interact with
@@ -370,6 +684,8 @@ impl CliBackend {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -385,6 +701,8 @@ impl CliBackend {
             prompt_flag: Some("-i".to_string()), // NOT -p!
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -400,6 +718,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -415,6 +735,8 @@ impl CliBackend {
             prompt_flag: Some("-x".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -430,6 +752,8 @@ impl CliBackend {
             prompt_flag: Some("-p".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -450,6 +774,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -468,6 +794,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -488,6 +816,8 @@ impl CliBackend {
             prompt_flag: Some("--prompt".to_string()),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -508,6 +838,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::PiStreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -523,6 +855,8 @@ impl CliBackend {
             prompt_flag: None, // Positional argument
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         }
     }
 
@@ -545,6 +879,11 @@ impl CliBackend {
             prompt_flag: config.prompt_flag.clone(),
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt {
+                delivery: LargePromptDelivery::Stdin,
+                ..LargePrompt::default()
+            },
+            continue_flag: None,
         })
     }
 
@@ -565,38 +904,18 @@ impl CliBackend {
             args = self.filter_args_for_interactive(args);
         }
 
-        // Handle large prompts for Claude (>7000 chars)
+        // Prompts over the threshold leave the command line, which the OS
+        // limits in size
         let (stdin_input, temp_file) = match self.prompt_mode {
+            PromptMode::Arg if self.large_prompt.applies_to(prompt) => {
+                self.push_large_prompt(&mut args, prompt)
+            }
             PromptMode::Arg => {
-                let (prompt_text, temp_file) = if self.command == "claude" && prompt.len() > 7000 {
-                    // Write to temp file and instruct Claude to read it
-                    match NamedTempFile::new() {
-                        Ok(mut file) => {
-                            if let Err(e) = file.write_all(prompt.as_bytes()) {
-                                tracing::warn!("Failed to write prompt to temp file: {}", e);
-                                (prompt.to_string(), None)
-                            } else {
-                                let path = file.path().display().to_string();
-                                (
-                                    format!("Please read and execute the task in {}", path),
-                                    Some(file),
-                                )
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to create temp file: {}", e);
-                            (prompt.to_string(), None)
-                        }
-                    }
-                } else {
-                    (prompt.to_string(), None)
-                };
-
                 if let Some(ref flag) = self.prompt_flag {
                     args.push(flag.clone());
                 }
-                args.push(prompt_text);
-                (None, temp_file)
+                args.push(prompt.to_string());
+                (None, None)
             }
             PromptMode::Stdin => (Some(prompt.to_string()), None),
         };
@@ -617,6 +936,52 @@ impl CliBackend {
         (self.command.clone(), args, stdin_input, temp_file)
     }
 
+    /// Passes a prompt over the large-prompt threshold per
+    /// `large_prompt.delivery`, returning the stdin input and the temp file
+    /// to keep alive until the process exits. Falls back to the argument if
+    /// the temp file cannot be written.
+    fn push_large_prompt(
+        &self,
+        args: &mut Vec<String>,
+        prompt: &str,
+    ) -> (Option<String>, Option<NamedTempFile>) {
+        if self.large_prompt.delivery == LargePromptDelivery::Stdin {
+            return (Some(prompt.to_string()), None);
+        }
+
+        let file = NamedTempFile::new().and_then(|mut file| {
+            file.write_all(prompt.as_bytes())?;
+            file.flush()?;
+            Ok(file)
+        });
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Failed to write prompt to temp file: {}", e);
+                if let Some(ref flag) = self.prompt_flag {
+                    args.push(flag.clone());
+                }
+                args.push(prompt.to_string());
+                return (None, None);
+            }
+        };
+        let path = file.path().display().to_string();
+
+        match &self.large_prompt.delivery {
+            LargePromptDelivery::FileFlag(file_flag) => {
+                args.push(file_flag.clone());
+                args.push(path);
+            }
+            _ => {
+                if let Some(ref flag) = self.prompt_flag {
+                    args.push(flag.clone());
+                }
+                args.push(format!("Please read and execute the task in {}", path));
+            }
+        }
+        (None, Some(file))
+    }
+
     /// Filters args for interactive mode per spec table.
     fn filter_args_for_interactive(&self, args: Vec<String>) -> Vec<String> {
         match self.command.as_str() {
@@ -750,6 +1115,55 @@ mod tests {
         assert!(temp.is_none());
     }
 
+    #[test]
+    fn test_large_prompt_threshold_from_config() {
+        let config = CliConfig {
+            backend: "gemini".to_string(),
+            large_prompt_threshold: Some(0),
+            ..Default::default()
+        };
+        let backend = CliBackend::from_config(&config).unwrap();
+        let (_cmd, args, stdin, temp) = backend.build_command("short", false);
+
+        let temp = temp.expect("threshold 0 sends every prompt through a file");
+        assert_eq!(std::fs::read_to_string(temp.path()).unwrap(), "short");
+        assert_eq!(
+            args.last().unwrap(),
+            &format!(
+                "Please read and execute the task in {}",
+                temp.path().display()
+            )
+        );
+        assert!(stdin.is_none());
+    }
+
+    #[test]
+    fn test_custom_backend_large_prompt_uses_file_flag_or_stdin() {
+        let mut custom = CustomBackendConfig::new("aider");
+        custom.prompt_mode = CustomPromptMode::Flag;
+        custom.prompt_flag = Some("--message".to_string());
+        let large_prompt = "x".repeat(DEFAULT_LARGE_PROMPT_THRESHOLD + 1);
+
+        let (_cmd, args, stdin, temp) =
+            CliBackend::from_custom_backend(&custom).build_command(&large_prompt, false);
+        assert!(args.is_empty());
+        assert_eq!(stdin.as_deref(), Some(large_prompt.as_str()));
+        assert!(temp.is_none());
+
+        custom.prompt_file_flag = Some("--message-file".to_string());
+        let (_cmd, args, stdin, temp) =
+            CliBackend::from_custom_backend(&custom).build_command(&large_prompt, false);
+        let temp = temp.expect("prompt written to a temp file");
+        assert_eq!(
+            args,
+            vec![
+                "--message-file".to_string(),
+                temp.path().display().to_string()
+            ]
+        );
+        assert!(stdin.is_none());
+    }
+
     #[test]
     fn test_kiro_backend() {
         let backend = CliBackend::kiro();
@@ -1006,6 +1420,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_ralph_config_custom_backends() {
+        let mut config = RalphConfig::default();
+        config.cli.backend = "aider".to_string();
+        config.cli.args = vec!["--no-git".to_string()];
+        let mut aider = CustomBackendConfig::new("aider");
+        aider.prompt_mode = CustomPromptMode::Flag;
+        aider.prompt_flag = Some("--message".to_string());
+        aider.args = vec!["--yes-always".to_string()];
+        let mut piped = CustomBackendConfig::new("piped-agent");
+        piped.prompt_mode = CustomPromptMode::Stdin;
+        piped.output_format = CustomOutputFormat::StreamJson;
+        config.adapters.custom_backends =
+            BTreeMap::from([("aider".to_string(), aider), ("piped".to_string(), piped)]);
+
+        let backend = CliBackend::from_ralph_config(&config).unwrap();
+        let (cmd, args, stdin, _temp) = backend.build_command("test prompt", false);
+        assert_eq!(cmd, "aider");
+        assert_eq!(
+            args,
+            vec!["--yes-always", "--no-git", "--message", "test prompt"]
+        );
+        assert!(stdin.is_none());
+
+        let hat_backend = HatBackend::NamedWithArgs {
+            backend_type: "piped".to_string(),
+            args: vec!["--fast".to_string()],
+        };
+        let backend = CliBackend::from_hat_backend_with_custom(
+            &hat_backend,
+            &config.adapters.custom_backends,
+        )
+        .unwrap();
+        let (cmd, args, stdin, _temp) = backend.build_command("test prompt", false);
+        assert_eq!(cmd, "piped-agent");
+        assert_eq!(args, vec!["--fast"]);
+        assert_eq!(stdin.as_deref(), Some("test prompt"));
+        assert_eq!(backend.output_format, OutputFormat::StreamJson);
+
+        // Built-in names still resolve when not overridden
+        let backend = CliBackend::from_hat_backend_with_custom(
+            &HatBackend::Named("gemini".to_string()),
+            &config.adapters.custom_backends,
+        )
+        .unwrap();
+        assert_eq!(backend.command, "gemini");
+    }
+
     #[test]
     fn test_kiro_with_agent() {
         let backend = CliBackend::kiro_with_agent("my-agent".to_string(), &[]);
@@ -1201,6 +1663,30 @@ mod tests {
         assert_eq!(backend.prompt_flag, None);
     }
 
+    #[test]
+    fn test_for_print_prompt_claude_uses_text_output() {
+        let backend = CliBackend::for_print_prompt("claude").unwrap();
+        let (cmd, args, stdin, _temp) = backend.build_command("test prompt", false);
+
+        assert_eq!(cmd, "claude");
+        assert_eq!(
+            args,
+            vec![
+                "--dangerously-skip-permissions",
+                "--disallowedTools=TodoWrite,TaskCreate,TaskUpdate,TaskList,TaskGet",
+                "-p",
+                "test prompt"
+            ]
+        );
+        assert!(stdin.is_none());
+        assert_eq!(backend.output_format, OutputFormat::Text);
+        assert_eq!(
+            CliBackend::for_print_prompt("kiro").unwrap().args,
+            CliBackend::kiro().args
+        );
+        assert!(CliBackend::for_print_prompt("nope").is_err());
+    }
+
     #[test]
     fn test_for_interactive_prompt_kiro() {
         let backend = CliBackend::for_interactive_prompt("kiro").unwrap();
@@ -1551,6 +2037,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_config_applies_cli_env() {
+        let mut config = CliConfig {
+            backend: "custom".to_string(),
+            command: Some("my-agent".to_string()),
+            ..Default::default()
+        };
+        config.env.insert(
+            "OPENAI_BASE_URL".to_string(),
+            "http://localhost:8080".to_string(),
+        );
+        let backend = CliBackend::from_config(&config).unwrap();
+        assert_eq!(
+            backend.env_vars,
+            vec![(
+                "OPENAI_BASE_URL".to_string(),
+                "http://localhost:8080".to_string()
+            )]
+        );
+
+        config.backend = "claude".to_string();
+        let backend = CliBackend::from_config(&config).unwrap();
+        assert_eq!(backend.env_vars.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_env_overrides_existing_keys() {
+        let mut backend = CliBackend::claude();
+        backend.env_vars = vec![
+            ("A".to_string(), "global".to_string()),
+            ("B".to_string(), "kept".to_string()),
+        ];
+        let hat_env = BTreeMap::from([
+            ("A".to_string(), "hat".to_string()),
+            ("C".to_string(), "new".to_string()),
+        ]);
+        backend.merge_env(&hat_env);
+        assert_eq!(
+            backend.env_vars,
+            vec![
+                ("A".to_string(), "hat".to_string()),
+                ("B".to_string(), "kept".to_string()),
+                ("C".to_string(), "new".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_env_reads_keychain_entries() {
+        struct Keychain;
+        impl SecretStore for Keychain {
+            fn get_secret(&self, entry: &str) -> Option<String> {
+                (entry == "npm-token").then(|| "npm_s3cr3t".to_string())
+            }
+        }
+
+        let mut backend = CliBackend::claude();
+        let env = BTreeMap::from([
+            ("NPM_TOKEN".to_string(), "keychain:npm-token".to_string()),
+            ("PYPI_TOKEN".to_string(), "keychain:missing".to_string()),
+        ]);
+        backend.merge_env_from(&env, &Keychain);
+        assert_eq!(
+            backend.env_vars,
+            vec![
+                ("NPM_TOKEN".to_string(), "npm_s3cr3t".to_string()),
+                ("PYPI_TOKEN".to_string(), String::new()),
+            ]
+        );
+    }
+
     #[test]
     fn test_env_vars_default_empty() {
         // All non-teams constructors should have empty env_vars
@@ -1564,4 +2121,46 @@ mod tests {
         assert!(CliBackend::opencode().env_vars.is_empty());
         assert!(CliBackend::pi().env_vars.is_empty());
     }
+
+    #[test]
+    fn test_iteration_prompt_continues_only_same_resumable_backend() {
+        let claude = CliBackend::claude();
+        assert!(claude.supports_continuation());
+        assert_eq!(claude.iteration_prompt(true, None), IterationPrompt::Full);
+        assert_eq!(
+            claude.iteration_prompt(true, Some(&claude)),
+            IterationPrompt::Continuation
+        );
+        assert_eq!(
+            claude.iteration_prompt(false, Some(&claude)),
+            IterationPrompt::Full,
+            "continuation is opt-in"
+        );
+
+        let gemini = CliBackend::gemini();
+        assert!(!gemini.supports_continuation());
+        assert_eq!(
+            gemini.iteration_prompt(true, Some(&gemini)),
+            IterationPrompt::Full
+        );
+        assert_eq!(
+            claude.iteration_prompt(true, Some(&gemini)),
+            IterationPrompt::Full,
+            "a session from another backend cannot be resumed"
+        );
+    }
+
+    #[test]
+    fn test_continue_session_adds_flag_once() {
+        let mut claude = CliBackend::claude();
+        claude.continue_session();
+        claude.continue_session();
+        let (_, args, _, _) = claude.build_command("next step", false);
+        assert_eq!(args.iter().filter(|a| *a == "--continue").count(), 1);
+
+        let mut gemini = CliBackend::gemini();
+        let before = gemini.args.clone();
+        gemini.continue_session();
+        assert_eq!(gemini.args, before);
+    }
 }
@@ -1,20 +1,30 @@
 //! CLI executor for running prompts through backends.
 //!
-//! Executes prompts via CLI tools with real-time streaming output.
-//! Supports optional execution timeout with graceful SIGTERM termination.
+//! Executes prompts via CLI tools, either buffering the whole output or
+//! streaming it line by line as it arrives, optionally parsed into
+//! [`BackendEvent`]s. Supports optional execution and idle timeouts with
+//! graceful SIGTERM termination.
 
+use crate::backend_event::{BackendEvent, StreamJsonParser};
 use crate::cli_backend::CliBackend;
 #[cfg(test)]
 use crate::cli_backend::{OutputFormat, PromptMode};
+use futures::Stream;
 #[cfg(unix)]
-use nix::sys::signal::{Signal, kill};
+use nix::sys::signal::{Signal, kill, killpg};
 #[cfg(unix)]
 use nix::unistd::Pid;
+use ralph_core::Redactor;
 use std::io::Write;
+use std::pin::Pin;
 use std::process::Stdio;
+use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tracing::{debug, warn};
 
 /// Result of a CLI execution.
@@ -22,31 +32,140 @@ use tracing::{debug, warn};
 pub struct ExecutionResult {
     /// The full output from the CLI.
     pub output: String,
+    /// Stderr lines from the CLI, also appended to `output` with a
+    /// `[stderr]` prefix.
+    pub stderr: String,
     /// Whether the execution succeeded (exit code 0).
     pub success: bool,
     /// The exit code.
     pub exit_code: Option<i32>,
     /// Whether the execution was terminated due to timeout.
     pub timed_out: bool,
+    /// Whether the execution was terminated because it produced no output
+    /// within the idle timeout.
+    pub idle_timed_out: bool,
 }
 
-/// Executor for running prompts through CLI backends.
+/// A line of output from a streaming execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputLine<'a> {
+    /// A line written to stdout.
+    Stdout(&'a str),
+    /// A line written to stderr.
+    Stderr(&'a str),
+}
+
+/// What a streaming execution should do after a line has been handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineAction {
+    /// Keep reading output.
+    Continue,
+    /// Terminate the process and stop reading.
+    Stop,
+}
+
+/// Result of a streaming CLI execution. The output itself has already been
+/// delivered line by line.
+#[derive(Debug)]
+pub struct StreamResult {
+    /// Whether the execution succeeded (exit code 0, no timeout).
+    pub success: bool,
+    /// The exit code.
+    pub exit_code: Option<i32>,
+    /// Whether the execution was terminated due to the overall timeout.
+    pub timed_out: bool,
+    /// Whether the execution was terminated because no output arrived
+    /// within the idle timeout.
+    pub idle_timed_out: bool,
+    /// Whether the line callback asked to stop the process.
+    pub stopped: bool,
+}
+
+/// A line of output from [`CliExecutor::stream_events`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendLine {
+    /// The line as the backend wrote it, kept for recordings. Empty for the
+    /// final line carrying events only the end of output reveals.
+    pub raw: String,
+    /// Whether the line was written to stderr. Stderr is not parsed.
+    pub stderr: bool,
+    /// Events parsed from the line.
+    pub events: Vec<BackendEvent>,
+}
+
+/// Async stream of parsed output lines from a running backend.
+///
+/// Dropping the stream terminates the backend at its next line of output.
 #[derive(Debug)]
+pub struct BackendEventStream {
+    lines: mpsc::UnboundedReceiver<BackendLine>,
+    execution: JoinHandle<std::io::Result<StreamResult>>,
+}
+
+impl BackendEventStream {
+    /// Waits for the backend to exit, discarding lines not yet read.
+    pub async fn finish(mut self) -> std::io::Result<StreamResult> {
+        while self.lines.recv().await.is_some() {}
+        self.execution.await.map_err(std::io::Error::other)?
+    }
+}
+
+impl Stream for BackendEventStream {
+    type Item = BackendLine;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.lines.poll_recv(cx)
+    }
+}
+
+/// Executor for running prompts through CLI backends.
+#[derive(Debug, Clone)]
 pub struct CliExecutor {
     backend: CliBackend,
+    /// Seconds without output before the process is terminated (0 = never).
+    idle_timeout_secs: u32,
+    /// Masks secrets in the logged command line.
+    redactor: Redactor,
 }
 
 impl CliExecutor {
     /// Creates a new executor with the given backend.
     pub fn new(backend: CliBackend) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            idle_timeout_secs: 0,
+            redactor: Redactor::default(),
+        }
+    }
+
+    /// Masks secrets matched by `redactor` when logging the spawned command.
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Terminates buffered executions that produce no output for
+    /// `idle_timeout_secs` seconds. 0 disables the idle timeout.
+    #[must_use]
+    pub fn with_idle_timeout_secs(mut self, idle_timeout_secs: u32) -> Self {
+        self.idle_timeout_secs = idle_timeout_secs;
+        self
+    }
+
+    /// The idle timeout applied to buffered executions, if any.
+    fn idle_timeout(&self) -> Option<Duration> {
+        (self.idle_timeout_secs > 0).then(|| Duration::from_secs(u64::from(self.idle_timeout_secs)))
     }
 
     /// Executes a prompt and streams output to the provided writer.
     ///
-    /// Output is streamed line-by-line to the writer while being accumulated
-    /// for the return value. If `timeout` is provided and the execution exceeds
-    /// it, the process receives SIGTERM and the result indicates timeout.
+    /// Output is buffered until the process exits, then written to the writer
+    /// (stdout first, then stderr) and returned. If `timeout` is provided and
+    /// the execution exceeds it, or the configured idle timeout passes without
+    /// output, the process group receives SIGTERM and the result says which
+    /// limit was hit. Use [`execute_streaming`](Self::execute_streaming)
+    /// to see lines as they arrive.
     ///
     /// When `verbose` is true, stderr output is also written to the output writer
     /// with a `[stderr]` prefix. When false, stderr is captured but not displayed.
@@ -57,8 +176,88 @@ impl CliExecutor {
         timeout: Option<Duration>,
         verbose: bool,
     ) -> std::io::Result<ExecutionResult> {
+        let mut stdout_lines = Vec::new();
+        let mut stderr_lines = Vec::new();
+        let result = self
+            .execute_streaming(prompt, timeout, self.idle_timeout(), |line| {
+                match line {
+                    OutputLine::Stdout(line) => stdout_lines.push(line.to_string()),
+                    OutputLine::Stderr(line) => stderr_lines.push(line.to_string()),
+                }
+                LineAction::Continue
+            })
+            .await?;
+
+        if result.timed_out {
+            // Partial output is discarded on timeout
+            return Ok(ExecutionResult {
+                output: String::new(),
+                stderr: join_lines(&stderr_lines),
+                success: false,
+                exit_code: result.exit_code,
+                timed_out: true,
+                idle_timed_out: false,
+            });
+        }
+
+        // Write stdout lines first (main output)
+        for line in &stdout_lines {
+            writeln!(output_writer, "{line}")?;
+        }
+
+        // Write stderr lines (prefixed) only in verbose mode
+        if verbose {
+            for line in &stderr_lines {
+                writeln!(output_writer, "[stderr] {line}")?;
+            }
+        }
+
+        output_writer.flush()?;
+
+        // Build accumulated output (stdout first, then stderr)
+        let mut accumulated = String::new();
+        for line in stdout_lines {
+            accumulated.push_str(&line);
+            accumulated.push('\n');
+        }
+        for line in &stderr_lines {
+            accumulated.push_str("[stderr] ");
+            accumulated.push_str(line);
+            accumulated.push('\n');
+        }
+
+        Ok(ExecutionResult {
+            output: accumulated,
+            stderr: join_lines(&stderr_lines),
+            success: result.success,
+            exit_code: result.exit_code,
+            timed_out: false,
+            idle_timed_out: result.idle_timed_out,
+        })
+    }
+
+    /// Executes a prompt, passing each output line to `on_line` as it arrives.
+    ///
+    /// Nothing is buffered: stdout and stderr lines are delivered in arrival
+    /// order, so callers can parse events or spot a completion promise while
+    /// the process is still running. Returning [`LineAction::Stop`] from
+    /// `on_line` sends SIGTERM and stops reading.
+    ///
+    /// `timeout` bounds the whole execution; `idle_timeout` bounds the time
+    /// since the last byte of output (a partial line counts as activity).
+    /// Either one expiring sends SIGTERM to the process group.
+    pub async fn execute_streaming<F>(
+        &self,
+        prompt: &str,
+        timeout: Option<Duration>,
+        idle_timeout: Option<Duration>,
+        mut on_line: F,
+    ) -> std::io::Result<StreamResult>
+    where
+        F: FnMut(OutputLine<'_>) -> LineAction + Send,
+    {
         // Note: _temp_file is kept alive for the duration of this function scope.
-        // For large prompts (>7000 chars), Claude reads from the temp file.
+        // Prompts over the backend's large-prompt threshold are read from it.
         let (cmd, args, stdin_input, _temp_file) = self.backend.build_command(prompt, false);
 
         let mut command = Command::new(&cmd);
@@ -74,9 +273,10 @@ impl CliExecutor {
         // Apply backend-specific environment variables (e.g., Agent Teams env var)
         command.envs(self.backend.env_vars.iter().map(|(k, v)| (k, v)));
 
+        let logged_args: Vec<_> = args.iter().map(|arg| self.redactor.redact(arg)).collect();
         debug!(
             command = %cmd,
-            args = ?args,
+            args = ?logged_args,
             cwd = ?cwd,
             "Spawning CLI command"
         );
@@ -85,114 +285,156 @@ impl CliExecutor {
             command.stdin(Stdio::piped());
         }
 
+        // Run in its own process group so termination reaches any
+        // subprocesses the backend started
+        #[cfg(unix)]
+        command.process_group(0);
+
         let mut child = command.spawn()?;
 
-        // Write to stdin if needed
+        // Write to stdin while the output is read below: a large prompt
+        // would otherwise fill the pipe while the backend waits for us to
+        // drain its output. Dropping stdin at the end signals EOF.
         if let Some(input) = stdin_input
             && let Some(mut stdin) = child.stdin.take()
         {
-            stdin.write_all(input.as_bytes()).await?;
-            drop(stdin); // Close stdin to signal EOF
+            tokio::spawn(async move {
+                if let Err(e) = stdin.write_all(input.as_bytes()).await {
+                    debug!(error = %e, "Backend closed stdin before reading the whole prompt");
+                }
+            });
         }
 
-        let mut timed_out = false;
-
-        // Take both stdout and stderr handles upfront to read concurrently
-        // This prevents deadlock when stderr fills its buffer before stdout produces output
-        let stdout_handle = child.stdout.take();
-        let stderr_handle = child.stderr.take();
-
-        // Wrap the streaming in a timeout if configured
         // Read stdout and stderr CONCURRENTLY to avoid pipe buffer deadlock
-        let stream_result = async {
-            // Create futures for reading both streams
-            let stdout_future = async {
-                let mut lines_out = Vec::new();
-                if let Some(stdout) = stdout_handle {
-                    let reader = BufReader::new(stdout);
-                    let mut lines = reader.lines();
-                    while let Some(line) = lines.next_line().await? {
-                        lines_out.push(line);
-                    }
-                }
-                Ok::<_, std::io::Error>(lines_out)
-            };
+        // (stderr filling its buffer before stdout produces output)
+        let mut stdout = child.stdout.take();
+        let mut stderr = child.stderr.take();
+        let mut stdout_pending = Vec::new();
+        let mut stderr_pending = Vec::new();
+        let mut stdout_chunk = vec![0u8; 8192];
+        let mut stderr_chunk = vec![0u8; 8192];
+
+        let deadline = timeout.map(|duration| {
+            debug!(timeout_secs = duration.as_secs(), "Executing with timeout");
+            Instant::now() + duration
+        });
+        let mut last_output = Instant::now();
 
-            let stderr_future = async {
-                let mut lines_out = Vec::new();
-                if let Some(stderr) = stderr_handle {
-                    let reader = BufReader::new(stderr);
-                    let mut lines = reader.lines();
-                    while let Some(line) = lines.next_line().await? {
-                        lines_out.push(line);
+        let mut timed_out = false;
+        let mut idle_timed_out = false;
+        let mut stopped = false;
+
+        while stdout.is_some() || stderr.is_some() {
+            let idle_deadline = idle_timeout.map(|idle| last_output + idle);
+            let action = tokio::select! {
+                read = read_chunk(&mut stdout, &mut stdout_chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        stdout = None;
+                    } else {
+                        last_output = Instant::now();
+                        stdout_pending.extend_from_slice(&stdout_chunk[..n]);
                     }
-                }
-                Ok::<_, std::io::Error>(lines_out)
+                    deliver_lines(&mut stdout_pending, n == 0, false, &mut on_line)
+                },
+                read = read_chunk(&mut stderr, &mut stderr_chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        stderr = None;
+                    } else {
+                        last_output = Instant::now();
+                        stderr_pending.extend_from_slice(&stderr_chunk[..n]);
+                    }
+                    deliver_lines(&mut stderr_pending, n == 0, true, &mut on_line)
+                },
+                () = sleep_until(deadline) => {
+                    warn!(
+                        timeout_secs = timeout.unwrap_or_default().as_secs(),
+                        "Execution timeout reached, sending SIGTERM"
+                    );
+                    timed_out = true;
+                    LineAction::Stop
+                },
+                () = sleep_until(idle_deadline) => {
+                    warn!(
+                        idle_timeout_secs = idle_timeout.unwrap_or_default().as_secs(),
+                        "No output within idle timeout, sending SIGTERM"
+                    );
+                    idle_timed_out = true;
+                    LineAction::Stop
+                },
             };
 
-            // Read both streams concurrently to prevent deadlock
-            let (stdout_lines, stderr_lines) = tokio::try_join!(stdout_future, stderr_future)?;
-
-            // Write stdout lines first (main output)
-            for line in &stdout_lines {
-                writeln!(output_writer, "{line}")?;
-            }
-
-            // Write stderr lines (prefixed) only in verbose mode
-            if verbose {
-                for line in &stderr_lines {
-                    writeln!(output_writer, "[stderr] {line}")?;
-                }
-            }
-
-            output_writer.flush()?;
-
-            // Build accumulated output (stdout first, then stderr)
-            let mut accumulated = String::new();
-            for line in stdout_lines {
-                accumulated.push_str(&line);
-                accumulated.push('\n');
-            }
-            for line in stderr_lines {
-                accumulated.push_str("[stderr] ");
-                accumulated.push_str(&line);
-                accumulated.push('\n');
-            }
-
-            Ok::<_, std::io::Error>(accumulated)
-        };
-
-        let accumulated_output = match timeout {
-            Some(duration) => {
-                debug!(timeout_secs = duration.as_secs(), "Executing with timeout");
-                match tokio::time::timeout(duration, stream_result).await {
-                    Ok(result) => result?,
-                    Err(_) => {
-                        // Timeout elapsed - send SIGTERM to the child process
-                        warn!(
-                            timeout_secs = duration.as_secs(),
-                            "Execution timeout reached, sending SIGTERM"
-                        );
-                        timed_out = true;
-                        Self::terminate_child(&mut child)?;
-                        String::new() // Return empty output on timeout
-                    }
-                }
+            if action == LineAction::Stop {
+                stopped = !timed_out && !idle_timed_out;
+                Self::terminate_child(&mut child)?;
+                break;
             }
-            None => stream_result.await?,
-        };
+        }
 
         let status = child.wait().await?;
 
-        Ok(ExecutionResult {
-            output: accumulated_output,
-            success: status.success() && !timed_out,
+        Ok(StreamResult {
+            success: status.success() && !timed_out && !idle_timed_out,
             exit_code: status.code(),
             timed_out,
+            idle_timed_out,
+            stopped,
         })
     }
 
-    /// Terminates the child process with SIGTERM.
+    /// Executes a prompt, parsing its output into [`BackendEvent`]s as it
+    /// arrives.
+    ///
+    /// Stdout is parsed as Claude stream-json by a [`StreamJsonParser`];
+    /// other output comes through as raw text. The configured idle timeout
+    /// applies. Must be called within a Tokio runtime: the backend runs on a
+    /// spawned task, and [`BackendEventStream::finish`] returns its result.
+    pub fn stream_events(&self, prompt: &str, timeout: Option<Duration>) -> BackendEventStream {
+        let (tx, lines) = mpsc::unbounded_channel();
+        let executor = self.clone();
+        let prompt = prompt.to_string();
+
+        let execution = tokio::spawn(async move {
+            let mut parser = StreamJsonParser::new();
+            let result = executor
+                .execute_streaming(&prompt, timeout, executor.idle_timeout(), |line| {
+                    let line = match line {
+                        OutputLine::Stdout(raw) => BackendLine {
+                            raw: raw.to_string(),
+                            stderr: false,
+                            events: parser.parse_line(raw),
+                        },
+                        OutputLine::Stderr(raw) => BackendLine {
+                            raw: raw.to_string(),
+                            stderr: true,
+                            events: Vec::new(),
+                        },
+                    };
+                    // A dropped stream means nobody is listening any more
+                    if tx.send(line).is_err() {
+                        LineAction::Stop
+                    } else {
+                        LineAction::Continue
+                    }
+                })
+                .await;
+
+            let events = parser.finish();
+            if !events.is_empty() {
+                let _ = tx.send(BackendLine {
+                    raw: String::new(),
+                    stderr: false,
+                    events,
+                });
+            }
+            result
+        });
+
+        BackendEventStream { lines, execution }
+    }
+
+    /// Terminates the child's process group with SIGTERM.
     fn terminate_child(child: &mut tokio::process::Child) -> std::io::Result<()> {
         #[cfg(not(unix))]
         {
@@ -205,8 +447,12 @@ impl CliExecutor {
         if let Some(pid) = child.id() {
             #[allow(clippy::cast_possible_wrap)]
             let pid = Pid::from_raw(pid as i32);
-            debug!(%pid, "Sending SIGTERM to child process");
-            let _ = kill(pid, Signal::SIGTERM);
+            debug!(%pid, "Sending SIGTERM to child process group");
+            // The child leads its own process group; fall back to the child
+            // alone if the group is already gone
+            if killpg(pid, Signal::SIGTERM).is_err() {
+                let _ = kill(pid, Signal::SIGTERM);
+            }
             Ok(())
         } else {
             Ok(())
@@ -233,9 +479,74 @@ impl CliExecutor {
     }
 }
 
+/// Reads a chunk from the stream, or waits forever once it is closed.
+async fn read_chunk<R: AsyncRead + Unpin>(
+    stream: &mut Option<R>,
+    buf: &mut [u8],
+) -> std::io::Result<usize> {
+    match stream {
+        Some(stream) => stream.read(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Passes each complete line in `pending` to `on_line`, keeping any partial
+/// line for later. At `eof` the partial line is delivered too.
+fn deliver_lines<F>(
+    pending: &mut Vec<u8>,
+    eof: bool,
+    is_stderr: bool,
+    on_line: &mut F,
+) -> LineAction
+where
+    F: FnMut(OutputLine<'_>) -> LineAction,
+{
+    let wrap = |line: &[u8], on_line: &mut F| {
+        let text = String::from_utf8_lossy(line);
+        if is_stderr {
+            on_line(OutputLine::Stderr(&text))
+        } else {
+            on_line(OutputLine::Stdout(&text))
+        }
+    };
+    while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+        let mut line: Vec<u8> = pending.drain(..=pos).collect();
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if wrap(&line, on_line) == LineAction::Stop {
+            return LineAction::Stop;
+        }
+    }
+    if eof && !pending.is_empty() {
+        let line = std::mem::take(pending);
+        return wrap(&line, on_line);
+    }
+    LineAction::Continue
+}
+
+/// Joins lines back into text, each terminated by a newline.
+fn join_lines(lines: &[String]) -> String {
+    lines.iter().fold(String::new(), |mut text, line| {
+        text.push_str(line);
+        text.push('\n');
+        text
+    })
+}
+
+/// Sleeps until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli_backend::{LargePrompt, LargePromptDelivery};
 
     #[tokio::test]
     async fn test_execute_echo() {
@@ -247,6 +558,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
 
         let executor = CliExecutor::new(backend);
@@ -272,6 +585,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
 
         let executor = CliExecutor::new(backend);
@@ -281,6 +596,73 @@ mod tests {
         assert!(result.output.contains("stdin test"));
     }
 
+    /// 4 MiB: far over Linux's 128 KiB limit for a single argument.
+    const HUGE_PROMPT_LEN: usize = 4 * 1024 * 1024;
+
+    #[tokio::test]
+    async fn test_huge_arg_prompt_is_piped_to_stdin() {
+        let backend = CliBackend {
+            command: "wc".to_string(),
+            args: vec!["-c".to_string()],
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+            env_vars: vec![],
+            large_prompt: LargePrompt {
+                delivery: LargePromptDelivery::Stdin,
+                ..LargePrompt::default()
+            },
+            continue_flag: None,
+        };
+
+        let executor = CliExecutor::new(backend);
+        let result = executor
+            .execute_capture(&"x".repeat(HUGE_PROMPT_LEN))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{}", result.output);
+        assert_eq!(result.output.trim(), HUGE_PROMPT_LEN.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_huge_arg_prompt_uses_file_flag_and_cleans_up() {
+        // Prints the prompt file's size and path
+        let backend = CliBackend {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"wc -c < "$2"; echo "$2""#.to_string(),
+                "sh".to_string(),
+            ],
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+            env_vars: vec![],
+            large_prompt: LargePrompt {
+                delivery: LargePromptDelivery::FileFlag("--prompt-file".to_string()),
+                ..LargePrompt::default()
+            },
+            continue_flag: None,
+        };
+
+        let executor = CliExecutor::new(backend);
+        let result = executor
+            .execute_capture(&"x".repeat(HUGE_PROMPT_LEN))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{}", result.output);
+        let mut lines = result.output.lines();
+        assert_eq!(lines.next().unwrap().trim(), HUGE_PROMPT_LEN.to_string());
+        let path = std::path::PathBuf::from(lines.next().unwrap());
+        assert!(
+            !path.exists(),
+            "temp file {} was not removed",
+            path.display()
+        );
+    }
+
     #[tokio::test]
     async fn test_execute_failure() {
         let backend = CliBackend {
@@ -290,6 +672,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
 
         let executor = CliExecutor::new(backend);
@@ -312,6 +696,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
 
         let executor = CliExecutor::new(backend);
@@ -340,6 +726,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
 
         let executor = CliExecutor::new(backend);
@@ -355,4 +743,245 @@ mod tests {
         assert!(result.success);
         assert!(result.output.contains("fast"));
     }
+
+    fn shell_backend(script: &str) -> CliBackend {
+        CliBackend {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            prompt_mode: PromptMode::Stdin,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+            env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_reports_stderr_separately() {
+        let executor = CliExecutor::new(shell_backend("echo out; echo oops >&2; echo again >&2"));
+
+        let result = executor.execute_capture("").await.unwrap();
+
+        assert_eq!(result.stderr, "oops\nagain\n");
+        assert_eq!(result.output, "out\n[stderr] oops\n[stderr] again\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_delivers_lines_as_they_arrive() {
+        let executor = CliExecutor::new(shell_backend(
+            "echo first; echo oops >&2; sleep 0.2; echo second",
+        ));
+        let started = std::time::Instant::now();
+        let mut seen = Vec::new();
+
+        let result = executor
+            .execute_streaming("", None, None, |line| {
+                let text = match line {
+                    OutputLine::Stdout(text) => text.to_string(),
+                    OutputLine::Stderr(text) => format!("[stderr] {text}"),
+                };
+                seen.push((text, started.elapsed()));
+                LineAction::Continue
+            })
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        let lines: Vec<&str> = seen.iter().map(|(text, _)| text.as_str()).collect();
+        assert!(lines.contains(&"first"));
+        assert!(lines.contains(&"[stderr] oops"));
+        assert_eq!(lines.last(), Some(&"second"));
+        // "first" was delivered before the process finished sleeping
+        let first_at = seen.iter().find(|(text, _)| text == "first").unwrap().1;
+        assert!(first_at < Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_stop_terminates_process() {
+        let executor = CliExecutor::new(shell_backend("echo LOOP_COMPLETE; sleep 10; echo late"));
+        let started = std::time::Instant::now();
+        let mut lines = Vec::new();
+
+        let result = executor
+            .execute_streaming("", None, None, |line| {
+                lines.push(format!("{line:?}"));
+                if line == OutputLine::Stdout("LOOP_COMPLETE") {
+                    LineAction::Stop
+                } else {
+                    LineAction::Continue
+                }
+            })
+            .await
+            .unwrap();
+
+        assert!(result.stopped);
+        assert!(!result.timed_out);
+        assert_eq!(lines.len(), 1);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_idle_timeout() {
+        let executor = CliExecutor::new(shell_backend("echo started; sleep 10"));
+
+        let result = executor
+            .execute_streaming(
+                "",
+                Some(Duration::from_secs(30)),
+                Some(Duration::from_millis(200)),
+                |_| LineAction::Continue,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.idle_timed_out);
+        assert!(!result.timed_out);
+        assert!(!result.stopped);
+        assert!(!result.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_idle_timeout_kills_process_group() {
+        // The backgrounded sleep keeps the shell waiting without output
+        let executor =
+            CliExecutor::new(shell_backend("sleep 30 & echo $!; wait")).with_idle_timeout_secs(1);
+        let started = std::time::Instant::now();
+
+        let result = executor.execute_capture("").await.unwrap();
+
+        assert!(result.idle_timed_out, "Expected idle termination");
+        assert!(!result.timed_out);
+        assert!(!result.success);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // The grandchild was in the same process group and was terminated too
+        let sleep_pid: i32 = result.output.trim().parse().expect("sleep pid");
+        let mut alive = true;
+        for _ in 0..40 {
+            alive = kill(Pid::from_raw(sleep_pid), None).is_ok();
+            if !alive {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!alive, "background sleep should have been terminated");
+    }
+
+    #[tokio::test]
+    async fn test_execute_partial_lines_count_as_activity() {
+        // Output without newlines arrives more often than the idle window
+        let executor = CliExecutor::new(shell_backend(
+            "for i in 1 2 3 4 5; do printf x; sleep 0.4; done",
+        ))
+        .with_idle_timeout_secs(1);
+
+        let result = executor.execute_capture("").await.unwrap();
+
+        assert!(!result.idle_timed_out);
+        assert!(result.success);
+        assert_eq!(result.output, "xxxxx\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_merges_cli_and_hat_env() {
+        let mut config = ralph_core::CliConfig {
+            backend: "custom".to_string(),
+            command: Some("sh".to_string()),
+            prompt_mode: "stdin".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "echo \"$RALPH_A|$RALPH_B|$RALPH_PATH\"".to_string(),
+            ],
+            ..Default::default()
+        };
+        config.env = std::collections::BTreeMap::from([
+            ("RALPH_A".to_string(), "cli".to_string()),
+            ("RALPH_B".to_string(), "cli".to_string()),
+            ("RALPH_PATH".to_string(), "${PATH}".to_string()),
+        ]);
+        let mut backend = CliBackend::from_config(&config).unwrap();
+        backend.merge_env(&std::collections::BTreeMap::from([(
+            "RALPH_A".to_string(),
+            "hat".to_string(),
+        )]));
+
+        let result = CliExecutor::new(backend).execute_capture("").await.unwrap();
+
+        let path = std::env::var("PATH").unwrap_or_default();
+        assert_eq!(result.output.trim(), format!("hat|cli|{path}"));
+    }
+
+    fn script_backend(script: &str) -> CliBackend {
+        CliBackend {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            prompt_mode: PromptMode::Stdin,
+            prompt_flag: None,
+            output_format: OutputFormat::StreamJson,
+            env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_parses_fixture_output() {
+        use futures::StreamExt;
+
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/claude-stream-json/tool-session.jsonl"
+        );
+        let executor = CliExecutor::new(script_backend(&format!(
+            "cat '{fixture}'; echo 'rate limited' >&2"
+        )));
+
+        let mut stream = executor.stream_events("", None);
+        let mut lines = Vec::new();
+        while let Some(line) = stream.next().await {
+            lines.push(line);
+        }
+        let result = stream.finish().await.unwrap();
+
+        assert!(result.success);
+        let raw: Vec<_> = lines
+            .iter()
+            .filter(|line| !line.stderr)
+            .map(|line| line.raw.as_str())
+            .collect();
+        assert_eq!(
+            raw.join("\n"),
+            std::fs::read_to_string(fixture).unwrap().trim_end()
+        );
+        let tools: Vec<_> = lines
+            .iter()
+            .flat_map(|line| &line.events)
+            .filter_map(BackendEvent::summary)
+            .collect();
+        assert_eq!(
+            tools,
+            vec!["tool: Read src/main.rs", "tool: Bash cargo test"]
+        );
+
+        let stderr: Vec<_> = lines.iter().filter(|line| line.stderr).collect();
+        assert_eq!(stderr.len(), 1);
+        assert_eq!(stderr[0].raw, "rate limited");
+        assert!(stderr[0].events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_closes_cut_off_message() {
+        use futures::StreamExt;
+
+        let line = r#"{"type":"assistant","message":{"id":"msg_1","content":[{"type":"text","text":"Half"}]}}"#;
+        let executor = CliExecutor::new(script_backend(&format!("echo '{line}'; exit 3")));
+
+        let lines: Vec<_> = executor.stream_events("", None).collect().await;
+
+        let last = lines.last().unwrap();
+        assert!(last.raw.is_empty());
+        assert_eq!(last.events, vec![BackendEvent::MessageStop]);
+    }
 }
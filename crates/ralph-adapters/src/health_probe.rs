@@ -0,0 +1,258 @@
+//! Backend warm-up and health probe.
+//!
+//! Before the first iteration, `ralph run` sends the backend a trivial
+//! prompt so a CLI that is missing, not logged in, or busy downloading a
+//! model manifest fails fast with its own error output instead of timing
+//! out an iteration. `ralph doctor` runs the same probe.
+
+use crate::cli_backend::CliBackend;
+use crate::cli_executor::CliExecutor;
+use std::fmt;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Default time the probe waits for the backend to answer.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Prompt sent to the backend; asks for a one-token answer.
+pub const PROBE_PROMPT: &str = "Reply with the single word OK.";
+
+/// Stderr lines kept in a failure report.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Which step of the probe failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeStage {
+    /// `<command> --version`.
+    Version,
+    /// The trivial prompt.
+    Prompt,
+}
+
+impl fmt::Display for ProbeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version => write!(f, "version check"),
+            Self::Prompt => write!(f, "warm-up prompt"),
+        }
+    }
+}
+
+/// Why a backend failed the probe.
+#[derive(Debug, Clone)]
+pub struct ProbeFailure {
+    /// The backend command.
+    pub command: String,
+    /// The step that failed.
+    pub stage: ProbeStage,
+    /// What went wrong, e.g. "exited with code 1".
+    pub reason: String,
+    /// The last lines the backend wrote to stderr.
+    pub stderr: String,
+    /// How to log in or configure credentials for this backend.
+    pub hint: Option<&'static str>,
+}
+
+impl fmt::Display for ProbeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {} {}", self.command, self.stage, self.reason)?;
+        if !self.stderr.is_empty() {
+            write!(f, "\n\nstderr:\n{}", self.stderr)?;
+        }
+        if let Some(hint) = self.hint {
+            write!(f, "\n\nHint: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ProbeFailure {}
+
+/// Probes `backend`: runs its version command when it has one, then sends
+/// [`PROBE_PROMPT`], each bounded by `timeout`.
+///
+/// # Errors
+/// Returns a [`ProbeFailure`] if either step fails to start, exits
+/// unsuccessfully, or does not finish within `timeout`.
+pub async fn probe_backend(backend: &CliBackend, timeout: Duration) -> Result<(), ProbeFailure> {
+    if has_version_flag(&backend.command) {
+        check_version(backend, timeout).await?;
+    }
+
+    let executor = CliExecutor::new(backend.clone());
+    let result = executor
+        .execute_capture_with_timeout(PROBE_PROMPT, Some(timeout))
+        .await
+        .map_err(|e| {
+            failure(
+                backend,
+                ProbeStage::Prompt,
+                format!("failed to start: {e}"),
+                "",
+            )
+        })?;
+
+    if result.timed_out {
+        return Err(failure(
+            backend,
+            ProbeStage::Prompt,
+            format!("did not respond within {}s", timeout.as_secs()),
+            &result.stderr,
+        ));
+    }
+    if !result.success {
+        return Err(failure(
+            backend,
+            ProbeStage::Prompt,
+            exit_reason(result.exit_code),
+            &result.stderr,
+        ));
+    }
+    Ok(())
+}
+
+/// Login or credential hint for a known backend command.
+pub fn auth_hint(command: &str) -> Option<&'static str> {
+    match command_name(command) {
+        "claude" => Some("run `claude login`, or set ANTHROPIC_API_KEY"),
+        "kiro-cli" => Some("run `kiro-cli login`"),
+        "gemini" => Some("run `gemini` once to sign in, or set GEMINI_API_KEY"),
+        "codex" => Some("run `codex login`, or set OPENAI_API_KEY"),
+        "amp" => Some("run `amp login`, or set AMP_API_KEY"),
+        "copilot" => Some("run `copilot auth login`"),
+        "opencode" => Some("run `opencode auth login`"),
+        "pi" => Some("set the API key for the provider pi is configured to use"),
+        _ => None,
+    }
+}
+
+async fn check_version(backend: &CliBackend, timeout: Duration) -> Result<(), ProbeFailure> {
+    let mut command = Command::new(&backend.command);
+    command
+        .arg("--version")
+        .envs(backend.env_vars.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let output = match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            return Err(failure(
+                backend,
+                ProbeStage::Version,
+                format!("failed to start: {e}"),
+                "",
+            ));
+        }
+        Err(_) => {
+            return Err(failure(
+                backend,
+                ProbeStage::Version,
+                format!("did not respond within {}s", timeout.as_secs()),
+                "",
+            ));
+        }
+    };
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(failure(
+            backend,
+            ProbeStage::Version,
+            exit_reason(output.status.code()),
+            &String::from_utf8_lossy(&output.stderr),
+        ))
+    }
+}
+
+/// Known backends answer `--version`; custom commands might not.
+fn has_version_flag(command: &str) -> bool {
+    auth_hint(command).is_some()
+}
+
+/// File name of `command` without directory or extension.
+fn command_name(command: &str) -> &str {
+    Path::new(command)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(command)
+}
+
+fn exit_reason(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(code) => format!("exited with code {code}"),
+        None => "was terminated by a signal".to_string(),
+    }
+}
+
+fn failure(backend: &CliBackend, stage: ProbeStage, reason: String, stderr: &str) -> ProbeFailure {
+    let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+    let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+    ProbeFailure {
+        command: backend.command.clone(),
+        stage,
+        reason,
+        stderr: tail.join("\n"),
+        hint: auth_hint(&backend.command),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::cli_backend::{LargePrompt, OutputFormat, PromptMode};
+
+    fn sh(script: &str) -> CliBackend {
+        CliBackend {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            prompt_mode: PromptMode::Arg,
+            prompt_flag: None,
+            output_format: OutputFormat::Text,
+            env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_passes_when_backend_answers() {
+        let result = probe_backend(&sh("echo OK"), Duration::from_secs(10)).await;
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_stderr_on_failure() {
+        let backend = sh("echo 'Invalid API key' >&2; exit 1");
+
+        let failure = probe_backend(&backend, Duration::from_secs(10))
+            .await
+            .unwrap_err();
+
+        assert_eq!(failure.stage, ProbeStage::Prompt);
+        assert_eq!(failure.reason, "exited with code 1");
+        assert!(failure.stderr.contains("Invalid API key"), "{failure}");
+    }
+
+    #[tokio::test]
+    async fn test_probe_times_out() {
+        let failure = probe_backend(&sh("sleep 5"), Duration::from_millis(200))
+            .await
+            .unwrap_err();
+
+        assert_eq!(failure.stage, ProbeStage::Prompt);
+        assert!(failure.reason.starts_with("did not respond"), "{failure}");
+    }
+
+    #[test]
+    fn test_auth_hint_matches_command_path() {
+        assert_eq!(
+            auth_hint("/opt/bin/claude"),
+            Some("run `claude login`, or set ANTHROPIC_API_KEY")
+        );
+        assert_eq!(auth_hint("my-agent"), None);
+    }
+}
@@ -17,6 +17,11 @@
 //! When config specifies `agent: auto`, the `auto_detect` module handles
 //! detecting which backends are available in the system PATH.
 //!
+//! ## Health Probe
+//!
+//! The `health_probe` module sends a backend a trivial prompt before the
+//! loop starts, so login and install problems surface immediately.
+//!
 //! ## PTY Mode
 //!
 //! The `pty_executor` module provides PTY-based execution for Claude CLI,
@@ -25,23 +30,36 @@
 //! input forwarded) and observe mode (output-only).
 
 mod auto_detect;
+mod backend_event;
 mod claude_stream;
 mod cli_backend;
 mod cli_executor;
+mod health_probe;
 mod pi_stream;
 mod pty_executor;
 pub mod pty_handle;
 mod stream_handler;
 
 pub use auto_detect::{
-    DEFAULT_PRIORITY, NoBackendError, detect_backend, detect_backend_default, is_backend_available,
+    DEFAULT_PRIORITY, NoBackendError, detect_backend, detect_backend_default,
+    detect_configured_backend, is_backend_available, is_custom_backend_available,
 };
+pub use backend_event::{BackendEvent, StreamJsonParser, TokenUsage, estimate_cost};
 pub use claude_stream::{
     AssistantMessage, ClaudeStreamEvent, ClaudeStreamParser, ContentBlock, Usage, UserContentBlock,
     UserMessage,
 };
-pub use cli_backend::{CliBackend, CustomBackendError, OutputFormat, PromptMode};
-pub use cli_executor::{CliExecutor, ExecutionResult};
+pub use cli_backend::{
+    CliBackend, CustomBackendError, DEFAULT_LARGE_PROMPT_THRESHOLD, IterationPrompt, LargePrompt,
+    LargePromptDelivery, OutputFormat, PromptMode,
+};
+pub use cli_executor::{
+    BackendEventStream, BackendLine, CliExecutor, ExecutionResult, LineAction, OutputLine,
+    StreamResult,
+};
+pub use health_probe::{
+    DEFAULT_PROBE_TIMEOUT, PROBE_PROMPT, ProbeFailure, ProbeStage, auth_hint, probe_backend,
+};
 pub use pi_stream::{
     PiAssistantEvent, PiContentBlock, PiCost, PiSessionState, PiStreamEvent, PiStreamParser,
     PiToolResult, PiTurnMessage, PiUsage, dispatch_pi_stream_event,
@@ -1665,7 +1665,7 @@ mod tests {
     use super::*;
     use crate::claude_stream::{AssistantMessage, UserMessage};
     #[cfg(unix)]
-    use crate::cli_backend::PromptMode;
+    use crate::cli_backend::{LargePrompt, PromptMode};
     use crate::stream_handler::{SessionResult, StreamHandler};
     #[cfg(unix)]
     use tempfile::TempDir;
@@ -2125,6 +2125,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -2159,6 +2161,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -2192,6 +2196,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -2228,6 +2234,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::StreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -2269,6 +2277,8 @@ mod tests {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: true,
@@ -1,8 +1,8 @@
 #[cfg(unix)]
 mod pty_executor_integration {
     use ralph_adapters::{
-        CliBackend, OutputFormat, PromptMode, PtyConfig, PtyExecutor, SessionResult, StreamHandler,
-        TerminationType,
+        CliBackend, LargePrompt, OutputFormat, PromptMode, PtyConfig, PtyExecutor, SessionResult,
+        StreamHandler, TerminationType,
     };
     use tempfile::TempDir;
 
@@ -48,6 +48,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::Text,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -79,6 +81,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::StreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -113,6 +117,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::StreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -154,6 +160,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::PiStreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -216,6 +224,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::PiStreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -254,6 +264,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::PiStreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -290,6 +302,8 @@ mod pty_executor_integration {
             prompt_flag: None,
             output_format: OutputFormat::PiStreamJson,
             env_vars: vec![],
+            large_prompt: LargePrompt::default(),
+            continue_flag: None,
         };
         let config = PtyConfig {
             interactive: false,
@@ -0,0 +1,106 @@
+//! Hat-level tool restrictions translated into backend command lines.
+
+use ralph_adapters::CliBackend;
+use ralph_core::HatBackend;
+
+fn tools(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| (*name).to_string()).collect()
+}
+
+fn command_args(backend: &CliBackend) -> Vec<String> {
+    let (_cmd, args, _stdin, _temp) = backend.build_command("review the diff", false);
+    args
+}
+
+#[test]
+fn claude_merges_disallowed_tools_into_existing_flag() {
+    let mut backend = CliBackend::claude();
+    assert!(backend.restrict_tools(&[], &tools(&["Edit", "Write"])));
+
+    let args = command_args(&backend);
+    let disallowed: Vec<_> = args
+        .iter()
+        .filter(|arg| arg.starts_with("--disallowedTools="))
+        .collect();
+    assert_eq!(
+        disallowed,
+        vec!["--disallowedTools=TodoWrite,TaskCreate,TaskUpdate,TaskList,TaskGet,Edit,Write"]
+    );
+    assert!(!args.iter().any(|arg| arg.starts_with("--allowedTools")));
+}
+
+#[test]
+fn claude_allowed_tools_flag() {
+    let mut backend = CliBackend::claude();
+    assert!(backend.restrict_tools(&tools(&["Read", "Grep", "Bash(git diff:*)"]), &[]));
+
+    let args = command_args(&backend);
+    assert!(args.contains(&"--allowedTools=Read,Grep,Bash(git diff:*)".to_string()));
+    // The prompt still comes last, after -p
+    assert_eq!(args[args.len() - 2], "-p");
+    assert_eq!(args[args.len() - 1], "review the diff");
+}
+
+#[test]
+fn claude_hat_backend_gets_flags() {
+    let mut backend =
+        CliBackend::from_hat_backend(&HatBackend::Named("claude".to_string())).unwrap();
+    assert!(backend.restrict_tools(&tools(&["Read"]), &tools(&["Edit"])));
+
+    let args = command_args(&backend);
+    assert!(args.contains(&"--allowedTools=Read".to_string()));
+    assert!(
+        args.iter()
+            .any(|arg| arg.starts_with("--disallowedTools=") && arg.ends_with(",Edit"))
+    );
+}
+
+#[test]
+fn copilot_allowlist_replaces_allow_all_tools() {
+    let mut backend = CliBackend::copilot();
+    assert!(backend.restrict_tools(&tools(&["shell(git)"]), &tools(&["write"])));
+
+    let args = command_args(&backend);
+    assert!(!args.contains(&"--allow-all-tools".to_string()));
+    let joined = args.join(" ");
+    assert!(joined.contains("--allow-tool shell(git)"), "{joined}");
+    assert!(joined.contains("--deny-tool write"), "{joined}");
+}
+
+#[test]
+fn copilot_denylist_keeps_allow_all_tools() {
+    let mut backend = CliBackend::copilot();
+    assert!(backend.restrict_tools(&[], &tools(&["write"])));
+
+    let args = command_args(&backend);
+    assert!(args.contains(&"--allow-all-tools".to_string()));
+    assert!(args.join(" ").contains("--deny-tool write"));
+}
+
+#[test]
+fn backends_without_enforcement_are_unchanged() {
+    for mut backend in [
+        CliBackend::gemini(),
+        CliBackend::codex(),
+        CliBackend::kiro(),
+        CliBackend::amp(),
+        CliBackend::opencode(),
+        CliBackend::pi(),
+    ] {
+        let before = command_args(&backend);
+        assert!(
+            !backend.restrict_tools(&tools(&["Read"]), &tools(&["Edit"])),
+            "{} should report no enforcement",
+            backend.command
+        );
+        assert_eq!(command_args(&backend), before);
+    }
+}
+
+#[test]
+fn no_restrictions_is_always_enforceable() {
+    let mut backend = CliBackend::gemini();
+    let before = command_args(&backend);
+    assert!(backend.restrict_tools(&[], &[]));
+    assert_eq!(command_args(&backend), before);
+}
@@ -12,12 +12,12 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use ralph_adapters::{CliBackend, CliExecutor, detect_backend};
 use ralph_core::{
-    CleanupPolicy, CliCapture, EventLoop, PlayerConfig, RalphConfig, ReplayMode, SessionPlayer,
-    TaskSuite, TerminationReason, WorkspaceManager,
+    CleanupPolicy, CliCapture, EventLoop, KeyringStore, PlayerConfig, RalphConfig, ReplayMode,
+    SessionPlayer, TaskSuite, TerminationReason, WorkspaceManager,
 };
 use ralph_proto::FrameCapture;
 use std::fs::{self, File};
-use std::io::{self, BufReader, BufWriter};
+use std::io::{self, BufWriter};
 use std::path::PathBuf;
 use std::time::Duration;
 use tracing::{debug, info, warn};
@@ -341,7 +341,7 @@ async fn run_task_loop(
     // Build config for this task from task definition
     let mut config = RalphConfig::default();
     config.event_loop.max_iterations = task.max_iterations;
-    config.event_loop.completion_promise = task.completion_promise.clone();
+    config.event_loop.completion_promise = task.completion_promise.as_str().into();
     config.event_loop.max_runtime_seconds = task.timeout_seconds;
 
     // Auto-detect backend
@@ -371,11 +371,15 @@ async fn run_task_loop(
     let executor = CliExecutor::new(backend);
 
     // Setup session recording if requested
-    let recorder: Option<Arc<SessionRecorder<BufWriter<File>>>> =
+    let recorder: Option<Arc<SessionRecorder<Box<dyn io::Write + Send>>>> =
         if let Some(record_path) = record_path {
-            let file = File::create(record_path)
+            let recorder = SessionRecorder::create(record_path)
                 .with_context(|| format!("Failed to create recording file: {:?}", record_path))?;
-            let recorder = Arc::new(SessionRecorder::new(BufWriter::new(file)));
+            let recorder = Arc::new(
+                recorder
+                    .with_redactor(config.redactor(&KeyringStore)?)
+                    .with_max_bytes(config.features.recording.max_bytes),
+            );
             recorder.record_meta(Record::meta_loop_start(
                 &config.event_loop.prompt_file,
                 config.event_loop.max_iterations,
@@ -410,6 +414,7 @@ async fn run_task_loop(
 
     loop {
         // Check termination before execution
+        event_loop.enforce_hat_budgets();
         if let Some(reason) = event_loop.check_termination() {
             termination_reason = reason;
             break;
@@ -530,6 +535,12 @@ fn format_termination_reason(reason: &TerminationReason) -> String {
         TerminationReason::Stopped => "Stopped".to_string(),
         TerminationReason::Interrupted => "Interrupted".to_string(),
         TerminationReason::RestartRequested => "RestartRequested".to_string(),
+        TerminationReason::GateFailed => "GateFailed".to_string(),
+        TerminationReason::UntilEventReached => "UntilEventReached".to_string(),
+        TerminationReason::HatBudgetExceeded => "HatBudgetExceeded".to_string(),
+        TerminationReason::EventQueueOverflow => "EventQueueOverflow".to_string(),
+        TerminationReason::CompletionRejected => "CompletionRejected".to_string(),
+        TerminationReason::NoMatchingHat { topic } => format!("NoMatchingHat({topic})"),
     }
 }
 
@@ -541,13 +552,9 @@ fn cmd_replay(
     step: bool,
     filter: Option<String>,
 ) -> Result<()> {
-    // Open session file
-    let file = File::open(&session_path)
-        .with_context(|| format!("Failed to open session file: {:?}", session_path))?;
-
-    // Create player
-    let mut player = SessionPlayer::from_reader(BufReader::new(file))
-        .with_context(|| "Failed to parse session file")?;
+    // Open and parse the session file (gzipped recordings are decompressed)
+    let mut player = SessionPlayer::from_file(&session_path)
+        .with_context(|| format!("Failed to load session file: {:?}", session_path))?;
 
     info!(
         "Loaded {} records from {:?}",
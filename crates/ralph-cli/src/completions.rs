@@ -0,0 +1,244 @@
+//! Shell completions for `ralph completions <shell>`.
+//!
+//! clap generates the static part. Values it cannot know (preset names and
+//! the hats of the local config) come from the hidden `ralph __complete`
+//! helper, which the bash and zsh scripts call for `-c preset:<TAB>`,
+//! `-c builtin:<TAB>`, `init --preset <TAB>` and `hats show <TAB>`.
+
+use std::io::Write;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use clap_complete::Shell;
+
+use crate::ConfigSource;
+use crate::presets;
+
+/// Name of the hidden helper subcommand.
+pub const HELPER: &str = "__complete";
+
+/// Arguments for the hidden `__complete` helper.
+#[derive(Parser, Debug)]
+pub struct CompleteArgs {
+    /// What to complete
+    #[arg(value_enum)]
+    pub kind: CompletionKind,
+
+    /// The word being completed; only candidates starting with it are listed
+    #[arg(default_value = "", allow_hyphen_values = true)]
+    pub current: String,
+}
+
+/// Values the helper can list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    /// Preset names
+    Presets,
+    /// Preset sources for `-c`: `preset:<name>` and `builtin:<name>`
+    Config,
+    /// Hat IDs of the config selected with `-c` (default: ralph.yml)
+    Hats,
+}
+
+/// Prints the candidates for `args`, one per line.
+pub fn execute(config_sources: &[ConfigSource], args: &CompleteArgs) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for candidate in candidates(config_sources, args.kind, &args.current) {
+        writeln!(stdout, "{candidate}")?;
+    }
+    Ok(())
+}
+
+/// Candidates of `kind` starting with `current`, sorted.
+fn candidates(config_sources: &[ConfigSource], kind: CompletionKind, current: &str) -> Vec<String> {
+    let mut values = match kind {
+        CompletionKind::Presets => presets::preset_names(),
+        CompletionKind::Config => presets::preset_names()
+            .into_iter()
+            .flat_map(|name| [format!("preset:{name}"), format!("builtin:{name}")])
+            .collect(),
+        // A config that fails to load just has nothing to offer
+        CompletionKind::Hats => crate::hats::load_config(config_sources)
+            .map(|config| config.hats.into_keys().collect())
+            .unwrap_or_default(),
+    };
+    values.retain(|value| value.starts_with(current));
+    values.sort();
+    values
+}
+
+/// Writes the completion script for `shell`, hooking the dynamic helper into
+/// bash and zsh.
+pub fn generate(shell: Shell, cmd: &clap::Command, out: &mut dyn Write) -> Result<()> {
+    // clap_complete offers hidden subcommands too, so leave the helper out
+    let mut cmd = clap::Command::new("ralph")
+        .version(env!("CARGO_PKG_VERSION"))
+        .args(cmd.get_arguments().cloned())
+        .subcommands(
+            cmd.get_subcommands()
+                .filter(|sub| sub.get_name() != HELPER)
+                .cloned(),
+        );
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, "ralph", &mut script);
+    let script = String::from_utf8(script)?;
+    let script = match shell {
+        Shell::Bash => with_dynamic(script, BASH_DYNAMIC, "\nif [[ \"${BASH_VERSINFO"),
+        Shell::Zsh => with_dynamic(script, ZSH_DYNAMIC, "\nif [ \"$funcstack[1]\""),
+        _ => script,
+    };
+    out.write_all(script.as_bytes())?;
+    Ok(())
+}
+
+/// Renames clap's `_ralph` to `_ralph_static` and inserts `dynamic`, which
+/// defines a new `_ralph` wrapping it, before the registration at `tail`.
+/// Returns the script unchanged if it does not have the expected shape.
+fn with_dynamic(script: String, dynamic: &str, tail: &str) -> String {
+    const CLAP_FN: &str = "_ralph() {";
+    let fn_at = if script.starts_with(CLAP_FN) {
+        Some(0)
+    } else {
+        script.find(&format!("\n{CLAP_FN}")).map(|at| at + 1)
+    };
+    let (Some(fn_at), Some(tail_at)) = (fn_at, script.rfind(tail)) else {
+        return script;
+    };
+    if tail_at < fn_at {
+        return script;
+    }
+    format!(
+        "{}_ralph_static() {{{}\n{}{}",
+        &script[..fn_at],
+        &script[fn_at + CLAP_FN.len()..tail_at],
+        dynamic,
+        &script[tail_at..]
+    )
+}
+
+const BASH_DYNAMIC: &str = r#"
+_ralph_dynamic() {
+    local IFS=$'\n'
+    COMPREPLY=( $(ralph "$@" 2>/dev/null) )
+}
+
+_ralph() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local word="${line##*[[:space:]]}"
+    local rest="${line%"$word"}"
+    rest="${rest%"${rest##*[![:space:]]}"}"
+    local prev="${rest##*[[:space:]]}"
+
+    case "${prev}" in
+        -c|--config)
+            case "${word}" in
+                preset:*|builtin:*)
+                    _ralph_dynamic __complete config "${word}"
+                    # ':' splits words in bash, so complete only the part after it
+                    COMPREPLY=( "${COMPREPLY[@]#*:}" )
+                    return 0
+                    ;;
+            esac
+            ;;
+        --preset)
+            _ralph_dynamic __complete presets "${word}"
+            return 0
+            ;;
+    esac
+
+    if [[ "${line}" =~ (^|[[:space:]])hats[[:space:]]+show[[:space:]]+[^[:space:]]*$ ]]; then
+        local -a words config=()
+        read -ra words <<< "${line}"
+        local i
+        for (( i = 1; i < ${#words[@]} - 1; i++ )); do
+            case "${words[i]}" in
+                -c|--config) config+=( -c "${words[i+1]}" ) ;;
+            esac
+        done
+        _ralph_dynamic "${config[@]}" __complete hats "${word}"
+        return 0
+    fi
+
+    _ralph_static "$@"
+}
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_ralph() {
+    local -a candidates config
+    local i
+    for (( i = 2; i < CURRENT - 1; i++ )); do
+        [[ $words[i] == (-c|--config) ]] && config+=(-c "$words[i+1]")
+    done
+
+    case $words[CURRENT-1] in
+        (-c|--config)
+            if [[ $PREFIX == (preset|builtin):* ]]; then
+                candidates=(${(f)"$(ralph __complete config "$PREFIX" 2>/dev/null)"})
+                compadd -- $candidates
+                return
+            fi
+            ;;
+        (--preset)
+            candidates=(${(f)"$(ralph __complete presets "$PREFIX" 2>/dev/null)"})
+            compadd -- $candidates
+            return
+            ;;
+    esac
+
+    if (( CURRENT > 3 )) && [[ $words[CURRENT-2] == hats && $words[CURRENT-1] == show ]]; then
+        candidates=(${(f)"$(ralph $config __complete hats "$PREFIX" 2>/dev/null)"})
+        compadd -- $candidates
+        return
+    fi
+
+    _ralph_static "$@"
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_complete_lists_preset_names() {
+        let names = candidates(&[], CompletionKind::Presets, "");
+        assert!(names.iter().any(|name| name == "feature"));
+        assert!(names.iter().any(|name| name == "code-assist"));
+
+        assert_eq!(
+            candidates(&[], CompletionKind::Presets, "fea"),
+            vec!["feature"]
+        );
+        assert_eq!(
+            candidates(&[], CompletionKind::Config, "builtin:fea"),
+            vec!["builtin:feature"]
+        );
+    }
+
+    #[test]
+    fn test_complete_lists_hats_of_selected_config() {
+        let hats = candidates(
+            &[ConfigSource::Builtin("feature".to_string())],
+            CompletionKind::Hats,
+            "",
+        );
+        assert!(!hats.is_empty());
+        assert!(hats.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_generated_scripts_call_complete_helper() {
+        for shell in [Shell::Bash, Shell::Zsh] {
+            let mut out = Vec::new();
+            generate(shell, &crate::Cli::command(), &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("_ralph_static() {"), "{shell}");
+            assert!(script.contains("__complete presets"), "{shell}");
+            assert!(script.contains("__complete hats"), "{shell}");
+            assert_eq!(script.matches("\n_ralph() {").count(), 1, "{shell}");
+            assert!(!script.contains("_ralph____complete"), "{shell}");
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! CLI commands for the `ralph diagnostics` namespace.
+//!
+//! Subcommands:
+//! - `bundle`: Zip up diagnostics, config, events, and loop state for a bug report
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use ralph_core::diagnostics::DiagnosticsCollector;
+use ralph_core::loop_registry::LoopRegistry;
+use ralph_core::{EventLogger, KeyringStore, REDACTED, RalphConfig, Redactor};
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+use crate::ConfigSource;
+use crate::display::colors;
+
+/// Number of trailing events-file lines included in a bundle.
+const RECENT_EVENT_LINES: usize = 500;
+
+/// Collect diagnostics for bug reports.
+#[derive(Parser, Debug)]
+pub struct DiagnosticsArgs {
+    #[command(subcommand)]
+    pub command: DiagnosticsCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DiagnosticsCommands {
+    /// Bundle diagnostics, config, and recent events into a shareable zip
+    Bundle(BundleArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct BundleArgs {
+    /// Output path (default: ralph-diagnostics-<timestamp>.zip)
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// Execute a diagnostics command.
+pub fn execute(
+    config_sources: &[ConfigSource],
+    args: DiagnosticsArgs,
+    use_colors: bool,
+) -> Result<()> {
+    match args.command {
+        DiagnosticsCommands::Bundle(args) => bundle_command(config_sources, args, use_colors),
+    }
+}
+
+fn bundle_command(
+    config_sources: &[ConfigSource],
+    args: BundleArgs,
+    use_colors: bool,
+) -> Result<()> {
+    let config = crate::load_config_with_overrides(config_sources)?;
+    let workspace_root = config.core.workspace_root.clone();
+
+    // Mask the capture patterns plus every secret the config can resolve
+    let mut secrets = Vec::new();
+    secrets.extend(config.robot.resolve_bot_token());
+    if let Some(slack) = &config.robot.slack {
+        secrets.extend(slack.resolve_bot_token());
+        secrets.extend(slack.resolve_signing_secret());
+    }
+    let redactor = config.redactor(&KeyringStore)?.with_literals(&secrets);
+
+    let out = args.out.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "ralph-diagnostics-{}.zip",
+            chrono::Local::now().format("%Y-%m-%dT%H-%M-%S")
+        ))
+    });
+    let entries = write_bundle(&workspace_root, &config, &redactor, &out)?;
+
+    if use_colors {
+        println!(
+            "{}✓{} Wrote diagnostics bundle {} ({} files)",
+            colors::GREEN,
+            colors::RESET,
+            out.display(),
+            entries.len()
+        );
+    } else {
+        println!(
+            "Wrote diagnostics bundle {} ({} files)",
+            out.display(),
+            entries.len()
+        );
+    }
+    println!("Secrets were redacted; review the bundle before sharing it.");
+    Ok(())
+}
+
+/// Writes the bundle to `out` and returns the names of its entries.
+///
+/// Every entry passes through `redactor`; the config additionally has its
+/// bot secrets and env values masked outright.
+fn write_bundle(
+    workspace_root: &Path,
+    config: &RalphConfig,
+    redactor: &Redactor,
+    out: &Path,
+) -> Result<Vec<String>> {
+    let mut entries: Vec<(String, String)> = vec![
+        ("version.txt".to_string(), version_info()),
+        ("config.yml".to_string(), redacted_config_yaml(config)?),
+    ];
+
+    if let Some(events) = recent_events(workspace_root) {
+        entries.push(("events.jsonl".to_string(), events));
+    }
+
+    let registry_path = workspace_root.join(LoopRegistry::REGISTRY_FILE);
+    if let Ok(registry) = fs::read_to_string(registry_path) {
+        entries.push(("loops.json".to_string(), registry));
+    }
+
+    if let Some(session_dir) = DiagnosticsCollector::latest_session_dir(workspace_root) {
+        let session = session_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.extend(text_files(&session_dir, &format!("diagnostics/{session}")));
+    }
+    let logs_dir = workspace_root
+        .join(".ralph")
+        .join("diagnostics")
+        .join("logs");
+    entries.extend(text_files(&logs_dir, "diagnostics/logs"));
+
+    let file = fs::File::create(out)
+        .with_context(|| format!("Failed to create bundle {}", out.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in &entries {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(redactor.redact(content).as_bytes())?;
+    }
+    zip.finish()?;
+
+    Ok(entries.into_iter().map(|(name, _)| name).collect())
+}
+
+fn version_info() -> String {
+    format!(
+        "ralph {}\nos: {}\narch: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+/// Serializes the effective config with the bot secrets and env values masked.
+fn redacted_config_yaml(config: &RalphConfig) -> Result<String> {
+    let mut config = config.clone();
+    if let Some(telegram) = config.robot.telegram.as_mut()
+        && telegram.bot_token.is_some()
+    {
+        telegram.bot_token = Some(REDACTED.to_string());
+    }
+    if let Some(slack) = config.robot.slack.as_mut() {
+        for secret in [&mut slack.bot_token, &mut slack.signing_secret] {
+            if secret.is_some() {
+                *secret = Some(REDACTED.to_string());
+            }
+        }
+    }
+    let hat_envs = config.hats.values_mut().map(|hat| &mut hat.env);
+    for env in std::iter::once(&mut config.cli.env).chain(hat_envs) {
+        for value in env.values_mut() {
+            *value = REDACTED.to_string();
+        }
+    }
+    Ok(serde_yaml::to_string(&config)?)
+}
+
+/// Reads the tail of the active events file, if there is one.
+fn recent_events(workspace_root: &Path) -> Option<String> {
+    let events_path = fs::read_to_string(workspace_root.join(".ralph/current-events"))
+        .map(|path| workspace_root.join(path.trim()))
+        .unwrap_or_else(|_| workspace_root.join(EventLogger::DEFAULT_PATH));
+    let content = fs::read_to_string(events_path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(RECENT_EVENT_LINES);
+    Some(lines[start..].join("\n") + "\n")
+}
+
+/// Reads the regular files directly inside `dir`, named under `prefix`.
+fn text_files(dir: &Path, prefix: &str) -> Vec<(String, String)> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, String)> = read_dir
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((format!("{prefix}/{name}"), content))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    const TOKEN: &str = "123456:ABC-DEF-telegram-token";
+
+    #[test]
+    fn test_bundle_contains_expected_entries_without_raw_token() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+
+        let session_dir = root.join(".ralph/diagnostics/2024-01-21T08-49-56");
+        fs::create_dir_all(&session_dir).unwrap();
+        fs::write(
+            session_dir.join("orchestration.jsonl"),
+            format!("{{\"token\":\"{TOKEN}\"}}\n"),
+        )
+        .unwrap();
+        fs::create_dir_all(root.join(".ralph/diagnostics/logs")).unwrap();
+        fs::write(
+            root.join(".ralph/diagnostics/logs/ralph-2024.log"),
+            "started\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join(".ralph/events.jsonl"),
+            "{\"topic\":\"build.done\",\"payload\":\"sk-ant-REDACTED\"}\n",
+        )
+        .unwrap();
+        fs::write(root.join(".ralph/loops.json"), "{\"loops\":[]}").unwrap();
+
+        let mut config = RalphConfig::parse_yaml(&format!(
+            "cli:\n  env:\n    API_KEY: \"plain-config-key\"\nRObot:\n  telegram:\n    bot_token: \"{TOKEN}\"\n  slack:\n    channel_id: C1\n    signing_secret: \"slack-signing-value\"\n"
+        ))
+        .unwrap();
+        config.core.workspace_root = root.to_path_buf();
+        let redactor = config
+            .features
+            .capture
+            .redactor()
+            .unwrap()
+            .with_literals(&[TOKEN]);
+
+        let out = root.join("bug.zip");
+        let entries = write_bundle(root, &config, &redactor, &out).unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "config.yml",
+                "diagnostics/2024-01-21T08-49-56/orchestration.jsonl",
+                "diagnostics/logs/ralph-2024.log",
+                "events.jsonl",
+                "loops.json",
+                "version.txt",
+            ]
+        );
+        assert_eq!(entries.len(), names.len());
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            assert!(!content.contains(TOKEN), "{} leaks the token", file.name());
+            assert!(!content.contains("plain-config-key"), "{}", file.name());
+            assert!(!content.contains("slack-signing-value"), "{}", file.name());
+            assert!(!content.contains("sk-ant-api03"), "{}", file.name());
+        }
+
+        let mut config_yml = String::new();
+        archive
+            .by_name("config.yml")
+            .unwrap()
+            .read_to_string(&mut config_yml)
+            .unwrap();
+        assert!(config_yml.contains(REDACTED));
+    }
+}
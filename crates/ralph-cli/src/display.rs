@@ -149,6 +149,17 @@ pub fn print_termination(
         TerminationReason::Stopped => (CYAN, "?", "Manually stopped"),
         TerminationReason::Interrupted => (YELLOW, "?", "Interrupted by signal"),
         TerminationReason::RestartRequested => (CYAN, "↻", "Restarting by human request"),
+        TerminationReason::GateFailed => (RED, "?", "Failure gate event published"),
+        TerminationReason::UntilEventReached => (CYAN, "?", "Stopped at --until-event topic"),
+        TerminationReason::HatBudgetExceeded => (YELLOW, "?", "Hat iteration budget exceeded"),
+        TerminationReason::EventQueueOverflow => (RED, "?", "Event queue overflowed"),
+        TerminationReason::CompletionRejected => (RED, "?", "Completion check kept failing"),
+        TerminationReason::NoMatchingHat { .. } => (RED, "?", "No hat matched event"),
+    };
+    // Name the topic so a publishes/triggers typo is easy to spot
+    let unmatched_topic = match reason {
+        TerminationReason::NoMatchingHat { topic } => Some(topic),
+        _ => None,
     };
 
     let separator = "-".repeat(58);
@@ -173,6 +184,9 @@ pub fn print_termination(
                 state.cumulative_cost
             );
         }
+        if let Some(topic) = unmatched_topic {
+            println!("{BOLD}|{RESET}   Topic:       {RED}{topic}{RESET} (no hat triggers on it)");
+        }
         println!("{BOLD}+{separator}+{RESET}");
     } else {
         println!("\n+{}+", "-".repeat(58));
@@ -183,6 +197,9 @@ pub fn print_termination(
         if state.cumulative_cost > 0.0 {
             println!("|   Est. cost:   ${:.2}", state.cumulative_cost);
         }
+        if let Some(topic) = unmatched_topic {
+            println!("|   Topic:       {topic} (no hat triggers on it)");
+        }
         println!("+{}+", "-".repeat(58));
     }
 }
@@ -206,26 +223,44 @@ pub fn get_topic_color(topic: &str) -> &'static str {
 }
 
 /// Prints a table of event records.
-pub fn print_events_table(records: &[EventRecord], use_colors: bool) {
+///
+/// Times are shown in the local timezone unless `utc` is set. The Δ column
+/// is the gap since the previous row, so it reflects any filters applied.
+/// With `sources`, one file name per record, a Source column shows which
+/// run each event came from.
+pub fn print_events_table(
+    records: &[EventRecord],
+    sources: Option<&[String]>,
+    use_colors: bool,
+    utc: bool,
+) {
     use colors::*;
 
+    let (source_head, source_rule) = if sources.is_some() {
+        (" Source          |", "-----------------|")
+    } else {
+        ("", "")
+    };
+
     // Header
     if use_colors {
         println!(
-            "{BOLD}{DIM}  # | Time     | Iteration | Hat           | Topic              | Triggered      | Payload{RESET}"
+            "{BOLD}{DIM}  # | Time     | Δ       |{source_head} Iteration | Hat           | Topic              | Triggered      | Payload{RESET}"
         );
         println!(
-            "{DIM}----+----------+-----------+---------------+--------------------+----------------+-----------------{RESET}"
+            "{DIM}----+----------+---------+{}-----------+---------------+--------------------+----------------+-----------------{RESET}",
+            source_rule.replace('|', "+")
         );
     } else {
         println!(
-            "  # | Time     | Iteration | Hat           | Topic              | Triggered      | Payload"
+            "  # | Time     | Δ       |{source_head} Iteration | Hat           | Topic              | Triggered      | Payload"
         );
         println!(
-            "----|----------|-----------|---------------|--------------------|-----------------|-----------------"
+            "----|----------|---------|{source_rule}-----------|---------------|--------------------|-----------------|-----------------"
         );
     }
 
+    let mut previous_ts = None;
     for (i, record) in records.iter().enumerate() {
         let topic_color = get_topic_color(&record.topic);
         let triggered = record.triggered.as_deref().unwrap_or("-");
@@ -236,33 +271,36 @@ pub fn print_events_table(records: &[EventRecord], use_colors: bool) {
             payload_one_line
         };
 
-        // Extract time portion (HH:MM:SS) from ISO 8601 timestamp
-        let time = record
-            .ts
-            .find('T')
-            .and_then(|t_pos| {
-                let after_t = &record.ts[t_pos + 1..];
-                // Find end of time (before timezone indicator or end of string)
-                let end = after_t
-                    .find(|c| c == 'Z' || c == '+' || c == '-')
-                    .unwrap_or(after_t.len());
-                let time_str = &after_t[..end];
-                // Take only HH:MM:SS (usually ASCII), but still ensure we slice on a valid UTF-8
-                // boundary for robustness. Otherwise, an unexpected `ts` (e.g. CJK/emoji) can make
-                // `&s[..N]` panic.
-                let mut boundary = time_str.len().min(8);
-                while boundary > 0 && !time_str.is_char_boundary(boundary) {
-                    boundary -= 1;
-                }
-                Some(&time_str[..boundary])
-            })
-            .unwrap_or("-");
+        let ts = chrono::DateTime::parse_from_rfc3339(&record.ts).ok();
+        let time = match ts {
+            Some(ts) if utc => ts
+                .with_timezone(&chrono::Utc)
+                .format("%H:%M:%S")
+                .to_string(),
+            Some(ts) => ts
+                .with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string(),
+            None => raw_time_of_day(&record.ts).to_string(),
+        };
+        let gap = match (previous_ts, ts) {
+            (Some(previous), Some(current)) => format_gap(current - previous),
+            _ => "-".to_string(),
+        };
+        if ts.is_some() {
+            previous_ts = ts;
+        }
+        let source = sources
+            .and_then(|sources| sources.get(i))
+            .map(|source| format!(" {:<15} |", truncate(&source_label(source), 15)))
+            .unwrap_or_default();
 
         if use_colors {
             println!(
-                "{DIM}{:>3}{RESET} | {:<8} | {:>9} | {:<13} | {topic_color}{:<18}{RESET} | {:<14} | {DIM}{}{RESET}",
+                "{DIM}{:>3}{RESET} | {:<8} | {DIM}{:<7}{RESET} |{source} {:>9} | {:<13} | {topic_color}{:<18}{RESET} | {:<14} | {DIM}{}{RESET}",
                 i + 1,
                 time,
+                gap,
                 record.iteration,
                 truncate(&record.hat, 13),
                 truncate(&record.topic, 18),
@@ -271,9 +309,10 @@ pub fn print_events_table(records: &[EventRecord], use_colors: bool) {
             );
         } else {
             println!(
-                "{:>3} | {:<8} | {:>9} | {:<13} | {:<18} | {:<14} | {}",
+                "{:>3} | {:<8} | {:<7} |{source} {:>9} | {:<13} | {:<18} | {:<14} | {}",
                 i + 1,
                 time,
+                gap,
                 record.iteration,
                 truncate(&record.hat, 13),
                 truncate(&record.topic, 18),
@@ -291,6 +330,52 @@ pub fn print_events_table(records: &[EventRecord], use_colors: bool) {
     }
 }
 
+/// Short name for an events file: `events-20260101-100000.jsonl` becomes
+/// `20260101-100000`.
+fn source_label(file_name: &str) -> String {
+    let stem = file_name.strip_suffix(".jsonl").unwrap_or(file_name);
+    stem.strip_prefix("events-").unwrap_or(stem).to_string()
+}
+
+/// Extracts the time portion (HH:MM:SS) from a timestamp that isn't valid RFC 3339.
+fn raw_time_of_day(ts: &str) -> &str {
+    ts.find('T')
+        .map(|t_pos| {
+            let after_t = &ts[t_pos + 1..];
+            // Find end of time (before timezone indicator or end of string)
+            let end = after_t
+                .find(|c| c == 'Z' || c == '+' || c == '-')
+                .unwrap_or(after_t.len());
+            let time_str = &after_t[..end];
+            // Take only HH:MM:SS (usually ASCII), but still ensure we slice on a valid UTF-8
+            // boundary for robustness. Otherwise, an unexpected `ts` (e.g. CJK/emoji) can make
+            // `&s[..N]` panic.
+            let mut boundary = time_str.len().min(8);
+            while boundary > 0 && !time_str.is_char_boundary(boundary) {
+                boundary -= 1;
+            }
+            &time_str[..boundary]
+        })
+        .unwrap_or("-")
+}
+
+/// Formats the gap between two events (e.g., "+5s", "+2m03s", "+1h02m").
+fn format_gap(gap: chrono::Duration) -> String {
+    let sign = if gap < chrono::Duration::zero() {
+        '-'
+    } else {
+        '+'
+    };
+    let secs = gap.num_seconds().unsigned_abs();
+    if secs < 60 {
+        format!("{sign}{secs}s")
+    } else if secs < 3600 {
+        format!("{sign}{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{sign}{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
 /// Builds a map of event topics to hat display information for the TUI.
 ///
 /// This allows the TUI to dynamically resolve which hat should be displayed
@@ -375,7 +460,7 @@ mod tests {
             blocked_count: None,
         };
 
-        print_events_table(&[record], false);
+        print_events_table(&[record], None, false, false);
     }
 
     #[test]
@@ -392,7 +477,22 @@ mod tests {
             blocked_count: None,
         };
 
-        print_events_table(&[record], false);
+        print_events_table(&[record], None, false, false);
+    }
+
+    #[test]
+    fn test_format_gap() {
+        assert_eq!(format_gap(chrono::Duration::seconds(0)), "+0s");
+        assert_eq!(format_gap(chrono::Duration::seconds(5)), "+5s");
+        assert_eq!(format_gap(chrono::Duration::seconds(123)), "+2m03s");
+        assert_eq!(format_gap(chrono::Duration::seconds(3720)), "+1h02m");
+        assert_eq!(format_gap(chrono::Duration::seconds(-7)), "-7s");
+    }
+
+    #[test]
+    fn test_raw_time_of_day() {
+        assert_eq!(raw_time_of_day("2026-01-23T10:11:12.5Zjunk"), "10:11:12");
+        assert_eq!(raw_time_of_day("garbage"), "-");
     }
 
     #[test]
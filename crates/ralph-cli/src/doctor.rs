@@ -2,7 +2,9 @@
 
 use anyhow::Result;
 use clap::Parser;
-use ralph_adapters::{CliBackend, DEFAULT_PRIORITY};
+use ralph_adapters::{
+    CliBackend, DEFAULT_PRIORITY, DEFAULT_PROBE_TIMEOUT, detect_configured_backend, probe_backend,
+};
 use ralph_core::{CheckResult, CheckStatus, ConfigError, HatBackend, PreflightReport, RalphConfig};
 use std::collections::HashSet;
 use std::env;
@@ -45,7 +47,13 @@ pub async fn execute(
     checks.push(hat_collection_check(&config));
 
     let backend_checks = backend_checks(&config, command_version_ok, command_exists);
+    let backends_available = backend_checks
+        .iter()
+        .all(|check| check.status != CheckStatus::Fail);
     checks.extend(backend_checks);
+    if backends_available {
+        checks.push(backend_probe_check(&config).await);
+    }
 
     let auth_backends = auth_backend_names(&config);
     checks.push(auth_hint_check(&auth_backends, |key| env::var(key).ok()));
@@ -139,7 +147,11 @@ where
         }
         backend => {
             let backend = backend.trim().to_lowercase();
-            match command_for_named_backend(&backend, config.cli.command.as_deref()) {
+            let command = match config.adapters.custom_backends.get(&backend) {
+                Some(custom) => Ok(custom.command.clone()),
+                None => command_for_named_backend(&backend, config.cli.command.as_deref()),
+            };
+            match command {
                 Ok(command) => {
                     push_backend_check(
                         &mut checks,
@@ -174,7 +186,10 @@ where
             _ => CommandCheckMode::Version,
         };
 
-        match CliBackend::from_hat_backend(hat_backend) {
+        match CliBackend::from_hat_backend_with_custom(
+            hat_backend,
+            &config.adapters.custom_backends,
+        ) {
             Ok(cli_backend) => {
                 let backend_name = canonical_backend_name(
                     &hat_backend.to_cli_backend(),
@@ -256,9 +271,45 @@ fn push_backend_check<F, G>(
         label,
         status,
         message,
+        duration_ms: 0,
     });
 }
 
+/// Sends the configured backend the same warm-up prompt as `ralph run`.
+async fn backend_probe_check(config: &RalphConfig) -> CheckResult {
+    let mut config = config.clone();
+    if config.cli.backend == "auto" {
+        match detect_configured_backend(&config) {
+            Ok(backend) => config.cli.backend = backend,
+            Err(_) => {
+                return CheckResult::warn(
+                    "backend:probe",
+                    "Backend probe skipped",
+                    "No backend detected",
+                );
+            }
+        }
+    }
+
+    let backend = match CliBackend::from_ralph_config(&config) {
+        Ok(backend) => backend,
+        Err(e) => {
+            return CheckResult::warn("backend:probe", "Backend probe skipped", e.to_string());
+        }
+    };
+    match probe_backend(&backend, DEFAULT_PROBE_TIMEOUT).await {
+        Ok(()) => CheckResult::pass(
+            "backend:probe",
+            format!("{} answered a warm-up prompt", backend.command),
+        ),
+        Err(failure) => CheckResult::fail(
+            "backend:probe",
+            format!("{} did not answer a warm-up prompt", backend.command),
+            failure.to_string(),
+        ),
+    }
+}
+
 fn auth_hint_check<F>(_backends: &[String], _env_lookup: F) -> CheckResult
 where
     F: Fn(&str) -> Option<String>,
@@ -451,7 +502,7 @@ fn command_version_ok(command: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn command_exists(command: &str) -> bool {
+pub(crate) fn command_exists(command: &str) -> bool {
     let path = Path::new(command);
     if path.components().count() > 1 {
         return path.is_file();
@@ -607,10 +658,15 @@ mod tests {
             triggers: vec!["work.start".to_string()],
             publishes: vec![],
             instructions: String::new(),
+            instructions_file: None,
             extra_instructions: vec![],
             backend,
+            env: std::collections::BTreeMap::new(),
+            allowed_tools: vec![],
+            disallowed_tools: vec![],
             default_publishes: None,
             max_activations: None,
+            max_iterations: None,
         }
     }
 
@@ -0,0 +1,252 @@
+//! Reading several events files as one history for `ralph events`.
+//!
+//! Every run writes its own `.ralph/events-<timestamp>.jsonl`, so a session
+//! made of several phases is spread across files. `--all-runs` or a glob in
+//! `--file` reads them all, merges the records by timestamp and tags each
+//! one with the file it came from.
+
+use ralph_core::{EventHistory, EventRecord};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding the per-run events files.
+const RALPH_DIR: &str = ".ralph";
+
+/// An event record and the file it was read from.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourcedEvent {
+    /// File name of the events file, when reading several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    #[serde(flatten)]
+    pub record: EventRecord,
+}
+
+/// Records merged from several events files.
+#[derive(Debug, Default)]
+pub struct MergedEvents {
+    /// Records in timestamp order.
+    pub events: Vec<SourcedEvent>,
+
+    /// Lines across all files that couldn't be parsed.
+    pub skipped_lines: usize,
+}
+
+/// Returns true if `path` contains glob wildcards.
+pub fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+/// Files matching `pattern`, sorted by name.
+///
+/// Wildcards (`*`, `?`) are allowed in the file name only. A bare pattern
+/// such as `events-*.jsonl` that matches nothing in the working directory
+/// is tried in `.ralph/`, where run files live.
+pub fn expand_glob(pattern: &Path) -> Vec<PathBuf> {
+    let Some(name) = pattern.file_name().map(|name| name.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let dir = pattern.parent().filter(|dir| !dir.as_os_str().is_empty());
+
+    let files = matching_files(dir.unwrap_or(Path::new(".")), &name);
+    if files.is_empty() && dir.is_none() {
+        return matching_files(Path::new(RALPH_DIR), &name);
+    }
+    files
+}
+
+/// Every run's events file in `ralph_dir`, including the legacy
+/// `events.jsonl`, sorted by name.
+pub fn all_run_files(ralph_dir: &Path) -> Vec<PathBuf> {
+    let mut files = matching_files(ralph_dir, "events-*.jsonl");
+    let legacy = ralph_dir.join("events.jsonl");
+    if legacy.is_file() {
+        files.insert(0, legacy);
+    }
+    files
+}
+
+/// Every run's events file in the current workspace.
+pub fn all_runs() -> Vec<PathBuf> {
+    all_run_files(Path::new(RALPH_DIR))
+}
+
+/// Reads every file in `paths` and merges the records by timestamp.
+///
+/// The merge is stable, so records sharing a timestamp keep their file
+/// order. Records without a readable timestamp go last.
+pub fn read_merged(paths: &[PathBuf]) -> std::io::Result<MergedEvents> {
+    let mut merged = MergedEvents::default();
+    for path in paths {
+        let report = EventHistory::new(path).read_all_with_report()?;
+        merged.skipped_lines += report.skipped_lines;
+
+        let source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        merged
+            .events
+            .extend(report.records.into_iter().map(|record| SourcedEvent {
+                source: source.clone(),
+                record,
+            }));
+    }
+
+    merged.events.sort_by_key(|event| {
+        let ts = chrono::DateTime::parse_from_rfc3339(&event.record.ts).ok();
+        (ts.is_none(), ts)
+    });
+    Ok(merged)
+}
+
+fn matching_files(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| wildcard_match(pattern, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Matches `name` against `pattern`, where `*` matches any run of
+/// characters and `?` any single one.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it resumed from
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn line(ts: &str, topic: &str) -> String {
+        format!(r#"{{"ts":"{ts}","iteration":1,"hat":"ralph","topic":"{topic}","payload":""}}"#)
+    }
+
+    #[test]
+    fn test_read_merged_interleaves_by_timestamp() {
+        let temp = TempDir::new().unwrap();
+        let first = temp.path().join("events-20260101-100000.jsonl");
+        let second = temp.path().join("events-20260101-100500.jsonl");
+        fs::write(
+            &first,
+            [
+                line("2026-01-01T10:00:00Z", "plan.start"),
+                line("2026-01-01T10:02:00Z", "plan.done"),
+                line("2026-01-01T10:06:00Z", "plan.review"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            [
+                line("2026-01-01T10:01:00Z", "build.start"),
+                "not json".to_string(),
+                line("2026-01-01T10:05:00Z", "build.done"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let merged = read_merged(&[first, second]).unwrap();
+
+        let order: Vec<(&str, &str)> = merged
+            .events
+            .iter()
+            .map(|e| (e.record.topic.as_str(), e.source.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("plan.start", "events-20260101-100000.jsonl"),
+                ("build.start", "events-20260101-100500.jsonl"),
+                ("plan.done", "events-20260101-100000.jsonl"),
+                ("build.done", "events-20260101-100500.jsonl"),
+                ("plan.review", "events-20260101-100000.jsonl"),
+            ]
+        );
+        assert_eq!(merged.skipped_lines, 1);
+
+        let json = serde_json::to_value(&merged.events[0]).unwrap();
+        assert_eq!(json["source"], "events-20260101-100000.jsonl");
+        assert_eq!(json["topic"], "plan.start");
+    }
+
+    #[test]
+    fn test_all_run_files_and_glob_expansion() {
+        let temp = TempDir::new().unwrap();
+        for name in [
+            "events-20260102-000000.jsonl",
+            "events-20260101-000000.jsonl",
+            "events.jsonl",
+            "events-20260101-000000.jsonl.lock",
+            "history.jsonl",
+        ] {
+            fs::write(temp.path().join(name), "").unwrap();
+        }
+
+        let names = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            names(all_run_files(temp.path())),
+            vec![
+                "events.jsonl",
+                "events-20260101-000000.jsonl",
+                "events-20260102-000000.jsonl"
+            ]
+        );
+        assert_eq!(
+            names(expand_glob(&temp.path().join("events-202601?1-*.jsonl"))),
+            vec!["events-20260101-000000.jsonl"]
+        );
+        assert!(is_glob(Path::new("events-*.jsonl")));
+        assert!(!is_glob(Path::new(".ralph/events.jsonl")));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("events-*.jsonl", "events-1.jsonl"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("events-*.jsonl", "events-1.jsonl.lock"));
+        assert!(!wildcard_match("a?c", "ac"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Aggregate report for `ralph events stats`.
+//!
+//! Summarizes an events file: how often each topic was published, how many
+//! events each iteration produced, how long the run spanned, and the longest
+//! silence between two events, which usually marks where a loop stalled.
+
+use crate::display::{colors, format_elapsed};
+use chrono::{DateTime, FixedOffset};
+use ralph_core::EventRecord;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Summary of an events file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EventStats {
+    /// Number of events.
+    pub total: usize,
+
+    /// Events per topic.
+    pub by_topic: BTreeMap<String, usize>,
+
+    /// Events per iteration.
+    pub by_iteration: BTreeMap<u32, usize>,
+
+    /// Timestamp of the earliest event.
+    pub first: Option<String>,
+
+    /// Timestamp of the latest event.
+    pub last: Option<String>,
+
+    /// Seconds between the earliest and latest event.
+    pub span_secs: Option<i64>,
+
+    /// Longest time between two consecutive events.
+    pub longest_gap: Option<EventGap>,
+}
+
+/// Time between two consecutive events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventGap {
+    /// Length of the gap in seconds.
+    pub secs: i64,
+
+    /// Topic of the event before the gap.
+    pub after: String,
+
+    /// Topic of the event that ended the gap.
+    pub before: String,
+
+    /// Iteration the gap ended in.
+    pub iteration: u32,
+
+    /// Timestamp of the event before the gap.
+    pub from: String,
+
+    /// Timestamp of the event that ended the gap.
+    pub to: String,
+}
+
+impl EventStats {
+    /// Computes stats over `records`.
+    ///
+    /// Events without a readable timestamp count toward the totals but are
+    /// left out of the span and gap, which need a place in time.
+    pub fn from_records(records: &[EventRecord]) -> Self {
+        let mut stats = Self {
+            total: records.len(),
+            ..Self::default()
+        };
+        for record in records {
+            *stats.by_topic.entry(record.topic.clone()).or_default() += 1;
+            *stats.by_iteration.entry(record.iteration).or_default() += 1;
+        }
+
+        let mut timed: Vec<(DateTime<FixedOffset>, &EventRecord)> = records
+            .iter()
+            .filter_map(|r| DateTime::parse_from_rfc3339(&r.ts).ok().map(|ts| (ts, r)))
+            .collect();
+        // Stable, so events sharing a timestamp keep their file order
+        timed.sort_by_key(|(ts, _)| *ts);
+
+        if let (Some((first_ts, first)), Some((last_ts, last))) = (timed.first(), timed.last()) {
+            stats.first = Some(first.ts.clone());
+            stats.last = Some(last.ts.clone());
+            stats.span_secs = Some((*last_ts - *first_ts).num_seconds());
+        }
+
+        stats.longest_gap = timed
+            .windows(2)
+            .map(|pair| {
+                let ((from_ts, from), (to_ts, to)) = (&pair[0], &pair[1]);
+                EventGap {
+                    secs: (*to_ts - *from_ts).num_seconds(),
+                    after: from.topic.clone(),
+                    before: to.topic.clone(),
+                    iteration: to.iteration,
+                    from: from.ts.clone(),
+                    to: to.ts.clone(),
+                }
+            })
+            // First of equally long gaps wins
+            .reduce(|longest, gap| {
+                if gap.secs > longest.secs {
+                    gap
+                } else {
+                    longest
+                }
+            });
+
+        stats
+    }
+}
+
+/// Prints `stats` as a human-readable report.
+pub fn print_stats(stats: &EventStats, use_colors: bool) {
+    use colors::{BOLD, DIM, RESET};
+    let (bold, dim, reset) = if use_colors {
+        (BOLD, DIM, RESET)
+    } else {
+        ("", "", "")
+    };
+
+    println!(
+        "{bold}Events:{reset}       {} across {} iteration(s)",
+        stats.total,
+        stats.by_iteration.len()
+    );
+    if let (Some(first), Some(last)) = (&stats.first, &stats.last) {
+        println!("{bold}First:{reset}        {first}");
+        println!("{bold}Last:{reset}         {last}");
+    }
+    if let Some(span) = stats.span_secs {
+        println!("{bold}Span:{reset}         {}", format_secs(span));
+    }
+    if let Some(gap) = &stats.longest_gap {
+        println!(
+            "{bold}Longest gap:{reset}  {} between {} and {} (iteration {}, at {})",
+            format_secs(gap.secs),
+            gap.after,
+            gap.before,
+            gap.iteration,
+            gap.from
+        );
+    }
+
+    let mut topics: Vec<(&String, &usize)> = stats.by_topic.iter().collect();
+    topics.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let width = topics
+        .iter()
+        .map(|(topic, _)| topic.len())
+        .max()
+        .unwrap_or(0)
+        .max("Topic".len());
+
+    println!();
+    println!("{bold}{:<width$}  {:>6}{reset}", "Topic", "Count");
+    for (topic, count) in topics {
+        println!("{:<width$}  {:>6}", topic, count);
+    }
+
+    println!();
+    println!("{bold}{:>9}  {:>6}{reset}", "Iteration", "Events");
+    for (iteration, count) in &stats.by_iteration {
+        println!("{:>9}  {:>6}", iteration, count);
+    }
+
+    if stats.longest_gap.is_none() && stats.total > 0 {
+        println!("\n{dim}Fewer than two timestamped events; no gap to report.{reset}");
+    }
+}
+
+fn format_secs(secs: i64) -> String {
+    format_elapsed(Duration::from_secs(secs.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ts: &str, iteration: u32, topic: &str) -> EventRecord {
+        EventRecord {
+            ts: ts.to_string(),
+            iteration,
+            hat: "ralph".to_string(),
+            topic: topic.to_string(),
+            triggered: None,
+            payload: String::new(),
+            blocked_count: None,
+        }
+    }
+
+    #[test]
+    fn test_stats_over_known_events() {
+        let records = vec![
+            record("2026-01-01T10:00:00Z", 0, "task.start"),
+            record("2026-01-01T10:00:30Z", 1, "build.task"),
+            record("2026-01-01T10:01:00Z", 1, "build.done"),
+            // Stall: 14 minutes with nothing published
+            record("2026-01-01T10:15:00Z", 2, "build.task"),
+            record("not-a-timestamp", 2, "build.blocked"),
+            record("2026-01-01T10:16:00Z", 2, "build.done"),
+            record("2026-01-01T10:20:00Z", 3, "LOOP_COMPLETE"),
+        ];
+
+        let stats = EventStats::from_records(&records);
+
+        assert_eq!(stats.total, 7);
+        assert_eq!(
+            stats.by_topic,
+            BTreeMap::from([
+                ("LOOP_COMPLETE".to_string(), 1),
+                ("build.blocked".to_string(), 1),
+                ("build.done".to_string(), 2),
+                ("build.task".to_string(), 2),
+                ("task.start".to_string(), 1),
+            ])
+        );
+        assert_eq!(
+            stats.by_iteration,
+            BTreeMap::from([(0, 1), (1, 2), (2, 3), (3, 1)])
+        );
+        assert_eq!(stats.first.as_deref(), Some("2026-01-01T10:00:00Z"));
+        assert_eq!(stats.last.as_deref(), Some("2026-01-01T10:20:00Z"));
+        assert_eq!(stats.span_secs, Some(20 * 60));
+
+        let gap = stats.longest_gap.expect("gap");
+        assert_eq!(gap.secs, 14 * 60);
+        assert_eq!(gap.after, "build.done");
+        assert_eq!(gap.before, "build.task");
+        assert_eq!(gap.iteration, 2);
+        assert_eq!(gap.from, "2026-01-01T10:01:00Z");
+    }
+
+    #[test]
+    fn test_stats_without_timestamps_has_no_span() {
+        let stats = EventStats::from_records(&[record("garbage", 1, "build.task")]);
+
+        assert_eq!(stats.total, 1);
+        assert_eq!(stats.span_secs, None);
+        assert_eq!(stats.longest_gap, None);
+        print_stats(&stats, false);
+    }
+}
@@ -13,9 +13,11 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use indicatif::{ProgressBar, ProgressStyle};
 use ralph_adapters::{CliBackend, detect_backend_default};
-use ralph_core::{HatRegistry, RalphConfig};
-use std::collections::HashSet;
+use ralph_core::{CompletionMatcher, HatRegistry, RalphConfig};
+use ralph_proto::{HatId, Topic};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Write;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::time::Duration;
 use tracing::warn;
@@ -33,12 +35,15 @@ pub enum HatsCommands {
     Validate,
     /// Display hat topology graph
     Graph {
-        /// Output format (unicode, ascii, compact, mermaid)
+        /// Output format (unicode, ascii, compact, mermaid, dot)
         #[arg(long, default_value = "unicode")]
         format: GraphFormat,
         /// Backend to use for AI-generated diagrams (claude, kiro, gemini, codex, amp)
         #[arg(short = 'b', long = "backend")]
         backend: Option<String>,
+        /// Write the graph to a file instead of stdout
+        #[arg(short = 'o', long, value_name = "FILE")]
+        output: Option<PathBuf>,
     },
     /// List all configured hats (default if no subcommand)
     List {
@@ -59,8 +64,10 @@ pub enum GraphFormat {
     Ascii,
     /// Compact single-glyph nodes - minimal output
     Compact,
-    /// Raw Mermaid syntax - for external rendering tools
+    /// Fenced Mermaid flowchart - paste into Markdown docs and PRs
     Mermaid,
+    /// Graphviz dot - render with `dot -Tsvg`
+    Dot,
 }
 
 #[derive(ValueEnum, Clone, Debug, Default)]
@@ -95,9 +102,22 @@ pub fn execute(config_sources: &[ConfigSource], args: HatsArgs, use_colors: bool
             show_hat(&mut stdout, &registry, &show_args.name, use_colors)
         }
         Some(HatsCommands::Validate) => validate_hats(&mut stdout, &config, &registry, use_colors),
-        Some(HatsCommands::Graph { format, backend }) => {
-            graph_hats(&mut stdout, &config, &registry, format, backend.as_deref())
+        Some(HatsCommands::Graph {
+            format,
+            backend,
+            output: Some(path),
+        }) => {
+            let mut file = std::fs::File::create(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            graph_hats(&mut file, &config, &registry, format, backend.as_deref())?;
+            println!("Wrote hat graph to {}", path.display());
+            Ok(())
         }
+        Some(HatsCommands::Graph {
+            format,
+            backend,
+            output: None,
+        }) => graph_hats(&mut stdout, &config, &registry, format, backend.as_deref()),
     }
 }
 
@@ -108,7 +128,7 @@ pub fn execute(config_sources: &[ConfigSource], args: HatsArgs, use_colors: bool
 /// - Builtin presets (e.g., `builtin:confession-loop`)
 ///
 /// Remote URLs and overrides are not supported; returns an error with guidance.
-fn load_config(config_sources: &[ConfigSource]) -> Result<RalphConfig> {
+pub(crate) fn load_config(config_sources: &[ConfigSource]) -> Result<RalphConfig> {
     // Filter out overrides and remote URLs - not supported for hats command
     let sources: Vec<_> = config_sources
         .iter()
@@ -145,7 +165,7 @@ fn load_config(config_sources: &[ConfigSource]) -> Result<RalphConfig> {
             }
         }
         ConfigSource::Builtin(name) => {
-            let preset = presets::get_preset(name).ok_or_else(|| {
+            let preset = presets::preset_content(name).ok_or_else(|| {
                 let available = presets::preset_names().join(", ");
                 anyhow::anyhow!(
                     "Unknown preset '{}'. Run `ralph init --list-presets` to see available presets.\n\nAvailable: {}",
@@ -153,7 +173,7 @@ fn load_config(config_sources: &[ConfigSource]) -> Result<RalphConfig> {
                     available
                 )
             })?;
-            RalphConfig::parse_yaml(preset.content)
+            RalphConfig::parse_yaml(&preset)
                 .with_context(|| format!("Failed to parse builtin preset '{}'", name))
         }
         ConfigSource::Remote(url) => {
@@ -190,8 +210,12 @@ fn list_hats<W: Write>(writer: &mut W, registry: &HatRegistry, _use_colors: bool
         return Ok(());
     }
 
-    writeln!(writer, "{:<20} DESCRIPTION", "HAT")?;
-    writeln!(writer, "{}", "-".repeat(80))?;
+    writeln!(
+        writer,
+        "{:<20} {:<58} {:<8} TOOLS",
+        "HAT", "DESCRIPTION", "BUDGET"
+    )?;
+    writeln!(writer, "{}", "-".repeat(100))?;
 
     // Sort by name for consistent output
     let mut hats: Vec<_> = registry.all().collect();
@@ -211,7 +235,269 @@ fn list_hats<W: Write>(writer: &mut W, registry: &HatRegistry, _use_colors: bool
             desc.to_string()
         };
 
-        writeln!(writer, "{:<20} {}", hat.name, desc)?;
+        writeln!(
+            writer,
+            "{:<20} {:<58} {:<8} {}",
+            hat.name,
+            desc,
+            format_budget(registry, &hat.id),
+            format_tools(registry, &hat.id)
+        )?;
+    }
+    Ok(())
+}
+
+/// Formats a hat's `max_iterations` budget, or "-" when unlimited.
+fn format_budget(registry: &HatRegistry, id: &HatId) -> String {
+    registry
+        .get_config(id)
+        .and_then(|config| config.max_iterations)
+        .map_or_else(|| "-".to_string(), |max| max.to_string())
+}
+
+/// Formats a hat's tool restrictions (e.g. "allow: Read,Grep; deny: Edit"),
+/// or "-" when unrestricted.
+fn format_tools(registry: &HatRegistry, id: &HatId) -> String {
+    let Some(config) = registry.get_config(id) else {
+        return "-".to_string();
+    };
+    let mut parts = Vec::new();
+    if !config.allowed_tools.is_empty() {
+        parts.push(format!("allow: {}", config.allowed_tools.join(",")));
+    }
+    if !config.disallowed_tools.is_empty() {
+        parts.push(format!("deny: {}", config.disallowed_tools.join(",")));
+    }
+    if parts.is_empty() {
+        "-".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
+/// Returns the fewest hat activations needed to reach a completion event.
+///
+/// Walks the topology from the starting event (or `task.start`), treating
+/// each hat as one step from a topic it triggers on to the topics it
+/// publishes. Returns `None` if no hat path reaches completion.
+pub(crate) fn shortest_completion_path(config: &RalphConfig) -> Option<u32> {
+    let completion = CompletionMatcher::from_config(&config.event_loop);
+    let start = config
+        .event_loop
+        .starting_event
+        .clone()
+        .unwrap_or_else(|| "task.start".to_string());
+
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0u32)]);
+    while let Some((topic, steps)) = queue.pop_front() {
+        let topic = Topic::new(topic);
+        for hat in config.hats.values() {
+            if !hat.trigger_topics().iter().any(|t| t.matches(&topic)) {
+                continue;
+            }
+            for published in &hat.publishes {
+                if completion.matches(published) {
+                    return Some(steps + 1);
+                }
+                if seen.insert(published.clone()) {
+                    queue.push_back((published.clone(), steps + 1));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns a warning if the hat budgets can't cover a path to completion.
+///
+/// Only applies when every hat has a `max_iterations` budget; a single
+/// unbudgeted hat could cover the rest of the path.
+pub(crate) fn hat_budget_warning(config: &RalphConfig) -> Option<String> {
+    if config.hats.is_empty() {
+        return None;
+    }
+    let total = config
+        .hats
+        .values()
+        .map(|hat| hat.max_iterations)
+        .sum::<Option<u32>>()?;
+    let needed = shortest_completion_path(config)?;
+    (total < needed).then(|| {
+        format!(
+            "hat budgets total {} iterations, but the shortest path to completion needs {}",
+            total, needed
+        )
+    })
+}
+
+/// One hat activation in a simulated event flow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlannedStep {
+    /// Event that activates the hat
+    pub topic: String,
+    pub hat: HatId,
+    pub hat_name: String,
+    /// Everything the hat may publish
+    pub publishes: Vec<String>,
+}
+
+/// Where a simulated event flow ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PlanEnd {
+    /// The last step publishes this completion event
+    Completion(String),
+    /// No hat subscribes to this event, so Ralph handles it and decides
+    /// what comes next
+    Ralph(String),
+    /// Every path loops without completing or reaching Ralph
+    NoCompletion,
+}
+
+/// The event flow `ralph run --plan-only` prints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EventPlan {
+    pub start: String,
+    pub steps: Vec<PlannedStep>,
+    pub end: PlanEnd,
+}
+
+/// Simulates event routing from the starting event (or `task.start`)
+/// without invoking a backend.
+///
+/// Routes each event to the hat the registry would pick and follows what
+/// it publishes, returning the shortest path to a completion event. When no
+/// hat path completes, returns the shortest path to an event Ralph has to
+/// handle instead.
+pub(crate) fn plan_event_flow(config: &RalphConfig) -> EventPlan {
+    let registry = HatRegistry::from_config(config);
+    let completion = CompletionMatcher::from_config(&config.event_loop);
+    let start = config
+        .event_loop
+        .starting_event
+        .clone()
+        .unwrap_or_else(|| "task.start".to_string());
+
+    // Each reached topic maps to the topic whose hat published it
+    let mut parents: HashMap<String, Option<String>> = HashMap::from([(start.clone(), None)]);
+    let mut handed_to_ralph = None;
+    let mut queue = VecDeque::from([start.clone()]);
+    while let Some(topic) = queue.pop_front() {
+        let Some(hat) = registry.get_for_topic(&topic) else {
+            handed_to_ralph.get_or_insert(topic);
+            continue;
+        };
+        let publishes = registry
+            .get_config(&hat.id)
+            .map(|hat_config| hat_config.publishes.clone())
+            .unwrap_or_default();
+        if let Some(done) = publishes.iter().find(|p| completion.matches(p)) {
+            let steps = trace_steps(&registry, &parents, topic);
+            return EventPlan {
+                start,
+                steps,
+                end: PlanEnd::Completion(done.clone()),
+            };
+        }
+        for published in publishes {
+            if !parents.contains_key(&published) {
+                parents.insert(published.clone(), Some(topic.clone()));
+                queue.push_back(published);
+            }
+        }
+    }
+
+    match handed_to_ralph {
+        Some(topic) => {
+            let steps = match parents.get(&topic).cloned().flatten() {
+                Some(parent) => trace_steps(&registry, &parents, parent),
+                None => Vec::new(),
+            };
+            EventPlan {
+                start,
+                steps,
+                end: PlanEnd::Ralph(topic),
+            }
+        }
+        None => EventPlan {
+            start,
+            steps: Vec::new(),
+            end: PlanEnd::NoCompletion,
+        },
+    }
+}
+
+/// Rebuilds the hat activations leading to (and including) `last`.
+fn trace_steps(
+    registry: &HatRegistry,
+    parents: &HashMap<String, Option<String>>,
+    last: String,
+) -> Vec<PlannedStep> {
+    let mut topics = vec![last];
+    while let Some(Some(parent)) = topics.last().and_then(|topic| parents.get(topic)) {
+        topics.push(parent.clone());
+    }
+    topics
+        .into_iter()
+        .rev()
+        .filter_map(|topic| {
+            let hat = registry.get_for_topic(&topic)?;
+            let publishes = registry
+                .get_config(&hat.id)
+                .map(|hat_config| hat_config.publishes.clone())
+                .unwrap_or_default();
+            Some(PlannedStep {
+                topic,
+                hat: hat.id.clone(),
+                hat_name: hat.name.clone(),
+                publishes,
+            })
+        })
+        .collect()
+}
+
+/// Prints the simulated event flow for `ralph run --plan-only`.
+pub(crate) fn print_event_plan<W: Write>(writer: &mut W, config: &RalphConfig) -> Result<()> {
+    let plan = plan_event_flow(config);
+    writeln!(writer, "Event plan (simulated, no backend is invoked):")?;
+    writeln!(writer, "  Start: {}", plan.start)?;
+    if config.hats.is_empty() {
+        writeln!(
+            writer,
+            "  No hats configured: Ralph handles {} until it emits {}",
+            plan.start, config.event_loop.completion_promise
+        )?;
+        return Ok(());
+    }
+
+    for (i, step) in plan.steps.iter().enumerate() {
+        writeln!(
+            writer,
+            "  {}. {} -> {} ({})",
+            i + 1,
+            step.topic,
+            step.hat_name,
+            step.hat
+        )?;
+        writeln!(writer, "     publishes: {}", step.publishes.join(", "))?;
+    }
+
+    match plan.end {
+        PlanEnd::Completion(topic) => writeln!(
+            writer,
+            "  Completion: {} after {} hat activation(s)",
+            topic,
+            plan.steps.len()
+        )?,
+        PlanEnd::Ralph(topic) => writeln!(
+            writer,
+            "  Then: no hat subscribes to {}, so Ralph handles it and decides what comes next",
+            topic
+        )?,
+        PlanEnd::NoCompletion => writeln!(
+            writer,
+            "  No path reaches completion: the hats only publish events that loop back"
+        )?,
     }
     Ok(())
 }
@@ -266,11 +552,12 @@ fn validate_hats<W: Write>(
     }
 
     // 2. Orphan event detection (published but no subscribers)
+    let completion = CompletionMatcher::from_config(&config.event_loop);
     for hat in registry.all() {
         for pub_event in &hat.publishes {
             let topic = pub_event.as_str();
             // Ignore loop completion promise
-            if topic == config.event_loop.completion_promise {
+            if completion.matches(topic) {
                 continue;
             }
             // Ignore if Ralph subscribes (task.start, etc - though Ralph usually PUBLISHES task.start)
@@ -370,9 +657,12 @@ fn graph_hats<W: Write>(
     match format {
         GraphFormat::Mermaid => {
             writeln!(writer, "```mermaid")?;
-            write!(writer, "{}", generate_mermaid_string(registry))?;
+            write!(writer, "{}", generate_mermaid_string(config, registry))?;
             writeln!(writer, "```")?;
         }
+        GraphFormat::Dot => {
+            write!(writer, "{}", generate_dot_string(config, registry))?;
+        }
         GraphFormat::Unicode | GraphFormat::Ascii | GraphFormat::Compact => {
             // Generate ASCII diagram via AI backend
             let rendered = render_hat_dag_via_ai(config, registry, backend_override)?;
@@ -594,67 +884,216 @@ fn extract_diagram(response: &str) -> String {
     }
 }
 
-/// Generate Mermaid flowchart syntax for the hat topology.
-fn generate_mermaid_string(registry: &HatRegistry) -> String {
-    let mut output = String::new();
-    output.push_str("flowchart LR\n");
-    output.push_str("    Start[task.start] --> Ralph\n");
+/// Node for the loop's starting event.
+const START_NODE: &str = "Start";
+/// Node for Ralph, the coordinator every event is routed through.
+const RALPH_NODE: &str = "Ralph";
+/// Node for loop completion.
+const COMPLETE_NODE: &str = "Complete";
 
-    // Reconstruct Ralph's publishes (what hats subscribe to)
-    let mut ralph_publishes: HashSet<String> = HashSet::new();
-    for hat in registry.all() {
-        for sub in &hat.subscriptions {
-            ralph_publishes.insert(sub.as_str().to_string());
+/// Hat topology as nodes and labeled edges, in a stable order.
+///
+/// Hats are ordered by ID and edges follow the order of each hat's triggers
+/// and publishes, so regenerating a diagram for the same config produces
+/// identical output.
+struct HatGraph {
+    /// Label of the start node (the event that starts the loop).
+    start: String,
+    /// Label of the completion node (the primary completion promise).
+    completion: String,
+    /// Hat nodes as (node ID, display name).
+    hats: Vec<(String, String)>,
+    edges: Vec<GraphEdge>,
+}
+
+struct GraphEdge {
+    from: String,
+    to: String,
+    /// Topic or trigger pattern; `None` for the start edge.
+    label: Option<String>,
+    /// Hat-to-hat flow (drawn dashed); all other edges go through Ralph.
+    direct: bool,
+}
+
+impl HatGraph {
+    fn new(config: &RalphConfig, registry: &HatRegistry) -> Self {
+        let completion = CompletionMatcher::from_config(&config.event_loop);
+        let mut used = HashSet::from([
+            START_NODE.to_string(),
+            RALPH_NODE.to_string(),
+            COMPLETE_NODE.to_string(),
+        ]);
+        let node_ids: Vec<(&ralph_proto::Hat, String)> = registry
+            .all()
+            .map(|hat| (hat, unique_node_id(&hat.name, &mut used)))
+            .collect();
+
+        let mut edges = vec![GraphEdge {
+            from: START_NODE.to_string(),
+            to: RALPH_NODE.to_string(),
+            label: None,
+            direct: false,
+        }];
+        let mut push = |from: &str, to: &str, label: &str, direct: bool| {
+            edges.push(GraphEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                label: Some(label.to_string()),
+                direct,
+            });
+        };
+
+        // Ralph -> Hats (trigger patterns, globs rendered as written)
+        for (hat, id) in &node_ids {
+            for sub in &hat.subscriptions {
+                push(RALPH_NODE, id, sub.as_str(), false);
+            }
         }
-    }
 
-    // Ralph -> Hats
-    for hat in registry.all() {
-        let node_id = sanitize_id(&hat.name);
-        for sub in &hat.subscriptions {
-            output.push_str(&format!("    Ralph -->|{}| {}\n", sub.as_str(), node_id));
+        // Hats -> Ralph, or straight to completion for completion topics
+        for (hat, id) in &node_ids {
+            for topic in &hat.publishes {
+                let target = if completion.matches(topic.as_str()) {
+                    COMPLETE_NODE
+                } else {
+                    RALPH_NODE
+                };
+                push(id, target, topic.as_str(), false);
+            }
+        }
+        push(
+            RALPH_NODE,
+            COMPLETE_NODE,
+            config.event_loop.completion_promise.primary(),
+            false,
+        );
+
+        // Hat -> Hat (direct flow visualization)
+        // Even though everything goes through Ralph, it's useful to see A -> B
+        for (source, source_id) in &node_ids {
+            for topic in &source.publishes {
+                for (target, target_id) in &node_ids {
+                    if target.id != source.id
+                        && target
+                            .subscriptions
+                            .iter()
+                            .any(|sub| sub.matches_str(topic.as_str()))
+                    {
+                        push(source_id, target_id, topic.as_str(), true);
+                    }
+                }
+            }
+        }
+
+        Self {
+            start: "task.start".to_string(),
+            completion: config.event_loop.completion_promise.primary().to_string(),
+            hats: node_ids
+                .into_iter()
+                .map(|(hat, id)| (id, hat.name.clone()))
+                .collect(),
+            edges,
         }
     }
+}
 
-    // Hats -> Ralph
-    for hat in registry.all() {
-        let node_id = sanitize_id(&hat.name);
-        for pub_event in &hat.publishes {
-            output.push_str(&format!(
-                "    {} -->|{}| Ralph\n",
-                node_id,
-                pub_event.as_str()
-            ));
+/// Returns a node ID for a hat name that doesn't clash with `used`.
+fn unique_node_id(name: &str, used: &mut HashSet<String>) -> String {
+    let base = match sanitize_id(name) {
+        id if id.is_empty() => "Hat".to_string(),
+        id => id,
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while used.contains(&id) {
+        id = format!("{base}{n}");
+        n += 1;
+    }
+    used.insert(id.clone());
+    id
+}
+
+/// Generate Mermaid flowchart syntax for the hat topology.
+fn generate_mermaid_string(config: &RalphConfig, registry: &HatRegistry) -> String {
+    let graph = HatGraph::new(config, registry);
+    let label = |text: &str| text.replace('"', "#quot;");
+
+    let mut output = String::new();
+    output.push_str("flowchart LR\n");
+    output.push_str(&format!(
+        "    {START_NODE}([\"{}\"])\n",
+        label(&graph.start)
+    ));
+    output.push_str(&format!("    {RALPH_NODE}((\"Ralph\"))\n"));
+    for (id, name) in &graph.hats {
+        output.push_str(&format!("    {id}[\"{}\"]\n", label(name)));
+    }
+    output.push_str(&format!(
+        "    {COMPLETE_NODE}([\"{}\"])\n",
+        label(&graph.completion)
+    ));
+
+    for edge in &graph.edges {
+        let arrow = if edge.direct { "-.->" } else { "-->" };
+        match &edge.label {
+            Some(topic) => output.push_str(&format!(
+                "    {} {arrow}|{}| {}\n",
+                edge.from,
+                label(topic),
+                edge.to
+            )),
+            None => output.push_str(&format!("    {} {arrow} {}\n", edge.from, edge.to)),
         }
     }
 
-    // Hat -> Hat (direct flow visualization)
-    // Even though everything goes through Ralph, it's useful to see A -> B
-    for source in registry.all() {
-        let source_id = sanitize_id(&source.name);
-        for pub_event in &source.publishes {
-            // Find hats that subscribe to this
-            for target in registry.all() {
-                if target.id == source.id {
-                    continue;
-                }
-                if target
-                    .subscriptions
-                    .iter()
-                    .any(|s| s.as_str() == pub_event.as_str())
-                {
-                    let target_id = sanitize_id(&target.name);
-                    output.push_str(&format!(
-                        "    {} -.->|{}| {}\n",
-                        source_id,
-                        pub_event.as_str(),
-                        target_id
-                    ));
-                }
-            }
+    output
+}
+
+/// Generate Graphviz dot syntax for the hat topology (`dot -Tsvg`).
+fn generate_dot_string(config: &RalphConfig, registry: &HatRegistry) -> String {
+    let graph = HatGraph::new(config, registry);
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut output = String::new();
+    output.push_str("digraph hats {\n");
+    output.push_str("    rankdir=LR;\n");
+    output.push_str("    node [shape=box];\n");
+    output.push_str(&format!(
+        "    {START_NODE} [label={}, shape=oval];\n",
+        quote(&graph.start)
+    ));
+    output.push_str(&format!(
+        "    {RALPH_NODE} [label=\"Ralph\", shape=circle];\n"
+    ));
+    for (id, name) in &graph.hats {
+        output.push_str(&format!("    {id} [label={}];\n", quote(name)));
+    }
+    output.push_str(&format!(
+        "    {COMPLETE_NODE} [label={}, shape=oval];\n",
+        quote(&graph.completion)
+    ));
+
+    for edge in &graph.edges {
+        let mut attrs = Vec::new();
+        if let Some(topic) = &edge.label {
+            attrs.push(format!("label={}", quote(topic)));
+        }
+        if edge.direct {
+            attrs.push("style=dashed".to_string());
+        }
+        if attrs.is_empty() {
+            output.push_str(&format!("    {} -> {};\n", edge.from, edge.to));
+        } else {
+            output.push_str(&format!(
+                "    {} -> {} [{}];\n",
+                edge.from,
+                edge.to,
+                attrs.join(", ")
+            ));
         }
     }
 
+    output.push_str("}\n");
     output
 }
 
@@ -687,6 +1126,8 @@ fn show_hat<W: Write>(
     writeln!(writer)?;
 
     writeln!(writer, "ID: {}", hat.id)?;
+    writeln!(writer, "Budget: {}", format_budget(registry, &hat.id))?;
+    writeln!(writer, "Tools: {}", format_tools(registry, &hat.id))?;
 
     writeln!(writer, "\nTriggers On:")?;
     if hat.subscriptions.is_empty() {
@@ -706,7 +1147,26 @@ fn show_hat<W: Write>(
         }
     }
 
-    if !hat.instructions.is_empty() {
+    let instructions_file = registry
+        .get_config(&hat.id)
+        .and_then(|config| config.instructions_file.as_ref());
+    if let Some(path) = instructions_file {
+        // File-sourced instructions can be long; show where they come from
+        // and a short preview instead of the whole file
+        let lines: Vec<&str> = hat.instructions.lines().collect();
+        writeln!(
+            writer,
+            "\nInstructions: {} ({} lines)",
+            path.display(),
+            lines.len()
+        )?;
+        for line in lines.iter().take(INSTRUCTIONS_PREVIEW_LINES) {
+            writeln!(writer, "  {}", line)?;
+        }
+        if lines.len() > INSTRUCTIONS_PREVIEW_LINES {
+            writeln!(writer, "  ...")?;
+        }
+    } else if !hat.instructions.is_empty() {
         writeln!(writer, "\nInstructions:")?;
         for line in hat.instructions.lines() {
             writeln!(writer, "  {}", line)?;
@@ -716,6 +1176,9 @@ fn show_hat<W: Write>(
     Ok(())
 }
 
+/// Lines of file-sourced instructions shown by `hats show`.
+const INSTRUCTIONS_PREVIEW_LINES: usize = 5;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -837,7 +1300,7 @@ mod tests {
         registry.register(mock_hat("A", &["start"], &["mid"]));
         registry.register(mock_hat("B", &["mid"], &["end"]));
 
-        let output = generate_mermaid_string(&registry);
+        let output = generate_mermaid_string(&RalphConfig::default(), &registry);
 
         assert!(output.contains("flowchart LR"));
         assert!(output.contains("Ralph -->|start| A"));
@@ -847,6 +1310,111 @@ mod tests {
         assert!(output.contains("A -.->|mid| B"));
     }
 
+    fn preset_graph_inputs(name: &str) -> (RalphConfig, HatRegistry) {
+        let preset = presets::get_preset(name).unwrap();
+        let config = RalphConfig::parse_yaml(&preset.content).unwrap();
+        let registry = HatRegistry::from_config(&config);
+        (config, registry)
+    }
+
+    #[test]
+    fn test_mermaid_snapshot_bugfix_preset() {
+        let (config, registry) = preset_graph_inputs("bugfix");
+        insta::assert_snapshot!(generate_mermaid_string(&config, &registry));
+    }
+
+    #[test]
+    fn test_dot_snapshot_bugfix_preset() {
+        let (config, registry) = preset_graph_inputs("bugfix");
+        insta::assert_snapshot!(generate_dot_string(&config, &registry));
+    }
+
+    #[test]
+    fn test_graph_is_stable_across_runs() {
+        let (config, registry) = preset_graph_inputs("pdd-to-code-assist");
+        let first = generate_dot_string(&config, &registry);
+        for _ in 0..5 {
+            let registry = HatRegistry::from_config(&config);
+            assert_eq!(generate_dot_string(&config, &registry), first);
+        }
+    }
+
+    #[test]
+    fn test_graph_glob_triggers_and_completion() {
+        let mut registry = HatRegistry::new();
+        registry.register(mock_hat("Builder", &["build.task"], &["review.request"]));
+        registry.register(mock_hat("Reviewer", &["review.*"], &["LOOP_COMPLETE"]));
+        let config = RalphConfig::default();
+
+        let mermaid = generate_mermaid_string(&config, &registry);
+        assert!(
+            mermaid.contains("Ralph -->|review.*| Reviewer"),
+            "{mermaid}"
+        );
+        assert!(
+            mermaid.contains("Builder -.->|review.request| Reviewer"),
+            "{mermaid}"
+        );
+        assert!(
+            mermaid.contains("Reviewer -->|LOOP_COMPLETE| Complete"),
+            "{mermaid}"
+        );
+        assert!(!mermaid.contains("Reviewer -->|LOOP_COMPLETE| Ralph"));
+
+        let dot = generate_dot_string(&config, &registry);
+        assert!(dot.starts_with("digraph hats {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(
+            dot.contains(r#"Ralph -> Reviewer [label="review.*"];"#),
+            "{dot}"
+        );
+        assert!(
+            dot.contains(r#"Builder -> Reviewer [label="review.request", style=dashed];"#),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn test_graph_node_ids_do_not_collide() {
+        let mut registry = HatRegistry::new();
+        let mut ralph = mock_hat("Ralph", &["a"], &[]);
+        ralph.id = HatId::new("ralph_hat");
+        registry.register(ralph);
+        let mut quoted = mock_hat("Say \"hi\"", &["b"], &[]);
+        quoted.id = HatId::new("quoted");
+        registry.register(quoted);
+
+        let dot = generate_dot_string(&RalphConfig::default(), &registry);
+        assert!(dot.contains(r#"Ralph2 [label="Ralph"];"#), "{dot}");
+        assert!(dot.contains(r#"Ralph -> Ralph2 [label="a"];"#), "{dot}");
+        assert!(dot.contains(r#"Sayhi [label="Say \"hi\""];"#), "{dot}");
+
+        let mermaid = generate_mermaid_string(&RalphConfig::default(), &registry);
+        assert!(
+            mermaid.contains(r#"Sayhi["Say #quot;hi#quot;"]"#),
+            "{mermaid}"
+        );
+    }
+
+    #[test]
+    fn test_graph_output_writes_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("hats.dot");
+        let args = HatsArgs {
+            command: Some(HatsCommands::Graph {
+                format: GraphFormat::Dot,
+                backend: None,
+                output: Some(path.clone()),
+            }),
+        };
+
+        execute(&[ConfigSource::Builtin("bugfix".to_string())], args, false).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let (config, registry) = preset_graph_inputs("bugfix");
+        assert_eq!(written, generate_dot_string(&config, &registry));
+    }
+
     #[test]
     fn test_show_hat_found() {
         let mut registry = HatRegistry::new();
@@ -863,6 +1431,39 @@ mod tests {
         assert!(output.contains("build.done"));
     }
 
+    #[test]
+    fn test_show_hat_summarizes_instructions_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = (1..=8)
+            .map(|i| format!("Step {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(dir.path().join("builder.md"), body).unwrap();
+        std::fs::write(
+            dir.path().join("ralph.yml"),
+            r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    instructions_file: builder.md
+"#,
+        )
+        .unwrap();
+        let config = RalphConfig::from_file(dir.path().join("ralph.yml")).unwrap();
+        let registry = HatRegistry::from_config(&config);
+
+        let mut buf = Vec::new();
+        show_hat(&mut buf, &registry, "builder", false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(output.contains("builder.md (8 lines)"), "{output}");
+        assert!(output.contains("Step 5"), "{output}");
+        assert!(!output.contains("Step 6"), "{output}");
+        assert!(output.contains("  ..."), "{output}");
+    }
+
     #[test]
     fn test_show_hat_not_found() {
         let registry = HatRegistry::new();
@@ -967,6 +1568,177 @@ mod tests {
         assert!(output.contains("..."));
     }
 
+    #[test]
+    fn test_list_hats_shows_budgets() {
+        let config = RalphConfig::parse_yaml(
+            r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    max_iterations: 5
+  reviewer:
+    name: "Reviewer"
+    triggers: ["build.done"]
+    publishes: ["LOOP_COMPLETE"]
+"#,
+        )
+        .unwrap();
+        let registry = HatRegistry::from_config(&config);
+
+        let mut buf = Vec::new();
+        list_hats(&mut buf, &registry, false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        let line = |name: &str| output.lines().find(|l| l.starts_with(name)).unwrap();
+        assert!(output.lines().next().unwrap().contains(" BUDGET "));
+        assert!(line("Builder").contains(" 5 "));
+        assert!(line("Reviewer").ends_with(" -        -"));
+    }
+
+    #[test]
+    fn test_list_and_show_hats_show_tool_restrictions() {
+        let config = RalphConfig::parse_yaml(
+            r#"
+hats:
+  reviewer:
+    name: "Reviewer"
+    triggers: ["build.done"]
+    publishes: ["LOOP_COMPLETE"]
+    allowed_tools: ["Read", "Grep"]
+    disallowed_tools: ["Edit", "Write"]
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+"#,
+        )
+        .unwrap();
+        let registry = HatRegistry::from_config(&config);
+
+        let mut buf = Vec::new();
+        list_hats(&mut buf, &registry, false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let line = |name: &str| output.lines().find(|l| l.starts_with(name)).unwrap();
+        assert!(output.lines().next().unwrap().ends_with("TOOLS"));
+        assert!(line("Reviewer").ends_with("allow: Read,Grep; deny: Edit,Write"));
+        assert!(line("Builder").ends_with(" -"));
+
+        let mut buf = Vec::new();
+        show_hat(&mut buf, &registry, "reviewer", false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Tools: allow: Read,Grep; deny: Edit,Write"));
+    }
+
+    const LINEAR_TWO_HATS: &str = r#"
+event_loop:
+  starting_event: "plan.start"
+hats:
+  planner:
+    name: "Planner"
+    triggers: ["plan.start"]
+    publishes: ["build.task"]
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["LOOP_COMPLETE"]
+"#;
+
+    #[test]
+    fn test_plan_event_flow_follows_linear_hats_to_completion() {
+        let config = RalphConfig::parse_yaml(LINEAR_TWO_HATS).unwrap();
+        let plan = plan_event_flow(&config);
+
+        assert_eq!(plan.start, "plan.start");
+        let route: Vec<_> = plan
+            .steps
+            .iter()
+            .map(|step| (step.topic.as_str(), step.hat.as_str()))
+            .collect();
+        assert_eq!(
+            route,
+            vec![("plan.start", "planner"), ("build.task", "builder")]
+        );
+        assert_eq!(plan.end, PlanEnd::Completion("LOOP_COMPLETE".to_string()));
+    }
+
+    #[test]
+    fn test_print_event_plan_lists_steps_in_order() {
+        let config = RalphConfig::parse_yaml(LINEAR_TWO_HATS).unwrap();
+        let mut out = Vec::new();
+        print_event_plan(&mut out, &config).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let lines: Vec<_> = out.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "  Start: plan.start",
+                "  1. plan.start -> Planner (planner)",
+                "     publishes: build.task",
+                "  2. build.task -> Builder (builder)",
+                "     publishes: LOOP_COMPLETE",
+                "  Completion: LOOP_COMPLETE after 2 hat activation(s)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_event_flow_hands_unrouted_events_to_ralph() {
+        let yaml = r#"
+event_loop:
+  starting_event: "plan.start"
+hats:
+  planner:
+    name: "Planner"
+    triggers: ["plan.start"]
+    publishes: ["build.task"]
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        let plan = plan_event_flow(&config);
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.end, PlanEnd::Ralph("build.task".to_string()));
+    }
+
+    #[test]
+    fn test_hat_budget_warning_when_budgets_cannot_reach_completion() {
+        let yaml = r#"
+event_loop:
+  starting_event: "plan.start"
+hats:
+  planner:
+    name: "Planner"
+    triggers: ["plan.start"]
+    publishes: ["build.task"]
+    max_iterations: 1
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["review.ready"]
+    max_iterations: 0
+  reviewer:
+    name: "Reviewer"
+    triggers: ["review.ready"]
+    publishes: ["LOOP_COMPLETE", "build.task"]
+    max_iterations: 1
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        assert_eq!(shortest_completion_path(&config), Some(3));
+        let warning = hat_budget_warning(&config).unwrap();
+        assert!(warning.contains("total 2 iterations"));
+        assert!(warning.contains("needs 3"));
+
+        let config =
+            RalphConfig::parse_yaml(&yaml.replace("max_iterations: 0", "max_iterations: 4"))
+                .unwrap();
+        assert_eq!(hat_budget_warning(&config), None);
+
+        // An unbudgeted hat can make up the difference
+        let config = RalphConfig::parse_yaml(&yaml.replace("    max_iterations: 0\n", "")).unwrap();
+        assert_eq!(hat_budget_warning(&config), None);
+    }
+
     #[test]
     fn test_load_config_missing_explicit_file_errors() {
         // When user explicitly specifies a non-existent config file, it should error
@@ -0,0 +1,120 @@
+//! CLI commands for the `ralph tools ignore` namespace.
+//!
+//! Provides subcommands for `.ralphignore`:
+//! - `check`: Report whether paths are ignored, and by which rule
+
+use crate::display::colors;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use ralph_core::{IgnoreRule, RALPHIGNORE_FILE, RalphIgnore};
+use std::path::{Path, PathBuf};
+
+/// `.ralphignore` commands.
+#[derive(Parser, Debug)]
+pub struct IgnoreArgs {
+    #[command(subcommand)]
+    pub command: IgnoreCommands,
+
+    /// Working directory (default: current directory)
+    #[arg(long, global = true)]
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IgnoreCommands {
+    /// Report whether paths are ignored, and by which rule
+    Check(CheckArgs),
+}
+
+/// Arguments for the `ignore check` command.
+#[derive(Parser, Debug)]
+pub struct CheckArgs {
+    /// Paths to check, relative to the workspace root
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Executes ignore CLI commands.
+pub fn execute(args: IgnoreArgs, use_colors: bool) -> Result<()> {
+    let root = args.root.unwrap_or_else(|| PathBuf::from("."));
+    let ignore = RalphIgnore::load(&root);
+
+    match args.command {
+        IgnoreCommands::Check(check_args) => {
+            if ignore.is_empty() {
+                println!("No rules in {}", root.join(RALPHIGNORE_FILE).display());
+            }
+            for path in &check_args.paths {
+                println!(
+                    "{}",
+                    describe(path, ignore.rule_for(path).as_ref(), use_colors)
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// One line of `check` output for `path`.
+fn describe(path: &Path, rule: Option<&IgnoreRule>, use_colors: bool) -> String {
+    let (status, color) = match rule {
+        Some(rule) if !rule.negated => ("ignored", colors::YELLOW),
+        _ => ("not ignored", colors::GREEN),
+    };
+    let status = if use_colors {
+        format!("{color}{status}{}", colors::RESET)
+    } else {
+        status.to_string()
+    };
+
+    let Some(rule) = rule else {
+        return format!("{}: {status}", path.display());
+    };
+    let location = rule.line.map_or_else(
+        || RALPHIGNORE_FILE.to_string(),
+        |line| format!("{RALPHIGNORE_FILE}:{line}"),
+    );
+    let verb = if rule.negated {
+        "re-included by"
+    } else {
+        "matched by"
+    };
+    format!(
+        "{}: {status} ({verb} `{}` at {location})",
+        path.display(),
+        rule.pattern
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_reports_rule() {
+        let rule = IgnoreRule {
+            pattern: "*.pem".to_string(),
+            line: Some(2),
+            negated: false,
+        };
+        assert_eq!(
+            describe(Path::new("certs/dev.pem"), Some(&rule), false),
+            "certs/dev.pem: ignored (matched by `*.pem` at .ralphignore:2)"
+        );
+
+        let rule = IgnoreRule {
+            pattern: "!README.md".to_string(),
+            line: Some(4),
+            negated: true,
+        };
+        assert_eq!(
+            describe(Path::new("README.md"), Some(&rule), false),
+            "README.md: not ignored (re-included by `!README.md` at .ralphignore:4)"
+        );
+
+        assert_eq!(
+            describe(Path::new("src/main.rs"), None, false),
+            "src/main.rs: not ignored"
+        );
+    }
+}
@@ -1,11 +1,14 @@
 //! Init command implementation for ralph.
 //!
 //! Handles initialization of ralph.yml configuration files, either from
-//! a minimal backend template or from an embedded preset.
+//! a minimal backend template, from an embedded preset, or from an existing
+//! config with its machine-specific values stripped.
 
-use crate::presets::{get_preset, list_presets, preset_names};
+use crate::presets::{list_presets, preset_content, preset_names, user_presets_dir};
+use ralph_core::RalphConfig;
+use serde_yaml::Value;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Errors that can occur during initialization.
 #[derive(Debug, thiserror::Error)]
@@ -21,10 +24,30 @@ pub enum InitError {
     UnknownPreset(String, String),
 
     #[error(
-        "Unknown backend '{0}'. Valid backends: claude, kiro, gemini, codex, amp, copilot, opencode, pi, custom.\nSee: docs/reference/troubleshooting.md#unknown-backend"
+        "Unknown backend '{0}'. Valid backends: claude, kiro, gemini, codex, amp, copilot, opencode, pi, custom, or the name of an executable on PATH.\nSee: docs/reference/troubleshooting.md#unknown-backend"
     )]
     UnknownBackend(String),
 
+    #[error("{0} already exists. Use --force to overwrite.")]
+    OutputExists(PathBuf),
+
+    #[error("Failed to read {path}: {source}")]
+    ReadSource {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("{path} is not a valid config: {message}")]
+    InvalidSource { path: PathBuf, message: String },
+
+    #[error(
+        "Invalid preset name '{0}'. Use letters, digits, '-' and '_' (e.g. --as-preset team-feature)."
+    )]
+    InvalidPresetName(String),
+
+    #[error("Cannot locate the user presets directory. Set RALPH_PRESETS_DIR or HOME.")]
+    NoPresetsDir,
+
     #[error("Failed to write ralph.yml: {0}")]
     WriteError(#[from] std::io::Error),
 
@@ -78,6 +101,34 @@ event_loop:
     )
 }
 
+/// Generates a config template that declares `backend` in `adapters.custom_backends`.
+fn generate_custom_backend_template(backend: &str) -> String {
+    let adapters = format!(
+        r#"adapters:
+  custom_backends:
+    {backend}:
+      command: "{backend}"
+      prompt_mode: "arg"          # arg | flag | stdin
+      # prompt_flag: "--message"  # required when prompt_mode is "flag"
+      args: []
+      output_format: "text"       # text | stream-json | pi-stream-json
+      probe: ["{backend}", "--version"]
+
+event_loop:"#
+    );
+    generate_template(backend).replacen("event_loop:", &adapters, 1)
+}
+
+/// Returns true if `backend` can be used as a custom backend name: a plain
+/// executable name found on PATH.
+fn is_custom_backend_candidate(backend: &str) -> bool {
+    !backend.is_empty()
+        && backend
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && crate::doctor::command_exists(backend)
+}
+
 /// Checks if ralph.yml exists and handles the force flag.
 fn check_file_exists(force: bool) -> Result<(), InitError> {
     let path = Path::new("ralph.yml");
@@ -90,20 +141,24 @@ fn check_file_exists(force: bool) -> Result<(), InitError> {
 /// Initializes ralph.yml from a minimal backend template.
 ///
 /// # Arguments
-/// * `backend` - The backend name (claude, kiro, gemini, codex, amp, copilot, opencode, custom)
+/// * `backend` - The backend name (claude, kiro, gemini, codex, amp, copilot, opencode, custom),
+///   or an executable on PATH to declare under `adapters.custom_backends`
 /// * `force` - If true, overwrite existing ralph.yml
 ///
 /// # Errors
 /// Returns error if file exists (without force) or backend is invalid.
 pub fn init_from_backend(backend: &str, force: bool) -> Result<(), InitError> {
     // Validate backend
-    if !VALID_BACKENDS.contains(&backend) {
+    let content = if VALID_BACKENDS.contains(&backend) {
+        generate_template(backend)
+    } else if is_custom_backend_candidate(backend) {
+        generate_custom_backend_template(backend)
+    } else {
         return Err(InitError::UnknownBackend(backend.to_string()));
-    }
+    };
 
     check_file_exists(force)?;
 
-    let content = generate_template(backend);
     fs::write("ralph.yml", content)?;
 
     Ok(())
@@ -123,7 +178,7 @@ pub fn init_from_preset(
     backend_override: Option<&str>,
     force: bool,
 ) -> Result<(), InitError> {
-    let preset = get_preset(preset_name).ok_or_else(|| {
+    let preset = preset_content(preset_name).ok_or_else(|| {
         let available = preset_names().join(", ");
         InitError::UnknownPreset(preset_name.to_string(), available)
     })?;
@@ -138,9 +193,9 @@ pub fn init_from_preset(
     check_file_exists(force)?;
 
     let content = if let Some(backend) = backend_override {
-        override_backend_in_yaml(preset.content, backend)?
+        override_backend_in_yaml(&preset, backend)?
     } else {
-        preset.content.to_string()
+        preset.into_owned()
     };
 
     fs::write("ralph.yml", content)?;
@@ -148,6 +203,252 @@ pub fn init_from_preset(
     Ok(())
 }
 
+/// A field `ralph init --from` changed while generalizing a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneralizedField {
+    /// Dotted path of the field, e.g. `core.scratchpad`.
+    pub path: String,
+    /// What was done to it.
+    pub change: String,
+}
+
+/// Path fields reset to their defaults when absolute: an absolute path names
+/// a location on this machine.
+const MACHINE_PATH_FIELDS: &[&[&str]] = &[
+    &["core", "scratchpad"],
+    &["core", "specs_dir"],
+    &["core", "workspace_root"],
+    &["event_loop", "prompt_file"],
+    &["event_loop", "summary_out"],
+    &["features", "worktree_dir"],
+    &["prompt_file"],
+    &["cli", "command"],
+];
+
+/// Environment variable names containing one of these hold secrets.
+const SECRET_NAME_MARKERS: &[&str] = &["TOKEN", "KEY", "SECRET", "PASSWORD"];
+
+/// Initializes a config from an existing one, dropping machine-specific
+/// values (see [`generalize_config`]).
+///
+/// Writes to `output`, creating its directory. Returns the fields that
+/// were changed.
+///
+/// # Errors
+/// Returns error if `output` exists (without force), the source cannot be
+/// read or is not a valid config, or the result does not round-trip.
+pub fn init_from_config(
+    source: &Path,
+    output: &Path,
+    generalize_backend: bool,
+    force: bool,
+) -> Result<Vec<GeneralizedField>, InitError> {
+    let yaml = fs::read_to_string(source).map_err(|e| InitError::ReadSource {
+        path: source.to_path_buf(),
+        source: e,
+    })?;
+    let (body, changes) = generalize_config(&yaml, generalize_backend).map_err(|e| match e {
+        InitError::YamlError(message) => InitError::InvalidSource {
+            path: source.to_path_buf(),
+            message,
+        },
+        other => other,
+    })?;
+
+    if output.exists() && !force {
+        return Err(InitError::OutputExists(output.to_path_buf()));
+    }
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, render_generalized(source, &body, &changes))?;
+
+    Ok(changes)
+}
+
+/// Returns where `--as-preset <name>` saves a preset.
+///
+/// # Errors
+/// Returns error if the name is unusable or no presets directory is known.
+pub fn user_preset_path(name: &str) -> Result<PathBuf, InitError> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(InitError::InvalidPresetName(name.to_string()));
+    }
+    let dir = user_presets_dir().ok_or(InitError::NoPresetsDir)?;
+    Ok(dir.join(format!("{name}.yml")))
+}
+
+/// Strips machine-specific values from a config so it can seed other repos.
+///
+/// Absolute paths go back to their defaults, the Telegram and web tokens
+/// are removed, and literal secrets in `env` maps become `${NAME}`
+/// references. With `generalize_backend`, `cli.backend` becomes `auto`.
+/// Returns the YAML and the changed fields; the YAML is checked to parse
+/// as a config.
+fn generalize_config(
+    yaml: &str,
+    generalize_backend: bool,
+) -> Result<(String, Vec<GeneralizedField>), InitError> {
+    RalphConfig::parse_yaml(yaml).map_err(|e| InitError::YamlError(e.to_string()))?;
+    let mut value: Value =
+        serde_yaml::from_str(yaml).map_err(|e| InitError::YamlError(e.to_string()))?;
+    let mut changes = Vec::new();
+    let mut record = |path: String, change: &str| {
+        changes.push(GeneralizedField {
+            path,
+            change: change.to_string(),
+        });
+    };
+
+    for path in MACHINE_PATH_FIELDS {
+        let absolute = lookup(&value, path)
+            .and_then(Value::as_str)
+            .is_some_and(|p| p.starts_with('~') || Path::new(p).is_absolute());
+        if absolute {
+            remove(&mut value, path);
+            record(path.join("."), "absolute path removed (default applies)");
+        }
+    }
+
+    if remove(&mut value, &["RObot", "telegram", "bot_token"]).is_some() {
+        record(
+            "RObot.telegram.bot_token".to_string(),
+            "secret removed (set RALPH_TELEGRAM_BOT_TOKEN)",
+        );
+    }
+    for (field, env_var) in [
+        ("bot_token", "RALPH_SLACK_BOT_TOKEN"),
+        ("signing_secret", "RALPH_SLACK_SIGNING_SECRET"),
+    ] {
+        if remove(&mut value, &["RObot", "slack", field]).is_some() {
+            record(
+                format!("RObot.slack.{field}"),
+                &format!("secret removed (set {env_var})"),
+            );
+        }
+    }
+    let web_token_is_literal = lookup(&value, &["web", "token"])
+        .and_then(Value::as_str)
+        .is_some_and(is_literal_value);
+    if web_token_is_literal {
+        remove(&mut value, &["web", "token"]);
+        record(
+            "web.token".to_string(),
+            "secret removed (a token is generated at startup)",
+        );
+    }
+
+    let hat_ids: Vec<String> = lookup(&value, &["hats"])
+        .and_then(Value::as_mapping)
+        .map(|hats| {
+            hats.keys()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let env_paths = std::iter::once(vec!["cli", "env"])
+        .chain(hat_ids.iter().map(|id| vec!["hats", id.as_str(), "env"]));
+    for path in env_paths {
+        let label = path.join(".");
+        let Some(env) = lookup_mut(&mut value, &path).and_then(Value::as_mapping_mut) else {
+            continue;
+        };
+        for (name, env_value) in env.iter_mut() {
+            let Some(name) = name.as_str() else {
+                continue;
+            };
+            let secret = SECRET_NAME_MARKERS
+                .iter()
+                .any(|marker| name.to_ascii_uppercase().contains(marker));
+            if secret && env_value.as_str().is_some_and(is_literal_value) {
+                *env_value = Value::String(format!("${{{name}}}"));
+                record(
+                    format!("{label}.{name}"),
+                    &format!("secret replaced with ${{{name}}}"),
+                );
+            }
+        }
+    }
+
+    if generalize_backend
+        && lookup(&value, &["cli", "backend"]).and_then(Value::as_str) != Some("auto")
+    {
+        if let Some(cli) = value.get_mut("cli").and_then(Value::as_mapping_mut) {
+            cli.insert("backend".into(), "auto".into());
+        } else if let Some(root) = value.as_mapping_mut() {
+            let mut cli = serde_yaml::Mapping::new();
+            cli.insert("backend".into(), "auto".into());
+            root.insert("cli".into(), Value::Mapping(cli));
+        }
+        record(
+            "cli.backend".to_string(),
+            "set to auto (detected at run time)",
+        );
+    }
+
+    let body = serde_yaml::to_string(&value).map_err(|e| InitError::YamlError(e.to_string()))?;
+    RalphConfig::parse_yaml(&body).map_err(|e| {
+        InitError::YamlError(format!("generalized config does not round-trip: {e}"))
+    })?;
+    Ok((body, changes))
+}
+
+/// True for a value written out in the config rather than referenced from
+/// the environment or keychain.
+fn is_literal_value(value: &str) -> bool {
+    !value.contains("${") && !value.starts_with("keychain:")
+}
+
+fn lookup<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, path: &[&str]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, key| value.get_mut(key))
+}
+
+/// Removes the value at `path`, along with any sections left empty.
+fn remove(value: &mut Value, path: &[&str]) -> Option<Value> {
+    let (first, rest) = path.split_first()?;
+    let map = value.as_mapping_mut()?;
+    if rest.is_empty() {
+        return map.remove(*first);
+    }
+    let child = map.get_mut(*first)?;
+    let removed = remove(child, rest)?;
+    if child
+        .as_mapping()
+        .is_some_and(serde_yaml::Mapping::is_empty)
+    {
+        map.remove(*first);
+    }
+    Some(removed)
+}
+
+/// Prefixes the generalized YAML with a header naming the changed fields.
+fn render_generalized(source: &Path, body: &str, changes: &[GeneralizedField]) -> String {
+    let mut content = format!(
+        "# Ralph Orchestrator Configuration\n# Generated by: ralph init --from {}\n",
+        source.display()
+    );
+    if changes.is_empty() {
+        content.push_str("# No machine-specific fields found.\n");
+    } else {
+        content.push_str("#\n# Generalized fields:\n");
+        for field in changes {
+            content.push_str(&format!("#   {}: {}\n", field.path, field.change));
+        }
+    }
+    content.push('\n');
+    content.push_str(body);
+    content
+}
+
 /// Overrides the backend field in YAML content using regex for surgical replacement.
 /// Preserves all comments and formatting.
 fn override_backend_in_yaml(content: &str, backend: &str) -> Result<String, InitError> {
@@ -192,11 +493,26 @@ fn override_backend_in_yaml(content: &str, backend: &str) -> Result<String, Init
 }
 
 /// Formats the list of presets for display.
-pub fn format_preset_list() -> String {
+///
+/// In verbose mode, each preset's hats are listed with their iteration budgets.
+pub fn format_preset_list(verbose: bool) -> String {
     let mut output = String::from("Available presets:\n\n");
 
     for preset in list_presets() {
-        output.push_str(&format!("  {:<25} {}\n", preset.name, preset.description));
+        output.push_str(&format!(
+            "  {:<25} {:<10} {}\n",
+            preset.name, preset.source, preset.description
+        ));
+        if verbose && let Ok(config) = RalphConfig::parse_yaml(&preset.content) {
+            let mut hats: Vec<_> = config.hats.iter().collect();
+            hats.sort_by_key(|(id, _)| id.as_str());
+            for (id, hat) in hats {
+                let budget = hat
+                    .max_iterations
+                    .map_or_else(|| "-".to_string(), |max| max.to_string());
+                output.push_str(&format!("    {:<23} budget: {}\n", id, budget));
+            }
+        }
     }
 
     output.push_str("\nUsage: ralph init --preset <preset-name>\n");
@@ -237,14 +553,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_backend_template_is_valid_config() {
+        let template = generate_custom_backend_template("aider");
+        let config = RalphConfig::parse_yaml(&template).expect("valid config");
+        assert_eq!(config.cli.backend, "aider");
+        let custom = &config.adapters.custom_backends["aider"];
+        assert_eq!(custom.command, "aider");
+        assert_eq!(custom.probe_command(), vec!["aider", "--version"]);
+        assert_eq!(config.event_loop.max_iterations, 100);
+        config.validate().expect("template validates");
+    }
+
     #[test]
     fn test_format_preset_list() {
-        let output = format_preset_list();
+        let output = format_preset_list(false);
         assert!(output.contains("Available presets:"));
         assert!(output.contains("feature"));
         assert!(output.contains("code-assist"));
         assert!(output.contains("debug"));
         assert!(output.contains("Usage:"));
+        assert!(!output.contains("budget:"));
+    }
+
+    #[test]
+    fn test_format_preset_list_verbose_shows_hat_budgets() {
+        let output = format_preset_list(true);
+        assert!(output.contains("budget: -"));
     }
 
     #[test]
@@ -332,4 +667,113 @@ cli:
         assert!(result.contains("# This is a preset"));
         assert!(result.contains("# With helpful comments"));
     }
+
+    #[test]
+    fn test_generalize_config_strips_machine_specific_fields() {
+        let yaml = r#"cli:
+  backend: "claude"
+  command: "/opt/bin/claude"
+  env:
+    OPENAI_BASE_URL: "http://localhost:8080"
+    OPENAI_API_KEY: "sk-live-123"
+    NPM_TOKEN: "keychain:npm-token"
+core:
+  scratchpad: "/home/me/work/.ralph/agent/scratchpad.md"
+  specs_dir: "specs/"
+event_loop:
+  max_iterations: 40
+web:
+  token: "hunter2"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    env:
+      GH_TOKEN: "ghp_abc"
+"#;
+
+        let (body, changes) = generalize_config(yaml, true).unwrap();
+
+        let paths: Vec<_> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "core.scratchpad",
+                "cli.command",
+                "web.token",
+                "cli.env.OPENAI_API_KEY",
+                "hats.builder.env.GH_TOKEN",
+                "cli.backend",
+            ]
+        );
+        assert!(!body.contains("sk-live-123") && !body.contains("ghp_abc"));
+        assert!(!body.contains("hunter2") && !body.contains("/home/me"));
+
+        let config = RalphConfig::parse_yaml(&body).unwrap();
+        assert_eq!(config.cli.backend, "auto");
+        assert_eq!(config.cli.command, None);
+        assert_eq!(config.cli.env["OPENAI_API_KEY"], "${OPENAI_API_KEY}");
+        assert_eq!(config.cli.env["OPENAI_BASE_URL"], "http://localhost:8080");
+        assert_eq!(config.cli.env["NPM_TOKEN"], "keychain:npm-token");
+        assert_eq!(
+            config.core.scratchpad,
+            RalphConfig::default().core.scratchpad
+        );
+        assert_eq!(config.core.specs_dir, "specs/");
+        assert_eq!(config.event_loop.max_iterations, 40);
+        assert_eq!(config.hats["builder"].env["GH_TOKEN"], "${GH_TOKEN}");
+    }
+
+    #[test]
+    fn test_generalize_config_keeps_backend_without_flag() {
+        let (body, changes) = generalize_config("cli:\n  backend: kiro\n", false).unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(RalphConfig::parse_yaml(&body).unwrap().cli.backend, "kiro");
+    }
+
+    #[test]
+    fn test_init_from_config_writes_commented_output() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let source = temp_dir.path().join("ralph.yml");
+        fs::write(
+            &source,
+            "cli:\n  backend: claude\nRObot:\n  enabled: false\n  telegram:\n    bot_token: \"123:abc\"\n",
+        )
+        .unwrap();
+        let output = temp_dir.path().join("out/new.yml");
+
+        let changes = init_from_config(&source, &output, false, false).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("# Generated by: ralph init --from"));
+        assert!(content.contains("#   RObot.telegram.bot_token: secret removed"));
+        assert!(!content.contains("telegram:"), "emptied section is dropped");
+        assert!(!content.contains("123:abc"));
+        RalphConfig::parse_yaml(&content).expect("output is a valid config");
+
+        let err = init_from_config(&source, &output, false, false).unwrap_err();
+        assert!(matches!(err, InitError::OutputExists(_)), "{err}");
+        init_from_config(&source, &output, false, true).expect("--force overwrites");
+    }
+
+    #[test]
+    fn test_init_from_config_rejects_invalid_source() {
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let source = temp_dir.path().join("ralph.yml");
+        fs::write(&source, "event_loop: [not, a, map]\n").unwrap();
+
+        let err =
+            init_from_config(&source, &temp_dir.path().join("out.yml"), false, false).unwrap_err();
+        assert!(matches!(err, InitError::InvalidSource { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_user_preset_path_validates_name() {
+        assert!(matches!(
+            user_preset_path("../escape"),
+            Err(InitError::InvalidPresetName(_))
+        ));
+    }
 }
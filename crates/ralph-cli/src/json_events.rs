@@ -0,0 +1,114 @@
+//! Event stream for `ralph run --json-events-on-stdout`.
+//!
+//! Every event published during the run is written to stdout as one JSON
+//! line in the events file's record format, so a CI step can consume the
+//! loop with `ralph run ... | while read -r line; do ...; done`.
+
+use ralph_core::EventRecord;
+use ralph_proto::Event;
+use std::io::Write;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Writes bus events as JSON lines.
+#[derive(Clone)]
+pub struct JsonEventStream {
+    /// Iteration stamped on records; 0 until the first iteration starts.
+    iteration: Arc<AtomicU32>,
+    out: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl JsonEventStream {
+    /// Stream to stdout.
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+
+    /// Stream to `out`.
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            iteration: Arc::new(AtomicU32::new(0)),
+            out: Arc::new(Mutex::new(Box::new(out))),
+        }
+    }
+
+    /// Sets the iteration stamped on subsequent records.
+    pub fn set_iteration(&self, iteration: u32) {
+        self.iteration.store(iteration, Ordering::Relaxed);
+    }
+
+    /// Returns a bus observer that writes each event it sees.
+    pub fn observer(&self) -> impl Fn(&Event) + Send + 'static {
+        let stream = self.clone();
+        move |event| stream.write(event)
+    }
+
+    fn write(&self, event: &Event) {
+        let hat = event.source.as_ref().map_or("loop", |hat| hat.as_str());
+        let record = EventRecord::new(
+            self.iteration.load(Ordering::Relaxed),
+            hat,
+            event,
+            event.target.as_ref(),
+        );
+        let Ok(line) = serde_json::to_string(&record) else {
+            return;
+        };
+        let Ok(mut out) = self.out.lock() else {
+            return;
+        };
+        // Flush per line so a reader sees events as they happen; a write
+        // error only means nobody is reading any more
+        let _ = writeln!(out, "{line}").and_then(|()| out.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralph_core::EventLoop;
+    use ralph_core::RalphConfig;
+
+    /// `Write` handle onto a shared buffer, standing in for stdout.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_each_stdout_line_is_an_event_record() {
+        let stdout = SharedBuffer::default();
+        let stream = JsonEventStream::new(stdout.clone());
+        let mut event_loop = EventLoop::new(RalphConfig::default());
+        event_loop.add_observer(stream.observer());
+
+        event_loop.initialize("Build the parser");
+        stream.set_iteration(1);
+        let mut event = Event::new("build.done", "line one\nline two");
+        event.source = Some("builder".into());
+        event_loop.bus().publish(event);
+
+        let output = String::from_utf8(stdout.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<EventRecord> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is an event record"))
+            .collect();
+
+        assert_eq!(records.len(), 2, "{output}");
+        assert_eq!(records[0].topic, "task.start");
+        assert_eq!(records[0].iteration, 0);
+        assert_eq!(records[0].hat, "loop");
+        assert_eq!(records[1].topic, "build.done");
+        assert_eq!(records[1].iteration, 1);
+        assert_eq!(records[1].hat, "builder");
+        assert_eq!(records[1].payload, "line one\nline two");
+    }
+}
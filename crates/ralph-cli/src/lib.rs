@@ -1,6 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use ralph_core::loop_lock::LoopLock;
+use ralph_core::loop_registry::LoopRegistry;
+use ralph_core::run_history::RunHistory;
+use ralph_core::worktree::{WorktreeError, delete_branch, list_ralph_worktrees, remove_worktree};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 mod colors {
     pub const DIM: &str = "\x1b[2m";
@@ -79,6 +84,276 @@ pub fn clean_diagnostics(workspace_root: &Path, use_colors: bool, dry_run: bool)
     Ok(())
 }
 
+/// Options for `ralph clean --all-loops`.
+#[derive(Debug, Clone)]
+pub struct DeepCleanOptions {
+    /// List what would be removed without removing anything.
+    pub dry_run: bool,
+
+    /// Clean even if a loop is still running.
+    pub force: bool,
+
+    /// Event files modified more recently than this are kept.
+    pub max_event_age: Duration,
+
+    /// Number of run records to keep (`core.runs_keep`; 0 = keep all).
+    pub runs_keep: usize,
+}
+
+/// What `ralph clean --all-loops` removed (or would remove, in a dry run).
+#[derive(Debug, Default)]
+pub struct DeepCleanSummary {
+    /// Loop worktrees.
+    pub worktrees: Vec<PathBuf>,
+
+    /// IDs of registry entries whose process has exited.
+    pub registry_entries: Vec<String>,
+
+    /// Lock files no process holds.
+    pub stale_locks: Vec<PathBuf>,
+
+    /// Event files older than the age limit.
+    pub event_files: Vec<PathBuf>,
+
+    /// IDs of run records beyond the retention limit.
+    pub run_records: Vec<String>,
+
+    /// The diagnostics directory, if present.
+    pub diagnostics: Option<PathBuf>,
+}
+
+/// Removes everything left behind by finished loops: worktrees, stale
+/// registry entries, stale locks, old event files, run records beyond the
+/// retention limit and diagnostics.
+///
+/// Refuses to run while any loop is alive unless `options.force` is set.
+pub fn clean_all_loops(
+    workspace_root: &Path,
+    options: &DeepCleanOptions,
+    use_colors: bool,
+) -> Result<DeepCleanSummary> {
+    let registry = LoopRegistry::new(workspace_root);
+    let running = running_loops(workspace_root, &registry)?;
+    if !running.is_empty() && !options.force {
+        bail!(
+            "Refusing to clean: {} loop(s) still running ({}). Stop them first or pass --force.",
+            running.len(),
+            running.join(", ")
+        );
+    }
+
+    let mut summary = DeepCleanSummary::default();
+    let ralph_dir = workspace_root.join(".ralph");
+
+    for worktree in list_ralph_worktrees(workspace_root).unwrap_or_default() {
+        if options.dry_run {
+            summary.worktrees.push(worktree.path);
+            continue;
+        }
+        match remove_worktree(workspace_root, &worktree.path) {
+            Ok(()) => summary.worktrees.push(worktree.path),
+            Err(e) => {
+                eprintln!(
+                    "Warning: failed to remove worktree {}: {}",
+                    worktree.path.display(),
+                    e
+                );
+                continue;
+            }
+        }
+        match delete_branch(workspace_root, &worktree.branch, false) {
+            Ok(_) => {}
+            Err(WorktreeError::UnmergedBranch(branch)) => eprintln!(
+                "Kept branch {}: it has unmerged commits (delete with `ralph loops prune --force` once the loop is merged or discarded)",
+                branch
+            ),
+            Err(e) => eprintln!(
+                "Warning: failed to delete branch {}: {}",
+                worktree.branch, e
+            ),
+        }
+    }
+
+    if registry_exists(workspace_root) {
+        summary.registry_entries = registry
+            .stale_entries()
+            .context("Failed to read loop registry")?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        if !options.dry_run {
+            registry
+                .clean_stale()
+                .context("Failed to clean loop registry")?;
+        }
+    }
+
+    let loop_lock = workspace_root.join(LoopLock::LOCK_FILE);
+    if loop_lock.exists() && !LoopLock::is_locked(workspace_root).unwrap_or(true) {
+        if !options.dry_run {
+            remove_file(&loop_lock)?;
+        }
+        summary.stale_locks.push(loop_lock);
+    }
+
+    for path in old_event_files(&ralph_dir, options.max_event_age)? {
+        if !options.dry_run {
+            remove_file(&path)?;
+            let sidecar = path.with_extension("jsonl.lock");
+            if sidecar.exists() {
+                remove_file(&sidecar)?;
+            }
+        }
+        summary.event_files.push(path);
+    }
+
+    let run_history = RunHistory::new(workspace_root);
+    summary.run_records = if options.dry_run {
+        run_history
+            .expired(options.runs_keep)
+            .context("Failed to read run records")?
+            .into_iter()
+            .map(|record| record.id)
+            .collect()
+    } else {
+        run_history
+            .prune(options.runs_keep)
+            .context("Failed to prune run records")?
+    };
+
+    let diagnostics_dir = ralph_dir.join("diagnostics");
+    if diagnostics_dir.exists() {
+        if !options.dry_run {
+            fs::remove_dir_all(&diagnostics_dir).with_context(|| {
+                format!(
+                    "Failed to delete directory '{}'. Check permissions and try again.",
+                    diagnostics_dir.display()
+                )
+            })?;
+        }
+        summary.diagnostics = Some(diagnostics_dir);
+    }
+
+    print_deep_clean_summary(&summary, options.dry_run, use_colors);
+    Ok(summary)
+}
+
+/// Describes loops that are still running: live registry entries plus the
+/// primary loop if it holds the loop lock.
+fn running_loops(workspace_root: &Path, registry: &LoopRegistry) -> Result<Vec<String>> {
+    let mut running: Vec<String> = if registry_exists(workspace_root) {
+        registry
+            .list()
+            .context("Failed to read loop registry")?
+            .into_iter()
+            .map(|entry| format!("{} (PID {})", entry.id, entry.pid))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if let Ok(true) = LoopLock::is_locked(workspace_root)
+        && let Ok(Some(metadata)) = LoopLock::read_existing(workspace_root)
+        && !running
+            .iter()
+            .any(|desc| desc.ends_with(&format!("(PID {})", metadata.pid)))
+    {
+        running.push(format!("primary (PID {})", metadata.pid));
+    }
+
+    Ok(running)
+}
+
+fn registry_exists(workspace_root: &Path) -> bool {
+    workspace_root.join(LoopRegistry::REGISTRY_FILE).exists()
+}
+
+/// Rotated `events-*.jsonl` files older than `max_age`, skipping the one the
+/// current-events marker points at.
+fn old_event_files(ralph_dir: &Path, max_age: Duration) -> Result<Vec<PathBuf>> {
+    let Ok(entries) = fs::read_dir(ralph_dir) else {
+        return Ok(Vec::new());
+    };
+    let current = fs::read_to_string(ralph_dir.join("current-events"))
+        .ok()
+        .and_then(|marker| {
+            Path::new(marker.trim())
+                .file_name()
+                .map(|name| name.to_os_string())
+        });
+    let now = SystemTime::now();
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        if !name_str.starts_with("events-")
+            || !name_str.ends_with(".jsonl")
+            || current.as_ref() == Some(&name)
+        {
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        if now.duration_since(modified).unwrap_or_default() >= max_age {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    fs::remove_file(path).with_context(|| format!("Failed to delete '{}'", path.display()))
+}
+
+fn print_deep_clean_summary(summary: &DeepCleanSummary, dry_run: bool, use_colors: bool) {
+    if dry_run {
+        if use_colors {
+            println!(
+                "{}Dry run mode:{} Would remove:",
+                colors::CYAN,
+                colors::RESET
+            );
+        } else {
+            println!("Dry run mode: Would remove:");
+        }
+        let categories: [(&str, Vec<String>); 6] = [
+            ("Worktrees", display_paths(&summary.worktrees)),
+            ("Registry entries", summary.registry_entries.clone()),
+            ("Stale locks", display_paths(&summary.stale_locks)),
+            ("Event files", display_paths(&summary.event_files)),
+            ("Run records", summary.run_records.clone()),
+            ("Diagnostics", display_paths(summary.diagnostics.as_slice())),
+        ];
+        for (label, items) in categories {
+            println!("  {} ({}):", label, items.len());
+            for item in items {
+                println!("    {}", item);
+            }
+        }
+    }
+
+    let counts = format!(
+        "{} worktrees, {} registry entries, {} stale locks, {} event files, {} run records, {} diagnostics directory",
+        summary.worktrees.len(),
+        summary.registry_entries.len(),
+        summary.stale_locks.len(),
+        summary.event_files.len(),
+        summary.run_records.len(),
+        usize::from(summary.diagnostics.is_some())
+    );
+    match (dry_run, use_colors) {
+        (true, _) => println!("Would clean: {}", counts),
+        (false, true) => println!("{}✓{} Cleaned: {}", colors::GREEN, colors::RESET, counts),
+        (false, false) => println!("Cleaned: {}", counts),
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter().map(|p| p.display().to_string()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +387,112 @@ mod tests {
         clean_diagnostics(temp_dir.path(), false, false).expect("clean diagnostics");
         assert!(!diagnostics_dir.exists());
     }
+
+    fn deep_options(dry_run: bool, force: bool) -> DeepCleanOptions {
+        DeepCleanOptions {
+            dry_run,
+            force,
+            max_event_age: Duration::from_hours(7 * 24),
+            runs_keep: 1,
+        }
+    }
+
+    fn write_aged(path: &Path, age: Duration) {
+        std::fs::write(path, "{}\n").expect("write file");
+        let file = std::fs::File::options()
+            .write(true)
+            .open(path)
+            .expect("open file");
+        file.set_modified(SystemTime::now() - age)
+            .expect("set mtime");
+    }
+
+    fn seed_workspace(root: &Path) {
+        let ralph_dir = root.join(".ralph");
+        std::fs::create_dir_all(ralph_dir.join("diagnostics/session")).expect("create dirs");
+        let old = Duration::from_hours(30 * 24);
+        write_aged(&ralph_dir.join("events-20250101-000000.jsonl"), old);
+        write_aged(&ralph_dir.join("events-20250102-000000.jsonl"), old);
+        write_aged(
+            &ralph_dir.join("events-20990101-000000.jsonl"),
+            Duration::ZERO,
+        );
+        std::fs::write(
+            ralph_dir.join("current-events"),
+            ".ralph/events-20250102-000000.jsonl",
+        )
+        .expect("write marker");
+        std::fs::write(ralph_dir.join("loop.lock"), "").expect("write lock");
+
+        let history = RunHistory::new(root);
+        for (id, days_ago) in [("20250101-000000", 2), ("20250102-000000", 1)] {
+            let mut record = ralph_core::RunRecord::new(id, "prompt", "claude", "primary");
+            record.started = chrono::Utc::now() - chrono::Duration::days(days_ago);
+            history.start(record).expect("write run record");
+        }
+    }
+
+    #[test]
+    fn clean_all_loops_dry_run_removes_nothing() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        seed_workspace(temp_dir.path());
+
+        let summary =
+            clean_all_loops(temp_dir.path(), &deep_options(true, false), false).expect("dry run");
+
+        assert_eq!(summary.event_files.len(), 1);
+        assert_eq!(summary.stale_locks.len(), 1);
+        assert_eq!(summary.run_records, vec!["20250101-000000"]);
+        assert!(summary.diagnostics.is_some());
+        assert!(summary.event_files[0].exists());
+        assert_eq!(RunHistory::new(temp_dir.path()).list().unwrap().len(), 2);
+        assert!(temp_dir.path().join(".ralph/loop.lock").exists());
+        assert!(temp_dir.path().join(".ralph/diagnostics").exists());
+    }
+
+    #[test]
+    fn clean_all_loops_keeps_current_and_recent_event_files() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        seed_workspace(temp_dir.path());
+        let ralph_dir = temp_dir.path().join(".ralph");
+
+        let summary =
+            clean_all_loops(temp_dir.path(), &deep_options(false, false), false).expect("clean");
+
+        assert_eq!(
+            summary.event_files,
+            vec![ralph_dir.join("events-20250101-000000.jsonl")]
+        );
+        assert!(!ralph_dir.join("events-20250101-000000.jsonl").exists());
+        assert_eq!(summary.run_records, vec!["20250101-000000"]);
+        assert!(!ralph_dir.join("runs/20250101-000000").exists());
+        assert!(ralph_dir.join("runs/20250102-000000").exists());
+        assert!(ralph_dir.join("events-20250102-000000.jsonl").exists());
+        assert!(ralph_dir.join("events-20990101-000000.jsonl").exists());
+        assert!(!ralph_dir.join("loop.lock").exists());
+        assert!(!ralph_dir.join("diagnostics").exists());
+    }
+
+    #[test]
+    fn clean_all_loops_refuses_while_a_loop_is_alive() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        seed_workspace(temp_dir.path());
+        let registry = LoopRegistry::new(temp_dir.path());
+        registry
+            .register(ralph_core::loop_registry::LoopEntry::new(
+                "running",
+                None::<String>,
+            ))
+            .expect("register");
+
+        let err = clean_all_loops(temp_dir.path(), &deep_options(false, false), false)
+            .expect_err("should refuse");
+        assert!(err.to_string().contains("still running"));
+        assert!(temp_dir.path().join(".ralph/diagnostics").exists());
+
+        clean_all_loops(temp_dir.path(), &deep_options(false, true), false).expect("forced");
+        assert!(!temp_dir.path().join(".ralph/diagnostics").exists());
+        // Live entries are never removed from the registry
+        assert_eq!(registry.list().expect("list").len(), 1);
+    }
 }
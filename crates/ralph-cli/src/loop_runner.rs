@@ -1,41 +1,43 @@
-//! Core orchestration loop implementation.
+//! CLI adapter over the core orchestration loop.
 //!
-//! This module contains the main `run_loop_impl` function that executes
-//! the Ralph orchestration loop, along with supporting types and helper
-//! functions for PTY execution and termination handling.
+//! `run_loop_impl` sets up the CLI environment (markers, TUI, signal handling,
+//! session recording) and drives `ralph_core::Orchestrator` with a PTY-backed
+//! executor and CLI hooks for console output, event logging, and termination
+//! handling.
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use ralph_adapters::{
-    CliBackend, CliExecutor, ConsoleStreamHandler, OutputFormat as BackendOutputFormat,
-    PrettyStreamHandler, PtyConfig, PtyExecutor, QuietStreamHandler, TuiStreamHandler,
+    CliBackend, CliExecutor, ConsoleStreamHandler, IterationPrompt,
+    OutputFormat as BackendOutputFormat, PrettyStreamHandler, PtyConfig, PtyExecutor,
+    QuietStreamHandler, SessionResult, StreamHandler, StreamJsonParser, TuiStreamHandler,
+    estimate_cost,
 };
 use ralph_core::{
-    CompletionAction, EventLogger, EventLoop, EventParser, EventRecord, LoopCompletionHandler,
-    LoopContext, LoopHistory, LoopRegistry, MergeQueue, RalphConfig, Record, SessionRecorder,
-    SummaryWriter, TerminationReason,
+    AutoMergeConflictPolicy, CompletionAction, EventHistory, EventHookRunner, EventLogger,
+    EventLoop, EventParser, EventRecord, IterationExecutor, IterationOutcome, IterationRequest,
+    KeyringStore, LoopCompletionHandler, LoopContext, LoopHistory, LoopHooks, LoopRegistry,
+    LoopStatusWriter, MarkdownMemoryStore, MergePreview, MergeQueue, Orchestrator, PauseControl,
+    RalphConfig, Record, Redactor, RunHistory, RunRecord, ScratchpadArchive, SessionRecorder,
+    SummaryWriter, TerminationReason, memory_extraction, merged_file_content, preview_merge,
 };
 use ralph_proto::{Event, HatId};
 use ralph_tui::Tui;
+use std::collections::{BTreeMap, HashSet};
 use std::ffi::OsStr;
-use std::fs::{self, File};
-use std::io::{BufWriter, IsTerminal, stdin, stdout};
+use std::fs;
+use std::io::{IsTerminal, Write, stdin, stdout};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
-use std::time::Duration;
-use tracing::{debug, error, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 use crate::display::{build_tui_hat_map, print_iteration_separator, print_termination};
+use crate::json_events::JsonEventStream;
 use crate::process_management;
 use crate::{ColorMode, Verbosity};
 
-/// Outcome of executing a prompt via PTY or CLI executor.
-pub(crate) struct ExecutionOutcome {
-    pub output: String,
-    pub success: bool,
-    pub termination: Option<TerminationReason>,
-}
-
 /// Core loop implementation supporting both fresh start and continue modes.
 ///
 /// # Arguments
@@ -43,8 +45,11 @@ pub(crate) struct ExecutionOutcome {
 /// * `resume` - If true, publishes `task.resume` instead of `task.start`,
 ///   signaling the planner to read existing scratchpad rather than doing fresh gap analysis.
 /// * `record_session` - If provided, records all events to the specified JSONL file for replay testing.
+/// * `json_events_on_stdout` - Writes every event to stdout as a JSON line and keeps the
+///   console output (iteration separators, termination summary) off stdout.
 /// * `auto_merge_override` - Explicit auto-merge setting. If `Some(false)`, disables auto-merge
 ///   (equivalent to `--no-auto-merge`). If `None`, uses `config.features.auto_merge`.
+/// * `config_sources` - The `-c` sources the config was loaded from, kept in the run record.
 pub async fn run_loop_impl(
     config: RalphConfig,
     color_mode: ColorMode,
@@ -52,9 +57,11 @@ pub async fn run_loop_impl(
     enable_tui: bool,
     verbosity: Verbosity,
     record_session: Option<PathBuf>,
+    json_events_on_stdout: bool,
     loop_context: Option<LoopContext>,
     custom_args: Vec<String>,
     auto_merge_override: Option<bool>,
+    config_sources: Vec<String>,
 ) -> Result<TerminationReason> {
     // Set up process group leadership per spec
     // "The orchestrator must run as a process group leader"
@@ -95,7 +102,7 @@ pub async fn run_loop_impl(
     // 3. Config prompt (inline text)
     // 4. Config prompt_file (file path)
     // 5. Default PROMPT.md
-    let prompt_content = resolve_prompt_content(&config.event_loop)?;
+    let prompt_content = resolve_prompt_content(&config.event_loop, &config.vars)?;
 
     // Create or use provided loop context for path resolution
     // This ensures events are written to the correct location for worktree loops
@@ -117,8 +124,8 @@ pub async fn run_loop_impl(
     // For fresh runs (not resume), generate a unique timestamped events file
     // This prevents stale events from previous runs polluting new runs (issue #82)
     // The marker file `.ralph/current-events` coordinates path between Ralph and agents
+    let run_id = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
     if !resume {
-        let run_id = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
         // Use relative path in marker file for portability across agents
         // The actual file is at ctx.ralph_dir()/events-{run_id}.jsonl
         let relative_events_path = format!(".ralph/events-{}.jsonl", run_id);
@@ -129,23 +136,47 @@ pub async fn run_loop_impl(
 
         debug!("Created events file for this run: {}", relative_events_path);
 
-        // Clear scratchpad for fresh objective start
-        // Stale content from previous runs can confuse the agent about current task state
+        // Archive scratchpad for fresh objective start
+        // Stale content from previous runs can confuse the agent about current task state,
+        // but it's kept under .ralph/agent/archive/ so `ralph tools scratchpad restore` can bring it back
         let scratchpad_path = ctx.scratchpad_path();
-        if scratchpad_path.exists() {
-            fs::remove_file(&scratchpad_path)
-                .with_context(|| format!("Failed to clear scratchpad: {:?}", scratchpad_path))?;
-            debug!(
-                "Cleared scratchpad for fresh objective: {:?}",
-                scratchpad_path
-            );
+        let archived = ScratchpadArchive::from_context(&ctx)
+            .archive(&scratchpad_path, config.core.scratchpad_archive_keep)
+            .with_context(|| format!("Failed to archive scratchpad: {:?}", scratchpad_path))?;
+        if let Some(archived) = archived {
+            info!("Archived previous scratchpad to {}", archived.display());
         }
     }
 
+    // Record the run under .ralph/runs/ so `ralph runs` can list it later
+    let run_history = RunHistory::from_context(&ctx);
+    let mut run_record = RunRecord::new(
+        run_id,
+        ralph_core::truncate_with_ellipsis(prompt_content.trim(), 100),
+        config.cli.backend.clone(),
+        loop_id.clone(),
+    );
+    run_record.config_sources = config_sources;
+    run_record.workspace = Some(ctx.workspace().to_path_buf());
+    run_record.events_file = Some(resolve_current_events_path(&ctx));
+    run_record.session_recording = record_session.clone();
+    let run_id = match run_history.start(run_record) {
+        Ok(id) => {
+            if let Err(e) = run_history.prune(config.core.runs_keep) {
+                warn!("Failed to prune old run records: {}", e);
+            }
+            Some(id)
+        }
+        Err(e) => {
+            warn!("Failed to create run record: {}", e);
+            None
+        }
+    };
+
     // Initialize event loop with context for proper path resolution
     let mut event_loop = EventLoop::with_context(config.clone(), ctx.clone());
 
-    // Inject robot service (Telegram) for human-in-the-loop communication
+    // Inject robot service (Telegram and/or Slack) for human-in-the-loop communication
     if config.robot.enabled
         && ctx.is_primary()
         && let Some(service) = create_robot_service(&config, &ctx)
@@ -156,42 +187,33 @@ pub async fn run_loop_impl(
     // Capture the robot service shutdown flag so signal handlers can interrupt wait_for_response()
     let robot_shutdown = event_loop.robot_shutdown_flag();
 
-    // For resume mode, we initialize with a different event topic
-    // This tells the planner to read existing scratchpad rather than creating a new one
-    if resume {
-        event_loop.initialize_resume(&prompt_content);
-    } else {
-        event_loop.initialize(&prompt_content);
-    }
-
-    // Set up session recording if requested
-    // This records all events to a JSONL file for replay testing
-    let _session_recorder: Option<Arc<SessionRecorder<BufWriter<File>>>> =
-        if let Some(record_path) = record_session {
-            let file = File::create(&record_path).with_context(|| {
-                format!("Failed to create session recording file: {:?}", record_path)
-            })?;
-            let recorder = Arc::new(SessionRecorder::new(BufWriter::new(file)));
-
-            // Record metadata for the session
-            recorder.record_meta(Record::meta_loop_start(
-                &config.event_loop.prompt_file,
-                config.event_loop.max_iterations,
-                if enable_tui { Some("tui") } else { Some("cli") },
-            ));
+    // Registered before initialization so the starting event is streamed too
+    let json_events = json_events_on_stdout.then(|| {
+        let stream = JsonEventStream::stdout();
+        event_loop.add_observer(stream.observer());
+        stream
+    });
 
-            // Wire observer to EventBus so events are recorded
-            let observer = SessionRecorder::make_observer(Arc::clone(&recorder));
-            event_loop.add_observer(observer);
+    // Shell commands run in the background on matching events (`hooks`)
+    let event_hooks = (!config.hooks.is_empty()).then(|| {
+        let runner = EventHookRunner::new(
+            &config.hooks,
+            ctx.workspace(),
+            resolve_current_events_path(&ctx),
+        );
+        event_loop.add_observer(runner.observer());
+        runner
+    });
 
-            info!("Session recording enabled: {:?}", record_path);
-            Some(recorder)
-        } else {
-            None
-        };
+    // Iteration, last event and resource use for `ralph loops list`
+    let loop_status = LoopStatusWriter::new(ctx.workspace());
+    event_loop.add_observer(loop_status.observer());
+    loop_status.write();
 
-    // Initialize event logger for debugging (uses context for path resolution)
-    let mut event_logger = EventLogger::from_context(&ctx);
+    // Initialize event logger for debugging (uses context for path resolution).
+    // The start record goes in first so hook records can't precede it
+    let mut event_logger =
+        EventLogger::from_context(&ctx).with_fsync(config.event_loop.fsync_events);
 
     // Log initial event (use configured starting_event or default to task.start/task.resume)
     let default_start_topic = if resume { "task.resume" } else { "task.start" };
@@ -201,16 +223,64 @@ pub async fn run_loop_impl(
         .as_deref()
         .unwrap_or(default_start_topic);
     let start_triggered = "planner"; // Default triggered hat for backward compat
-    let start_event = Event::new(start_topic, &prompt_content);
+    let start_payload = match &config.event_loop.starting_payload {
+        Some(payload) if !resume => payload.as_str(),
+        _ => prompt_content.as_str(),
+    };
+    let start_event = Event::new(start_topic, start_payload);
     let start_record =
         EventRecord::new(0, "loop", &start_event, Some(&HatId::new(start_triggered)));
     if let Err(e) = event_logger.log(&start_record) {
         warn!("Failed to log start event: {}", e);
     }
 
+    // For resume mode, we initialize with a different event topic
+    // This tells the planner to read existing scratchpad rather than creating a new one
+    if resume {
+        event_loop.initialize_resume(&prompt_content);
+    } else {
+        event_loop.initialize(&prompt_content);
+    }
+    event_loop.notify_loop_started(&prompt_content);
+
+    // Secrets from cli.env/hat env sources and the security rules are masked
+    // alongside the capture patterns. Installing the redactor covers the
+    // events file, tracing logs and diagnostics files.
+    let redactor = config.redactor(&KeyringStore)?;
+    ralph_core::install_redactor(redactor.clone());
+
+    // Set up session recording if requested
+    // This records all events to a JSONL file (gzipped for *.gz) for replay testing
+    let session_recorder: Option<SessionRecording> = if let Some(record_path) = record_session {
+        let recorder = SessionRecorder::create(&record_path).with_context(|| {
+            format!("Failed to create session recording file: {:?}", record_path)
+        })?;
+        let recorder = Arc::new(
+            recorder
+                .with_redactor(redactor.clone())
+                .with_max_bytes(config.features.recording.max_bytes),
+        );
+
+        // Record metadata for the session
+        recorder.record_meta(Record::meta_loop_start(
+            &config.event_loop.prompt_file,
+            config.event_loop.max_iterations,
+            if enable_tui { Some("tui") } else { Some("cli") },
+        ));
+
+        // Wire observer to EventBus so events are recorded
+        let observer = SessionRecorder::make_observer(Arc::clone(&recorder));
+        event_loop.add_observer(observer);
+
+        info!("Session recording enabled: {:?}", record_path);
+        Some(recorder)
+    } else {
+        None
+    };
+
     // Create backend from config - TUI mode uses the same backend as non-TUI
     // The TUI is an observation layer that displays output, not a different mode
-    let mut backend = CliBackend::from_config(&config.cli).map_err(|e| anyhow::Error::new(e))?;
+    let mut backend = CliBackend::from_ralph_config(&config).map_err(|e| anyhow::Error::new(e))?;
 
     // Append custom args from CLI if provided (e.g., `ralph run -b opencode -- --model="some-model"`)
     if !custom_args.is_empty() {
@@ -218,7 +288,7 @@ pub async fn run_loop_impl(
     }
 
     // Create PTY executor if using interactive mode
-    let mut pty_executor = if use_pty {
+    let pty_executor = if use_pty {
         let idle_timeout_secs = if user_interactive {
             config.cli.idle_timeout_secs
         } else {
@@ -243,6 +313,7 @@ pub async fn run_loop_impl(
     // Requirements: both stdin and stdout must be terminals for TUI
     // (Crossterm requires stdin for keyboard input, stdout for rendering)
     let enable_tui = enable_tui && stdin().is_terminal() && stdout().is_terminal();
+    let pause_control = enable_tui.then(PauseControl::new);
     let (mut tui_handle, tui_state, guidance_next_queue) = if enable_tui {
         // Build hat map for dynamic topic-to-hat resolution
         // This allows TUI to display custom hats (e.g., "Security Reviewer")
@@ -261,6 +332,12 @@ pub async fn run_loop_impl(
         // (raw mode prevents SIGINT from being generated by the OS)
         let tui = tui.with_interrupt_tx(interrupt_tx.clone());
 
+        // Pause/step keys gate iteration starts in the orchestrator
+        let tui = match &pause_control {
+            Some(control) => tui.with_pause_control(control.clone()),
+            None => tui,
+        };
+
         let observer = tui.observer();
         event_loop.add_observer(observer);
         (
@@ -342,13 +419,6 @@ pub async fn run_loop_impl(
     };
     debug!(execution_mode = %exec_mode, "Execution mode configured");
 
-    // Track the last hat to detect hat changes for logging
-    let mut last_hat: Option<HatId> = None;
-
-    // Track consecutive fallback attempts to prevent infinite loops
-    let mut consecutive_fallbacks: u32 = 0;
-    const MAX_FALLBACK_ATTEMPTS: u32 = 3;
-
     // Initialize loop history if we have a loop context
     let loop_history = loop_context
         .as_ref()
@@ -392,14 +462,578 @@ pub async fn run_loop_impl(
         }
     }
 
-    // Helper closure to handle termination (writes summary, prints status, records history)
-    let handle_termination = |reason: &TerminationReason,
-                              state: &ralph_core::LoopState,
-                              scratchpad: &str,
-                              history: &Option<LoopHistory>,
-                              context: &Option<LoopContext>,
-                              auto_merge: bool,
-                              prompt: &str| {
+    let mut hooks = CliLoopHooks {
+        config: &config,
+        ctx: ctx.clone(),
+        event_logger,
+        loop_history,
+        loop_context,
+        merge_loop_id,
+        auto_merge,
+        prompt_content,
+        enable_tui,
+        use_colors,
+        verbosity,
+        interrupt_rx: interrupt_rx.clone(),
+        tui_state: tui_state.clone(),
+        guidance_next_queue,
+        last_hat: None,
+        last_output: String::new(),
+        session_recorder: session_recorder.clone(),
+        iteration_started: None,
+        json_events,
+        event_hooks: event_hooks.clone(),
+        loop_status,
+    };
+    if config.cli.continue_session && !backend.supports_continuation() {
+        warn!(
+            "Backend '{}' cannot resume sessions; cli.continue_session is ignored and every iteration gets the full prompt",
+            config.cli.backend
+        );
+    }
+    let mut executor = LoopExecutor {
+        config: &config,
+        backend,
+        pty_executor,
+        use_pty,
+        user_interactive,
+        verbosity,
+        interrupt_rx,
+        tui_state,
+        unenforced_tool_hats: HashSet::new(),
+        redactor,
+        session_recorder,
+        previous_backend: None,
+    };
+
+    // Main orchestration loop
+    let mut orchestrator = Orchestrator::from_event_loop(event_loop);
+    if let Some(control) = pause_control {
+        // An interrupt must not wait behind a pause
+        let mut interrupted = executor.interrupt_rx.clone();
+        let resume = control.clone();
+        tokio::spawn(async move {
+            if interrupted
+                .wait_for(|interrupted| *interrupted)
+                .await
+                .is_ok()
+            {
+                resume.resume();
+            }
+        });
+        orchestrator = orchestrator.with_pause_control(control);
+    }
+    // Keeps backend resource use in the status file current mid-iteration
+    let status_refresh = hooks.loop_status.spawn_refresh();
+    let summary = orchestrator.run(&mut executor, &mut hooks).await;
+    status_refresh.abort();
+    let summary = summary?;
+
+    // Let hooks fired by the final events (e.g. the completion) finish
+    if let Some(event_hooks) = &event_hooks
+        && summary.reason != TerminationReason::Interrupted
+        && !event_hooks.wait_idle(event_hooks.max_timeout()).await
+    {
+        warn!("Hooks still running at exit were stopped");
+    }
+
+    if let Some(run_id) = &run_id
+        && let Err(e) = run_history.finish(run_id, summary.reason.as_str(), summary.iterations)
+    {
+        warn!("Failed to update run record {}: {}", run_id, e);
+    }
+
+    if summary.reason == TerminationReason::Interrupted {
+        // Signal TUI to exit immediately on interrupt
+        let _ = terminated_tx.send(true);
+    } else if let Some(handle) = tui_handle.take() {
+        // Wait for user to exit TUI (press 'q') on natural completion
+        let _ = handle.await;
+    }
+
+    if config.features.memory.auto_extract && memory_extraction::should_extract(&summary.reason) {
+        let iteration = summary.iterations + 1;
+        if let Err(e) = extract_memories(
+            &config,
+            &ctx,
+            &loop_id,
+            &hooks.last_output,
+            &mut executor,
+            iteration,
+            use_colors,
+            json_events_on_stdout,
+        )
+        .await
+        {
+            warn!("Memory extraction failed: {:#}", e);
+        }
+    }
+
+    Ok(summary.reason)
+}
+
+/// Runs the post-run memory extraction pass (`features.memory.auto_extract`).
+///
+/// Asks the backend for candidate memories over the scratchpad and the final
+/// iteration's output, then stores them with the run ID as provenance. With
+/// `features.memory.review`, candidates are printed and only stored once the
+/// user confirms; without a terminal to ask on (or with
+/// `--json-events-on-stdout`), reviewed candidates are discarded. Output goes
+/// to stderr when stdout carries JSON events.
+async fn extract_memories(
+    config: &RalphConfig,
+    ctx: &LoopContext,
+    run_id: &str,
+    final_output: &str,
+    executor: &mut LoopExecutor<'_>,
+    iteration: u32,
+    use_colors: bool,
+    json_events_on_stdout: bool,
+) -> Result<()> {
+    let features = &config.features.memory;
+    let scratchpad = fs::read_to_string(ctx.scratchpad_path()).unwrap_or_default();
+
+    info!("Extracting memories from run {}", run_id);
+    let request = IterationRequest {
+        iteration,
+        hat_id: HatId::new("ralph"),
+        active_hat: HatId::new("ralph"),
+        hat_name: "Memory extraction".to_string(),
+        backend: None,
+        env: BTreeMap::new(),
+        allowed_tools: Vec::new(),
+        disallowed_tools: Vec::new(),
+        prompt: memory_extraction::build_prompt(&scratchpad, final_output, features.max_extracted),
+    };
+    let outcome = executor.execute(&request).await?;
+    if outcome.termination.is_some() || !outcome.success {
+        anyhow::bail!("extraction invocation did not complete");
+    }
+
+    let candidates = memory_extraction::parse_candidates(&outcome.output, features.max_extracted);
+    if candidates.is_empty() {
+        info!("No memories extracted from run {}", run_id);
+        return Ok(());
+    }
+
+    let mut out: Box<dyn Write> = if json_events_on_stdout {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(stdout())
+    };
+
+    if features.review {
+        writeln!(out, "\nExtracted {} candidate memories:", candidates.len())?;
+        for (i, candidate) in candidates.iter().enumerate() {
+            let tags = if candidate.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", candidate.tags.join(", "))
+            };
+            writeln!(
+                out,
+                "  {}. {} {}{}",
+                i + 1,
+                candidate.memory_type.emoji(),
+                candidate.memory_type,
+                tags
+            )?;
+            for line in candidate.content.lines() {
+                writeln!(out, "     {}", line)?;
+            }
+        }
+
+        let confirmed = if json_events_on_stdout || !stdin().is_terminal() {
+            writeln!(
+                out,
+                "No terminal to confirm on; not storing reviewed memories."
+            )?;
+            false
+        } else {
+            eprintln!("Store these memories? [y/N] ");
+            let mut input = String::new();
+            stdin().read_line(&mut input)?;
+            input.trim().eq_ignore_ascii_case("y")
+        };
+        if !confirmed {
+            writeln!(out, "Discarded extracted memories.")?;
+            return Ok(());
+        }
+    }
+
+    let store = MarkdownMemoryStore::with_default_path(&config.core.workspace_root);
+    let count = candidates.len();
+    for candidate in candidates {
+        store
+            .append(&candidate.into_memory(run_id))
+            .context("Failed to store extracted memory")?;
+    }
+
+    if use_colors {
+        writeln!(
+            out,
+            "{}📝 Stored {} extracted memories{} in {}",
+            crate::display::colors::GREEN,
+            count,
+            crate::display::colors::RESET,
+            store.path().display()
+        )?;
+    } else {
+        writeln!(
+            out,
+            "Stored {} extracted memories in {}",
+            count,
+            store.path().display()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Terminates the current process group (SIGTERM, brief grace, then SIGKILL).
+#[cfg(unix)]
+async fn kill_process_group() {
+    use nix::sys::signal::{Signal, killpg};
+    use nix::unistd::getpgrp;
+    let pgid = getpgrp();
+    debug!("Sending SIGTERM to process group {}", pgid);
+    let _ = killpg(pgid, Signal::SIGTERM);
+
+    // Wait briefly for graceful exit, then SIGKILL
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let _ = killpg(pgid, Signal::SIGKILL);
+}
+
+/// Runs iterations through the PTY (or buffered CLI) executor.
+///
+/// Resolves hat-level backends, streams output to the TUI or console, and
+/// races execution against the interrupt signal.
+struct LoopExecutor<'a> {
+    config: &'a RalphConfig,
+    backend: CliBackend,
+    pty_executor: Option<PtyExecutor>,
+    use_pty: bool,
+    user_interactive: bool,
+    verbosity: Verbosity,
+    interrupt_rx: tokio::sync::watch::Receiver<bool>,
+    tui_state: Option<Arc<std::sync::Mutex<ralph_tui::TuiState>>>,
+    /// Hats already warned about unenforceable tool restrictions.
+    unenforced_tool_hats: HashSet<HatId>,
+    /// Masks secrets in logged command lines.
+    redactor: Redactor,
+    /// Receives backend stderr and tool results with `--record-session`.
+    session_recorder: Option<SessionRecording>,
+    /// Backend of the previous iteration, whose session `cli.continue_session`
+    /// resumes.
+    previous_backend: Option<CliBackend>,
+}
+
+impl LoopExecutor<'_> {
+    /// Resolves the backend for a hat, returning it with the name used for timeouts.
+    ///
+    /// Hat-level backend configuration takes precedence over global `cli.backend`,
+    /// and hat-level `env` over `cli.env`. The hat's tool restrictions are
+    /// translated into backend flags where the backend supports them.
+    fn resolve_backend(&mut self, request: &IterationRequest) -> (CliBackend, String) {
+        let (mut resolved, backend_name) = self.resolve_hat_backend(request);
+        resolved.merge_env(&request.env);
+        // `ralph tools ...` run by the agent must use this loop's workspace,
+        // not one inherited from Ralph's own environment
+        resolved.set_env(
+            crate::workspace_root::WORKSPACE_ENV,
+            self.config
+                .core
+                .workspace_root
+                .to_string_lossy()
+                .into_owned(),
+        );
+        if !resolved.restrict_tools(&request.allowed_tools, &request.disallowed_tools)
+            && self.unenforced_tool_hats.insert(request.active_hat.clone())
+        {
+            warn!(
+                "Backend '{}' cannot enforce tool restrictions for hat '{}'; they only apply through the hat's instructions",
+                backend_name, request.hat_name
+            );
+        }
+        (resolved, backend_name)
+    }
+
+    fn resolve_hat_backend(&self, request: &IterationRequest) -> (CliBackend, String) {
+        let config = self.config;
+        let display_hat = &request.active_hat;
+        let backend = &self.backend;
+        match request.backend.as_ref() {
+            Some(hat_backend) => {
+                // Hat has custom backend configuration
+                match CliBackend::from_hat_backend_with_custom(
+                    hat_backend,
+                    &config.adapters.custom_backends,
+                ) {
+                    Ok(mut hat_backend_instance) => {
+                        hat_backend_instance.merge_env(&config.cli.env);
+                        hat_backend_instance
+                            .set_large_prompt_threshold(config.cli.large_prompt_threshold);
+                        debug!(
+                            "Using hat-level backend for '{}': {:?}",
+                            display_hat, hat_backend
+                        );
+
+                        // Determine backend name for timeout based on hat backend type
+                        let backend_name = match hat_backend {
+                            ralph_core::HatBackend::Named(name) => name.clone(),
+                            ralph_core::HatBackend::NamedWithArgs { backend_type, .. } => {
+                                backend_type.clone()
+                            }
+                            ralph_core::HatBackend::KiroAgent { .. } => "kiro".to_string(),
+                            // For Custom backends, extract command name from path
+                            // Handles both Unix ("/usr/bin/codex") and commands with args ("ollama run llama3")
+                            ralph_core::HatBackend::Custom { command, .. } => {
+                                // First split by whitespace to handle commands with arguments
+                                // e.g., "ollama run llama3" -> "ollama"
+                                let base_command =
+                                    command.split_whitespace().next().unwrap_or(command);
+                                // Then extract filename from path
+                                // e.g., "/usr/bin/codex" -> "codex"
+                                std::path::Path::new(base_command)
+                                    .file_name()
+                                    .and_then(|s| s.to_str())
+                                    .unwrap_or("custom")
+                                    .to_string()
+                            }
+                        };
+
+                        (hat_backend_instance, backend_name)
+                    }
+                    Err(e) => {
+                        // Failed to create backend from hat config - fall back to global
+                        warn!(
+                            "Failed to create backend from hat configuration for '{}': {}. Falling back to global backend.",
+                            display_hat, e
+                        );
+                        // IMPORTANT: Use global backend name for timeout since we're using global backend
+                        (backend.clone(), config.cli.backend.clone())
+                    }
+                }
+            }
+            None => {
+                // No custom backend - use global configuration
+                debug!(
+                    "Using global backend for '{}': {}",
+                    display_hat, config.cli.backend
+                );
+                (backend.clone(), config.cli.backend.clone())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl IterationExecutor for LoopExecutor<'_> {
+    type Error = anyhow::Error;
+
+    async fn execute(&mut self, request: &IterationRequest) -> Result<IterationOutcome> {
+        let (mut effective_backend, backend_name_for_timeout) = self.resolve_backend(request);
+
+        // Resuming the previous session, only what changed needs sending
+        let previous_backend = self.previous_backend.replace(effective_backend.clone());
+        let prompt = match effective_backend
+            .iteration_prompt(self.config.cli.continue_session, previous_backend.as_ref())
+        {
+            IterationPrompt::Full => request.prompt.clone(),
+            IterationPrompt::Continuation => {
+                effective_backend.continue_session();
+                ralph_core::continuation_prompt(&request.prompt)
+            }
+        };
+
+        // Get timeout from config based on actual backend being used
+        let timeout_secs = self
+            .config
+            .adapter_settings(&backend_name_for_timeout)
+            .timeout;
+        let timeout = Some(Duration::from_secs(timeout_secs));
+
+        // For TUI mode, get the shared lines buffer for this iteration.
+        // The buffer is owned by TuiState's IterationBuffer, so writes from
+        // TuiStreamHandler appear immediately in the TUI (real-time streaming).
+        let tui_lines = self.tui_state.as_ref().and_then(|state| {
+            // Start new iteration and get handle to the LATEST iteration's lines buffer.
+            // We must use latest_iteration_lines_handle() instead of current_iteration_lines_handle()
+            // because the user may be viewing an older iteration while a new one executes.
+            prepare_tui_iteration(
+                state,
+                request.hat_name.clone(),
+                backend_name_for_timeout.clone(),
+                self.config.event_loop.max_iterations,
+            )
+        });
+
+        // Race execution against interrupt signal for immediate termination on Ctrl+C
+        let mut interrupt_rx = self.interrupt_rx.clone();
+        let interrupt_rx_for_pty = self.interrupt_rx.clone();
+        let execute_future = async {
+            if self.use_pty {
+                execute_pty(
+                    self.pty_executor.as_mut(),
+                    &effective_backend,
+                    self.config,
+                    &prompt,
+                    self.user_interactive,
+                    interrupt_rx_for_pty,
+                    self.verbosity,
+                    tui_lines,
+                    self.session_recorder
+                        .clone()
+                        .map(|recorder| (recorder, request.iteration)),
+                )
+                .await
+            } else {
+                let executor = CliExecutor::new(effective_backend.clone())
+                    .with_redactor(self.redactor.clone());
+                let result = executor
+                    .execute(
+                        &prompt,
+                        stdout(),
+                        timeout,
+                        self.verbosity == Verbosity::Verbose,
+                    )
+                    .await?;
+                if let Some(recorder) = &self.session_recorder
+                    && !result.stderr.is_empty()
+                {
+                    recorder.record_stderr(request.iteration, &result.stderr);
+                }
+                let failure = if result.timed_out || result.idle_timed_out {
+                    None
+                } else {
+                    exit_failure(result.success, result.exit_code)
+                };
+                let cost_usd = session_cost(&effective_backend, self.config, &result.output);
+                Ok(IterationOutcome {
+                    output: result.output,
+                    success: result.success,
+                    termination: None,
+                    failure,
+                    cost_usd,
+                })
+            }
+        };
+        // With retries enabled, a backend that cannot be started is retried
+        // like a crash instead of ending the loop.
+        let execute_future = async {
+            match execute_future.await {
+                Err(e) if self.config.event_loop.retry.enabled() => Ok(IterationOutcome::crashed(
+                    String::new(),
+                    format!("failed to start backend: {e:#}"),
+                )),
+                result => result,
+            }
+        };
+
+        tokio::select! {
+            result = execute_future => result,
+            _ = interrupt_rx.changed() => {
+                // Immediately terminate children via process group signal
+                #[cfg(unix)]
+                kill_process_group().await;
+                Ok(IterationOutcome::terminated(TerminationReason::Interrupted))
+            }
+        }
+    }
+}
+
+/// CLI-side loop hooks: console/TUI output, event logging, and termination
+/// bookkeeping (summary file, history, merge queue, landing).
+struct CliLoopHooks<'a> {
+    config: &'a RalphConfig,
+    ctx: LoopContext,
+    event_logger: EventLogger,
+    loop_history: Option<LoopHistory>,
+    loop_context: Option<LoopContext>,
+    merge_loop_id: Option<String>,
+    auto_merge: bool,
+    prompt_content: String,
+    enable_tui: bool,
+    use_colors: bool,
+    verbosity: Verbosity,
+    interrupt_rx: tokio::sync::watch::Receiver<bool>,
+    tui_state: Option<Arc<std::sync::Mutex<ralph_tui::TuiState>>>,
+    guidance_next_queue: Option<Arc<std::sync::Mutex<Vec<String>>>>,
+    /// Last hat worn, to detect hat changes for logging.
+    last_hat: Option<HatId>,
+    /// Output of the most recent iteration, for post-run memory extraction.
+    last_output: String,
+    /// Receives prompts, iteration boundaries and termination with `--record-session`.
+    session_recorder: Option<SessionRecording>,
+    /// When the current iteration started, for its end record.
+    iteration_started: Option<Instant>,
+    /// Event lines on stdout with `--json-events-on-stdout`.
+    json_events: Option<JsonEventStream>,
+    /// Runs `hooks` on matching events.
+    event_hooks: Option<EventHookRunner>,
+    /// Status snapshot rewritten at iteration boundaries.
+    loop_status: LoopStatusWriter,
+}
+
+impl CliLoopHooks<'_> {
+    /// Drains the TUI next-loop guidance queue into `human.guidance` events.
+    ///
+    /// These will be picked up by process_events_from_jsonl() during build_prompt().
+    fn flush_guidance(&self) {
+        let Some(ref queue) = self.guidance_next_queue else {
+            return;
+        };
+        let messages: Vec<String> = {
+            let mut q = queue.lock().unwrap();
+            q.drain(..).collect()
+        };
+        if messages.is_empty() {
+            return;
+        }
+
+        let events_path = resolve_current_events_path(&self.ctx);
+
+        for msg in &messages {
+            let timestamp = chrono::Utc::now().to_rfc3339();
+            let event = serde_json::json!({
+                "topic": "human.guidance",
+                "payload": msg,
+                "ts": timestamp,
+            });
+
+            match serde_json::to_string(&event) {
+                Ok(line) => {
+                    if let Err(e) =
+                        EventHistory::new(&events_path).append(&line, self.event_logger.fsyncs())
+                    {
+                        // Skip the rest - keep loop running
+                        warn!(error = %e, path = ?events_path, "Failed writing guidance event line");
+                        break;
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed serializing guidance event");
+                }
+            }
+        }
+        info!(
+            count = messages.len(),
+            "Wrote TUI guidance events to events.jsonl"
+        );
+    }
+
+    /// Handles termination: writes summary, records history, updates the
+    /// merge queue, lands completed loops, and prints status.
+    fn handle_termination(&self, reason: &TerminationReason, state: &ralph_core::LoopState) {
+        let scratchpad = self.config.core.scratchpad.as_str();
+        let history = &self.loop_history;
+        let context = &self.loop_context;
+        let merge_loop_id = self.merge_loop_id.as_ref();
+        let auto_merge = self.auto_merge;
+        let prompt = self.prompt_content.as_str();
+        let enable_tui = self.enable_tui;
+        let use_colors = self.use_colors;
+
         // Per spec: Write summary file on termination
         let summary_writer = SummaryWriter::default();
         let scratchpad_path = std::path::Path::new(scratchpad);
@@ -430,6 +1064,12 @@ pub async fn run_loop_impl(
                 TerminationReason::Stopped => "stopped",
                 TerminationReason::Interrupted => "interrupted",
                 TerminationReason::RestartRequested => "restart_requested",
+                TerminationReason::GateFailed => "gate_failed",
+                TerminationReason::UntilEventReached => "until_event_reached",
+                TerminationReason::HatBudgetExceeded => "hat_budget_exceeded",
+                TerminationReason::EventQueueOverflow => "event_queue_overflow",
+                TerminationReason::CompletionRejected => "completion_rejected",
+                TerminationReason::NoMatchingHat { .. } => "no_matching_hat",
             };
 
             if matches!(reason, TerminationReason::Interrupted) {
@@ -443,7 +1083,7 @@ pub async fn run_loop_impl(
 
         // Handle merge queue state transitions for merge loops
         // Per spec: CompletionPromise → merged, other → needs-review
-        if let Some(ref loop_id) = merge_loop_id {
+        if let Some(loop_id) = merge_loop_id {
             let repo_root = context
                 .as_ref()
                 .map(|ctx| ctx.repo_root().to_path_buf())
@@ -472,6 +1112,11 @@ pub async fn run_loop_impl(
                             warn!(loop_id = %loop_id, error = %e, "Failed to mark merge as completed");
                         } else {
                             info!(loop_id = %loop_id, commit = %sha, "Merge completed successfully");
+                            if let Err(e) =
+                                crate::loops::cleanup_merged_loop(&repo_root, loop_id, false)
+                            {
+                                warn!(loop_id = %loop_id, error = %e, "Failed to clean up merged loop");
+                            }
                         }
                     }
                     None => {
@@ -498,6 +1143,12 @@ pub async fn run_loop_impl(
                     TerminationReason::Interrupted => "interrupted by signal",
                     TerminationReason::CompletionPromise => unreachable!(),
                     TerminationReason::RestartRequested => "restart requested",
+                    TerminationReason::GateFailed => "failure gate event published",
+                    TerminationReason::UntilEventReached => "stopped at --until-event topic",
+                    TerminationReason::HatBudgetExceeded => "hat iteration budget exceeded",
+                    TerminationReason::EventQueueOverflow => "event queue overflowed",
+                    TerminationReason::CompletionRejected => "completion check kept failing",
+                    TerminationReason::NoMatchingHat { .. } => "no hat matched a published event",
                 };
                 if let Err(e) = queue.mark_needs_review(loop_id, reason_str) {
                     warn!(loop_id = %loop_id, error = %e, "Failed to mark merge as needs-review");
@@ -565,7 +1216,7 @@ pub async fn run_loop_impl(
 
             // Handle merge queue processing for primary loop completion
             if ctx.is_primary() && matches!(reason, TerminationReason::CompletionPromise) {
-                process_pending_merges(ctx.repo_root());
+                process_pending_merges(ctx.repo_root(), self.config.features.auto_merge_conflict);
             }
 
             // Always deregister from registry — process is exiting regardless of reason.
@@ -576,17 +1227,19 @@ pub async fn run_loop_impl(
             }
         }
 
-        // Print termination info to console (skip in TUI mode - TUI handles display)
-        if !enable_tui {
+        // Print termination info to console (skip in TUI mode - TUI handles display,
+        // and with --json-events-on-stdout, where stdout carries only events)
+        if !enable_tui && self.json_events.is_none() {
             print_termination(reason, state, use_colors);
         }
-    };
+    }
+}
 
-    // Main orchestration loop
-    loop {
+impl LoopHooks for CliLoopHooks<'_> {
+    fn before_iteration(&mut self, _event_loop: &mut EventLoop) -> Option<TerminationReason> {
         // Check for interrupt signal at start of each iteration
         // This catches TUI Ctrl+C (via interrupt_tx) before printing iteration separator
-        if *interrupt_rx.borrow() {
+        if *self.interrupt_rx.borrow() {
             #[cfg(unix)]
             {
                 use nix::sys::signal::{Signal, killpg};
@@ -597,545 +1250,235 @@ pub async fn run_loop_impl(
                     pgid
                 );
                 let _ = killpg(pgid, Signal::SIGTERM);
-                tokio::time::sleep(Duration::from_millis(250)).await;
+                std::thread::sleep(Duration::from_millis(250));
                 let _ = killpg(pgid, Signal::SIGKILL);
             }
-            let reason = TerminationReason::Interrupted;
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            // Signal TUI to exit immediately on interrupt
-            let _ = terminated_tx.send(true);
-            return Ok(reason);
+            return Some(TerminationReason::Interrupted);
         }
 
-        // Drain next-loop guidance queue and write as human.guidance events.
-        // These will be picked up by process_events_from_jsonl() during build_prompt().
-        if let Some(ref queue) = guidance_next_queue {
-            let messages: Vec<String> = {
-                let mut q = queue.lock().unwrap();
-                q.drain(..).collect()
-            };
-            if !messages.is_empty() {
-                let events_path = resolve_current_events_path(&ctx);
-
-                use std::io::Write;
-                let file = std::fs::OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&events_path);
-
-                let mut writer = match file {
-                    Ok(f) => std::io::BufWriter::new(f),
-                    Err(e) => {
-                        warn!(error = %e, path = ?events_path, "Failed to open events file for guidance flush");
-                        // Skip flushing - keep loop running
-                        continue;
-                    }
-                };
-
-                for msg in &messages {
-                    let timestamp = chrono::Utc::now().to_rfc3339();
-                    let event = serde_json::json!({
-                        "topic": "human.guidance",
-                        "payload": msg,
-                        "ts": timestamp,
-                    });
-
-                    match serde_json::to_string(&event) {
-                        Ok(line) => {
-                            if writeln!(writer, "{}", line).is_err() {
-                                warn!(path = ?events_path, "Failed writing guidance event line");
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            warn!(error = %e, "Failed serializing guidance event");
-                        }
-                    }
-                }
-                info!(
-                    count = messages.len(),
-                    "Wrote TUI guidance events to events.jsonl"
-                );
-            }
-        }
+        self.flush_guidance();
+        None
+    }
 
-        // Check termination before execution
-        if let Some(reason) = event_loop.check_termination() {
-            // Per spec: Publish loop.terminate event to observers
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            // Wait for user to exit TUI (press 'q') on natural completion
-            if let Some(handle) = tui_handle.take() {
-                let _ = handle.await;
-            }
-            return Ok(reason);
+    fn on_iteration_start(&mut self, request: &IterationRequest, event_loop: &EventLoop) {
+        let tui_active = self.tui_state.is_some();
+        if let Some(mut s) = self.tui_state.as_ref().and_then(|state| state.lock().ok()) {
+            s.queue_depth = event_loop.queue_depth();
         }
 
-        // Get next hat to execute, with fallback recovery if no pending events
-        let hat_id = match event_loop.next_hat() {
-            Some(id) => {
-                // Reset fallback counter on successful event routing
-                consecutive_fallbacks = 0;
-                id.clone()
-            }
-            None => {
-                // No pending events - try to recover by injecting a fallback event
-                // This triggers the built-in planner to assess the situation
-                consecutive_fallbacks += 1;
-
-                if consecutive_fallbacks > MAX_FALLBACK_ATTEMPTS {
-                    warn!(
-                        attempts = consecutive_fallbacks,
-                        "Fallback recovery exhausted after {} attempts, terminating",
-                        MAX_FALLBACK_ATTEMPTS
-                    );
-                    let reason = TerminationReason::Stopped;
-                    let terminate_event = event_loop.publish_terminate_event(&reason);
-                    log_terminate_event(
-                        &mut event_logger,
-                        event_loop.state().iteration,
-                        &terminate_event,
-                    );
-                    handle_termination(
-                        &reason,
-                        event_loop.state(),
-                        &config.core.scratchpad,
-                        &loop_history,
-                        &loop_context,
-                        auto_merge,
-                        &prompt_content,
-                    );
-                    // Wait for user to exit TUI (press 'q') on natural completion
-                    if let Some(handle) = tui_handle.take() {
-                        let _ = handle.await;
-                    }
-                    return Ok(reason);
-                }
-
-                if event_loop.inject_fallback_event() {
-                    // Fallback injected successfully, continue to next iteration
-                    // The planner will be triggered and can either:
-                    // - Dispatch more work if tasks remain
-                    // - Output LOOP_COMPLETE if done
-                    // - Determine what went wrong and recover
-                    continue;
-                }
-
-                // Fallback not possible (no planner hat or doesn't subscribe to task.resume)
-                warn!("No hats with pending events and fallback not available, terminating");
-                let reason = TerminationReason::Stopped;
-                // Per spec: Publish loop.terminate event to observers
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(
-                    &mut event_logger,
-                    event_loop.state().iteration,
-                    &terminate_event,
-                );
-                handle_termination(
-                    &reason,
-                    event_loop.state(),
-                    &config.core.scratchpad,
-                    &loop_history,
-                    &loop_context,
-                    auto_merge,
-                    &prompt_content,
-                );
-                // Wait for user to exit TUI (press 'q') on natural completion
-                if let Some(handle) = tui_handle.take() {
-                    let _ = handle.await;
-                }
-                return Ok(reason);
-            }
-        };
-
-        let iteration = event_loop.state().iteration + 1;
-
-        // Determine which hat to display in iteration separator
-        // When Ralph is coordinating (hat_id == "ralph"), show the active hat being worked on
-        let display_hat = if hat_id.as_str() == "ralph" {
-            event_loop.get_active_hat_id()
-        } else {
-            hat_id.clone()
-        };
-
         // Per spec: Print iteration demarcation separator
         // "Each iteration must be clearly demarcated in the output so users can
         // visually distinguish where one iteration ends and another begins."
         // Skip when TUI is enabled - TUI has its own header showing iteration info
-        if tui_state.is_none() {
+        self.loop_status
+            .start_iteration(request.iteration, request.active_hat.as_str());
+        if let Some(event_hooks) = &self.event_hooks {
+            event_hooks.set_iteration(request.iteration);
+        }
+
+        if let Some(stream) = &self.json_events {
+            stream.set_iteration(request.iteration);
+        } else if !tui_active {
             print_iteration_separator(
-                iteration,
-                display_hat.as_str(),
+                request.iteration,
+                request.active_hat.as_str(),
                 event_loop.state().elapsed(),
-                config.event_loop.max_iterations,
-                use_colors,
+                self.config.event_loop.max_iterations,
+                self.use_colors,
             );
         }
 
         // Log hat changes with appropriate messaging
         // Skip in TUI mode - TUI shows hat info in header, and stdout would corrupt display
-        if last_hat.as_ref() != Some(&hat_id) {
-            if tui_state.is_none() {
+        let hat_id = &request.hat_id;
+        if self.last_hat.as_ref() != Some(hat_id) {
+            if !tui_active {
                 if hat_id.as_str() == "ralph" {
                     info!("I'm Ralph. Let's do this.");
                 } else {
                     info!("Putting on my {} hat.", hat_id);
                 }
             }
-            last_hat = Some(hat_id.clone());
+            self.last_hat = Some(hat_id.clone());
         }
         debug!(
             "Iteration {}/{} - {} active",
-            iteration, config.event_loop.max_iterations, hat_id
+            request.iteration, self.config.event_loop.max_iterations, hat_id
         );
 
-        // Build prompt for this hat
-        let prompt = match event_loop.build_prompt(&hat_id) {
-            Some(p) => p,
-            None => {
-                error!("Failed to build prompt for hat '{}'", hat_id);
-                continue;
-            }
-        };
+        if let Some(recorder) = &self.session_recorder {
+            recorder.record_meta(Record::meta_iteration_start(
+                request.iteration,
+                request.active_hat.as_str(),
+            ));
+            recorder.record_meta(Record::meta_prompt(
+                request.iteration,
+                request.active_hat.as_str(),
+                &request.prompt,
+            ));
+            self.iteration_started = Some(Instant::now());
+        }
 
         // In verbose mode, print the full prompt before execution
-        if verbosity == Verbosity::Verbose {
+        if self.verbosity == Verbosity::Verbose {
             eprintln!("\n{}", "=".repeat(80));
-            eprintln!("PROMPT FOR {} (iteration {})", hat_id, iteration);
+            eprintln!("PROMPT FOR {} (iteration {})", hat_id, request.iteration);
             eprintln!("{}", "-".repeat(80));
-            eprintln!("{}", prompt);
+            eprintln!("{}", request.prompt);
             eprintln!("{}\n", "=".repeat(80));
         }
+    }
 
-        // Execute the prompt (interactive or autonomous mode)
-        // Determine which backend to use for this hat and the appropriate timeout
-        // Hat-level backend configuration takes precedence over global cli.backend
-
-        // Step 1: Get hat backend configuration for the active hat
-        // Use display_hat (the active hat) instead of hat_id ("ralph" in multi-hat mode)
-        let hat_backend_opt = event_loop.get_hat_backend(&display_hat);
-
-        // Step 2: Resolve effective backend and determine backend name for timeout
-        // Note: backend_name_for_timeout is owned String to avoid lifetime issues with hat_backend reference
-        let (effective_backend, backend_name_for_timeout): (CliBackend, String) =
-            match hat_backend_opt {
-                Some(hat_backend) => {
-                    // Hat has custom backend configuration
-                    match CliBackend::from_hat_backend(hat_backend) {
-                        Ok(hat_backend_instance) => {
-                            debug!(
-                                "Using hat-level backend for '{}': {:?}",
-                                display_hat, hat_backend
-                            );
-
-                            // Determine backend name for timeout based on hat backend type
-                            // Use owned String to avoid borrowing issues and improve code clarity
-                            let backend_name = match hat_backend {
-                                ralph_core::HatBackend::Named(name) => name.clone(),
-                                ralph_core::HatBackend::NamedWithArgs { backend_type, .. } => {
-                                    backend_type.clone()
-                                }
-                                ralph_core::HatBackend::KiroAgent { .. } => "kiro".to_string(),
-                                // For Custom backends, extract command name from path
-                                // Handles both Unix ("/usr/bin/codex") and commands with args ("ollama run llama3")
-                                ralph_core::HatBackend::Custom { command, .. } => {
-                                    // First split by whitespace to handle commands with arguments
-                                    // e.g., "ollama run llama3" -> "ollama"
-                                    let base_command =
-                                        command.split_whitespace().next().unwrap_or(command);
-                                    // Then extract filename from path
-                                    // e.g., "/usr/bin/codex" -> "codex"
-                                    std::path::Path::new(base_command)
-                                        .file_name()
-                                        .and_then(|s| s.to_str())
-                                        .unwrap_or("custom")
-                                        .to_string()
-                                }
-                            };
-
-                            (hat_backend_instance, backend_name)
-                        }
-                        Err(e) => {
-                            // Failed to create backend from hat config - fall back to global
-                            warn!(
-                                "Failed to create backend from hat configuration for '{}': {}. Falling back to global backend.",
-                                display_hat, e
-                            );
-                            // IMPORTANT: Use global backend name for timeout since we're using global backend
-                            (backend.clone(), config.cli.backend.clone())
-                        }
-                    }
-                }
-                None => {
-                    // No custom backend - use global configuration
-                    debug!(
-                        "Using global backend for '{}': {}",
-                        display_hat, config.cli.backend
-                    );
-                    (backend.clone(), config.cli.backend.clone())
-                }
-            };
-
-        // Step 3: Get timeout from config based on actual backend being used
-        let timeout_secs = config.adapter_settings(&backend_name_for_timeout).timeout;
-        let timeout = Some(Duration::from_secs(timeout_secs));
-
-        // For TUI mode, get the shared lines buffer for this iteration.
-        // The buffer is owned by TuiState's IterationBuffer, so writes from
-        // TuiStreamHandler appear immediately in the TUI (real-time streaming).
-        let hat_display = event_loop
-            .registry()
-            .get(&display_hat)
-            .map(|hat| hat.name.clone())
-            .unwrap_or_else(|| display_hat.as_str().to_string());
-
-        let tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>> =
-            if let Some(ref state) = tui_state {
-                // Start new iteration and get handle to the LATEST iteration's lines buffer.
-                // We must use latest_iteration_lines_handle() instead of current_iteration_lines_handle()
-                // because the user may be viewing an older iteration while a new one executes.
-                prepare_tui_iteration(
-                    state,
-                    hat_display.clone(),
-                    backend_name_for_timeout.clone(),
-                    config.event_loop.max_iterations,
-                )
-            } else {
-                None
-            };
-
-        // Race execution against interrupt signal for immediate termination on Ctrl+C
-        let mut interrupt_rx_clone = interrupt_rx.clone();
-        let interrupt_rx_for_pty = interrupt_rx.clone();
-        let tui_lines_for_pty = tui_lines.clone();
-        let execute_future = async {
-            if use_pty {
-                execute_pty(
-                    pty_executor.as_mut(),
-                    &effective_backend,
-                    &config,
-                    &prompt,
-                    user_interactive,
-                    interrupt_rx_for_pty,
-                    verbosity,
-                    tui_lines_for_pty,
-                )
-                .await
-            } else {
-                let executor = CliExecutor::new(effective_backend.clone());
-                let result = executor
-                    .execute(&prompt, stdout(), timeout, verbosity == Verbosity::Verbose)
-                    .await?;
-                Ok(ExecutionOutcome {
-                    output: result.output,
-                    success: result.success,
-                    termination: None,
-                })
-            }
-        };
-
-        let outcome = tokio::select! {
-            result = execute_future => result?,
-            _ = interrupt_rx_clone.changed() => {
-                // Immediately terminate children via process group signal
-                #[cfg(unix)]
-                {
-                    use nix::sys::signal::{killpg, Signal};
-                    use nix::unistd::getpgrp;
-                    let pgid = getpgrp();
-                    debug!("Sending SIGTERM to process group {}", pgid);
-                    let _ = killpg(pgid, Signal::SIGTERM);
-
-                    // Wait briefly for graceful exit, then SIGKILL
-                    tokio::time::sleep(Duration::from_millis(250)).await;
-                    let _ = killpg(pgid, Signal::SIGKILL);
-                }
-
-                let reason = TerminationReason::Interrupted;
-                let terminate_event = event_loop.publish_terminate_event(&reason);
-                log_terminate_event(&mut event_logger, event_loop.state().iteration, &terminate_event);
-                handle_termination(&reason, event_loop.state(), &config.core.scratchpad, &loop_history, &loop_context, auto_merge, &prompt_content);
-                // Signal TUI to exit immediately on interrupt
-                let _ = terminated_tx.send(true);
-                return Ok(reason);
-            }
-        };
-
-        if let Some(reason) = outcome.termination {
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            // Wait for user to exit TUI (press 'q') on natural completion
-            if let Some(handle) = tui_handle.take() {
-                let _ = handle.await;
-            }
-            return Ok(reason);
-        }
-
-        let output = outcome.output;
-        let success = outcome.success;
-
-        // Note: TUI lines are now written directly to IterationBuffer during streaming,
+    fn on_output(&mut self, request: &IterationRequest, output: &str, event_loop: &EventLoop) {
+        // Note: TUI lines are written directly to IterationBuffer during streaming,
         // so no post-execution transfer is needed.
-        if let Some(mut s) = tui_state.as_ref().and_then(|state| state.lock().ok()) {
+        if let Some(mut s) = self.tui_state.as_ref().and_then(|state| state.lock().ok()) {
             s.finish_latest_iteration();
         }
 
+        self.last_output = output.to_string();
+
+        if let Some(recorder) = &self.session_recorder {
+            let elapsed_ms = self
+                .iteration_started
+                .take()
+                .map_or(0, |started| started.elapsed().as_millis() as u64);
+            recorder.record_meta(Record::meta_iteration_end(
+                request.iteration,
+                request.active_hat.as_str(),
+                elapsed_ms,
+            ));
+        }
+
         // Log events from output before processing
         log_events_from_output(
-            &mut event_logger,
-            iteration,
-            &hat_id,
-            &output,
+            &mut self.event_logger,
+            request.iteration,
+            &request.hat_id,
+            output,
             event_loop.registry(),
         );
+    }
 
-        // Process output
-        if let Some(reason) = event_loop.process_output(&hat_id, &output, success) {
-            // Per spec: Log "All done! {promise} detected." when completion promise found
-            if reason == TerminationReason::CompletionPromise {
-                info!(
-                    "All done! {} detected.",
-                    config.event_loop.completion_promise
-                );
-            }
-            // Per spec: Publish loop.terminate event to observers
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            // Wait for user to exit TUI (press 'q') on natural completion
-            if let Some(handle) = tui_handle.take() {
-                let _ = handle.await;
-            }
-            return Ok(reason);
-        }
-
+    fn after_output(&mut self, event_loop: &mut EventLoop) {
         // Check for planning session user responses (if in planning mode)
-        if let Err(e) = check_planning_session_responses(&mut event_loop) {
+        if let Err(e) = check_planning_session_responses(event_loop) {
             warn!(error = %e, "Failed to check planning session responses");
         }
 
-        // Read events from JSONL that agent may have written
-        let agent_wrote_events = matches!(
-            event_loop
-                .process_events_from_jsonl()
-                .inspect_err(|e| warn!(error = %e, "Failed to read events from JSONL")),
-            Ok(true)
-        );
+        // The iteration's events have been published by now
+        self.loop_status.write();
+    }
 
-        // Inject default_publishes for active hats only when agent wrote no events
-        if !agent_wrote_events {
-            let active_hats = event_loop.state().last_active_hat_ids.clone();
-            for active_hat_id in &active_hats {
-                event_loop.check_default_publishes(active_hat_id);
-                if event_loop.has_pending_events() {
-                    break; // One default is sufficient
-                }
-            }
-        }
+    fn on_pause_changed(&mut self, event: &Event, event_loop: &EventLoop) {
+        log_loop_event(&mut self.event_logger, event_loop.state().iteration, event);
+    }
 
-        if let Some(reason) = event_loop.check_completion_event() {
-            info!(
-                "Completion event {} detected.",
-                config.event_loop.completion_promise
-            );
+    fn on_completion_check(&mut self, event: &Event, event_loop: &EventLoop) {
+        log_loop_event(&mut self.event_logger, event_loop.state().iteration, event);
+    }
 
-            let terminate_event = event_loop.publish_terminate_event(&reason);
-            log_terminate_event(
-                &mut event_logger,
-                event_loop.state().iteration,
-                &terminate_event,
-            );
-            handle_termination(
-                &reason,
-                event_loop.state(),
-                &config.core.scratchpad,
-                &loop_history,
-                &loop_context,
-                auto_merge,
-                &prompt_content,
-            );
-            if let Some(handle) = tui_handle.take() {
-                let _ = handle.await;
+    fn on_event_unhandled(&mut self, event: &Event, event_loop: &EventLoop) {
+        log_loop_event(&mut self.event_logger, event_loop.state().iteration, event);
+    }
+
+    fn on_terminate(
+        &mut self,
+        reason: &TerminationReason,
+        terminate_event: &Event,
+        event_loop: &EventLoop,
+    ) {
+        log_terminate_event(
+            &mut self.event_logger,
+            event_loop.state().iteration,
+            terminate_event,
+        );
+        if let Some(recorder) = &self.session_recorder {
+            let state = event_loop.state();
+            recorder.record_meta(Record::meta_termination(
+                reason.as_str(),
+                state.iteration,
+                state.elapsed().as_secs_f64(),
+                recorder.ux_write_count(),
+            ));
+            if let Err(e) = recorder.flush() {
+                warn!("Failed to flush session recording: {}", e);
             }
-            return Ok(reason);
         }
+        self.handle_termination(reason, event_loop.state());
+    }
 
-        // Precheck validation: Warn if no pending events after processing output
-        // Per EventLoop doc: "Use has_pending_events after process_output to detect
-        // if the LLM failed to publish an event."
-        if !event_loop.has_pending_events() {
-            let expected = event_loop.get_hat_publishes(&hat_id);
-            debug!(
-                hat = %hat_id.as_str(),
-                expected_topics = ?expected,
-                "No pending events after iteration. Agent may have failed to publish a valid event. \
-                 Expected one of: {:?}. Loop will terminate on next iteration.",
-                expected
-            );
-        }
+    fn on_failure_event(&mut self, event: &Event, event_loop: &EventLoop) {
+        log_loop_event(&mut self.event_logger, event_loop.state().iteration, event);
+    }
+}
 
-        // Cooldown delay between iterations (skip for human events)
-        let cooldown = config.event_loop.cooldown_delay_seconds;
-        if cooldown > 0 && !event_loop.has_pending_human_events() {
-            debug!(
-                delay_seconds = cooldown,
-                "Cooldown delay before next iteration"
-            );
-            tokio::time::sleep(Duration::from_secs(cooldown)).await;
+/// A session recorder shared between the loop's hooks and executor.
+type SessionRecording = Arc<SessionRecorder<Box<dyn Write + Send>>>;
+
+/// Stream handler wrapper that records tool results to the session
+/// recording before passing every event on to `inner`.
+struct RecordingStreamHandler<H> {
+    inner: H,
+    /// The recorder and the iteration being streamed.
+    recording: Option<(SessionRecording, u32)>,
+}
+
+impl<H> RecordingStreamHandler<H> {
+    fn new(inner: H, recording: Option<(SessionRecording, u32)>) -> Self {
+        Self { inner, recording }
+    }
+}
+
+impl<H: StreamHandler> StreamHandler for RecordingStreamHandler<H> {
+    fn on_text(&mut self, text: &str) {
+        self.inner.on_text(text);
+    }
+
+    fn on_tool_call(&mut self, name: &str, id: &str, input: &serde_json::Value) {
+        self.inner.on_tool_call(name, id, input);
+    }
+
+    fn on_tool_result(&mut self, id: &str, output: &str) {
+        if let Some((recorder, iteration)) = &self.recording {
+            recorder.record_tool_result(*iteration, id, output);
         }
+        self.inner.on_tool_result(id, output);
+    }
+
+    fn on_error(&mut self, error: &str) {
+        self.inner.on_error(error);
+    }
+
+    fn on_complete(&mut self, result: &SessionResult) {
+        self.inner.on_complete(result);
+    }
+}
+
+/// Estimated spend of a backend session, from the usage in its stream-json
+/// output. Backends that report no usage cost nothing.
+fn session_cost(backend: &CliBackend, config: &RalphConfig, output: &str) -> f64 {
+    if backend.output_format != BackendOutputFormat::StreamJson {
+        return 0.0;
+    }
+    let mut parser = StreamJsonParser::new();
+    let mut events: Vec<_> = output
+        .lines()
+        .flat_map(|line| parser.parse_line(line))
+        .collect();
+    events.extend(parser.finish());
+    estimate_cost(&events, &config.cli).unwrap_or(0.0)
+}
+
+/// Summarizes why a backend process that exited on its own failed.
+fn exit_failure(success: bool, exit_code: Option<i32>) -> Option<String> {
+    if success {
+        return None;
     }
+    Some(match exit_code {
+        Some(code) => format!("backend exited with code {code}"),
+        None => "backend was killed by a signal".to_string(),
+    })
 }
 
 /// Executes a prompt in PTY mode with raw terminal handling.
@@ -1218,7 +1561,8 @@ async fn execute_pty(
     interrupt_rx: tokio::sync::watch::Receiver<bool>,
     verbosity: Verbosity,
     tui_lines: Option<Arc<std::sync::Mutex<Vec<ratatui::text::Line<'static>>>>>,
-) -> Result<ExecutionOutcome> {
+    recording: Option<(SessionRecording, u32)>,
+) -> Result<IterationOutcome> {
     use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 
     // Use provided executor or create a new one
@@ -1274,7 +1618,8 @@ async fn execute_pty(
     } else if let Some(lines) = tui_lines {
         // TUI mode: use TuiStreamHandler to capture output for TUI display
         let verbose = verbosity == Verbosity::Verbose;
-        let mut handler = TuiStreamHandler::with_lines(verbose, lines);
+        let mut handler =
+            RecordingStreamHandler::new(TuiStreamHandler::with_lines(verbose, lines), recording);
         exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
             .await
     } else {
@@ -1286,28 +1631,32 @@ async fn execute_pty(
 
         match verbosity {
             Verbosity::Quiet => {
-                let mut handler = QuietStreamHandler;
+                let mut handler = RecordingStreamHandler::new(QuietStreamHandler, recording);
                 exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
                     .await
             }
             Verbosity::Normal => {
                 if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(false);
+                    let mut handler =
+                        RecordingStreamHandler::new(PrettyStreamHandler::new(false), recording);
                     exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
                         .await
                 } else {
-                    let mut handler = ConsoleStreamHandler::new(false);
+                    let mut handler =
+                        RecordingStreamHandler::new(ConsoleStreamHandler::new(false), recording);
                     exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
                         .await
                 }
             }
             Verbosity::Verbose => {
                 if use_pretty {
-                    let mut handler = PrettyStreamHandler::new(true);
+                    let mut handler =
+                        RecordingStreamHandler::new(PrettyStreamHandler::new(true), recording);
                     exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
                         .await
                 } else {
-                    let mut handler = ConsoleStreamHandler::new(true);
+                    let mut handler =
+                        RecordingStreamHandler::new(ConsoleStreamHandler::new(true), recording);
                     exec.run_observe_streaming(prompt, interrupt_rx, &mut handler)
                         .await
                 }
@@ -1317,7 +1666,13 @@ async fn execute_pty(
 
     match result {
         Ok(pty_result) => {
+            let failure = if pty_result.termination == ralph_adapters::TerminationType::Natural {
+                exit_failure(pty_result.success, pty_result.exit_code)
+            } else {
+                None
+            };
             let termination = convert_termination_type(pty_result.termination, interactive);
+            let cost_usd = session_cost(backend, config, &pty_result.stripped_output);
 
             // Use extracted_text for event parsing when available (NDJSON backends like Claude),
             // otherwise fall back to stripped_output (non-JSON backends or interactive mode).
@@ -1328,10 +1683,12 @@ async fn execute_pty(
             } else {
                 pty_result.extracted_text
             };
-            Ok(ExecutionOutcome {
+            Ok(IterationOutcome {
                 output: output_for_parsing,
                 success: pty_result.success,
                 termination,
+                failure,
+                cost_usd,
             })
         }
         Err(e) => {
@@ -1423,6 +1780,17 @@ fn log_terminate_event(logger: &mut EventLogger, iteration: u32, event: &Event)
     }
 }
 
+/// Logs a loop-published event (`loop.paused`, `loop.resumed`,
+/// `event.unhandled`, `event.duplicate`, or the `on_failure_emit` event) to
+/// the event history.
+fn log_loop_event(logger: &mut EventLogger, iteration: u32, event: &Event) {
+    let record = EventRecord::new(iteration, "loop", event, None::<&HatId>);
+
+    if let Err(e) = logger.log(&record) {
+        warn!("Failed to log {} event: {}", event.topic, e);
+    }
+}
+
 /// Gets the last commit info (short SHA and subject) for the summary file.
 fn get_last_commit_info_with_cmd(git_cmd: &OsStr) -> Option<String> {
     let output = Command::new(git_cmd)
@@ -1451,44 +1819,36 @@ fn get_last_commit_info() -> Option<String> {
 /// 4. Config event_loop.prompt_file (prompt file path)
 /// 5. Default PROMPT.md
 ///
+/// `{{name}}` placeholders are then filled from `vars`.
+///
 /// Note: CLI overrides are already applied to config before this function is called.
-fn resolve_prompt_content(event_loop_config: &ralph_core::EventLoopConfig) -> Result<String> {
+pub(crate) fn resolve_prompt_content(
+    event_loop_config: &ralph_core::EventLoopConfig,
+    vars: &BTreeMap<String, String>,
+) -> Result<String> {
+    let template = read_prompt_source(event_loop_config)?;
+    ralph_core::render_prompt(&template, vars).map_err(|e| {
+        anyhow::anyhow!(
+            "{}. Pass values with --var KEY=VALUE or set them under `vars:` in ralph.yml.",
+            e
+        )
+    })
+}
+
+/// Reads the raw prompt text from the inline prompt or prompt file.
+///
+/// Relative prompt files resolve against the current directory.
+fn read_prompt_source(event_loop_config: &ralph_core::EventLoopConfig) -> Result<String> {
     debug!(
         inline_prompt = ?event_loop_config.prompt.as_ref().map(|s| format!("{}...", &s[..s.len().min(50)])),
         prompt_file = %event_loop_config.prompt_file,
         "Resolving prompt content"
     );
 
-    // Check for inline prompt first (CLI -p or config prompt)
-    if let Some(ref inline_text) = event_loop_config.prompt {
-        debug!(len = inline_text.len(), "Using inline prompt text");
-        return Ok(inline_text.clone());
-    }
-
-    // Check for prompt file (CLI -P or config prompt_file or default)
-    let prompt_file = &event_loop_config.prompt_file;
-    if !prompt_file.is_empty() {
-        let path = std::path::Path::new(prompt_file);
-        debug!(path = %prompt_file, exists = path.exists(), "Checking prompt file");
-        if path.exists() {
-            let content = std::fs::read_to_string(path)
-                .with_context(|| format!("Failed to read prompt file: {}", prompt_file))?;
-            debug!(path = %prompt_file, len = content.len(), "Read prompt from file");
-            return Ok(content);
-        } else {
-            // File specified but doesn't exist - error with helpful message
-            anyhow::bail!(
-                "Prompt file '{}' not found. Check the path or use -p \"text\" for inline prompt.",
-                prompt_file
-            );
-        }
-    }
-
-    // No valid prompt source found
-    anyhow::bail!(
-        "No prompt specified. Use -p \"text\" for inline prompt, -P path for file, \
-         or create PROMPT.md in the current directory."
-    )
+    let working_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let content = ralph_core::read_prompt_source(event_loop_config, &working_dir)?;
+    debug!(len = content.len(), "Resolved prompt content");
+    Ok(content)
 }
 
 /// Checks for planning session user responses and publishes them as events.
@@ -1593,8 +1953,13 @@ fn check_planning_session_responses_for_session(
 /// Processes pending merges from the merge queue.
 ///
 /// Called when the primary loop completes successfully. Spawns merge-ralph
-/// processes for each queued loop in FIFO order.
-fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
+/// processes for each queued loop in FIFO order; a loop whose branch
+/// conflicts with main is handled according to `policy`.
+fn process_pending_merges_with_command(
+    repo_root: &Path,
+    ralph_cmd: &OsStr,
+    policy: AutoMergeConflictPolicy,
+) {
     let queue = MergeQueue::new(repo_root);
 
     // Get all pending merges
@@ -1627,7 +1992,7 @@ fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
 
     // Write the merge config once (shared by all merge loops)
     let config_path = repo_root.join(".ralph/merge-loop-config.yml");
-    if let Err(e) = fs::write(&config_path, preset.content) {
+    if let Err(e) = fs::write(&config_path, preset.content.as_bytes()) {
         warn!(
             error = %e,
             "Failed to write merge config, pending merges will remain queued"
@@ -1639,9 +2004,15 @@ fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
     for entry in pending {
         let loop_id = &entry.loop_id;
 
+        let resolution = match plan_merge(repo_root, &queue, loop_id, policy) {
+            MergePlan::Spawn(resolution) => resolution,
+            MergePlan::SetAside => continue,
+        };
+
         info!(loop_id = %loop_id, "Spawning merge-ralph process");
 
-        match Command::new(ralph_cmd)
+        let mut command = Command::new(ralph_cmd);
+        command
             .current_dir(repo_root)
             .args([
                 "run",
@@ -1652,9 +2023,12 @@ fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
                 "-p",
                 &format!("Merge loop {} from branch ralph/{}", loop_id, loop_id),
             ])
-            .env("RALPH_MERGE_LOOP_ID", loop_id)
-            .spawn()
-        {
+            .env("RALPH_MERGE_LOOP_ID", loop_id);
+        if let Some(resolution) = resolution {
+            command.env("RALPH_MERGE_RESOLUTION", resolution);
+        }
+
+        match command.spawn() {
             Ok(child) => {
                 info!(
                     loop_id = %loop_id,
@@ -1673,15 +2047,121 @@ fn process_pending_merges_with_command(repo_root: &Path, ralph_cmd: &OsStr) {
     }
 }
 
-fn process_pending_merges(repo_root: &Path) {
-    process_pending_merges_with_command(repo_root, OsStr::new("ralph"));
+/// What to do with a queued loop.
+#[derive(Debug, PartialEq, Eq)]
+enum MergePlan {
+    /// Spawn merge-ralph, with `RALPH_MERGE_RESOLUTION` if set.
+    Spawn(Option<&'static str>),
+    /// Leave the loop in needs-review for manual handling.
+    SetAside,
+}
+
+/// Applies the conflict policy to a queued loop.
+///
+/// Loops that merge cleanly, or whose merge cannot be previewed, go to
+/// merge-ralph as before.
+fn plan_merge(
+    repo_root: &Path,
+    queue: &MergeQueue,
+    loop_id: &str,
+    policy: AutoMergeConflictPolicy,
+) -> MergePlan {
+    let branch = format!("ralph/{}", loop_id);
+    let preview = match preview_merge(repo_root, "main", &branch) {
+        Ok(preview) => preview,
+        Err(e) => {
+            debug!(loop_id = %loop_id, error = %e, "Could not preview merge, leaving it to merge-ralph");
+            return MergePlan::Spawn(None);
+        }
+    };
+    if !preview.has_conflicts() {
+        return MergePlan::Spawn(None);
+    }
+
+    match policy {
+        AutoMergeConflictPolicy::PreferOurs => return MergePlan::Spawn(Some("ours")),
+        AutoMergeConflictPolicy::PreferTheirs => return MergePlan::Spawn(Some("theirs")),
+        AutoMergeConflictPolicy::Keep => {
+            if let Err(e) = keep_conflicts(repo_root, loop_id, &branch, &preview) {
+                warn!(loop_id = %loop_id, error = %e, "Failed to record merge conflicts");
+            }
+        }
+        AutoMergeConflictPolicy::Abort => {}
+    }
+
+    let reason = format!(
+        "Conflicts with main in {} (auto_merge_conflict: {})",
+        preview.conflicted.join(", "),
+        policy.as_str()
+    );
+    // needs-review is only reachable from merging
+    if let Err(e) = queue
+        .mark_merging(loop_id, std::process::id())
+        .and_then(|()| queue.mark_needs_review(loop_id, &reason))
+    {
+        warn!(loop_id = %loop_id, error = %e, "Failed to mark loop for review");
+    }
+    warn!(
+        loop_id = %loop_id,
+        conflicted = ?preview.conflicted,
+        "Loop conflicts with main, left for manual merge"
+    );
+    MergePlan::SetAside
+}
+
+/// Saves the conflicted files, markers included, under
+/// `.ralph/merge-conflicts/<loop_id>/` and emits `merge.failed` on the
+/// primary loop's events file.
+fn keep_conflicts(
+    repo_root: &Path,
+    loop_id: &str,
+    branch: &str,
+    preview: &MergePreview,
+) -> Result<()> {
+    let dir = repo_root.join(".ralph/merge-conflicts").join(loop_id);
+    for file in &preview.conflicted {
+        let content = merged_file_content(repo_root, preview, file)
+            .with_context(|| format!("Failed to read merged {}", file))?;
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+    }
+
+    let payload = serde_json::json!({
+        "loop_id": loop_id,
+        "branch": branch,
+        "target": "main",
+        "policy": AutoMergeConflictPolicy::Keep.as_str(),
+        "conflicted": preview.conflicted,
+        "markers": dir.strip_prefix(repo_root).unwrap_or(&dir),
+    });
+    // Built directly rather than via EventRecord::new, which truncates long payloads
+    let record = EventRecord {
+        ts: chrono::Utc::now().to_rfc3339(),
+        iteration: 0,
+        hat: "loops".to_string(),
+        topic: "merge.failed".to_string(),
+        triggered: None,
+        payload: payload.to_string(),
+        blocked_count: None,
+    };
+    let mut logger = EventLogger::from_context(&LoopContext::primary(repo_root.to_path_buf()));
+    logger
+        .log(&record)
+        .context("Failed to record merge.failed event")
+}
+
+fn process_pending_merges(repo_root: &Path, policy: AutoMergeConflictPolicy) {
+    process_pending_merges_with_command(repo_root, OsStr::new("ralph"), policy);
 }
 
 /// Public wrapper for CLI invocation of process_pending_merges.
 ///
 /// Called by `ralph loops process` command to process the merge queue.
-pub fn process_pending_merges_cli(repo_root: &Path) {
-    process_pending_merges(repo_root);
+pub fn process_pending_merges_cli(repo_root: &Path, policy: AutoMergeConflictPolicy) {
+    process_pending_merges(repo_root, policy);
 }
 
 /// Start a loop from an external caller (e.g., the bot daemon).
@@ -1730,10 +2210,7 @@ pub async fn start_loop(
 
     // Auto-detect backend if needed
     if config.cli.backend == "auto" {
-        let priority = config.get_agent_priority();
-        let detected = ralph_adapters::detect_backend(&priority, |backend| {
-            config.adapter_settings(backend).enabled
-        });
+        let detected = ralph_adapters::detect_configured_backend(&config);
         match detected {
             Ok(backend) => {
                 info!("Auto-detected backend: {}", backend);
@@ -1756,24 +2233,31 @@ pub async fn start_loop(
     let loop_context = ralph_core::LoopContext::primary(workspace_root);
 
     // Run the loop headlessly
-    run_loop_impl(
+    Box::pin(run_loop_impl(
         config,
         ColorMode::Never,
         false, // not resume
         false, // no TUI
         Verbosity::Normal,
-        None, // no session recording
+        None,  // no session recording
+        false, // no JSON event stream
         Some(loop_context),
         Vec::new(), // no custom args
         None,       // default auto-merge
-    )
+        Vec::new(), // daemon config comes from its own sources
+    ))
     .await
 }
 
-/// Creates a robot service (Telegram) for human-in-the-loop communication.
+/// Creates the robot service for human-in-the-loop communication.
+///
+/// Starts every configured transport: Telegram unless only Slack is set up
+/// (or it has a token alongside Slack), and Slack when `RObot.slack` is set.
+/// Two transports are combined with [`ralph_core::RobotFanout`], so messages
+/// reach both and the first answer wins.
 ///
 /// Called by `run_loop_impl` when `robot.enabled` is true and this is the primary loop.
-/// Returns `None` if the service cannot be created or started.
+/// Returns `None` if no service can be created or started.
 fn create_robot_service(
     config: &RalphConfig,
     context: &LoopContext,
@@ -1786,8 +2270,49 @@ fn create_robot_service(
         .map(String::from)
         .unwrap_or_else(|| "main".to_string());
 
+    let mut services: Vec<Box<dyn ralph_proto::RobotService>> = Vec::new();
+
+    if (bot_token.is_some() || config.robot.slack.is_none())
+        && let Some(service) = create_telegram_service(
+            config,
+            workspace_root.clone(),
+            bot_token,
+            timeout_secs,
+            loop_id.clone(),
+        )
+    {
+        services.push(Box::new(service));
+    }
+
+    if let Some(slack) = &config.robot.slack
+        && let Some(service) =
+            create_slack_service(config, slack, workspace_root, timeout_secs, loop_id)
+    {
+        services.push(Box::new(service));
+    }
+
+    if services.len() > 1 {
+        Some(Box::new(ralph_core::RobotFanout::new(services)))
+    } else {
+        services.pop()
+    }
+}
+
+/// Creates and starts the Telegram transport.
+fn create_telegram_service(
+    config: &RalphConfig,
+    workspace_root: std::path::PathBuf,
+    bot_token: Option<String>,
+    timeout_secs: u64,
+    loop_id: String,
+) -> Option<ralph_telegram::TelegramService> {
     match ralph_telegram::TelegramService::new(workspace_root, bot_token, timeout_secs, loop_id) {
         Ok(service) => {
+            let notifications = &config.robot.notifications;
+            // The start notification supersedes the generic "bot online" greeting
+            let service = service
+                .with_notification_limit(notifications.max_per_hour)
+                .with_presence_messages(!notifications.start);
             if let Err(e) = service.start() {
                 warn!(error = %e, "Failed to start robot service");
                 return None;
@@ -1797,7 +2322,7 @@ fn create_robot_service(
                 timeout_secs = service.timeout_secs(),
                 "Robot human-in-the-loop service active"
             );
-            Some(Box::new(service))
+            Some(service)
         }
         Err(e) => {
             warn!(error = %e, "Failed to create robot service");
@@ -1806,6 +2331,114 @@ fn create_robot_service(
     }
 }
 
+/// Creates and starts the Slack transport, including its Events API listener.
+fn create_slack_service(
+    config: &RalphConfig,
+    slack: &ralph_core::SlackBotConfig,
+    workspace_root: std::path::PathBuf,
+    timeout_secs: u64,
+    loop_id: String,
+) -> Option<ralph_slack::SlackService> {
+    let service = match new_slack_service(slack, workspace_root, timeout_secs, loop_id) {
+        Ok(service) => service.with_notification_limit(config.robot.notifications.max_per_hour),
+        Err(e) => {
+            warn!(error = %e, "Failed to create Slack robot service");
+            return None;
+        }
+    };
+    match service.start() {
+        Ok(addr) => {
+            info!(
+                bot_token = %service.bot_token_masked(),
+                listen = %addr,
+                timeout_secs = service.timeout_secs(),
+                "Slack human-in-the-loop service active"
+            );
+            Some(service)
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to start Slack robot service");
+            None
+        }
+    }
+}
+
+fn new_slack_service(
+    slack: &ralph_core::SlackBotConfig,
+    workspace_root: std::path::PathBuf,
+    timeout_secs: u64,
+    loop_id: String,
+) -> ralph_slack::SlackResult<ralph_slack::SlackService> {
+    ralph_slack::SlackService::new(
+        workspace_root,
+        slack.resolve_bot_token(),
+        slack.resolve_signing_secret(),
+        slack.channel_id.clone().unwrap_or_default(),
+        slack.listen.clone(),
+        timeout_secs,
+        loop_id,
+    )
+}
+
+/// Tells the human (via Telegram and/or Slack) that preflight checks kept
+/// the loop from starting.
+///
+/// Best effort: failures are logged, never surfaced.
+pub(crate) fn notify_preflight_failure(config: &RalphConfig, context: &LoopContext, detail: &str) {
+    if !config.robot.enabled || !config.robot.notifications.preflight || !context.is_primary() {
+        return;
+    }
+
+    let loop_id = context
+        .loop_id()
+        .map(String::from)
+        .unwrap_or_else(|| "main".to_string());
+    let timeout_secs = config.robot.timeout_seconds.unwrap_or(300);
+    let max_per_hour = config.robot.notifications.max_per_hour;
+    let notification = ralph_proto::LifecycleNotification::PreflightFailed {
+        detail: detail.to_string(),
+    };
+
+    let bot_token = config.robot.resolve_bot_token();
+    if bot_token.is_some() || config.robot.slack.is_none() {
+        match ralph_telegram::TelegramService::new(
+            context.workspace().to_path_buf(),
+            bot_token,
+            timeout_secs,
+            loop_id.clone(),
+        ) {
+            Ok(service) => {
+                let service = service.with_notification_limit(max_per_hour);
+                if let Err(e) = service.send_notification(&notification) {
+                    warn!(error = %e, "Failed to send preflight failure notification");
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to create robot service for preflight notification");
+            }
+        }
+    }
+
+    if let Some(slack) = &config.robot.slack {
+        match new_slack_service(
+            slack,
+            context.workspace().to_path_buf(),
+            timeout_secs,
+            loop_id,
+        ) {
+            Ok(service) => {
+                let service = service.with_notification_limit(max_per_hour);
+                if let Err(e) = service.send_notification(&notification) {
+                    warn!(error = %e, "Failed to send preflight failure notification to Slack");
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to create Slack service for preflight notification");
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2018,7 +2651,7 @@ mod tests {
         let repo_root = temp_dir.path();
         std::fs::create_dir_all(repo_root.join(".ralph/merge-queue")).expect("queue dir");
 
-        process_pending_merges(repo_root);
+        process_pending_merges(repo_root, AutoMergeConflictPolicy::default());
     }
 
     #[cfg(unix)]
@@ -2039,7 +2672,11 @@ mod tests {
         std::fs::create_dir_all(&bin_dir).expect("bin dir");
         let ralph_path = write_fake_executable(&bin_dir, "ralph", "exit 0");
 
-        process_pending_merges_with_command(repo_root, ralph_path.as_os_str());
+        process_pending_merges_with_command(
+            repo_root,
+            ralph_path.as_os_str(),
+            AutoMergeConflictPolicy::default(),
+        );
     }
 
     #[test]
@@ -2049,7 +2686,11 @@ mod tests {
         let queue = ralph_core::merge_queue::MergeQueue::new(repo_root);
         queue.enqueue("loop-9999", "merge prompt").expect("enqueue");
 
-        process_pending_merges_with_command(repo_root, OsStr::new("ralph-command-missing-12345"));
+        process_pending_merges_with_command(
+            repo_root,
+            OsStr::new("ralph-command-missing-12345"),
+            AutoMergeConflictPolicy::default(),
+        );
 
         let config_path = repo_root.join(".ralph/merge-loop-config.yml");
         assert!(config_path.exists());
@@ -2069,18 +2710,180 @@ mod tests {
         let config_path = repo_root.join(".ralph/merge-loop-config.yml");
         assert!(!config_path.exists());
 
-        process_pending_merges_with_command(repo_root, OsStr::new("ralph"));
+        process_pending_merges_with_command(
+            repo_root,
+            OsStr::new("ralph"),
+            AutoMergeConflictPolicy::default(),
+        );
 
         assert!(!config_path.exists());
     }
 
+    #[cfg(unix)]
+    /// Repo on `main` whose `ralph/<loop_id>` branch conflicts with it in
+    /// README.md, with the loop queued for merge.
+    fn seed_conflicting_loop(repo_root: &Path, loop_id: &str) -> MergeQueue {
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=Test User",
+                    "-c",
+                    "user.email=test@example.com",
+                ])
+                .args(args)
+                .current_dir(repo_root)
+                .output()
+                .expect("git");
+            assert!(
+                output.status.success(),
+                "git {args:?}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        };
+        let commit = |content: &str| {
+            std::fs::write(repo_root.join("README.md"), content).expect("write README");
+            git(&["add", "README.md"]);
+            git(&["commit", "-q", "-m", content]);
+        };
+
+        git(&["init", "-q", "-b", "main"]);
+        commit("# Base");
+        git(&["checkout", "-q", "-b", &format!("ralph/{loop_id}")]);
+        commit("# From loop");
+        git(&["checkout", "-q", "main"]);
+        commit("# From main");
+
+        let queue = MergeQueue::new(repo_root);
+        queue.enqueue(loop_id, "merge prompt").expect("enqueue");
+        queue
+    }
+
+    #[cfg(unix)]
+    /// Fake `ralph` that records the resolution it was spawned with.
+    fn resolution_recorder(repo_root: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let bin_dir = repo_root.join(".ralph/bin");
+        std::fs::create_dir_all(&bin_dir).expect("bin dir");
+        let out = repo_root.join(".ralph/resolution");
+        let body = format!("echo \"$RALPH_MERGE_RESOLUTION\" > {}", out.display());
+        (write_fake_executable(&bin_dir, "ralph", &body), out)
+    }
+
+    #[cfg(unix)]
+    fn wait_for_file(path: &Path) -> String {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if let Ok(content) = std::fs::read_to_string(path)
+                && !content.is_empty()
+            {
+                return content;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("{} was never written", path.display());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_conflict_policy_abort_leaves_loop_for_review() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo_root = temp_dir.path();
+        let queue = seed_conflicting_loop(repo_root, "loop-1");
+        let (ralph, _) = resolution_recorder(repo_root);
+
+        process_pending_merges_with_command(
+            repo_root,
+            ralph.as_os_str(),
+            AutoMergeConflictPolicy::Abort,
+        );
+
+        let entry = queue.get_entry("loop-1").unwrap().unwrap();
+        assert_eq!(
+            entry.state,
+            ralph_core::merge_queue::MergeState::NeedsReview
+        );
+        assert!(!repo_root.join(".ralph/merge-conflicts").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_conflict_policy_keep_records_markers_and_merge_failed() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo_root = temp_dir.path();
+        let queue = seed_conflicting_loop(repo_root, "loop-1");
+        let (ralph, _) = resolution_recorder(repo_root);
+
+        process_pending_merges_with_command(
+            repo_root,
+            ralph.as_os_str(),
+            AutoMergeConflictPolicy::Keep,
+        );
+
+        let entry = queue.get_entry("loop-1").unwrap().unwrap();
+        assert_eq!(
+            entry.state,
+            ralph_core::merge_queue::MergeState::NeedsReview
+        );
+
+        let kept =
+            std::fs::read_to_string(repo_root.join(".ralph/merge-conflicts/loop-1/README.md"))
+                .expect("conflicted file kept");
+        assert!(kept.contains("<<<<<<<"), "{kept}");
+        assert!(kept.contains("# From main") && kept.contains("# From loop"));
+
+        let history = ralph_core::EventHistory::new(repo_root.join(".ralph/events.jsonl"));
+        let records = history.filter_by_topic("merge.failed").unwrap();
+        assert_eq!(records.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&records[0].payload).unwrap();
+        assert_eq!(payload["loop_id"], "loop-1");
+        assert_eq!(payload["conflicted"], serde_json::json!(["README.md"]));
+        assert_eq!(payload["markers"], ".ralph/merge-conflicts/loop-1");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_conflict_policy_prefer_ours_spawns_with_resolution() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo_root = temp_dir.path();
+        let queue = seed_conflicting_loop(repo_root, "loop-1");
+        let (ralph, out) = resolution_recorder(repo_root);
+
+        process_pending_merges_with_command(
+            repo_root,
+            ralph.as_os_str(),
+            AutoMergeConflictPolicy::PreferOurs,
+        );
+
+        assert_eq!(wait_for_file(&out).trim(), "ours");
+        let entry = queue.get_entry("loop-1").unwrap().unwrap();
+        assert_eq!(entry.state, ralph_core::merge_queue::MergeState::Queued);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_conflict_policy_prefer_theirs_spawns_with_resolution() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo_root = temp_dir.path();
+        seed_conflicting_loop(repo_root, "loop-1");
+        let (ralph, out) = resolution_recorder(repo_root);
+
+        process_pending_merges_with_command(
+            repo_root,
+            ralph.as_os_str(),
+            AutoMergeConflictPolicy::PreferTheirs,
+        );
+
+        assert_eq!(wait_for_file(&out).trim(), "theirs");
+    }
+
     #[test]
     fn test_resolve_prompt_content_inline_precedence() {
         let mut config = RalphConfig::default();
         config.event_loop.prompt = Some("inline prompt".to_string());
         config.event_loop.prompt_file = "missing.md".to_string();
 
-        let resolved = resolve_prompt_content(&config.event_loop).expect("inline prompt");
+        let resolved =
+            resolve_prompt_content(&config.event_loop, &config.vars).expect("inline prompt");
         assert_eq!(resolved, "inline prompt");
     }
 
@@ -2094,10 +2897,31 @@ mod tests {
         config.event_loop.prompt = None;
         config.event_loop.prompt_file = prompt_path.to_string_lossy().to_string();
 
-        let resolved = resolve_prompt_content(&config.event_loop).expect("file prompt");
+        let resolved =
+            resolve_prompt_content(&config.event_loop, &config.vars).expect("file prompt");
         assert_eq!(resolved, "file prompt");
     }
 
+    #[test]
+    fn test_resolve_prompt_content_fills_vars() {
+        let mut config = RalphConfig::default();
+        config.event_loop.prompt = Some("Fix {{service}} (\\{{literal}})".to_string());
+        config
+            .vars
+            .insert("service".to_string(), "billing".to_string());
+
+        let resolved = resolve_prompt_content(&config.event_loop, &config.vars).expect("vars");
+        assert_eq!(resolved, "Fix billing ({{literal}})");
+
+        config.vars.clear();
+        let err = resolve_prompt_content(&config.event_loop, &config.vars).expect_err("missing");
+        assert!(
+            err.to_string().contains("{{service}}"),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("--var"), "unexpected error: {err}");
+    }
+
     #[test]
     fn test_resolve_prompt_content_missing_file_errors() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -2107,7 +2931,8 @@ mod tests {
         config.event_loop.prompt = None;
         config.event_loop.prompt_file = missing_path.to_string_lossy().to_string();
 
-        let err = resolve_prompt_content(&config.event_loop).expect_err("missing prompt");
+        let err =
+            resolve_prompt_content(&config.event_loop, &config.vars).expect_err("missing prompt");
         assert!(
             err.to_string().contains("Prompt file"),
             "unexpected error: {err}"
@@ -2120,7 +2945,8 @@ mod tests {
         config.event_loop.prompt = None;
         config.event_loop.prompt_file = String::new();
 
-        let err = resolve_prompt_content(&config.event_loop).expect_err("missing prompt");
+        let err =
+            resolve_prompt_content(&config.event_loop, &config.vars).expect_err("missing prompt");
         assert!(
             err.to_string().contains("No prompt specified"),
             "unexpected error: {err}"
@@ -2184,6 +3010,26 @@ mod tests {
         assert_eq!(records[0].iteration, 7);
     }
 
+    #[test]
+    fn test_log_loop_event_writes_pause_records() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let log_path = temp_dir.path().join("events.jsonl");
+        let mut logger = EventLogger::new(&log_path);
+
+        log_loop_event(&mut logger, 3, &Event::new("loop.paused", "paused"));
+        log_loop_event(&mut logger, 3, &Event::new("loop.resumed", "resumed"));
+
+        let content = std::fs::read_to_string(&log_path).expect("read events");
+        let records: Vec<EventRecord> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("record"))
+            .collect();
+
+        let topics: Vec<_> = records.iter().map(|r| r.topic.as_str()).collect();
+        assert_eq!(topics, vec!["loop.paused", "loop.resumed"]);
+        assert!(records.iter().all(|r| r.hat == "loop" && r.iteration == 3));
+    }
+
     #[test]
     fn test_check_planning_session_responses_publishes_user_response() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
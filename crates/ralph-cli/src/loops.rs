@@ -9,18 +9,38 @@
 //! - `retry`: Re-run merge for failed loop
 //! - `discard`: Abandon loop and cleanup
 //! - `stop`: Terminate running loop
-//! - `prune`: Clean up stale loops
+//! - `prune`: Clean up stale loops and branches of merged/discarded loops
+//! - `gc`: Remove merged/discarded entries from the merge queue
 //! - `attach`: Open shell in worktree
+//! - `exec`: Run one command in a loop's worktree
+//! - `rename`: Give a loop a new name (worktree, branch, and merge queue entry)
 //! - `diff`: Show changes from merge-base
+//! - `merge`: Merge a completed loop (with `--dry-run` conflict preview and
+//!   `--strategy merge|rebase|squash`)
 
-use std::path::PathBuf;
-use std::process::Command;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 
 use anyhow::{Context, Result, bail};
-use clap::{Parser, Subcommand};
-
-use ralph_core::worktree::{list_ralph_worktrees, remove_worktree};
-use ralph_core::{LoopRegistry, MergeButtonState, MergeQueue, MergeState, merge_button_state};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use regex::Regex;
+use tracing::warn;
+
+use ralph_core::worktree::{
+    WorktreeConfig, WorktreeError, delete_branch, list_ralph_branches, list_ralph_worktrees,
+    remove_worktree, rename_worktree, worktree_exists,
+};
+use ralph_core::{
+    EventLogger, EventRecord, FeaturesConfig, IntegrationOutcome, LoopContext, LoopRegistry,
+    LoopStatus, MergeButtonState, MergePreview, MergeQueue, MergeState, RalphConfig,
+    get_current_branch, merge_button_state, preview_merge, rebase_and_fast_forward,
+    sanitize_for_git, smart_merge_summary, squash_merge,
+};
+
+use crate::time_args::{parse_duration, parse_since};
 
 /// Manage parallel loops.
 #[derive(Parser, Debug)]
@@ -49,12 +69,22 @@ pub enum LoopsCommands {
     /// Stop a running loop
     Stop(StopArgs),
 
-    /// Clean up stale loops (crashed processes)
-    Prune,
+    /// Clean up stale loops (crashed processes) and branches of merged or
+    /// discarded loops
+    Prune(PruneArgs),
+
+    /// Remove merged and discarded loops from the merge queue
+    Gc(GcArgs),
 
     /// Open shell in loop's worktree
     Attach(AttachArgs),
 
+    /// Run a command in loop's worktree and exit with its status
+    Exec(ExecArgs),
+
+    /// Rename a loop's worktree and branch
+    Rename(RenameArgs),
+
     /// Show diff of loop's changes from merge-base
     Diff(DiffArgs),
 
@@ -87,6 +117,14 @@ pub struct LogsArgs {
     /// Follow output in real-time
     #[arg(short, long)]
     pub follow: bool,
+
+    /// Only show records at or after this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long, value_name = "TIME")]
+    pub since: Option<String>,
+
+    /// Only show lines matching this regular expression
+    #[arg(long, value_name = "PATTERN")]
+    pub grep: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -113,6 +151,40 @@ pub struct DiscardArgs {
     /// Skip confirmation prompt
     #[arg(short = 'y', long)]
     pub yes: bool,
+
+    /// Also delete the loop's branch if it has commits not merged into the checkout
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct PruneArgs {
+    /// List what would be removed without removing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Also delete loop branches with commits not merged into the checkout
+    #[arg(long)]
+    pub force: bool,
+
+    /// Skip confirmation prompt
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct GcArgs {
+    /// Only remove loops that finished more than this long ago (e.g. 7d, 12h)
+    #[arg(long, value_name = "DURATION")]
+    pub older_than: Option<String>,
+
+    /// Keep the N most recently finished loops
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub keep_last: usize,
+
+    /// Remove the entries (default: only preview what would be removed)
+    #[arg(short = 'y', long)]
+    pub yes: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -132,6 +204,25 @@ pub struct AttachArgs {
     pub loop_id: String,
 }
 
+#[derive(Parser, Debug)]
+pub struct ExecArgs {
+    /// Loop ID
+    pub loop_id: String,
+
+    /// Command and arguments, after `--`
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct RenameArgs {
+    /// Loop ID
+    pub loop_id: String,
+
+    /// New name (lowercase letters, digits, and hyphens)
+    pub new_name: String,
+}
+
 #[derive(Parser, Debug)]
 pub struct DiffArgs {
     /// Loop ID
@@ -147,9 +238,66 @@ pub struct MergeArgs {
     /// Loop ID
     pub loop_id: String,
 
-    /// Force merge even if state is 'merging'
+    /// Force merge even if state is 'merging', and delete the loop branch
+    /// afterwards even if its commits are not ancestors of main (squash)
     #[arg(long)]
     pub force: bool,
+
+    /// Preview conflicts without merging or touching the working tree
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// How the loop branch is brought into main
+    #[arg(long, value_enum, default_value_t = MergeStrategy::Merge)]
+    pub strategy: MergeStrategy,
+
+    /// How merge-ralph resolves conflicted files (--strategy merge only)
+    #[arg(long, value_enum, default_value_t = ConflictResolution::Manual)]
+    pub resolve: ConflictResolution,
+}
+
+/// Git operation used to bring a loop branch into main.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MergeStrategy {
+    /// Spawn merge-ralph to run `git merge --no-ff` and resolve conflicts
+    #[default]
+    Merge,
+    /// Rebase the loop branch onto main, then fast-forward main
+    Rebase,
+    /// Squash the loop branch into a single commit on main
+    Squash,
+}
+
+impl MergeStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "merge",
+            MergeStrategy::Rebase => "rebase",
+            MergeStrategy::Squash => "squash",
+        }
+    }
+}
+
+/// Conflict resolution strategy passed to merge-ralph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ConflictResolution {
+    /// Keep main's version of conflicted files
+    Ours,
+    /// Keep the loop branch's version of conflicted files
+    Theirs,
+    /// Resolve conflicts by reconciling the intent of both sides
+    #[default]
+    Manual,
+}
+
+impl ConflictResolution {
+    fn as_str(self) -> &'static str {
+        match self {
+            ConflictResolution::Ours => "ours",
+            ConflictResolution::Theirs => "theirs",
+            ConflictResolution::Manual => "manual",
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -174,8 +322,11 @@ pub fn execute(args: LoopsArgs, use_colors: bool) -> Result<()> {
         Some(LoopsCommands::Retry(retry_args)) => retry_merge(retry_args),
         Some(LoopsCommands::Discard(discard_args)) => discard_loop(discard_args),
         Some(LoopsCommands::Stop(stop_args)) => stop_loop(stop_args),
-        Some(LoopsCommands::Prune) => prune_stale(),
+        Some(LoopsCommands::Prune(prune_args)) => prune_stale(prune_args),
+        Some(LoopsCommands::Gc(gc_args)) => gc_loops(gc_args),
         Some(LoopsCommands::Attach(attach_args)) => attach_to_loop(attach_args),
+        Some(LoopsCommands::Exec(exec_args)) => exec_in_loop(exec_args),
+        Some(LoopsCommands::Rename(rename_args)) => rename_loop(rename_args),
         Some(LoopsCommands::Diff(diff_args)) => show_diff(diff_args),
         Some(LoopsCommands::Merge(merge_args)) => merge_loop(merge_args),
         Some(LoopsCommands::Process) => process_queue(),
@@ -185,17 +336,18 @@ pub fn execute(args: LoopsArgs, use_colors: bool) -> Result<()> {
 
 /// Process pending merge queue entries.
 fn process_queue() -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
 
     // Delegate to the loop_runner's process_pending_merges function
-    crate::loop_runner::process_pending_merges_cli(&cwd);
+    let policy = workspace_features(&cwd).auto_merge_conflict;
+    crate::loop_runner::process_pending_merges_cli(&cwd, policy);
 
     Ok(())
 }
 
 /// Get merge button state for a loop (JSON output for web API).
 fn get_merge_button_state(args: MergeButtonStateArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let state = merge_button_state(&cwd, &args.loop_id)?;
 
     let json = match state {
@@ -245,7 +397,7 @@ fn format_age(duration: chrono::Duration) -> String {
 fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     use ralph_core::LoopLock;
 
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let registry = LoopRegistry::new(&cwd);
     let merge_queue = MergeQueue::new(&cwd);
     let now = chrono::Utc::now();
@@ -283,6 +435,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                     prompt: truncate(&metadata.prompt, 40),
                     age: None,   // Primary loop age not easily available
                     merge: None, // Primary loop doesn't have merge state
+                    activity: LoopActivity::read(&cwd, metadata.pid, now),
                 });
             }
         }
@@ -302,6 +455,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
             .map(|p| shorten_path(p))
             .unwrap_or_else(|| "(in-place)".to_string());
 
+        let workspace = cwd.join(entry.worktree_path.as_deref().unwrap_or(&entry.workspace));
         rows.push(LoopRow {
             id: entry.id.clone(),
             status: status.to_string(),
@@ -309,6 +463,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
             prompt: truncate(&entry.prompt, 40),
             age: None, // Registry doesn't track start time
             merge: None,
+            activity: LoopActivity::read(&workspace, entry.pid, now),
         });
     }
 
@@ -361,6 +516,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                 prompt: truncate(&entry.prompt, 40),
                 age,
                 merge: merge_status,
+                activity: None,
             });
         }
     }
@@ -378,6 +534,7 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
                     prompt: String::new(),
                     age: None,
                     merge: None,
+                    activity: None,
                 });
             }
         }
@@ -429,12 +586,16 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
         println!();
     }
 
+    let has_stale = rows
+        .iter()
+        .any(|row| row.activity.as_ref().is_some_and(|activity| activity.stale));
+
     // Print table
     println!(
-        "{:<20} {:<12} {:<8} {:<8} {:<20} PROMPT",
-        "ID", "STATUS", "MERGE", "AGE", "LOCATION"
+        "{:<20} {:<12} {:<8} {:<8} {:<5} {:<10} {:<6} {:<6} {:<20} PROMPT",
+        "ID", "STATUS", "MERGE", "AGE", "ITER", "ACTIVE", "CPU", "MEM", "LOCATION"
     );
-    println!("{}", "-".repeat(88));
+    println!("{}", "-".repeat(119));
 
     for row in rows {
         let status_display = if use_colors {
@@ -445,13 +606,28 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
 
         let age_display = row.age.as_deref().unwrap_or("-");
         let merge_display = row.merge.as_deref().unwrap_or("-");
+        let [iteration, active, cpu, mem] = row.activity.as_ref().map_or_else(
+            || {
+                [
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                ]
+            },
+            |activity| activity.columns(now),
+        );
 
         println!(
-            "{:<20} {:<12} {:<8} {:<8} {:<20} {}",
+            "{:<20} {:<12} {:<8} {:<8} {:<5} {:<10} {:<6} {:<6} {:<20} {}",
             truncate(&row.id, 20),
             status_display,
             merge_display,
             age_display,
+            iteration,
+            active,
+            cpu,
+            mem,
             truncate(&row.location, 20),
             row.prompt
         );
@@ -468,6 +644,12 @@ fn list_loops(args: ListArgs, use_colors: bool) -> Result<()> {
     if has_needs_review {
         println!("Hint: Use `ralph loops retry <id>` to retry failed merges.");
     }
+    if has_stale {
+        println!(
+            "Hint: stale loops have not reached an iteration boundary in {}m or have exited.",
+            ralph_core::STALE_AFTER_MINUTES
+        );
+    }
     println!("Use `ralph loops --help` for more commands.");
 
     Ok(())
@@ -483,6 +665,73 @@ struct LoopRow {
     age: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     merge: Option<String>,
+    #[serde(flatten)]
+    activity: Option<LoopActivity>,
+}
+
+/// What a running loop last wrote to its status file.
+#[derive(serde::Serialize)]
+struct LoopActivity {
+    iteration: u32,
+    last_activity: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rss_bytes: Option<u64>,
+    /// The loop has exited or not updated its status in a while.
+    stale: bool,
+}
+
+impl LoopActivity {
+    /// Reads the status of loop process `pid` running in `workspace`.
+    ///
+    /// A status written by another process (an earlier loop in the same
+    /// workspace) is ignored.
+    fn read(workspace: &Path, pid: u32, now: DateTime<Utc>) -> Option<Self> {
+        let status = LoopStatus::read(workspace).filter(|status| status.pid == pid)?;
+        Some(Self::from_status(&status, is_process_alive(pid), now))
+    }
+
+    fn from_status(status: &LoopStatus, alive: bool, now: DateTime<Utc>) -> Self {
+        Self {
+            iteration: status.iteration,
+            last_activity: status.last_activity(),
+            cpu_ms: status.cpu_ms,
+            rss_bytes: status.rss_bytes,
+            stale: !alive || status.is_stale(now),
+        }
+    }
+
+    /// ITER, ACTIVE, CPU and MEM table cells.
+    fn columns(&self, now: DateTime<Utc>) -> [String; 4] {
+        let mut active = format_age(now.signed_duration_since(self.last_activity));
+        if self.stale {
+            active.push_str(" stale");
+        }
+        [
+            self.iteration.to_string(),
+            active,
+            self.cpu_ms.map_or_else(
+                || "-".to_string(),
+                |ms| format_age(chrono::Duration::milliseconds(ms as i64)),
+            ),
+            self.rss_bytes.map_or_else(|| "-".to_string(), format_bytes),
+        ]
+    }
+}
+
+/// Format a byte count compactly (e.g., "512K", "34M", "1.2G").
+fn format_bytes(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = KIB * 1024;
+    const GIB: u64 = MIB * 1024;
+    if bytes >= GIB {
+        format!("{:.1}G", bytes as f64 / GIB as f64)
+    } else if bytes >= MIB {
+        format!("{}M", bytes / MIB)
+    } else {
+        format!("{}K", bytes / KIB)
+    }
 }
 
 fn colorize_status(status: &str) -> String {
@@ -526,9 +775,96 @@ fn shorten_path(path: &str) -> String {
     }
 }
 
+/// Line filter built from `--since` and `--grep`.
+#[derive(Debug, Default)]
+struct LogFilter {
+    since: Option<DateTime<Utc>>,
+    pattern: Option<Regex>,
+}
+
+impl LogFilter {
+    fn from_args(args: &LogsArgs, now: DateTime<Utc>) -> Result<Self> {
+        let since = args
+            .since
+            .as_deref()
+            .map(|value| parse_since(value, now))
+            .transpose()?;
+        let pattern = args
+            .grep
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("Invalid --grep pattern '{}'", pattern))
+            })
+            .transpose()?;
+        Ok(Self { since, pattern })
+    }
+
+    /// Returns true if the line should be shown.
+    ///
+    /// With `--since`, lines without a readable `ts` field are dropped since
+    /// they cannot be placed in time.
+    fn matches(&self, line: &str) -> bool {
+        if let Some(pattern) = &self.pattern
+            && !pattern.is_match(line)
+        {
+            return false;
+        }
+        let Some(since) = self.since else {
+            return true;
+        };
+        serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|record| {
+                record
+                    .get("ts")
+                    .and_then(|ts| ts.as_str())
+                    .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            })
+            .is_some_and(|ts| ts >= since)
+    }
+
+    fn print_matching(&self, contents: &str) {
+        for line in contents.lines().filter(|line| self.matches(line)) {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Prints matching lines of `path`, then keeps printing appended lines.
+///
+/// Only complete lines are printed; a file that shrinks is re-read from the
+/// start. Runs until interrupted.
+fn follow_file(path: &Path, filter: &LogFilter) -> Result<()> {
+    let mut offset = 0u64;
+    loop {
+        let len = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .len();
+        if len < offset {
+            offset = 0;
+        }
+        if len > offset {
+            let mut file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buf = Vec::new();
+            file.take(len - offset).read_to_end(&mut buf)?;
+            if let Some(last_newline) = buf.iter().rposition(|b| *b == b'\n') {
+                filter.print_matching(&String::from_utf8_lossy(&buf[..=last_newline]));
+                std::io::stdout().flush()?;
+                offset += last_newline as u64 + 1;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+}
+
 /// Show logs for a loop.
 fn show_logs(args: LogsArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    // Reject bad filters before touching (or tailing) any file
+    let filter = LogFilter::from_args(&args, Utc::now())?;
+
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
 
     let base_path = if let Some(ref wt_path) = worktree_path {
@@ -550,9 +886,7 @@ fn show_logs(args: LogsArgs) -> Result<()> {
             );
             let contents =
                 std::fs::read_to_string(&history_path).context("Failed to read history file")?;
-            for line in contents.lines() {
-                println!("{}", line);
-            }
+            filter.print_matching(&contents);
             return Ok(());
         }
 
@@ -563,20 +897,11 @@ fn show_logs(args: LogsArgs) -> Result<()> {
     }
 
     if args.follow {
-        // Use tail -f for following
-        let status = Command::new("tail")
-            .args(["-f", events_path.to_string_lossy().as_ref()])
-            .status()
-            .context("Failed to run tail")?;
-
-        if !status.success() {
-            bail!("tail exited with error");
-        }
+        follow_file(&events_path, &filter)?;
     } else {
-        // Just cat the file
         let contents =
             std::fs::read_to_string(&events_path).context("Failed to read events file")?;
-        print!("{}", contents);
+        filter.print_matching(&contents);
     }
 
     Ok(())
@@ -584,7 +909,7 @@ fn show_logs(args: LogsArgs) -> Result<()> {
 
 /// Show history for a loop.
 fn show_history(args: HistoryArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
 
     let history_path = if let Some(wt_path) = worktree_path {
@@ -629,7 +954,7 @@ fn show_history(args: HistoryArgs) -> Result<()> {
 
 /// Retry merge for a failed loop.
 fn retry_merge(args: RetryArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let merge_queue = MergeQueue::new(&cwd);
 
     let entry = merge_queue
@@ -644,12 +969,12 @@ fn retry_merge(args: RetryArgs) -> Result<()> {
         );
     }
 
-    spawn_merge_ralph(&cwd, &args.loop_id)
+    spawn_merge_ralph(&cwd, &args.loop_id, ConflictResolution::Manual)
 }
 
 /// Discard a loop and clean up.
 fn discard_loop(args: DiscardArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
 
     // Confirmation unless -y
@@ -683,6 +1008,7 @@ fn discard_loop(args: DiscardArgs) -> Result<()> {
         println!("Removing worktree at {}...", wt_path);
         remove_worktree(&cwd, &wt_path)?;
     }
+    remove_loop_branch(&cwd, &format!("ralph/{}", loop_id), args.force)?;
 
     println!("Loop '{}' discarded.", loop_id);
     Ok(())
@@ -692,7 +1018,7 @@ fn discard_loop(args: DiscardArgs) -> Result<()> {
 fn stop_loop(args: StopArgs) -> Result<()> {
     use ralph_core::LoopLock;
 
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, worktree_path) = match args.loop_id.as_deref() {
         Some(id) => resolve_loop(&cwd, id)?,
         None => ("(primary)".to_string(), None),
@@ -752,15 +1078,21 @@ fn stop_loop(args: StopArgs) -> Result<()> {
     Ok(())
 }
 
-/// Prune stale loops.
-fn prune_stale() -> Result<()> {
-    let cwd = std::env::current_dir()?;
+/// Prune stale loops and the branches of merged or discarded loops.
+fn prune_stale(args: PruneArgs) -> Result<()> {
+    let cwd = crate::workspace_root::current()?.path;
     let registry = LoopRegistry::new(&cwd);
 
-    let count = registry.clean_stale()?;
+    let count = if args.dry_run {
+        registry.stale_entries()?.len()
+    } else {
+        registry.clean_stale()?
+    };
 
     if count == 0 {
         println!("No stale loops found.");
+    } else if args.dry_run {
+        println!("Would clean up {} stale loop(s).", count);
     } else {
         println!("Cleaned up {} stale loop(s).", count);
     }
@@ -789,26 +1121,373 @@ fn prune_stale() -> Result<()> {
         println!("\nTo remove orphan worktrees, use: ralph loops discard <id>");
     }
 
+    let branches = finished_loop_branches(&cwd)?;
+    if branches.is_empty() {
+        return Ok(());
+    }
+
+    println!("\nBranches of merged or discarded loops:");
+    for (branch, state) in &branches {
+        println!(
+            "  {:<40} {}",
+            branch,
+            if *state == MergeState::Merged {
+                "merged"
+            } else {
+                "discarded"
+            }
+        );
+    }
+    if args.dry_run {
+        println!("\nWould delete {} branch(es).", branches.len());
+        return Ok(());
+    }
+    if !args.yes {
+        eprintln!("Delete {} branch(es)? [y/N] ", branches.len());
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Kept branches.");
+            return Ok(());
+        }
+    }
+    for (branch, _) in &branches {
+        remove_loop_branch(&cwd, branch, args.force)?;
+    }
+
     Ok(())
 }
 
-/// Attach to a loop's worktree.
-fn attach_to_loop(args: AttachArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+/// `ralph/` branches with no worktree whose loop the merge queue records as
+/// merged or discarded.
+fn finished_loop_branches(cwd: &Path) -> Result<Vec<(String, MergeState)>> {
+    let checked_out: Vec<String> = list_ralph_worktrees(cwd)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|wt| wt.branch)
+        .collect();
+    let merge_queue = MergeQueue::new(cwd);
+
+    let mut finished = Vec::new();
+    for branch in list_ralph_branches(cwd)? {
+        if checked_out.contains(&branch) {
+            continue;
+        }
+        let loop_id = branch.trim_start_matches("ralph/");
+        if let Some(entry) = merge_queue.get_entry(loop_id)?
+            && entry.state.is_terminal()
+        {
+            finished.push((branch, entry.state));
+        }
+    }
+    Ok(finished)
+}
+
+/// Deletes a loop branch, keeping it (with a note) when it has unmerged
+/// commits and `force` is not set.
+fn remove_loop_branch(cwd: &Path, branch: &str, force: bool) -> Result<()> {
+    match delete_branch(cwd, branch, force) {
+        Ok(true) => println!("Deleted branch {}", branch),
+        Ok(false) => {}
+        Err(WorktreeError::UnmergedBranch(_)) => println!(
+            "Kept branch {}: it has commits not merged into the checkout. Use --force to delete it.",
+            branch
+        ),
+        Err(e) => return Err(e).with_context(|| format!("Failed to delete branch {}", branch)),
+    }
+    Ok(())
+}
+
+/// Features of the workspace's `ralph.yml`, or the defaults.
+fn workspace_features(repo_root: &Path) -> FeaturesConfig {
+    let path = repo_root.join("ralph.yml");
+    if !path.exists() {
+        return FeaturesConfig::default();
+    }
+    match RalphConfig::from_file(&path) {
+        Ok(config) => config.features,
+        Err(e) => {
+            warn!(
+                "Failed to load {}: {}; using default features",
+                path.display(),
+                e
+            );
+            FeaturesConfig::default()
+        }
+    }
+}
+
+/// Branch loops are merged into: `features.base_branch`, or `main` when unset.
+fn merge_target(repo_root: &Path) -> String {
+    workspace_features(repo_root)
+        .base_branch
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// Cleans up after loop `loop_id` was merged: removes its worktree and any
+/// leftover worktree directory, then deletes `ralph/<loop_id>` when
+/// `features.worktree.delete_branch_on_merge` is set. Without `force` a
+/// branch with commits not merged into the checkout is kept.
+pub(crate) fn cleanup_merged_loop(repo_root: &Path, loop_id: &str, force: bool) -> Result<()> {
+    let features = workspace_features(repo_root);
+    let branch = format!("ralph/{}", loop_id);
+
+    if let Some(worktree) = list_ralph_worktrees(repo_root)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|wt| wt.branch == branch)
+    {
+        remove_worktree(repo_root, &worktree.path)
+            .with_context(|| format!("Failed to remove worktree {}", worktree.path.display()))?;
+    }
+    let leftover = features
+        .worktree_config()
+        .worktree_path(repo_root)
+        .join(loop_id);
+    if leftover.exists() {
+        std::fs::remove_dir_all(&leftover)
+            .with_context(|| format!("Failed to remove {}", leftover.display()))?;
+    }
+
+    if features.worktree.delete_branch_on_merge {
+        remove_loop_branch(repo_root, &branch, force)?;
+    }
+    Ok(())
+}
+
+/// Remove finished loops from the merge queue.
+fn gc_loops(args: GcArgs) -> Result<()> {
+    let cwd = crate::workspace_root::current()?.path;
+    let now = Utc::now();
+    let older_than = args
+        .older_than
+        .as_deref()
+        .map(|value| {
+            parse_duration(value.trim()).with_context(|| {
+                format!(
+                    "Invalid --older-than '{}': expected a duration like 30s, 10m, 2h, 1d, 1w",
+                    value
+                )
+            })
+        })
+        .transpose()?;
+
+    let merge_queue = MergeQueue::new(&cwd);
+    let entries = merge_queue.list()?;
+    let running: Vec<String> = LoopRegistry::new(&cwd)
+        .list()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.is_alive())
+        .map(|entry| entry.id)
+        .collect();
+    let candidates = gc_candidates(&entries, &running, now, older_than, args.keep_last);
+
+    if candidates.is_empty() {
+        println!("No finished loops to remove.");
+        return Ok(());
+    }
+
+    if !args.yes {
+        println!("Would remove {} finished loop(s):", candidates.len());
+        for entry in &candidates {
+            println!(
+                "  {:<24} {:<10} {} ago",
+                entry.loop_id,
+                if entry.state == MergeState::Merged {
+                    "merged"
+                } else {
+                    "discarded"
+                },
+                format_age(now.signed_duration_since(entry.updated_at))
+            );
+        }
+        println!("\nRun with --yes to remove them.");
+        return Ok(());
+    }
+
+    let ids: Vec<&str> = candidates.iter().map(|e| e.loop_id.as_str()).collect();
+    let removed = merge_queue.remove_terminal(&ids)?;
+    println!("Removed {} finished loop(s).", removed);
+    Ok(())
+}
+
+/// Merge queue entries `ralph loops gc` would remove: merged or discarded
+/// loops, newest first, after skipping the `keep_last` most recent and any
+/// that finished less than `older_than` ago. Loops that are still running
+/// are never included.
+fn gc_candidates<'a>(
+    entries: &'a [ralph_core::MergeEntry],
+    running: &[String],
+    now: DateTime<Utc>,
+    older_than: Option<chrono::Duration>,
+    keep_last: usize,
+) -> Vec<&'a ralph_core::MergeEntry> {
+    let mut finished: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.state.is_terminal() && !running.contains(&entry.loop_id))
+        .collect();
+    finished.sort_by_key(|entry| std::cmp::Reverse(entry.updated_at));
+    finished
+        .into_iter()
+        .skip(keep_last)
+        .filter(|entry| older_than.is_none_or(|age| now - entry.updated_at >= age))
+        .collect()
+}
+
+/// Rename a loop's worktree, branch, and merge queue entry.
+fn rename_loop(args: RenameArgs) -> Result<()> {
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
+    let new_id = args.new_name;
+
+    if new_id == "main" || sanitize_for_git(&new_id) != new_id {
+        bail!(
+            "Invalid loop name '{}': use lowercase letters, digits, and single hyphens (e.g., '{}')",
+            new_id,
+            sanitize_for_git(&new_id)
+        );
+    }
+    if new_id == loop_id {
+        bail!("Loop '{}' already has that name.", loop_id);
+    }
+    let Some(worktree_path) = worktree_path else {
+        bail!(
+            "Loop '{}' is not a worktree-based loop (it runs in-place)",
+            loop_id
+        );
+    };
+
+    // Rename within whichever directory the loop's worktree was created in
+    let config = std::path::Path::new(&worktree_path)
+        .parent()
+        .map_or_else(WorktreeConfig::default, WorktreeConfig::with_dir);
+    if worktree_exists(&cwd, &new_id, &config) {
+        bail!(
+            "Cannot rename to '{}': a worktree already exists at {}",
+            new_id,
+            config.worktree_path(&cwd).join(&new_id).display()
+        );
+    }
+
+    // A running loop holds paths into its worktree, so it can't move under it
+    let registry = LoopRegistry::new(&cwd);
+    if let Ok(Some(entry)) = registry.get(&loop_id)
+        && entry.is_alive()
+    {
+        bail!("Loop '{}' is still running. Stop it first.", loop_id);
+    }
+    if registry.get(&new_id)?.is_some() {
+        bail!("Cannot rename to '{}': a running loop has that ID", new_id);
+    }
+
+    let merge_queue = MergeQueue::new(&cwd);
+    if merge_queue.get_entry(&new_id)?.is_some() {
+        bail!(
+            "Cannot rename to '{}': the merge queue already has a loop with that ID",
+            new_id
+        );
+    }
+    let queued = merge_queue.get_entry(&loop_id)?;
+    if let Some(entry) = &queued
+        && entry.state == MergeState::Merging
+    {
+        bail!(
+            "Loop '{}' is currently merging. Try again once it finishes.",
+            loop_id
+        );
+    }
+
+    let worktree = rename_worktree(&cwd, &loop_id, &new_id, &config)
+        .with_context(|| format!("Failed to rename loop '{}'", loop_id))?;
+    if queued.is_some() {
+        merge_queue.rename(&loop_id, &new_id)?;
+    }
 
-    let wt_path = worktree_path.context(format!(
+    println!("Renamed loop '{}' to '{}'", loop_id, new_id);
+    println!("  Worktree: {}", worktree.path.display());
+    println!("  Branch:   {}", worktree.branch);
+    Ok(())
+}
+
+/// A loop's worktree, as resolved for `attach` and `exec`.
+#[derive(Debug)]
+struct LoopWorktree {
+    loop_id: String,
+    path: PathBuf,
+    branch: String,
+}
+
+impl LoopWorktree {
+    /// Environment exported to commands run in the worktree.
+    fn env(&self) -> [(&'static str, String); 3] {
+        [
+            ("RALPH_LOOP_ID", self.loop_id.clone()),
+            ("RALPH_LOOP_BRANCH", self.branch.clone()),
+            (
+                crate::workspace_root::WORKSPACE_ENV,
+                self.path.to_string_lossy().into_owned(),
+            ),
+        ]
+    }
+}
+
+/// Resolves a loop to its worktree, failing for in-place loops and
+/// worktrees that were removed.
+fn resolve_worktree(cwd: &Path, id: &str) -> Result<LoopWorktree> {
+    let (loop_id, worktree_path) = resolve_loop(cwd, id)?;
+
+    let path = PathBuf::from(worktree_path.context(format!(
         "Loop '{}' is not a worktree-based loop (it runs in-place)",
         loop_id
-    ))?;
+    ))?);
+    if !path.is_dir() {
+        bail!(
+            "Worktree for loop '{}' no longer exists at {}.\nRun `ralph loops prune` to clean up stale loops.",
+            loop_id,
+            path.display()
+        );
+    }
+
+    let branch = list_ralph_worktrees(cwd)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|wt| wt.path == path)
+        .map_or_else(|| format!("ralph/{}", loop_id), |wt| wt.branch);
+
+    Ok(LoopWorktree {
+        loop_id,
+        path,
+        branch,
+    })
+}
+
+/// Attach to a loop's worktree.
+fn attach_to_loop(args: AttachArgs) -> Result<()> {
+    use std::io::IsTerminal;
+
+    let cwd = crate::workspace_root::current()?.path;
+    let worktree = resolve_worktree(&cwd, &args.loop_id)?;
+
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "`ralph loops attach` opens an interactive shell, but stdin is not a terminal.\nTo run a single command in the loop's worktree, use: ralph loops exec {} -- <command>",
+            worktree.loop_id
+        );
+    }
 
-    println!("Attaching to loop '{}' at {}...", loop_id, wt_path);
+    println!(
+        "Attaching to loop '{}' at {}...",
+        worktree.loop_id,
+        worktree.path.display()
+    );
     println!("Type 'exit' to return.\n");
 
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
 
     let status = Command::new(&shell)
-        .current_dir(&wt_path)
+        .current_dir(&worktree.path)
+        .envs(worktree.env())
         .status()
         .context("Failed to spawn shell")?;
 
@@ -819,9 +1498,46 @@ fn attach_to_loop(args: AttachArgs) -> Result<()> {
     Ok(())
 }
 
+/// Run a command in a loop's worktree, exiting with its status.
+fn exec_in_loop(args: ExecArgs) -> Result<()> {
+    let cwd = crate::workspace_root::current()?.path;
+    let worktree = resolve_worktree(&cwd, &args.loop_id)?;
+
+    let status = run_in_worktree(&worktree, &args.command)?;
+    if !status.success() {
+        std::process::exit(exit_code(status));
+    }
+    Ok(())
+}
+
+/// Runs `command` with its cwd in the worktree and the loop's env exported.
+///
+/// Stdin, stdout and stderr are inherited so output streams through.
+fn run_in_worktree(worktree: &LoopWorktree, command: &[String]) -> Result<ExitStatus> {
+    let (program, args) = command.split_first().context("No command given")?;
+    Command::new(program)
+        .args(args)
+        .current_dir(&worktree.path)
+        .envs(worktree.env())
+        .status()
+        .with_context(|| format!("Failed to run '{}'", program))
+}
+
+/// Exit code to propagate for a finished command; signals map to 128 + N.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
 /// Show diff for a loop.
 fn show_diff(args: DiffArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let (loop_id, _worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
 
     // Find the branch
@@ -840,7 +1556,7 @@ fn show_diff(args: DiffArgs) -> Result<()> {
 
     // Show diff from merge-base
     // Note: three-dot syntax requires both refs in a single argument: "main...branch"
-    let diff_range = format!("main...{}", branch);
+    let diff_range = format!("{}...{}", merge_target(&cwd), branch);
     let mut git_args = vec!["diff", &diff_range];
 
     if args.stat {
@@ -862,12 +1578,21 @@ fn show_diff(args: DiffArgs) -> Result<()> {
 
 /// Merge a completed loop (or force retry).
 fn merge_loop(args: MergeArgs) -> Result<()> {
-    let cwd = std::env::current_dir()?;
+    let cwd = crate::workspace_root::current()?.path;
     let registry = LoopRegistry::new(&cwd);
     let merge_queue = MergeQueue::new(&cwd);
 
     // Try to find the loop in various places
     let (loop_id, worktree_path) = resolve_loop(&cwd, &args.loop_id)?;
+    let target = merge_target(&cwd);
+
+    if args.dry_run {
+        let branch = format!("ralph/{}", loop_id);
+        let preview = preview_merge(&cwd, &target, &branch)
+            .with_context(|| format!("Failed to preview merge of '{}'", branch))?;
+        print_merge_preview(&branch, &target, &preview);
+        return Ok(());
+    }
 
     // 1. Check if it's running
     if let Ok(Some(entry)) = registry.get(&loop_id)
@@ -909,7 +1634,7 @@ fn merge_loop(args: MergeArgs) -> Result<()> {
             );
             // We need a prompt for the queue entry. Since it's an orphan, we might not have it easily.
             // Try to read it from the worktree's loop lock if available, or use a placeholder.
-            let prompt = if let Some(wt_path) = worktree_path {
+            let prompt = if let Some(wt_path) = &worktree_path {
                 use ralph_core::LoopLock;
                 LoopLock::read_existing(std::path::Path::new(&wt_path))
                     .ok()
@@ -929,16 +1654,246 @@ fn merge_loop(args: MergeArgs) -> Result<()> {
         }
     }
 
-    spawn_merge_ralph(&cwd, &loop_id)
+    if args.strategy != MergeStrategy::Merge {
+        return land_loop_branch(
+            &cwd,
+            &loop_id,
+            &target,
+            worktree_path.as_deref(),
+            args.strategy,
+            args.force,
+        );
+    }
+
+    // Record conflicts up front so merge-ralph (or a human) has structured data
+    let branch = format!("ralph/{}", loop_id);
+    match preview_merge(&cwd, &target, &branch) {
+        Ok(preview) if preview.has_conflicts() => {
+            println!(
+                "Merge will conflict in {} file(s); resolving with '{}'",
+                preview.conflicted.len(),
+                args.resolve.as_str()
+            );
+            record_conflict_summary(&cwd, &loop_id, &branch, &target, &preview, args.resolve)?;
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Could not preview merge of '{}': {}", branch, e),
+    }
+
+    spawn_merge_ralph(&cwd, &loop_id, args.resolve)
+}
+
+/// Rebase or squash a loop branch into `target` directly, without merge-ralph.
+///
+/// On conflict the git operation is rolled back, the worktree and branch are
+/// kept, and the loop is marked for review.
+fn land_loop_branch(
+    cwd: &Path,
+    loop_id: &str,
+    target: &str,
+    worktree_path: Option<&str>,
+    strategy: MergeStrategy,
+    force: bool,
+) -> Result<()> {
+    let branch = format!("ralph/{}", loop_id);
+
+    let current = get_current_branch(cwd)?;
+    if current != target {
+        bail!(
+            "--strategy {} lands on {}, but the checkout is on '{}'. Switch to {} first.",
+            strategy.as_str(),
+            target,
+            current,
+            target
+        );
+    }
+    if strategy == MergeStrategy::Rebase && worktree_path.is_none() {
+        bail!(
+            "--strategy rebase needs the worktree for loop '{}', but none was found.",
+            loop_id
+        );
+    }
+
+    let merge_queue = MergeQueue::new(cwd);
+    if !matches!(
+        merge_queue.get_entry(loop_id)?,
+        Some(entry) if entry.state == MergeState::Merging
+    ) {
+        merge_queue.mark_merging(loop_id, std::process::id())?;
+    }
+
+    let outcome = match (strategy, worktree_path) {
+        (MergeStrategy::Rebase, Some(worktree)) => {
+            rebase_and_fast_forward(cwd, worktree, target, &branch)
+        }
+        _ => {
+            let summary = smart_merge_summary(cwd, loop_id)?;
+            squash_merge(
+                cwd,
+                &branch,
+                &format!("merge(ralph): {} (loop {})", summary, loop_id),
+            )
+        }
+    };
+
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            merge_queue.mark_needs_review(loop_id, &e.to_string())?;
+            return Err(e).with_context(|| format!("Failed to {} '{}'", strategy.as_str(), branch));
+        }
+    };
+
+    match outcome {
+        IntegrationOutcome::Landed { commit } => {
+            merge_queue.mark_merged(loop_id, &commit)?;
+            let _ = LoopRegistry::new(cwd).deregister(loop_id);
+            println!(
+                "Merged loop '{}' into {} with {} ({})",
+                loop_id,
+                target,
+                strategy.as_str(),
+                commit
+            );
+            cleanup_merged_loop(cwd, loop_id, force)
+        }
+        IntegrationOutcome::Conflicted { files } => {
+            merge_queue.mark_needs_review(
+                loop_id,
+                &format!("{} conflicts in: {}", strategy.as_str(), files.join(", ")),
+            )?;
+            print_conflict_guidance(loop_id, &branch, target, worktree_path, strategy, &files);
+            bail!(
+                "Could not {} loop '{}' onto {}: {} conflicted file(s)",
+                strategy.as_str(),
+                loop_id,
+                target,
+                files.len()
+            );
+        }
+    }
+}
+
+/// Explain how to proceed after a rebase or squash stopped on conflicts.
+fn print_conflict_guidance(
+    loop_id: &str,
+    branch: &str,
+    target: &str,
+    worktree_path: Option<&str>,
+    strategy: MergeStrategy,
+    files: &[String],
+) {
+    eprintln!(
+        "The {} of {} onto {} stopped on conflicts and was rolled back.",
+        strategy.as_str(),
+        branch,
+        target
+    );
+    eprintln!("Conflicted files ({}):", files.len());
+    for file in files {
+        eprintln!("  {}", file);
+    }
+    if let Some(wt_path) = worktree_path {
+        eprintln!("\nThe worktree at {} was left as it was.", wt_path);
+    }
+
+    eprintln!("\nNext steps:");
+    eprintln!(
+        "  ralph loops diff {}                     # review the loop's changes",
+        loop_id
+    );
+    eprintln!(
+        "  ralph loops merge {}                    # let merge-ralph resolve the conflicts",
+        loop_id
+    );
+    match (strategy, worktree_path) {
+        (MergeStrategy::Rebase, Some(wt_path)) => eprintln!(
+            "  cd {} && git rebase {}   # resolve by hand, then rerun with --strategy rebase",
+            wt_path, target
+        ),
+        _ => eprintln!(
+            "  git merge --squash {}   # resolve by hand on {}, then commit",
+            branch, target
+        ),
+    }
+}
+
+/// Print the result of a merge simulation.
+fn print_merge_preview(branch: &str, target: &str, preview: &MergePreview) {
+    println!("Merge preview: {} -> {}", branch, target);
+    println!("  Merge base: {}", preview.merge_base);
+    println!(
+        "  Commits: {} on {}, {} on {}",
+        preview.source_ahead, branch, preview.target_ahead, target
+    );
+
+    println!("  Clean ({}):", preview.clean.len());
+    for file in &preview.clean {
+        println!("    {}", file);
+    }
+
+    println!("  Conflicts ({}):", preview.conflicted.len());
+    for file in &preview.conflicted {
+        println!("    {}", file);
+    }
+
+    if preview.has_conflicts() {
+        println!("\nMerge would conflict. Use --resolve to choose how conflicts are resolved.");
+    } else {
+        println!("\nMerge would apply cleanly.");
+    }
+}
+
+/// Append a `merge.conflicts` summary event to the primary loop's events file.
+fn record_conflict_summary(
+    cwd: &std::path::Path,
+    loop_id: &str,
+    branch: &str,
+    target: &str,
+    preview: &MergePreview,
+    resolution: ConflictResolution,
+) -> Result<()> {
+    let payload = serde_json::json!({
+        "loop_id": loop_id,
+        "branch": branch,
+        "target": target,
+        "resolution": resolution.as_str(),
+        "merge_base": preview.merge_base,
+        "source_ahead": preview.source_ahead,
+        "target_ahead": preview.target_ahead,
+        "conflicted": preview.conflicted,
+        "clean": preview.clean,
+    });
+
+    // Built directly rather than via EventRecord::new, which truncates long payloads
+    let record = EventRecord {
+        ts: chrono::Utc::now().to_rfc3339(),
+        iteration: 0,
+        hat: "loops".to_string(),
+        topic: "merge.conflicts".to_string(),
+        triggered: None,
+        payload: payload.to_string(),
+        blocked_count: None,
+    };
+
+    let mut logger = EventLogger::from_context(&LoopContext::primary(cwd.to_path_buf()));
+    logger
+        .log(&record)
+        .context("Failed to record merge conflict summary")
 }
 
 /// Helper to spawn merge-ralph
-fn spawn_merge_ralph(cwd: &std::path::Path, loop_id: &str) -> Result<()> {
+fn spawn_merge_ralph(
+    cwd: &std::path::Path,
+    loop_id: &str,
+    resolution: ConflictResolution,
+) -> Result<()> {
     // Get the merge-loop preset and write to config file
     let preset = crate::presets::get_preset("merge-loop").context("merge-loop preset not found")?;
 
     let config_path = cwd.join(".ralph/merge-loop-config.yml");
-    std::fs::write(&config_path, preset.content).context("Failed to write merge config file")?;
+    std::fs::write(&config_path, preset.content.as_bytes())
+        .context("Failed to write merge config file")?;
 
     // Spawn merge-ralph
     println!("Spawning merge-ralph for loop '{}'...", loop_id);
@@ -953,6 +1908,7 @@ fn spawn_merge_ralph(cwd: &std::path::Path, loop_id: &str) -> Result<()> {
             &format!("Merge loop {} from branch ralph/{}", loop_id, loop_id),
         ])
         .env("RALPH_MERGE_LOOP_ID", loop_id)
+        .env("RALPH_MERGE_RESOLUTION", resolution.as_str())
         .status()
         .context("Failed to spawn merge-ralph")?;
 
@@ -1097,6 +2053,39 @@ mod tests {
         assert_eq!(format_age(chrono::Duration::seconds(86400)), "1d");
     }
 
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512 * 1024), "512K");
+        assert_eq!(format_bytes(34 * 1024 * 1024 + 1), "34M");
+        assert_eq!(format_bytes(1288 * 1024 * 1024), "1.3G");
+    }
+
+    #[test]
+    fn test_loop_activity_columns_flag_stale_status() {
+        let now = Utc::now();
+        let mut status = LoopStatus {
+            pid: 42,
+            iteration: 7,
+            hat: Some("builder".to_string()),
+            updated: now - chrono::Duration::minutes(2),
+            last_event: Some(now - chrono::Duration::minutes(3)),
+            cpu_ms: Some(95_000),
+            rss_bytes: Some(48 * 1024 * 1024),
+        };
+
+        let activity = LoopActivity::from_status(&status, true, now);
+        assert_eq!(activity.columns(now), ["7", "3m", "1m", "48M"]);
+
+        status.updated = now - chrono::Duration::hours(1);
+        status.last_event = None;
+        let activity = LoopActivity::from_status(&status, true, now);
+        assert!(activity.stale);
+        assert_eq!(activity.columns(now)[1], "1h stale");
+
+        status.updated = now;
+        assert!(LoopActivity::from_status(&status, false, now).stale);
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_is_process_alive_current_pid() {
@@ -1284,10 +2273,63 @@ mod tests {
         show_logs(LogsArgs {
             loop_id: "loop-log-1234".to_string(),
             follow: false,
+            since: None,
+            grep: None,
         })
         .expect("show logs");
     }
 
+    #[test]
+    fn test_log_filter_since_and_grep() {
+        let now = Utc::now();
+        let args = LogsArgs {
+            loop_id: "any".to_string(),
+            follow: false,
+            since: Some("2026-01-01T00:00:00Z".to_string()),
+            grep: Some("build\\.(done|blocked)".to_string()),
+        };
+        let filter = LogFilter::from_args(&args, now).unwrap();
+
+        assert!(filter.matches(r#"{"ts":"2026-01-02T00:00:00Z","topic":"build.done"}"#));
+        assert!(!filter.matches(r#"{"ts":"2025-12-31T23:59:59Z","topic":"build.done"}"#));
+        assert!(!filter.matches(r#"{"ts":"2026-01-02T00:00:00Z","topic":"build.task"}"#));
+        assert!(!filter.matches("build.done without a timestamp"));
+
+        let grep_only = LogFilter::from_args(
+            &LogsArgs {
+                since: None,
+                ..args
+            },
+            now,
+        )
+        .unwrap();
+        assert!(grep_only.matches("build.done without a timestamp"));
+    }
+
+    #[test]
+    fn test_show_logs_rejects_invalid_filters_before_reading() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        let err = show_logs(LogsArgs {
+            loop_id: "does-not-exist".to_string(),
+            follow: true,
+            since: None,
+            grep: Some("(unclosed".to_string()),
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid --grep pattern"), "{err}");
+
+        let err = show_logs(LogsArgs {
+            loop_id: "does-not-exist".to_string(),
+            follow: true,
+            since: Some("last tuesday".to_string()),
+            grep: None,
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid --since"), "{err}");
+    }
+
     #[test]
     fn test_show_history_formats_table() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -1352,6 +2394,7 @@ mod tests {
         discard_loop(DiscardArgs {
             loop_id: "loop-discard-1".to_string(),
             yes: true,
+            force: false,
         })
         .expect("discard loop");
 
@@ -1403,6 +2446,83 @@ mod tests {
         assert!(err.to_string().contains("not a worktree-based loop"));
     }
 
+    #[test]
+    fn test_run_in_worktree_sets_cwd_env_and_exit_code() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let worktree_dir = temp_dir.path().join(".worktrees/loop-wt-1");
+        std::fs::create_dir_all(&worktree_dir).expect("create worktree dir");
+
+        let registry = LoopRegistry::new(temp_dir.path());
+        let entry = LoopEntry::with_id(
+            "loop-wt-1",
+            "in a worktree",
+            Some(worktree_dir.display().to_string()),
+            temp_dir.path().display().to_string(),
+        );
+        registry.register(entry).expect("register loop");
+
+        let worktree = resolve_worktree(temp_dir.path(), "wt-1").expect("resolve worktree");
+        assert_eq!(worktree.loop_id, "loop-wt-1");
+        assert_eq!(worktree.path, worktree_dir);
+
+        let command: Vec<String> = [
+            "sh",
+            "-c",
+            "pwd > out.txt; echo \"$RALPH_LOOP_ID $RALPH_LOOP_BRANCH\" >> out.txt; exit 3",
+        ]
+        .map(String::from)
+        .to_vec();
+        let status = run_in_worktree(&worktree, &command).expect("run command");
+
+        assert_eq!(exit_code(status), 3);
+        let out = std::fs::read_to_string(worktree_dir.join("out.txt")).expect("read output");
+        let mut lines = out.lines();
+        assert_eq!(
+            PathBuf::from(lines.next().unwrap()).canonicalize().unwrap(),
+            worktree_dir.canonicalize().unwrap()
+        );
+        assert_eq!(lines.next(), Some("loop-wt-1 ralph/loop-wt-1"));
+    }
+
+    #[test]
+    fn test_resolve_worktree_rejects_removed_worktree() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let registry = LoopRegistry::new(temp_dir.path());
+        let entry = LoopEntry::with_id(
+            "loop-gone-1",
+            "removed worktree",
+            Some(
+                temp_dir
+                    .path()
+                    .join(".worktrees/loop-gone-1")
+                    .display()
+                    .to_string(),
+            ),
+            temp_dir.path().display().to_string(),
+        );
+        registry.register(entry).expect("register loop");
+
+        let err = resolve_worktree(temp_dir.path(), "loop-gone-1")
+            .expect_err("removed worktree should be rejected");
+
+        assert!(err.to_string().contains("no longer exists"), "{err}");
+        assert!(err.to_string().contains("ralph loops prune"), "{err}");
+    }
+
+    #[test]
+    fn test_exec_args_take_command_after_separator() {
+        let args =
+            LoopsArgs::try_parse_from(["loops", "exec", "a3f2", "--", "cargo", "test", "--quiet"])
+                .expect("parse exec args");
+
+        let Some(LoopsCommands::Exec(exec)) = args.command else {
+            panic!("expected exec subcommand");
+        };
+        assert_eq!(exec.loop_id, "a3f2");
+        assert_eq!(exec.command, vec!["cargo", "test", "--quiet"]);
+        assert!(LoopsArgs::try_parse_from(["loops", "exec", "a3f2"]).is_err());
+    }
+
     #[test]
     fn test_show_diff_missing_branch_errors() {
         if Command::new("git").arg("--version").output().is_err() {
@@ -1491,6 +2611,9 @@ mod tests {
         let err = merge_loop(MergeArgs {
             loop_id: "loop-merged-1".to_string(),
             force: false,
+            dry_run: false,
+            strategy: MergeStrategy::Merge,
+            resolve: ConflictResolution::Manual,
         })
         .expect_err("merge should fail for merged loop");
 
@@ -1513,6 +2636,9 @@ mod tests {
         let err = merge_loop(MergeArgs {
             loop_id: "loop-discarded-1".to_string(),
             force: false,
+            dry_run: false,
+            strategy: MergeStrategy::Merge,
+            resolve: ConflictResolution::Manual,
         })
         .expect_err("merge should fail for discarded loop");
 
@@ -1533,9 +2659,326 @@ mod tests {
         let err = merge_loop(MergeArgs {
             loop_id: "loop-merging-1".to_string(),
             force: false,
+            dry_run: false,
+            strategy: MergeStrategy::Merge,
+            resolve: ConflictResolution::Manual,
         })
         .expect_err("merge should fail for merging loop without force");
 
         assert!(err.to_string().contains("currently merging"));
     }
+
+    fn git(args: &[&str]) {
+        let status = Command::new("git").args(args).status().expect("git");
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Repo on `main` with a `ralph/<loop_id>` branch that conflicts on README.md.
+    fn setup_conflicting_branch(loop_id: &str) {
+        git(&["init", "-q", "--initial-branch=main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test User"]);
+        std::fs::write("README.md", "# Test").expect("write README");
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Initial commit"]);
+
+        git(&["checkout", "-q", "-b", &format!("ralph/{}", loop_id)]);
+        std::fs::write("README.md", "# Loop").expect("write README");
+        std::fs::write("feature.txt", "feature").expect("write feature");
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Loop work"]);
+
+        git(&["checkout", "-q", "main"]);
+        std::fs::write("README.md", "# Main").expect("write README");
+        git(&["commit", "-q", "-am", "Main work"]);
+    }
+
+    /// Repo on `main` with worktrees for each of `loop_ids`.
+    fn setup_worktrees(loop_ids: &[&str]) {
+        git(&["init", "-q", "--initial-branch=main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test User"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "Initial commit"]);
+        for loop_id in loop_ids {
+            ralph_core::create_worktree(".", loop_id, &WorktreeConfig::default())
+                .expect("create worktree");
+        }
+    }
+
+    /// Commits a new file in a loop's worktree.
+    fn commit_in_worktree(loop_id: &str, file: &str) {
+        let worktree = PathBuf::from(".worktrees").join(loop_id);
+        std::fs::write(worktree.join(file), file).expect("write file");
+        for args in [&["add", "."][..], &["commit", "-q", "-m", file]] {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(&worktree)
+                .status()
+                .expect("git");
+            assert!(status.success(), "git {:?} failed", args);
+        }
+    }
+
+    fn land(loop_id: &str, strategy: MergeStrategy, force: bool) -> Result<()> {
+        merge_loop(MergeArgs {
+            loop_id: loop_id.to_string(),
+            force,
+            dry_run: false,
+            strategy,
+            resolve: ConflictResolution::Manual,
+        })
+    }
+
+    fn rename(loop_id: &str, new_name: &str) -> Result<()> {
+        rename_loop(RenameArgs {
+            loop_id: loop_id.to_string(),
+            new_name: new_name.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_rename_loop_moves_worktree_branch_and_queue_entry() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&["able-raven"]);
+        let queue = MergeQueue::new(temp_dir.path());
+        queue.enqueue("able-raven", "prompt").expect("enqueue");
+
+        rename("able-raven", "auth-rework").expect("rename");
+
+        assert!(temp_dir.path().join(".worktrees/auth-rework").exists());
+        assert!(!temp_dir.path().join(".worktrees/able-raven").exists());
+        let worktrees = list_ralph_worktrees(temp_dir.path()).unwrap();
+        assert_eq!(worktrees[0].branch, "ralph/auth-rework");
+        assert!(queue.get_entry("able-raven").unwrap().is_none());
+        assert_eq!(
+            queue.get_entry("auth-rework").unwrap().unwrap().state,
+            MergeState::Queued
+        );
+        assert_eq!(
+            resolve_loop(temp_dir.path(), "auth-rework").unwrap().0,
+            "auth-rework"
+        );
+    }
+
+    #[test]
+    fn test_rename_loop_refuses_collisions_and_bad_names() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&["able-raven", "brave-otter"]);
+
+        let err = rename("able-raven", "brave-otter").expect_err("collision");
+        assert!(
+            err.to_string().contains("a worktree already exists"),
+            "{err}"
+        );
+
+        let err = rename("able-raven", "Auth Rework").expect_err("bad name");
+        assert!(err.to_string().contains("'auth-rework'"), "{err}");
+        assert!(rename("able-raven", "main").is_err());
+
+        assert!(temp_dir.path().join(".worktrees/able-raven").exists());
+        assert!(temp_dir.path().join(".worktrees/brave-otter").exists());
+    }
+
+    #[test]
+    fn test_merge_loop_dry_run_leaves_state_untouched() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_conflicting_branch("loop-preview");
+
+        let queue = MergeQueue::new(temp_dir.path());
+        queue.enqueue("loop-preview", "prompt").expect("enqueue");
+
+        merge_loop(MergeArgs {
+            loop_id: "loop-preview".to_string(),
+            force: false,
+            dry_run: true,
+            strategy: MergeStrategy::Merge,
+            resolve: ConflictResolution::Manual,
+        })
+        .expect("dry run should succeed");
+
+        let entry = queue.get_entry("loop-preview").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Queued);
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("README.md")).unwrap(),
+            "# Main"
+        );
+    }
+
+    #[test]
+    fn test_merge_loop_cleans_up_worktree_and_merged_branch() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&["loop-rebased"]);
+        commit_in_worktree("loop-rebased", "rebased.txt");
+        let queue = MergeQueue::new(temp_dir.path());
+        queue.enqueue("loop-rebased", "prompt").expect("enqueue");
+
+        land("loop-rebased", MergeStrategy::Rebase, false).expect("rebase merge");
+
+        let entry = queue.get_entry("loop-rebased").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::Merged);
+        assert!(entry.merge_commit.is_some());
+        assert!(!temp_dir.path().join(".worktrees/loop-rebased").exists());
+        assert!(list_ralph_branches(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_loop_lands_on_configured_base_branch() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&[]);
+        std::fs::write("ralph.yml", "features:\n  base_branch: develop\n").expect("write config");
+        git(&["add", "ralph.yml"]);
+        git(&["commit", "-q", "-m", "Add config"]);
+        git(&["branch", "develop"]);
+        ralph_core::create_worktree(
+            ".",
+            "loop-develop",
+            &WorktreeConfig::default().with_base_branch("develop"),
+        )
+        .expect("create worktree");
+        commit_in_worktree("loop-develop", "develop.txt");
+        let queue = MergeQueue::new(temp_dir.path());
+        queue.enqueue("loop-develop", "prompt").expect("enqueue");
+
+        let err = land("loop-develop", MergeStrategy::Rebase, false)
+            .expect_err("landing from main should be refused");
+        assert!(err.to_string().contains("Switch to develop first"));
+
+        git(&["checkout", "-q", "develop"]);
+        land("loop-develop", MergeStrategy::Rebase, false).expect("rebase merge");
+
+        assert!(temp_dir.path().join("develop.txt").exists());
+        git(&["checkout", "-q", "main"]);
+        assert!(!temp_dir.path().join("develop.txt").exists());
+    }
+
+    #[test]
+    fn test_merge_loop_keeps_squashed_branch_without_force() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&["loop-squashed", "loop-forced"]);
+        commit_in_worktree("loop-squashed", "squashed.txt");
+        commit_in_worktree("loop-forced", "forced.txt");
+        let queue = MergeQueue::new(temp_dir.path());
+        queue.enqueue("loop-squashed", "prompt").expect("enqueue");
+        queue.enqueue("loop-forced", "prompt").expect("enqueue");
+
+        land("loop-squashed", MergeStrategy::Squash, false).expect("squash merge");
+        land("loop-forced", MergeStrategy::Squash, true).expect("squash merge");
+
+        // Squashed commits are not ancestors of main, so only --force deletes
+        assert_eq!(
+            list_ralph_branches(temp_dir.path()).unwrap(),
+            vec!["ralph/loop-squashed"]
+        );
+        assert!(!temp_dir.path().join(".worktrees/loop-squashed").exists());
+    }
+
+    #[test]
+    fn test_prune_deletes_finished_loop_branches() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_worktrees(&["loop-merged", "loop-discarded", "loop-queued"]);
+        commit_in_worktree("loop-discarded", "abandoned.txt");
+        for loop_id in ["loop-merged", "loop-discarded"] {
+            remove_worktree(".", format!(".worktrees/{loop_id}")).expect("remove worktree");
+        }
+        let queue = MergeQueue::new(temp_dir.path());
+        for loop_id in ["loop-merged", "loop-discarded", "loop-queued"] {
+            queue.enqueue(loop_id, "prompt").expect("enqueue");
+        }
+        queue.mark_merging("loop-merged", 1).unwrap();
+        queue.mark_merged("loop-merged", "abc123").unwrap();
+        queue.discard("loop-discarded", None).unwrap();
+
+        let finished = finished_loop_branches(temp_dir.path()).unwrap();
+        assert_eq!(
+            finished,
+            vec![
+                ("ralph/loop-discarded".to_string(), MergeState::Discarded),
+                ("ralph/loop-merged".to_string(), MergeState::Merged),
+            ],
+            "branches still checked out or queued are not candidates"
+        );
+
+        let prune = |dry_run, force| {
+            prune_stale(PruneArgs {
+                dry_run,
+                force,
+                yes: true,
+            })
+            .expect("prune");
+            list_ralph_branches(temp_dir.path()).unwrap()
+        };
+        assert_eq!(prune(true, false).len(), 3, "dry run deletes nothing");
+        assert_eq!(
+            prune(false, false),
+            vec!["ralph/loop-discarded", "ralph/loop-queued"],
+            "unmerged commits need --force"
+        );
+        assert_eq!(prune(false, true), vec!["ralph/loop-queued"]);
+    }
+
+    #[test]
+    fn test_record_conflict_summary_writes_event() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        setup_conflicting_branch("loop-conflict");
+
+        let preview = preview_merge(temp_dir.path(), "main", "ralph/loop-conflict").unwrap();
+        record_conflict_summary(
+            temp_dir.path(),
+            "loop-conflict",
+            "ralph/loop-conflict",
+            "main",
+            &preview,
+            ConflictResolution::Theirs,
+        )
+        .expect("record summary");
+
+        let history = ralph_core::EventHistory::new(temp_dir.path().join(".ralph/events.jsonl"));
+        let records = history.filter_by_topic("merge.conflicts").unwrap();
+        assert_eq!(records.len(), 1);
+
+        let payload: serde_json::Value = serde_json::from_str(&records[0].payload).unwrap();
+        assert_eq!(payload["resolution"], "theirs");
+        assert_eq!(payload["conflicted"], serde_json::json!(["README.md"]));
+        assert_eq!(payload["clean"], serde_json::json!(["feature.txt"]));
+    }
 }
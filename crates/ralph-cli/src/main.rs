@@ -13,31 +13,42 @@
 //! - Work item tracking via `ralph task`
 
 mod bot;
+mod completions;
+mod diagnostics_cli;
 mod display;
 mod doctor;
+mod event_sources;
+mod event_stats;
 mod hats;
+mod ignore_cli;
 mod init;
 mod interact;
+mod json_events;
 mod loop_runner;
 mod loops;
 mod memory;
 mod preflight;
 mod presets;
+mod runs;
+mod scratchpad_cli;
 mod skill_cli;
 mod sop_runner;
 mod task_cli;
+mod telemetry;
 #[cfg(test)]
 mod test_support;
+mod time_args;
 mod tools;
 mod web;
+mod workspace_root;
 
 use anyhow::{Context, Result};
 use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
-use ralph_adapters::detect_backend;
+use ralph_adapters::{CliBackend, DEFAULT_PROBE_TIMEOUT, detect_configured_backend, probe_backend};
 use ralph_core::{
-    CheckStatus, EventHistory, LockError, LoopContext, LoopEntry, LoopLock, LoopRegistry,
-    PreflightReport, PreflightRunner, RalphConfig, TerminationReason,
-    worktree::{WorktreeConfig, create_worktree, ensure_gitignore, remove_worktree},
+    CheckStatus, EventHistory, EventRecord, LockError, LoopContext, LoopEntry, LoopLock,
+    LoopRegistry, PreflightReport, PreflightRunner, RalphConfig, TerminationReason,
+    worktree::{create_worktree, delete_branch, ensure_worktree_dir_ignored, remove_worktree},
 };
 use std::fs;
 use std::io::{IsTerminal, Write, stdout};
@@ -221,6 +232,30 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Conflict policy for `ralph run --auto-merge-conflict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AutoMergeConflictArg {
+    /// Leave the worktree for manual handling
+    Abort,
+    /// Save the conflicted files with markers and emit merge.failed
+    Keep,
+    /// Resolve conflicts in favor of main
+    PreferOurs,
+    /// Resolve conflicts in favor of the loop's branch
+    PreferTheirs,
+}
+
+impl From<AutoMergeConflictArg> for ralph_core::AutoMergeConflictPolicy {
+    fn from(arg: AutoMergeConflictArg) -> Self {
+        match arg {
+            AutoMergeConflictArg::Abort => Self::Abort,
+            AutoMergeConflictArg::Keep => Self::Keep,
+            AutoMergeConflictArg::PreferOurs => Self::PreferOurs,
+            AutoMergeConflictArg::PreferTheirs => Self::PreferTheirs,
+        }
+    }
+}
+
 // Re-export colors and truncate from display module for use in this file
 use display::colors;
 use display::truncate;
@@ -230,7 +265,8 @@ use display::truncate;
 pub enum ConfigSource {
     /// Local file path (default behavior)
     File(PathBuf),
-    /// Builtin preset name (e.g., "builtin:feature")
+    /// Preset name (e.g., "preset:feature" or "builtin:feature"), resolved
+    /// across workspace, user, and embedded presets
     Builtin(String),
     /// Remote URL (e.g., "http://example.com/preset.yml")
     Remote(String),
@@ -243,7 +279,7 @@ impl ConfigSource {
     ///
     /// Format:
     /// - `core.field=value` → Override (for core.* fields)
-    /// - `builtin:preset-name` → Builtin preset
+    /// - `preset:preset-name` or `builtin:preset-name` → Preset
     /// - `http://...` or `https://...` → Remote URL
     /// - Anything else → File path
     fn parse(s: &str) -> Self {
@@ -258,7 +294,10 @@ impl ConfigSource {
             };
         }
         // Existing logic unchanged
-        if let Some(name) = s.strip_prefix("builtin:") {
+        if let Some(name) = s
+            .strip_prefix("preset:")
+            .or_else(|| s.strip_prefix("builtin:"))
+        {
             ConfigSource::Builtin(name.to_string())
         } else if s.starts_with("http://") || s.starts_with("https://") {
             ConfigSource::Remote(s.to_string())
@@ -268,6 +307,17 @@ impl ConfigSource {
     }
 }
 
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Builtin(name) => write!(f, "builtin:{}", name),
+            ConfigSource::Remote(url) => write!(f, "{}", url),
+            ConfigSource::Override { key, value } => write!(f, "{}={}", key, value),
+        }
+    }
+}
+
 /// Known core fields that can be overridden via CLI.
 const KNOWN_CORE_FIELDS: &[&str] = &["scratchpad", "specs_dir"];
 
@@ -352,9 +402,8 @@ pub(crate) fn load_config_with_overrides(
 
     config.normalize();
 
-    // Set workspace_root to current directory
-    config.core.workspace_root =
-        std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    // Set workspace_root to the resolved workspace (see `workspace_root`)
+    config.core.workspace_root = workspace_root::current_path();
 
     // Apply CLI config overrides
     let override_sources: Vec<_> = overrides.into_iter().cloned().collect();
@@ -373,7 +422,7 @@ struct Cli {
     // ─────────────────────────────────────────────────────────────────────────
     // Global options (available for all subcommands)
     // ─────────────────────────────────────────────────────────────────────────
-    /// Configuration source: file path, builtin:preset, URL, or core.field=value override.
+    /// Configuration source: file path, preset:name (or builtin:name), URL, or core.field=value override.
     /// Can be specified multiple times. Overrides are applied after config file loading.
     #[arg(short, long, default_value = "ralph.yml", global = true, action = ArgAction::Append)]
     config: Vec<String>,
@@ -385,6 +434,11 @@ struct Cli {
     /// Color output mode (auto, always, never)
     #[arg(long, value_enum, default_value_t = ColorMode::Auto, global = true)]
     color: ColorMode,
+
+    /// Workspace root (default: $RALPH_WORKSPACE, else the nearest directory
+    /// with a ralph.yml up to the git root, else the current directory)
+    #[arg(long, value_name = "PATH", global = true)]
+    workspace: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -398,6 +452,9 @@ enum Commands {
     /// Run first-run diagnostics and environment checks
     Doctor(doctor::DoctorArgs),
 
+    /// Collect diagnostics for bug reports
+    Diagnostics(diagnostics_cli::DiagnosticsArgs),
+
     /// Interactive walkthrough of hats, presets, and workflow
     Tutorial(TutorialArgs),
 
@@ -433,6 +490,9 @@ enum Commands {
     /// Manage parallel loops
     Loops(loops::LoopsArgs),
 
+    /// Browse the history of past runs
+    Runs(runs::RunsArgs),
+
     /// Manage configured hats
     Hats(hats::HatsArgs),
 
@@ -444,6 +504,10 @@ enum Commands {
 
     /// Generate shell completions
     Completions(CompletionsArgs),
+
+    /// List dynamic completion values (used by the completion scripts)
+    #[command(name = completions::HELPER, hide = true)]
+    Complete(completions::CompleteArgs),
 }
 
 /// Arguments for the init subcommand.
@@ -463,6 +527,30 @@ struct InitArgs {
     #[arg(long, conflicts_with = "backend", conflicts_with = "preset")]
     list_presets: bool,
 
+    /// Scaffold a config from an existing one, stripping machine-specific
+    /// paths and secrets
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["backend", "preset", "list_presets"])]
+    from: Option<PathBuf>,
+
+    /// Where to write the config made with --from (default: ralph.yml)
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        requires = "from",
+        conflicts_with = "as_preset"
+    )]
+    output: Option<PathBuf>,
+
+    /// Save the config made with --from as a user preset, usable as
+    /// `-c preset:<NAME>` and `ralph init --preset <NAME>`
+    #[arg(long, value_name = "NAME", requires = "from")]
+    as_preset: Option<String>,
+
+    /// Reset cli.backend to auto-detection in the config made with --from
+    #[arg(long, requires = "from")]
+    generalize_backend: bool,
+
     /// Overwrite existing ralph.yml if present
     #[arg(long)]
     force: bool,
@@ -479,15 +567,25 @@ struct RunArgs {
     #[arg(short = 'b', long = "backend", value_name = "BACKEND")]
     backend: Option<String>,
 
-    /// Prompt file path (mutually exclusive with -p/--prompt)
+    /// Prompt file path, or `-` to read the prompt from stdin (mutually
+    /// exclusive with -p/--prompt)
     #[arg(short = 'P', long = "prompt-file", conflicts_with = "prompt_text")]
     prompt_file: Option<PathBuf>,
 
+    /// Fill `{{KEY}}` placeholders in the prompt (repeatable, overrides `vars:` in config)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_prompt_var)]
+    vars: Vec<(String, String)>,
+
     /// Override max iterations
     #[arg(long)]
     max_iterations: Option<u32>,
 
-    /// Override completion promise
+    /// Stop once the estimated spend reaches this many USD (priced from
+    /// backend usage with `cli.pricing`)
+    #[arg(long, value_name = "USD")]
+    max_cost: Option<f64>,
+
+    /// Override completion promise (comma-separated for multiple)
     #[arg(long)]
     completion_promise: Option<String>,
 
@@ -495,6 +593,11 @@ struct RunArgs {
     #[arg(long)]
     dry_run: bool,
 
+    /// Print the event flow the hats would follow, from the starting event
+    /// to completion, without invoking a backend
+    #[arg(long, conflicts_with = "dry_run")]
+    plan_only: bool,
+
     /// Continue from existing scratchpad (resume interrupted loop).
     /// Use this when a previous run was interrupted and you want to
     /// continue from where it left off.
@@ -532,6 +635,11 @@ struct RunArgs {
     #[arg(long)]
     no_auto_merge: bool,
 
+    /// What auto-merge does when a loop's branch conflicts with main
+    /// (overrides features.auto_merge_conflict).
+    #[arg(long, value_enum, value_name = "POLICY")]
+    auto_merge_conflict: Option<AutoMergeConflictArg>,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Preflight Options
     // ─────────────────────────────────────────────────────────────────────────
@@ -540,6 +648,20 @@ struct RunArgs {
     #[arg(long)]
     skip_preflight: bool,
 
+    /// Skip the backend warm-up prompt before the first iteration.
+    /// Overrides cli.warmup from config.
+    #[arg(long)]
+    skip_warmup: bool,
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Memory Options
+    // ─────────────────────────────────────────────────────────────────────────
+    /// Extract memories after a successful run, printing them for
+    /// confirmation before they are stored.
+    /// Enables features.memory.auto_extract with features.memory.review.
+    #[arg(long)]
+    review_memories: bool,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Verbosity Options
     // ─────────────────────────────────────────────────────────────────────────
@@ -555,6 +677,51 @@ struct RunArgs {
     #[arg(long, value_name = "FILE")]
     record_session: Option<PathBuf>,
 
+    /// Write every event to stdout as a JSON line, in the events file's
+    /// record format. Implies --autonomous; agent output and logs stay off
+    /// stdout
+    #[arg(long, conflicts_with = "verbose")]
+    json_events_on_stdout: bool,
+
+    /// Record output verbatim, without masking secrets (see features.capture.redact)
+    #[arg(long)]
+    no_redact: bool,
+
+    /// Fail if hat instructions reference unknown `{{variables}}`
+    /// instead of leaving them as written (sets event_loop.strict_templates)
+    #[arg(long)]
+    strict_templates: bool,
+
+    /// Publish this event first instead of event_loop.starting_event.
+    /// Fails if no hat triggers on it
+    #[arg(
+        long = "start-event",
+        visible_alias = "starting-event",
+        value_name = "TOPIC",
+        conflicts_with = "continue_mode"
+    )]
+    start_event: Option<String>,
+
+    /// Payload of the starting event instead of the prompt; `@FILE` reads
+    /// it from a file
+    #[arg(long, value_name = "TEXT|@FILE", conflicts_with = "continue_mode")]
+    start_payload: Option<String>,
+
+    /// Emit this event when the loop stops without completing
+    /// (sets event_loop.on_failure_emit)
+    #[arg(long, value_name = "TOPIC")]
+    on_failure_emit: Option<String>,
+
+    /// Write the JSON run summary here instead of .ralph/agent/run-summary.json
+    #[arg(long, value_name = "PATH")]
+    summary_out: Option<PathBuf>,
+
+    /// Stop cleanly (exit 0) as soon as this event is published, even
+    /// without the completion promise. Resume later with --continue
+    /// (sets event_loop.until_event)
+    #[arg(long, value_name = "TOPIC")]
+    until_event: Option<String>,
+
     /// Custom backend command and arguments (use after --)
     #[arg(last = true)]
     custom_args: Vec<String>,
@@ -570,6 +737,10 @@ struct ResumeArgs {
     #[arg(long)]
     max_iterations: Option<u32>,
 
+    /// Fill `{{KEY}}` placeholders in the prompt (repeatable, overrides `vars:` in config)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_prompt_var)]
+    vars: Vec<(String, String)>,
+
     /// Disable TUI observation mode (TUI is enabled by default)
     #[arg(long, conflicts_with = "autonomous")]
     no_tui: bool,
@@ -593,6 +764,10 @@ struct ResumeArgs {
     /// Record session to JSONL file for replay testing
     #[arg(long, value_name = "FILE")]
     record_session: Option<PathBuf>,
+
+    /// Record output verbatim, without masking secrets (see features.capture.redact)
+    #[arg(long)]
+    no_redact: bool,
 }
 
 /// Arguments for the events subcommand.
@@ -610,17 +785,54 @@ struct EventsArgs {
     #[arg(long)]
     iteration: Option<u32>,
 
+    /// Only show events at or after this time: a duration ago (30s, 10m, 2h, 1d, 1w)
+    /// or an RFC 3339 timestamp
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Show table times in UTC instead of the local timezone
+    #[arg(long)]
+    utc: bool,
+
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
     format: OutputFormat,
 
-    /// Path to events file (default: auto-detects current run)
+    /// Path to events file (default: auto-detects current run). Wildcards in
+    /// the file name (e.g. 'events-*.jsonl') read every matching file, merged
+    /// by timestamp
     #[arg(long)]
     file: Option<PathBuf>,
 
+    /// Read every run's events file in .ralph/, merged by timestamp
+    #[arg(long, conflicts_with = "file")]
+    all_runs: bool,
+
     /// Clear the event history
-    #[arg(long)]
+    #[arg(long, conflicts_with = "all_runs")]
     clear: bool,
+
+    #[command(subcommand)]
+    command: Option<EventsCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum EventsCommands {
+    /// Summarize the events file: counts per topic and iteration, time span,
+    /// and the longest gap between events
+    Stats(EventsStatsArgs),
+}
+
+/// Arguments for the events stats subcommand.
+#[derive(Parser, Debug)]
+struct EventsStatsArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Path to events file (default: auto-detects current run)
+    #[arg(long)]
+    file: Option<PathBuf>,
 }
 
 /// Arguments for the clean subcommand.
@@ -633,6 +845,19 @@ struct CleanArgs {
     /// Clean diagnostic logs instead of .agent directory
     #[arg(long)]
     diagnostics: bool,
+
+    /// Remove everything left by finished loops: worktrees, stale registry
+    /// entries and locks, old event files and diagnostics
+    #[arg(long, visible_alias = "deep", conflicts_with = "diagnostics")]
+    all_loops: bool,
+
+    /// With --all-loops, clean even if a loop is still running
+    #[arg(long, requires = "all_loops")]
+    force: bool,
+
+    /// With --all-loops, only delete event files older than this many days
+    #[arg(long, value_name = "DAYS", default_value_t = 7, requires = "all_loops")]
+    max_event_age_days: u64,
 }
 
 /// Arguments for the emit subcommand.
@@ -685,6 +910,15 @@ struct PlanArgs {
     #[arg(long)]
     teams: bool,
 
+    /// Run without a user: print mode, captured output, nonzero exit if the
+    /// SOP does not produce its files (requires --output-dir)
+    #[arg(long, requires = "output_dir")]
+    non_interactive: bool,
+
+    /// Directory the SOP writes its artifacts to
+    #[arg(short, long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
     /// Custom backend command and arguments (use after --)
     #[arg(last = true)]
     custom_args: Vec<String>,
@@ -709,6 +943,15 @@ struct CodeTaskArgs {
     #[arg(long)]
     teams: bool,
 
+    /// Run without a user: print mode, captured output, nonzero exit if the
+    /// SOP does not produce its files (requires --output-dir)
+    #[arg(long, requires = "output_dir")]
+    non_interactive: bool,
+
+    /// Directory the SOP writes its artifacts to
+    #[arg(short, long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
     /// Custom backend command and arguments (use after --)
     #[arg(last = true)]
     custom_args: Vec<String>,
@@ -723,11 +966,7 @@ struct CompletionsArgs {
 }
 
 fn completions_command(args: CompletionsArgs) -> Result<()> {
-    use clap_complete::generate;
-
-    let mut cli = Cli::command();
-    generate(args.shell, &mut cli, "ralph", &mut std::io::stdout());
-    Ok(())
+    completions::generate(args.shell, &Cli::command(), &mut std::io::stdout())
 }
 
 #[tokio::main]
@@ -738,101 +977,117 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Pin the workspace root and run from it, so relative config paths
+    // (scratchpad, specs, .ralph/) resolve there
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let workspace = workspace_root::resolve_workspace_root(
+        cli.workspace.as_deref(),
+        std::env::var_os(workspace_root::WORKSPACE_ENV),
+        &cwd,
+    )?;
+    if workspace.path != cwd {
+        std::env::set_current_dir(&workspace.path)
+            .with_context(|| format!("Failed to enter workspace {}", workspace.path.display()))?;
+    }
+    workspace_root::pin(workspace.clone(), cwd.clone());
+
     // Detect if TUI mode is requested - TUI owns the terminal, so logs must not go to stdout
     // TUI is enabled by default unless --no-tui is specified or --autonomous is used
     let tui_enabled = match &cli.command {
-        Some(Commands::Run(args)) => !args.no_tui && !args.autonomous,
+        Some(Commands::Run(args)) => {
+            !args.no_tui && !args.autonomous && !args.json_events_on_stdout
+        }
         Some(Commands::Resume(args)) => !args.no_tui && !args.autonomous,
         None => true,
         _ => false,
     };
 
     // Initialize logging - suppress in TUI mode to avoid corrupting the display
-    let filter = if cli.verbose { "debug" } else { "info" };
+    // The completion helper's stdout is parsed by the shell, so it logs nothing
+    let filter = if matches!(cli.command, Some(Commands::Complete(_))) {
+        "off"
+    } else if cli.verbose {
+        "debug"
+    } else {
+        "info"
+    };
 
     // Check if diagnostics are enabled
     let diagnostics_enabled = std::env::var("RALPH_DIAGNOSTICS")
         .map(|v| v == "1")
         .unwrap_or(false);
 
-    if tui_enabled {
-        // TUI mode: logs would corrupt the display, so write to a rotating log file
-        if let Ok((file, _log_path)) =
+    // Log lines pass through the installed redactor, which the loop sets once
+    // the config (and its `security` rules) is loaded
+    {
+        use ralph_core::RedactingMakeWriter;
+        use ralph_core::diagnostics::DiagnosticTraceLayer;
+        use tracing_subscriber::prelude::*;
+
+        let fmt_layer = if tui_enabled {
+            // TUI mode: logs would corrupt the display, so write to a rotating log file.
+            // If log file creation fails, silently continue without logging
             ralph_core::diagnostics::create_log_file(std::path::Path::new("."))
-        {
-            if diagnostics_enabled {
-                use ralph_core::diagnostics::DiagnosticTraceLayer;
-                use tracing_subscriber::prelude::*;
-
-                if let Ok(collector) =
-                    ralph_core::diagnostics::DiagnosticsCollector::new(std::path::Path::new("."))
-                    && let Some(session_dir) = collector.session_dir()
-                {
-                    if let Ok(trace_layer) = DiagnosticTraceLayer::new(session_dir) {
-                        tracing_subscriber::registry()
-                            .with(
-                                tracing_subscriber::fmt::layer()
-                                    .with_writer(std::sync::Mutex::new(file))
-                                    .with_ansi(false),
-                            )
-                            .with(tracing_subscriber::EnvFilter::new(filter))
-                            .with(trace_layer)
-                            .init();
-                    } else {
-                        tracing_subscriber::fmt()
-                            .with_env_filter(filter)
-                            .with_writer(std::sync::Mutex::new(file))
-                            .with_ansi(false)
-                            .init();
-                    }
-                }
-            } else {
-                tracing_subscriber::fmt()
-                    .with_env_filter(filter)
-                    .with_writer(std::sync::Mutex::new(file))
-                    .with_ansi(false)
-                    .init();
-            }
-        }
-        // If log file creation fails, silently continue without logging
-    } else {
-        // Normal mode: logs go to stdout
-        if diagnostics_enabled {
-            // Normal mode + diagnostics: stdout + trace layer
-            use ralph_core::diagnostics::DiagnosticTraceLayer;
-            use tracing_subscriber::prelude::*;
-
-            if let Ok(collector) =
-                ralph_core::diagnostics::DiagnosticsCollector::new(std::path::Path::new("."))
-                && let Some(session_dir) = collector.session_dir()
-            {
-                if let Ok(trace_layer) = DiagnosticTraceLayer::new(session_dir) {
-                    tracing_subscriber::registry()
-                        .with(tracing_subscriber::fmt::layer())
-                        .with(tracing_subscriber::EnvFilter::new(filter))
-                        .with(trace_layer)
-                        .init();
-                } else {
-                    // Fallback: just stdout
-                    tracing_subscriber::fmt().with_env_filter(filter).init();
-                }
-            } else {
-                // Fallback: just stdout
-                tracing_subscriber::fmt().with_env_filter(filter).init();
-            }
+                .ok()
+                .map(|(file, _log_path)| {
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(RedactingMakeWriter::new(std::sync::Mutex::new(file)))
+                        .with_ansi(false)
+                        .boxed()
+                })
+        } else if matches!(&cli.command, Some(Commands::Run(args)) if args.json_events_on_stdout) {
+            // stdout carries only event lines
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(RedactingMakeWriter::new(std::io::stderr))
+                    .boxed(),
+            )
         } else {
-            // Normal mode without diagnostics: just stdout
-            tracing_subscriber::fmt().with_env_filter(filter).init();
-        }
+            // Normal mode: logs go to stdout
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(RedactingMakeWriter::new(std::io::stdout))
+                    .boxed(),
+            )
+        };
+
+        let trace_layer = if diagnostics_enabled {
+            ralph_core::diagnostics::DiagnosticsCollector::new(std::path::Path::new("."))
+                .ok()
+                .and_then(|collector| {
+                    collector
+                        .session_dir()
+                        .and_then(|dir| DiagnosticTraceLayer::new(dir).ok())
+                })
+        } else {
+            None
+        };
+
+        tracing_subscriber::registry()
+            .with(fmt_layer)
+            .with(tracing_subscriber::EnvFilter::new(filter))
+            .with(trace_layer)
+            .with(telemetry::layer())
+            .init();
     }
 
     // Parse all config sources from CLI
-    let config_sources: Vec<ConfigSource> =
-        cli.config.iter().map(|s| ConfigSource::parse(s)).collect();
+    let config_sources: Vec<ConfigSource> = cli
+        .config
+        .iter()
+        .map(|s| ConfigSource::parse(s))
+        .map(|source| {
+            if workspace.path == cwd {
+                source
+            } else {
+                relative_to_invocation(source, &cwd)
+            }
+        })
+        .collect();
 
-    match cli.command {
+    let result = match cli.command {
         Some(Commands::Run(args)) => {
-            run_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(run_command(&config_sources, cli.verbose, cli.color, args)).await
         }
         Some(Commands::Preflight(args)) => {
             preflight::execute(&config_sources, args, cli.color.should_use_colors()).await
@@ -840,50 +1095,93 @@ async fn main() -> Result<()> {
         Some(Commands::Doctor(args)) => {
             doctor::execute(&config_sources, args, cli.color.should_use_colors()).await
         }
+        Some(Commands::Diagnostics(args)) => {
+            diagnostics_cli::execute(&config_sources, args, cli.color.should_use_colors())
+        }
         Some(Commands::Tutorial(args)) => tutorial_command(cli.color, args),
         Some(Commands::Resume(args)) => {
-            resume_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(resume_command(
+                &config_sources,
+                cli.verbose,
+                cli.color,
+                args,
+            ))
+            .await
         }
         Some(Commands::Events(args)) => events_command(cli.color, args),
-        Some(Commands::Init(args)) => init_command(cli.color, args),
+        Some(Commands::Init(args)) => init_command(cli.color, cli.verbose, args),
         Some(Commands::Clean(args)) => clean_command(&config_sources, cli.color, args),
-        Some(Commands::Emit(args)) => emit_command(cli.color, args),
+        Some(Commands::Emit(args)) => emit_command(&config_sources, cli.color, args),
         Some(Commands::Plan(args)) => plan_command(&config_sources, cli.color, args),
         Some(Commands::CodeTask(args)) => code_task_command(&config_sources, cli.color, args),
         Some(Commands::Task(args)) => code_task_command(&config_sources, cli.color, args),
         Some(Commands::Tools(args)) => tools::execute(args, cli.color.should_use_colors()).await,
         Some(Commands::Loops(args)) => loops::execute(args, cli.color.should_use_colors()),
+        Some(Commands::Runs(args)) => runs::execute(args, cli.color.should_use_colors()),
         Some(Commands::Hats(args)) => {
             hats::execute(&config_sources, args, cli.color.should_use_colors())
         }
-        Some(Commands::Web(args)) => web::execute(args).await,
+        Some(Commands::Web(mut args)) => {
+            if workspace.rule == workspace_root::WorkspaceRule::Flag {
+                args.workspace = Some(workspace.path);
+            }
+            web::execute(&config_sources, args).await
+        }
         Some(Commands::Bot(args)) => {
             bot::execute(args, &config_sources, cli.color.should_use_colors()).await
         }
         Some(Commands::Completions(args)) => completions_command(args),
+        Some(Commands::Complete(args)) => completions::execute(&config_sources, &args),
         None => {
             // Default to run with TUI enabled (new default behavior)
             let args = RunArgs {
                 prompt_text: None,
                 prompt_file: None,
+                vars: Vec::new(),
                 backend: None,
                 max_iterations: None,
+                max_cost: None,
                 completion_promise: None,
                 dry_run: false,
+                plan_only: false,
                 continue_mode: false,
                 no_tui: false, // TUI enabled by default
                 autonomous: false,
                 idle_timeout: None,
                 exclusive: false,
                 no_auto_merge: false,
+                auto_merge_conflict: None,
                 skip_preflight: false,
+                skip_warmup: false,
+                review_memories: false,
                 verbose: false,
                 quiet: false,
                 record_session: None,
+                json_events_on_stdout: false,
+                no_redact: false,
+                strict_templates: false,
+                start_event: None,
+                start_payload: None,
+                on_failure_emit: None,
+                summary_out: None,
+                until_event: None,
                 custom_args: Vec::new(),
             };
-            run_command(&config_sources, cli.verbose, cli.color, args).await
+            Box::pin(run_command(&config_sources, cli.verbose, cli.color, args)).await
         }
+    };
+    telemetry::shutdown();
+    result
+}
+
+/// Keeps a relative `-c` file that exists where ralph was invoked pointing at
+/// that file after `main` changes into the workspace root.
+fn relative_to_invocation(source: ConfigSource, invocation_dir: &Path) -> ConfigSource {
+    match source {
+        ConfigSource::File(path) if path.is_relative() && invocation_dir.join(&path).exists() => {
+            ConfigSource::File(invocation_dir.join(path))
+        }
+        other => other,
     }
 }
 
@@ -1002,6 +1300,29 @@ async fn run_auto_preflight(
     }
 }
 
+/// Sends the backend a trivial prompt so a CLI that is not installed, not
+/// logged in, or not responding fails the run before the first iteration.
+async fn run_backend_warmup(
+    config: &RalphConfig,
+    skip_warmup: bool,
+    custom_args: &[String],
+) -> Result<()> {
+    if skip_warmup || !config.cli.warmup_enabled() {
+        return Ok(());
+    }
+
+    let mut backend = CliBackend::from_ralph_config(config)?;
+    backend.args.extend(custom_args.iter().cloned());
+    info!("Warming up backend: {}", backend.command);
+    probe_backend(&backend, DEFAULT_PROBE_TIMEOUT)
+        .await
+        .map_err(|failure| {
+            anyhow::anyhow!(
+                "Backend warm-up failed: {failure}\n\nUse --skip-warmup or set cli.warmup: false to bypass."
+            )
+        })
+}
+
 fn print_preflight_summary(
     report: &PreflightReport,
     verbose: bool,
@@ -1071,7 +1392,7 @@ async fn run_command(
                 }
             }
             ConfigSource::Builtin(name) => {
-                let preset = presets::get_preset(name).ok_or_else(|| {
+                let preset = presets::preset_content(name).ok_or_else(|| {
                     let available = presets::preset_names().join(", ");
                     anyhow::anyhow!(
                         "Unknown preset '{}'. Run `ralph run --list-presets` to see available presets.\n\nAvailable: {}",
@@ -1079,7 +1400,7 @@ async fn run_command(
                         available
                     )
                 })?;
-                RalphConfig::parse_yaml(preset.content)
+                RalphConfig::parse_yaml(&preset)
                     .with_context(|| format!("Failed to parse builtin preset '{}'", name))?
             }
             ConfigSource::Remote(url) => {
@@ -1121,47 +1442,65 @@ async fn run_command(
     // Normalize v1 flat fields into v2 nested structure
     config.normalize();
 
-    // Set workspace_root to current directory (critical for E2E tests in isolated workspaces).
+    // Set workspace_root to the resolved workspace (critical for E2E tests in isolated workspaces).
     // This must happen after config load because workspace_root has #[serde(skip)] and
-    // defaults to cwd at deserialize time - but we need it set to the actual runtime cwd.
-    config.core.workspace_root =
-        std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    // defaults to cwd at deserialize time - but we need it set to the actual runtime root.
+    config.core.workspace_root = workspace_root::current_path();
 
     // Apply CLI config overrides (takes precedence over config file values)
     let override_sources: Vec<_> = overrides.into_iter().cloned().collect();
     apply_config_overrides(&mut config, &override_sources)?;
 
-    // Handle --continue mode: check scratchpad exists before proceeding
+    // Handle --continue mode: check there is a previous state to continue from.
+    // The loop state snapshot, when present, is restored by the event loop.
     let resume = args.continue_mode;
     if resume {
         let scratchpad_path = std::path::Path::new(&config.core.scratchpad);
-        if !scratchpad_path.exists() {
+        let snapshot_path =
+            LoopContext::primary(config.core.workspace_root.clone()).loop_state_path();
+        if snapshot_path.exists() {
+            info!(
+                "Found loop state snapshot at '{}', continuing from previous state",
+                snapshot_path.display()
+            );
+        } else if scratchpad_path.exists() {
+            info!(
+                "Found existing scratchpad at '{}', continuing from previous state",
+                config.core.scratchpad
+            );
+        } else {
             anyhow::bail!(
                 "Cannot continue: scratchpad not found at '{}'. \
-                 Start a fresh run with `ralph run`.",
+                 Start a fresh run with `ralph run`, or restore an archived one \
+                 with `ralph tools scratchpad restore <timestamp>`.",
                 config.core.scratchpad
             );
         }
-        info!(
-            "Found existing scratchpad at '{}', continuing from previous state",
-            config.core.scratchpad
-        );
     }
 
     // Apply CLI overrides (after normalization so they take final precedence)
-    // Per spec: CLI -p and -P are mutually exclusive (enforced by clap)
-    if let Some(text) = args.prompt_text {
-        config.event_loop.prompt = Some(text);
-        config.event_loop.prompt_file = String::new(); // Clear file path
-    } else if let Some(path) = args.prompt_file {
-        config.event_loop.prompt_file = path.to_string_lossy().to_string();
-        config.event_loop.prompt = None; // Clear inline
-    }
+    let prompt_from_stdin = apply_prompt_args(
+        &mut config,
+        args.prompt_text,
+        args.prompt_file,
+        std::io::stdin().lock(),
+    )?;
+    config.vars.extend(args.vars);
     if let Some(max_iter) = args.max_iterations {
         config.event_loop.max_iterations = max_iter;
     }
+    if let Some(max_cost) = args.max_cost {
+        config.event_loop.max_cost_usd = Some(max_cost);
+    }
     if let Some(promise) = args.completion_promise {
-        config.event_loop.completion_promise = promise;
+        config.event_loop.completion_promise = ralph_core::CompletionPromises::parse_list(&promise);
+    }
+    if args.review_memories {
+        config.features.memory.auto_extract = true;
+        config.features.memory.review = true;
+    }
+    if let Some(policy) = args.auto_merge_conflict {
+        config.features.auto_merge_conflict = policy.into();
     }
     if verbose {
         config.verbose = true;
@@ -1169,7 +1508,7 @@ async fn run_command(
 
     // Apply execution mode overrides per spec
     // TUI is enabled by default (unless --no-tui is specified)
-    if args.autonomous {
+    if args.autonomous || args.json_events_on_stdout {
         config.cli.default_mode = "autonomous".to_string();
     } else if !args.no_tui {
         config.cli.default_mode = "interactive".to_string();
@@ -1180,6 +1519,35 @@ async fn run_command(
         config.cli.idle_timeout_secs = timeout;
     }
 
+    if args.no_redact {
+        config.features.capture.redact.clear();
+    }
+
+    if args.strict_templates {
+        config.event_loop.strict_templates = true;
+    }
+
+    if let Some(topic) = args.start_event {
+        apply_starting_event(&mut config, topic)?;
+    }
+
+    if let Some(payload) = args.start_payload {
+        config.event_loop.starting_payload = Some(read_start_payload(&payload)?);
+    }
+
+    if let Some(topic) = args.on_failure_emit {
+        config.event_loop.on_failure_emit = Some(topic);
+    }
+
+    if let Some(topic) = args.until_event {
+        config.event_loop.until_event = Some(topic);
+    }
+
+    // Relative to where ralph was invoked, even when the loop runs in a worktree
+    if let Some(path) = args.summary_out {
+        config.event_loop.summary_out = Some(config.core.workspace_root.join(path));
+    }
+
     // Apply backend override from CLI (takes precedence over config)
     if let Some(backend) = args.backend {
         config.cli.backend = backend;
@@ -1193,12 +1561,14 @@ async fn run_command(
         eprintln!("{warning}");
     }
 
+    // The plan only needs the hat topology, so stop before touching backends
+    if args.plan_only {
+        return hats::print_event_plan(&mut std::io::stdout(), &config);
+    }
+
     // Handle auto-detection if backend is "auto"
     if config.cli.backend == "auto" {
-        let priority = config.get_agent_priority();
-        let detected = detect_backend(&priority, |backend| {
-            config.adapter_settings(backend).enabled
-        });
+        let detected = detect_configured_backend(&config);
 
         match detected {
             Ok(backend) => {
@@ -1212,6 +1582,10 @@ async fn run_command(
         }
     }
 
+    // Resolve the prompt and fill its placeholders now, so a missing prompt
+    // file or variable fails before anything is spawned
+    let rendered_prompt = render_configured_prompt(&mut config, resume)?;
+
     let preflight_verbose = verbose || args.verbose;
 
     if args.dry_run {
@@ -1223,6 +1597,12 @@ async fn run_command(
         )
         .await?;
         println!("Dry run mode - configuration:");
+        let workspace = workspace_root::current()?;
+        println!(
+            "  Workspace: {} ({})",
+            workspace.path.display(),
+            workspace.rule
+        );
         println!(
             "  Hats: {}",
             if config.hats.is_empty() {
@@ -1232,24 +1612,54 @@ async fn run_command(
             }
         );
 
-        // Show prompt source
-        if let Some(ref inline) = config.event_loop.prompt {
-            let preview = if inline.len() > 60 {
-                format!("{}...", &inline[..60].replace('\n', " "))
-            } else {
-                inline.replace('\n', " ")
-            };
+        // Show prompt source, previewing the prompt after variable substitution
+        let flat = rendered_prompt.replace('\n', " ");
+        let preview = if flat.chars().count() > 60 {
+            format!("{}...", flat.chars().take(60).collect::<String>())
+        } else {
+            flat
+        };
+        if prompt_from_stdin {
+            println!("  Prompt: stdin ({})", preview);
+        } else if config.event_loop.prompt.is_some() {
             println!("  Prompt: inline text ({})", preview);
         } else {
             println!("  Prompt file: {}", config.event_loop.prompt_file);
+            println!("  Prompt preview: {}", preview);
         }
 
+        // --continue always publishes task.resume with the prompt
+        let start_topic = match &config.event_loop.starting_event {
+            _ if resume => "task.resume",
+            Some(topic) => topic.as_str(),
+            None => "task.start",
+        };
+        match &config.event_loop.starting_payload {
+            Some(payload) if !resume => println!(
+                "  Starting event: {} (payload: {})",
+                start_topic,
+                ralph_core::truncate_with_ellipsis(&payload.replace('\n', " "), 60)
+            ),
+            _ => println!("  Starting event: {} (payload: prompt)", start_topic),
+        }
         println!(
             "  Completion promise: {}",
             config.event_loop.completion_promise
         );
         println!("  Max iterations: {}", config.event_loop.max_iterations);
+        if let Some(check) = &config.event_loop.completion_check {
+            println!(
+                "  Completion check: {} (timeout {}s, max {} rejections)",
+                check.command, check.timeout_secs, check.max_rejections
+            );
+        }
+        if let Some(warning) = hats::hat_budget_warning(&config) {
+            println!("  Hat budgets: warning: {}", warning);
+        }
         println!("  Max runtime: {}s", config.event_loop.max_runtime_seconds);
+        if let Some(max_cost) = config.event_loop.max_cost_usd {
+            println!("  Max cost: ${:.2}", max_cost);
+        }
         println!("  Scratchpad: {}", config.core.scratchpad);
         println!("  Specs dir: {}", config.core.specs_dir);
         println!("  Backend: {}", config.cli.backend);
@@ -1273,26 +1683,8 @@ async fn run_command(
     ensure_scratchpad_directory(&config)?;
 
     // Get the prompt for lock metadata (short version for display)
-    // When prompt_file is used, read its content for the summary instead of showing the file path
-    let prompt_summary = config
-        .event_loop
-        .prompt
-        .clone()
-        .or_else(|| {
-            let prompt_file = &config.event_loop.prompt_file;
-            if prompt_file.is_empty() {
-                None
-            } else {
-                let path = std::path::Path::new(prompt_file);
-                if path.exists() {
-                    std::fs::read_to_string(path).ok()
-                } else {
-                    None
-                }
-            }
-        })
-        .map(|p| truncate(&p, 100))
-        .unwrap_or_else(|| "[no prompt]".to_string());
+    // When prompt_file is used, the summary shows its rendered content instead of the file path
+    let prompt_summary = truncate(&rendered_prompt, 100);
 
     let mut pending_worktree_registration: Option<LoopEntry> = None;
 
@@ -1336,7 +1728,7 @@ async fn run_command(
                     existing.prompt.chars().take(50).collect::<String>()
                 );
 
-                let worktree_config = WorktreeConfig::default();
+                let worktree_config = config.features.worktree_config();
 
                 // Generate memorable loop ID (adjective-noun only, no prompt keywords)
                 // This ID will be used consistently for: registry ID, worktree path, and branch name
@@ -1346,8 +1738,8 @@ async fn run_command(
                     ralph_core::worktree_exists(workspace_root, name, &worktree_config)
                 });
 
-                // Ensure worktree directory is in .gitignore
-                ensure_gitignore(workspace_root, ".worktrees")
+                // Ensure worktree directory is in .gitignore (if it's inside the repo)
+                ensure_worktree_dir_ignored(workspace_root, &worktree_config)
                     .context("Failed to update .gitignore for worktrees")?;
 
                 // Create the worktree
@@ -1422,23 +1814,31 @@ async fn run_command(
         .ensure_directories()
         .context("Failed to create loop directories")?;
 
-    if let Err(err) = run_auto_preflight(
-        &config,
-        args.skip_preflight,
-        preflight_verbose,
-        AutoPreflightMode::Run,
-    )
-    .await
-    {
-        if !loop_context.is_primary()
-            && let Err(clean_err) =
+    let startup_checks = async {
+        run_auto_preflight(
+            &config,
+            args.skip_preflight,
+            preflight_verbose,
+            AutoPreflightMode::Run,
+        )
+        .await?;
+        run_backend_warmup(&config, args.skip_warmup, &args.custom_args).await
+    };
+    if let Err(err) = startup_checks.await {
+        loop_runner::notify_preflight_failure(&config, &loop_context, &err.to_string());
+        if !loop_context.is_primary() {
+            if let Err(clean_err) =
                 remove_worktree(loop_context.repo_root(), loop_context.workspace())
-        {
-            warn!(
-                "Preflight failed; unable to remove worktree {}: {}",
-                loop_context.workspace().display(),
-                clean_err
-            );
+            {
+                warn!(
+                    "Preflight failed; unable to remove worktree {}: {}",
+                    loop_context.workspace().display(),
+                    clean_err
+                );
+            } else if let Some(loop_id) = loop_context.loop_id() {
+                // The branch has no commits yet, so a safe delete suffices
+                let _ = delete_branch(loop_context.repo_root(), &format!("ralph/{loop_id}"), false);
+            }
         }
         return Err(err);
     }
@@ -1452,8 +1852,12 @@ async fn run_command(
 
     // Run the orchestration loop and exit with proper exit code
     // TUI is enabled by default (unless --no-tui or --autonomous is specified)
-    let enable_tui = !args.no_tui && !args.autonomous;
-    let verbosity = Verbosity::resolve(verbose || args.verbose, args.quiet);
+    let enable_tui = !args.no_tui && !args.autonomous && !args.json_events_on_stdout;
+    // Agent output would interleave with the event lines
+    let verbosity = Verbosity::resolve(
+        verbose || args.verbose,
+        args.quiet || args.json_events_on_stdout,
+    );
     let custom_args = args.custom_args;
     // --no-auto-merge CLI flag overrides config.features.auto_merge
     let auto_merge_override = if args.no_auto_merge {
@@ -1462,17 +1866,19 @@ async fn run_command(
         None
     };
     let workspace_root = config.core.workspace_root.clone();
-    let reason = loop_runner::run_loop_impl(
+    let reason = Box::pin(loop_runner::run_loop_impl(
         config,
         color_mode,
         resume,
         enable_tui,
         verbosity,
         args.record_session,
+        args.json_events_on_stdout,
         Some(loop_context),
         custom_args,
         auto_merge_override,
-    )
+        config_sources.iter().map(ToString::to_string).collect(),
+    ))
     .await?;
 
     // Handle restart: exec-replace current process with same CLI args
@@ -1500,12 +1906,131 @@ async fn run_command(
 
     // Use explicit exit for non-zero codes to ensure proper exit status
     if exit_code != 0 {
+        telemetry::shutdown();
         std::process::exit(exit_code);
     }
 
     Ok(())
 }
 
+/// `-P` value that reads the prompt from stdin.
+const STDIN_PROMPT_FILE: &str = "-";
+
+/// Applies `-p` / `-P` to the config; `-P -` reads the prompt from `stdin`.
+///
+/// Returns true when the prompt was read from stdin.
+fn apply_prompt_args(
+    config: &mut RalphConfig,
+    prompt_text: Option<String>,
+    prompt_file: Option<PathBuf>,
+    mut stdin: impl std::io::Read,
+) -> Result<bool> {
+    // Per spec: CLI -p and -P are mutually exclusive (enforced by clap)
+    if let Some(text) = prompt_text {
+        config.event_loop.prompt = Some(text);
+        config.event_loop.prompt_file = String::new(); // Clear file path
+    } else if let Some(path) = prompt_file {
+        if path.as_os_str() == STDIN_PROMPT_FILE {
+            let mut text = String::new();
+            stdin
+                .read_to_string(&mut text)
+                .context("Failed to read prompt from stdin")?;
+            if text.trim().is_empty() {
+                anyhow::bail!("No prompt on stdin (-P -)");
+            }
+            config.event_loop.prompt = Some(text);
+            config.event_loop.prompt_file = String::new();
+            return Ok(true);
+        }
+        config.event_loop.prompt_file = path.to_string_lossy().to_string();
+        config.event_loop.prompt = None; // Clear inline
+    }
+    Ok(false)
+}
+
+/// Overrides `event_loop.starting_event`.
+///
+/// With custom hats, fails unless a hat triggers on `topic`, listing the
+/// triggers that would work. In solo mode Ralph handles every event.
+fn apply_starting_event(config: &mut RalphConfig, topic: String) -> Result<()> {
+    let triggered = config.hats.values().any(|hat| {
+        hat.triggers
+            .iter()
+            .any(|trigger| ralph_proto::Topic::new(trigger.as_str()).matches_str(&topic))
+    });
+    if !config.hats.is_empty() && !triggered {
+        let mut triggers: Vec<&str> = config
+            .hats
+            .values()
+            .flat_map(|hat| hat.triggers.iter().map(String::as_str))
+            .collect();
+        triggers.sort_unstable();
+        triggers.dedup();
+        anyhow::bail!(
+            "Start event '{}' does not match any hat's triggers. Valid triggers: {}",
+            topic,
+            triggers.join(", ")
+        );
+    }
+    config.event_loop.starting_event = Some(topic);
+    Ok(())
+}
+
+/// Reads a `--start-payload` value: literal text, or `@FILE` for a file's
+/// contents (relative to where ralph was invoked).
+fn read_start_payload(value: &str) -> Result<String> {
+    let Some(path) = value.strip_prefix('@') else {
+        return Ok(value.to_string());
+    };
+    let path = workspace_root::invocation_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| PathBuf::from(path));
+    std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read start payload from {}", path.display()))
+}
+
+/// Parses a `--var KEY=VALUE` flag.
+fn parse_prompt_var(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
+
+/// Prompt used by `--continue` when the configured prompt is unavailable.
+const CONTINUE_FALLBACK_PROMPT: &str =
+    "Continue the previous run from the scratchpad; the original prompt is unavailable.";
+
+/// Loads the configured prompt and fills its `{{name}}` placeholders.
+///
+/// A missing, unreadable, or blank prompt is an error, as are unresolved
+/// placeholders. With `--continue` the scratchpad carries the state, so an
+/// unavailable prompt only warns and the run continues from
+/// [`CONTINUE_FALLBACK_PROMPT`], which is written back into `config`.
+fn render_configured_prompt(config: &mut RalphConfig, resume: bool) -> Result<String> {
+    match loop_runner::resolve_prompt_content(&config.event_loop, &config.vars) {
+        Err(err)
+            if resume
+                && err
+                    .downcast_ref::<ralph_core::PromptSourceError>()
+                    .is_some() =>
+        {
+            eprintln!(
+                "{}warning:{} {} Continuing from the scratchpad.",
+                colors::YELLOW,
+                colors::RESET,
+                err
+            );
+            config.event_loop.prompt = Some(CONTINUE_FALLBACK_PROMPT.to_string());
+            config.event_loop.prompt_file = String::new();
+            Ok(CONTINUE_FALLBACK_PROMPT.to_string())
+        }
+        result => result,
+    }
+}
+
 /// Resume a previously interrupted loop from existing scratchpad.
 ///
 /// DEPRECATED: Use `ralph run --continue` instead.
@@ -1548,6 +2073,7 @@ async fn resume_command(
     if let Some(max_iter) = args.max_iterations {
         config.event_loop.max_iterations = max_iter;
     }
+    config.vars.extend(args.vars);
     if verbose {
         config.verbose = true;
     }
@@ -1565,6 +2091,10 @@ async fn resume_command(
         config.cli.idle_timeout_secs = timeout;
     }
 
+    if args.no_redact {
+        config.features.capture.redact.clear();
+    }
+
     // Validate configuration
     let warnings = config
         .validate()
@@ -1575,10 +2105,7 @@ async fn resume_command(
 
     // Handle auto-detection if backend is "auto"
     if config.cli.backend == "auto" {
-        let priority = config.get_agent_priority();
-        let detected = detect_backend(&priority, |backend| {
-            config.adapter_settings(backend).enabled
-        });
+        let detected = detect_configured_backend(&config);
 
         match detected {
             Ok(backend) => {
@@ -1592,39 +2119,79 @@ async fn resume_command(
         }
     }
 
+    // Same leniency as `run --continue`: warn if the prompt is unavailable
+    render_configured_prompt(&mut config, true)?;
+
     // Run the orchestration loop in resume mode
     // The key difference: we publish task.resume instead of task.start,
     // signaling the planner to read the existing scratchpad
     // TUI is enabled by default (unless --no-tui or --autonomous is specified)
     let enable_tui = !args.no_tui && !args.autonomous;
     let verbosity = Verbosity::resolve(verbose || args.verbose, args.quiet);
-    let reason = loop_runner::run_loop_impl(
+    let reason = Box::pin(loop_runner::run_loop_impl(
         config,
         color_mode,
         true,
         enable_tui,
         verbosity,
         args.record_session,
+        false,      // Resume command doesn't stream events to stdout
         None,       // Deprecated resume command doesn't have loop_context
         Vec::new(), // Resume command doesn't support custom args
         None,       // Use config.features.auto_merge (deprecated command)
-    )
+        config_sources.iter().map(ToString::to_string).collect(),
+    ))
     .await?;
     let exit_code = reason.exit_code();
 
     if exit_code != 0 {
+        telemetry::shutdown();
         std::process::exit(exit_code);
     }
 
     Ok(())
 }
 
-fn init_command(color_mode: ColorMode, args: InitArgs) -> Result<()> {
+fn init_command(color_mode: ColorMode, verbose: bool, args: InitArgs) -> Result<()> {
     let use_colors = color_mode.should_use_colors();
 
-    // Handle --list-presets
+    // Handle --list-presets (-v adds each preset's hats and budgets)
     if args.list_presets {
-        println!("{}", init::format_preset_list());
+        println!("{}", init::format_preset_list(verbose));
+        return Ok(());
+    }
+
+    // Handle --from (scaffold from an existing config)
+    if let Some(source) = args.from {
+        let output = match (&args.as_preset, args.output) {
+            (Some(name), _) => init::user_preset_path(name)?,
+            (None, Some(output)) => output,
+            (None, None) => PathBuf::from("ralph.yml"),
+        };
+        let changes =
+            init::init_from_config(&source, &output, args.generalize_backend, args.force)?;
+        let msg = format!(
+            "Created {} from {} ({} field{} generalized)",
+            output.display(),
+            source.display(),
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" }
+        );
+        if use_colors {
+            println!("{}✓{} {}", colors::GREEN, colors::RESET, msg);
+        } else {
+            println!("{}", msg);
+        }
+        for field in &changes {
+            println!("  {}: {}", field.path, field.change);
+        }
+        if let Some(name) = args.as_preset {
+            println!(
+                "
+Run with: ralph run -c builtin:{}",
+                name
+            );
+        }
         return Ok(());
     }
 
@@ -1697,59 +2264,113 @@ fn init_command(color_mode: ColorMode, args: InitArgs) -> Result<()> {
     println!("Usage:");
     println!("  ralph init --backend <backend>   Generate minimal config for backend");
     println!("  ralph init --preset <preset>     Use an embedded preset");
-    println!("  ralph init --list-presets        Show available presets\n");
+    println!("  ralph init --list-presets        Show available presets");
+    println!("  ralph init --from <file>         Scaffold from an existing config\n");
     println!("Backends: claude, kiro, gemini, codex, amp, custom");
     println!("\nRun 'ralph init --list-presets' to see available presets.");
 
     Ok(())
 }
 
-fn events_command(color_mode: ColorMode, args: EventsArgs) -> Result<()> {
-    let use_colors = color_mode.should_use_colors();
-
+/// Opens `file`, or the current run's events file.
+fn events_history(file: Option<PathBuf>) -> EventHistory {
     // Read events path from marker file, fall back to default if marker doesn't exist
     // This ensures `ralph events` reads from the same events file as the active run
-    let history = match args.file {
+    match file {
         Some(path) => EventHistory::new(path),
         None => fs::read_to_string(".ralph/current-events")
             .map(|s| EventHistory::new(s.trim()))
             .unwrap_or_else(|_| EventHistory::default_path()),
-    };
+    }
+}
 
-    // Handle clear command
-    if args.clear {
-        history.clear()?;
-        if use_colors {
-            println!("{}✓{} Event history cleared", colors::GREEN, colors::RESET);
-        } else {
-            println!("Event history cleared");
-        }
-        return Ok(());
+fn events_command(color_mode: ColorMode, args: EventsArgs) -> Result<()> {
+    let use_colors = color_mode.should_use_colors();
+
+    if let Some(EventsCommands::Stats(stats_args)) = args.command {
+        return events_stats_command(use_colors, stats_args);
     }
 
-    if !history.exists() {
-        if use_colors {
-            println!(
-                "{}No event history found.{} Run `ralph` to generate events.",
-                colors::DIM,
-                colors::RESET
-            );
-        } else {
-            println!("No event history found. Run `ralph` to generate events.");
+    // Several files: --all-runs, or a glob in --file
+    let merged_files = if args.all_runs {
+        Some(event_sources::all_runs())
+    } else {
+        args.file
+            .as_deref()
+            .filter(|file| event_sources::is_glob(file))
+            .map(event_sources::expand_glob)
+    };
+
+    let (mut records, skipped_lines) = match merged_files {
+        Some(files) => {
+            if args.clear {
+                anyhow::bail!("--clear works on a single events file, not a pattern");
+            }
+            if files.is_empty() {
+                println!("No events files matched.");
+                return Ok(());
+            }
+            let merged = event_sources::read_merged(&files)?;
+            (merged.events, merged.skipped_lines)
         }
-        return Ok(());
-    }
+        None => {
+            let history = events_history(args.file);
 
-    // Read and filter events
-    let mut records = history.read_all()?;
+            // Handle clear command
+            if args.clear {
+                history.clear()?;
+                if use_colors {
+                    println!("{}✓{} Event history cleared", colors::GREEN, colors::RESET);
+                } else {
+                    println!("Event history cleared");
+                }
+                return Ok(());
+            }
+
+            if !history.exists() {
+                if use_colors {
+                    println!(
+                        "{}No event history found.{} Run `ralph` to generate events.",
+                        colors::DIM,
+                        colors::RESET
+                    );
+                } else {
+                    println!("No event history found. Run `ralph` to generate events.");
+                }
+                return Ok(());
+            }
+
+            let report = history.read_all_with_report()?;
+            let records = report
+                .records
+                .into_iter()
+                .map(|record| event_sources::SourcedEvent {
+                    source: None,
+                    record,
+                })
+                .collect();
+            (records, report.skipped_lines)
+        }
+    };
+    if skipped_lines > 0 {
+        eprintln!("Warning: skipped {skipped_lines} unparseable line(s) in the events file");
+    }
 
     // Apply filters in sequence
     if let Some(ref topic) = args.topic {
-        records.retain(|r| r.topic == *topic);
+        records.retain(|r| r.record.topic == *topic);
     }
 
     if let Some(iteration) = args.iteration {
-        records.retain(|r| r.iteration == iteration);
+        records.retain(|r| r.record.iteration == iteration);
+    }
+
+    // Events without a readable timestamp can't be placed in time, so --since drops them
+    if let Some(ref since) = args.since {
+        let since = time_args::parse_since(since, chrono::Utc::now())?;
+        records.retain(|r| {
+            chrono::DateTime::parse_from_rfc3339(&r.record.ts).is_ok_and(|ts| ts >= since)
+        });
     }
 
     // Apply 'last' filter after other filters (to get last N of filtered results)
@@ -1774,13 +2395,35 @@ fn events_command(color_mode: ColorMode, args: EventsArgs) -> Result<()> {
             println!("{json}");
         }
         OutputFormat::Table => {
-            display::print_events_table(&records, use_colors);
+            let (sources, records): (Vec<Option<String>>, Vec<EventRecord>) = records
+                .into_iter()
+                .map(|event| (event.source, event.record))
+                .unzip();
+            // Only merged reads tag records with their file
+            let sources: Option<Vec<String>> = sources.into_iter().collect();
+            display::print_events_table(&records, sources.as_deref(), use_colors, args.utc);
         }
     }
 
     Ok(())
 }
 
+fn events_stats_command(use_colors: bool, args: EventsStatsArgs) -> Result<()> {
+    let history = events_history(args.file);
+    if !history.exists() {
+        println!("No event history found. Run `ralph` to generate events.");
+        return Ok(());
+    }
+
+    let records = history.read_all()?;
+    let stats = event_stats::EventStats::from_records(&records);
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Table => event_stats::print_stats(&stats, use_colors),
+    }
+    Ok(())
+}
+
 fn clean_command(
     config_sources: &[ConfigSource],
     color_mode: ColorMode,
@@ -1790,10 +2433,26 @@ fn clean_command(
 
     // If --diagnostics flag is set, clean diagnostics directory
     if args.diagnostics {
-        let workspace_root = std::env::current_dir().context("Failed to get current directory")?;
+        let workspace_root = workspace_root::current()?.path;
         return ralph_cli::clean_diagnostics(&workspace_root, use_colors, args.dry_run);
     }
 
+    if args.all_loops {
+        let workspace_root = workspace_root::current()?.path;
+        // Run retention comes from config; fall back to the default if none loads
+        let runs_keep = load_config_with_overrides(config_sources)
+            .map(|config| config.core.runs_keep)
+            .unwrap_or_else(|_| ralph_core::CoreConfig::default().runs_keep);
+        let options = ralph_cli::DeepCleanOptions {
+            dry_run: args.dry_run,
+            force: args.force,
+            max_event_age: std::time::Duration::from_secs(args.max_event_age_days * 24 * 60 * 60),
+            runs_keep,
+        };
+        ralph_cli::clean_all_loops(&workspace_root, &options, use_colors)?;
+        return Ok(());
+    }
+
     // Load config with overrides applied
     let config = load_config_with_overrides(config_sources)?;
 
@@ -1878,7 +2537,11 @@ fn clean_command(
 ///
 /// Events are written to the path specified in `.ralph/current-events` marker file
 /// (created by `ralph run`), or falls back to `.ralph/events.jsonl` if no marker exists.
-fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
+fn emit_command(
+    config_sources: &[ConfigSource],
+    color_mode: ColorMode,
+    args: EmitArgs,
+) -> Result<()> {
     let use_colors = color_mode.should_use_colors();
 
     // Generate timestamp if not provided
@@ -1895,7 +2558,7 @@ fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
 
     // Build the event record
     // We use serde_json directly to ensure proper escaping
-    let record = serde_json::json!({
+    let mut record = serde_json::json!({
         "topic": args.topic,
         "payload": if args.json && !payload.is_empty() {
             // Parse and embed as object
@@ -1908,30 +2571,33 @@ fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
         "ts": ts
     });
 
+    let config = match load_config_with_overrides(config_sources) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            warn!("Skipping event schema check and redaction: {:#}", e);
+            None
+        }
+    };
+    if let Some(config) = &config {
+        check_emit_schema(config, &args.topic, &record["payload"])?;
+        // Mask secrets before the payload reaches the events file
+        config
+            .security
+            .apply(config.features.capture.redactor()?)?
+            .redact_json(&mut record);
+    }
+
     // Read events path from marker file, fall back to CLI arg if marker doesn't exist
     // This ensures `ralph emit` writes to the same events file as the active run
     let events_file = fs::read_to_string(".ralph/current-events")
         .map(|s| PathBuf::from(s.trim()))
         .unwrap_or_else(|_| args.file.clone());
 
-    // Ensure parent directory exists
-    if let Some(parent) = events_file.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-    }
-
-    // Append to file
-    let mut file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&events_file)
-        .with_context(|| format!("Failed to open events file: {}", events_file.display()))?;
-
-    // Write as single-line JSON (JSONL format)
+    // Write as single-line JSON (JSONL format), locked against concurrent writers
     let json_line = serde_json::to_string(&record)?;
-    writeln!(file, "{}", json_line)?;
+    EventHistory::new(&events_file)
+        .append(&json_line, false)
+        .with_context(|| format!("Failed to write events file: {}", events_file.display()))?;
 
     // Success message
     if use_colors {
@@ -1948,6 +2614,26 @@ fn emit_command(color_mode: ColorMode, args: EmitArgs) -> Result<()> {
     Ok(())
 }
 
+/// Checks an emitted payload against the topic's `event_schemas` entry.
+///
+/// With `event_loop.schema_mismatch: warn` a mismatch is only printed.
+fn check_emit_schema(config: &RalphConfig, topic: &str, payload: &serde_json::Value) -> Result<()> {
+    if config.event_schemas.is_empty() {
+        return Ok(());
+    }
+    let schemas = ralph_core::EventSchemas::compile(&config.event_schemas)?;
+    match schemas.check_value(topic, payload) {
+        Ok(()) => Ok(()),
+        Err(violation)
+            if config.event_loop.schema_mismatch == ralph_core::SchemaMismatchAction::Warn =>
+        {
+            eprintln!("Warning: {violation}");
+            Ok(())
+        }
+        Err(violation) => Err(anyhow::Error::new(violation).context("Event not emitted")),
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct TutorialStep {
     title: &'static str,
@@ -2092,7 +2778,7 @@ fn plan_command(
     color_mode: ColorMode,
     args: PlanArgs,
 ) -> Result<()> {
-    use sop_runner::{Sop, SopRunConfig, SopRunError};
+    use sop_runner::{Sop, SopRunConfig};
 
     let use_colors = color_mode.should_use_colors();
 
@@ -2125,16 +2811,11 @@ fn plan_command(
             Some(args.custom_args)
         },
         agent_teams: args.teams,
+        non_interactive: args.non_interactive,
+        output_dir: args.output_dir,
     };
 
-    sop_runner::run_sop(config).map_err(|e| match e {
-        SopRunError::NoBackend(no_backend) => anyhow::Error::new(no_backend),
-        SopRunError::UnknownBackend(name) => anyhow::anyhow!(
-            "Unknown backend: {}\n\nValid backends: claude, kiro, gemini, codex, amp",
-            name
-        ),
-        SopRunError::SpawnError(io_err) => anyhow::anyhow!("Failed to spawn backend: {}", io_err),
-    })
+    sop_runner::run_sop(config).map_err(sop_error)
 }
 
 /// Starts a code-task-generator session.
@@ -2146,7 +2827,7 @@ fn code_task_command(
     color_mode: ColorMode,
     args: CodeTaskArgs,
 ) -> Result<()> {
-    use sop_runner::{Sop, SopRunConfig, SopRunError};
+    use sop_runner::{Sop, SopRunConfig};
 
     let use_colors = color_mode.should_use_colors();
 
@@ -2179,16 +2860,26 @@ fn code_task_command(
             Some(args.custom_args)
         },
         agent_teams: args.teams,
+        non_interactive: args.non_interactive,
+        output_dir: args.output_dir,
     };
 
-    sop_runner::run_sop(config).map_err(|e| match e {
+    sop_runner::run_sop(config).map_err(sop_error)
+}
+
+/// Converts a SOP session error into a user-facing error.
+fn sop_error(e: sop_runner::SopRunError) -> anyhow::Error {
+    use sop_runner::SopRunError;
+
+    match e {
         SopRunError::NoBackend(no_backend) => anyhow::Error::new(no_backend),
         SopRunError::UnknownBackend(name) => anyhow::anyhow!(
             "Unknown backend: {}\n\nValid backends: claude, kiro, gemini, codex, amp",
             name
         ),
         SopRunError::SpawnError(io_err) => anyhow::anyhow!("Failed to spawn backend: {}", io_err),
-    })
+        other => anyhow::Error::new(other),
+    }
 }
 
 /// Lists directory contents recursively for dry-run mode.
@@ -2282,6 +2973,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_source_parse_preset_prefix() {
+        let source = ConfigSource::parse("preset:team-feature");
+        match source {
+            ConfigSource::Builtin(name) => assert_eq!(name, "team-feature"),
+            _ => panic!("Expected Builtin variant"),
+        }
+    }
+
+    #[test]
+    fn test_complete_helper_parses_with_global_config() {
+        let cli = Cli::try_parse_from(["ralph", "-c", "builtin:feature", "__complete", "hats"])
+            .expect("parse __complete");
+        assert_eq!(cli.config, vec!["builtin:feature"]);
+        match cli.command {
+            Some(Commands::Complete(args)) => {
+                assert_eq!(args.kind, completions::CompletionKind::Hats);
+                assert_eq!(args.current, "");
+            }
+            _ => panic!("Expected Complete command"),
+        }
+    }
+
     #[test]
     fn test_config_source_parse_remote_https() {
         let source = ConfigSource::parse("https://example.com/preset.yml");
@@ -2347,6 +3061,70 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_events_parses_since_and_utc() {
+        let cli = Cli::try_parse_from(["ralph", "events", "--since", "10m", "--utc"])
+            .expect("CLI parse failed");
+
+        let Some(Commands::Events(args)) = cli.command else {
+            panic!("expected events command");
+        };
+        assert_eq!(args.since.as_deref(), Some("10m"));
+        assert!(args.utc);
+    }
+
+    #[test]
+    fn test_events_parses_stats_subcommand() {
+        let cli = Cli::try_parse_from([
+            "ralph", "events", "stats", "--format", "json", "--file", "e.jsonl",
+        ])
+        .expect("CLI parse failed");
+
+        let Some(Commands::Events(args)) = cli.command else {
+            panic!("expected events command");
+        };
+        let Some(EventsCommands::Stats(stats)) = args.command else {
+            panic!("expected stats subcommand");
+        };
+        assert_eq!(stats.format, OutputFormat::Json);
+        assert_eq!(stats.file, Some(PathBuf::from("e.jsonl")));
+    }
+
+    #[test]
+    fn test_events_all_runs_conflicts_with_file() {
+        let cli = Cli::try_parse_from(["ralph", "events", "--all-runs"]).expect("CLI parse failed");
+        let Some(Commands::Events(args)) = cli.command else {
+            panic!("expected events command");
+        };
+        assert!(args.all_runs);
+
+        assert!(
+            Cli::try_parse_from(["ralph", "events", "--all-runs", "--file", "e.jsonl"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_plan_parses_non_interactive_output_dir() {
+        let cli = Cli::try_parse_from([
+            "ralph",
+            "plan",
+            "--non-interactive",
+            "-o",
+            "specs/my-feature",
+            "idea",
+        ])
+        .expect("CLI parse failed");
+
+        let Some(Commands::Plan(args)) = cli.command else {
+            panic!("expected plan command");
+        };
+        assert!(args.non_interactive);
+        assert_eq!(args.output_dir, Some(PathBuf::from("specs/my-feature")));
+        assert_eq!(args.idea.as_deref(), Some("idea"));
+
+        assert!(Cli::try_parse_from(["ralph", "code-task", "--non-interactive", "idea"]).is_err());
+    }
+
     #[test]
     fn test_doctor_parses_command() {
         let cli = Cli::try_parse_from(["ralph", "doctor"]).expect("CLI parse failed");
@@ -2530,6 +3308,89 @@ mod tests {
         assert!(err.to_string().contains("Preflight checks failed"));
     }
 
+    #[tokio::test]
+    async fn test_auto_preflight_disk_and_git_checks_follow_skip_and_strict() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .expect("git");
+        };
+        git(&["init", "--initial-branch=main"]);
+        git(&[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@test.local",
+            "commit",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]);
+        std::fs::write(temp_dir.path().join("stray.txt"), "local edit").unwrap();
+
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp_dir.path().to_path_buf();
+        config.features.preflight.enabled = true;
+        config.features.preflight.skip = PreflightRunner::default_checks()
+            .check_names()
+            .into_iter()
+            .filter(|name| !matches!(*name, "git" | "disk"))
+            .map(str::to_string)
+            .collect();
+        config.features.preflight.min_free_disk_mb = 0;
+        config.features.preflight.disk_floor_mb = 0;
+
+        // A dirty tree only warns, which strict mode turns into a failure
+        run_auto_preflight(&config, false, false, AutoPreflightMode::Run)
+            .await
+            .expect("dirty tree should only warn");
+        config.features.preflight.strict = true;
+        let err = run_auto_preflight(&config, false, false, AutoPreflightMode::Run)
+            .await
+            .expect_err("strict mode should fail on the dirty tree");
+        assert!(err.to_string().contains("0 failures, 1 warning"), "{err}");
+
+        config.features.preflight.strict = false;
+        config.features.preflight.require_clean_tree = true;
+        let report = run_auto_preflight(&config, false, false, AutoPreflightMode::DryRun)
+            .await
+            .unwrap()
+            .expect("dry-run report");
+        assert!(!report.passed);
+        let git_check = report.checks.iter().find(|c| c.name == "git").unwrap();
+        assert_eq!(git_check.status, CheckStatus::Fail);
+        assert!(
+            git_check
+                .message
+                .as_deref()
+                .unwrap_or_default()
+                .contains("stray.txt")
+        );
+
+        // Skipping git leaves only the disk check, failing below its floor
+        config.features.preflight.skip.push("GIT".to_string());
+        run_auto_preflight(&config, false, false, AutoPreflightMode::Run)
+            .await
+            .expect("git check skipped");
+        config.features.preflight.disk_floor_mb = u64::MAX;
+        let report = run_auto_preflight(&config, false, false, AutoPreflightMode::DryRun)
+            .await
+            .unwrap()
+            .expect("dry-run report");
+        let names: Vec<_> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["disk"]);
+        if cfg!(unix) {
+            assert!(!report.passed);
+        }
+        config.features.preflight.skip.push("disk".to_string());
+        run_auto_preflight(&config, false, false, AutoPreflightMode::Run)
+            .await
+            .expect("all checks skipped");
+    }
+
     #[test]
     fn test_partition_config_sources_separates_overrides() {
         let sources = [
@@ -2699,43 +3560,6 @@ core:
         assert!(prompt_summary.ends_with("..."));
     }
 
-    #[test]
-    fn test_prompt_summary_returns_no_prompt_for_missing_file() {
-        let mut config = RalphConfig::default();
-        config.event_loop.prompt_file = "/nonexistent/path/PROMPT.md".to_string();
-        config.event_loop.prompt = None;
-
-        // Simulate the prompt_summary logic
-        let prompt_summary = config
-            .event_loop
-            .prompt
-            .clone()
-            .or_else(|| {
-                let prompt_file = &config.event_loop.prompt_file;
-                if prompt_file.is_empty() {
-                    None
-                } else {
-                    let path = std::path::Path::new(prompt_file);
-                    if path.exists() {
-                        std::fs::read_to_string(path).ok()
-                    } else {
-                        None
-                    }
-                }
-            })
-            .map(|p| {
-                if p.len() > 100 {
-                    format!("{}...", &p[..100])
-                } else {
-                    p
-                }
-            })
-            .unwrap_or_else(|| "[no prompt]".to_string());
-
-        // Assert: returns "[no prompt]" for missing file
-        assert_eq!(prompt_summary, "[no prompt]");
-    }
-
     #[test]
     fn test_format_preflight_summary_with_failures() {
         let report = PreflightReport {
@@ -2891,19 +3715,33 @@ core:
             prompt_text: None,
             backend: Some("claude".to_string()),
             prompt_file: None,
+            vars: Vec::new(),
             max_iterations: None,
+            max_cost: None,
             completion_promise: None,
             dry_run: false,
+            plan_only: false,
             continue_mode: false,
             no_tui: true,
             autonomous: false,
             idle_timeout: None,
             exclusive: false,
             no_auto_merge: false,
+            auto_merge_conflict: None,
             skip_preflight: true,
+            skip_warmup: true,
+            review_memories: false,
             verbose: false,
             quiet: false,
             record_session: None,
+            json_events_on_stdout: false,
+            no_redact: false,
+            strict_templates: false,
+            start_event: None,
+            start_payload: None,
+            on_failure_emit: None,
+            summary_out: None,
+            until_event: None,
             custom_args: Vec::new(),
         }
     }
@@ -2916,12 +3754,228 @@ core:
         let mut args = default_run_args();
         args.continue_mode = true;
 
-        let err = run_command(&[], false, ColorMode::Never, args)
+        let err = Box::pin(run_command(&[], false, ColorMode::Never, args))
             .await
             .expect_err("expected missing scratchpad error");
         assert!(err.to_string().contains("scratchpad not found"));
     }
 
+    #[test]
+    fn test_prompt_file_dash_reads_stdin() {
+        let cli = Cli::try_parse_from(["ralph", "run", "-P", "-"]).expect("CLI parse failed");
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("expected run command");
+        };
+        let mut config = RalphConfig::default();
+
+        let from_stdin = apply_prompt_args(
+            &mut config,
+            args.prompt_text,
+            args.prompt_file,
+            "Build a REST API\n".as_bytes(),
+        )
+        .unwrap();
+
+        assert!(from_stdin);
+        assert_eq!(
+            config.event_loop.prompt.as_deref(),
+            Some("Build a REST API\n")
+        );
+        assert!(config.event_loop.prompt_file.is_empty());
+
+        let err = apply_prompt_args(
+            &mut config,
+            None,
+            Some(PathBuf::from("-")),
+            "  \n".as_bytes(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No prompt on stdin"), "{err}");
+
+        assert!(Cli::try_parse_from(["ralph", "run", "-p", "inline", "-P", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_prompt_file_path_does_not_read_stdin() {
+        let mut config = RalphConfig::default();
+
+        let from_stdin = apply_prompt_args(
+            &mut config,
+            None,
+            Some(PathBuf::from("PROMPT.md")),
+            "ignored".as_bytes(),
+        )
+        .unwrap();
+
+        assert!(!from_stdin);
+        assert_eq!(config.event_loop.prompt_file, "PROMPT.md");
+        assert!(config.event_loop.prompt.is_none());
+    }
+
+    #[test]
+    fn test_starting_event_override_reaches_config() {
+        let cli = Cli::try_parse_from(["ralph", "run", "--starting-event", "review.request"])
+            .expect("CLI parse failed");
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("expected run command");
+        };
+        let mut config = RalphConfig::parse_yaml(
+            r#"
+event_loop:
+  starting_event: "build.start"
+hats:
+  reviewer:
+    name: "Reviewer"
+    triggers: ["review.*"]
+    publishes: ["review.done"]
+"#,
+        )
+        .unwrap();
+        config.normalize();
+
+        apply_starting_event(&mut config, args.start_event.unwrap()).unwrap();
+
+        assert_eq!(
+            config.event_loop.starting_event.as_deref(),
+            Some("review.request")
+        );
+    }
+
+    #[test]
+    fn test_start_event_without_subscriber_lists_triggers() {
+        let mut config = RalphConfig::parse_yaml(
+            r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task", "review.rejected"]
+    publishes: ["build.done"]
+  reviewer:
+    name: "Reviewer"
+    triggers: ["build.done"]
+    publishes: ["review.approved", "review.rejected"]
+"#,
+        )
+        .unwrap();
+
+        let err = apply_starting_event(&mut config, "review.request".to_string()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Start event 'review.request' does not match any hat's triggers. \
+             Valid triggers: build.done, build.task, review.rejected"
+        );
+        assert!(config.event_loop.starting_event.is_none());
+
+        // Solo mode has no hat graph to check against
+        let mut solo = RalphConfig::default();
+        apply_starting_event(&mut solo, "anything.start".to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_start_event_flags_parse_and_reject_continue() {
+        let cli = Cli::try_parse_from([
+            "ralph",
+            "run",
+            "--start-event",
+            "build.done",
+            "--start-payload",
+            "@changes.md",
+        ])
+        .expect("CLI parse failed");
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("expected run command");
+        };
+        assert_eq!(args.start_event.as_deref(), Some("build.done"));
+        assert_eq!(args.start_payload.as_deref(), Some("@changes.md"));
+
+        for flags in [
+            ["--start-event", "build.done"],
+            ["--start-payload", "review this"],
+        ] {
+            let err = Cli::try_parse_from(["ralph", "run", "--continue", flags[0], flags[1]])
+                .unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn test_read_start_payload_text_and_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("payload.md");
+        std::fs::write(&path, "Review the diff in src/").unwrap();
+
+        assert_eq!(read_start_payload("plain text").unwrap(), "plain text");
+        assert_eq!(
+            read_start_payload(&format!("@{}", path.display())).unwrap(),
+            "Review the diff in src/"
+        );
+        assert!(read_start_payload("@/nonexistent/payload.md").is_err());
+    }
+
+    #[test]
+    fn test_run_max_cost_flag_parses() {
+        let cli =
+            Cli::try_parse_from(["ralph", "run", "--max-cost", "2.50"]).expect("CLI parse failed");
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("expected run command");
+        };
+        assert_eq!(args.max_cost, Some(2.5));
+        assert!(Cli::try_parse_from(["ralph", "run", "--max-cost", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_prompt_var() {
+        assert_eq!(
+            parse_prompt_var("ticket=OPS-1=2").unwrap(),
+            ("ticket".to_string(), "OPS-1=2".to_string())
+        );
+        assert_eq!(
+            parse_prompt_var("empty=").unwrap(),
+            ("empty".to_string(), String::new())
+        );
+        assert!(parse_prompt_var("novalue").is_err());
+        assert!(parse_prompt_var("=value").is_err());
+    }
+
+    #[test]
+    fn test_render_configured_prompt_requires_prompt() {
+        let mut config = RalphConfig::default();
+        config.event_loop.prompt = None;
+        config.event_loop.prompt_file = "/nonexistent/path/PROMPT.md".to_string();
+        let err = render_configured_prompt(&mut config, false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Prompt file '/nonexistent/path/PROMPT.md' not found"),
+            "{err}"
+        );
+
+        config.event_loop.prompt = Some("Hi {{name}}".to_string());
+        assert!(render_configured_prompt(&mut config, false).is_err());
+        config.vars.insert("name".to_string(), "Ralph".to_string());
+        assert_eq!(
+            render_configured_prompt(&mut config, false).unwrap(),
+            "Hi Ralph"
+        );
+    }
+
+    #[test]
+    fn test_render_configured_prompt_continue_falls_back() {
+        let mut config = RalphConfig::default();
+        config.event_loop.prompt = None;
+        config.event_loop.prompt_file = "/nonexistent/path/PROMPT.md".to_string();
+
+        let prompt = render_configured_prompt(&mut config, true).unwrap();
+        assert_eq!(prompt, CONTINUE_FALLBACK_PROMPT);
+        assert_eq!(
+            config.event_loop.prompt.as_deref(),
+            Some(CONTINUE_FALLBACK_PROMPT)
+        );
+
+        // Placeholder errors are not excused by --continue
+        config.event_loop.prompt = Some("Hi {{name}}".to_string());
+        assert!(render_configured_prompt(&mut config, true).is_err());
+    }
+
     #[tokio::test]
     async fn test_run_command_dry_run_inline_prompt_skips_execution() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -2931,7 +3985,7 @@ core:
         args.dry_run = true;
         args.prompt_text = Some("Test inline prompt".to_string());
 
-        run_command(&[], false, ColorMode::Never, args)
+        Box::pin(run_command(&[], false, ColorMode::Never, args))
             .await
             .expect("dry run should succeed");
     }
@@ -7,12 +7,17 @@
 //! - `delete`: Delete a memory by ID
 //! - `search`: Find memories by query
 //! - `prime`: Output memories for context injection
+//! - `export`: Write memories as JSON for sharing with other projects
+//! - `import`: Load memories exported from another project
 //! - `init`: Initialize memories file
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
-use ralph_core::{MarkdownMemoryStore, Memory, MemoryType};
-use std::path::PathBuf;
+use ralph_core::{
+    EvictionResult, ImportOptions, MarkdownMemoryStore, Memory, MemoryEviction, MemoryExport,
+    MemoryType, RalphConfig, evict_to_budget, query_terms, rank_by_relevance,
+};
+use std::path::{Path, PathBuf};
 
 /// ANSI color codes for terminal output.
 mod colors {
@@ -21,6 +26,7 @@ mod colors {
     pub const DIM: &str = "\x1b[2m";
     pub const GREEN: &str = "\x1b[32m";
     pub const CYAN: &str = "\x1b[36m";
+    pub const YELLOW: &str = "\x1b[33m";
 }
 
 /// Format a date string as a human-readable relative time.
@@ -100,6 +106,12 @@ pub enum MemoryCommands {
     /// Output memories for context injection
     Prime(PrimeArgs),
 
+    /// Export memories as JSON (e.g., to seed another project)
+    Export(ExportArgs),
+
+    /// Import memories from a JSON export
+    Import(ImportArgs),
+
     /// Initialize memories file
     Init(InitArgs),
 }
@@ -118,6 +130,15 @@ pub struct AddArgs {
     #[arg(long)]
     pub tags: Option<String>,
 
+    /// Similarity (0.0-1.0) at which an existing memory of the same type
+    /// counts as a duplicate (0 = disable)
+    #[arg(long, default_value_t = 0.8)]
+    pub dedup_threshold: f64,
+
+    /// Merge into a duplicate memory instead of refusing
+    #[arg(long)]
+    pub merge: bool,
+
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
     pub format: OutputFormat,
@@ -160,7 +181,7 @@ pub struct DeleteArgs {
 /// Arguments for the `memory search` command.
 #[derive(Parser, Debug)]
 pub struct SearchArgs {
-    /// Search query (fuzzy match on content/tags)
+    /// Search query (results ranked by relevance; tag matches weigh most)
     pub query: Option<String>,
 
     /// Filter by memory type
@@ -171,6 +192,10 @@ pub struct SearchArgs {
     #[arg(long)]
     pub tags: Option<String>,
 
+    /// Maximum number of results to show
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
     /// Show all results (no limit)
     #[arg(long)]
     pub all: bool,
@@ -180,6 +205,27 @@ pub struct SearchArgs {
     pub format: OutputFormat,
 }
 
+/// Eviction strategy for `memory prime --budget`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EvictionArg {
+    /// Drop the oldest memories first
+    Recent,
+    /// Drop context, then patterns, then decisions, then fixes
+    ByTypePriority,
+    /// Drop the largest memories first
+    LargestFirst,
+}
+
+impl From<EvictionArg> for MemoryEviction {
+    fn from(arg: EvictionArg) -> Self {
+        match arg {
+            EvictionArg::Recent => Self::Recent,
+            EvictionArg::ByTypePriority => Self::ByTypePriority,
+            EvictionArg::LargestFirst => Self::LargestFirst,
+        }
+    }
+}
+
 /// Arguments for the `memory prime` command.
 #[derive(Parser, Debug)]
 pub struct PrimeArgs {
@@ -187,6 +233,10 @@ pub struct PrimeArgs {
     #[arg(long)]
     pub budget: Option<usize>,
 
+    /// Which memories to drop when over budget (default: features.memory.eviction)
+    #[arg(long, value_enum)]
+    pub eviction: Option<EvictionArg>,
+
     /// Filter by types (comma-separated)
     #[arg(short = 't', long)]
     pub r#type: Option<String>,
@@ -204,6 +254,53 @@ pub struct PrimeArgs {
     pub format: OutputFormat,
 }
 
+/// Arguments for the `memory export` command.
+#[derive(Parser, Debug)]
+pub struct ExportArgs {
+    /// Filter by types (comma-separated)
+    #[arg(short = 't', long)]
+    pub r#type: Option<String>,
+
+    /// Filter by tags (comma-separated)
+    #[arg(long)]
+    pub tags: Option<String>,
+
+    /// Write the export to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for the `memory import` command.
+#[derive(Parser, Debug)]
+pub struct ImportArgs {
+    /// JSON file from `memory export` ("-" for stdin)
+    pub file: PathBuf,
+
+    /// Add to the existing memories (default)
+    #[arg(long, conflicts_with = "replace")]
+    pub merge: bool,
+
+    /// Replace all existing memories with the imported ones
+    #[arg(long)]
+    pub replace: bool,
+
+    /// Report what would be added or skipped without writing
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip memories similar to ones already stored
+    #[arg(long)]
+    pub dedup: bool,
+
+    /// Similarity (0.0-1.0) at which --dedup treats memories as duplicates
+    #[arg(long, default_value_t = 0.8, requires = "dedup")]
+    pub dedup_threshold: f64,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
 /// Arguments for the `memory init` command.
 #[derive(Parser, Debug)]
 pub struct InitArgs {
@@ -223,7 +320,9 @@ pub fn execute(args: MemoryArgs, use_colors: bool) -> Result<()> {
         MemoryCommands::Show(show_args) => show_command(&store, show_args, use_colors),
         MemoryCommands::Delete(delete_args) => delete_command(&store, delete_args, use_colors),
         MemoryCommands::Search(search_args) => search_command(&store, search_args, use_colors),
-        MemoryCommands::Prime(prime_args) => prime_command(&store, prime_args),
+        MemoryCommands::Prime(prime_args) => prime_command(&store, &root, prime_args),
+        MemoryCommands::Export(export_args) => export_command(&store, export_args),
+        MemoryCommands::Import(import_args) => import_command(&store, import_args, use_colors),
         MemoryCommands::Init(init_args) => init_command(&store, init_args, use_colors),
     }
 }
@@ -237,12 +336,40 @@ fn add_command(store: &MarkdownMemoryStore, args: AddArgs, use_colors: bool) ->
 
     // Create and store the memory
     let memory = Memory::new(args.r#type, args.content, tags);
-    let id = memory.id.clone();
+
+    if args.dedup_threshold > 0.0
+        && let Some((existing, score)) = store
+            .find_similar(&memory, args.dedup_threshold)
+            .context("Failed to check for duplicate memories")?
+    {
+        if !args.merge {
+            anyhow::bail!(
+                "Similar {} memory already exists: {} (similarity {:.2}). \
+                 Use --merge to merge into it, or `ralph tools memory show {}` to view it.",
+                existing.memory_type,
+                existing.id,
+                score,
+                existing.id
+            );
+        }
+
+        let merged = store
+            .merge(&existing.id, &memory)
+            .context("Failed to merge memory")?
+            .with_context(|| format!("Memory {} disappeared during merge", existing.id))?;
+        return print_added(&merged, args.format, "Memory merged into:", use_colors);
+    }
 
     store.append(&memory).context("Failed to store memory")?;
+    print_added(&memory, args.format, "Memory stored:", use_colors)
+}
+
+/// Prints a stored (or merged) memory in the requested format.
+fn print_added(memory: &Memory, format: OutputFormat, label: &str, use_colors: bool) -> Result<()> {
+    let id = &memory.id;
 
     // Output based on format
-    match args.format {
+    match format {
         OutputFormat::Quiet => {
             println!("{}", id);
         }
@@ -252,9 +379,9 @@ fn add_command(store: &MarkdownMemoryStore, args: AddArgs, use_colors: bool) ->
         }
         _ => {
             if use_colors {
-                println!("{}📝 Memory stored:{} {}", colors::GREEN, colors::RESET, id);
+                println!("{}📝 {}{} {}", colors::GREEN, label, colors::RESET, id);
             } else {
-                println!("Memory stored: {}", id);
+                println!("{} {}", label, id);
             }
         }
     }
@@ -337,11 +464,6 @@ fn search_command(store: &MarkdownMemoryStore, args: SearchArgs, use_colors: boo
     let total_count = all_memories.len();
     let mut memories = all_memories;
 
-    // Filter by query if provided
-    if let Some(ref query) = args.query {
-        memories.retain(|m| m.matches_query(query));
-    }
-
     // Filter by type if specified
     if let Some(memory_type) = args.r#type {
         memories.retain(|m| m.memory_type == memory_type);
@@ -353,15 +475,21 @@ fn search_command(store: &MarkdownMemoryStore, args: SearchArgs, use_colors: boo
         memories.retain(|m| m.has_any_tag(&tags));
     }
 
-    let match_count = memories.len();
-    let truncated = !args.all && match_count > 10;
+    // Rank by relevance to the query, best match first
+    let mut results: Vec<(Memory, f64)> = match args.query {
+        Some(ref query) => rank_by_relevance(memories, query),
+        None => memories.into_iter().map(|m| (m, 0.0)).collect(),
+    };
+
+    let match_count = results.len();
+    let truncated = !args.all && match_count > args.limit;
 
     // Limit results unless --all is specified
     if truncated {
-        memories.truncate(10);
+        results.truncate(args.limit);
     }
 
-    if memories.is_empty() {
+    if results.is_empty() {
         if use_colors {
             println!(
                 "\n{}No matching memories found in {} total memories.{}",
@@ -405,21 +533,30 @@ fn search_command(store: &MarkdownMemoryStore, args: SearchArgs, use_colors: boo
         }
     }
 
-    output_memories(&memories, args.format, use_colors);
+    match args.query {
+        Some(ref query) if args.format == OutputFormat::Table => {
+            print_search_results(&results, &query_terms(query), use_colors);
+        }
+        _ => {
+            let memories: Vec<Memory> = results.into_iter().map(|(m, _)| m).collect();
+            output_memories(&memories, args.format, use_colors);
+        }
+    }
 
     // Show truncation hint (only for table format)
     if truncated && args.format == OutputFormat::Table {
         if use_colors {
             println!(
-                "{}Showing 10 of {} matches • Use --all to see all results{}\n",
+                "{}Showing {} of {} matches • Use --all to see all results{}\n",
                 colors::DIM,
+                args.limit,
                 match_count,
                 colors::RESET
             );
         } else {
             println!(
-                "Showing 10 of {} matches • Use --all to see all results\n",
-                match_count
+                "Showing {} of {} matches • Use --all to see all results\n",
+                args.limit, match_count
             );
         }
     }
@@ -427,7 +564,7 @@ fn search_command(store: &MarkdownMemoryStore, args: SearchArgs, use_colors: boo
     Ok(())
 }
 
-fn prime_command(store: &MarkdownMemoryStore, args: PrimeArgs) -> Result<()> {
+fn prime_command(store: &MarkdownMemoryStore, root: &Path, args: PrimeArgs) -> Result<()> {
     let mut memories = store.load().context("Failed to load memories")?;
 
     // Filter by types if specified
@@ -458,26 +595,172 @@ fn prime_command(store: &MarkdownMemoryStore, args: PrimeArgs) -> Result<()> {
         return Ok(());
     }
 
+    // Drop whole memories until the rest fit the budget
+    let budget = args.budget.unwrap_or(0);
+    let eviction = args
+        .eviction
+        .map_or_else(|| configured_eviction(root), MemoryEviction::from);
+    let EvictionResult {
+        kept: memories,
+        evicted,
+    } = evict_to_budget(memories, budget, eviction);
+
     // Generate output
     let output = match args.format {
-        OutputFormat::Json => serde_json::to_string_pretty(&memories)?,
+        OutputFormat::Json => {
+            if !evicted.is_empty() {
+                eprintln!(
+                    "Evicted {} memories ({}): {}",
+                    evicted.len(),
+                    eviction,
+                    evicted_ids(&evicted)
+                );
+            }
+            serde_json::to_string_pretty(&memories)?
+        }
         OutputFormat::Markdown | OutputFormat::Table | OutputFormat::Quiet => {
-            format_memories_as_markdown(&memories)
+            // A single memory can still exceed the budget on its own
+            let mut output = format_memories_as_markdown(&memories);
+            if budget > 0 {
+                output = truncate_to_budget(&output, budget);
+            }
+            if !evicted.is_empty() {
+                output.push_str(&format!(
+                    "\n<!-- evicted ({}): {} -->\n",
+                    eviction,
+                    evicted_ids(&evicted)
+                ));
+            }
+            output
         }
     };
 
-    // Apply budget if specified
-    let final_output = if let Some(budget) = args.budget {
-        if budget > 0 {
-            truncate_to_budget(&output, budget)
-        } else {
-            output
+    print!("{}", output);
+    Ok(())
+}
+
+/// Reads `features.memory.eviction` from `ralph.yml` in `root`, if present.
+fn configured_eviction(root: &Path) -> MemoryEviction {
+    let config_path = root.join("ralph.yml");
+    if !config_path.exists() {
+        return MemoryEviction::default();
+    }
+    RalphConfig::from_file(&config_path)
+        .map(|config| config.features.memory.eviction)
+        .unwrap_or_default()
+}
+
+fn evicted_ids(evicted: &[Memory]) -> String {
+    evicted
+        .iter()
+        .map(|m| m.id.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn export_command(store: &MarkdownMemoryStore, args: ExportArgs) -> Result<()> {
+    let mut memories = store.load().context("Failed to load memories")?;
+
+    if let Some(ref types_str) = args.r#type {
+        let types: Vec<MemoryType> = types_str
+            .split(',')
+            .map(|s| s.trim().parse().map_err(anyhow::Error::msg))
+            .collect::<Result<_>>()?;
+        memories.retain(|m| types.contains(&m.memory_type));
+    }
+
+    if let Some(ref tags_str) = args.tags {
+        let tags: Vec<String> = tags_str.split(',').map(|s| s.trim().to_string()).collect();
+        memories.retain(|m| m.has_any_tag(&tags));
+    }
+
+    let count = memories.len();
+    let json = serde_json::to_string_pretty(&MemoryExport::new(memories))?;
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, format!("{}\n", json))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Exported {} memories to {}", count, path.display());
         }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+fn import_command(store: &MarkdownMemoryStore, args: ImportArgs, use_colors: bool) -> Result<()> {
+    let content = if args.file.as_os_str() == "-" {
+        let mut buf = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)
+            .context("Failed to read memories from stdin")?;
+        buf
     } else {
-        output
+        std::fs::read_to_string(&args.file)
+            .with_context(|| format!("Failed to read {}", args.file.display()))?
     };
+    let memories = MemoryExport::parse(&content)
+        .with_context(|| format!("Failed to read memories from {}", args.file.display()))?;
+
+    let options = ImportOptions {
+        dedup_threshold: args.dedup.then_some(args.dedup_threshold),
+        replace: args.replace,
+        dry_run: args.dry_run,
+    };
+    let result = store
+        .import(memories, options)
+        .context("Failed to import memories")?;
+
+    match args.format {
+        OutputFormat::Quiet => {
+            for memory in &result.imported {
+                println!("{}", memory.id);
+            }
+        }
+        OutputFormat::Json => {
+            let skipped: Vec<_> = result
+                .skipped
+                .iter()
+                .map(|(memory, duplicate_of)| {
+                    serde_json::json!({ "memory": memory, "duplicate_of": duplicate_of })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "imported": result.imported,
+                "skipped": skipped,
+                "replaced": result.replaced,
+                "dry_run": args.dry_run,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            let verb = if args.dry_run { "Would add" } else { "Added" };
+            let mut summary = format!(
+                "{} {} memories to {}, skipped {} duplicates",
+                verb,
+                result.imported.len(),
+                store.path().display(),
+                result.skipped.len()
+            );
+            if args.replace {
+                summary.push_str(&format!(", replacing {} existing", result.replaced));
+            }
+            if use_colors && !args.dry_run {
+                println!("{}✓{} {}", colors::GREEN, colors::RESET, summary);
+            } else {
+                println!("{}", summary);
+            }
+            for (memory, duplicate_of) in &result.skipped {
+                println!(
+                    "  Skipped duplicate of {}: {}",
+                    duplicate_of,
+                    ralph_core::truncate_with_ellipsis(
+                        memory.content.lines().next().unwrap_or(""),
+                        60
+                    )
+                );
+            }
+        }
+    }
 
-    print!("{}", final_output);
     Ok(())
 }
 
@@ -624,6 +907,128 @@ fn print_memories_table(memories: &[Memory], use_colors: bool) {
     }
 }
 
+fn print_search_results(results: &[(Memory, f64)], terms: &[String], use_colors: bool) {
+    use colors::*;
+
+    if use_colors {
+        println!("\n{BOLD}  # │ Type      │ Score │ Tags             │ Match{RESET}");
+        println!(
+            "{DIM}────┼───────────┼───────┼──────────────────┼────────────────────────────────────────{RESET}"
+        );
+    } else {
+        println!("\n  # | Type      | Score | Tags             | Match");
+        println!(
+            "----|-----------|-------|------------------|----------------------------------------"
+        );
+    }
+
+    for (i, (memory, score)) in results.iter().enumerate() {
+        let emoji = memory.memory_type.emoji();
+        let type_name = memory.memory_type.to_string();
+        let tags = if memory.tags.is_empty() {
+            "-".to_string()
+        } else {
+            memory.tags.join(", ")
+        };
+        let snippet = search_snippet(&memory.content, terms, 60, use_colors);
+
+        if use_colors {
+            println!(
+                "{DIM}{:>3}{RESET} │ {} {:<7} │ {:>5.1} │ {CYAN}{:<16}{RESET} │ {}",
+                i + 1,
+                emoji,
+                type_name,
+                score,
+                truncate_str(&tags, 16),
+                snippet
+            );
+        } else {
+            println!(
+                "{:>3} | {} {:<7} | {:>5.1} | {:<16} | {}",
+                i + 1,
+                emoji,
+                type_name,
+                score,
+                truncate_str(&tags, 16),
+                snippet
+            );
+        }
+    }
+
+    if use_colors {
+        println!(
+            "\n{DIM}Showing {} memories • Use `ralph tools memory show <id>` for details{RESET}",
+            results.len()
+        );
+    } else {
+        println!(
+            "\nShowing {} memories • Use `ralph tools memory show <id>` for details",
+            results.len()
+        );
+    }
+}
+
+/// Returns a window of about `width` characters of `content` around the first
+/// occurrence of any search term, with matches highlighted when colors are on.
+///
+/// Falls back to the start of the content when only tags matched.
+fn search_snippet(content: &str, terms: &[String], width: usize, use_colors: bool) -> String {
+    use colors::*;
+
+    let chars: Vec<char> = content
+        .chars()
+        .map(|c| if c == '\n' { ' ' } else { c })
+        .collect();
+    // One lowercase char per original char keeps match offsets aligned
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|t| t.chars().collect::<Vec<_>>())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let matches_at = |pos: usize| -> Option<usize> {
+        terms
+            .iter()
+            .filter(|t| lower[pos..].starts_with(t))
+            .map(Vec::len)
+            .max()
+    };
+
+    let first_match = (0..lower.len()).find(|&pos| matches_at(pos).is_some());
+    let start = first_match.map_or(0, |pos| pos.saturating_sub(width / 3));
+    let end = (start + width).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut pos = start;
+    while pos < end {
+        match matches_at(pos).filter(|_| use_colors) {
+            Some(len) => {
+                let len = len.min(end - pos);
+                snippet.push_str(BOLD);
+                snippet.push_str(YELLOW);
+                snippet.extend(&chars[pos..pos + len]);
+                snippet.push_str(RESET);
+                pos += len;
+            }
+            None => {
+                snippet.push(chars[pos]);
+                pos += 1;
+            }
+        }
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
 fn print_memory_detail(memory: &Memory, use_colors: bool) {
     use colors::*;
 
@@ -842,6 +1247,38 @@ mod tests {
         assert!(truncated.contains("truncated: budget 1 tokens exceeded"));
     }
 
+    #[test]
+    fn search_snippet_centers_on_first_match() {
+        let content = format!("{} the docker daemon hangs", "padding ".repeat(10));
+        let terms = vec!["docker".to_string()];
+
+        let snippet = search_snippet(&content, &terms, 30, false);
+
+        assert!(snippet.starts_with('…'), "{}", snippet);
+        assert!(snippet.contains("docker daemon"), "{}", snippet);
+        assert_eq!(
+            search_snippet("short\ntext", &terms, 30, false),
+            "short text"
+        );
+    }
+
+    #[test]
+    fn search_snippet_highlights_matches_with_colors() {
+        let terms = vec!["docker".to_string()];
+
+        let snippet = search_snippet("Restart Docker often", &terms, 60, true);
+
+        assert_eq!(
+            snippet,
+            format!(
+                "Restart {}{}Docker{} often",
+                colors::BOLD,
+                colors::YELLOW,
+                colors::RESET
+            )
+        );
+    }
+
     #[test]
     fn truncate_str_handles_short_and_long_values() {
         assert_eq!(truncate_str("short", 10), "short");
@@ -857,6 +1294,7 @@ mod tests {
                 content: "alpha".to_string(),
                 tags: vec!["tag1".to_string()],
                 created: "2026-01-31".to_string(),
+                provenance: None,
             },
             Memory {
                 id: "mem-2".to_string(),
@@ -864,6 +1302,7 @@ mod tests {
                 content: "beta".to_string(),
                 tags: vec![],
                 created: "2026-01-31".to_string(),
+                provenance: None,
             },
         ];
 
@@ -875,4 +1314,20 @@ mod tests {
         assert!(output.contains("mem-1"));
         assert!(output.contains("mem-2"));
     }
+
+    #[test]
+    fn configured_eviction_reads_features_memory() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(configured_eviction(temp.path()), MemoryEviction::Recent);
+
+        std::fs::write(
+            temp.path().join("ralph.yml"),
+            "features:\n  memory:\n    eviction: largest-first\n",
+        )
+        .unwrap();
+        assert_eq!(
+            configured_eviction(temp.path()),
+            MemoryEviction::LargestFirst
+        );
+    }
 }
@@ -177,8 +177,15 @@ fn print_check_line(check: &CheckResult, name_width: usize, use_colors: bool) {
         status_padded
     };
 
+    let duration = format_check_duration(check.duration_ms);
+    let duration = if use_colors {
+        format!("{}({duration}){}", colors::DIM, colors::RESET)
+    } else {
+        format!("({duration})")
+    };
+
     println!(
-        "  {status} {name:<width$} {label}",
+        "  {status} {name:<width$} {label} {duration}",
         status = status_display,
         name = check.name,
         width = name_width,
@@ -192,6 +199,15 @@ fn print_check_line(check: &CheckResult, name_width: usize, use_colors: bool) {
     }
 }
 
+/// Formats a check duration, e.g. "12ms" or "1.5s".
+fn format_check_duration(duration_ms: u64) -> String {
+    if duration_ms < 1000 {
+        format!("{duration_ms}ms")
+    } else {
+        format!("{:.1}s", duration_ms as f64 / 1000.0)
+    }
+}
+
 pub(crate) async fn load_config_for_preflight(
     config_sources: &[ConfigSource],
 ) -> Result<RalphConfig> {
@@ -215,7 +231,7 @@ pub(crate) async fn load_config_for_preflight(
                 }
             }
             ConfigSource::Builtin(name) => {
-                let preset = presets::get_preset(name).ok_or_else(|| {
+                let preset = presets::preset_content(name).ok_or_else(|| {
                     let available = presets::preset_names().join(", ");
                     anyhow::anyhow!(
                         "Unknown preset '{}'. Run `ralph run --list-presets` to see available presets.\n\nAvailable: {}",
@@ -223,7 +239,7 @@ pub(crate) async fn load_config_for_preflight(
                         available
                     )
                 })?;
-                RalphConfig::parse_yaml(preset.content)
+                RalphConfig::parse_yaml(&preset)
                     .with_context(|| format!("Failed to parse builtin preset '{}'.", name))?
             }
             ConfigSource::Remote(url) => {
@@ -262,8 +278,7 @@ pub(crate) async fn load_config_for_preflight(
     };
 
     config.normalize();
-    config.core.workspace_root =
-        std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    config.core.workspace_root = crate::workspace_root::current_path();
 
     let override_sources: Vec<_> = overrides.into_iter().cloned().collect();
     crate::apply_config_overrides(&mut config, &override_sources)?;
@@ -288,6 +303,12 @@ pub(crate) fn config_source_label(config_sources: &[ConfigSource]) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn format_check_duration_switches_to_seconds() {
+        assert_eq!(format_check_duration(12), "12ms");
+        assert_eq!(format_check_duration(1500), "1.5s");
+    }
+
     #[test]
     fn normalize_checks_lowercases() {
         let checks = vec!["Config".to_string(), "BaCkEnD".to_string()];
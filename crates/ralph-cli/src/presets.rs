@@ -7,6 +7,23 @@
 //! Canonical presets live in the shared `presets/` directory at the repo root.
 //! The sync script (`scripts/sync-embedded-files.sh`) mirrors them into
 //! `crates/ralph-cli/presets/` for `include_str!` to work with crates.io publishing.
+//!
+//! Presets are also read from `*.yml` files in the user presets directory
+//! ([`user_presets_dir`], where `ralph init --from <file> --as-preset <name>`
+//! saves them) and in `<workspace>/.ralph/presets`. On a name collision the
+//! workspace preset wins over the user preset, which wins over the embedded
+//! one; each override is logged as a warning.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use ralph_core::RalphConfig;
+use tracing::warn;
+
+/// Environment variable overriding the user presets directory.
+pub const PRESETS_DIR_ENV: &str = "RALPH_PRESETS_DIR";
 
 /// An embedded preset with its name, description, and full content.
 #[derive(Debug, Clone)]
@@ -103,46 +120,230 @@ const PRESETS: &[EmbeddedPreset] = &[
     },
 ];
 
-/// Returns all embedded presets.
-pub fn list_presets() -> &'static [EmbeddedPreset] {
-    PRESETS
+/// Where a preset was loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetSource {
+    /// Compiled into the binary.
+    Builtin,
+    /// The user presets directory.
+    User,
+    /// `<workspace>/.ralph/presets`.
+    Workspace,
+}
+
+impl fmt::Display for PresetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetSource::Builtin => write!(f, "builtin"),
+            PresetSource::User => write!(f, "user"),
+            PresetSource::Workspace => write!(f, "workspace"),
+        }
+    }
+}
+
+/// A resolved preset: embedded, or read from a presets directory.
+#[derive(Debug, Clone)]
+pub struct Preset {
+    /// The preset name (file stem for presets on disk)
+    pub name: String,
+    /// Short description from the preset's header comment
+    pub description: String,
+    /// Full YAML content of the preset
+    pub content: Cow<'static, str>,
+    /// Where the preset came from
+    pub source: PresetSource,
+}
+
+impl From<&'static EmbeddedPreset> for Preset {
+    fn from(preset: &'static EmbeddedPreset) -> Self {
+        Self {
+            name: preset.name.to_string(),
+            description: preset.description.to_string(),
+            content: Cow::Borrowed(preset.content),
+            source: PresetSource::Builtin,
+        }
+    }
+}
+
+/// The directories searched for presets beyond the embedded ones.
+#[derive(Debug, Clone, Default)]
+pub struct PresetSearch {
+    /// User presets directory, if known
+    pub user_dir: Option<PathBuf>,
+    /// Workspace presets directory, if known
+    pub workspace_dir: Option<PathBuf>,
+}
+
+impl PresetSearch {
+    /// Searches [`user_presets_dir`] and `.ralph/presets` under the current
+    /// workspace root.
+    pub fn from_env() -> Self {
+        Self {
+            user_dir: user_presets_dir(),
+            workspace_dir: Some(crate::workspace_root::current_path().join(".ralph/presets")),
+        }
+    }
+
+    /// Returns every preset by name, with higher-precedence sources
+    /// replacing lower ones.
+    ///
+    /// Files that cannot be read or do not parse as a config are skipped
+    /// with a warning.
+    pub fn list(&self) -> Vec<Preset> {
+        let mut presets: BTreeMap<String, Preset> = PRESETS
+            .iter()
+            .map(|preset| (preset.name.to_string(), Preset::from(preset)))
+            .collect();
+
+        let dirs = [
+            (self.user_dir.as_deref(), PresetSource::User),
+            (self.workspace_dir.as_deref(), PresetSource::Workspace),
+        ];
+        for (dir, source) in dirs {
+            let Some(dir) = dir else { continue };
+            for preset in read_preset_dir(dir, source) {
+                if let Some(shadowed) = presets.get(&preset.name) {
+                    warn!(
+                        preset = %preset.name,
+                        "{} preset overrides the {} preset of the same name",
+                        source,
+                        shadowed.source
+                    );
+                }
+                presets.insert(preset.name.clone(), preset);
+            }
+        }
+
+        presets.into_values().collect()
+    }
+
+    /// Looks up a preset by name across all sources.
+    pub fn get(&self, name: &str) -> Option<Preset> {
+        self.list().into_iter().find(|p| p.name == name)
+    }
+}
+
+/// Reads the `*.yml` presets in `dir`, sorted by name. A missing directory
+/// yields no presets.
+fn read_preset_dir(dir: &Path, source: PresetSource) -> Vec<Preset> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yml"))
+        .collect();
+    paths.sort();
+
+    let mut presets = Vec::new();
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Skipping unreadable preset");
+                continue;
+            }
+        };
+        if let Err(e) = RalphConfig::parse_yaml(&content) {
+            warn!(path = %path.display(), error = %e, "Skipping malformed preset");
+            continue;
+        }
+        presets.push(Preset {
+            name: name.to_string(),
+            description: header_description(&content),
+            content: Cow::Owned(content),
+            source,
+        });
+    }
+    presets
+}
+
+/// Takes a description from a preset's leading comment block: the line
+/// after the title when there is one, else the title itself.
+fn header_description(content: &str) -> String {
+    let comments: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .filter_map(|line| line.strip_prefix('#').map(str::trim))
+        .filter(|line| !line.is_empty())
+        .take(2)
+        .collect();
+    comments.last().copied().unwrap_or_default().to_string()
 }
 
-/// Looks up a preset by name.
+/// Returns all presets: embedded, user, and workspace.
+pub fn list_presets() -> Vec<Preset> {
+    PresetSearch::from_env().list()
+}
+
+/// Looks up a preset by name, preferring workspace, then user, then
+/// embedded presets.
 ///
 /// Returns `None` if the preset doesn't exist.
-pub fn get_preset(name: &str) -> Option<&'static EmbeddedPreset> {
-    PRESETS.iter().find(|p| p.name == name)
+pub fn get_preset(name: &str) -> Option<Preset> {
+    PresetSearch::from_env().get(name)
+}
+
+/// Directory holding user presets: `$RALPH_PRESETS_DIR`, else
+/// `~/.config/ralph/presets`. `None` when neither can be determined.
+pub fn user_presets_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(PRESETS_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".config/ralph/presets"))
+}
+
+/// Returns the YAML of preset `name`, resolved like [`get_preset`].
+pub fn preset_content(name: &str) -> Option<Cow<'static, str>> {
+    get_preset(name).map(|preset| preset.content)
 }
 
 /// Returns a formatted list of preset names for error messages.
-pub fn preset_names() -> Vec<&'static str> {
-    PRESETS.iter().map(|p| p.name).collect()
+pub fn preset_names() -> Vec<String> {
+    list_presets().into_iter().map(|p| p.name).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn builtin_presets() -> Vec<Preset> {
+        PresetSearch::default().list()
+    }
+
+    fn write_preset(dir: &Path, name: &str, content: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(format!("{name}.yml")), content).unwrap();
+    }
+
     #[test]
     fn test_list_presets_returns_all() {
-        let presets = list_presets();
+        let presets = builtin_presets();
         assert_eq!(presets.len(), 16, "Expected 16 presets");
     }
 
     #[test]
     fn test_get_preset_by_name() {
-        let preset = get_preset("feature");
+        let preset = PresetSearch::default().get("feature");
         assert!(preset.is_some(), "feature preset should exist");
         let preset = preset.unwrap();
         assert_eq!(preset.name, "feature");
+        assert_eq!(preset.source, PresetSource::Builtin);
         assert!(!preset.description.is_empty());
         assert!(!preset.content.is_empty());
     }
 
     #[test]
     fn test_merge_loop_preset_is_embedded() {
-        let preset = get_preset("merge-loop").expect("merge-loop preset should exist");
+        let preset = PresetSearch::default()
+            .get("merge-loop")
+            .expect("merge-loop preset should exist");
         assert_eq!(
             preset.description,
             "Merges completed parallel loop from worktree back to main branch"
@@ -157,15 +358,94 @@ mod tests {
         assert!(preset.content.contains("git worktree remove"));
     }
 
+    #[test]
+    fn test_presets_on_disk_override_by_precedence() {
+        let temp = tempfile::tempdir().unwrap();
+        let user_dir = temp.path().join("user");
+        let workspace_dir = temp.path().join("workspace/.ralph/presets");
+        write_preset(
+            &user_dir,
+            "team",
+            "# Team Preset\n# Shared team workflow\ncli:\n  backend: claude\n",
+        );
+        write_preset(
+            &user_dir,
+            "feature",
+            "# User feature\ncli:\n  backend: claude\n",
+        );
+        write_preset(
+            &workspace_dir,
+            "team",
+            "# Repo team\ncli:\n  backend: gemini\n",
+        );
+        let search = PresetSearch {
+            user_dir: Some(user_dir),
+            workspace_dir: Some(workspace_dir),
+        };
+
+        let team = search.get("team").expect("team preset should resolve");
+        assert_eq!(team.source, PresetSource::Workspace);
+        assert!(team.content.contains("gemini"));
+
+        let feature = search
+            .get("feature")
+            .expect("feature preset should resolve");
+        assert_eq!(feature.source, PresetSource::User);
+        assert_eq!(feature.description, "User feature");
+
+        let bugfix = search.get("bugfix").expect("bugfix preset should resolve");
+        assert_eq!(bugfix.source, PresetSource::Builtin);
+
+        let presets = search.list();
+        assert_eq!(presets.len(), 17, "one new name on top of the builtins");
+        assert_eq!(presets.iter().filter(|p| p.name == "team").count(), 1);
+    }
+
+    #[test]
+    fn test_malformed_preset_files_are_skipped() {
+        let temp = tempfile::tempdir().unwrap();
+        write_preset(temp.path(), "broken", "cli: [unclosed\n");
+        write_preset(temp.path(), "bugfix", "hats: 42\n");
+        write_preset(temp.path(), "good", "# Good one\ncli:\n  backend: claude\n");
+        std::fs::write(temp.path().join("notes.txt"), "not a preset").unwrap();
+        let search = PresetSearch {
+            user_dir: Some(temp.path().to_path_buf()),
+            workspace_dir: Some(temp.path().join("missing")),
+        };
+
+        assert!(search.get("broken").is_none());
+        assert!(search.get("notes").is_none());
+        assert_eq!(search.get("good").unwrap().source, PresetSource::User);
+        let bugfix = search.get("bugfix").unwrap();
+        assert_eq!(
+            bugfix.source,
+            PresetSource::Builtin,
+            "a malformed override must not hide the builtin"
+        );
+    }
+
+    #[test]
+    fn test_header_description_skips_title_line() {
+        assert_eq!(
+            header_description("# Team Preset\n# Shared workflow\n\ncli: {}\n"),
+            "Shared workflow"
+        );
+        assert_eq!(
+            header_description("# Only a title\ncli: {}\n"),
+            "Only a title"
+        );
+        assert_eq!(header_description("cli: {}\n# late comment\n"), "");
+    }
+
     #[test]
     fn test_get_preset_invalid_name() {
-        let preset = get_preset("nonexistent-preset");
+        let preset = PresetSearch::default().get("nonexistent-preset");
         assert!(preset.is_none(), "Nonexistent preset should return None");
     }
 
     #[test]
     fn test_all_presets_have_description() {
-        for preset in list_presets() {
+        for preset in builtin_presets() {
             assert!(
                 !preset.description.is_empty(),
                 "Preset '{}' should have a description",
@@ -176,7 +456,7 @@ mod tests {
 
     #[test]
     fn test_all_presets_have_content() {
-        for preset in list_presets() {
+        for preset in builtin_presets() {
             assert!(
                 !preset.content.is_empty(),
                 "Preset '{}' should have content",
@@ -187,8 +467,8 @@ mod tests {
 
     #[test]
     fn test_preset_content_is_valid_yaml() {
-        for preset in list_presets() {
-            let result: Result<serde_yaml::Value, _> = serde_yaml::from_str(preset.content);
+        for preset in builtin_presets() {
+            let result: Result<serde_yaml::Value, _> = serde_yaml::from_str(&preset.content);
             assert!(
                 result.is_ok(),
                 "Preset '{}' should be valid YAML: {:?}",
@@ -201,11 +481,14 @@ mod tests {
     #[test]
     fn test_preset_names_returns_all_names() {
         let names = preset_names();
-        assert_eq!(names.len(), 16);
-        assert!(names.contains(&"feature"));
-        assert!(names.contains(&"debug"));
-        assert!(names.contains(&"merge-loop"));
-        assert!(names.contains(&"code-assist"));
-        assert!(names.contains(&"fresh-eyes"));
+        for name in [
+            "feature",
+            "debug",
+            "merge-loop",
+            "code-assist",
+            "fresh-eyes",
+        ] {
+            assert!(names.iter().any(|n| n == name), "missing {name}");
+        }
     }
 }
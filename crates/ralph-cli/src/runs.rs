@@ -0,0 +1,240 @@
+//! CLI commands for the `ralph runs` namespace.
+//!
+//! Browse the per-run records written to `.ralph/runs/<run-id>/meta.json`.
+//!
+//! Subcommands:
+//! - `list`: Show recent runs (default)
+//! - `show`: Print a run's metadata and where its artifacts live
+
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use ralph_core::{RunHistory, RunRecord};
+
+use crate::display::{colors, truncate};
+
+/// Browse the history of past runs.
+#[derive(Parser, Debug)]
+pub struct RunsArgs {
+    #[command(subcommand)]
+    pub command: Option<RunsCommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RunsCommands {
+    /// List recent runs (default if no subcommand)
+    List(ListArgs),
+
+    /// Show a run's metadata and artifacts
+    Show(ShowArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ListArgs {
+    /// Maximum number of runs to show
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output JSON instead of table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ShowArgs {
+    /// Run ID (as shown by `ralph runs list`)
+    pub run_id: String,
+
+    /// Output JSON instead of formatted text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Execute a runs command.
+pub fn execute(args: RunsArgs, use_colors: bool) -> Result<()> {
+    let history = RunHistory::new(crate::workspace_root::current()?.path);
+    match args.command {
+        None => list_runs(
+            &history,
+            ListArgs {
+                limit: 20,
+                json: false,
+            },
+            use_colors,
+        ),
+        Some(RunsCommands::List(args)) => list_runs(&history, args, use_colors),
+        Some(RunsCommands::Show(args)) => show_run(&history, args, use_colors),
+    }
+}
+
+fn list_runs(history: &RunHistory, args: ListArgs, use_colors: bool) -> Result<()> {
+    let runs: Vec<RunRecord> = history.list()?.into_iter().take(args.limit).collect();
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+
+    if runs.is_empty() {
+        println!("No runs recorded yet.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<17} {:<9} {:<6} {:<16} PROMPT",
+        "ID", "STARTED", "DURATION", "ITERS", "REASON"
+    );
+    println!("{}", "-".repeat(88));
+
+    for run in &runs {
+        let reason = format!("{:<16}", reason_label(run));
+        let reason = if use_colors {
+            colorize_reason(run, &reason)
+        } else {
+            reason
+        };
+        println!(
+            "{:<20} {:<17} {:<9} {:<6} {} {}",
+            run.id,
+            run.started
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            run.duration()
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+            run.iterations
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            reason,
+            truncate(&run.prompt, 40)
+        );
+    }
+
+    println!();
+    println!("Use `ralph runs show <id>` for details.");
+    Ok(())
+}
+
+fn show_run(history: &RunHistory, args: ShowArgs, use_colors: bool) -> Result<()> {
+    let run = history.get(&args.run_id)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&run)?);
+        return Ok(());
+    }
+
+    let (bold, reset) = if use_colors {
+        (colors::BOLD, colors::RESET)
+    } else {
+        ("", "")
+    };
+
+    println!("{bold}Run {}{reset}", run.id);
+    println!(
+        "  Started:     {}",
+        run.started
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+    );
+    if let Some(finished) = run.finished {
+        println!(
+            "  Finished:    {}",
+            finished
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+    if let Some(duration) = run.duration() {
+        println!("  Duration:    {}", format_duration(duration));
+    }
+    println!("  Reason:      {}", reason_label(&run));
+    if let Some(iterations) = run.iterations {
+        println!("  Iterations:  {}", iterations);
+    }
+    println!("  Backend:     {}", run.backend);
+    println!("  Loop:        {}", run.loop_id);
+    if !run.config_sources.is_empty() {
+        println!("  Config:      {}", run.config_sources.join(", "));
+    }
+    println!("  Prompt:      {}", run.prompt);
+
+    println!();
+    println!("{bold}Artifacts{reset}");
+    match &run.events_file {
+        Some(path) => println!("  Events:      {}", describe_path(path)),
+        None => println!("  Events:      -"),
+    }
+    match run.scratchpad_archive() {
+        Some(archive) => println!("  Scratchpad:  {}", archive.path.display()),
+        None => println!("  Scratchpad:  - (not archived yet)"),
+    }
+    match &run.session_recording {
+        Some(path) => println!("  Recording:   {}", describe_path(path)),
+        None => println!("  Recording:   -"),
+    }
+
+    Ok(())
+}
+
+/// Termination reason, or the state of a run that never recorded one.
+fn reason_label(run: &RunRecord) -> &str {
+    match &run.termination_reason {
+        Some(reason) => reason,
+        None if run.finished.is_none() => "unfinished",
+        None => "-",
+    }
+}
+
+fn colorize_reason(run: &RunRecord, padded: &str) -> String {
+    let color = match run.termination_reason.as_deref() {
+        Some("completed") => colors::GREEN,
+        Some("interrupted" | "stopped") | None => colors::YELLOW,
+        Some(_) => colors::RED,
+    };
+    format!("{color}{padded}{}", colors::RESET)
+}
+
+fn describe_path(path: &Path) -> String {
+    if path.exists() {
+        path.display().to_string()
+    } else {
+        format!("{} (missing)", path.display())
+    }
+}
+
+/// Format a run duration (e.g., "45s", "12m 5s", "2h 3m").
+fn format_duration(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(chrono::Duration::seconds(45)), "45s");
+        assert_eq!(format_duration(chrono::Duration::seconds(725)), "12m 5s");
+        assert_eq!(format_duration(chrono::Duration::seconds(7380)), "2h 3m");
+        assert_eq!(format_duration(chrono::Duration::seconds(-3)), "0s");
+    }
+
+    #[test]
+    fn test_reason_label() {
+        let mut run = RunRecord::new("id", "prompt", "claude", "primary");
+        assert_eq!(reason_label(&run), "unfinished");
+
+        run.finished = Some(chrono::Utc::now());
+        run.termination_reason = Some("max_iterations".to_string());
+        assert_eq!(reason_label(&run), "max_iterations");
+    }
+}
@@ -0,0 +1,145 @@
+//! CLI commands for the `ralph tools scratchpad` namespace.
+//!
+//! Provides subcommands for archived scratchpads:
+//! - `history`: List scratchpads archived by previous runs
+//! - `restore`: Bring an archived scratchpad back for `ralph run --continue`
+
+use crate::display::colors;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use ralph_core::{LoopContext, ScratchpadArchive};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Output format for scratchpad history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable table format
+    #[default]
+    Table,
+    /// JSON format for programmatic access
+    Json,
+    /// Timestamp-only output for scripting
+    Quiet,
+}
+
+/// Scratchpad archive commands.
+#[derive(Parser, Debug)]
+pub struct ScratchpadArgs {
+    #[command(subcommand)]
+    pub command: ScratchpadCommands,
+
+    /// Working directory (default: current directory)
+    #[arg(long, global = true)]
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ScratchpadCommands {
+    /// List archived scratchpads (newest first)
+    History(HistoryArgs),
+
+    /// Restore an archived scratchpad (use with `ralph run --continue`)
+    Restore(RestoreArgs),
+}
+
+/// Arguments for the `scratchpad history` command.
+#[derive(Parser, Debug)]
+pub struct HistoryArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+/// Arguments for the `scratchpad restore` command.
+#[derive(Parser, Debug)]
+pub struct RestoreArgs {
+    /// Timestamp of the archive to restore (from `history`)
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryItem {
+    timestamp: String,
+    path: String,
+    size: u64,
+}
+
+/// Executes scratchpad CLI commands.
+pub fn execute(args: ScratchpadArgs, use_colors: bool) -> Result<()> {
+    let root = args.root.unwrap_or_else(|| PathBuf::from("."));
+    let ctx = LoopContext::primary(root);
+
+    match args.command {
+        ScratchpadCommands::History(history_args) => execute_history(&ctx, &history_args),
+        ScratchpadCommands::Restore(restore_args) => {
+            execute_restore(&ctx, &restore_args, use_colors)
+        }
+    }
+}
+
+fn execute_history(ctx: &LoopContext, args: &HistoryArgs) -> Result<()> {
+    let archives = ScratchpadArchive::from_context(ctx)
+        .list()
+        .context("Failed to read scratchpad archive")?;
+
+    match args.format {
+        OutputFormat::Table => {
+            if archives.is_empty() {
+                println!("No archived scratchpads");
+                return Ok(());
+            }
+
+            println!("{:<20} {:>10}  Path", "Timestamp", "Size");
+            println!("{}", "-".repeat(72));
+            for archive in &archives {
+                println!(
+                    "{:<20} {:>10}  {}",
+                    archive.timestamp,
+                    archive.size,
+                    archive.path.display()
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let items: Vec<HistoryItem> = archives
+                .into_iter()
+                .map(|a| HistoryItem {
+                    timestamp: a.timestamp,
+                    path: a.path.display().to_string(),
+                    size: a.size,
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&items)?);
+        }
+        OutputFormat::Quiet => {
+            for archive in archives {
+                println!("{}", archive.timestamp);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_restore(ctx: &LoopContext, args: &RestoreArgs, use_colors: bool) -> Result<()> {
+    let scratchpad = ctx.scratchpad_path();
+    let source = ScratchpadArchive::from_context(ctx)
+        .restore(&args.timestamp, &scratchpad)
+        .context("Failed to restore scratchpad")?;
+
+    if use_colors {
+        println!(
+            "{}Restored{} {} -> {}",
+            colors::GREEN,
+            colors::RESET,
+            source.display(),
+            scratchpad.display()
+        );
+    } else {
+        println!("Restored {} -> {}", source.display(), scratchpad.display());
+    }
+    println!("Resume with: ralph run --continue");
+
+    Ok(())
+}
@@ -190,24 +190,7 @@ fn resolve_root(explicit_root: Option<PathBuf>) -> Result<PathBuf> {
     if let Some(root) = explicit_root {
         return Ok(root);
     }
-
-    let cwd = std::env::current_dir().context("failed to get current directory")?;
-    if let Some(found) = find_workspace_root(&cwd) {
-        return Ok(found);
-    }
-
-    Ok(cwd)
-}
-
-fn find_workspace_root(start: &Path) -> Option<PathBuf> {
-    let mut current = Some(start);
-    while let Some(dir) = current {
-        if dir.join("ralph.yml").exists() || dir.join("ralph.yaml").exists() {
-            return Some(dir.to_path_buf());
-        }
-        current = dir.parent();
-    }
-    None
+    Ok(crate::workspace_root::current()?.path)
 }
 
 fn find_default_skills_dir(root: &Path) -> Option<PathBuf> {
@@ -216,7 +199,7 @@ fn find_default_skills_dir(root: &Path) -> Option<PathBuf> {
         return Some(default_dir);
     }
 
-    let cwd = std::env::current_dir().ok()?;
+    let cwd = crate::workspace_root::invocation_dir()?;
     if !cwd.starts_with(root) {
         return None;
     }
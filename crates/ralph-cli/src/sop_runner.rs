@@ -5,11 +5,16 @@
 //! 1. Resolve which backend to use (flag → config → auto-detect)
 //! 2. Build a prompt with the SOP content wrapped in XML tags
 //! 3. Spawn an interactive session with the backend
+//!
+//! With `--non-interactive` the backend runs in print mode instead: the
+//! prompt tells it nobody will answer, its output is captured, and the run
+//! fails unless the SOP left its expected files in `--output-dir`.
 
 use ralph_adapters::{CliBackend, CustomBackendError, NoBackendError, detect_backend_default};
 use ralph_core::RalphConfig;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, ExitStatus, Stdio};
 use thiserror::Error;
 
 /// Bundled SOP content - embedded at compile time for self-contained binary.
@@ -28,6 +33,24 @@ pub mod sops {
     pub const PDD_TEAM_ADDENDUM: &str = include_str!("../sops/pdd-team-addendum.md");
 }
 
+/// Addendum for runs where nobody is there to answer the SOP's questions.
+const NON_INTERACTIVE_ADDENDUM: &str = "\
+You are running non-interactively (for example in CI). No user will answer \
+questions or confirm steps.
+- Skip every confirmation gate and continue through all steps.
+- Answer clarifying questions yourself with reasonable assumptions, and record \
+each question and assumed answer where the SOP records answers.
+- Produce every artifact the SOP describes, then stop.";
+
+/// Files a finished PDD run leaves in its project directory.
+const PDD_OUTPUTS: [&str; 5] = [
+    "rough-idea.md",
+    "requirements.md",
+    "design.md",
+    "plan.md",
+    "summary.md",
+];
+
 /// Which SOP to run.
 #[derive(Debug, Clone, Copy)]
 pub enum Sop {
@@ -53,6 +76,49 @@ impl Sop {
             Sop::CodeTaskGenerator => "Code Task Generator",
         }
     }
+
+    /// The SOP parameter naming the directory its artifacts go to.
+    fn output_parameter(self) -> &'static str {
+        match self {
+            Sop::Pdd => "project_dir",
+            Sop::CodeTaskGenerator => "output_dir",
+        }
+    }
+
+    /// Expected artifacts that are missing from `dir`.
+    fn missing_outputs(self, dir: &Path) -> Vec<String> {
+        match self {
+            Sop::Pdd => PDD_OUTPUTS
+                .iter()
+                .filter(|name| !dir.join(name).is_file())
+                .map(|name| (*name).to_string())
+                .collect(),
+            Sop::CodeTaskGenerator => {
+                if contains_code_task(dir) {
+                    Vec::new()
+                } else {
+                    vec!["*.code-task.md".to_string()]
+                }
+            }
+        }
+    }
+}
+
+/// Whether `dir` or any directory below it holds a `.code-task.md` file.
+fn contains_code_task(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            contains_code_task(&path)
+        } else {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(".code-task.md"))
+        }
+    })
 }
 
 /// Configuration for running an SOP.
@@ -69,6 +135,11 @@ pub struct SopRunConfig {
     pub custom_args: Option<Vec<String>>,
     /// Enable Claude Code's experimental Agent Teams feature.
     pub agent_teams: bool,
+    /// Run in print mode and capture the output instead of spawning an
+    /// interactive session. Requires `output_dir`.
+    pub non_interactive: bool,
+    /// Directory the SOP writes its artifacts to.
+    pub output_dir: Option<PathBuf>,
 }
 
 /// Errors that can occur when running an SOP.
@@ -82,6 +153,19 @@ pub enum SopRunError {
 
     #[error("Failed to spawn backend: {0}")]
     SpawnError(#[from] std::io::Error),
+
+    #[error("--non-interactive requires an idea/input and --output-dir")]
+    MissingNonInteractiveInput,
+
+    #[error("Backend exited with {0}")]
+    BackendFailed(ExitStatus),
+
+    #[error(
+        "The SOP did not produce the expected files in {}: {}",
+        dir.display(),
+        missing.join(", ")
+    )]
+    MissingOutputs { dir: PathBuf, missing: Vec<String> },
 }
 
 impl From<CustomBackendError> for SopRunError {
@@ -95,6 +179,15 @@ impl From<CustomBackendError> for SopRunError {
 /// This is the main entry point for `ralph plan` and `ralph code-task` commands.
 /// It resolves the backend, builds the prompt, and spawns an interactive session.
 pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
+    let non_interactive_output = if config.non_interactive {
+        match (&config.output_dir, &config.user_input) {
+            (Some(dir), Some(input)) if !input.is_empty() => Some(dir.clone()),
+            _ => return Err(SopRunError::MissingNonInteractiveInput),
+        }
+    } else {
+        None
+    };
+
     // 1. Resolve backend
     let backend_name = resolve_backend(
         config.backend_override.as_deref(),
@@ -113,6 +206,20 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
         }
     }
 
+    let output_dir_note = config.output_dir.as_ref().map(|dir| {
+        format!(
+            "Write all artifacts to `{}`. Use it as the `{}` parameter instead of deriving one.",
+            dir.display(),
+            config.sop.output_parameter()
+        )
+    });
+    if let Some(note) = &output_dir_note {
+        addendums.push(("output-dir", note));
+    }
+    if config.non_interactive {
+        addendums.push(("non-interactive", NON_INTERACTIVE_ADDENDUM));
+    }
+
     let prompt = build_prompt(config.sop, config.user_input.as_deref(), &addendums);
 
     // 3. Get interactive backend configuration
@@ -134,6 +241,8 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
                 prompt_flag: None, // Prompt appended as last arg by default
                 output_format: ralph_adapters::OutputFormat::Text,
                 env_vars: vec![],
+                large_prompt: ralph_adapters::LargePrompt::default(),
+                continue_flag: None,
             }
         } else {
             // For custom backend from config, we need to load the configuration to get the command/args
@@ -156,16 +265,43 @@ pub fn run_sop(config: SopRunConfig) -> Result<(), SopRunError> {
                 ));
             }
         }
+    } else if config.non_interactive {
+        if config.agent_teams && is_claude {
+            CliBackend {
+                prompt_flag: Some("-p".to_string()),
+                ..CliBackend::claude_interactive_teams()
+            }
+        } else {
+            CliBackend::for_print_prompt(&backend_name)?
+        }
     } else if config.agent_teams && is_claude {
         CliBackend::claude_interactive_teams()
     } else {
         CliBackend::for_interactive_prompt(&backend_name)?
     };
 
-    // 4. Spawn the interactive session
-    spawn_interactive(&cli_backend, &prompt)?;
+    // 4. Spawn the interactive session, or run to completion and check the artifacts
+    let Some(output_dir) = non_interactive_output else {
+        spawn_interactive(&cli_backend, &prompt)?;
+        return Ok(());
+    };
 
-    Ok(())
+    let output = run_captured(&cli_backend, &prompt)?;
+    std::io::stdout().write_all(&output.stdout)?;
+    if !output.status.success() {
+        std::io::stderr().write_all(&output.stderr)?;
+        return Err(SopRunError::BackendFailed(output.status));
+    }
+
+    let missing = config.sop.missing_outputs(&output_dir);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(SopRunError::MissingOutputs {
+            dir: output_dir,
+            missing,
+        })
+    }
 }
 
 /// Resolves which backend to use.
@@ -259,6 +395,32 @@ fn spawn_interactive(backend: &CliBackend, prompt: &str) -> Result<(), SopRunErr
     Ok(())
 }
 
+/// Runs the backend to completion in print mode, capturing its output.
+fn run_captured(backend: &CliBackend, prompt: &str) -> Result<std::process::Output, SopRunError> {
+    // The temp file (large Claude prompts) must outlive the child
+    let (command, args, stdin_input, _temp_file) = backend.build_command(prompt, false);
+
+    let mut cmd = Command::new(&command);
+    cmd.args(&args)
+        .stdin(if stdin_input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd.envs(backend.env_vars.iter().map(|(k, v)| (k, v)));
+
+    let mut child = cmd.spawn()?;
+    if let Some(input) = stdin_input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    Ok(child.wait_with_output()?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,6 +542,8 @@ mod tests {
             config_path: None,
             custom_args: None,
             agent_teams: false,
+            non_interactive: false,
+            output_dir: None,
         };
 
         let err = run_sop(config).expect_err("expected error");
@@ -407,11 +571,91 @@ mod tests {
                 "exit 0".to_string(),
             ]),
             agent_teams: false,
+            non_interactive: false,
+            output_dir: None,
         };
 
         run_sop(config).expect("run sop");
     }
 
+    fn non_interactive_config(script: &str, output_dir: &Path) -> SopRunConfig {
+        SopRunConfig {
+            sop: Sop::Pdd,
+            user_input: Some("Build a REST API".to_string()),
+            backend_override: Some("custom".to_string()),
+            config_path: None,
+            custom_args: Some(vec!["sh".to_string(), "-c".to_string(), script.to_string()]),
+            agent_teams: false,
+            non_interactive: true,
+            output_dir: Some(output_dir.to_path_buf()),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_sop_non_interactive_checks_outputs() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+        let output_dir = temp_dir.path().join("specs/api");
+
+        let script = format!(
+            "mkdir -p {dir} && cd {dir} && touch rough-idea.md requirements.md design.md plan.md summary.md",
+            dir = output_dir.display()
+        );
+        run_sop(non_interactive_config(&script, &output_dir)).expect("run sop");
+
+        std::fs::remove_file(output_dir.join("design.md")).unwrap();
+        let err = run_sop(non_interactive_config("exit 0", &output_dir)).expect_err("missing");
+        match err {
+            SopRunError::MissingOutputs { dir, missing } => {
+                assert_eq!(dir, output_dir);
+                assert_eq!(missing, vec!["design.md".to_string()]);
+            }
+            other => panic!("expected MissingOutputs, got {other:?}"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_sop_non_interactive_backend_failure() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let _cwd = CwdGuard::set(temp_dir.path());
+
+        let err = run_sop(non_interactive_config("exit 3", temp_dir.path())).expect_err("failure");
+
+        assert!(matches!(err, SopRunError::BackendFailed(status) if status.code() == Some(3)));
+    }
+
+    #[test]
+    fn test_run_sop_non_interactive_requires_input() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let mut config = non_interactive_config("exit 0", temp_dir.path());
+        config.user_input = None;
+
+        let err = run_sop(config).expect_err("missing input");
+
+        assert!(matches!(err, SopRunError::MissingNonInteractiveInput));
+    }
+
+    #[test]
+    fn test_code_task_outputs_found_recursively() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        assert_eq!(
+            Sop::CodeTaskGenerator.missing_outputs(temp_dir.path()),
+            vec!["*.code-task.md".to_string()]
+        );
+
+        let tasks = temp_dir.path().join("tasks");
+        std::fs::create_dir_all(&tasks).unwrap();
+        std::fs::write(tasks.join("task-01-setup.code-task.md"), "# Task").unwrap();
+
+        assert!(
+            Sop::CodeTaskGenerator
+                .missing_outputs(temp_dir.path())
+                .is_empty()
+        );
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Tests for build_prompt addendums
     // ─────────────────────────────────────────────────────────────────────────
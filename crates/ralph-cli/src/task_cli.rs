@@ -5,14 +5,18 @@
 //! - `list`: List all tasks
 //! - `ready`: Show unblocked tasks
 //! - `close`: Mark a task as complete
+//! - `update`: Change a task's status or add blockers
 //! - `show`: Show a single task by ID
+//! - `block` / `unblock`: Add or remove a dependency between tasks
+//! - `reprioritize`: Change a task's priority
 
 use crate::display::colors;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use clap::{Parser, Subcommand, ValueEnum};
-use ralph_core::{Task, TaskStatus, TaskStore};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use ralph_core::{Task, TaskStatus, TaskStore, parse_priority};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Output format for task commands.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
@@ -54,8 +58,20 @@ pub enum TaskCommands {
     /// Mark a task as failed
     Fail(FailArgs),
 
+    /// Change a task's status or add blockers
+    Update(UpdateArgs),
+
     /// Show a single task by ID
     Show(ShowArgs),
+
+    /// Make a task wait for another task to complete
+    Block(BlockArgs),
+
+    /// Remove a dependency between two tasks
+    Unblock(BlockArgs),
+
+    /// Change a task's priority
+    Reprioritize(ReprioritizeArgs),
 }
 
 /// Arguments for the `task add` command.
@@ -64,8 +80,8 @@ pub struct AddArgs {
     /// Task title
     pub title: String,
 
-    /// Priority (1-5, default 3)
-    #[arg(short = 'p', long, default_value = "3")]
+    /// Priority: high, normal, low, or 1-5 (1 = highest)
+    #[arg(short = 'p', long, default_value = "normal", value_parser = parse_priority)]
     pub priority: u8,
 
     /// Task description
@@ -76,6 +92,14 @@ pub struct AddArgs {
     #[arg(long)]
     pub blocked_by: Option<String>,
 
+    /// Tags for grouping and filtering (comma-separated or repeated)
+    #[arg(short = 't', long = "tag", value_delimiter = ',')]
+    pub tags: Vec<String>,
+
+    /// ID of the task this one was split out of
+    #[arg(long)]
+    pub parent: Option<String>,
+
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
     pub format: OutputFormat,
@@ -84,9 +108,17 @@ pub struct AddArgs {
 /// Arguments for the `task list` command.
 #[derive(Parser, Debug)]
 pub struct ListArgs {
-    /// Filter by status: open, in_progress, closed, failed
-    #[arg(short = 's', long)]
-    pub status: Option<String>,
+    /// Filter by status: open, in_progress, closed (done), failed
+    #[arg(short = 's', long, value_parser = TaskStatus::from_str)]
+    pub status: Option<TaskStatus>,
+
+    /// Show only tasks with this tag
+    #[arg(short = 't', long)]
+    pub tag: Option<String>,
+
+    /// Show only unfinished tasks still waiting on a blocker
+    #[arg(long, short = 'b')]
+    pub blocked: bool,
 
     /// Show only tasks from the last N days
     #[arg(long, short = 'd')]
@@ -131,6 +163,26 @@ pub struct FailArgs {
     pub id: String,
 }
 
+/// Arguments for the `task update` command.
+#[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("change").required(true).multiple(true).args(["status", "block_on"])))]
+pub struct UpdateArgs {
+    /// Task ID
+    pub id: String,
+
+    /// New status: open, in_progress, closed (done), failed
+    #[arg(short = 's', long, value_parser = TaskStatus::from_str)]
+    pub status: Option<TaskStatus>,
+
+    /// Task ID that must complete first (repeatable)
+    #[arg(long)]
+    pub block_on: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
 /// Arguments for the `task show` command.
 #[derive(Parser, Debug)]
 pub struct ShowArgs {
@@ -142,30 +194,42 @@ pub struct ShowArgs {
     pub format: OutputFormat,
 }
 
+/// Arguments for the `task block` and `task unblock` commands.
+#[derive(Parser, Debug)]
+pub struct BlockArgs {
+    /// Task ID whose dependency changes
+    pub id: String,
+
+    /// Task ID it depends on
+    #[arg(long)]
+    pub on: String,
+}
+
+/// Arguments for the `task reprioritize` command.
+#[derive(Parser, Debug)]
+pub struct ReprioritizeArgs {
+    /// Task ID
+    pub id: String,
+
+    /// New priority: high, normal, low, or 1-5 (1 = highest)
+    #[arg(value_parser = parse_priority)]
+    pub priority: u8,
+}
+
+/// Formats a task's priority as its number and level name (e.g., `1 high`).
+fn priority_label(task: &Task) -> String {
+    format!("{} {}", task.priority, task.priority_level())
+}
+
 /// Gets the tasks file path.
 fn get_tasks_path(root: Option<&PathBuf>) -> PathBuf {
     let base = root.map(|p| p.as_path()).unwrap_or(Path::new("."));
     base.join(".ralph").join("agent").join("tasks.jsonl")
 }
 
-fn status_matches_filter(status: TaskStatus, filter: &str) -> bool {
-    let normalized = filter.to_lowercase().replace(['_', '-'], "");
-    match status {
-        TaskStatus::Open => normalized == "open",
-        TaskStatus::InProgress => normalized == "inprogress",
-        TaskStatus::Closed => normalized == "closed",
-        TaskStatus::Failed => normalized == "failed",
-    }
-}
-
 fn filter_tasks_for_list(store: &TaskStore, args: &ListArgs) -> Vec<Task> {
-    let mut tasks: Vec<_> = if let Some(status_str) = args.status.as_deref() {
-        store
-            .all()
-            .iter()
-            .filter(|t| status_matches_filter(t.status, status_str))
-            .cloned()
-            .collect()
+    let mut tasks: Vec<_> = if let Some(status) = args.status {
+        store.by_status(status).into_iter().cloned().collect()
     } else if args.all {
         store.all().to_vec()
     } else {
@@ -177,6 +241,15 @@ fn filter_tasks_for_list(store: &TaskStore, args: &ListArgs) -> Vec<Task> {
             .collect()
     };
 
+    if args.blocked {
+        let blocked: Vec<&str> = store.blocked().iter().map(|t| t.id.as_str()).collect();
+        tasks.retain(|t| blocked.contains(&t.id.as_str()));
+    }
+
+    if let Some(tag) = args.tag.as_deref() {
+        tasks.retain(|t| t.has_tag(tag));
+    }
+
     if let Some(days) = args.days {
         let cutoff = Utc::now() - chrono::Duration::days(days);
         tasks.retain(|t| {
@@ -262,7 +335,13 @@ pub fn execute(args: TaskArgs, use_colors: bool) -> Result<()> {
         TaskCommands::Ready(ready_args) => execute_ready(ready_args, root.as_ref(), use_colors),
         TaskCommands::Close(close_args) => execute_close(close_args, root.as_ref(), use_colors),
         TaskCommands::Fail(fail_args) => execute_fail(fail_args, root.as_ref(), use_colors),
+        TaskCommands::Update(update_args) => execute_update(update_args, root.as_ref(), use_colors),
         TaskCommands::Show(show_args) => execute_show(show_args, root.as_ref(), use_colors),
+        TaskCommands::Block(block_args) => execute_block(block_args, root.as_ref(), use_colors),
+        TaskCommands::Unblock(block_args) => execute_unblock(block_args, root.as_ref(), use_colors),
+        TaskCommands::Reprioritize(reprioritize_args) => {
+            execute_reprioritize(reprioritize_args, root.as_ref(), use_colors)
+        }
     }
 }
 
@@ -270,7 +349,9 @@ fn execute_add(args: AddArgs, root: Option<&PathBuf>, use_colors: bool) -> Resul
     let path = get_tasks_path(root);
     let mut store = TaskStore::load(&path).context("Failed to load tasks")?;
 
-    let mut task = Task::new(args.title, args.priority);
+    let mut task = Task::new(args.title, args.priority)
+        .with_tags(args.tags)
+        .with_parent(args.parent);
 
     // Auto-tag with loop ID from marker file if available
     let loop_id_marker = get_tasks_path(root)
@@ -297,8 +378,11 @@ fn execute_add(args: AddArgs, root: Option<&PathBuf>, use_colors: bool) -> Resul
     }
 
     let task_id = task.id.clone();
-    store.add(task.clone());
-    store.save().context("Failed to save tasks")?;
+    store
+        .with_exclusive_lock(|s| {
+            s.add(task.clone());
+        })
+        .context("Failed to save tasks")?;
 
     match args.format {
         OutputFormat::Table => {
@@ -308,10 +392,13 @@ fn execute_add(args: AddArgs, root: Option<&PathBuf>, use_colors: bool) -> Resul
                 println!("Created task {}", task_id);
             }
             println!("  Title: {}", task.title);
-            println!("  Priority: {}", task.priority);
+            println!("  Priority: {}", priority_label(&task));
             if !task.blocked_by.is_empty() {
                 println!("  Blocked by: {}", task.blocked_by.join(", "));
             }
+            if !task.tags.is_empty() {
+                println!("  Tags: {}", task.tags.join(", "));
+            }
         }
         OutputFormat::Json => {
             println!("{}", serde_json::to_string(&task)?);
@@ -384,14 +471,17 @@ fn execute_list(args: ListArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
                             status_str,
                             colors::RESET,
                             priority_color,
-                            task.priority,
+                            priority_label(task),
                             colors::RESET,
                             title_truncated
                         );
                     } else {
                         println!(
                             "{:<20} {:<15} {:<8} {:<60}",
-                            task.id, status_str, task.priority, title_truncated
+                            task.id,
+                            status_str,
+                            priority_label(task),
+                            title_truncated
                         );
                     }
                 }
@@ -416,6 +506,14 @@ fn execute_ready(args: ReadyArgs, root: Option<&PathBuf>, use_colors: bool) -> R
 
     let ready = filter_tasks_for_ready(&store, &args, root);
 
+    // Tasks in a cycle never become ready; say why instead of silently omitting them
+    if let Some(cycle) = store.find_cycle() {
+        eprintln!(
+            "Warning: dependency cycle {} — these tasks will never be ready. Break it with `ralph tools task unblock`.",
+            cycle.join(" -> ")
+        );
+    }
+
     match args.format {
         OutputFormat::Table => {
             if ready.is_empty() {
@@ -456,14 +554,16 @@ fn execute_ready(args: ReadyArgs, root: Option<&PathBuf>, use_colors: bool) -> R
                             task.id,
                             colors::RESET,
                             priority_color,
-                            task.priority,
+                            priority_label(task),
                             colors::RESET,
                             title_truncated
                         );
                     } else {
                         println!(
                             "{:<20} {:<8} {:<60}",
-                            task.id, task.priority, title_truncated
+                            task.id,
+                            priority_label(task),
+                            title_truncated
                         );
                     }
                 }
@@ -488,12 +588,9 @@ fn execute_close(args: CloseArgs, root: Option<&PathBuf>, use_colors: bool) -> R
 
     let task_id = args.id.clone();
     let title = store
-        .close(&task_id)
-        .context(format!("Task {} not found", task_id))?
-        .title
-        .clone();
-
-    store.save().context("Failed to save tasks")?;
+        .with_exclusive_lock(|s| s.close(&task_id).map(|t| t.title.clone()))
+        .context("Failed to save tasks")?
+        .context(format!("Task {} not found", task_id))?;
 
     if use_colors {
         println!(
@@ -516,12 +613,9 @@ fn execute_fail(args: FailArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
 
     let task_id = args.id.clone();
     let title = store
-        .fail(&task_id)
-        .context(format!("Task {} not found", task_id))?
-        .title
-        .clone();
-
-    store.save().context("Failed to save tasks")?;
+        .with_exclusive_lock(|s| s.fail(&task_id).map(|t| t.title.clone()))
+        .context("Failed to save tasks")?
+        .context(format!("Task {} not found", task_id))?;
 
     if use_colors {
         println!(
@@ -538,6 +632,150 @@ fn execute_fail(args: FailArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
     Ok(())
 }
 
+fn execute_block(args: BlockArgs, root: Option<&PathBuf>, use_colors: bool) -> Result<()> {
+    let path = get_tasks_path(root);
+    let mut store = TaskStore::load(&path).context("Failed to load tasks")?;
+
+    let title = store
+        .with_exclusive_lock(|s| s.block(&args.id, &args.on).map(|t| t.title.clone()))
+        .context("Failed to save tasks")??;
+
+    if use_colors {
+        println!(
+            "{}Blocked task: {} - {} (waits on {}){}",
+            colors::YELLOW,
+            args.id,
+            title,
+            args.on,
+            colors::RESET
+        );
+    } else {
+        println!(
+            "Blocked task: {} - {} (waits on {})",
+            args.id, title, args.on
+        );
+    }
+
+    Ok(())
+}
+
+fn execute_reprioritize(
+    args: ReprioritizeArgs,
+    root: Option<&PathBuf>,
+    use_colors: bool,
+) -> Result<()> {
+    let path = get_tasks_path(root);
+    let mut store = TaskStore::load(&path).context("Failed to load tasks")?;
+
+    let (title, label) = store
+        .with_exclusive_lock(|s| {
+            s.set_priority(&args.id, args.priority)
+                .map(|t| (t.title.clone(), priority_label(t)))
+        })
+        .context("Failed to save tasks")?
+        .context(format!("Task {} not found", args.id))?;
+
+    if use_colors {
+        println!(
+            "{}Reprioritized task: {} - {} (priority {}){}",
+            colors::GREEN,
+            args.id,
+            title,
+            label,
+            colors::RESET
+        );
+    } else {
+        println!(
+            "Reprioritized task: {} - {} (priority {})",
+            args.id, title, label
+        );
+    }
+
+    Ok(())
+}
+
+fn execute_unblock(args: BlockArgs, root: Option<&PathBuf>, use_colors: bool) -> Result<()> {
+    let path = get_tasks_path(root);
+    let mut store = TaskStore::load(&path).context("Failed to load tasks")?;
+
+    if !store
+        .with_exclusive_lock(|s| s.unblock(&args.id, &args.on))
+        .context("Failed to save tasks")??
+    {
+        println!("Task {} was not blocked by {}", args.id, args.on);
+        return Ok(());
+    }
+
+    if use_colors {
+        println!(
+            "{}Unblocked task: {} (no longer waits on {}){}",
+            colors::GREEN,
+            args.id,
+            args.on,
+            colors::RESET
+        );
+    } else {
+        println!(
+            "Unblocked task: {} (no longer waits on {})",
+            args.id, args.on
+        );
+    }
+
+    Ok(())
+}
+
+/// Applies `args` to the task, adding blockers before changing status.
+fn apply_update(store: &mut TaskStore, args: &UpdateArgs) -> Result<Task> {
+    if store.get(&args.id).is_none() {
+        anyhow::bail!("Task {} not found", args.id);
+    }
+    for on in &args.block_on {
+        store.block(&args.id, on)?;
+    }
+    if let Some(status) = args.status {
+        store.set_status(&args.id, status);
+    }
+    Ok(store
+        .get(&args.id)
+        .expect("task existence checked above")
+        .clone())
+}
+
+fn execute_update(args: UpdateArgs, root: Option<&PathBuf>, use_colors: bool) -> Result<()> {
+    let path = get_tasks_path(root);
+    let mut store = TaskStore::load(&path).context("Failed to load tasks")?;
+
+    let task = store
+        .with_exclusive_lock(|s| apply_update(s, &args))
+        .context("Failed to save tasks")??;
+
+    match args.format {
+        OutputFormat::Table => {
+            let mut summary = format!(
+                "Updated task: {} - {} ({}",
+                task.id, task.title, task.status
+            );
+            if !task.blocked_by.is_empty() {
+                summary.push_str(&format!(", waits on {}", task.blocked_by.join(", ")));
+            }
+            summary.push(')');
+            if use_colors {
+                println!("{}{}{}", colors::GREEN, summary, colors::RESET);
+            } else {
+                println!("{}", summary);
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&task)?);
+        }
+        OutputFormat::Quiet => {
+            println!("{}", task.id);
+        }
+    }
+
+    Ok(())
+}
+
 fn execute_show(args: ShowArgs, root: Option<&PathBuf>, use_colors: bool) -> Result<()> {
     let path = get_tasks_path(root);
     let store = TaskStore::load(&path).context("Failed to load tasks")?;
@@ -548,12 +786,7 @@ fn execute_show(args: ShowArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
 
     match args.format {
         OutputFormat::Table => {
-            let status_str = match task.status {
-                TaskStatus::Open => "open",
-                TaskStatus::InProgress => "in_progress",
-                TaskStatus::Closed => "closed",
-                TaskStatus::Failed => "failed",
-            };
+            let status_str = task.status.as_str();
 
             if use_colors {
                 let status_color = match task.status {
@@ -582,13 +815,22 @@ fn execute_show(args: ShowArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
                 println!(
                     "Priority:    {}{}{}",
                     priority_color,
-                    task.priority,
+                    priority_label(task),
                     colors::RESET
                 );
                 if !task.blocked_by.is_empty() {
                     println!("Blocked by:  {}", task.blocked_by.join(", "));
                 }
+                if !task.tags.is_empty() {
+                    println!("Tags:        {}", task.tags.join(", "));
+                }
+                if let Some(parent) = &task.parent {
+                    println!("Parent:      {}", parent);
+                }
                 println!("Created:     {}", task.created);
+                if let Some(updated) = &task.updated {
+                    println!("Updated:     {}", updated);
+                }
                 if let Some(closed) = &task.closed {
                     println!("Closed:      {}", closed);
                 }
@@ -599,11 +841,20 @@ fn execute_show(args: ShowArgs, root: Option<&PathBuf>, use_colors: bool) -> Res
                     println!("Description: {}", desc);
                 }
                 println!("Status:      {}", status_str);
-                println!("Priority:    {}", task.priority);
+                println!("Priority:    {}", priority_label(task));
                 if !task.blocked_by.is_empty() {
                     println!("Blocked by:  {}", task.blocked_by.join(", "));
                 }
+                if !task.tags.is_empty() {
+                    println!("Tags:        {}", task.tags.join(", "));
+                }
+                if let Some(parent) = &task.parent {
+                    println!("Parent:      {}", parent);
+                }
                 println!("Created:     {}", task.created);
+                if let Some(updated) = &task.updated {
+                    println!("Updated:     {}", updated);
+                }
                 if let Some(closed) = &task.closed {
                     println!("Closed:      {}", closed);
                 }
@@ -647,7 +898,9 @@ mod tests {
         let store = write_tasks(temp_dir.path(), vec![open_task, in_progress]);
 
         let args = ListArgs {
-            status: Some("in_progress".to_string()),
+            status: Some("in_progress".parse().unwrap()),
+            tag: None,
+            blocked: false,
             days: None,
             limit: None,
             all: true,
@@ -684,4 +937,75 @@ mod tests {
         assert_eq!(ready.len(), 1);
         assert_eq!(ready[0].loop_id.as_deref(), Some("loop-a"));
     }
+
+    #[test]
+    fn test_list_filters_by_tag_and_blocked() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let blocker = Task::new("Schema".to_string(), 3).with_tags(vec!["db".to_string()]);
+        let waiting = Task::new("Migrate".to_string(), 3)
+            .with_tags(vec!["db".to_string()])
+            .with_blocker(blocker.id.clone());
+        let other = Task::new("Docs".to_string(), 3);
+
+        let store = write_tasks(temp_dir.path(), vec![blocker, waiting, other]);
+
+        let mut args = ListArgs {
+            status: None,
+            tag: Some("DB".to_string()),
+            blocked: false,
+            days: None,
+            limit: None,
+            all: false,
+            format: OutputFormat::Json,
+        };
+        let titles = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.title).collect::<Vec<_>>();
+        assert_eq!(
+            titles(filter_tasks_for_list(&store, &args)),
+            vec!["Schema", "Migrate"]
+        );
+
+        args.tag = None;
+        args.blocked = true;
+        assert_eq!(
+            titles(filter_tasks_for_list(&store, &args)),
+            vec!["Migrate"]
+        );
+    }
+
+    #[test]
+    fn test_apply_update_sets_status_and_blockers() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let first = Task::new("First".to_string(), 3);
+        let second = Task::new("Second".to_string(), 3);
+        let (first_id, second_id) = (first.id.clone(), second.id.clone());
+        let mut store = write_tasks(temp_dir.path(), vec![first, second]);
+
+        let args = UpdateArgs {
+            id: second_id.clone(),
+            status: Some(TaskStatus::InProgress),
+            block_on: vec![first_id.clone()],
+            format: OutputFormat::Json,
+        };
+        let task = apply_update(&mut store, &args).unwrap();
+        assert_eq!(task.status, TaskStatus::InProgress);
+        assert_eq!(task.blocked_by, vec![first_id.clone()]);
+        assert!(task.updated.is_some());
+
+        // A cycle is rejected
+        let args = UpdateArgs {
+            id: first_id,
+            status: None,
+            block_on: vec![second_id],
+            format: OutputFormat::Json,
+        };
+        assert!(apply_update(&mut store, &args).is_err());
+
+        let args = UpdateArgs {
+            id: "task-missing".to_string(),
+            status: Some(TaskStatus::Closed),
+            block_on: vec![],
+            format: OutputFormat::Json,
+        };
+        assert!(apply_update(&mut store, &args).is_err());
+    }
 }
@@ -0,0 +1,83 @@
+//! OpenTelemetry export of the loop's tracing spans.
+//!
+//! The orchestrator opens a `ralph.iteration` span per iteration and a
+//! `ralph.backend` span per backend invocation. Built with the `otel`
+//! feature, [`layer`] exports them over OTLP/HTTP when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set; the standard `OTEL_*` variables
+//! configure the exporter. Without the feature it never exports.
+
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variable that turns span export on.
+#[cfg(feature = "otel")]
+const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+#[cfg(feature = "otel")]
+static PROVIDER: std::sync::OnceLock<opentelemetry_sdk::trace::SdkTracerProvider> =
+    std::sync::OnceLock::new();
+
+/// Returns the layer exporting spans to the OTLP endpoint, or `None` if no
+/// endpoint is set or the exporter can't be built.
+#[cfg(feature = "otel")]
+pub fn layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig as _;
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+
+    std::env::var_os(ENDPOINT_ENV).filter(|endpoint| !endpoint.is_empty())?;
+
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            // Logging isn't set up yet
+            eprintln!("Warning: OpenTelemetry export disabled: {e}");
+            return None;
+        }
+    };
+
+    // OTEL_SERVICE_NAME, when set, wins over the default name
+    let mut resource = Resource::builder();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+        resource = resource.with_service_name("ralph");
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    let tracer = provider.tracer("ralph");
+    PROVIDER.set(provider).ok()?;
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Returns `None`: span export needs the `otel` feature.
+#[cfg(not(feature = "otel"))]
+pub fn layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    None::<tracing_subscriber::layer::Identity>
+}
+
+/// Flushes spans still waiting to be exported.
+///
+/// Call before exiting; `std::process::exit` skips the exporter's own
+/// shutdown.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    if let Some(provider) = PROVIDER.get()
+        && let Err(e) = provider.shutdown()
+    {
+        tracing::debug!(error = %e, "OpenTelemetry shutdown failed");
+    }
+}
@@ -0,0 +1,95 @@
+//! Parsing for time-valued CLI flags such as `--since` and `--older-than`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+/// Parses a `--since` value: an RFC 3339 timestamp, or a duration before
+/// `now` such as `90s`, `10m`, `2h`, `1d` or `1w`.
+pub(crate) fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+
+    parse_duration(value)
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .with_context(|| {
+            format!(
+                "Invalid --since '{}': expected an RFC 3339 timestamp (2025-01-24T10:00:00Z) \
+                 or a relative duration like 30s, 10m, 2h, 1d, 1w",
+                value
+            )
+        })
+}
+
+/// Parses a duration such as `90s`, `10m`, `2h`, `1d` or `1w`.
+pub(crate) fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    chrono::Duration::try_seconds(amount.checked_mul(seconds_per_unit)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since_relative_durations() {
+        let now = DateTime::parse_from_rfc3339("2026-01-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let cases = [
+            ("30s", "2026-01-10T11:59:30Z"),
+            ("10m", "2026-01-10T11:50:00Z"),
+            ("2h", "2026-01-10T10:00:00Z"),
+            ("1d", "2026-01-09T12:00:00Z"),
+            ("1w", "2026-01-03T12:00:00Z"),
+            ("0m", "2026-01-10T12:00:00Z"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_since(input, now).unwrap().to_rfc3339(),
+                DateTime::parse_from_rfc3339(expected)
+                    .unwrap()
+                    .with_timezone(&Utc)
+                    .to_rfc3339(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_since_rfc3339_and_invalid() {
+        let now = Utc::now();
+        assert_eq!(
+            parse_since("2025-01-24T10:00:00+02:00", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2025-01-24T08:00:00+00:00"
+        );
+
+        for input in [
+            "",
+            "m",
+            "10",
+            "10 m",
+            "10min",
+            "-5m",
+            "yesterday",
+            "99999999999999w",
+        ] {
+            let err = parse_since(input, now).unwrap_err().to_string();
+            assert!(err.contains("Invalid --since"), "{input}: {err}");
+        }
+    }
+}
@@ -8,13 +8,17 @@
 //! - `memory`: Persistent memories for accumulated learning
 //! - `task`: Work item tracking (beads-lite)
 //! - `skill`: Load skill content on demand
+//! - `scratchpad`: Inspect and restore archived scratchpads
+//! - `ignore`: Check which paths `.ralphignore` hides from the agent
 //! - `interact`: Human-in-the-loop communication (progress updates, notifications)
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+use crate::ignore_cli;
 use crate::interact;
 use crate::memory;
+use crate::scratchpad_cli;
 use crate::skill_cli;
 use crate::task_cli;
 
@@ -36,6 +40,12 @@ pub enum ToolsCommands {
     /// Load and manage skills
     Skill(skill_cli::SkillArgs),
 
+    /// Inspect and restore scratchpads archived by previous runs
+    Scratchpad(scratchpad_cli::ScratchpadArgs),
+
+    /// Check which paths .ralphignore keeps out of prompts
+    Ignore(ignore_cli::IgnoreArgs),
+
     /// Interact with human via Telegram (progress updates, notifications)
     Interact(interact::InteractArgs),
 }
@@ -46,6 +56,10 @@ pub async fn execute(args: ToolsArgs, use_colors: bool) -> Result<()> {
         ToolsCommands::Memory(memory_args) => memory::execute(memory_args, use_colors),
         ToolsCommands::Task(task_args) => task_cli::execute(task_args, use_colors),
         ToolsCommands::Skill(skill_args) => skill_cli::execute(skill_args),
+        ToolsCommands::Scratchpad(scratchpad_args) => {
+            scratchpad_cli::execute(scratchpad_args, use_colors)
+        }
+        ToolsCommands::Ignore(ignore_args) => ignore_cli::execute(ignore_args, use_colors),
         ToolsCommands::Interact(interact_args) => interact::execute(interact_args).await,
     }
 }
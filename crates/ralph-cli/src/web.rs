@@ -4,8 +4,13 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use indicatif::{ProgressBar, ProgressStyle};
+use ralph_core::{
+    KeyringStore, SecretStore, WebAuthConfig, WebConfig, missing_env_sources, resolve_env_value,
+};
 use std::env;
 use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Duration;
@@ -35,8 +40,27 @@ pub struct WebArgs {
     #[arg(long, default_value = "5173")]
     pub frontend_port: u16,
 
-    /// Workspace root directory (default: current directory)
+    /// Dashboard address as HOST:PORT (default: `web.bind` on --frontend-port).
+    /// Use e.g. 0.0.0.0:8080 with --allow-public to reach the dashboard from other machines.
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "frontend_port")]
+    pub bind: Option<String>,
+
+    /// Allow binding a non-loopback address (default: `web.allow_public`)
     #[arg(long)]
+    pub allow_public: bool,
+
+    /// Bearer token for dashboard and API requests
+    /// (default: $RALPH_WEB_TOKEN, then `web.token`, else generated at startup)
+    #[arg(long, value_name = "SECRET")]
+    pub token: Option<String>,
+
+    /// Serve the dashboard without a token, ignoring `web.auth`
+    #[arg(long, conflicts_with = "token")]
+    pub no_auth: bool,
+
+    /// Workspace root directory, set from the global `--workspace` flag
+    /// (default: current directory)
+    #[arg(skip)]
     pub workspace: Option<PathBuf>,
 
     /// Don't open the dashboard in the default browser
@@ -144,14 +168,148 @@ async fn run_npm_install_with(root: &Path, npm_cmd: &OsStr) -> Result<()> {
     Ok(())
 }
 
+/// Parse a `--bind` value (`HOST:PORT`, with IPv6 hosts in brackets).
+fn parse_bind(bind: &str) -> Result<(String, u16)> {
+    let (host, port) = bind
+        .rsplit_once(':')
+        .with_context(|| format!("Invalid --bind '{bind}': expected HOST:PORT"))?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        anyhow::bail!("Invalid --bind '{bind}': missing host");
+    }
+    let port = port
+        .parse()
+        .with_context(|| format!("Invalid --bind '{bind}': '{port}' is not a port"))?;
+    Ok((host.to_string(), port))
+}
+
+/// Whether `host` only accepts connections from this machine.
+fn is_loopback(host: &str) -> bool {
+    host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// The URL to open the dashboard at, carrying the token if auth is enabled.
+fn dashboard_url(host: &str, port: u16, token: Option<&str>) -> String {
+    let host = match host.parse::<IpAddr>() {
+        Ok(ip) if ip.is_unspecified() => "localhost".to_string(),
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]"),
+        _ => host.to_string(),
+    };
+    match token {
+        Some(token) => {
+            let token: String = token
+                .bytes()
+                .map(|b| {
+                    if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                        char::from(b).to_string()
+                    } else {
+                        format!("%{b:02X}")
+                    }
+                })
+                .collect();
+            format!("http://{host}:{port}/?token={token}")
+        }
+        None => format!("http://{host}:{port}"),
+    }
+}
+
+/// Dashboard address and access settings after applying flags over `web:`.
+#[derive(Debug, PartialEq, Eq)]
+struct WebSettings {
+    host: String,
+    port: u16,
+    auth: WebAuthConfig,
+    /// `None` when no request needs a token.
+    token: Option<String>,
+    /// True if `token` was generated for this session.
+    generated_token: bool,
+}
+
+/// Resolves the dashboard settings from flags, `$RALPH_WEB_TOKEN` and config.
+///
+/// Flags win over the environment, which wins over the config. A
+/// non-loopback bind needs `--allow-public` or `web.allow_public`.
+fn resolve_settings(
+    args: &WebArgs,
+    config: &WebConfig,
+    env_token: Option<String>,
+    store: &dyn SecretStore,
+) -> Result<WebSettings> {
+    let (host, port) = match &args.bind {
+        Some(bind) => parse_bind(bind)?,
+        None => (config.bind.clone(), args.frontend_port),
+    };
+    if !(is_loopback(&host) || args.allow_public || config.allow_public) {
+        anyhow::bail!(
+            "Refusing to serve the dashboard on {host}, which is reachable from other machines.\n\
+             Pass --allow-public or set `web.allow_public: true` to allow it."
+        );
+    }
+
+    let auth = if args.no_auth {
+        WebAuthConfig::NONE
+    } else {
+        config.auth
+    };
+    if !auth.requires_token() {
+        return Ok(WebSettings {
+            host,
+            port,
+            auth,
+            token: None,
+            generated_token: false,
+        });
+    }
+
+    let configured = match &config.token {
+        Some(source) => {
+            if let Some(missing) = missing_env_sources(source, store).first() {
+                anyhow::bail!("web.token: {missing}");
+            }
+            Some(resolve_env_value(source, store).value)
+        }
+        None => None,
+    };
+    let token = args
+        .token
+        .clone()
+        .or(env_token)
+        .or(configured)
+        .filter(|token| !token.is_empty());
+    let generated_token = token.is_none();
+    let token = match token {
+        Some(token) => token,
+        None => generate_token()?,
+    };
+
+    Ok(WebSettings {
+        host,
+        port,
+        auth,
+        token: Some(token),
+        generated_token,
+    })
+}
+
+/// Generates a random 256-bit token, hex-encoded.
+fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to generate a dashboard token: {e}"))?;
+    Ok(bytes.iter().fold(String::with_capacity(64), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    }))
+}
+
 /// Check that a TCP port is available for binding.
-fn check_port_available(port: u16) -> Result<()> {
-    match std::net::TcpListener::bind(("127.0.0.1", port)) {
+fn check_port_available(host: &str, port: u16) -> Result<()> {
+    match std::net::TcpListener::bind((host, port)) {
         Ok(_) => Ok(()),
         Err(_) => {
             anyhow::bail!(
                 "Port {} is already in use.\n\
-                 Use --backend-port or --frontend-port to pick a different port.\n\
+                 Use --backend-port, --frontend-port or --bind to pick a different port.\n\
                  To free the port: fuser -k {}/tcp",
                 port,
                 port
@@ -266,11 +424,11 @@ async fn forward_output(
 }
 
 /// Run both backend and frontend dev servers in parallel
-pub async fn execute(args: WebArgs) -> Result<()> {
+pub async fn execute(config_sources: &[crate::ConfigSource], args: WebArgs) -> Result<()> {
     println!("Starting Ralph web servers...");
 
     // Determine workspace root: explicit flag or current directory
-    let workspace_root = match args.workspace {
+    let workspace_root = match &args.workspace {
         Some(path) => {
             // Canonicalize to get absolute path
             path.canonicalize()
@@ -286,20 +444,54 @@ pub async fn execute(args: WebArgs) -> Result<()> {
     // Verify Node.js/npm, check tsx version, and auto-install dependencies if needed
     preflight(&workspace_root, &backend_dir).await?;
 
+    let config = crate::load_config_with_overrides(config_sources)?;
+    let WebSettings {
+        host: frontend_host,
+        port: frontend_port,
+        auth,
+        token,
+        generated_token,
+    } = resolve_settings(
+        &args,
+        &config.web,
+        env::var("RALPH_WEB_TOKEN").ok(),
+        &KeyringStore,
+    )?;
+
+    if let Some(token) = token.as_deref().filter(|_| generated_token) {
+        println!("Generated a dashboard token for this session (it is not saved): {token}");
+    }
+    if !is_loopback(&frontend_host) && token.is_none() {
+        println!(
+            "Warning: the dashboard is reachable from other machines on {} without authentication.",
+            frontend_host
+        );
+    }
+
     // Check ports before spawning anything
-    check_port_available(args.backend_port)?;
-    check_port_available(args.frontend_port)?;
+    check_port_available("127.0.0.1", args.backend_port)?;
+    check_port_available(&frontend_host, frontend_port)?;
 
     println!("Using workspace: {}", workspace_root.display());
 
     // Spawn backend server with piped output
     // Pass RALPH_WORKSPACE_ROOT so the backend knows where to spawn ralph run from
     // Pass PORT so the backend listens on the configured port
-    let mut backend = AsyncCommand::new("npm")
+    // The backend stays on localhost; remote clients reach it through the frontend proxy
+    let mut backend_command = AsyncCommand::new("npm");
+    backend_command
         .args(["run", "dev"])
         .current_dir(&backend_dir)
         .env("RALPH_WORKSPACE_ROOT", &workspace_root)
         .env("PORT", args.backend_port.to_string())
+        .env("HOST", "127.0.0.1");
+    if let Some(token) = &token {
+        backend_command
+            .env("RALPH_WEB_TOKEN", token)
+            .env("RALPH_WEB_AUTH_READ", auth.read.to_string())
+            .env("RALPH_WEB_AUTH_MUTATE", auth.mutate.to_string());
+    }
+    let mut backend = backend_command
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
@@ -312,17 +504,26 @@ pub async fn execute(args: WebArgs) -> Result<()> {
         })?;
 
     // Spawn frontend server with piped output
-    // Pass --port for Vite and RALPH_BACKEND_PORT for proxy config
-    let mut frontend = AsyncCommand::new("npm")
+    // Pass --port/--host for Vite and RALPH_BACKEND_PORT for proxy config
+    let mut frontend_command = AsyncCommand::new("npm");
+    frontend_command
         .args([
             "run",
             "dev",
             "--",
             "--port",
-            &args.frontend_port.to_string(),
+            &frontend_port.to_string(),
+            "--host",
+            &frontend_host,
         ])
         .current_dir(&frontend_dir)
-        .env("RALPH_BACKEND_PORT", args.backend_port.to_string())
+        .env("RALPH_BACKEND_PORT", args.backend_port.to_string());
+    if let Some(token) = &token {
+        frontend_command
+            .env("RALPH_WEB_TOKEN", token)
+            .env("RALPH_WEB_AUTH_READ", auth.read.to_string());
+    }
+    let mut frontend = frontend_command
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
@@ -370,7 +571,7 @@ pub async fn execute(args: WebArgs) -> Result<()> {
     });
 
     // Wait for both servers to become ready
-    let dashboard_url = format!("http://localhost:{}", args.frontend_port);
+    let dashboard_url = dashboard_url(&frontend_host, frontend_port, token.as_deref());
     let api_url = format!("http://localhost:{}", args.backend_port);
 
     let ready_result = tokio::time::timeout(READY_TIMEOUT, async {
@@ -544,14 +745,14 @@ mod tests {
         match TcpListener::bind(("127.0.0.1", 0)) {
             Ok(listener) => {
                 let port = listener.local_addr().expect("addr").port();
-                assert!(check_port_available(port).is_err());
+                assert!(check_port_available("127.0.0.1", port).is_err());
                 drop(listener);
 
                 // Some environments (CI, heavily loaded systems) can take a moment to fully
                 // release the port after the listener is dropped. Retry briefly to avoid flakes.
                 let mut freed = false;
                 for _ in 0..25 {
-                    if check_port_available(port).is_ok() {
+                    if check_port_available("127.0.0.1", port).is_ok() {
                         freed = true;
                         break;
                     }
@@ -565,11 +766,177 @@ mod tests {
             Err(err) => {
                 // Some sandboxes disallow binding; ensure we handle that path gracefully.
                 assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
-                assert!(check_port_available(0).is_err());
+                assert!(check_port_available("127.0.0.1", 0).is_err());
             }
         }
     }
 
+    #[test]
+    fn parse_bind_accepts_host_and_port() {
+        assert_eq!(
+            parse_bind("0.0.0.0:8080").unwrap(),
+            ("0.0.0.0".to_string(), 8080)
+        );
+        assert_eq!(parse_bind("[::]:8080").unwrap(), ("::".to_string(), 8080));
+        assert_eq!(
+            parse_bind("localhost:5173").unwrap(),
+            ("localhost".to_string(), 5173)
+        );
+        assert!(parse_bind("8080").is_err());
+        assert!(parse_bind(":8080").is_err());
+        assert!(parse_bind("0.0.0.0:http").is_err());
+    }
+
+    #[test]
+    fn loopback_hosts() {
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("::1"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("192.168.1.10"));
+        assert!(!is_loopback("studio"));
+    }
+
+    #[test]
+    fn dashboard_url_includes_token() {
+        assert_eq!(
+            dashboard_url("localhost", 5173, None),
+            "http://localhost:5173"
+        );
+        assert_eq!(
+            dashboard_url("0.0.0.0", 8080, Some("s3cret")),
+            "http://localhost:8080/?token=s3cret"
+        );
+        assert_eq!(
+            dashboard_url("localhost", 5173, Some("a b&c")),
+            "http://localhost:5173/?token=a%20b%26c"
+        );
+        assert_eq!(
+            dashboard_url("fe80::1", 8080, None),
+            "http://[fe80::1]:8080"
+        );
+    }
+
+    /// Keychain stand-in holding a single `web-token` entry.
+    struct WebTokenStore;
+
+    impl SecretStore for WebTokenStore {
+        fn get_secret(&self, entry: &str) -> Option<String> {
+            (entry == "web-token").then(|| "from-keychain".to_string())
+        }
+    }
+
+    #[derive(Parser)]
+    struct WebCli {
+        #[command(flatten)]
+        web: WebArgs,
+    }
+
+    fn web_args(extra: &[&str]) -> WebArgs {
+        let argv = std::iter::once("ralph-web").chain(extra.iter().copied());
+        WebCli::try_parse_from(argv).expect("valid web args").web
+    }
+
+    #[test]
+    fn resolve_settings_defaults_to_localhost_with_generated_token() {
+        let settings =
+            resolve_settings(&web_args(&[]), &WebConfig::default(), None, &WebTokenStore).unwrap();
+
+        assert_eq!(settings.host, "127.0.0.1");
+        assert_eq!(settings.port, 5173);
+        assert!(settings.generated_token);
+        let token = settings.token.expect("generated token");
+        assert_eq!(token.len(), 64);
+
+        let again =
+            resolve_settings(&web_args(&[]), &WebConfig::default(), None, &WebTokenStore).unwrap();
+        assert_ne!(again.token.as_deref(), Some(token.as_str()));
+    }
+
+    #[test]
+    fn resolve_settings_token_precedence() {
+        let config = WebConfig {
+            token: Some("keychain:web-token".to_string()),
+            ..WebConfig::default()
+        };
+        let env_token = || Some("from-env".to_string());
+
+        let flag = resolve_settings(
+            &web_args(&["--token", "from-flag"]),
+            &config,
+            env_token(),
+            &WebTokenStore,
+        )
+        .unwrap();
+        assert_eq!(flag.token.as_deref(), Some("from-flag"));
+        assert!(!flag.generated_token);
+
+        let env = resolve_settings(&web_args(&[]), &config, env_token(), &WebTokenStore).unwrap();
+        assert_eq!(env.token.as_deref(), Some("from-env"));
+
+        let keychain = resolve_settings(&web_args(&[]), &config, None, &WebTokenStore).unwrap();
+        assert_eq!(keychain.token.as_deref(), Some("from-keychain"));
+
+        let missing = WebConfig {
+            token: Some("keychain:other".to_string()),
+            ..WebConfig::default()
+        };
+        let err = resolve_settings(&web_args(&[]), &missing, None, &WebTokenStore).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("web.token: keychain entry 'other'")
+        );
+    }
+
+    #[test]
+    fn resolve_settings_no_auth_skips_token() {
+        let settings = resolve_settings(
+            &web_args(&["--no-auth"]),
+            &WebConfig::default(),
+            Some("from-env".to_string()),
+            &WebTokenStore,
+        )
+        .unwrap();
+        assert_eq!(settings.auth, WebAuthConfig::NONE);
+        assert_eq!(settings.token, None);
+
+        assert!(WebCli::try_parse_from(["ralph-web", "--no-auth", "--token", "x"]).is_err());
+    }
+
+    #[test]
+    fn resolve_settings_requires_opt_in_for_public_bind() {
+        let err = resolve_settings(
+            &web_args(&["--bind", "0.0.0.0:8080"]),
+            &WebConfig::default(),
+            None,
+            &WebTokenStore,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("--allow-public"));
+
+        let config = WebConfig {
+            bind: "0.0.0.0".to_string(),
+            ..WebConfig::default()
+        };
+        assert!(resolve_settings(&web_args(&[]), &config, None, &WebTokenStore).is_err());
+
+        let flag = resolve_settings(
+            &web_args(&["--bind", "0.0.0.0:8080", "--allow-public"]),
+            &WebConfig::default(),
+            None,
+            &WebTokenStore,
+        )
+        .unwrap();
+        assert_eq!((flag.host.as_str(), flag.port), ("0.0.0.0", 8080));
+
+        let config = WebConfig {
+            allow_public: true,
+            ..config
+        };
+        let configured = resolve_settings(&web_args(&[]), &config, None, &WebTokenStore).unwrap();
+        assert_eq!(configured.host, "0.0.0.0");
+    }
+
     #[cfg(unix)]
     #[test]
     fn check_node_accepts_supported_version() {
@@ -905,11 +1272,15 @@ exit 1",
         let args = WebArgs {
             backend_port: 3000,
             frontend_port: 5173,
+            bind: None,
+            allow_public: false,
+            token: None,
+            no_auth: false,
             workspace: Some(missing),
             no_open: true,
         };
 
-        let err = execute(args).await.expect_err("invalid workspace");
+        let err = execute(&[], args).await.expect_err("invalid workspace");
         assert!(err.to_string().contains("Invalid workspace path"));
     }
 }
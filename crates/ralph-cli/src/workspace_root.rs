@@ -0,0 +1,181 @@
+//! Workspace root resolution shared by every command.
+//!
+//! The root is, in order of precedence:
+//! 1. the global `--workspace <PATH>` flag
+//! 2. the `RALPH_WORKSPACE` environment variable
+//! 3. the nearest directory containing `ralph.yml`, searching up from the
+//!    current directory but not past the enclosing git root
+//! 4. the current directory
+//!
+//! `main` resolves the root once, pins it, and changes into it, so relative
+//! paths in config (scratchpad, specs, `.ralph/`) resolve against it.
+
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that pins the workspace root.
+pub(crate) const WORKSPACE_ENV: &str = "RALPH_WORKSPACE";
+
+/// Which rule determined the workspace root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WorkspaceRule {
+    Flag,
+    Env,
+    RalphYml,
+    CurrentDir,
+}
+
+impl fmt::Display for WorkspaceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Flag => "--workspace flag",
+            Self::Env => "RALPH_WORKSPACE",
+            Self::RalphYml => "nearest ralph.yml",
+            Self::CurrentDir => "current directory",
+        })
+    }
+}
+
+/// A resolved workspace root and the rule that chose it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WorkspaceRoot {
+    pub path: PathBuf,
+    pub rule: WorkspaceRule,
+}
+
+static PINNED: OnceLock<WorkspaceRoot> = OnceLock::new();
+static INVOCATION_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Resolves the workspace root from an explicit flag, the `RALPH_WORKSPACE`
+/// value, and the current directory.
+pub(crate) fn resolve_workspace_root(
+    flag: Option<&Path>,
+    env: Option<OsString>,
+    cwd: &Path,
+) -> Result<WorkspaceRoot> {
+    let explicit = flag
+        .map(|path| (path.to_path_buf(), WorkspaceRule::Flag))
+        .or_else(|| {
+            env.filter(|value| !value.is_empty())
+                .map(|value| (PathBuf::from(value), WorkspaceRule::Env))
+        });
+    if let Some((path, rule)) = explicit {
+        let path = cwd.join(path);
+        let path = path
+            .canonicalize()
+            .ok()
+            .filter(|path| path.is_dir())
+            .with_context(|| format!("Invalid workspace path: {} (from {rule})", path.display()))?;
+        return Ok(WorkspaceRoot { path, rule });
+    }
+
+    for dir in cwd.ancestors() {
+        if dir.join("ralph.yml").is_file() {
+            return Ok(WorkspaceRoot {
+                path: dir.to_path_buf(),
+                rule: WorkspaceRule::RalphYml,
+            });
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    Ok(WorkspaceRoot {
+        path: cwd.to_path_buf(),
+        rule: WorkspaceRule::CurrentDir,
+    })
+}
+
+/// Pins the root for the rest of the process, remembering the directory
+/// ralph was started in. Later calls are ignored.
+pub(crate) fn pin(root: WorkspaceRoot, invocation_dir: PathBuf) {
+    let _ = PINNED.set(root);
+    let _ = INVOCATION_DIR.set(invocation_dir);
+}
+
+/// The directory ralph was started in, before `main` changed into the root.
+pub(crate) fn invocation_dir() -> Option<PathBuf> {
+    INVOCATION_DIR
+        .get()
+        .cloned()
+        .or_else(|| std::env::current_dir().ok())
+}
+
+/// The workspace root every command should use.
+///
+/// Returns the root pinned by `main`, or resolves one from `RALPH_WORKSPACE`
+/// and the current directory when nothing was pinned (e.g. in tests).
+pub(crate) fn current() -> Result<WorkspaceRoot> {
+    if let Some(root) = PINNED.get() {
+        return Ok(root.clone());
+    }
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    resolve_workspace_root(None, std::env::var_os(WORKSPACE_ENV), &cwd)
+}
+
+/// Like [`current`], falling back to `.` if the root can't be determined.
+pub(crate) fn current_path() -> PathBuf {
+    current().map_or_else(|_| PathBuf::from("."), |root| root.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_flag_beats_env_and_must_exist() {
+        let temp = TempDir::new().unwrap();
+        let flag_dir = temp.path().join("flag");
+        let env_dir = temp.path().join("env");
+        std::fs::create_dir_all(&flag_dir).unwrap();
+        std::fs::create_dir_all(&env_dir).unwrap();
+
+        let root = resolve_workspace_root(
+            Some(Path::new("flag")),
+            Some(env_dir.clone().into_os_string()),
+            temp.path(),
+        )
+        .unwrap();
+        assert_eq!(root.path, flag_dir.canonicalize().unwrap());
+        assert_eq!(root.rule, WorkspaceRule::Flag);
+
+        let root =
+            resolve_workspace_root(None, Some(env_dir.clone().into_os_string()), temp.path())
+                .unwrap();
+        assert_eq!(root.path, env_dir.canonicalize().unwrap());
+        assert_eq!(root.rule, WorkspaceRule::Env);
+
+        let err = resolve_workspace_root(Some(Path::new("missing")), None, temp.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid workspace path"), "{err}");
+        assert!(err.contains("--workspace flag"), "{err}");
+    }
+
+    #[test]
+    fn test_nearest_ralph_yml_within_git_root() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("monorepo");
+        let project = repo.join("teams/web");
+        let nested = project.join("src/components");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(project.join("ralph.yml"), "").unwrap();
+
+        let root = resolve_workspace_root(None, None, &nested).unwrap();
+        assert_eq!(root.path, project);
+        assert_eq!(root.rule, WorkspaceRule::RalphYml);
+
+        // A ralph.yml above the git root is not picked up
+        std::fs::remove_file(project.join("ralph.yml")).unwrap();
+        std::fs::write(temp.path().join("ralph.yml"), "").unwrap();
+        let root = resolve_workspace_root(None, Some(OsString::new()), &nested).unwrap();
+        assert_eq!(root.path, nested);
+        assert_eq!(root.rule, WorkspaceRule::CurrentDir);
+    }
+}
@@ -314,3 +314,91 @@ core:
 
     Ok(())
 }
+
+fn git(temp_path: &std::path::Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(temp_path)
+        .output()?
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+#[test]
+fn test_clean_all_loops_removes_worktrees_and_diagnostics() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+
+    git(temp_path, &["init"])?;
+    git(temp_path, &["config", "user.email", "test@example.com"])?;
+    git(temp_path, &["config", "user.name", "Test User"])?;
+    fs::write(temp_path.join("README.md"), "# Test Repo")?;
+    git(temp_path, &["add", "."])?;
+    git(temp_path, &["commit", "-m", "Initial commit"])?;
+    git(
+        temp_path,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "ralph/old-loop",
+            ".worktrees/old-loop",
+        ],
+    )?;
+    let worktree = temp_path.join(".worktrees/old-loop");
+    let diagnostics = temp_path.join(".ralph/diagnostics/session");
+    fs::create_dir_all(&diagnostics)?;
+
+    // Dry run lists the worktree but leaves it in place
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args(["clean", "--all-loops", "--dry-run"])
+        .current_dir(temp_path)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Worktrees (1):"), "stdout: {}", stdout);
+    assert!(stdout.contains("old-loop"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Would clean: 1 worktrees"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(worktree.exists());
+    assert!(diagnostics.exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args(["clean", "--deep"])
+        .current_dir(temp_path)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Cleaned: 1 worktrees, 0 registry entries"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(!worktree.exists());
+    assert!(!temp_path.join(".ralph/diagnostics").exists());
+
+    let branches = Command::new("git")
+        .args(["branch", "--list", "ralph/*"])
+        .current_dir(temp_path)
+        .output()?;
+    assert!(String::from_utf8_lossy(&branches.stdout).trim().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_force_requires_all_loops() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args(["clean", "--force"])
+        .current_dir(temp_dir.path())
+        .output()?;
+
+    assert!(!output.status.success());
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Integration tests for backends declared in `adapters.custom_backends`,
+//! using fake agent scripts placed on PATH.
+
+#[cfg(unix)]
+mod custom_backends {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn write_executable(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        let script = format!("#!/bin/sh\n{}\n", body);
+        fs::write(&path, script).expect("write script");
+        let mut perms = fs::metadata(&path).expect("metadata").permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&path, perms).expect("chmod");
+        path
+    }
+
+    fn path_with(bin_dir: &Path) -> String {
+        let original_path = std::env::var("PATH").unwrap_or_default();
+        format!("{}:{}", bin_dir.display(), original_path)
+    }
+
+    #[test]
+    fn test_auto_detect_uses_custom_backend_probe() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let workspace = temp_dir.path();
+        let bin_dir = workspace.join("bin");
+        fs::create_dir_all(&bin_dir).expect("bin dir");
+
+        // Only answers the configured probe, not `--version`
+        write_executable(
+            &bin_dir,
+            "fake-agent",
+            "if [ \"$1\" = \"--healthcheck\" ]; then exit 0; fi\n\
+if [ \"$1\" = \"--version\" ]; then exit 1; fi\n\
+printf '%s\\n' \"$@\" > agent-args.log\n\
+cat > agent-stdin.log",
+        );
+
+        fs::write(
+            workspace.join("ralph.yml"),
+            r#"
+agent: auto
+agent_priority: ["missing-agent", "fake-agent"]
+
+adapters:
+  custom_backends:
+    missing-agent:
+      command: "definitely-not-installed-agent"
+    fake-agent:
+      command: "fake-agent"
+      prompt_mode: stdin
+      args: ["--headless"]
+      probe: ["fake-agent", "--healthcheck"]
+
+event_loop:
+  prompt_file: "PROMPT.md"
+  completion_promise: "LOOP_COMPLETE"
+  max_iterations: 1
+  max_runtime_seconds: 10
+
+features:
+  preflight:
+    enabled: false
+"#,
+        )
+        .expect("write config");
+        fs::write(workspace.join("PROMPT.md"), "Say hello from the fake agent").expect("prompt");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+            .args(["run", "--no-tui", "--config"])
+            .arg(workspace.join("ralph.yml"))
+            .env("PATH", path_with(&bin_dir))
+            .current_dir(workspace)
+            .output()
+            .expect("run ralph");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let args = fs::read_to_string(workspace.join("agent-args.log"))
+            .unwrap_or_else(|_| panic!("fake agent was not run; stderr: {stderr}"));
+        assert_eq!(args.trim(), "--headless");
+        let stdin = fs::read_to_string(workspace.join("agent-stdin.log")).expect("stdin log");
+        assert!(
+            stdin.contains("Say hello from the fake agent"),
+            "stdin: {stdin}"
+        );
+    }
+
+    #[test]
+    fn test_init_backend_generates_custom_backend_config() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let workspace = temp_dir.path();
+        let bin_dir = workspace.join("bin");
+        fs::create_dir_all(&bin_dir).expect("bin dir");
+        write_executable(&bin_dir, "fake-agent", "exit 0");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+            .args(["init", "--backend", "fake-agent"])
+            .env("PATH", path_with(&bin_dir))
+            .current_dir(workspace)
+            .output()
+            .expect("run ralph init");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let content = fs::read_to_string(workspace.join("ralph.yml")).expect("read ralph.yml");
+        assert!(content.contains("backend: \"fake-agent\""), "{content}");
+        assert!(content.contains("custom_backends:"), "{content}");
+        assert!(content.contains("command: \"fake-agent\""), "{content}");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+            .args(["init", "--force", "--backend", "not-on-path-agent"])
+            .env("PATH", path_with(&bin_dir))
+            .current_dir(workspace)
+            .output()
+            .expect("run ralph init");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("Unknown backend 'not-on-path-agent'")
+        );
+    }
+}
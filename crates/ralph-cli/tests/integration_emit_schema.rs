@@ -0,0 +1,95 @@
+//! Integration tests for `event_schemas` validation in `ralph emit`.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn write_config(dir: &Path, mismatch: &str) -> Result<()> {
+    let config = format!(
+        r"
+event_loop:
+  schema_mismatch: {mismatch}
+
+event_schemas:
+  build.done:
+    type: object
+    required: [summary, result]
+    properties:
+      summary: {{ type: string }}
+      result: {{ type: object }}
+"
+    );
+    fs::write(dir.join("ralph.yml"), config)?;
+    Ok(())
+}
+
+fn emit(dir: &Path, payload: &str) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args([
+            "emit",
+            "build.done",
+            payload,
+            "--json",
+            "--file",
+            "events.jsonl",
+        ])
+        .current_dir(dir)
+        .output()?)
+}
+
+#[test]
+fn test_emit_accepts_conforming_payload() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    write_config(temp_dir.path(), "reject")?;
+
+    let output = emit(
+        temp_dir.path(),
+        r#"{"summary":"all green","result":{"tests":"pass"}}"#,
+    )?;
+
+    assert!(
+        output.status.success(),
+        "ralph emit should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let events = fs::read_to_string(temp_dir.path().join("events.jsonl"))?;
+    assert!(events.contains("all green"));
+    Ok(())
+}
+
+#[test]
+fn test_emit_rejects_non_conforming_payload() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    write_config(temp_dir.path(), "reject")?;
+
+    let output = emit(
+        temp_dir.path(),
+        r#"{"summary":"all green","result":"{\"tests\":\"pass\"}"}"#,
+    )?;
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not match its schema"), "{stderr}");
+    assert!(stderr.contains("/result"), "{stderr}");
+    assert!(!temp_dir.path().join("events.jsonl").exists());
+    Ok(())
+}
+
+#[test]
+fn test_emit_warns_on_mismatch_when_configured() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    write_config(temp_dir.path(), "warn")?;
+
+    let output = emit(temp_dir.path(), r#"{"summary":"all green"}"#)?;
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Warning: payload for 'build.done'"),
+        "{stderr}"
+    );
+    assert!(temp_dir.path().join("events.jsonl").exists());
+    Ok(())
+}
@@ -323,6 +323,47 @@ fn test_ralph_emit_writes_to_marker_specified_file() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_concurrent_ralph_emit_processes_write_whole_lines() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+    let writers = 6;
+    let per_writer = 10;
+    // Larger than PIPE_BUF, so O_APPEND alone wouldn't keep lines whole
+    let payload = "y".repeat(64 * 1024);
+
+    let handles: Vec<_> = (0..writers)
+        .map(|w| {
+            let dir = temp_path.to_path_buf();
+            let payload = payload.clone();
+            thread::spawn(move || {
+                for i in 0..per_writer {
+                    let status = Command::new(ralph_bin())
+                        .args(["emit", &format!("writer.{w}"), &format!("{i}:{payload}")])
+                        .current_dir(&dir)
+                        .status()
+                        .expect("spawn ralph emit");
+                    assert!(status.success());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("writer thread panicked");
+    }
+
+    let content = fs::read_to_string(temp_path.join(".ralph/events.jsonl"))?;
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), writers * per_writer);
+    for line in lines {
+        let event: serde_json::Value = serde_json::from_str(line)?;
+        let (_, body) = event["payload"].as_str().unwrap().split_once(':').unwrap();
+        assert_eq!(body.len(), payload.len());
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_ralph_emit_fallback_without_marker() -> Result<()> {
     let temp_dir = TempDir::new()?;
@@ -7,6 +7,7 @@
 //! 2. ralph loops UX with summary header and age column
 //! 3. --exclusive flag for merge-ralph spawns
 //! 4. Merge commit conventional format
+//! 5. --strategy rebase|squash landing and conflict handling
 
 use anyhow::Result;
 use std::fs;
@@ -478,3 +479,240 @@ fn test_merge_uses_no_ff_flag() -> Result<()> {
 
     Ok(())
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// 5. --strategy rebase|squash Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Run git in `dir` and return trimmed stdout, asserting success.
+fn git(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to execute git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Queue a loop whose worktree commits `readme` to README.md; main then
+/// commits `main_readme` if given, setting up a conflict.
+fn setup_queued_worktree(
+    temp_path: &std::path::Path,
+    loop_id: &str,
+    readme: &str,
+    main_readme: Option<&str>,
+) -> Result<std::path::PathBuf> {
+    git(temp_path, &["branch", "-M", "main"]);
+    let worktree = temp_path.join(".worktrees").join(loop_id);
+    git(
+        temp_path,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            &format!("ralph/{}", loop_id),
+            worktree.to_str().unwrap(),
+        ],
+    );
+
+    fs::write(worktree.join("README.md"), readme)?;
+    git(&worktree, &["commit", "-q", "-am", "Loop work"]);
+    if let Some(main_readme) = main_readme {
+        fs::write(temp_path.join("README.md"), main_readme)?;
+        git(temp_path, &["commit", "-q", "-am", "Main work"]);
+    }
+
+    ralph_core::MergeQueue::new(temp_path).enqueue(loop_id, "test prompt")?;
+    Ok(worktree)
+}
+
+#[test]
+fn test_merge_rebase_conflict_preserves_worktree() -> Result<()> {
+    let temp_dir = setup_workspace()?;
+    let temp_path = temp_dir.path();
+    let worktree = setup_queued_worktree(temp_path, "ralph-rebase-001", "# Loop", Some("# Main"))?;
+    let loop_head = git(&worktree, &["rev-parse", "HEAD"]);
+    let main_head = git(temp_path, &["rev-parse", "HEAD"]);
+
+    let output = ralph_loops(
+        temp_path,
+        &["merge", "ralph-rebase-001", "--strategy", "rebase"],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Conflicted files (1):"), "stderr: {stderr}");
+    assert!(stderr.contains("  README.md"), "stderr: {stderr}");
+    assert!(stderr.contains("git rebase main"), "stderr: {stderr}");
+
+    // Worktree, branch and main are untouched
+    assert_eq!(fs::read_to_string(worktree.join("README.md"))?, "# Loop");
+    assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), loop_head);
+    assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+    assert_eq!(git(temp_path, &["rev-parse", "HEAD"]), main_head);
+
+    let entry = ralph_core::MergeQueue::new(temp_path)
+        .get_entry("ralph-rebase-001")?
+        .expect("Entry should exist");
+    assert_eq!(entry.state, ralph_core::MergeState::NeedsReview);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_squash_conflict_preserves_worktree() -> Result<()> {
+    let temp_dir = setup_workspace()?;
+    let temp_path = temp_dir.path();
+    let worktree = setup_queued_worktree(temp_path, "ralph-squash-001", "# Loop", Some("# Main"))?;
+    let main_head = git(temp_path, &["rev-parse", "HEAD"]);
+
+    let output = ralph_loops(
+        temp_path,
+        &["merge", "ralph-squash-001", "--strategy", "squash"],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("  README.md"), "stderr: {stderr}");
+    assert!(worktree.join("README.md").exists());
+    assert_eq!(fs::read_to_string(temp_path.join("README.md"))?, "# Main");
+    assert_eq!(git(temp_path, &["rev-parse", "HEAD"]), main_head);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_squash_lands_single_commit_and_removes_worktree() -> Result<()> {
+    let temp_dir = setup_workspace()?;
+    let temp_path = temp_dir.path();
+    let worktree = setup_queued_worktree(temp_path, "ralph-squash-002", "# Loop", None)?;
+
+    let stdout = ralph_loops_ok(
+        temp_path,
+        &["merge", "ralph-squash-002", "--strategy", "squash"],
+    );
+
+    assert!(stdout.contains("with squash"), "stdout: {stdout}");
+    assert_eq!(fs::read_to_string(temp_path.join("README.md"))?, "# Loop");
+    assert_eq!(
+        git(temp_path, &["log", "-1", "--format=%s"]),
+        "merge(ralph): Loop work (loop ralph-squash-002)"
+    );
+    assert_eq!(git(temp_path, &["rev-list", "--count", "HEAD"]), "2");
+    assert!(!worktree.exists());
+
+    let entry = ralph_core::MergeQueue::new(temp_path)
+        .get_entry("ralph-squash-002")?
+        .expect("Entry should exist");
+    assert_eq!(entry.state, ralph_core::MergeState::Merged);
+
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// 6. Garbage Collection
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Write a loop that was queued and reached `final_event` `days_ago` days ago.
+fn write_finished_loop(
+    temp_path: &std::path::Path,
+    loop_id: &str,
+    final_event: &str,
+    days_ago: i64,
+) -> Result<()> {
+    let ts = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+    write_merge_queue_entry(
+        temp_path,
+        &format!(
+            r#"{{"ts":"{ts}","loop_id":"{loop_id}","event":{{"type":"queued","prompt":"p"}}}}"#
+        ),
+    )?;
+    write_merge_queue_entry(
+        temp_path,
+        &format!(r#"{{"ts":"{ts}","loop_id":"{loop_id}","event":{final_event}}}"#),
+    )
+}
+
+#[test]
+fn test_loops_gc_removes_only_finished_loops() -> Result<()> {
+    let temp_dir = setup_workspace()?;
+    let temp_path = temp_dir.path();
+
+    write_finished_loop(
+        temp_path,
+        "old-merged",
+        r#"{"type":"merged","commit":"abc"}"#,
+        30,
+    )?;
+    write_finished_loop(temp_path, "old-discarded", r#"{"type":"discarded"}"#, 20)?;
+    write_finished_loop(
+        temp_path,
+        "recent-merged",
+        r#"{"type":"merged","commit":"def"}"#,
+        1,
+    )?;
+    // Finished in the queue, but its loop is still registered and running
+    write_finished_loop(
+        temp_path,
+        "still-running",
+        r#"{"type":"merged","commit":"123"}"#,
+        40,
+    )?;
+    ralph_core::LoopRegistry::new(temp_path).register(ralph_core::LoopEntry::with_id(
+        "still-running",
+        "p",
+        None::<String>,
+        temp_path.display().to_string(),
+    ))?;
+    let queue = ralph_core::MergeQueue::new(temp_path);
+    queue.enqueue("active-queued", "p")?;
+    queue.enqueue("active-review", "p")?;
+    queue.mark_merging("active-review", 1)?;
+    queue.mark_needs_review("active-review", "conflicts")?;
+
+    // Preview by default: nothing removed
+    let stdout = ralph_loops_ok(temp_path, &["gc", "--older-than", "7d"]);
+    assert!(
+        stdout.contains("Would remove 2 finished loop(s)"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("old-merged"), "{stdout}");
+    assert!(stdout.contains("old-discarded"), "{stdout}");
+    assert!(!stdout.contains("recent-merged"), "{stdout}");
+    assert!(!stdout.contains("still-running"), "{stdout}");
+    assert_eq!(queue.list()?.len(), 6);
+
+    // --keep-last skips the most recently finished candidates
+    let stdout = ralph_loops_ok(temp_path, &["gc", "--keep-last", "2"]);
+    assert!(
+        stdout.contains("Would remove 1 finished loop(s)"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("old-merged"), "{stdout}");
+
+    let stdout = ralph_loops_ok(temp_path, &["gc", "--yes"]);
+    assert!(stdout.contains("Removed 3 finished loop(s)"), "{stdout}");
+    let mut remaining: Vec<_> = queue.list()?.into_iter().map(|e| e.loop_id).collect();
+    remaining.sort();
+    assert_eq!(
+        remaining,
+        ["active-queued", "active-review", "still-running"]
+    );
+    assert!(
+        ralph_core::LoopRegistry::new(temp_path)
+            .get("still-running")?
+            .is_some()
+    );
+
+    let stdout = ralph_loops_ok(temp_path, &["gc", "--yes"]);
+    assert!(stdout.contains("No finished loops to remove"), "{stdout}");
+
+    Ok(())
+}
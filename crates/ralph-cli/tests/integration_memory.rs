@@ -220,6 +220,228 @@ fn test_memory_add_json_format() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_memory_add_refuses_near_duplicate() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+
+    let id = ralph_memory_ok(
+        temp_path,
+        &[
+            "add",
+            "Use barrel exports for every module",
+            "--format",
+            "quiet",
+        ],
+    );
+
+    let output = ralph_memory(temp_path, &["add", "use barrel exports for every module!"]);
+    assert!(!output.status.success(), "Near-duplicate should be refused");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(id.trim()),
+        "Should point at existing id: {}",
+        stderr
+    );
+
+    // A distinct memory is still accepted
+    ralph_memory_ok(temp_path, &["add", "Integration tests live under tests/"]);
+
+    let stdout = ralph_memory_ok(temp_path, &["list", "--format", "quiet"]);
+    assert_eq!(stdout.lines().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_add_merge_into_duplicate() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+
+    let id = ralph_memory_ok(
+        temp_path,
+        &[
+            "add",
+            "Use barrel exports for every module",
+            "--tags",
+            "imports",
+            "--format",
+            "quiet",
+        ],
+    );
+
+    let stdout = ralph_memory_ok(
+        temp_path,
+        &[
+            "add",
+            "Use barrel exports for every single module",
+            "--tags",
+            "structure",
+            "--merge",
+            "--format",
+            "json",
+        ],
+    );
+
+    let merged: serde_json::Value = serde_json::from_str(&stdout)?;
+    assert_eq!(merged["id"], id.trim());
+    assert_eq!(
+        merged["content"],
+        "Use barrel exports for every single module"
+    );
+    assert_eq!(merged["tags"], serde_json::json!(["imports", "structure"]));
+
+    let stdout = ralph_memory_ok(temp_path, &["list", "--format", "quiet"]);
+    assert_eq!(stdout.lines().count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_export_import_round_trip() -> Result<()> {
+    let source = TempDir::new()?;
+    let target = TempDir::new()?;
+
+    ralph_memory_ok(
+        source.path(),
+        &["add", "Use barrel exports", "--tags", "imports"],
+    );
+    ralph_memory_ok(source.path(), &["add", "Chose Postgres", "-t", "decision"]);
+
+    let exported = ralph_memory_ok(source.path(), &["export", "--type", "pattern"]);
+    let parsed: serde_json::Value = serde_json::from_str(&exported)?;
+    assert_eq!(parsed["version"], 1);
+    let parsed = &parsed["memories"];
+    assert_eq!(parsed.as_array().unwrap().len(), 1);
+    assert_eq!(parsed[0]["type"], "pattern");
+    let source_id = parsed[0]["id"].as_str().unwrap().to_string();
+
+    let export_file = target.path().join("mem.json");
+    fs::write(&export_file, &exported)?;
+    let export_arg = export_file.to_str().unwrap();
+
+    let stdout = ralph_memory_ok(target.path(), &["import", export_arg, "--format", "json"]);
+    let result: serde_json::Value = serde_json::from_str(&stdout)?;
+    let imported = &result["imported"][0];
+    assert_ne!(
+        imported["id"],
+        source_id.as_str(),
+        "IDs should be regenerated"
+    );
+    assert_eq!(imported["content"], "Use barrel exports");
+    assert_eq!(imported["memory_type"], "pattern");
+    assert_eq!(imported["tags"], serde_json::json!(["imports"]));
+    assert_eq!(imported["created"], parsed[0]["created"]);
+    assert_eq!(
+        imported["provenance"],
+        format!("imported from {}", source_id).as_str()
+    );
+
+    // Importing again with --dedup skips the existing memory
+    let stdout = ralph_memory_ok(
+        target.path(),
+        &["import", export_arg, "--dedup", "--format", "json"],
+    );
+    let result: serde_json::Value = serde_json::from_str(&stdout)?;
+    assert!(result["imported"].as_array().unwrap().is_empty());
+    assert_eq!(result["skipped"][0]["duplicate_of"], imported["id"]);
+
+    let stdout = ralph_memory_ok(target.path(), &["list", "--format", "quiet"]);
+    assert_eq!(stdout.lines().count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_export_import_is_lossless() -> Result<()> {
+    let source = TempDir::new()?;
+    let target = TempDir::new()?;
+
+    ralph_memory_ok(
+        source.path(),
+        &[
+            "add",
+            "Errors use thiserror\n\nNever anyhow in libraries",
+            "--tags",
+            "errors,style",
+        ],
+    );
+    ralph_memory_ok(source.path(), &["add", "Chose Postgres", "-t", "decision"]);
+
+    let export_file = source.path().join("memories.json");
+    let export_arg = export_file.to_str().unwrap();
+    ralph_memory_ok(source.path(), &["export", "--output", export_arg]);
+
+    // A dry run reports without writing
+    let stdout = ralph_memory_ok(target.path(), &["import", export_arg, "--dry-run"]);
+    assert!(
+        stdout.contains("Would add 2 memories") && stdout.contains("skipped 0 duplicates"),
+        "stdout: {stdout}"
+    );
+    assert!(!target.path().join(".ralph/agent/memories.md").exists());
+
+    let stdout = ralph_memory_ok(target.path(), &["import", export_arg, "--merge"]);
+    assert!(stdout.contains("Added 2 memories"), "stdout: {stdout}");
+
+    // Exporting the target gives back the same memories under new IDs
+    let strip = |json: &str| -> Result<Vec<serde_json::Value>> {
+        let export: serde_json::Value = serde_json::from_str(json)?;
+        Ok(export["memories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| serde_json::json!([m["type"], m["content"], m["tags"], m["created"]]))
+            .collect())
+    };
+    let reexported = ralph_memory_ok(target.path(), &["export"]);
+    assert_eq!(
+        strip(&reexported)?,
+        strip(&fs::read_to_string(&export_file)?)?
+    );
+
+    // Importing again skips everything by content
+    let stdout = ralph_memory_ok(target.path(), &["import", export_arg]);
+    assert!(
+        stdout.contains("Added 0 memories") && stdout.contains("skipped 2 duplicates"),
+        "stdout: {stdout}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_import_replace_and_validation() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+    ralph_memory_ok(temp_path, &["add", "Old memory"]);
+
+    let export_file = temp_path.join("mem.json");
+    fs::write(
+        &export_file,
+        r#"{"version":1,"memories":[{"id":"mem-1-abcd","type":"fix","content":"New memory","tags":[],"created":"2025-01-20"}]}"#,
+    )?;
+    let export_arg = export_file.to_str().unwrap();
+
+    let stdout = ralph_memory_ok(temp_path, &["import", export_arg, "--replace"]);
+    assert!(stdout.contains("replacing 1 existing"), "stdout: {stdout}");
+    let stdout = ralph_memory_ok(temp_path, &["list", "--format", "json"]);
+    assert!(stdout.contains("New memory") && !stdout.contains("Old memory"));
+
+    fs::write(
+        &export_file,
+        r#"{"version":1,"memories":[{"id":"mem-1-abcd","type":"fix","content":"","created":"2025-01-20"}]}"#,
+    )?;
+    let output = ralph_memory(temp_path, &["import", export_arg]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("memories[0]: content is empty"),
+        "stderr: {stderr}"
+    );
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // List Command Tests
 // ─────────────────────────────────────────────────────────────────────────────
@@ -551,6 +773,64 @@ fn test_memory_search_json_format() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_memory_search_ranks_tag_matches_first() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+
+    ralph_memory_ok(temp_path, &["add", "restart docker when the daemon hangs"]);
+    ralph_memory_ok(
+        temp_path,
+        &["add", "clear the build cache first", "--tags", "docker"],
+    );
+    ralph_memory_ok(temp_path, &["add", "unrelated note", "--tags", "git"]);
+
+    let stdout = ralph_memory_ok(temp_path, &["search", "docker", "--format", "json"]);
+
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(&stdout)?;
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[0]["content"], "clear the build cache first");
+    assert_eq!(parsed[1]["content"], "restart docker when the daemon hangs");
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_search_respects_limit() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+
+    ralph_memory_ok(temp_path, &["add", "cargo build is slow", "-t", "fix"]);
+    ralph_memory_ok(
+        temp_path,
+        &["add", "cargo test needs --offline", "-t", "fix"],
+    );
+    ralph_memory_ok(
+        temp_path,
+        &["add", "cargo fmt before commit", "-t", "pattern"],
+    );
+
+    let stdout = ralph_memory_ok(
+        temp_path,
+        &[
+            "search", "cargo", "-t", "fix", "--limit", "1", "--format", "json",
+        ],
+    );
+
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(&stdout)?;
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0]["memory_type"], "fix");
+
+    let stdout = ralph_memory_ok(temp_path, &["search", "cargo", "--limit", "1"]);
+    assert!(
+        stdout.contains("Showing 1 of 3 matches"),
+        "Should hint at truncation: {}",
+        stdout
+    );
+
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Prime Command Tests
 // ─────────────────────────────────────────────────────────────────────────────
@@ -590,6 +870,9 @@ fn test_memory_prime_respects_budget() -> Result<()> {
                     "This is memory number {} with some longer content to fill space",
                     i
                 ),
+                // Entries differ by one word; keep them all despite the overlap
+                "--dedup-threshold",
+                "0",
             ],
         );
     }
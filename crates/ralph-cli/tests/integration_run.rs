@@ -58,3 +58,448 @@ fn test_run_continue_requires_scratchpad() {
         "stderr: {stderr}"
     );
 }
+
+#[test]
+fn test_run_dry_run_previews_prompt_with_vars() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    std::fs::write(
+        temp_path.join("ralph.yml"),
+        "cli:\n  backend: claude\nvars:\n  service: billing\n  ticket: 41\n",
+    )
+    .expect("write config");
+    std::fs::write(
+        temp_path.join("PROMPT.md"),
+        "Fix {{service}} for OPS-{{ticket}}",
+    )
+    .expect("write prompt");
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--dry-run",
+            "--skip-preflight",
+            "--no-tui",
+            "--var",
+            "ticket=42",
+        ],
+    );
+
+    assert!(
+        output.status.success(),
+        "run failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Prompt preview: Fix billing for OPS-42"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn test_run_missing_prompt_vars_fail_before_start() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--skip-preflight",
+            "--no-tui",
+            "--backend",
+            "claude",
+            "--prompt",
+            "Deploy {{service}} to {{env}}",
+        ],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unresolved placeholders: {{env}}, {{service}}"),
+        "stderr: {stderr}"
+    );
+    assert!(!temp_path.join(".ralph/loop.lock").exists());
+}
+
+/// Runs `ralph run -P <prompt_file>` and returns its stderr, asserting it
+/// failed before taking the loop lock.
+fn run_with_unusable_prompt(temp_path: &std::path::Path, prompt_file: &str) -> String {
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--skip-preflight",
+            "--no-tui",
+            "--backend",
+            "claude",
+            "-P",
+            prompt_file,
+        ],
+    );
+
+    assert!(!output.status.success());
+    assert!(!temp_path.join(".ralph/loop.lock").exists());
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_run_fails_fast_on_unusable_prompt_file() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+
+    let stderr = run_with_unusable_prompt(temp_path, "PROMTP.md");
+    assert!(stderr.contains("PROMTP.md' not found"), "stderr: {stderr}");
+    assert!(stderr.contains("working directory:"), "stderr: {stderr}");
+    assert!(stderr.contains("-p \"text\""), "stderr: {stderr}");
+
+    std::fs::write(temp_path.join("EMPTY.md"), "  \n\n").expect("write prompt");
+    let stderr = run_with_unusable_prompt(temp_path, "EMPTY.md");
+    assert!(stderr.contains("EMPTY.md' is empty"), "stderr: {stderr}");
+
+    std::fs::create_dir(temp_path.join("prompts")).expect("create dir");
+    let stderr = run_with_unusable_prompt(temp_path, "prompts");
+    assert!(
+        stderr.contains("prompts' is a directory, not a file"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn test_run_continue_warns_on_missing_prompt() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    std::fs::create_dir_all(temp_path.join(".ralph/agent")).expect("create dir");
+    std::fs::write(
+        temp_path.join(".ralph/agent/scratchpad.md"),
+        "- [ ] keep going",
+    )
+    .expect("write scratchpad");
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--continue",
+            "--dry-run",
+            "--skip-preflight",
+            "--backend",
+            "claude",
+            "-P",
+            "MISSING.md",
+        ],
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("warning:"), "stderr: {stderr}");
+    assert!(stderr.contains("MISSING.md' not found"), "stderr: {stderr}");
+}
+
+const SECRET: &str = "sk-ant-REDACTED";
+
+/// Runs a loop whose backend emits an event carrying `SECRET` and returns
+/// the session recording.
+fn record_secret_session(extra_args: &[&str]) -> String {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    let script = format!(
+        r#"printf '%s\n' '{{"topic":"research.done","payload":"key={SECRET}","ts":"2025-01-01T00:00:00Z"}}' >> "$(cat .ralph/current-events)""#
+    );
+    let config = format!(
+        "event_loop:\n  max_iterations: 2\n  max_runtime_seconds: 10\ncli:\n  backend: custom\n  command: sh\n  args: [\"-c\", {}]\n",
+        serde_json::to_string(&script).unwrap()
+    );
+    std::fs::write(temp_path.join("ralph.yml"), config).expect("write config");
+    std::fs::write(temp_path.join("PROMPT.md"), "Build it").expect("write prompt");
+
+    let mut args = vec![
+        "run",
+        "--no-tui",
+        "--skip-preflight",
+        // The scripted backend only works inside an iteration
+        "--skip-warmup",
+        "--record-session",
+        "session.jsonl",
+    ];
+    args.extend_from_slice(extra_args);
+    run_ralph(temp_path, &args);
+
+    std::fs::read_to_string(temp_path.join("session.jsonl")).expect("read recording")
+}
+
+#[test]
+fn test_record_session_redacts_secrets() {
+    let recording = record_secret_session(&[]);
+    assert!(
+        recording.contains("research.done"),
+        "recording: {recording}"
+    );
+    assert!(!recording.contains(SECRET), "recording: {recording}");
+    assert!(
+        recording.contains("key=***REDACTED***"),
+        "recording: {recording}"
+    );
+}
+
+#[test]
+fn test_record_session_no_redact_keeps_output() {
+    let recording = record_secret_session(&["--no-redact"]);
+    assert!(
+        recording.contains(&format!("key={SECRET}")),
+        "recording: {recording}"
+    );
+}
+
+#[test]
+fn test_dry_run_shows_start_event_override() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    std::fs::write(
+        temp_path.join("ralph.yml"),
+        "cli:\n  backend: claude\nhats:\n  reviewer:\n    name: Reviewer\n    description: Reviews changes\n    triggers: [\"review.request\"]\n    publishes: [\"review.done\"]\n",
+    )
+    .expect("write config");
+    std::fs::write(temp_path.join("PROMPT.md"), "Review it").expect("write prompt");
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--dry-run",
+            "--skip-preflight",
+            "--no-tui",
+            "--start-event",
+            "review.request",
+            "--start-payload",
+            "Only the auth module",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("Starting event: review.request (payload: Only the auth module)"),
+        "stdout: {stdout}"
+    );
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--dry-run",
+            "--skip-preflight",
+            "--no-tui",
+            "--start-event",
+            "build.start",
+        ],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("Valid triggers: review.request"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn test_until_event_stops_run_with_exit_zero() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    let script = format!(
+        r#""{}" emit design.approved "looks good""#,
+        env!("CARGO_BIN_EXE_ralph")
+    );
+    let config = format!(
+        "event_loop:\n  max_iterations: 5\n  max_runtime_seconds: 20\ncli:\n  backend: custom\n  command: sh\n  args: [\"-c\", {}]\n",
+        serde_json::to_string(&script).unwrap()
+    );
+    std::fs::write(temp_path.join("ralph.yml"), config).expect("write config");
+    std::fs::write(temp_path.join("PROMPT.md"), "Design it").expect("write prompt");
+
+    let output = run_ralph(
+        temp_path,
+        &[
+            "run",
+            "--no-tui",
+            "--skip-preflight",
+            "--skip-warmup",
+            "--until-event",
+            "design.approved",
+        ],
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        !files_containing(&temp_path.join(".ralph"), "until_event_reached").is_empty(),
+        "loop.terminate should record the reason"
+    );
+}
+
+const SEEDED_SECRET: &str = "seeded-4f9c2e7a1b";
+
+/// Collects every file under `dir` whose contents include `needle`.
+fn files_containing(dir: &std::path::Path, needle: &str) -> Vec<std::path::PathBuf> {
+    let mut found = Vec::new();
+    for entry in std::fs::read_dir(dir).expect("read dir").flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            found.extend(files_containing(&path, needle));
+        } else if std::fs::read(&path)
+            .is_ok_and(|bytes| bytes.windows(needle.len()).any(|w| w == needle.as_bytes()))
+        {
+            found.push(path);
+        }
+    }
+    found
+}
+
+#[test]
+fn test_security_redaction_keeps_seeded_secret_off_disk() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+    // The backend echoes the secret and emits it in an event payload, once
+    // bare and once inside a connection string matched by a named pattern
+    let script = format!(
+        r#"echo "using token $RALPH_SEEDED_TOKEN"; "{}" emit research.done "key=$RALPH_SEEDED_TOKEN db=postgres://app:$RALPH_SEEDED_TOKEN@db/app""#,
+        env!("CARGO_BIN_EXE_ralph")
+    );
+    let config = format!(
+        "event_loop:\n  max_iterations: 2\n  max_runtime_seconds: 20\ncli:\n  backend: custom\n  command: sh\n  args: [\"-c\", {}]\nsecurity:\n  redact:\n    - name: db_url\n      pattern: \"postgres://\\\\S+\"\n  secret_env: [RALPH_SEEDED_TOKEN]\n",
+        serde_json::to_string(&script).unwrap()
+    );
+    std::fs::write(temp_path.join("ralph.yml"), config).expect("write config");
+    std::fs::write(temp_path.join("PROMPT.md"), "Build it").expect("write prompt");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args([
+            "run",
+            "--no-tui",
+            "--skip-preflight",
+            "--skip-warmup",
+            "--verbose",
+            "--record-session",
+            "session.jsonl",
+        ])
+        .env("RALPH_SEEDED_TOKEN", SEEDED_SECRET)
+        .env("RALPH_DIAGNOSTICS", "1")
+        .current_dir(temp_path)
+        .output()
+        .expect("execute ralph");
+
+    let leaked = files_containing(temp_path, SEEDED_SECRET);
+    assert!(
+        leaked.is_empty(),
+        "secret written to {leaked:?}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let recording = std::fs::read_to_string(temp_path.join("session.jsonl")).expect("recording");
+    let payload = "key=[REDACTED:RALPH_SEEDED_TOKEN] db=[REDACTED:db_url]";
+    assert!(recording.contains(payload), "recording: {recording}");
+    assert!(
+        !files_containing(&temp_path.join(".ralph"), payload).is_empty(),
+        "redacted event missing from the events file"
+    );
+    assert!(
+        temp_path.join(".ralph/diagnostics").is_dir(),
+        "diagnostics were not written"
+    );
+}
+
+/// Creates a monorepo whose ralph project lives two levels below the git root.
+fn monorepo() -> (TempDir, std::path::PathBuf) {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let project = temp_dir.path().join("teams/web");
+    std::fs::create_dir_all(temp_dir.path().join(".git")).expect("create .git");
+    std::fs::create_dir_all(project.join("src")).expect("create project");
+    std::fs::write(
+        project.join("ralph.yml"),
+        "cli:\n  backend: claude\nevent_loop:\n  prompt: \"web team task\"\n",
+    )
+    .expect("write config");
+    let project = project.canonicalize().expect("canonicalize");
+    (temp_dir, project)
+}
+
+fn dry_run_stdout(command: &mut Command) -> String {
+    let output = command
+        .args(["run", "--dry-run", "--skip-preflight", "--no-tui"])
+        .env_remove("RALPH_WORKSPACE")
+        .output()
+        .expect("execute ralph");
+    assert!(
+        output.status.success(),
+        "run failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_run_resolves_workspace_from_nearest_ralph_yml() {
+    let (_temp_dir, project) = monorepo();
+
+    let stdout =
+        dry_run_stdout(Command::new(env!("CARGO_BIN_EXE_ralph")).current_dir(project.join("src")));
+    assert!(
+        stdout.contains(&format!(
+            "Workspace: {} (nearest ralph.yml)",
+            project.display()
+        )),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("web team task"), "stdout: {stdout}");
+}
+
+#[test]
+fn test_run_workspace_flag_and_env_pin_the_root() {
+    let (temp_dir, project) = monorepo();
+
+    let stdout = dry_run_stdout(
+        Command::new(env!("CARGO_BIN_EXE_ralph"))
+            .current_dir(temp_dir.path())
+            .args(["--workspace", "teams/web"]),
+    );
+    assert!(
+        stdout.contains(&format!(
+            "Workspace: {} (--workspace flag)",
+            project.display()
+        )),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("web team task"), "stdout: {stdout}");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .current_dir(temp_dir.path())
+        .env("RALPH_WORKSPACE", &project)
+        .args(["run", "--dry-run", "--skip-preflight", "--no-tui"])
+        .output()
+        .expect("execute ralph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "Workspace: {} (RALPH_WORKSPACE)",
+            project.display()
+        )),
+        "stdout: {stdout}"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .current_dir(temp_dir.path())
+        .args(["--workspace", "missing", "run", "--dry-run"])
+        .output()
+        .expect("execute ralph");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid workspace path"),
+        "stderr: {stderr}"
+    );
+}
@@ -0,0 +1,173 @@
+//! Integration tests for the `ralph runs` command, which browses the
+//! per-run records under `.ralph/runs/`.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn write_run(root: &Path, id: &str, meta: &str) -> Result<()> {
+    let dir = root.join(".ralph/runs").join(id);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("meta.json"), meta)?;
+    Ok(())
+}
+
+fn ralph_runs(root: &Path, args: &[&str]) -> Result<std::process::Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .arg("runs")
+        .args(args)
+        .arg("--color")
+        .arg("never")
+        .current_dir(root)
+        .output()?)
+}
+
+fn seed_runs(root: &Path) -> Result<()> {
+    write_run(
+        root,
+        "20250101-100000",
+        r#"{
+  "id": "20250101-100000",
+  "started": "2025-01-01T10:00:00Z",
+  "prompt": "Fix the flaky tests",
+  "backend": "claude",
+  "config_sources": ["ralph.yml"],
+  "loop_id": "primary-20250101-100000",
+  "events_file": ".ralph/events-20250101-100000.jsonl",
+  "finished": "2025-01-01T10:12:05Z",
+  "termination_reason": "completed",
+  "iterations": 7
+}"#,
+    )?;
+    write_run(
+        root,
+        "20250102-090000",
+        r#"{
+  "id": "20250102-090000",
+  "started": "2025-01-02T09:00:00Z",
+  "prompt": "Add caching layer",
+  "backend": "gemini",
+  "loop_id": "primary-20250102-090000"
+}"#,
+    )
+}
+
+#[test]
+fn test_runs_list_shows_newest_first() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    seed_runs(temp_dir.path())?;
+
+    let output = ralph_runs(temp_dir.path(), &["list"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let newer = stdout.find("20250102-090000").expect("newer run listed");
+    let older = stdout.find("20250101-100000").expect("older run listed");
+    assert!(newer < older, "stdout: {}", stdout);
+    assert!(stdout.contains("12m 5s"), "stdout: {}", stdout);
+    assert!(stdout.contains("completed"), "stdout: {}", stdout);
+    assert!(stdout.contains("unfinished"), "stdout: {}", stdout);
+
+    let output = ralph_runs(temp_dir.path(), &["list", "--limit", "1", "--json"])?;
+    assert!(output.status.success());
+    let runs: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(runs.as_array().map(Vec::len), Some(1));
+    assert_eq!(runs[0]["id"], "20250102-090000");
+    Ok(())
+}
+
+#[test]
+fn test_runs_list_empty() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    let output = ralph_runs(temp_dir.path(), &[])?;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No runs recorded yet."));
+    Ok(())
+}
+
+#[test]
+fn test_runs_show_prints_metadata_and_artifacts() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    seed_runs(temp_dir.path())?;
+
+    let output = ralph_runs(temp_dir.path(), &["show", "20250101-100000"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Run 20250101-100000"), "stdout: {}", stdout);
+    assert!(stdout.contains("Iterations:  7"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Config:      ralph.yml"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Events:      .ralph/events-20250101-100000.jsonl (missing)"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("Recording:   -"), "stdout: {}", stdout);
+
+    let output = ralph_runs(temp_dir.path(), &["show", "nope"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No run found with ID 'nope'"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_writes_run_record() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let temp_path = temp_dir.path();
+    fs::write(
+        temp_path.join("ralph.yml"),
+        r#"
+event_loop:
+  prompt_file: "PROMPT.md"
+  completion_promise: "LOOP_COMPLETE"
+  max_iterations: 1
+  max_runtime_seconds: 5
+
+cli:
+  backend: "custom"
+  command: "true"
+
+core:
+  scratchpad: ".ralph/agent/scratchpad.md"
+
+features:
+  preflight:
+    enabled: false
+"#,
+    )?;
+    fs::write(temp_path.join("PROMPT.md"), "Record this run")?;
+
+    Command::new(env!("CARGO_BIN_EXE_ralph"))
+        .args(["run", "--no-tui", "--config"])
+        .arg(temp_path.join("ralph.yml"))
+        .current_dir(temp_path)
+        .output()?;
+
+    let output = ralph_runs(temp_path, &["list", "--json"])?;
+    assert!(output.status.success());
+    let runs: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let run = &runs[0];
+    assert_eq!(run["prompt"], "Record this run");
+    assert_eq!(run["backend"], "custom");
+    assert!(run["loop_id"].as_str().unwrap().starts_with("primary-"));
+    assert!(
+        run["config_sources"][0]
+            .as_str()
+            .unwrap()
+            .ends_with("ralph.yml")
+    );
+    assert!(run["events_file"].as_str().is_some());
+    assert!(run["finished"].as_str().is_some(), "run: {}", run);
+    assert!(run["termination_reason"].as_str().is_some(), "run: {}", run);
+    Ok(())
+}
@@ -111,6 +111,57 @@ fn test_task_ready_respects_blockers() {
     assert_eq!(ready[0].title, "Blocked");
 }
 
+#[test]
+fn test_task_block_unblock_and_cycle_refusal() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+
+    let first = ralph_task_ok(temp_path, &["add", "First", "--format", "quiet"]);
+    let second = ralph_task_ok(temp_path, &["add", "Second", "--format", "quiet"]);
+    let (first, second) = (first.trim(), second.trim());
+
+    ralph_task_ok(temp_path, &["block", second, "--on", first]);
+    let stdout = ralph_task_ok(temp_path, &["ready", "--format", "quiet"]);
+    assert_eq!(stdout.trim(), first);
+
+    // first -> second would close a cycle
+    let output = ralph_task(temp_path, &["block", first, "--on", second]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Dependency cycle"), "stderr: {}", stderr);
+
+    ralph_task_ok(temp_path, &["unblock", second, "--on", first]);
+    let stdout = ralph_task_ok(temp_path, &["ready", "--format", "quiet"]);
+    assert_eq!(stdout.lines().count(), 2);
+}
+
+#[test]
+fn test_task_ready_orders_by_priority_then_creation() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+
+    let low = ralph_task_ok(
+        temp_path,
+        &["add", "Low", "--priority", "low", "--format", "quiet"],
+    );
+    ralph_task_ok(temp_path, &["add", "Normal"]);
+    ralph_task_ok(temp_path, &["add", "High", "--priority", "high"]);
+    ralph_task_ok(temp_path, &["add", "Numeric", "-p", "2"]);
+
+    let titles = |temp_path| {
+        let stdout = ralph_task_ok(temp_path, &["ready", "--format", "json"]);
+        let ready: Vec<Task> = serde_json::from_str(&stdout).expect("parse ready JSON");
+        ready.into_iter().map(|t| t.title).collect::<Vec<_>>()
+    };
+    assert_eq!(titles(temp_path), vec!["High", "Numeric", "Normal", "Low"]);
+
+    ralph_task_ok(temp_path, &["reprioritize", low.trim(), "high"]);
+    assert_eq!(titles(temp_path), vec!["Low", "High", "Numeric", "Normal"]);
+
+    let output = ralph_task(temp_path, &["reprioritize", low.trim(), "urgent"]);
+    assert!(!output.status.success());
+}
+
 #[test]
 fn test_task_close_and_fail_update_status() {
     let temp_dir = TempDir::new().expect("temp dir");
@@ -148,3 +199,49 @@ fn test_task_show_json() {
     assert_eq!(task.id, task_id);
     assert_eq!(task.title, "Show me");
 }
+
+#[test]
+fn test_task_update_status_block_on_and_tag_filter() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let temp_path = temp_dir.path();
+
+    let schema = ralph_task_ok(
+        temp_path,
+        &["add", "Schema", "--tag", "db,backend", "--format", "quiet"],
+    );
+    let schema = schema.trim();
+    let migrate = ralph_task_ok(
+        temp_path,
+        &[
+            "add", "Migrate", "--tag", "db", "--parent", schema, "--format", "quiet",
+        ],
+    );
+    let migrate = migrate.trim();
+
+    let stdout = ralph_task_ok(
+        temp_path,
+        &["update", migrate, "--block-on", schema, "--format", "json"],
+    );
+    let updated: Task = serde_json::from_str(&stdout).expect("update JSON");
+    assert_eq!(updated.blocked_by, vec![schema.to_string()]);
+    assert_eq!(updated.parent.as_deref(), Some(schema));
+    assert!(updated.updated.is_some());
+
+    let blocked = list_tasks(temp_path, &["--blocked"]);
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].id, migrate);
+
+    ralph_task_ok(temp_path, &["update", schema, "--status", "done"]);
+    let done = list_tasks(temp_path, &["--status", "done"]);
+    assert_eq!(done.len(), 1);
+    assert_eq!(done[0].status, TaskStatus::Closed);
+    assert!(list_tasks(temp_path, &["--blocked"]).is_empty());
+
+    let backend = list_tasks(temp_path, &["--all", "--tag", "backend"]);
+    assert_eq!(backend.len(), 1);
+    assert_eq!(backend[0].tags, vec!["db", "backend"]);
+
+    // update needs something to change
+    let output = ralph_task(temp_path, &["update", migrate]);
+    assert!(!output.status.success());
+}
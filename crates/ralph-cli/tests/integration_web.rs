@@ -91,4 +91,92 @@ exit 1",
         let stdout = String::from_utf8_lossy(&output.stdout);
         assert!(stdout.contains("Starting Ralph web servers"));
     }
+
+    #[test]
+    fn test_web_command_bind_and_token() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let workspace = temp_dir.path();
+
+        fs::create_dir_all(workspace.join("backend/ralph-web-server")).expect("backend dir");
+        fs::create_dir_all(workspace.join("frontend/ralph-web")).expect("frontend dir");
+        fs::create_dir_all(workspace.join("node_modules")).expect("node_modules dir");
+        fs::write(workspace.join("node_modules/.package-lock.json"), "").expect("lockfile");
+
+        let bin_dir = workspace.join("bin");
+        fs::create_dir_all(&bin_dir).expect("bin dir");
+        let log = workspace.join("npm.log");
+
+        write_executable(&bin_dir, "node", "echo v20.0.0");
+        write_executable(&bin_dir, "npx", "echo 4.21.0");
+        write_executable(
+            &bin_dir,
+            "npm",
+            &format!(
+                "if [ \"$1\" = \"--version\" ]; then echo 9.6.0; exit 0; fi\n\
+echo \"$(basename \"$PWD\") host=$HOST token=$RALPH_WEB_TOKEN args=$*\" >> {log}\n\
+echo \"Server started on http://localhost\"\n\
+echo \"Local: http://localhost\"\n\
+exit 0",
+                log = log.display()
+            ),
+        );
+
+        let original_path = std::env::var("PATH").unwrap_or_default();
+        let path = format!("{}:{}", bin_dir.display(), original_path);
+
+        let listeners: Vec<TcpListener> = (0..2)
+            .filter_map(|_| TcpListener::bind(("127.0.0.1", 0)).ok())
+            .collect();
+        let ports: Vec<u16> = listeners
+            .iter()
+            .map(|listener| listener.local_addr().expect("addr").port())
+            .collect();
+        drop(listeners);
+        let [backend_port, frontend_port] = ports[..] else {
+            return; // Skip if binding isn't allowed in this environment
+        };
+
+        let output = Command::new(env!("CARGO_BIN_EXE_ralph"))
+            .current_dir(workspace)
+            .env("PATH", path)
+            .env_remove("RALPH_WEB_TOKEN")
+            .env_remove("HOST")
+            .args([
+                "web",
+                "--workspace",
+                workspace.to_str().expect("workspace path"),
+                "--no-open",
+                "--backend-port",
+                &backend_port.to_string(),
+                "--bind",
+                &format!("127.0.0.1:{frontend_port}"),
+                "--token",
+                "s3cret",
+            ])
+            .output()
+            .expect("execute ralph web");
+
+        assert!(
+            output.status.success(),
+            "ralph web failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains(&format!("http://127.0.0.1:{frontend_port}/?token=s3cret")),
+            "stdout: {stdout}"
+        );
+
+        let log = fs::read_to_string(&log).expect("npm log");
+        assert!(
+            log.contains("ralph-web-server host=127.0.0.1 token=s3cret args=run dev"),
+            "log: {log}"
+        );
+        assert!(
+            log.contains(&format!(
+                "ralph-web host= token=s3cret args=run dev -- --port {frontend_port} --host 127.0.0.1"
+            )),
+            "log: {log}"
+        );
+    }
 }
@@ -0,0 +1,193 @@
+//! Completion verification (`event_loop.completion_check`).
+//!
+//! When a hat emits the completion promise, the configured shell command
+//! runs in the workspace before the loop accepts it. A failing command
+//! turns the completion into a `completion.rejected` event carrying the
+//! tail of the command's output.
+
+use crate::config::CompletionCheckConfig;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// Lines of command output kept for the `completion.rejected` payload.
+const OUTPUT_TAIL_LINES: usize = 40;
+
+/// Shell builtins that are never found on `PATH`.
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "cd", "command", "eval", "exec", "export", "set", "source", "true", "false",
+];
+
+/// Result of one completion check run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionCheckOutcome {
+    /// The command that ran.
+    pub command: String,
+
+    /// Exit code, or `None` if the command timed out, was killed by a
+    /// signal, or could not be started.
+    pub exit_code: Option<i32>,
+
+    /// Whether the command was killed after `timeout_secs`.
+    pub timed_out: bool,
+
+    /// Last lines of combined stdout and stderr.
+    pub output_tail: String,
+
+    /// How long the command ran.
+    pub elapsed: Duration,
+}
+
+impl CompletionCheckOutcome {
+    /// Returns true if the check accepts the completion (exit 0).
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Short description of the result, e.g. `exit 1 after 12s`.
+    pub fn status(&self) -> String {
+        let secs = self.elapsed.as_secs();
+        match self.exit_code {
+            Some(code) => format!("exit {code} after {secs}s"),
+            None if self.timed_out => format!("timed out after {secs}s"),
+            None => format!("failed after {secs}s"),
+        }
+    }
+}
+
+/// Runs the completion check command with `sh -c` in `workspace`.
+///
+/// Never fails: a command that can't be started or that times out is
+/// reported as a rejection.
+pub async fn run_completion_check(
+    check: &CompletionCheckConfig,
+    workspace: &Path,
+) -> CompletionCheckOutcome {
+    let started = Instant::now();
+    // Merge stderr into stdout so the tail keeps the order the user would see
+    let script = format!("exec 2>&1\n{}", check.command);
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(&script)
+        .current_dir(workspace)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+
+    let outcome = |exit_code, timed_out, output: &str| CompletionCheckOutcome {
+        command: check.command.clone(),
+        exit_code,
+        timed_out,
+        output_tail: output_tail(output, OUTPUT_TAIL_LINES),
+        elapsed: started.elapsed(),
+    };
+
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => return outcome(None, false, &format!("Failed to start `sh`: {e}")),
+    };
+
+    let timeout = Duration::from_secs(check.timeout_secs);
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let text = String::from_utf8_lossy(&output.stdout);
+            outcome(output.status.code(), false, &text)
+        }
+        Ok(Err(e)) => outcome(None, false, &format!("Failed to wait for command: {e}")),
+        // Dropping the future kills the child (kill_on_drop)
+        Err(_) => outcome(
+            None,
+            true,
+            &format!("Command timed out after {}s", check.timeout_secs),
+        ),
+    }
+}
+
+/// Returns the executable the command starts with, skipping leading
+/// `VAR=value` assignments. `None` for shell builtins and empty commands.
+pub fn command_executable(command: &str) -> Option<&str> {
+    let program = command
+        .split_whitespace()
+        .find(|word| !is_assignment(word))?;
+    (!SHELL_BUILTINS.contains(&program)).then_some(program)
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Last `max_lines` lines of a command's output, trailing whitespace trimmed.
+pub(crate) fn output_tail(output: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(max_lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_passing_command_accepts_completion() {
+        let temp = TempDir::new().unwrap();
+        let check = CompletionCheckConfig::new("echo ok");
+
+        let outcome = run_completion_check(&check, temp.path()).await;
+
+        assert!(outcome.passed());
+        assert_eq!(outcome.output_tail, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_failing_command_keeps_output_tail() {
+        let temp = TempDir::new().unwrap();
+        let check = CompletionCheckConfig::new("seq 1 100; echo 'test failed' >&2; exit 3");
+
+        let outcome = run_completion_check(&check, temp.path()).await;
+
+        assert!(!outcome.passed());
+        assert_eq!(outcome.exit_code, Some(3));
+        let lines: Vec<_> = outcome.output_tail.lines().collect();
+        assert_eq!(lines.len(), OUTPUT_TAIL_LINES);
+        assert_eq!(lines.last(), Some(&"test failed"));
+        assert_eq!(lines[0], "62");
+    }
+
+    #[tokio::test]
+    async fn test_command_runs_in_workspace() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("marker"), "").unwrap();
+        let check = CompletionCheckConfig::new("test -f marker");
+
+        assert!(run_completion_check(&check, temp.path()).await.passed());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_rejects_completion() {
+        let temp = TempDir::new().unwrap();
+        let mut check = CompletionCheckConfig::new("sleep 5");
+        check.timeout_secs = 0;
+
+        let outcome = run_completion_check(&check, temp.path()).await;
+
+        assert!(!outcome.passed());
+        assert!(outcome.timed_out);
+        assert!(outcome.status().starts_with("timed out"));
+    }
+
+    #[test]
+    fn test_command_executable() {
+        assert_eq!(command_executable("cargo test --all"), Some("cargo"));
+        assert_eq!(
+            command_executable("RUST_LOG=debug ./check.sh"),
+            Some("./check.sh")
+        );
+        assert_eq!(command_executable("cd crates && cargo test"), None);
+        assert_eq!(command_executable("   "), None);
+    }
+}
@@ -3,10 +3,14 @@
 //! This module supports both v1.x flat configuration format and v2.0 nested format.
 //! Users can switch from Python v1.x to Rust v2.0 with zero config changes.
 
+use crate::event_loop::TerminationReason;
+use crate::memory::MemoryEviction;
+use crate::worktree::WorktreeConfig;
 use ralph_proto::Topic;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::debug;
 
 /// Top-level configuration for Ralph Orchestrator.
@@ -34,12 +38,47 @@ pub struct RalphConfig {
     #[serde(default)]
     pub hats: HashMap<String, HatConfig>,
 
+    /// Markdown files prepended, in order, to every hat's instructions.
+    ///
+    /// Relative paths resolve against the config file's directory. Once the
+    /// config is loaded this holds the resolved paths.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<PathBuf>,
+
+    /// Concatenated contents of `includes`, read at load time.
+    ///
+    /// Kept apart from hat instructions so a hat without its own still gets
+    /// contract-derived instructions; prompt building prepends it.
+    #[serde(skip)]
+    pub include_preamble: String,
+
     /// Event metadata definitions (optional).
     /// Defines what each event topic means, enabling auto-derived instructions.
     /// If a hat uses custom events, define them here for proper behavior injection.
     #[serde(default)]
     pub events: HashMap<String, EventMetadata>,
 
+    /// Values for `{{name}}` placeholders in the prompt.
+    /// `--var name=value` on the command line overrides these.
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "deserialize_vars"
+    )]
+    pub vars: BTreeMap<String, String>,
+
+    /// JSON schemas for event payloads, keyed by topic.
+    ///
+    /// Payloads from `ralph emit` and the events file are checked against
+    /// them; `event_loop.schema_mismatch` decides what happens on a mismatch.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub event_schemas: BTreeMap<String, serde_json::Value>,
+
+    /// Shell commands run in the background when a matching event is
+    /// recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<EventHookConfig>,
+
     // ─────────────────────────────────────────────────────────────────────────
     // V1 COMPATIBILITY FIELDS (flat format)
     // These map to nested v2 fields for backwards compatibility.
@@ -133,6 +172,14 @@ pub struct RalphConfig {
     /// RObot (Ralph-Orchestrator bot) configuration for Telegram-based interaction.
     #[serde(default, rename = "RObot")]
     pub robot: RobotConfig,
+
+    /// Web dashboard (`ralph web`) configuration.
+    #[serde(default)]
+    pub web: WebConfig,
+
+    /// Secret redaction for everything Ralph writes to disk.
+    #[serde(default)]
+    pub security: SecurityConfig,
 }
 
 fn default_true() -> bool {
@@ -147,7 +194,12 @@ impl Default for RalphConfig {
             cli: CliConfig::default(),
             core: CoreConfig::default(),
             hats: HashMap::new(),
+            includes: Vec::new(),
+            include_preamble: String::new(),
             events: HashMap::new(),
+            vars: BTreeMap::new(),
+            event_schemas: BTreeMap::new(),
+            hooks: Vec::new(),
             // V1 compatibility fields
             agent: None,
             agent_priority: vec![],
@@ -178,10 +230,38 @@ impl Default for RalphConfig {
             features: FeaturesConfig::default(),
             // RObot (Ralph-Orchestrator bot)
             robot: RobotConfig::default(),
+            web: WebConfig::default(),
+            security: SecurityConfig::default(),
         }
     }
 }
 
+/// Reads prompt variables, accepting numbers and booleans as well as strings
+/// so `ticket: 42` works without quoting.
+fn deserialize_vars<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    BTreeMap::<String, serde_yaml::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_yaml::Value::String(s) => s,
+                serde_yaml::Value::Number(n) => n.to_string(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                _ => {
+                    return Err(D::Error::custom(format!(
+                        "vars.{key} must be a string, number or boolean"
+                    )));
+                }
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
 /// V1 adapter settings per backend.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AdaptersConfig {
@@ -204,6 +284,99 @@ pub struct AdaptersConfig {
     /// Amp adapter settings.
     #[serde(default)]
     pub amp: AdapterSettings,
+
+    /// Additional CLI backends defined in config, keyed by backend name.
+    ///
+    /// A custom backend can be selected with `cli.backend`, a hat's `backend`,
+    /// or auto-detection. An entry named after a built-in backend replaces it.
+    #[serde(default)]
+    pub custom_backends: BTreeMap<String, CustomBackendConfig>,
+}
+
+/// A CLI backend declared in `adapters.custom_backends`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomBackendConfig {
+    /// Executable name or path.
+    pub command: String,
+
+    /// How the prompt is passed to the command.
+    #[serde(default)]
+    pub prompt_mode: CustomPromptMode,
+
+    /// Flag preceding the prompt when `prompt_mode` is `flag` (e.g., "--message").
+    #[serde(default)]
+    pub prompt_flag: Option<String>,
+
+    /// Flag taking the path of a file holding the prompt (e.g.,
+    /// "--message-file"). Prompts over `cli.large_prompt_threshold` are
+    /// passed this way; without it they are written to stdin.
+    #[serde(default)]
+    pub prompt_file_flag: Option<String>,
+
+    /// Arguments passed before the prompt on every invocation.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// How the command's output is parsed.
+    #[serde(default)]
+    pub output_format: CustomOutputFormat,
+
+    /// Availability probe (command and arguments). Defaults to `<command> --version`.
+    #[serde(default)]
+    pub probe: Option<Vec<String>>,
+
+    /// Timeout and auto-detection settings.
+    #[serde(flatten)]
+    pub settings: AdapterSettings,
+}
+
+impl CustomBackendConfig {
+    /// Creates a backend definition that passes the prompt as a positional argument.
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            prompt_mode: CustomPromptMode::default(),
+            prompt_flag: None,
+            prompt_file_flag: None,
+            args: Vec::new(),
+            output_format: CustomOutputFormat::default(),
+            probe: None,
+            settings: AdapterSettings::default(),
+        }
+    }
+
+    /// Returns the availability probe, defaulting to `<command> --version`.
+    pub fn probe_command(&self) -> Vec<String> {
+        self.probe
+            .clone()
+            .unwrap_or_else(|| vec![self.command.clone(), "--version".to_string()])
+    }
+}
+
+/// How a custom backend receives the prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CustomPromptMode {
+    /// Last positional argument.
+    #[default]
+    Arg,
+    /// Argument following `prompt_flag`.
+    Flag,
+    /// Written to stdin.
+    Stdin,
+}
+
+/// How a custom backend's output is read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CustomOutputFormat {
+    /// Plain text; completion is detected in the raw output.
+    #[default]
+    Text,
+    /// Claude-style NDJSON stream.
+    StreamJson,
+    /// Pi-style NDJSON stream.
+    PiStreamJson,
 }
 
 /// Per-adapter settings.
@@ -238,16 +411,31 @@ impl Default for AdapterSettings {
 
 impl RalphConfig {
     /// Loads configuration from a YAML file.
+    ///
+    /// Hat `instructions_file` and `includes` paths resolve relative to the
+    /// file's directory.
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
         let path_ref = path.as_ref();
         debug!(path = %path_ref.display(), "Loading configuration from file");
         let content = std::fs::read_to_string(path_ref)?;
-        Self::parse_yaml(&content)
+        let base_dir = path_ref
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self::parse_yaml_in(&content, base_dir)
     }
 
     /// Parses configuration from a YAML string.
+    ///
+    /// Hat `instructions_file` and `includes` paths resolve relative to the
+    /// current directory.
     pub fn parse_yaml(content: &str) -> Result<Self, ConfigError> {
-        let config: Self = serde_yaml::from_str(content)?;
+        Self::parse_yaml_in(content, Path::new("."))
+    }
+
+    fn parse_yaml_in(content: &str, base_dir: &Path) -> Result<Self, ConfigError> {
+        let mut config: Self = serde_yaml::from_str(content)?;
+        config.load_instruction_files(base_dir)?;
         debug!(
             backend = %config.cli.backend,
             has_v1_fields = config.agent.is_some(),
@@ -257,6 +445,77 @@ impl RalphConfig {
         Ok(config)
     }
 
+    /// Reads each hat's `instructions_file` into its `instructions`.
+    ///
+    /// Relative paths resolve against `base_dir`. Inside a linked git
+    /// worktree they resolve against the same directory in the main
+    /// checkout, since the worktree may not carry the file. On success
+    /// `instructions_file` holds the resolved path.
+    fn load_instruction_files(&mut self, base_dir: &Path) -> Result<(), ConfigError> {
+        let base_dir = main_checkout_dir(base_dir).unwrap_or_else(|| base_dir.to_path_buf());
+        for (hat_id, hat) in &mut self.hats {
+            let Some(file) = hat.instructions_file.as_ref() else {
+                continue;
+            };
+            if !hat.instructions.is_empty() {
+                return Err(ConfigError::MutuallyExclusive {
+                    field1: format!("hats.{hat_id}.instructions"),
+                    field2: format!("hats.{hat_id}.instructions_file"),
+                });
+            }
+
+            let path = base_dir.join(file);
+            let instructions_error = |reason: String| ConfigError::InstructionsFile {
+                hat: hat_id.clone(),
+                path: path.display().to_string(),
+                reason,
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                instructions_error(if e.kind() == std::io::ErrorKind::NotFound {
+                    "does not exist".to_string()
+                } else {
+                    format!("could not be read: {e}")
+                })
+            })?;
+            if content.trim().is_empty() {
+                return Err(instructions_error("is empty".to_string()));
+            }
+
+            debug!(hat = %hat_id, path = %path.display(), "Loaded hat instructions from file");
+            hat.instructions = content;
+            hat.instructions_file = Some(path);
+        }
+
+        // Shared preamble files, prepended to each hat's instructions at prompt time
+        let mut preamble = String::new();
+        for include in &mut self.includes {
+            let path = base_dir.join(&*include);
+            let include_error = |reason: String| ConfigError::IncludeFile {
+                path: path.display().to_string(),
+                reason,
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                include_error(if e.kind() == std::io::ErrorKind::NotFound {
+                    "does not exist".to_string()
+                } else {
+                    format!("could not be read: {e}")
+                })
+            })?;
+            if content.trim().is_empty() {
+                return Err(include_error("is empty".to_string()));
+            }
+
+            debug!(path = %path.display(), "Loaded shared instructions include");
+            preamble.push_str(&content);
+            if !preamble.ends_with('\n') {
+                preamble.push('\n');
+            }
+            *include = path;
+        }
+        self.include_preamble = preamble;
+        Ok(())
+    }
+
     /// Normalizes v1 flat fields into v2 nested structure.
     ///
     /// V1 flat fields take precedence over v2 nested fields when both are present.
@@ -285,7 +544,7 @@ impl RalphConfig {
                 to = "event_loop.completion_promise",
                 "Normalizing v1 field"
             );
-            self.event_loop.completion_promise = cp.clone();
+            self.event_loop.completion_promise = CompletionPromises::from(cp.as_str());
             normalized_count += 1;
         }
 
@@ -346,6 +605,21 @@ impl RalphConfig {
         }
     }
 
+    /// Builds the redactor for everything Ralph writes: the
+    /// `features.capture.redact` patterns, resolved secret `env` values and
+    /// the `security` rules.
+    pub fn redactor(
+        &self,
+        store: &dyn crate::env_source::SecretStore,
+    ) -> Result<crate::redact::Redactor, ConfigError> {
+        let redactor = self
+            .features
+            .capture
+            .redactor()?
+            .with_literals(&crate::env_source::secret_env_values(self, store));
+        self.security.apply(redactor)
+    }
+
     /// Validates the configuration and returns warnings.
     ///
     /// This method checks for:
@@ -374,14 +648,57 @@ impl RalphConfig {
                 field2: "event_loop.prompt_file".to_string(),
             });
         }
-        if self.event_loop.completion_promise.trim().is_empty() {
+        if self.event_loop.completion_promise.is_blank() {
             return Err(ConfigError::InvalidCompletionPromise);
         }
+        if let Err(e) = self.event_loop.completion_regex() {
+            return Err(ConfigError::InvalidCompletionPromiseRegex(e.to_string()));
+        }
+        if self
+            .event_loop
+            .completion_check
+            .as_ref()
+            .is_some_and(|check| check.command.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidCompletionCheck);
+        }
+        if self.event_loop.queue.max_depth == 0 {
+            return Err(ConfigError::InvalidQueueDepth);
+        }
+        if let Some(index) = self
+            .hooks
+            .iter()
+            .position(|hook| hook.on.trim().is_empty() || hook.run.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidHook(index));
+        }
+        self.features.capture.redactor()?;
+        self.security.apply(crate::redact::Redactor::default())?;
+        crate::event_schema::EventSchemas::compile(&self.event_schemas)?;
 
         // Check custom backend has a command
         if self.cli.backend == "custom" && self.cli.command.as_ref().is_none_or(String::is_empty) {
             return Err(ConfigError::CustomBackendRequiresCommand);
         }
+        for (name, custom) in &self.adapters.custom_backends {
+            let problem = if custom.command.trim().is_empty() {
+                Some("'command' must be set")
+            } else if custom.prompt_mode == CustomPromptMode::Flag
+                && custom.prompt_flag.as_ref().is_none_or(String::is_empty)
+            {
+                Some("'prompt_flag' is required when prompt_mode is 'flag'")
+            } else if custom.probe.as_ref().is_some_and(Vec::is_empty) {
+                Some("'probe' must name a command")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                return Err(ConfigError::InvalidCustomBackend {
+                    name: name.clone(),
+                    message: problem.to_string(),
+                });
+            }
+        }
 
         // Check for deferred features
         if self.archive_prompts {
@@ -422,6 +739,17 @@ impl RalphConfig {
             });
         }
 
+        let preflight = &self.features.preflight;
+        if preflight.disk_floor_mb > preflight.min_free_disk_mb {
+            warnings.push(ConfigWarning::InvalidValue {
+                field: "features.preflight.disk_floor_mb".to_string(),
+                message: format!(
+                    "Floor ({} MB) is above min_free_disk_mb ({} MB); the disk check will fail without warning first",
+                    preflight.disk_floor_mb, preflight.min_free_disk_mb
+                ),
+            });
+        }
+
         // Check adapter tool_permissions (dropped field)
         if self.adapters.claude.tool_permissions.is_some()
             || self.adapters.gemini.tool_permissions.is_some()
@@ -437,6 +765,39 @@ impl RalphConfig {
         // Validate RObot config
         self.robot.validate()?;
 
+        if self.event_loop.strict_templates {
+            let mut hats: Vec<_> = self.hats.iter().collect();
+            hats.sort_by_key(|(name, _)| name.as_str());
+            for (name, hat) in hats {
+                let unknown = crate::instructions::unknown_variables(&hat.instructions);
+                if !unknown.is_empty() {
+                    return Err(ConfigError::UnknownTemplateVariables {
+                        hat: name.clone(),
+                        names: unknown.join(", "),
+                    });
+                }
+            }
+        }
+
+        // Instruction files are read at load time; make sure they're still there
+        if let Some(path) = self.includes.iter().find(|path| !path.is_file()) {
+            return Err(ConfigError::IncludeFile {
+                path: path.display().to_string(),
+                reason: "does not exist".to_string(),
+            });
+        }
+        for (hat_id, hat_config) in &self.hats {
+            if let Some(path) = &hat_config.instructions_file
+                && !path.is_file()
+            {
+                return Err(ConfigError::InstructionsFile {
+                    hat: hat_id.clone(),
+                    path: path.display().to_string(),
+                    reason: "does not exist".to_string(),
+                });
+            }
+        }
+
         // Check for required description field on all hats
         for (hat_id, hat_config) in &self.hats {
             if hat_config
@@ -494,7 +855,13 @@ impl RalphConfig {
     /// If empty, returns the default priority order.
     pub fn get_agent_priority(&self) -> Vec<&str> {
         if self.agent_priority.is_empty() {
-            vec!["claude", "kiro", "gemini", "codex", "amp"]
+            let mut priority = vec!["claude", "kiro", "gemini", "codex", "amp"];
+            for name in self.adapters.custom_backends.keys() {
+                if !priority.contains(&name.as_str()) {
+                    priority.push(name);
+                }
+            }
+            priority
         } else {
             self.agent_priority.iter().map(String::as_str).collect()
         }
@@ -503,6 +870,9 @@ impl RalphConfig {
     /// Gets the adapter settings for a specific backend.
     #[allow(clippy::match_same_arms)] // Explicit match arms for each backend improves readability
     pub fn adapter_settings(&self, backend: &str) -> &AdapterSettings {
+        if let Some(custom) = self.adapters.custom_backends.get(backend) {
+            return &custom.settings;
+        }
         match backend {
             "claude" => &self.adapters.claude,
             "gemini" => &self.adapters.gemini,
@@ -514,6 +884,22 @@ impl RalphConfig {
     }
 }
 
+/// Maps a directory inside a linked git worktree to the same directory in
+/// the main checkout. Returns `None` outside linked worktrees.
+fn main_checkout_dir(dir: &Path) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    let worktree_root = dir.ancestors().find(|d| d.join(".git").exists())?;
+    // A linked worktree has a `.git` file: `gitdir: <repo>/.git/worktrees/<name>`
+    let git_file = std::fs::read_to_string(worktree_root.join(".git")).ok()?;
+    let gitdir = PathBuf::from(git_file.strip_prefix("gitdir:")?.trim());
+    let worktrees_dir = gitdir.parent()?;
+    if worktrees_dir.file_name()? != "worktrees" {
+        return None;
+    }
+    let main_root = worktrees_dir.parent()?.parent()?;
+    Some(main_root.join(dir.strip_prefix(worktree_root).ok()?))
+}
+
 /// Configuration warnings emitted during validation.
 #[derive(Debug, Clone)]
 pub enum ConfigWarning {
@@ -550,9 +936,18 @@ pub struct EventLoopConfig {
     #[serde(default = "default_prompt_file")]
     pub prompt_file: String,
 
-    /// Event topic that signals loop completion (must be emitted via `ralph emit`).
+    /// Event topic(s) that signal loop completion (must be emitted via `ralph emit`).
+    ///
+    /// Accepts a single string or a list of strings.
     #[serde(default = "default_completion_promise")]
-    pub completion_promise: String,
+    pub completion_promise: CompletionPromises,
+
+    /// Regex alternative for completion topics (e.g., `(?i)^loop_complete\W*$`).
+    ///
+    /// A topic completes the loop if it equals any `completion_promise` or
+    /// matches this pattern.
+    #[serde(default)]
+    pub completion_promise_regex: Option<String>,
 
     /// Maximum number of iterations before timeout.
     #[serde(default = "default_max_iterations")]
@@ -588,6 +983,12 @@ pub struct EventLoopConfig {
     /// event from the hat topology.
     pub starting_event: Option<String>,
 
+    /// Payload of the starting event (`ralph run --start-payload`).
+    ///
+    /// Defaults to the prompt. The prompt stays the loop's objective either way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starting_payload: Option<String>,
+
     /// Warn when mutation testing score drops below this percentage (0-100).
     ///
     /// Warning-only: build.done is still accepted even if below threshold.
@@ -602,14 +1003,79 @@ pub struct EventLoopConfig {
     /// max_cost), consecutive failures, or explicit interrupt/stop.
     #[serde(default)]
     pub persistent: bool,
+
+    /// Event topics that fail the loop when published by a hat.
+    ///
+    /// If any hat emits one of these topics (e.g., `review.critical`), the loop
+    /// terminates with `GateFailed` and a non-zero exit code, even if a
+    /// completion event is emitted in the same iteration.
+    #[serde(default)]
+    pub fail_on_event: Vec<String>,
+
+    /// Fsync the events file after each event the loop writes.
+    ///
+    /// Off by default: appends are already serialized with a file lock, so
+    /// this only matters for surviving a machine crash mid-run.
+    #[serde(default)]
+    pub fsync_events: bool,
+
+    /// Retry policy for iterations whose backend crashed.
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    /// Shell command that must pass before a completion event is accepted.
+    #[serde(default)]
+    pub completion_check: Option<CompletionCheckConfig>,
+
+    /// Queue for events published faster than hats consume them.
+    #[serde(default)]
+    pub queue: EventQueueConfig,
+
+    /// Suppression of repeated events from the events file.
+    #[serde(default)]
+    pub dedup: EventDedupConfig,
+
+    /// Reject hat instructions that reference unknown `{{variables}}`.
+    ///
+    /// Off by default: unknown placeholders are left as written. See
+    /// [`INSTRUCTION_VARIABLES`](crate::instructions::INSTRUCTION_VARIABLES).
+    #[serde(default)]
+    pub strict_templates: bool,
+
+    /// What to do with an event whose payload does not match its
+    /// `event_schemas` entry.
+    #[serde(default)]
+    pub schema_mismatch: SchemaMismatchAction,
+
+    /// Topic of a final event published when the loop stops for any
+    /// reason other than completion (e.g., `loop.terminated`).
+    ///
+    /// The payload is a JSON object with the termination `reason`,
+    /// `exit_code`, `iterations`, and `elapsed_secs`.
+    #[serde(default)]
+    pub on_failure_emit: Option<String>,
+
+    /// Stop the loop, exiting 0, as soon as a hat publishes this topic
+    /// (`ralph run --until-event`). Continue later with `ralph run --continue`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_event: Option<String>,
+
+    /// Context block injected at the top of each iteration's prompt.
+    #[serde(default)]
+    pub context: IterationContextConfig,
+
+    /// Where to write the JSON run summary on termination, relative to the
+    /// workspace root. Defaults to `.ralph/agent/run-summary.json`.
+    #[serde(default)]
+    pub summary_out: Option<PathBuf>,
 }
 
 fn default_prompt_file() -> String {
     "PROMPT.md".to_string()
 }
 
-fn default_completion_promise() -> String {
-    "LOOP_COMPLETE".to_string()
+fn default_completion_promise() -> CompletionPromises {
+    CompletionPromises::from("LOOP_COMPLETE")
 }
 
 fn default_max_iterations() -> u32 {
@@ -630,6 +1096,7 @@ impl Default for EventLoopConfig {
             prompt: None,
             prompt_file: default_prompt_file(),
             completion_promise: default_completion_promise(),
+            completion_promise_regex: None,
             max_iterations: default_max_iterations(),
             max_runtime_seconds: default_max_runtime(),
             max_cost_usd: None,
@@ -637,115 +1104,591 @@ impl Default for EventLoopConfig {
             cooldown_delay_seconds: 0,
             starting_hat: None,
             starting_event: None,
+            starting_payload: None,
             mutation_score_warn_threshold: None,
             persistent: false,
+            fail_on_event: Vec::new(),
+            fsync_events: false,
+            retry: RetryConfig::default(),
+            completion_check: None,
+            queue: EventQueueConfig::default(),
+            dedup: EventDedupConfig::default(),
+            strict_templates: false,
+            schema_mismatch: SchemaMismatchAction::default(),
+            on_failure_emit: None,
+            until_event: None,
+            context: IterationContextConfig::default(),
+            summary_out: None,
         }
     }
 }
 
-/// Core paths and settings shared across all hats.
+impl EventLoopConfig {
+    /// Compiles `completion_promise_regex`, if set.
+    pub fn completion_regex(&self) -> Result<Option<regex::Regex>, regex::Error> {
+        self.completion_promise_regex
+            .as_deref()
+            .map(regex::Regex::new)
+            .transpose()
+    }
+}
+
+/// Verification run when a completion event arrives
+/// (`event_loop.completion_check`).
 ///
-/// Per spec: "Core behaviors (always injected, can customize paths)"
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CoreConfig {
-    /// Path to the scratchpad file (shared state between hats).
-    #[serde(default = "default_scratchpad")]
-    pub scratchpad: String,
+/// The command runs with `sh -c` in the workspace. Exit 0 accepts the
+/// completion; anything else (including a timeout) rejects it and publishes
+/// `completion.rejected` with the tail of the command's output, sending the
+/// loop back to the hats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionCheckConfig {
+    /// Shell command to run (e.g., `cargo test`).
+    pub command: String,
+
+    /// Seconds before the command is killed and the completion rejected.
+    #[serde(default = "default_completion_check_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Rejections before the loop gives up and stops with
+    /// `CompletionRejected`.
+    #[serde(default = "default_completion_check_max_rejections")]
+    pub max_rejections: u32,
+}
 
-    /// Path to the specs directory (source of truth for requirements).
-    #[serde(default = "default_specs_dir")]
-    pub specs_dir: String,
+fn default_completion_check_timeout_secs() -> u64 {
+    600
+}
 
-    /// Guardrails injected into every prompt (core behaviors).
-    ///
-    /// Per spec: These are always present regardless of hat.
-    #[serde(default = "default_guardrails")]
-    pub guardrails: Vec<String>,
+fn default_completion_check_max_rejections() -> u32 {
+    3
+}
 
-    /// Root directory for workspace-relative paths (.ralph/, specs, etc.).
-    ///
-    /// All relative paths (scratchpad, specs_dir, memories) are resolved relative
-    /// to this directory. Defaults to the current working directory.
-    ///
-    /// This is especially important for E2E tests that run in isolated workspaces.
-    #[serde(skip)]
-    pub workspace_root: std::path::PathBuf,
+impl CompletionCheckConfig {
+    /// Creates a check for `command` with the default limits.
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            timeout_secs: default_completion_check_timeout_secs(),
+            max_rejections: default_completion_check_max_rejections(),
+        }
+    }
 }
 
-fn default_scratchpad() -> String {
-    ".ralph/agent/scratchpad.md".to_string()
+/// Shell command run when a matching event is recorded (`hooks`).
+///
+/// Hooks run with `sh -c` in the workspace, in the background: they never
+/// delay an iteration. The event is passed in `RALPH_EVENT_TOPIC` and
+/// `RALPH_EVENT_PAYLOAD`, and each run is recorded in the events file as
+/// `hook.completed` or `hook.failed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventHookConfig {
+    /// Topic pattern to match (e.g., `review.approved`, `build.*`, `*`).
+    pub on: String,
+
+    /// Shell command to run.
+    pub run: String,
+
+    /// Seconds before the command is killed and the hook counted as failed.
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// What a failed run does beyond being recorded.
+    #[serde(default)]
+    pub on_failure: HookFailurePolicy,
 }
 
-fn default_specs_dir() -> String {
-    ".ralph/specs/".to_string()
+fn default_hook_timeout_secs() -> u64 {
+    30
 }
 
-fn default_guardrails() -> Vec<String> {
-    vec![
-        "Fresh context each iteration - scratchpad is memory".to_string(),
-        "Don't assume 'not implemented' - search first".to_string(),
-        "Backpressure is law - tests/typecheck/lint/audit must pass".to_string(),
-        "Confidence protocol: score decisions 0-100. >80 proceed autonomously; 50-80 proceed + document in .ralph/agent/decisions.md; <50 choose safe default + document".to_string(),
-        "Commit atomically - one logical change per commit, capture the why".to_string(),
-    ]
+impl EventHookConfig {
+    /// Creates a hook running `run` on topics matching `on`, with the
+    /// default timeout and failure policy.
+    pub fn new(on: impl Into<String>, run: impl Into<String>) -> Self {
+        Self {
+            on: on.into(),
+            run: run.into(),
+            timeout_secs: default_hook_timeout_secs(),
+            on_failure: HookFailurePolicy::default(),
+        }
+    }
 }
 
-impl Default for CoreConfig {
+/// What happens when a hook exits non-zero, times out, or can't start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookFailurePolicy {
+    /// Log a warning and record `hook.failed`.
+    #[default]
+    Log,
+    /// Also request a stop; the loop ends at the next iteration boundary.
+    Stop,
+}
+
+/// Retry policy for iterations whose backend crashed.
+///
+/// An attempt is retried when the backend failed (non-zero exit, signal,
+/// spawn error) without publishing any events, unless a stop was requested.
+/// Retries don't advance the iteration counter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Attempts per iteration, including the first (1 = never retry).
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in seconds; doubles with each retry.
+    #[serde(default = "default_retry_backoff_secs")]
+    pub backoff_secs: u64,
+
+    /// Upper bound for the delay between retries, in seconds.
+    #[serde(default = "default_retry_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+}
+
+fn default_retry_max_attempts() -> u32 {
+    1
+}
+
+fn default_retry_backoff_secs() -> u64 {
+    5
+}
+
+fn default_retry_max_backoff_secs() -> u64 {
+    60
+}
+
+impl Default for RetryConfig {
     fn default() -> Self {
         Self {
-            scratchpad: default_scratchpad(),
-            specs_dir: default_specs_dir(),
-            guardrails: default_guardrails(),
-            workspace_root: std::env::var("RALPH_WORKSPACE_ROOT")
-                .map(std::path::PathBuf::from)
-                .unwrap_or_else(|_| {
-                    std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
-                }),
+            max_attempts: default_retry_max_attempts(),
+            backoff_secs: default_retry_backoff_secs(),
+            max_backoff_secs: default_retry_max_backoff_secs(),
         }
     }
 }
 
-impl CoreConfig {
-    /// Sets the workspace root for resolving relative paths.
-    ///
-    /// This is used by E2E tests to point to their isolated test workspace.
-    pub fn with_workspace_root(mut self, root: impl Into<std::path::PathBuf>) -> Self {
-        self.workspace_root = root.into();
-        self
+impl RetryConfig {
+    /// Returns true if failed attempts may be retried.
+    pub fn enabled(&self) -> bool {
+        self.max_attempts > 1
     }
 
-    /// Resolves a relative path against the workspace root.
-    ///
-    /// If the path is already absolute, it is returned as-is.
-    /// Otherwise, it is joined with the workspace root.
-    pub fn resolve_path(&self, relative: &str) -> std::path::PathBuf {
-        let path = std::path::Path::new(relative);
-        if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            self.workspace_root.join(path)
-        }
+    /// Delay before retry number `retry` (1-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(32);
+        Duration::from_secs(
+            self.backoff_secs
+                .saturating_mul(factor)
+                .min(self.max_backoff_secs),
+        )
     }
 }
 
-/// CLI backend configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CliConfig {
-    /// Backend to use: "claude", "kiro", "gemini", "codex", "amp", "pi", or "custom".
-    #[serde(default = "default_backend")]
-    pub backend: String,
+/// Queue for events read from the events file.
+///
+/// Events wait in the order they were written. Each iteration takes the
+/// oldest one a hat subscribes to; events no hat subscribes to go to Ralph
+/// when nothing else is waiting, and are dropped (recorded as
+/// `event.unhandled`) after waiting `unmatched_ttl` iterations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventQueueConfig {
+    /// Most events that may wait at once.
+    #[serde(default = "default_queue_max_depth")]
+    pub max_depth: usize,
+
+    /// What to do with an event that arrives while the queue is full.
+    #[serde(default)]
+    pub on_overflow: QueueOverflowPolicy,
 
-    /// Command override. Required for "custom" backend.
-    /// For named backends, overrides the default binary path.
-    pub command: Option<String>,
+    /// Iterations an event no hat subscribes to may be passed over before
+    /// it is dropped.
+    #[serde(default = "default_queue_unmatched_ttl")]
+    pub unmatched_ttl: u32,
+}
 
-    /// How to pass prompts: "arg" or "stdin".
-    #[serde(default = "default_prompt_mode")]
-    pub prompt_mode: String,
+fn default_queue_max_depth() -> usize {
+    100
+}
 
-    /// Execution mode when --interactive not specified.
-    /// Values: "autonomous" (default), "interactive"
-    #[serde(default = "default_mode")]
+fn default_queue_unmatched_ttl() -> u32 {
+    3
+}
+
+impl Default for EventQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: default_queue_max_depth(),
+            on_overflow: QueueOverflowPolicy::default(),
+            unmatched_ttl: default_queue_unmatched_ttl(),
+        }
+    }
+}
+
+/// Suppression of repeated events from the events file.
+///
+/// An event whose topic and payload match one already processed within
+/// `window_iterations` iterations, or that exceeds its topic's
+/// `max_per_iteration` limit, is recorded as `event.duplicate` and does not
+/// trigger hats. `human.guidance`, `human.response` and completion events
+/// always pass through.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventDedupConfig {
+    /// Suppress duplicates. Off by default.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Iterations, counting the current one, in which an identical event
+    /// counts as a duplicate. `1` only catches repeats within an iteration.
+    #[serde(default = "default_dedup_window_iterations")]
+    pub window_iterations: u32,
+
+    /// Further topics exempt from deduplication and rate limits.
+    #[serde(default)]
+    pub exempt_topics: Vec<String>,
+
+    /// Most events per iteration for a topic, whatever their payloads.
+    #[serde(default)]
+    pub max_per_iteration: BTreeMap<String, u32>,
+}
+
+fn default_dedup_window_iterations() -> u32 {
+    1
+}
+
+impl Default for EventDedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_iterations: default_dedup_window_iterations(),
+            exempt_topics: Vec::new(),
+            max_per_iteration: BTreeMap::new(),
+        }
+    }
+}
+
+/// Context block injected at the top of each iteration's prompt.
+///
+/// Shows the iteration budget, the most recent events, and open tasks so
+/// hats don't have to rediscover what happened before. When the block
+/// exceeds `max_chars`, events are dropped first (oldest first), then tasks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IterationContextConfig {
+    /// Inject the block. Off by default.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Show the iteration number and the iterations remaining.
+    #[serde(default = "default_true")]
+    pub iteration: bool,
+
+    /// Show the most recent events from the events file.
+    #[serde(default = "default_true")]
+    pub events: bool,
+
+    /// How many recent events to show.
+    #[serde(default = "default_context_recent_events")]
+    pub recent_events: usize,
+
+    /// Show open tasks from the task store.
+    #[serde(default = "default_true")]
+    pub tasks: bool,
+
+    /// Upper bound on the size of the block, in characters.
+    #[serde(default = "default_context_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_context_recent_events() -> usize {
+    5
+}
+
+fn default_context_max_chars() -> usize {
+    4000
+}
+
+impl Default for IterationContextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            iteration: true,
+            events: true,
+            recent_events: default_context_recent_events(),
+            tasks: true,
+            max_chars: default_context_max_chars(),
+        }
+    }
+}
+
+/// Handling for events that arrive at a full queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueOverflowPolicy {
+    /// Drop the oldest waiting event to make room.
+    #[default]
+    DropOldest,
+    /// Hold new events back until there is room.
+    Block,
+    /// Stop the loop.
+    Fail,
+}
+
+/// Handling for event payloads that do not match their topic's schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaMismatchAction {
+    /// Drop the event and publish `event.malformed` (`ralph emit` fails).
+    #[default]
+    Reject,
+    /// Log a warning and deliver the event anyway.
+    Warn,
+}
+
+/// One or more completion promise topics.
+///
+/// Serialized as a plain string when there is a single promise, so existing
+/// configs round-trip unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "CompletionPromisesRepr", into = "CompletionPromisesRepr")]
+pub struct CompletionPromises(Vec<String>);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CompletionPromisesRepr {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<CompletionPromisesRepr> for CompletionPromises {
+    fn from(repr: CompletionPromisesRepr) -> Self {
+        match repr {
+            CompletionPromisesRepr::One(promise) => Self(vec![promise]),
+            CompletionPromisesRepr::Many(promises) => Self(promises),
+        }
+    }
+}
+
+impl From<CompletionPromises> for CompletionPromisesRepr {
+    fn from(promises: CompletionPromises) -> Self {
+        match <[String; 1]>::try_from(promises.0) {
+            Ok([promise]) => Self::One(promise),
+            Err(promises) => Self::Many(promises),
+        }
+    }
+}
+
+impl CompletionPromises {
+    /// Parses a comma-separated list (e.g., from `--completion-promise`).
+    pub fn parse_list(value: &str) -> Self {
+        Self(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    /// Returns the first promise, used when instructing agents how to complete.
+    pub fn primary(&self) -> &str {
+        self.0.first().map_or("", String::as_str)
+    }
+
+    /// Returns all promises.
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns true if `topic` is exactly one of the promises.
+    pub fn contains(&self, topic: &str) -> bool {
+        self.0.iter().any(|p| p == topic)
+    }
+
+    /// Returns true if the list is empty or any promise is blank.
+    pub fn is_blank(&self) -> bool {
+        self.0.is_empty() || self.0.iter().any(|p| p.trim().is_empty())
+    }
+}
+
+impl From<&str> for CompletionPromises {
+    fn from(promise: &str) -> Self {
+        Self(vec![promise.to_string()])
+    }
+}
+
+impl From<String> for CompletionPromises {
+    fn from(promise: String) -> Self {
+        Self(vec![promise])
+    }
+}
+
+impl PartialEq<&str> for CompletionPromises {
+    fn eq(&self, other: &&str) -> bool {
+        self.0.len() == 1 && self.0[0] == *other
+    }
+}
+
+impl std::fmt::Display for CompletionPromises {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(", "))
+    }
+}
+
+/// Matches event topics against the configured completion promises.
+///
+/// Built once per loop so the regex is compiled a single time.
+#[derive(Debug, Clone)]
+pub struct CompletionMatcher {
+    promises: CompletionPromises,
+    regex: Option<regex::Regex>,
+}
+
+impl CompletionMatcher {
+    /// Builds a matcher from event loop config.
+    ///
+    /// An invalid regex is reported by `RalphConfig::validate()`; here it is
+    /// logged and ignored so literal promises keep working.
+    pub fn from_config(config: &EventLoopConfig) -> Self {
+        let regex = config.completion_regex().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Ignoring invalid completion_promise_regex");
+            None
+        });
+        Self {
+            promises: config.completion_promise.clone(),
+            regex,
+        }
+    }
+
+    /// Returns true if `topic` completes the loop.
+    pub fn matches(&self, topic: &str) -> bool {
+        self.promises.contains(topic) || self.regex.as_ref().is_some_and(|re| re.is_match(topic))
+    }
+}
+
+/// Core paths and settings shared across all hats.
+///
+/// Per spec: "Core behaviors (always injected, can customize paths)"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreConfig {
+    /// Path to the scratchpad file (shared state between hats).
+    #[serde(default = "default_scratchpad")]
+    pub scratchpad: String,
+
+    /// Number of archived scratchpads to keep (0 = keep all).
+    ///
+    /// Fresh runs move a non-empty scratchpad to `.ralph/agent/archive/`
+    /// instead of deleting it; older archives beyond this count are pruned.
+    #[serde(default = "default_scratchpad_archive_keep")]
+    pub scratchpad_archive_keep: usize,
+
+    /// Number of run records to keep under `.ralph/runs/` (0 = keep all).
+    #[serde(default = "default_runs_keep")]
+    pub runs_keep: usize,
+
+    /// Path to the specs directory (source of truth for requirements).
+    #[serde(default = "default_specs_dir")]
+    pub specs_dir: String,
+
+    /// Guardrails injected into every prompt (core behaviors).
+    ///
+    /// Per spec: These are always present regardless of hat.
+    #[serde(default = "default_guardrails")]
+    pub guardrails: Vec<String>,
+
+    /// Root directory for workspace-relative paths (.ralph/, specs, etc.).
+    ///
+    /// All relative paths (scratchpad, specs_dir, memories) are resolved relative
+    /// to this directory. Defaults to the current working directory.
+    ///
+    /// This is especially important for E2E tests that run in isolated workspaces.
+    #[serde(skip)]
+    pub workspace_root: std::path::PathBuf,
+}
+
+fn default_scratchpad() -> String {
+    ".ralph/agent/scratchpad.md".to_string()
+}
+
+fn default_scratchpad_archive_keep() -> usize {
+    10
+}
+
+fn default_runs_keep() -> usize {
+    100
+}
+
+fn default_specs_dir() -> String {
+    ".ralph/specs/".to_string()
+}
+
+fn default_guardrails() -> Vec<String> {
+    vec![
+        "Fresh context each iteration - scratchpad is memory".to_string(),
+        "Don't assume 'not implemented' - search first".to_string(),
+        "Backpressure is law - tests/typecheck/lint/audit must pass".to_string(),
+        "Confidence protocol: score decisions 0-100. >80 proceed autonomously; 50-80 proceed + document in .ralph/agent/decisions.md; <50 choose safe default + document".to_string(),
+        "Commit atomically - one logical change per commit, capture the why".to_string(),
+    ]
+}
+
+impl Default for CoreConfig {
+    fn default() -> Self {
+        Self {
+            scratchpad: default_scratchpad(),
+            scratchpad_archive_keep: default_scratchpad_archive_keep(),
+            runs_keep: default_runs_keep(),
+            specs_dir: default_specs_dir(),
+            guardrails: default_guardrails(),
+            workspace_root: std::env::var("RALPH_WORKSPACE_ROOT")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|_| {
+                    std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
+                }),
+        }
+    }
+}
+
+impl CoreConfig {
+    /// Sets the workspace root for resolving relative paths.
+    ///
+    /// This is used by E2E tests to point to their isolated test workspace.
+    pub fn with_workspace_root(mut self, root: impl Into<std::path::PathBuf>) -> Self {
+        self.workspace_root = root.into();
+        self
+    }
+
+    /// Resolves a relative path against the workspace root.
+    ///
+    /// If the path is already absolute, it is returned as-is.
+    /// Otherwise, it is joined with the workspace root.
+    pub fn resolve_path(&self, relative: &str) -> std::path::PathBuf {
+        let path = std::path::Path::new(relative);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.workspace_root.join(path)
+        }
+    }
+}
+
+/// CLI backend configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliConfig {
+    /// Backend to use: "claude", "kiro", "gemini", "codex", "amp", "pi", or "custom".
+    #[serde(default = "default_backend")]
+    pub backend: String,
+
+    /// Command override. Required for "custom" backend.
+    /// For named backends, overrides the default binary path.
+    pub command: Option<String>,
+
+    /// How to pass prompts: "arg" or "stdin".
+    #[serde(default = "default_prompt_mode")]
+    pub prompt_mode: String,
+
+    /// Execution mode when --interactive not specified.
+    /// Values: "autonomous" (default), "interactive"
+    #[serde(default = "default_mode")]
     pub default_mode: String,
 
     /// Idle timeout in seconds for interactive mode.
@@ -763,6 +1706,79 @@ pub struct CliConfig {
     /// If None, defaults to "-p" for arg mode.
     #[serde(default)]
     pub prompt_flag: Option<String>,
+
+    /// Environment variables set on the backend process.
+    /// Values may reference the parent environment as `${VAR}`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// Prompts longer than this many bytes are not passed as an argument
+    /// but through a temp file or stdin (0 = all prompts). Defaults to
+    /// 7000 for Claude and 100000 for other backends.
+    #[serde(default)]
+    pub large_prompt_threshold: Option<usize>,
+
+    /// After the first iteration, resume the backend's previous session and
+    /// send only what changed (guidance, pending events, the active hat)
+    /// instead of the full prompt. Falls back to full prompts on backends
+    /// that cannot resume a session.
+    #[serde(default)]
+    pub continue_session: bool,
+
+    /// Probe the backend with a trivial prompt before the first iteration,
+    /// failing fast if it is not installed, logged in, or responding.
+    /// Defaults to on in autonomous mode.
+    #[serde(default)]
+    pub warmup: Option<bool>,
+
+    /// Token prices per model, used to estimate spend for `max_cost_usd`.
+    /// Keys match the model the backend reports, exactly or as a prefix.
+    #[serde(default)]
+    pub pricing: BTreeMap<String, ModelPricing>,
+}
+
+impl CliConfig {
+    /// Whether to warm up the backend before the loop starts.
+    pub fn warmup_enabled(&self) -> bool {
+        self.warmup.unwrap_or(self.default_mode == "autonomous")
+    }
+
+    /// Prices for `model`: the exact entry, else the longest key that is a
+    /// prefix of it (so `claude-sonnet-4-5` covers dated model ids).
+    pub fn pricing_for(&self, model: &str) -> Option<&ModelPricing> {
+        self.pricing.get(model).or_else(|| {
+            self.pricing
+                .iter()
+                .filter(|(key, _)| model.starts_with(key.as_str()))
+                .max_by_key(|(key, _)| key.len())
+                .map(|(_, pricing)| pricing)
+        })
+    }
+}
+
+/// Token prices for one model, in USD per million tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelPricing {
+    /// Uncached input tokens.
+    pub input: f64,
+    /// Output tokens.
+    pub output: f64,
+    /// Input tokens written to the prompt cache.
+    pub cache_write: f64,
+    /// Input tokens read from the prompt cache.
+    pub cache_read: f64,
+}
+
+impl ModelPricing {
+    /// Cost in USD of the given token counts.
+    pub fn cost(&self, input: u64, output: u64, cache_write: u64, cache_read: u64) -> f64 {
+        let per_token = |tokens: u64, price: f64| tokens as f64 * price / 1_000_000.0;
+        per_token(input, self.input)
+            + per_token(output, self.output)
+            + per_token(cache_write, self.cache_write)
+            + per_token(cache_read, self.cache_read)
+    }
 }
 
 fn default_backend() -> String {
@@ -791,6 +1807,11 @@ impl Default for CliConfig {
             idle_timeout_secs: default_idle_timeout(),
             args: Vec::new(),
             prompt_flag: None,
+            env: BTreeMap::new(),
+            large_prompt_threshold: None,
+            continue_session: false,
+            warmup: None,
+            pricing: BTreeMap::new(),
         }
     }
 }
@@ -952,67 +1973,352 @@ pub struct SkillsConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
 
-    /// Directories to scan for skill files.
-    /// Relative paths resolved against workspace root.
-    #[serde(default)]
-    pub dirs: Vec<PathBuf>,
+    /// Directories to scan for skill files.
+    /// Relative paths resolved against workspace root.
+    #[serde(default)]
+    pub dirs: Vec<PathBuf>,
+
+    /// Per-skill overrides keyed by skill name.
+    #[serde(default)]
+    pub overrides: HashMap<String, SkillOverride>,
+}
+
+impl Default for SkillsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true, // Skills enabled by default
+            dirs: vec![],
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+/// Per-skill configuration override.
+///
+/// Allows enabling/disabling individual skills and overriding their
+/// frontmatter fields (hats, backends, tags, auto_inject).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillOverride {
+    /// Disable a discovered skill.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Restrict skill to specific hats.
+    #[serde(default)]
+    pub hats: Vec<String>,
+
+    /// Restrict skill to specific backends.
+    #[serde(default)]
+    pub backends: Vec<String>,
+
+    /// Tags for categorization.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Inject full content into prompt (not just index entry).
+    #[serde(default)]
+    pub auto_inject: Option<bool>,
+}
+
+/// Preflight check configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightConfig {
+    /// Whether to run preflight checks before `ralph run`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Whether to treat warnings as failures.
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Specific checks to skip (by name). Empty = run all checks.
+    #[serde(default)]
+    pub skip: Vec<String>,
+
+    /// Maximum number of checks to run concurrently (minimum 1).
+    #[serde(default = "default_preflight_max_concurrency")]
+    pub max_concurrency: usize,
+
+    /// Seconds a single check may run before it is reported as a warning.
+    /// Set to 0 to disable the timeout.
+    #[serde(default = "default_preflight_check_timeout_secs")]
+    pub check_timeout_secs: u64,
+
+    /// Free space (MB) at the workspace below which the `disk` check warns.
+    #[serde(default = "default_preflight_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+
+    /// Free space (MB) at the workspace below which the `disk` check fails.
+    #[serde(default = "default_preflight_disk_floor_mb")]
+    pub disk_floor_mb: u64,
+
+    /// Whether the `git` check fails, rather than warns, on a dirty working tree.
+    #[serde(default)]
+    pub require_clean_tree: bool,
+}
+
+fn default_preflight_max_concurrency() -> usize {
+    4
+}
+
+fn default_preflight_check_timeout_secs() -> u64 {
+    10
+}
+
+fn default_preflight_min_free_disk_mb() -> u64 {
+    2048
+}
+
+fn default_preflight_disk_floor_mb() -> u64 {
+    512
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strict: false,
+            skip: Vec::new(),
+            max_concurrency: default_preflight_max_concurrency(),
+            check_timeout_secs: default_preflight_check_timeout_secs(),
+            min_free_disk_mb: default_preflight_min_free_disk_mb(),
+            disk_floor_mb: default_preflight_disk_floor_mb(),
+            require_clean_tree: false,
+        }
+    }
+}
+
+/// Capture and session recording configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    /// Regex patterns for secrets masked in recorded output.
+    ///
+    /// Defaults to common key formats (`sk-`, `ghp_`, bearer tokens, ...).
+    /// Setting a list replaces the defaults; `[]` disables redaction.
+    #[serde(default = "default_redact_patterns")]
+    pub redact: Vec<String>,
+}
+
+fn default_redact_patterns() -> Vec<String> {
+    crate::redact::DEFAULT_REDACT_PATTERNS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            redact: default_redact_patterns(),
+        }
+    }
+}
+
+impl CaptureConfig {
+    /// Compiles the configured redaction patterns.
+    pub fn redactor(&self) -> Result<crate::redact::Redactor, ConfigError> {
+        crate::redact::Redactor::new(&self.redact)
+            .map_err(|e| ConfigError::InvalidRedactPattern(e.to_string()))
+    }
+}
+
+/// Secret redaction settings (`security:`).
+///
+/// Matches are replaced with `[REDACTED:<name>]` in event payloads, captured
+/// output, session recordings, tracing logs and diagnostics files before
+/// they are written.
+///
+/// ```yaml
+/// security:
+///   redact:
+///     - name: db_url
+///       pattern: "postgres://\\S+"
+///   secret_env: [STRIPE_KEY, DATABASE_PASSWORD]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Named regex patterns to mask.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact: Vec<RedactRule>,
+
+    /// Environment variables whose values are masked verbatim, named after
+    /// the variable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_env: Vec<String>,
+}
+
+/// A named redaction pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactRule {
+    /// Name shown in the replacement, `[REDACTED:<name>]`.
+    pub name: String,
+
+    /// Regex matching the secret.
+    pub pattern: String,
+}
+
+impl SecurityConfig {
+    /// Adds the configured rules and the current values of `secret_env` to
+    /// `redactor`.
+    pub fn apply(
+        &self,
+        redactor: crate::redact::Redactor,
+    ) -> Result<crate::redact::Redactor, ConfigError> {
+        let rules: Vec<(&str, &str)> = self
+            .redact
+            .iter()
+            .map(|rule| (rule.name.as_str(), rule.pattern.as_str()))
+            .collect();
+        let env_values: Vec<(&str, String)> = self
+            .secret_env
+            .iter()
+            .filter_map(|name| Some((name.as_str(), std::env::var(name).ok()?)))
+            .collect();
+        Ok(redactor
+            .with_named_patterns(&rules)
+            .map_err(|e| ConfigError::InvalidRedactPattern(e.to_string()))?
+            .with_named_literals(&env_values))
+    }
+}
+
+/// Session recording (`--record-session`) limits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    /// Maximum size of a recording in bytes, before compression.
+    ///
+    /// Once reached, a final `_meta.truncated` record is written and the
+    /// rest of the run is not recorded. 0 disables the cap.
+    #[serde(default = "default_recording_max_bytes")]
+    pub max_bytes: u64,
+}
 
-    /// Per-skill overrides keyed by skill name.
-    #[serde(default)]
-    pub overrides: HashMap<String, SkillOverride>,
+fn default_recording_max_bytes() -> u64 {
+    100 * 1024 * 1024
 }
 
-impl Default for SkillsConfig {
+impl Default for RecordingConfig {
     fn default() -> Self {
         Self {
-            enabled: true, // Skills enabled by default
-            dirs: vec![],
-            overrides: HashMap::new(),
+            max_bytes: default_recording_max_bytes(),
         }
     }
 }
 
-/// Per-skill configuration override.
+/// Retention limits for diagnostics sessions under `.ralph/diagnostics/`.
 ///
-/// Allows enabling/disabling individual skills and overriding their
-/// frontmatter fields (hats, backends, tags, auto_inject).
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct SkillOverride {
-    /// Disable a discovered skill.
-    #[serde(default)]
-    pub enabled: Option<bool>,
+/// Oldest sessions are pruned when a new session starts. A limit of 0
+/// disables it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsConfig {
+    /// Maximum number of sessions to keep, including the new one.
+    #[serde(default = "default_diagnostics_max_sessions")]
+    pub max_sessions: usize,
+
+    /// Maximum total size of all sessions, in megabytes.
+    #[serde(default = "default_diagnostics_max_total_mb")]
+    pub max_total_mb: u64,
+}
 
-    /// Restrict skill to specific hats.
-    #[serde(default)]
-    pub hats: Vec<String>,
+fn default_diagnostics_max_sessions() -> usize {
+    50
+}
 
-    /// Restrict skill to specific backends.
-    #[serde(default)]
-    pub backends: Vec<String>,
+fn default_diagnostics_max_total_mb() -> u64 {
+    1024
+}
 
-    /// Tags for categorization.
-    #[serde(default)]
-    pub tags: Vec<String>,
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: default_diagnostics_max_sessions(),
+            max_total_mb: default_diagnostics_max_total_mb(),
+        }
+    }
+}
 
-    /// Inject full content into prompt (not just index entry).
-    #[serde(default)]
-    pub auto_inject: Option<bool>,
+/// Worktree cleanup configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeFeaturesConfig {
+    /// Whether to delete a loop's `ralph/` branch after it is merged.
+    ///
+    /// Only branches fully merged into the checkout are deleted unless the
+    /// merge was run with `--force`.
+    #[serde(default = "default_true")]
+    pub delete_branch_on_merge: bool,
 }
 
-/// Preflight check configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct PreflightConfig {
-    /// Whether to run preflight checks before `ralph run`.
+impl Default for WorktreeFeaturesConfig {
+    fn default() -> Self {
+        Self {
+            delete_branch_on_merge: true,
+        }
+    }
+}
+
+/// What auto-merge does when a completed loop's branch conflicts with main.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoMergeConflictPolicy {
+    /// Leave the worktree and branch for manual handling.
+    #[default]
+    Abort,
+    /// Save the conflicted files with their markers, emit `merge.failed`,
+    /// and leave the worktree for manual handling.
+    Keep,
+    /// Let merge-ralph resolve conflicts in favor of main.
+    PreferOurs,
+    /// Let merge-ralph resolve conflicts in favor of the loop's branch.
+    PreferTheirs,
+}
+
+impl AutoMergeConflictPolicy {
+    /// The policy's name as written in config.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Abort => "abort",
+            Self::Keep => "keep",
+            Self::PreferOurs => "prefer-ours",
+            Self::PreferTheirs => "prefer-theirs",
+        }
+    }
+}
+
+/// Memory feature configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFeaturesConfig {
+    /// Which memories to drop when they exceed `memories.budget`.
     #[serde(default)]
-    pub enabled: bool,
+    pub eviction: MemoryEviction,
 
-    /// Whether to treat warnings as failures.
+    /// Whether to extract memories after a successful run.
+    ///
+    /// Runs one extra backend invocation over the scratchpad and final
+    /// output, then stores the proposed memories in `memories.md`.
     #[serde(default)]
-    pub strict: bool,
+    pub auto_extract: bool,
 
-    /// Specific checks to skip (by name). Empty = run all checks.
+    /// Maximum number of memories to extract per run.
+    #[serde(default = "default_max_extracted")]
+    pub max_extracted: usize,
+
+    /// Print extracted memories and ask for confirmation before storing them.
     #[serde(default)]
-    pub skip: Vec<String>,
+    pub review: bool,
+}
+
+fn default_max_extracted() -> usize {
+    5
+}
+
+impl Default for MemoryFeaturesConfig {
+    fn default() -> Self {
+        Self {
+            eviction: MemoryEviction::default(),
+            auto_extract: false,
+            max_extracted: default_max_extracted(),
+            review: false,
+        }
+    }
 }
 
 /// Feature flags for optional Ralph capabilities.
@@ -1022,13 +2328,28 @@ pub struct PreflightConfig {
 /// features:
 ///   parallel: true  # Enable parallel loops via git worktrees
 ///   auto_merge: false  # Auto-merge worktree branches on completion
+///   auto_merge_conflict: abort  # or "keep", "prefer-ours", "prefer-theirs"
+///   base_branch: develop  # Branch parallel loops from this ref (default: HEAD)
+///   worktree_dir: ../worktrees  # Where worktrees go (default: .worktrees)
+///   worktree:
+///     delete_branch_on_merge: true  # Delete ralph/<id> once merged
 ///   preflight:
 ///     enabled: false      # Opt-in: run preflight checks before `ralph run`
 ///     strict: false       # Treat warnings as failures
 ///     skip: ["telegram"]  # Skip specific checks by name
+///     max_concurrency: 4  # Checks run in parallel, up to this many at once
+///     check_timeout_secs: 10  # Warn on checks slower than this (0 = no timeout)
 ///   loop_naming:
 ///     format: human-readable  # or "timestamp" for legacy format
 ///     max_length: 50
+///   memory:
+///     eviction: recent     # or "by-type-priority", "largest-first"
+///     auto_extract: false  # Opt-in: extract memories after a successful run
+///     max_extracted: 5
+///     review: false        # Confirm extracted memories before storing
+///   diagnostics:
+///     max_sessions: 50     # Prune oldest sessions beyond this count (0 = no limit)
+///     max_total_mb: 1024   # ...or beyond this total size (0 = no limit)
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeaturesConfig {
@@ -1047,6 +2368,27 @@ pub struct FeaturesConfig {
     #[serde(default)]
     pub auto_merge: bool,
 
+    /// What auto-merge does when a loop's branch conflicts with main.
+    #[serde(default)]
+    pub auto_merge_conflict: AutoMergeConflictPolicy,
+
+    /// Ref that parallel loop worktrees branch from.
+    ///
+    /// When unset (default), worktrees branch from the current HEAD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// Directory parallel loop worktrees are created in, absolute or
+    /// relative to the repo root (default: `.worktrees`).
+    ///
+    /// Only a directory inside the repo is added to `.gitignore`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_dir: Option<PathBuf>,
+
+    /// Cleanup of merged loop branches.
+    #[serde(default)]
+    pub worktree: WorktreeFeaturesConfig,
+
     /// Loop naming configuration for worktree branches.
     ///
     /// Controls how loop IDs are generated for parallel loops.
@@ -1058,6 +2400,36 @@ pub struct FeaturesConfig {
     /// Preflight check configuration.
     #[serde(default)]
     pub preflight: PreflightConfig,
+
+    /// Memory feature configuration.
+    #[serde(default)]
+    pub memory: MemoryFeaturesConfig,
+
+    /// Capture and session recording configuration.
+    #[serde(default)]
+    pub capture: CaptureConfig,
+
+    /// Session recording size cap.
+    #[serde(default)]
+    pub recording: RecordingConfig,
+
+    /// Diagnostics session retention.
+    #[serde(default)]
+    pub diagnostics: DiagnosticsConfig,
+}
+
+impl FeaturesConfig {
+    /// Worktree settings for parallel loops.
+    pub fn worktree_config(&self) -> WorktreeConfig {
+        let mut config = self
+            .worktree_dir
+            .clone()
+            .map_or_else(WorktreeConfig::default, WorktreeConfig::with_dir);
+        if let Some(base) = &self.base_branch {
+            config = config.with_base_branch(base);
+        }
+        config
+    }
 }
 
 impl Default for FeaturesConfig {
@@ -1065,8 +2437,16 @@ impl Default for FeaturesConfig {
         Self {
             parallel: true,    // Parallel loops enabled by default
             auto_merge: false, // Auto-merge disabled by default for safety
+            auto_merge_conflict: AutoMergeConflictPolicy::default(),
+            base_branch: None,
+            worktree_dir: None,
+            worktree: WorktreeFeaturesConfig::default(),
             loop_naming: crate::loop_name::LoopNamingConfig::default(),
             preflight: PreflightConfig::default(),
+            memory: MemoryFeaturesConfig::default(),
+            capture: CaptureConfig::default(),
+            recording: RecordingConfig::default(),
+            diagnostics: DiagnosticsConfig::default(),
         }
     }
 }
@@ -1124,6 +2504,101 @@ impl TuiConfig {
     }
 }
 
+/// Web dashboard (`ralph web`) configuration.
+///
+/// The dashboard listens on localhost unless `allow_public` opts in to a
+/// non-loopback address. Requests carry a bearer token, read from `token`
+/// (a literal, `${VAR}`, or `keychain:<entry>`) or generated at startup.
+///
+/// Example configuration:
+/// ```yaml
+/// web:
+///   bind: 0.0.0.0
+///   allow_public: true
+///   token: keychain:web-token
+///   auth:
+///     read: none      # anyone on the network may watch
+///     mutate: token   # stopping loops and sending guidance needs the token
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebConfig {
+    /// Host the dashboard listens on.
+    #[serde(default = "default_web_bind")]
+    pub bind: String,
+
+    /// Permit binding a non-loopback address such as `0.0.0.0`.
+    #[serde(default)]
+    pub allow_public: bool,
+
+    /// Token source; when unset, a token is generated at startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Which requests need the token.
+    #[serde(default)]
+    pub auth: WebAuthConfig,
+}
+
+fn default_web_bind() -> String {
+    "127.0.0.1".to_string()
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            bind: default_web_bind(),
+            allow_public: false,
+            token: None,
+            auth: WebAuthConfig::default(),
+        }
+    }
+}
+
+/// Token requirements for read-only and mutating dashboard requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WebAuthConfig {
+    /// Viewing loops, events, and logs.
+    #[serde(default)]
+    pub read: WebAuthMode,
+
+    /// Changing state: stopping loops, sending guidance, editing tasks.
+    #[serde(default)]
+    pub mutate: WebAuthMode,
+}
+
+impl WebAuthConfig {
+    /// No request needs a token.
+    pub const NONE: Self = Self {
+        read: WebAuthMode::None,
+        mutate: WebAuthMode::None,
+    };
+
+    /// Returns true if any request needs a token.
+    pub fn requires_token(&self) -> bool {
+        self.read == WebAuthMode::Token || self.mutate == WebAuthMode::Token
+    }
+}
+
+/// Whether a class of dashboard requests needs the token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebAuthMode {
+    /// Requests must present the bearer token.
+    #[default]
+    Token,
+    /// Requests are served without a token.
+    None,
+}
+
+impl std::fmt::Display for WebAuthMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Token => write!(f, "token"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
 /// Metadata for an event topic.
 ///
 /// Defines what an event means, enabling auto-derived instructions for hats.
@@ -1220,6 +2695,14 @@ pub struct HatConfig {
     #[serde(default)]
     pub instructions: String,
 
+    /// Markdown file to read `instructions` from, instead of inline text.
+    ///
+    /// Relative paths resolve against the config file's directory. Once the
+    /// config is loaded this holds the resolved path and `instructions` holds
+    /// the file's content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions_file: Option<PathBuf>,
+
     /// Additional instruction fragments appended to `instructions`.
     ///
     /// Use with YAML anchors to share common instruction blocks across hats:
@@ -1243,6 +2726,20 @@ pub struct HatConfig {
     #[serde(default)]
     pub backend: Option<HatBackend>,
 
+    /// Environment variables for this hat's backend process.
+    /// Merged over `cli.env`; entries here win on conflicts.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// Tools the backend may use while wearing this hat (backend tool names).
+    /// Translated into backend flags where the backend supports it.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+
+    /// Tools the backend must not use while wearing this hat.
+    #[serde(default)]
+    pub disallowed_tools: Vec<String>,
+
     /// Default event to publish if hat forgets to write an event.
     #[serde(default)]
     pub default_publishes: Option<String>,
@@ -1252,6 +2749,14 @@ pub struct HatConfig {
     /// When the limit is exceeded, the orchestrator publishes `<hat_id>.exhausted`
     /// instead of activating the hat again.
     pub max_activations: Option<u32>,
+
+    /// Maximum number of iterations this hat may execute in a single loop run.
+    ///
+    /// When the budget is used up, the orchestrator publishes
+    /// `hat.budget_exceeded`. If no hat subscribes to it, the loop terminates
+    /// with `HatBudgetExceeded`.
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
 }
 
 impl HatConfig {
@@ -1271,7 +2776,8 @@ impl HatConfig {
 /// Enables bidirectional communication between AI agents and humans
 /// during orchestration loops. When enabled, agents can emit `human.interact`
 /// events to request clarification (blocking the loop), and humans can
-/// send proactive guidance via Telegram.
+/// send proactive guidance via Telegram, Slack, or both. With both, questions
+/// go to each and the first answer wins.
 ///
 /// Example configuration:
 /// ```yaml
@@ -1281,6 +2787,12 @@ impl HatConfig {
 ///   checkin_interval_seconds: 120  # Optional: send status every 2 min
 ///   telegram:
 ///     bot_token: "..."  # Or set RALPH_TELEGRAM_BOT_TOKEN env var
+///   slack:              # Optional: Slack via the Events API
+///     channel_id: "C0123456789"
+///   notifications:      # Optional: which lifecycle moments to announce
+///     start: true
+///     interrupted: false
+///     max_per_hour: 20
 /// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RobotConfig {
@@ -1300,6 +2812,14 @@ pub struct RobotConfig {
     /// Telegram bot configuration.
     #[serde(default)]
     pub telegram: Option<TelegramBotConfig>,
+
+    /// Slack bot configuration.
+    #[serde(default)]
+    pub slack: Option<SlackBotConfig>,
+
+    /// Loop lifecycle notifications (start, termination, preflight failure).
+    #[serde(default)]
+    pub notifications: RobotNotificationsConfig,
 }
 
 impl RobotConfig {
@@ -1316,8 +2836,15 @@ impl RobotConfig {
             });
         }
 
-        // Bot token must be available from config, keychain, or env var
-        if self.resolve_bot_token().is_none() {
+        if let Some(slack) = &self.slack {
+            slack.validate()?;
+        }
+
+        // Bot token must be available from config, keychain, or env var.
+        // With Slack configured, Telegram is only used if it has a token,
+        // unless its section asks for it explicitly
+        let telegram_required = self.slack.is_none() || self.telegram.is_some();
+        if telegram_required && self.resolve_bot_token().is_none() {
             return Err(ConfigError::RobotMissingField {
                 field: "RObot.telegram.bot_token".to_string(),
                 hint: "Run `ralph bot onboard --telegram`, set RALPH_TELEGRAM_BOT_TOKEN env var, or set RObot.telegram.bot_token in config"
@@ -1335,31 +2862,45 @@ impl RobotConfig {
     /// 2. `RObot.telegram.bot_token` in config file (explicit project override)
     /// 3. OS keychain (service: "ralph", user: "telegram-bot-token")
     pub fn resolve_bot_token(&self) -> Option<String> {
-        // 1. Env var (highest priority)
-        let env_token = std::env::var("RALPH_TELEGRAM_BOT_TOKEN").ok();
         let config_token = self
             .telegram
             .as_ref()
             .and_then(|telegram| telegram.bot_token.clone());
+        resolve_robot_secret(
+            "RALPH_TELEGRAM_BOT_TOKEN",
+            config_token,
+            "telegram-bot-token",
+        )
+    }
+}
 
-        if cfg!(test) {
-            return env_token.or(config_token);
-        }
+/// Resolves a bot secret from `env_var`, then `config_value`, then the OS
+/// keychain entry `keychain_user` (service "ralph").
+fn resolve_robot_secret(
+    env_var: &str,
+    config_value: Option<String>,
+    keychain_user: &str,
+) -> Option<String> {
+    // 1. Env var (highest priority)
+    let env_value = std::env::var(env_var).ok();
+
+    if cfg!(test) {
+        return env_value.or(config_value);
+    }
 
-        env_token
-            // 2. Config file (explicit override)
-            .or(config_token)
-            // 3. OS keychain (best effort)
-            .or_else(|| {
-                std::panic::catch_unwind(|| {
-                    keyring::Entry::new("ralph", "telegram-bot-token")
-                        .ok()
-                        .and_then(|e| e.get_password().ok())
-                })
-                .ok()
-                .flatten()
+    env_value
+        // 2. Config file (explicit override)
+        .or(config_value)
+        // 3. OS keychain (best effort)
+        .or_else(|| {
+            std::panic::catch_unwind(|| {
+                keyring::Entry::new("ralph", keychain_user)
+                    .ok()
+                    .and_then(|e| e.get_password().ok())
             })
-    }
+            .ok()
+            .flatten()
+        })
 }
 
 /// Telegram bot configuration.
@@ -1369,6 +2910,177 @@ pub struct TelegramBotConfig {
     pub bot_token: Option<String>,
 }
 
+/// Slack bot configuration.
+///
+/// Ralph posts to `channel_id` with the Web API and receives messages and
+/// slash commands through the Events API, so the app's request URL must
+/// reach `listen` (directly or through a tunnel).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackBotConfig {
+    /// Bot token (`xoxb-...`). Optional if `RALPH_SLACK_BOT_TOKEN` is set
+    /// or the token is in the keychain.
+    pub bot_token: Option<String>,
+
+    /// Signing secret used to verify requests from Slack. Optional if
+    /// `RALPH_SLACK_SIGNING_SECRET` is set or the secret is in the keychain.
+    pub signing_secret: Option<String>,
+
+    /// Channel the bot posts to and takes messages from.
+    pub channel_id: Option<String>,
+
+    /// Address the Events API listener binds to.
+    #[serde(default = "default_slack_listen")]
+    pub listen: String,
+}
+
+fn default_slack_listen() -> String {
+    "127.0.0.1:3978".to_string()
+}
+
+impl SlackBotConfig {
+    /// Creates a config for `channel_id` with secrets left to the
+    /// environment or keychain.
+    pub fn new(channel_id: impl Into<String>) -> Self {
+        Self {
+            bot_token: None,
+            signing_secret: None,
+            channel_id: Some(channel_id.into()),
+            listen: default_slack_listen(),
+        }
+    }
+
+    /// Validates that the channel and both secrets are available.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let missing = |field: &str, hint: &str| ConfigError::RobotMissingField {
+            field: format!("RObot.slack.{field}"),
+            hint: hint.to_string(),
+        };
+        if self.channel_id.as_deref().is_none_or(str::is_empty) {
+            return Err(missing(
+                "channel_id",
+                "Run `ralph bot onboard --slack` or set RObot.slack.channel_id in config",
+            ));
+        }
+        if self.resolve_bot_token().is_none() {
+            return Err(missing(
+                "bot_token",
+                "Run `ralph bot onboard --slack`, set RALPH_SLACK_BOT_TOKEN env var, or set RObot.slack.bot_token in config",
+            ));
+        }
+        if self.resolve_signing_secret().is_none() {
+            return Err(missing(
+                "signing_secret",
+                "Run `ralph bot onboard --slack`, set RALPH_SLACK_SIGNING_SECRET env var, or set RObot.slack.signing_secret in config",
+            ));
+        }
+        if self.listen.parse::<std::net::SocketAddr>().is_err() {
+            return Err(missing(
+                "listen",
+                "listen must be an address like 127.0.0.1:3978",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resolves the bot token from `RALPH_SLACK_BOT_TOKEN`, the config, or
+    /// the keychain (service: "ralph", user: "slack-bot-token").
+    pub fn resolve_bot_token(&self) -> Option<String> {
+        resolve_robot_secret(
+            "RALPH_SLACK_BOT_TOKEN",
+            self.bot_token.clone(),
+            "slack-bot-token",
+        )
+    }
+
+    /// Resolves the signing secret from `RALPH_SLACK_SIGNING_SECRET`, the
+    /// config, or the keychain (service: "ralph", user: "slack-signing-secret").
+    pub fn resolve_signing_secret(&self) -> Option<String> {
+        resolve_robot_secret(
+            "RALPH_SLACK_SIGNING_SECRET",
+            self.signing_secret.clone(),
+            "slack-signing-secret",
+        )
+    }
+}
+
+/// Which loop lifecycle notifications the RObot sends.
+///
+/// Start and terminal states are announced by default; user-initiated stops
+/// (Ctrl+C, `/stop`, `/restart`) are not, since the human already knows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobotNotificationsConfig {
+    /// Announce loop start (loop ID, backend, prompt summary).
+    #[serde(default = "default_true")]
+    pub start: bool,
+
+    /// Announce successful completion.
+    #[serde(default = "default_true")]
+    pub completion: bool,
+
+    /// Announce failures (consecutive failures, validation failures, failed gates).
+    #[serde(default = "default_true")]
+    pub failure: bool,
+
+    /// Announce safeguards firing (iteration, runtime, or cost limits; thrashing).
+    #[serde(default = "default_true")]
+    pub safeguard: bool,
+
+    /// Announce user-initiated stops, interrupts, and restarts.
+    #[serde(default)]
+    pub interrupted: bool,
+
+    /// Announce preflight failures that keep the loop from starting.
+    #[serde(default = "default_true")]
+    pub preflight: bool,
+
+    /// Maximum notifications per hour across restarts (0 = unlimited).
+    #[serde(default = "default_notifications_per_hour")]
+    pub max_per_hour: u32,
+}
+
+fn default_notifications_per_hour() -> u32 {
+    20
+}
+
+impl Default for RobotNotificationsConfig {
+    fn default() -> Self {
+        Self {
+            start: true,
+            completion: true,
+            failure: true,
+            safeguard: true,
+            interrupted: false,
+            preflight: true,
+            max_per_hour: default_notifications_per_hour(),
+        }
+    }
+}
+
+impl RobotNotificationsConfig {
+    /// Returns true if a loop ending with `reason` should be announced.
+    pub fn notifies_termination(&self, reason: &TerminationReason) -> bool {
+        match reason {
+            TerminationReason::CompletionPromise | TerminationReason::UntilEventReached => {
+                self.completion
+            }
+            TerminationReason::ConsecutiveFailures
+            | TerminationReason::ValidationFailure
+            | TerminationReason::GateFailed
+            | TerminationReason::EventQueueOverflow
+            | TerminationReason::CompletionRejected
+            | TerminationReason::NoMatchingHat { .. } => self.failure,
+            TerminationReason::MaxIterations
+            | TerminationReason::MaxRuntime
+            | TerminationReason::MaxCost
+            | TerminationReason::HatBudgetExceeded
+            | TerminationReason::LoopThrashing => self.safeguard,
+            TerminationReason::Stopped
+            | TerminationReason::Interrupted
+            | TerminationReason::RestartRequested => self.interrupted,
+        }
+    }
+}
+
 /// Configuration errors.
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -1395,15 +3107,59 @@ pub enum ConfigError {
     #[error("Invalid completion_promise: must be non-empty and non-whitespace")]
     InvalidCompletionPromise,
 
+    #[error("Invalid completion_promise_regex: {0}")]
+    InvalidCompletionPromiseRegex(String),
+
+    #[error("Invalid event_loop.completion_check: command must be non-empty")]
+    InvalidCompletionCheck,
+
+    #[error("Invalid redact pattern (features.capture.redact or security.redact): {0}")]
+    InvalidRedactPattern(String),
+
+    #[error(
+        "Invalid event_schemas entry for '{topic}': {message}\nFix: make the entry a valid JSON schema (e.g. {{ type: object, required: [summary] }})."
+    )]
+    InvalidEventSchema { topic: String, message: String },
+
+    #[error("Invalid event_loop.queue.max_depth: must be at least 1")]
+    InvalidQueueDepth,
+
+    #[error("Invalid hooks[{0}]: 'on' and 'run' must be non-empty")]
+    InvalidHook(usize),
+
     #[error(
         "Custom backend requires a command.\nFix: set 'cli.command' in your config (or run `ralph init --backend custom`).\nSee: docs/reference/troubleshooting.md#custom-backend-command"
     )]
     CustomBackendRequiresCommand,
 
+    #[error(
+        "Invalid custom backend '{name}': {message}.\nFix: update 'adapters.custom_backends.{name}' in your config."
+    )]
+    InvalidCustomBackend { name: String, message: String },
+
     #[error(
         "Reserved trigger '{trigger}' used by hat '{hat}' - task.start and task.resume are reserved for Ralph (the coordinator). Use a delegated event like 'work.start' instead.\nSee: docs/reference/troubleshooting.md#reserved-trigger"
     )]
-    ReservedTrigger { trigger: String, hat: String },
+    ReservedTrigger { trigger: String, hat: String },
+
+    #[error(
+        "Hat '{hat}' instructions_file '{path}' {reason}.\nFix: point 'hats.{hat}.instructions_file' at a non-empty file (relative paths resolve against the config file's directory)."
+    )]
+    InstructionsFile {
+        hat: String,
+        path: String,
+        reason: String,
+    },
+
+    #[error(
+        "Include '{path}' {reason}.\nFix: point each 'includes' entry at a non-empty file (relative paths resolve against the config file's directory)."
+    )]
+    IncludeFile { path: String, reason: String },
+
+    #[error(
+        "Hat '{hat}' instructions reference unknown template variables: {names}.\nAvailable: {{{{workspace_root}}}}, {{{{scratchpad}}}}, {{{{iteration}}}}, {{{{specs_dir}}}}. Write '\\{{{{' for a literal '{{{{', or turn off strict templates to leave unknown placeholders as written."
+    )]
+    UnknownTemplateVariables { hat: String, names: String },
 
     #[error(
         "Hat '{hat}' is missing required 'description' field - add a short description of the hat's purpose.\nSee: docs/reference/troubleshooting.md#missing-hat-description"
@@ -1465,6 +3221,8 @@ features:
     enabled: true
     strict: true
     skip: ["telegram", "git"]
+    min_free_disk_mb: 4096
+    require_clean_tree: true
 "#;
         let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
         assert!(config.features.preflight.enabled);
@@ -1473,6 +3231,9 @@ features:
             config.features.preflight.skip,
             vec!["telegram".to_string(), "git".to_string()]
         );
+        assert_eq!(config.features.preflight.min_free_disk_mb, 4096);
+        assert_eq!(config.features.preflight.disk_floor_mb, 512);
+        assert!(config.features.preflight.require_clean_tree);
     }
 
     #[test]
@@ -1517,6 +3278,164 @@ agent_priority: [gemini, claude, codex]
         assert_eq!(priority, vec!["gemini", "claude", "codex"]);
     }
 
+    #[test]
+    fn test_retry_config() {
+        let config = RalphConfig::default();
+        assert!(!config.event_loop.retry.enabled());
+
+        let yaml = r"
+event_loop:
+  retry:
+    max_attempts: 4
+    backoff_secs: 10
+    max_backoff_secs: 30
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let retry = &config.event_loop.retry;
+        assert!(retry.enabled());
+        assert_eq!(retry.backoff(1), Duration::from_secs(10));
+        assert_eq!(retry.backoff(2), Duration::from_secs(20));
+        assert_eq!(retry.backoff(3), Duration::from_secs(30));
+        assert_eq!(retry.backoff(40), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_event_queue_config() {
+        let config = RalphConfig::default();
+        assert_eq!(config.event_loop.queue.max_depth, 100);
+        assert_eq!(
+            config.event_loop.queue.on_overflow,
+            QueueOverflowPolicy::DropOldest
+        );
+
+        let yaml = r"
+event_loop:
+  queue:
+    max_depth: 5
+    on_overflow: block
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.event_loop.queue.max_depth, 5);
+        assert_eq!(
+            config.event_loop.queue.on_overflow,
+            QueueOverflowPolicy::Block
+        );
+        assert_eq!(config.event_loop.queue.unmatched_ttl, 3);
+
+        let config: RalphConfig =
+            serde_yaml::from_str("event_loop:\n  queue:\n    max_depth: 0\n").unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidQueueDepth)
+        ));
+    }
+
+    #[test]
+    fn test_event_schemas_parse_and_validate() {
+        let config: RalphConfig = serde_yaml::from_str(
+            r"
+event_loop:
+  schema_mismatch: warn
+event_schemas:
+  build.done:
+    type: object
+    required: [summary]
+",
+        )
+        .unwrap();
+        assert_eq!(
+            config.event_loop.schema_mismatch,
+            SchemaMismatchAction::Warn
+        );
+        assert_eq!(config.event_schemas["build.done"]["required"][0], "summary");
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            RalphConfig::default().event_loop.schema_mismatch,
+            SchemaMismatchAction::Reject
+        );
+
+        let config: RalphConfig =
+            serde_yaml::from_str("event_schemas:\n  build.done:\n    type: 42\n").unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidEventSchema { topic, .. }) if topic == "build.done"
+        ));
+    }
+
+    #[test]
+    fn test_hooks_parse_and_validate() {
+        let config: RalphConfig = serde_yaml::from_str(
+            r#"
+hooks:
+  - on: review.approved
+    run: curl -s -X POST "$SLACK_WEBHOOK"
+  - on: "commit.*"
+    run: ./ci-trigger.sh
+    timeout_secs: 5
+    on_failure: stop
+"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.hooks[0],
+            EventHookConfig::new("review.approved", r#"curl -s -X POST "$SLACK_WEBHOOK""#)
+        );
+        assert_eq!(config.hooks[0].timeout_secs, 30);
+        assert_eq!(config.hooks[1].timeout_secs, 5);
+        assert_eq!(config.hooks[1].on_failure, HookFailurePolicy::Stop);
+
+        let config: RalphConfig =
+            serde_yaml::from_str("hooks:\n  - on: build.done\n    run: ' '\n").unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidHook(0))
+        ));
+    }
+
+    #[test]
+    fn test_capture_redact_patterns() {
+        let config = RalphConfig::default();
+        assert!(!config.features.capture.redact.is_empty());
+
+        let config: RalphConfig =
+            serde_yaml::from_str("features:\n  capture:\n    redact: []\n").unwrap();
+        assert!(config.features.capture.redactor().unwrap().is_empty());
+
+        let config: RalphConfig =
+            serde_yaml::from_str("features:\n  capture:\n    redact: [\"(unclosed\"]\n").unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidRedactPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_security_redact_rules() {
+        let config: RalphConfig = serde_yaml::from_str(
+            "security:\n  redact:\n    - name: db_url\n      pattern: \"postgres://\\\\S+\"\n  secret_env: [RALPH_TEST_UNSET_SECRET_ENV]\n",
+        )
+        .unwrap();
+        assert_eq!(config.security.redact[0].name, "db_url");
+        let redactor = config
+            .security
+            .apply(crate::redact::Redactor::default())
+            .unwrap();
+        assert_eq!(
+            redactor.redact("url postgres://u:p@db/app"),
+            "url [REDACTED:db_url]"
+        );
+
+        let config: RalphConfig = serde_yaml::from_str(
+            "security:\n  redact:\n    - name: bad\n      pattern: \"(unclosed\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidRedactPattern(_))
+        ));
+    }
+
     #[test]
     fn test_default_agent_priority() {
         let config = RalphConfig::default();
@@ -1524,6 +3443,66 @@ agent_priority: [gemini, claude, codex]
         assert_eq!(priority, vec!["claude", "kiro", "gemini", "codex", "amp"]);
     }
 
+    #[test]
+    fn test_custom_backends() {
+        let yaml = r#"
+adapters:
+  custom_backends:
+    aider:
+      command: "aider"
+      prompt_mode: flag
+      prompt_flag: "--message"
+      args: ["--yes-always"]
+      probe: ["aider", "--help"]
+      timeout: 600
+    kiro:
+      command: "/opt/kiro/bin/kiro-cli"
+      prompt_mode: stdin
+      output_format: stream-json
+      enabled: false
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        config.validate().unwrap();
+
+        let aider = &config.adapters.custom_backends["aider"];
+        assert_eq!(aider.prompt_mode, CustomPromptMode::Flag);
+        assert_eq!(aider.output_format, CustomOutputFormat::Text);
+        assert_eq!(aider.probe_command(), vec!["aider", "--help"]);
+        assert_eq!(config.adapter_settings("aider").timeout, 600);
+
+        let kiro = &config.adapters.custom_backends["kiro"];
+        assert_eq!(kiro.output_format, CustomOutputFormat::StreamJson);
+        assert_eq!(
+            kiro.probe_command(),
+            vec!["/opt/kiro/bin/kiro-cli", "--version"]
+        );
+        assert!(!config.adapter_settings("kiro").enabled);
+
+        // Custom names join the default priority after the built-ins
+        assert_eq!(
+            config.get_agent_priority(),
+            vec!["claude", "kiro", "gemini", "codex", "amp", "aider"]
+        );
+    }
+
+    #[test]
+    fn test_custom_backend_flag_mode_requires_flag() {
+        let yaml = r#"
+adapters:
+  custom_backends:
+    aider:
+      command: "aider"
+      prompt_mode: flag
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::InvalidCustomBackend { name, .. } if name == "aider"
+        ));
+        assert!(err.to_string().contains("prompt_flag"));
+    }
+
     #[test]
     fn test_validate_deferred_features() {
         let yaml = r"
@@ -1876,6 +3855,95 @@ event_loop:
         );
     }
 
+    #[test]
+    fn test_completion_check_parses_with_defaults() {
+        let yaml = r#"
+event_loop:
+  completion_check:
+    command: "cargo test"
+"#;
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        assert_eq!(
+            config.event_loop.completion_check,
+            Some(CompletionCheckConfig::new("cargo test"))
+        );
+        assert_eq!(
+            config.event_loop.completion_check.unwrap().max_rejections,
+            3
+        );
+    }
+
+    #[test]
+    fn test_completion_check_rejects_blank_command() {
+        let mut config = RalphConfig::default();
+        config.event_loop.completion_check = Some(CompletionCheckConfig::new("  "));
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidCompletionCheck)
+        ));
+    }
+
+    #[test]
+    fn test_completion_promise_accepts_list() {
+        let yaml = r#"
+event_loop:
+  completion_promise: ["LOOP_COMPLETE", "REVIEW_DONE"]
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let promises = &config.event_loop.completion_promise;
+
+        assert_eq!(promises.as_slice(), ["LOOP_COMPLETE", "REVIEW_DONE"]);
+        assert_eq!(promises.primary(), "LOOP_COMPLETE");
+        assert!(promises.contains("REVIEW_DONE"));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_completion_promise_single_string_round_trips() {
+        let config: RalphConfig =
+            serde_yaml::from_str("event_loop:\n  completion_promise: DONE\n").unwrap();
+        assert_eq!(config.event_loop.completion_promise, "DONE");
+
+        let yaml = serde_yaml::to_string(&config.event_loop).unwrap();
+        assert!(yaml.contains("completion_promise: DONE"));
+    }
+
+    #[test]
+    fn test_completion_promise_parse_list() {
+        let promises = CompletionPromises::parse_list("LOOP_COMPLETE, DONE,,");
+        assert_eq!(promises.as_slice(), ["LOOP_COMPLETE", "DONE"]);
+    }
+
+    #[test]
+    fn test_invalid_completion_promise_regex_rejected() {
+        let yaml = r#"
+event_loop:
+  completion_promise_regex: "(unclosed"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::InvalidCompletionPromiseRegex(_)),
+            "Expected InvalidCompletionPromiseRegex error, got: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_completion_matcher_literal_and_regex() {
+        let yaml = r#"
+event_loop:
+  completion_promise: ["LOOP_COMPLETE", "SHIP_IT"]
+  completion_promise_regex: "(?i)^loop_complete\\W*$"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let matcher = CompletionMatcher::from_config(&config.event_loop);
+
+        assert!(matcher.matches("SHIP_IT"));
+        assert!(matcher.matches("loop_complete."));
+        assert!(!matcher.matches("build.done"));
+    }
+
     #[test]
     fn test_custom_backend_with_empty_command_errors() {
         // Custom backend with empty command should error
@@ -2009,6 +4077,61 @@ tui:
         assert!(result.unwrap_err().contains("Invalid key"));
     }
 
+    #[test]
+    fn test_strict_templates_rejects_unknown_variables() {
+        let yaml = r"
+hats:
+  builder:
+    name: Builder
+    description: Builds
+    triggers: [build.task]
+    instructions: Read {{scratchpad}} for {{ticket}}
+";
+        let mut config = RalphConfig::parse_yaml(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        config.event_loop.strict_templates = true;
+        let err = config.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::UnknownTemplateVariables { hat, names } if hat == "builder" && names == "ticket"),
+            "{err}"
+        );
+        assert!(err.to_string().contains("{{workspace_root}}"), "{err}");
+
+        config.hats.get_mut("builder").unwrap().instructions = r"Read \{{ticket}}".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_web_config_defaults_to_localhost_with_token() {
+        let config = RalphConfig::default();
+        assert_eq!(config.web.bind, "127.0.0.1");
+        assert!(!config.web.allow_public);
+        assert!(config.web.token.is_none());
+        assert_eq!(config.web.auth.read, WebAuthMode::Token);
+        assert_eq!(config.web.auth.mutate, WebAuthMode::Token);
+        assert!(config.web.auth.requires_token());
+    }
+
+    #[test]
+    fn test_web_config_parse_public_reads() {
+        let yaml = r"
+web:
+  bind: 0.0.0.0
+  allow_public: true
+  token: keychain:web-token
+  auth:
+    read: none
+";
+        let config = RalphConfig::parse_yaml(yaml).unwrap();
+        assert_eq!(config.web.bind, "0.0.0.0");
+        assert!(config.web.allow_public);
+        assert_eq!(config.web.token.as_deref(), Some("keychain:web-token"));
+        assert_eq!(config.web.auth.read, WebAuthMode::None);
+        assert_eq!(config.web.auth.mutate, WebAuthMode::Token);
+        assert!(!WebAuthConfig::NONE.requires_token());
+    }
+
     #[test]
     fn test_hat_backend_named() {
         let yaml = r#""claude""#;
@@ -2255,6 +4378,43 @@ features:
         );
     }
 
+    #[test]
+    fn test_prompt_vars_from_yaml() {
+        let yaml = r"
+vars:
+  service: billing
+  ticket: 42
+  dry: true
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.vars["service"], "billing");
+        assert_eq!(config.vars["ticket"], "42");
+        assert_eq!(config.vars["dry"], "true");
+
+        let err = serde_yaml::from_str::<RalphConfig>("vars:\n  nested: [1, 2]\n").unwrap_err();
+        assert!(err.to_string().contains("vars.nested"));
+    }
+
+    #[test]
+    fn test_features_config_auto_merge_conflict_from_yaml() {
+        let config = RalphConfig::default();
+        assert_eq!(
+            config.features.auto_merge_conflict,
+            AutoMergeConflictPolicy::Abort
+        );
+
+        let yaml = r"
+features:
+  auto_merge: true
+  auto_merge_conflict: prefer-theirs
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.features.auto_merge_conflict,
+            AutoMergeConflictPolicy::PreferTheirs
+        );
+    }
+
     #[test]
     fn test_features_config_auto_merge_false_from_yaml() {
         // Explicit false should work too
@@ -2440,6 +4600,8 @@ RObot:
             timeout_seconds: None,
             checkin_interval_seconds: None,
             telegram: None,
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -2464,6 +4626,8 @@ RObot:
             telegram: Some(TelegramBotConfig {
                 bot_token: Some("config-token".to_string()),
             }),
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
 
         // When RALPH_TELEGRAM_BOT_TOKEN is not set, config token is returned
@@ -2482,6 +4646,8 @@ RObot:
             timeout_seconds: Some(300),
             checkin_interval_seconds: None,
             telegram: None,
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
 
         // Without env var AND without config token, resolve returns None
@@ -2502,6 +4668,8 @@ RObot:
             telegram: Some(TelegramBotConfig {
                 bot_token: Some("test-token".to_string()),
             }),
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
         assert!(robot.validate().is_ok());
     }
@@ -2519,6 +4687,8 @@ RObot:
             timeout_seconds: Some(300),
             checkin_interval_seconds: None,
             telegram: None,
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -2544,6 +4714,8 @@ RObot:
             timeout_seconds: Some(300),
             checkin_interval_seconds: None,
             telegram: Some(TelegramBotConfig { bot_token: None }),
+            slack: None,
+            notifications: RobotNotificationsConfig::default(),
         };
         let result = robot.validate();
         assert!(result.is_err());
@@ -2556,6 +4728,65 @@ RObot:
         );
     }
 
+    #[test]
+    fn test_robot_config_slack_only_validates_without_telegram_token() {
+        if std::env::var("RALPH_TELEGRAM_BOT_TOKEN").is_ok() {
+            return;
+        }
+
+        let yaml = r#"
+RObot:
+  enabled: true
+  timeout_seconds: 300
+  slack:
+    bot_token: "xoxb-test"
+    signing_secret: "secret"
+    channel_id: "C0123456789"
+"#;
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+        let slack = config.robot.slack.as_ref().unwrap();
+        assert_eq!(slack.listen, "127.0.0.1:3978");
+        assert_eq!(slack.resolve_bot_token().as_deref(), Some("xoxb-test"));
+        assert!(config.validate().is_ok());
+
+        // Naming a telegram section still requires its token
+        let mut robot = config.robot.clone();
+        robot.telegram = Some(TelegramBotConfig { bot_token: None });
+        let err = robot.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::RobotMissingField { field, .. }
+                if field == "RObot.telegram.bot_token"),
+            "got: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_robot_config_slack_requires_channel_and_secret() {
+        if std::env::var("RALPH_SLACK_SIGNING_SECRET").is_ok() {
+            return;
+        }
+
+        let mut slack = SlackBotConfig::new("");
+        slack.bot_token = Some("xoxb-test".to_string());
+        let err = slack.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::RobotMissingField { field, .. }
+                if field == "RObot.slack.channel_id"),
+            "got: {err:?}"
+        );
+
+        slack.channel_id = Some("C0123456789".to_string());
+        let err = slack.validate().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::RobotMissingField { field, .. }
+                if field == "RObot.slack.signing_secret"),
+            "got: {err:?}"
+        );
+
+        slack.signing_secret = Some("secret".to_string());
+        assert!(slack.validate().is_ok());
+    }
+
     #[test]
     fn test_extra_instructions_merged_during_normalize() {
         let yaml = r#"
@@ -2604,4 +4835,196 @@ hats:
         let hat = config.hats.get("simple").unwrap();
         assert!(hat.extra_instructions.is_empty());
     }
+
+    const INSTRUCTIONS_FILE_YAML: &str = r#"
+hats:
+  builder:
+    name: "Builder"
+    description: "Builds things"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    instructions_file: prompts/builder.md
+"#;
+
+    #[test]
+    fn test_instructions_file_resolves_relative_to_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        std::fs::write(
+            dir.path().join("prompts/builder.md"),
+            "## BUILDER\nBuild it.\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("ralph.yml"), INSTRUCTIONS_FILE_YAML).unwrap();
+
+        let config = RalphConfig::from_file(dir.path().join("ralph.yml")).unwrap();
+        let hat = &config.hats["builder"];
+        assert_eq!(hat.instructions, "## BUILDER\nBuild it.\n");
+        assert_eq!(
+            hat.instructions_file.as_deref(),
+            Some(dir.path().join("prompts/builder.md").as_path())
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_instructions_file_accepts_absolute_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("builder.md");
+        std::fs::write(&file, "Build it.").unwrap();
+        let yaml =
+            INSTRUCTIONS_FILE_YAML.replace("prompts/builder.md", &file.display().to_string());
+
+        let config = RalphConfig::parse_yaml(&yaml).unwrap();
+        assert_eq!(config.hats["builder"].instructions, "Build it.");
+    }
+
+    #[test]
+    fn test_instructions_file_missing_or_empty_names_hat_and_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ralph.yml"), INSTRUCTIONS_FILE_YAML).unwrap();
+
+        let err = RalphConfig::from_file(dir.path().join("ralph.yml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Hat 'builder'"), "{err}");
+        assert!(err.contains("prompts/builder.md' does not exist"), "{err}");
+
+        std::fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        std::fs::write(dir.path().join("prompts/builder.md"), "  \n").unwrap();
+        let err = RalphConfig::from_file(dir.path().join("ralph.yml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is empty"), "{err}");
+    }
+
+    #[test]
+    fn test_instructions_file_conflicts_with_inline_instructions() {
+        let yaml = format!("{INSTRUCTIONS_FILE_YAML}    instructions: \"Inline\"\n");
+        let err = RalphConfig::parse_yaml(&yaml).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::MutuallyExclusive { ref field1, ref field2 }
+                if field1 == "hats.builder.instructions"
+                    && field2 == "hats.builder.instructions_file"
+        ));
+    }
+
+    #[test]
+    fn test_instructions_file_in_worktree_resolves_against_main_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let worktree = repo.join(".worktrees/loop-1");
+        std::fs::create_dir_all(repo.join(".git/worktrees/loop-1")).unwrap();
+        std::fs::create_dir_all(repo.join("prompts")).unwrap();
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(repo.join("prompts/builder.md"), "Build it.").unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", repo.join(".git/worktrees/loop-1").display()),
+        )
+        .unwrap();
+        std::fs::write(worktree.join("ralph.yml"), INSTRUCTIONS_FILE_YAML).unwrap();
+
+        let config = RalphConfig::from_file(worktree.join("ralph.yml")).unwrap();
+        let hat = &config.hats["builder"];
+        assert_eq!(hat.instructions, "Build it.");
+        assert_eq!(
+            hat.instructions_file.as_deref(),
+            Some(
+                repo.canonicalize()
+                    .unwrap()
+                    .join("prompts/builder.md")
+                    .as_path()
+            )
+        );
+    }
+
+    #[test]
+    fn test_includes_load_in_order_without_touching_hats() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        std::fs::write(dir.path().join("prompts/builder.md"), "Build it.\n").unwrap();
+        std::fs::write(dir.path().join("prompts/style.md"), "Keep it short.").unwrap();
+        std::fs::write(dir.path().join("prompts/safety.md"), "Never force-push.\n").unwrap();
+        let yaml = format!(
+            "includes:\n  - prompts/style.md\n  - prompts/safety.md\n{INSTRUCTIONS_FILE_YAML}  reviewer:\n    name: \"Reviewer\"\n    description: \"Reviews things\"\n    triggers: [\"build.done\"]\n    instructions: \"Review it.\"\n"
+        );
+        std::fs::write(dir.path().join("ralph.yml"), yaml).unwrap();
+
+        let config = RalphConfig::from_file(dir.path().join("ralph.yml")).unwrap();
+        assert_eq!(
+            config.include_preamble,
+            "Keep it short.\nNever force-push.\n"
+        );
+        assert_eq!(config.hats["builder"].instructions, "Build it.\n");
+        assert_eq!(config.hats["reviewer"].instructions, "Review it.");
+        assert_eq!(
+            config.includes,
+            vec![
+                dir.path().join("prompts/style.md"),
+                dir.path().join("prompts/safety.md")
+            ]
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_includes_missing_file_errors_with_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("ralph.yml"),
+            "includes:\n  - prompts/missing.md\n",
+        )
+        .unwrap();
+
+        let err = RalphConfig::from_file(dir.path().join("ralph.yml")).unwrap_err();
+        assert!(matches!(err, ConfigError::IncludeFile { .. }), "{err}");
+        let err = err.to_string();
+        assert!(err.contains("prompts/missing.md' does not exist"), "{err}");
+    }
+
+    #[test]
+    fn test_cli_pricing_parses_and_matches_model_prefix() {
+        let yaml = r"
+cli:
+  pricing:
+    claude-sonnet-4:
+      input: 3.0
+      output: 15.0
+      cache_write: 3.75
+      cache_read: 0.3
+    claude-sonnet-4-5:
+      input: 4.0
+";
+        let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+
+        let sonnet = config
+            .cli
+            .pricing_for("claude-sonnet-4-20250514")
+            .expect("prefix match");
+        assert!((sonnet.cost(1_000_000, 100_000, 0, 1_000_000) - 4.8).abs() < 1e-9);
+        let newer = config
+            .cli
+            .pricing_for("claude-sonnet-4-5-20250929")
+            .expect("longest prefix wins");
+        assert!((newer.input - 4.0).abs() < f64::EPSILON);
+        assert!(newer.output.abs() < f64::EPSILON);
+        assert!(config.cli.pricing_for("gpt-5").is_none());
+    }
+
+    #[test]
+    fn test_cli_warmup_defaults_to_autonomous_mode() {
+        let mut cli = CliConfig::default();
+        assert_eq!(cli.default_mode, "autonomous");
+        assert!(cli.warmup_enabled());
+
+        cli.default_mode = "interactive".to_string();
+        assert!(!cli.warmup_enabled());
+
+        let config: RalphConfig = serde_yaml::from_str("cli:\n  warmup: true\n").unwrap();
+        let mut cli = config.cli;
+        cli.default_mode = "interactive".to_string();
+        assert!(cli.warmup_enabled());
+    }
 }
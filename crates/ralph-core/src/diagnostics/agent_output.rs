@@ -78,7 +78,7 @@ impl AgentOutputLogger {
             content,
         };
 
-        let json = serde_json::to_string(&entry)?;
+        let json = crate::redact::installed_redactor().to_json_string(&entry)?;
         writeln!(self.file, "{}", json)?;
         self.file.flush()?;
 
@@ -150,7 +150,7 @@ impl ErrorLogger {
             context: error.context(),
         };
 
-        if let Ok(json) = serde_json::to_string(&entry) {
+        if let Ok(json) = crate::redact::installed_redactor().to_json_string(&entry) {
             let _ = writeln!(self.file, "{}", json);
             let _ = self.file.flush();
         }
@@ -8,6 +8,7 @@ mod errors;
 mod log_rotation;
 mod orchestration;
 mod performance;
+mod retention;
 mod stream_handler;
 mod trace_layer;
 
@@ -19,9 +20,11 @@ pub use errors::{DiagnosticError, ErrorLogger};
 pub use log_rotation::{create_log_file, rotate_logs};
 pub use orchestration::{OrchestrationEvent, OrchestrationLogger};
 pub use performance::{PerformanceLogger, PerformanceMetric};
+pub use retention::{prune_sessions, session_dirs};
 pub use stream_handler::DiagnosticStreamHandler;
 pub use trace_layer::{DiagnosticTraceLayer, TraceEntry};
 
+use crate::config::DiagnosticsConfig;
 use chrono::Local;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -44,22 +47,41 @@ impl DiagnosticsCollector {
     ///
     /// If `RALPH_DIAGNOSTICS=1`, creates `.ralph/diagnostics/<timestamp>/` directory.
     pub fn new(base_path: &Path) -> std::io::Result<Self> {
+        Self::with_config(base_path, &DiagnosticsConfig::default())
+    }
+
+    /// Creates a new diagnostics collector with configured session retention.
+    ///
+    /// If `RALPH_DIAGNOSTICS=1`, creates the session directory, then prunes
+    /// the oldest sessions past the configured limits.
+    pub fn with_config(base_path: &Path, config: &DiagnosticsConfig) -> std::io::Result<Self> {
         let enabled = std::env::var("RALPH_DIAGNOSTICS")
             .map(|v| v == "1")
             .unwrap_or(false);
 
-        Self::with_enabled(base_path, enabled)
+        Self::create(base_path, enabled, config)
     }
 
     /// Creates a diagnostics collector with explicit enabled flag (for testing).
     pub fn with_enabled(base_path: &Path, enabled: bool) -> std::io::Result<Self> {
+        Self::create(base_path, enabled, &DiagnosticsConfig::default())
+    }
+
+    fn create(
+        base_path: &Path,
+        enabled: bool,
+        config: &DiagnosticsConfig,
+    ) -> std::io::Result<Self> {
         let (session_dir, orchestration_logger, performance_logger, error_logger) = if enabled {
-            let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S");
-            let dir = base_path
-                .join(".ralph")
-                .join("diagnostics")
-                .join(timestamp.to_string());
+            let diagnostics_dir = base_path.join(".ralph").join("diagnostics");
+            let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+            let dir = diagnostics_dir.join(timestamp);
             fs::create_dir_all(&dir)?;
+            retention::prune_sessions(
+                &diagnostics_dir,
+                config.max_sessions,
+                config.max_total_mb.saturating_mul(1024 * 1024),
+            )?;
 
             let orch_logger = orchestration::OrchestrationLogger::new(&dir)?;
             let perf_logger = performance::PerformanceLogger::new(&dir)?;
@@ -104,6 +126,14 @@ impl DiagnosticsCollector {
         self.session_dir.as_deref()
     }
 
+    /// Returns the most recent session directory under `.ralph/diagnostics/`.
+    ///
+    /// Session directories are named by timestamp, so the lexicographically
+    /// greatest name is the newest. The `logs/` directory is not a session.
+    pub fn latest_session_dir(base_path: &Path) -> Option<PathBuf> {
+        session_dirs(&base_path.join(".ralph").join("diagnostics")).pop()
+    }
+
     /// Wraps a stream handler with diagnostic logging.
     ///
     /// Returns the original handler if diagnostics are disabled.
@@ -200,6 +230,51 @@ mod tests {
         assert!(dir_name.chars().nth(16) == Some('-'));
     }
 
+    #[test]
+    fn test_latest_session_dir_skips_logs() {
+        let temp = TempDir::new().unwrap();
+        assert!(DiagnosticsCollector::latest_session_dir(temp.path()).is_none());
+
+        let diagnostics_dir = temp.path().join(".ralph").join("diagnostics");
+        for name in ["2024-01-21T08-49-56", "2024-03-02T10-00-00", "logs"] {
+            fs::create_dir_all(diagnostics_dir.join(name)).unwrap();
+        }
+
+        assert_eq!(
+            DiagnosticsCollector::latest_session_dir(temp.path()),
+            Some(diagnostics_dir.join("2024-03-02T10-00-00"))
+        );
+    }
+
+    #[test]
+    fn test_new_session_prunes_oldest_past_max_sessions() {
+        let temp = TempDir::new().unwrap();
+        let diagnostics_dir = temp.path().join(".ralph").join("diagnostics");
+        let old_sessions = [
+            "2024-01-01T00-00-00",
+            "2024-01-02T00-00-00",
+            "2024-01-03T00-00-00",
+        ];
+        for name in old_sessions {
+            fs::create_dir_all(diagnostics_dir.join(name)).unwrap();
+        }
+        let config = DiagnosticsConfig {
+            max_sessions: 3,
+            ..DiagnosticsConfig::default()
+        };
+
+        let collector = DiagnosticsCollector::create(temp.path(), true, &config).unwrap();
+
+        let sessions = session_dirs(&diagnostics_dir);
+        assert_eq!(sessions.len(), 3);
+        assert!(!diagnostics_dir.join(old_sessions[0]).exists());
+        assert!(diagnostics_dir.join(old_sessions[1]).exists());
+        assert_eq!(
+            sessions.last().map(PathBuf::as_path),
+            collector.session_dir()
+        );
+    }
+
     #[test]
     fn test_performance_logger_integration() {
         let temp = TempDir::new().unwrap();
@@ -14,12 +14,27 @@ pub struct OrchestrationEntry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum OrchestrationEvent {
-    IterationStarted,
-    HatSelected { hat: String, reason: String },
-    EventPublished { topic: String },
-    BackpressureTriggered { reason: String },
-    LoopTerminated { reason: String },
-    TaskAbandoned { reason: String },
+    IterationStarted {
+        /// Events waiting in the queue when the iteration started.
+        #[serde(default)]
+        queue_depth: usize,
+    },
+    HatSelected {
+        hat: String,
+        reason: String,
+    },
+    EventPublished {
+        topic: String,
+    },
+    BackpressureTriggered {
+        reason: String,
+    },
+    LoopTerminated {
+        reason: String,
+    },
+    TaskAbandoned {
+        reason: String,
+    },
 }
 
 pub struct OrchestrationLogger {
@@ -49,8 +64,8 @@ impl OrchestrationLogger {
             hat: hat.to_string(),
             event,
         };
-        serde_json::to_writer(&mut self.writer, &entry)?;
-        self.writer.write_all(b"\n")?;
+        let json = crate::redact::installed_redactor().to_json_string(&entry)?;
+        writeln!(self.writer, "{}", json)?;
         self.writer.flush()?;
         Ok(())
     }
@@ -65,7 +80,7 @@ mod tests {
     #[test]
     fn test_all_event_types_serialize() {
         let events = vec![
-            OrchestrationEvent::IterationStarted,
+            OrchestrationEvent::IterationStarted { queue_depth: 0 },
             OrchestrationEvent::HatSelected {
                 hat: "ralph".to_string(),
                 reason: "pending_events".to_string(),
@@ -123,7 +138,11 @@ mod tests {
         let mut logger = OrchestrationLogger::new(temp_dir.path()).unwrap();
 
         logger
-            .log(1, "ralph", OrchestrationEvent::IterationStarted)
+            .log(
+                1,
+                "ralph",
+                OrchestrationEvent::IterationStarted { queue_depth: 0 },
+            )
             .unwrap();
 
         // Don't drop logger - verify file has content immediately
@@ -0,0 +1,158 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Lists session directories under `diagnostics_dir`, oldest first.
+///
+/// Session directories are named by timestamp, so lexicographic order is
+/// chronological. The `logs/` directory is not a session.
+pub fn session_dirs(diagnostics_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(diagnostics_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()) && entry.file_name() != "logs")
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Deletes the oldest sessions past the retention limits.
+///
+/// Keeps at most `max_sessions` sessions, then drops the oldest until the
+/// remaining sessions total at most `max_total_bytes`. The newest session is
+/// always kept, and a limit of 0 is ignored. Returns the deleted session
+/// directories.
+pub fn prune_sessions(
+    diagnostics_dir: &Path,
+    max_sessions: usize,
+    max_total_bytes: u64,
+) -> io::Result<Vec<PathBuf>> {
+    let sessions = session_dirs(diagnostics_dir);
+    let mut sizes: Vec<u64> = sessions.iter().map(|dir| dir_size(dir)).collect();
+    let mut total: u64 = sizes.iter().sum();
+
+    let removable = sessions.len().saturating_sub(1);
+    let mut to_remove = 0;
+    if max_sessions > 0 {
+        to_remove = sessions.len().saturating_sub(max_sessions).min(removable);
+    }
+    total -= sizes.drain(..to_remove).sum::<u64>();
+    if max_total_bytes > 0 {
+        for size in sizes {
+            if total <= max_total_bytes || to_remove == removable {
+                break;
+            }
+            total -= size;
+            to_remove += 1;
+        }
+    }
+
+    let removed = sessions[..to_remove].to_vec();
+    for dir in &removed {
+        fs::remove_dir_all(dir)?;
+    }
+    Ok(removed)
+}
+
+/// Total size of the files under `dir`, recursively.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_session(dir: &Path, name: &str, bytes: usize) {
+        let session = dir.join(name);
+        fs::create_dir_all(&session).unwrap();
+        fs::write(session.join("trace.jsonl"), "x".repeat(bytes)).unwrap();
+    }
+
+    #[test]
+    fn test_prune_by_count_removes_oldest() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("logs")).unwrap();
+        for name in [
+            "2024-01-01T00-00-00",
+            "2024-01-02T00-00-00",
+            "2024-01-03T00-00-00",
+        ] {
+            create_session(tmp.path(), name, 10);
+        }
+
+        let removed = prune_sessions(tmp.path(), 2, 0).unwrap();
+
+        assert_eq!(removed, vec![tmp.path().join("2024-01-01T00-00-00")]);
+        assert_eq!(
+            session_dirs(tmp.path()),
+            vec![
+                tmp.path().join("2024-01-02T00-00-00"),
+                tmp.path().join("2024-01-03T00-00-00"),
+            ]
+        );
+        assert!(tmp.path().join("logs").exists());
+    }
+
+    #[test]
+    fn test_prune_by_size_removes_oldest_until_under_cap() {
+        let tmp = TempDir::new().unwrap();
+        for name in [
+            "2024-01-01T00-00-00",
+            "2024-01-02T00-00-00",
+            "2024-01-03T00-00-00",
+        ] {
+            create_session(tmp.path(), name, 100);
+        }
+
+        let removed = prune_sessions(tmp.path(), 0, 150).unwrap();
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            session_dirs(tmp.path()),
+            vec![tmp.path().join("2024-01-03T00-00-00")]
+        );
+    }
+
+    #[test]
+    fn test_newest_session_is_always_kept() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["2024-01-01T00-00-00", "2024-01-02T00-00-00"] {
+            create_session(tmp.path(), name, 100);
+        }
+
+        prune_sessions(tmp.path(), 1, 1).unwrap();
+
+        assert_eq!(
+            session_dirs(tmp.path()),
+            vec![tmp.path().join("2024-01-02T00-00-00")]
+        );
+    }
+
+    #[test]
+    fn test_zero_limits_keep_everything() {
+        let tmp = TempDir::new().unwrap();
+        create_session(tmp.path(), "2024-01-01T00-00-00", 100);
+
+        assert!(prune_sessions(tmp.path(), 0, 0).unwrap().is_empty());
+        assert!(
+            prune_sessions(&tmp.path().join("missing"), 1, 1)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -76,7 +76,7 @@ impl<S: Subscriber> Layer<S> for DiagnosticTraceLayer {
 
         // Write to file
         let mut writer = self.writer.lock().unwrap();
-        if let Ok(json) = serde_json::to_string(&entry) {
+        if let Ok(json) = crate::redact::installed_redactor().to_json_string(&entry) {
             let _ = writeln!(writer, "{}", json);
             let _ = writer.flush();
         }
@@ -0,0 +1,240 @@
+//! Value sources for `cli.env` and hat `env` entries.
+//!
+//! A value is either a literal, which may reference Ralph's own environment as
+//! `${VAR}`, or a `keychain:<entry>` lookup in the OS keychain under the
+//! `ralph` service (the same store that holds the Telegram bot token).
+//! Values from the environment or the keychain count as secrets: they are
+//! masked in logs and session recordings.
+
+use std::collections::BTreeMap;
+
+use crate::config::RalphConfig;
+
+/// Prefix marking a value read from the OS keychain.
+pub const KEYCHAIN_PREFIX: &str = "keychain:";
+
+/// Keychain service that `keychain:` entries are read from.
+pub const KEYCHAIN_SERVICE: &str = "ralph";
+
+/// A store of secrets looked up by entry name.
+pub trait SecretStore: Send + Sync {
+    /// Returns the secret stored under `entry`, if there is one.
+    fn get_secret(&self, entry: &str) -> Option<String>;
+}
+
+/// The OS keychain, read under [`KEYCHAIN_SERVICE`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyringStore;
+
+impl SecretStore for KeyringStore {
+    fn get_secret(&self, entry: &str) -> Option<String> {
+        // Some platform backends panic when no secret service is running
+        std::panic::catch_unwind(|| {
+            keyring::Entry::new(KEYCHAIN_SERVICE, entry)
+                .ok()
+                .and_then(|e| e.get_password().ok())
+        })
+        .ok()
+        .flatten()
+    }
+}
+
+/// An env value after resolving its source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedEnvValue {
+    /// The value passed to the backend process.
+    pub value: String,
+    /// True if any part of the value came from the environment or the keychain.
+    pub secret: bool,
+}
+
+/// Resolves a configured env value.
+///
+/// Unset variables and missing keychain entries resolve to an empty string;
+/// preflight reports them through [`missing_env_sources`].
+pub fn resolve_env_value(value: &str, store: &dyn SecretStore) -> ResolvedEnvValue {
+    if let Some(entry) = value.strip_prefix(KEYCHAIN_PREFIX) {
+        return ResolvedEnvValue {
+            value: store.get_secret(entry.trim()).unwrap_or_default(),
+            secret: true,
+        };
+    }
+    ResolvedEnvValue {
+        value: expand_env_refs(value, |name| std::env::var(name).ok()),
+        secret: !env_refs(value).is_empty(),
+    }
+}
+
+/// Describes each source referenced by `value` that can't be resolved.
+pub fn missing_env_sources(value: &str, store: &dyn SecretStore) -> Vec<String> {
+    if let Some(entry) = value.strip_prefix(KEYCHAIN_PREFIX) {
+        let entry = entry.trim();
+        return if store.get_secret(entry).is_some() {
+            Vec::new()
+        } else {
+            vec![format!(
+                "keychain entry '{entry}' not found (service '{KEYCHAIN_SERVICE}')"
+            )]
+        };
+    }
+    env_refs(value)
+        .into_iter()
+        .filter(|name| std::env::var_os(name).is_none())
+        .map(|name| format!("${{{name}}} is not set"))
+        .collect()
+}
+
+/// Resolved secret values across `cli.env` and every hat's `env`.
+///
+/// Used to mask those values wherever Ralph records output.
+pub fn secret_env_values(config: &RalphConfig, store: &dyn SecretStore) -> Vec<String> {
+    let hat_envs = config.hats.values().map(|hat| &hat.env);
+    let mut values: Vec<String> = std::iter::once(&config.cli.env)
+        .chain(hat_envs)
+        .flat_map(BTreeMap::values)
+        .map(|value| resolve_env_value(value, store))
+        .filter(|resolved| resolved.secret && !resolved.value.is_empty())
+        .map(|resolved| resolved.value)
+        .collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Expands `${VAR}` references in `value` using `lookup`.
+///
+/// Unknown variables expand to an empty string. A `$` not followed by `{`,
+/// or a `${` without a closing brace, is kept as-is.
+pub fn expand_env_refs(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                out.push_str(&lookup(&after[..end]).unwrap_or_default());
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Names of the variables `value` references as `${VAR}`.
+fn env_refs(value: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        names.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+    names
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// In-memory stand-in for the OS keychain.
+    #[derive(Default)]
+    pub(crate) struct MockStore(pub HashMap<String, String>);
+
+    impl SecretStore for MockStore {
+        fn get_secret(&self, entry: &str) -> Option<String> {
+            self.0.get(entry).cloned()
+        }
+    }
+
+    fn store() -> MockStore {
+        MockStore(HashMap::from([(
+            "npm-token".to_string(),
+            "npm_s3cr3t".to_string(),
+        )]))
+    }
+
+    #[test]
+    fn test_expand_env_refs() {
+        let lookup = |name: &str| match name {
+            "HOME" => Some("/home/ralph".to_string()),
+            "KEY" => Some("secret".to_string()),
+            _ => None,
+        };
+        assert_eq!(expand_env_refs("${HOME}/bin", lookup), "/home/ralph/bin");
+        assert_eq!(
+            expand_env_refs("Bearer ${KEY}${KEY}", lookup),
+            "Bearer secretsecret"
+        );
+        assert_eq!(expand_env_refs("x${MISSING}y", lookup), "xy");
+        assert_eq!(
+            expand_env_refs("$HOME and ${HOME", lookup),
+            "$HOME and ${HOME"
+        );
+        assert_eq!(expand_env_refs("plain", lookup), "plain");
+    }
+
+    #[test]
+    fn test_resolve_keychain_and_literal_values() {
+        let store = store();
+
+        let resolved = resolve_env_value("keychain:npm-token", &store);
+        assert_eq!(resolved.value, "npm_s3cr3t");
+        assert!(resolved.secret);
+
+        let resolved = resolve_env_value("http://localhost:8080", &store);
+        assert_eq!(resolved.value, "http://localhost:8080");
+        assert!(!resolved.secret);
+
+        let resolved = resolve_env_value("${RALPH_TEST_SURELY_UNSET_VAR}", &store);
+        assert_eq!(resolved.value, "");
+        assert!(resolved.secret);
+    }
+
+    #[test]
+    fn test_missing_env_sources() {
+        let store = store();
+        assert!(missing_env_sources("keychain:npm-token", &store).is_empty());
+        assert!(missing_env_sources("literal", &store).is_empty());
+        assert_eq!(
+            missing_env_sources("keychain:pypi-token", &store),
+            vec!["keychain entry 'pypi-token' not found (service 'ralph')"]
+        );
+        assert_eq!(
+            missing_env_sources("a${RALPH_TEST_SURELY_UNSET_VAR}b", &store),
+            vec!["${RALPH_TEST_SURELY_UNSET_VAR} is not set"]
+        );
+    }
+
+    #[test]
+    fn test_secret_env_values_cover_cli_and_hats() {
+        let config: RalphConfig = serde_yaml::from_str(
+            r#"
+cli:
+  env:
+    NPM_TOKEN: "keychain:npm-token"
+    BASE_URL: "http://localhost:8080"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    env:
+      TOKEN: "keychain:npm-token"
+      MISSING: "keychain:nope"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(secret_env_values(&config, &store()), vec!["npm_s3cr3t"]);
+    }
+}
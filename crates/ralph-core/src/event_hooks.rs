@@ -0,0 +1,374 @@
+//! Event-driven hooks (`hooks`).
+//!
+//! Each hook maps a topic pattern to a shell command. When a matching event
+//! is published, the command runs in the background with the event in
+//! `RALPH_EVENT_TOPIC` and `RALPH_EVENT_PAYLOAD`, so hooks can post
+//! webhooks or trigger CI without a hat. Hooks never delay an iteration:
+//! the bus observer only spawns them, and at most [`MAX_CONCURRENT_HOOKS`]
+//! run at once. Every run is recorded in the events file as `hook.completed`
+//! or `hook.failed`.
+
+use crate::completion_check::output_tail;
+use crate::config::{EventHookConfig, HookFailurePolicy};
+use crate::event_logger::{EventLogger, EventRecord};
+use ralph_proto::{Event, HatId, Topic};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::sync::{Notify, Semaphore};
+use tracing::{debug, warn};
+
+/// Topic recorded when a hook exits 0.
+pub const HOOK_COMPLETED_TOPIC: &str = "hook.completed";
+
+/// Topic recorded when a hook exits non-zero, times out, or can't start.
+pub const HOOK_FAILED_TOPIC: &str = "hook.failed";
+
+/// Hooks running at once; further matches wait for a free slot.
+pub const MAX_CONCURRENT_HOOKS: usize = 4;
+
+/// Output lines kept in a `hook.failed` record.
+const OUTPUT_TAIL_LINES: usize = 10;
+
+/// Runs the configured hooks for events seen on the bus.
+#[derive(Clone)]
+pub struct EventHookRunner {
+    hooks: Arc<Vec<(Topic, EventHookConfig)>>,
+    workspace: PathBuf,
+    events_path: PathBuf,
+    /// Iteration stamped on hook records.
+    iteration: Arc<AtomicU32>,
+    permits: Arc<Semaphore>,
+    /// Hooks dispatched and not yet finished, and a wake-up for `wait_idle`.
+    in_flight: Arc<(AtomicUsize, Notify)>,
+}
+
+impl EventHookRunner {
+    /// Creates a runner for `hooks`, running commands in `workspace` and
+    /// recording their results in `events_path`.
+    pub fn new(
+        hooks: &[EventHookConfig],
+        workspace: impl Into<PathBuf>,
+        events_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            hooks: Arc::new(
+                hooks
+                    .iter()
+                    .map(|hook| (Topic::new(&hook.on), hook.clone()))
+                    .collect(),
+            ),
+            workspace: workspace.into(),
+            events_path: events_path.into(),
+            iteration: Arc::new(AtomicU32::new(0)),
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_HOOKS)),
+            in_flight: Arc::new((AtomicUsize::new(0), Notify::new())),
+        }
+    }
+
+    /// Sets the iteration stamped on subsequent hook records.
+    pub fn set_iteration(&self, iteration: u32) {
+        self.iteration.store(iteration, Ordering::Relaxed);
+    }
+
+    /// Returns a bus observer that starts the hooks matching each event.
+    ///
+    /// Must be called from, and the observer used within, a Tokio runtime.
+    pub fn observer(&self) -> impl Fn(&Event) + Send + 'static {
+        let runner = self.clone();
+        move |event| runner.dispatch(event)
+    }
+
+    /// Starts every hook matching `event` in the background.
+    pub fn dispatch(&self, event: &Event) {
+        // A hook on `*` must not fire for its own records
+        if event.topic.as_str().starts_with("hook.") {
+            return;
+        }
+        for (pattern, hook) in self.hooks.iter() {
+            if !pattern.matches(&event.topic) {
+                continue;
+            }
+            let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                warn!(hook = %hook.run, "No async runtime, skipping hook");
+                return;
+            };
+            let runner = self.clone();
+            let hook = hook.clone();
+            let event = event.clone();
+            self.in_flight.0.fetch_add(1, Ordering::SeqCst);
+            runtime.spawn(async move {
+                if let Ok(_permit) = runner.permits.clone().acquire_owned().await {
+                    runner.run(&hook, &event).await;
+                }
+                let (count, idle) = &*runner.in_flight;
+                if count.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    idle.notify_waiters();
+                }
+            });
+        }
+    }
+
+    /// Waits up to `timeout` for running and queued hooks to finish.
+    ///
+    /// Returns false if some were still running.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let (count, idle) = &*self.in_flight;
+        let drained = async {
+            loop {
+                // Registered before the check so a wake-up in between isn't lost
+                let notified = idle.notified();
+                if count.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, drained).await.is_ok()
+    }
+
+    /// Longest timeout of any configured hook.
+    pub fn max_timeout(&self) -> Duration {
+        let secs = self
+            .hooks
+            .iter()
+            .map(|(_, hook)| hook.timeout_secs)
+            .max()
+            .unwrap_or(0);
+        Duration::from_secs(secs)
+    }
+
+    async fn run(&self, hook: &EventHookConfig, event: &Event) {
+        debug!(topic = %event.topic, hook = %hook.run, "Running hook");
+        let outcome = run_hook(hook, event, &self.workspace).await;
+
+        let topic = if outcome.passed() {
+            HOOK_COMPLETED_TOPIC
+        } else {
+            warn!(
+                topic = %event.topic,
+                hook = %hook.run,
+                status = %outcome.status(),
+                "Hook failed"
+            );
+            HOOK_FAILED_TOPIC
+        };
+        let mut payload = format!(
+            "Hook `{run}` for '{topic}': {status}.\n- hook: {on}\n- event: {topic}\n- exit_code: {code}\n- elapsed_ms: {ms}",
+            run = hook.run,
+            topic = event.topic,
+            status = outcome.status(),
+            on = hook.on,
+            code = outcome
+                .exit_code
+                .map_or_else(|| "none".to_string(), |code| code.to_string()),
+            ms = outcome.elapsed.as_millis(),
+        );
+        if !outcome.passed() && !outcome.output_tail.is_empty() {
+            payload.push_str("\n- output:\n");
+            payload.push_str(&outcome.output_tail);
+        }
+
+        let record = EventRecord::new(
+            self.iteration.load(Ordering::Relaxed),
+            "loop",
+            &Event::new(topic, payload),
+            None::<&HatId>,
+        );
+        if let Err(e) = EventLogger::new(&self.events_path).log(&record) {
+            warn!(error = %e, "Failed to record {} event", topic);
+        }
+
+        if !outcome.passed() && hook.on_failure == HookFailurePolicy::Stop {
+            request_stop(&self.workspace);
+        }
+    }
+}
+
+/// Result of one hook run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HookOutcome {
+    /// Exit code, or `None` if the command timed out, was killed by a
+    /// signal, or could not be started.
+    exit_code: Option<i32>,
+    timed_out: bool,
+    /// Last lines of combined stdout and stderr.
+    output_tail: String,
+    elapsed: Duration,
+}
+
+impl HookOutcome {
+    fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    fn status(&self) -> String {
+        match self.exit_code {
+            Some(code) => format!("exit {code}"),
+            None if self.timed_out => format!("timed out after {}s", self.elapsed.as_secs()),
+            None => "failed".to_string(),
+        }
+    }
+}
+
+async fn run_hook(hook: &EventHookConfig, event: &Event, workspace: &Path) -> HookOutcome {
+    let started = Instant::now();
+    let outcome = |exit_code, timed_out, output: &str| HookOutcome {
+        exit_code,
+        timed_out,
+        output_tail: output_tail(output, OUTPUT_TAIL_LINES),
+        elapsed: started.elapsed(),
+    };
+
+    // Merge stderr into stdout so the tail keeps the order the user would see
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(format!("exec 2>&1\n{}", hook.run))
+        .current_dir(workspace)
+        .env("RALPH_EVENT_TOPIC", event.topic.as_str())
+        .env("RALPH_EVENT_PAYLOAD", &event.payload)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => return outcome(None, false, &format!("Failed to start `sh`: {e}")),
+    };
+
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => outcome(
+            output.status.code(),
+            false,
+            &String::from_utf8_lossy(&output.stdout),
+        ),
+        Ok(Err(e)) => outcome(None, false, &format!("Failed to wait for hook: {e}")),
+        // Dropping the future kills the child (kill_on_drop)
+        Err(_) => outcome(None, true, ""),
+    }
+}
+
+/// Writes `.ralph/stop-requested`; the loop stops at the next iteration
+/// boundary.
+fn request_stop(workspace: &Path) {
+    let stop_path = workspace.join(".ralph/stop-requested");
+    let written = stop_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&stop_path, ""));
+    match written {
+        Ok(()) => warn!("Hook failed with on_failure: stop, stopping after this iteration"),
+        Err(e) => warn!(error = %e, "Failed to request stop after hook failure"),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::event_logger::EventHistory;
+    use tempfile::TempDir;
+
+    fn runner(temp: &TempDir, hooks: &[EventHookConfig]) -> EventHookRunner {
+        EventHookRunner::new(hooks, temp.path(), temp.path().join("events.jsonl"))
+    }
+
+    fn records(temp: &TempDir) -> Vec<EventRecord> {
+        EventHistory::new(temp.path().join("events.jsonl"))
+            .read_all()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_matching_hook_receives_event_and_is_recorded() {
+        let temp = TempDir::new().unwrap();
+        let runner = runner(
+            &temp,
+            &[
+                EventHookConfig::new(
+                    "review.*",
+                    r#"printf '%s|%s' "$RALPH_EVENT_TOPIC" "$RALPH_EVENT_PAYLOAD" > seen"#,
+                ),
+                EventHookConfig::new("build.done", "touch never"),
+            ],
+        );
+        runner.set_iteration(3);
+
+        runner.observer()(&Event::new("review.approved", "LGTM"));
+        assert!(runner.wait_idle(Duration::from_secs(10)).await);
+
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("seen")).unwrap(),
+            "review.approved|LGTM"
+        );
+        assert!(!temp.path().join("never").exists());
+
+        let records = records(&temp);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].topic, HOOK_COMPLETED_TOPIC);
+        assert_eq!(records[0].hat, "loop");
+        assert_eq!(records[0].iteration, 3);
+        assert!(records[0].payload.contains("exit_code: 0"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_hook_is_recorded_and_logs_only_by_default() {
+        let temp = TempDir::new().unwrap();
+        let runner = runner(
+            &temp,
+            &[EventHookConfig::new("*", "echo 'webhook down'; exit 7")],
+        );
+
+        runner.dispatch(&Event::new("commit.complete", ""));
+        assert!(runner.wait_idle(Duration::from_secs(10)).await);
+
+        let records = records(&temp);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].topic, HOOK_FAILED_TOPIC);
+        assert!(records[0].payload.contains("exit_code: 7"));
+        assert!(records[0].payload.contains("webhook down"));
+        assert!(!temp.path().join(".ralph/stop-requested").exists());
+
+        // Its own records never fire hooks, even on `*`
+        runner.dispatch(&Event::new(HOOK_FAILED_TOPIC, ""));
+        assert!(runner.wait_idle(Duration::from_secs(10)).await);
+        assert_eq!(self::records(&temp).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_hook_with_stop_policy_requests_stop() {
+        let temp = TempDir::new().unwrap();
+        let mut hook = EventHookConfig::new("build.done", "sleep 5");
+        hook.timeout_secs = 0;
+        hook.on_failure = HookFailurePolicy::Stop;
+        let runner = runner(&temp, &[hook]);
+
+        runner.dispatch(&Event::new("build.done", ""));
+        assert!(runner.wait_idle(Duration::from_secs(10)).await);
+
+        let records = records(&temp);
+        assert_eq!(records[0].topic, HOOK_FAILED_TOPIC);
+        assert!(records[0].payload.contains("timed out"));
+        assert!(temp.path().join(".ralph/stop-requested").exists());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_does_not_wait_for_hooks() {
+        let temp = TempDir::new().unwrap();
+        let hooks = vec![EventHookConfig::new("*", "sleep 1"); MAX_CONCURRENT_HOOKS + 2];
+        let runner = runner(&temp, &hooks);
+
+        let started = Instant::now();
+        runner.dispatch(&Event::new("build.done", ""));
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        // Six one-second hooks, four at a time
+        assert!(runner.wait_idle(Duration::from_secs(10)).await);
+        assert!(started.elapsed() >= Duration::from_secs(2));
+        assert_eq!(records(&temp).len(), MAX_CONCURRENT_HOOKS + 2);
+    }
+}
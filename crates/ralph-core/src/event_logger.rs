@@ -2,7 +2,12 @@
 //!
 //! Logs all events to `.ralph/events.jsonl` as specified in the event-loop spec.
 //! The observer pattern allows hooking into the event bus without modifying routing.
+//!
+//! Events files are shared: the loop, `ralph emit`, the TUI and the Telegram
+//! bot all append to them from different processes. Every writer goes through
+//! [`EventHistory::append`], which serializes appends with a [`FileLock`].
 
+use crate::file_lock::FileLock;
 use crate::loop_context::LoopContext;
 use ralph_proto::{Event, HatId};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -11,6 +16,28 @@ use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
+/// Appends one JSON line to an events file; see [`EventHistory::append`].
+fn append_event_line(path: &Path, line: &str, sync: bool) -> std::io::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut buf = String::with_capacity(line.len() + 1);
+    buf.push_str(line.trim_end_matches(['\r', '\n']));
+    buf.push('\n');
+
+    let lock = FileLock::new(path)?;
+    let _guard = lock.exclusive()?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(buf.as_bytes())?;
+    if sync {
+        file.sync_data()?;
+    }
+    Ok(())
+}
+
 /// Custom deserializer that accepts both String and structured JSON payloads.
 ///
 /// Agents sometimes write structured data as JSON objects instead of strings.
@@ -131,8 +158,8 @@ pub struct EventLogger {
     /// Path to the events file.
     path: PathBuf,
 
-    /// File handle for appending.
-    file: Option<File>,
+    /// Whether to fsync after each event.
+    sync: bool,
 }
 
 impl EventLogger {
@@ -145,10 +172,19 @@ impl EventLogger {
     pub fn new(path: impl Into<PathBuf>) -> Self {
         Self {
             path: path.into(),
-            file: None,
+            sync: false,
         }
     }
 
+    /// Fsyncs the events file after each logged event.
+    ///
+    /// Set from `event_loop.fsync_events`.
+    #[must_use]
+    pub fn with_fsync(mut self, sync: bool) -> Self {
+        self.sync = sync;
+        self
+    }
+
     /// Creates a logger with the default path.
     pub fn default_path() -> Self {
         Self::new(Self::DEFAULT_PATH)
@@ -172,33 +208,15 @@ impl EventLogger {
         Self::new(events_path)
     }
 
-    /// Ensures the parent directory exists and opens the file.
-    fn ensure_open(&mut self) -> std::io::Result<&mut File> {
-        if self.file.is_none() {
-            if let Some(parent) = self.path.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            let file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&self.path)?;
-            self.file = Some(file);
-        }
-        Ok(self.file.as_mut().unwrap())
-    }
-
     /// Logs an event record.
     ///
-    /// Uses a single `write_all` call to ensure the JSON line is written atomically.
-    /// This prevents corruption when multiple processes append to the same file
-    /// concurrently (e.g., during parallel merge queue processing).
+    /// Appends through [`EventHistory::append`], so records never interleave
+    /// with lines written concurrently by other processes (e.g., during
+    /// parallel merge queue processing). Secrets are masked with the
+    /// installed redactor first.
     pub fn log(&mut self, record: &EventRecord) -> std::io::Result<()> {
-        let file = self.ensure_open()?;
-        let mut json = serde_json::to_string(record)?;
-        json.push('\n');
-        // Single write_all ensures atomic append on POSIX with O_APPEND
-        file.write_all(json.as_bytes())?;
-        file.flush()?;
+        let json = crate::redact::installed_redactor().to_json_string(record)?;
+        EventHistory::new(&self.path).append(&json, self.sync)?;
         debug!(topic = %record.topic, iteration = record.iteration, "Event logged");
         Ok(())
     }
@@ -219,6 +237,21 @@ impl EventLogger {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Returns true if the logger fsyncs after each event.
+    pub fn fsyncs(&self) -> bool {
+        self.sync
+    }
+}
+
+/// Records read from an events file.
+#[derive(Debug, Clone, Default)]
+pub struct EventReadReport {
+    /// Records that parsed successfully, in file order.
+    pub records: Vec<EventRecord>,
+
+    /// Number of non-empty lines skipped because they couldn't be parsed.
+    pub skipped_lines: usize,
 }
 
 /// Reader for event history files.
@@ -251,29 +284,52 @@ impl EventHistory {
     }
 
     /// Reads all event records from the file.
+    ///
+    /// Unparseable lines are skipped with a warning; use
+    /// [`Self::read_all_with_report`] to learn how many were skipped.
     pub fn read_all(&self) -> std::io::Result<Vec<EventRecord>> {
+        Ok(self.read_all_with_report()?.records)
+    }
+
+    /// Reads all event records, counting lines that couldn't be parsed.
+    ///
+    /// Corrupt lines (malformed JSON, invalid UTF-8) are skipped rather than
+    /// failing the whole read.
+    pub fn read_all_with_report(&self) -> std::io::Result<EventReadReport> {
+        let mut report = EventReadReport::default();
         if !self.exists() {
-            return Ok(Vec::new());
+            return Ok(report);
         }
 
-        let file = File::open(&self.path)?;
-        let reader = BufReader::new(file);
-        let mut records = Vec::new();
+        // Best effort: a read-only workspace can still be read unlocked
+        let _guard = FileLock::new(&self.path)
+            .and_then(|lock| lock.shared())
+            .ok();
+        let reader = BufReader::new(File::open(&self.path)?);
 
-        for (line_num, line) in reader.lines().enumerate() {
+        for (line_num, line) in reader.split(b'\n').enumerate() {
             let line = line?;
+            let Ok(line) = std::str::from_utf8(&line) else {
+                warn!(
+                    line = line_num + 1,
+                    "Skipping event record with invalid UTF-8"
+                );
+                report.skipped_lines += 1;
+                continue;
+            };
             if line.trim().is_empty() {
                 continue;
             }
-            match serde_json::from_str(&line) {
-                Ok(record) => records.push(record),
+            match serde_json::from_str(line) {
+                Ok(record) => report.records.push(record),
                 Err(e) => {
                     warn!(line = line_num + 1, error = %e, "Failed to parse event record");
+                    report.skipped_lines += 1;
                 }
             }
         }
 
-        Ok(records)
+        Ok(report)
     }
 
     /// Reads the last N event records.
@@ -298,6 +354,20 @@ impl EventHistory {
             .collect())
     }
 
+    /// Appends one JSON line to the history file.
+    ///
+    /// Holds an exclusive advisory lock (`flock` on Unix, `File::lock` on
+    /// Windows; best-effort elsewhere) while writing the whole line, newline
+    /// included, with a single `write_all`, so concurrent appends from
+    /// parallel loops or `ralph emit` never interleave partial lines. With
+    /// `sync`, the data is flushed to disk before the lock is released.
+    ///
+    /// Creates the file and its parent directory if needed. A trailing
+    /// newline in `line` is ignored.
+    pub fn append(&self, line: &str, sync: bool) -> std::io::Result<()> {
+        append_event_line(&self.path, line, sync)
+    }
+
     /// Clears the event history file.
     pub fn clear(&self) -> std::io::Result<()> {
         if self.exists() {
@@ -565,4 +635,76 @@ mod tests {
         let parsed: serde_json::Value = serde_json::from_str(&records[2].payload).unwrap();
         assert_eq!(parsed["evidence"]["tests"], "pass");
     }
+
+    #[test]
+    fn test_append_event_line_adds_single_newline() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested/events.jsonl");
+
+        append_event_line(&path, r#"{"topic":"a","ts":"t"}"#, false).unwrap();
+        append_event_line(&path, "{\"topic\":\"b\",\"ts\":\"t\"}\n", true).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "{\"topic\":\"a\",\"ts\":\"t\"}\n{\"topic\":\"b\",\"ts\":\"t\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_read_all_with_report_counts_corrupt_lines() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, r#"{{"topic":"ok.one","ts":"t"}}"#).unwrap();
+        writeln!(file, r#"{{"topic":"half"#).unwrap();
+        file.write_all(b"\xff\xfe not utf-8\n").unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, r#"{{"topic":"ok.two","ts":"t"}}"#).unwrap();
+
+        let report = EventHistory::new(&path).read_all_with_report().unwrap();
+
+        let topics: Vec<&str> = report.records.iter().map(|r| r.topic.as_str()).collect();
+        assert_eq!(topics, vec!["ok.one", "ok.two"]);
+        assert_eq!(report.skipped_lines, 2);
+        assert_eq!(EventHistory::new(&path).read_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_appends_never_interleave() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let writers = 8;
+        let per_writer = 50;
+        // Larger than PIPE_BUF, so O_APPEND alone wouldn't keep lines whole
+        let payload = "x".repeat(16 * 1024);
+
+        let handles: Vec<_> = (0..writers)
+            .map(|w| {
+                let path = path.clone();
+                let payload = payload.clone();
+                std::thread::spawn(move || {
+                    let mut logger = EventLogger::new(&path);
+                    for i in 0..per_writer {
+                        let event = Event::new(format!("writer.{w}"), format!("{i}:{payload}"));
+                        let mut record = EventRecord::new(i, "hat", &event, None);
+                        record.payload = event.payload.clone();
+                        logger.log(&record).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let report = EventHistory::new(&path).read_all_with_report().unwrap();
+        assert_eq!(report.skipped_lines, 0);
+        assert_eq!(report.records.len(), writers * per_writer as usize);
+        for record in &report.records {
+            let (_, body) = record.payload.split_once(':').unwrap();
+            assert_eq!(body.len(), payload.len());
+        }
+    }
 }
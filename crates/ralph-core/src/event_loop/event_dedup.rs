@@ -0,0 +1,170 @@
+//! Suppression of repeated events from the events file.
+//!
+//! A confused hat can write the same event several times per iteration, and
+//! every copy would retrigger the hats downstream. The filter remembers the
+//! topic+payload hash of each event it lets through and counts events per
+//! topic in the current iteration.
+
+use crate::config::EventDedupConfig;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Topics that always pass through: a human must always be heard.
+const ALWAYS_PASS_TOPICS: [&str; 2] = ["human.guidance", "human.response"];
+
+/// Why an event was suppressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Suppressed {
+    /// An identical event was let through in iteration `seen_in`.
+    Duplicate { seen_in: u32 },
+    /// The topic already reached its `max_per_iteration` limit.
+    RateLimited { limit: u32 },
+}
+
+impl fmt::Display for Suppressed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { seen_in } => {
+                write!(
+                    f,
+                    "identical event already processed in iteration {seen_in}"
+                )
+            }
+            Self::RateLimited { limit } => {
+                write!(f, "topic limit of {limit} per iteration reached")
+            }
+        }
+    }
+}
+
+/// Duplicate and rate-limit filter for incoming events.
+#[derive(Debug)]
+pub(crate) struct EventDedup {
+    config: EventDedupConfig,
+    /// Iteration each topic+payload hash was let through in.
+    seen: HashMap<u64, u32>,
+    /// Events let through per topic in `counted_iteration`.
+    topic_counts: HashMap<String, u32>,
+    counted_iteration: u32,
+}
+
+impl EventDedup {
+    pub(crate) fn new(config: EventDedupConfig) -> Self {
+        Self {
+            config,
+            seen: HashMap::new(),
+            topic_counts: HashMap::new(),
+            counted_iteration: 0,
+        }
+    }
+
+    /// Checks an event read during `iteration`, remembering it if it passes.
+    pub(crate) fn check(
+        &mut self,
+        iteration: u32,
+        topic: &str,
+        payload: Option<&str>,
+    ) -> Result<(), Suppressed> {
+        if !self.config.enabled
+            || ALWAYS_PASS_TOPICS.contains(&topic)
+            || self
+                .config
+                .exempt_topics
+                .iter()
+                .any(|exempt| exempt == topic)
+        {
+            return Ok(());
+        }
+
+        if iteration != self.counted_iteration {
+            self.topic_counts.clear();
+            self.counted_iteration = iteration;
+        }
+        let window = self.config.window_iterations;
+        self.seen
+            .retain(|_, seen_in| iteration.saturating_sub(*seen_in) < window);
+
+        let key = event_hash(topic, payload);
+        if let Some(&seen_in) = self.seen.get(&key) {
+            return Err(Suppressed::Duplicate { seen_in });
+        }
+        let count = self.topic_counts.get(topic).copied().unwrap_or(0);
+        if let Some(&limit) = self.config.max_per_iteration.get(topic)
+            && count >= limit
+        {
+            return Err(Suppressed::RateLimited { limit });
+        }
+
+        if window > 0 {
+            self.seen.insert(key, iteration);
+        }
+        self.topic_counts.insert(topic.to_string(), count + 1);
+        Ok(())
+    }
+}
+
+fn event_hash(topic: &str, payload: Option<&str>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    topic.hash(&mut hasher);
+    payload.unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dedup(window_iterations: u32) -> EventDedup {
+        EventDedup::new(EventDedupConfig {
+            enabled: true,
+            window_iterations,
+            ..EventDedupConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_duplicates_suppressed_within_window() {
+        let mut dedup = dedup(2);
+
+        assert_eq!(dedup.check(1, "build.blocked", Some("no deps")), Ok(()));
+        assert_eq!(dedup.check(1, "build.blocked", Some("other")), Ok(()));
+        assert_eq!(
+            dedup.check(2, "build.blocked", Some("no deps")),
+            Err(Suppressed::Duplicate { seen_in: 1 })
+        );
+        assert_eq!(dedup.check(3, "build.blocked", Some("no deps")), Ok(()));
+    }
+
+    #[test]
+    fn test_rate_limit_resets_each_iteration() {
+        let mut dedup = dedup(1);
+        dedup
+            .config
+            .max_per_iteration
+            .insert("build.task".to_string(), 2);
+
+        assert_eq!(dedup.check(1, "build.task", Some("a")), Ok(()));
+        assert_eq!(dedup.check(1, "build.task", Some("b")), Ok(()));
+        assert_eq!(
+            dedup.check(1, "build.task", Some("c")),
+            Err(Suppressed::RateLimited { limit: 2 })
+        );
+        assert_eq!(dedup.check(2, "build.task", Some("c")), Ok(()));
+    }
+
+    #[test]
+    fn test_exempt_topics_and_disabled_filter_pass() {
+        let mut dedup = dedup(1);
+        dedup.config.exempt_topics.push("progress.note".to_string());
+
+        for topic in ["human.guidance", "human.response", "progress.note"] {
+            assert_eq!(dedup.check(1, topic, Some("same")), Ok(()));
+            assert_eq!(dedup.check(1, topic, Some("same")), Ok(()));
+        }
+
+        let mut disabled = EventDedup::new(EventDedupConfig::default());
+        assert_eq!(disabled.check(1, "build.blocked", None), Ok(()));
+        assert_eq!(disabled.check(1, "build.blocked", None), Ok(()));
+    }
+}
@@ -0,0 +1,217 @@
+//! In-run queue for events read from the events file.
+//!
+//! An iteration can emit several events at once. They wait here in the order
+//! they were written, and each iteration releases one to the bus: the oldest
+//! event a hat subscribes to, or, when only unmatched events are waiting, the
+//! oldest of those for Ralph to handle as the universal fallback.
+
+use crate::config::{EventQueueConfig, QueueOverflowPolicy};
+use ralph_proto::Event;
+use std::collections::VecDeque;
+
+/// An event waiting to be released.
+#[derive(Debug)]
+struct QueuedEvent {
+    event: Event,
+    /// Releases that passed over this event because it matched no hat.
+    skipped: u32,
+}
+
+/// What happened to an event that arrived at a full queue.
+#[derive(Debug)]
+pub(crate) enum Overflow {
+    /// The oldest waiting event was dropped to make room.
+    Dropped(Event),
+    /// The queue is full and `on_overflow: fail` rejected the event.
+    Rejected(Event),
+}
+
+/// Result of releasing the next event.
+#[derive(Debug, Default)]
+pub(crate) struct Release {
+    /// The event to publish this iteration.
+    pub event: Option<Event>,
+    /// Unmatched events that waited longer than `unmatched_ttl`.
+    pub expired: Vec<Event>,
+}
+
+/// Ordered queue of events waiting for a hat.
+#[derive(Debug)]
+pub(crate) struct EventQueue {
+    config: EventQueueConfig,
+    waiting: VecDeque<QueuedEvent>,
+    /// Events held back by `on_overflow: block` until there is room.
+    held: VecDeque<Event>,
+}
+
+impl EventQueue {
+    pub(crate) fn new(config: EventQueueConfig) -> Self {
+        Self {
+            config,
+            waiting: VecDeque::new(),
+            held: VecDeque::new(),
+        }
+    }
+
+    /// Number of events waiting, including any held back by `block`.
+    pub(crate) fn len(&self) -> usize {
+        self.waiting.len() + self.held.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if any waiting or held event satisfies `f`.
+    pub(crate) fn any(&self, f: impl Fn(&Event) -> bool) -> bool {
+        self.waiting.iter().any(|queued| f(&queued.event)) || self.held.iter().any(f)
+    }
+
+    /// Waiting events in release order, followed by any held back by `block`.
+    pub(crate) fn events(&self) -> impl Iterator<Item = &Event> {
+        self.waiting
+            .iter()
+            .map(|queued| &queued.event)
+            .chain(self.held.iter())
+    }
+
+    /// Adds an event to the back of the queue, applying `on_overflow` when full.
+    pub(crate) fn push(&mut self, event: Event) -> Option<Overflow> {
+        if self.waiting.len() < self.config.max_depth.max(1) {
+            self.waiting.push_back(QueuedEvent { event, skipped: 0 });
+            return None;
+        }
+
+        match self.config.on_overflow {
+            QueueOverflowPolicy::DropOldest => {
+                let dropped = self.waiting.pop_front().map(|queued| queued.event);
+                self.waiting.push_back(QueuedEvent { event, skipped: 0 });
+                dropped.map(Overflow::Dropped)
+            }
+            QueueOverflowPolicy::Block => {
+                self.held.push_back(event);
+                None
+            }
+            QueueOverflowPolicy::Fail => Some(Overflow::Rejected(event)),
+        }
+    }
+
+    /// Releases the next event and ages the unmatched events passed over.
+    ///
+    /// `matches` reports whether some hat subscribes to an event.
+    pub(crate) fn release(&mut self, matches: impl Fn(&Event) -> bool) -> Release {
+        let index = self
+            .waiting
+            .iter()
+            .position(|queued| matches(&queued.event))
+            .or(if self.waiting.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        let event = index
+            .and_then(|index| self.waiting.remove(index))
+            .map(|queued| queued.event);
+
+        let mut expired = Vec::new();
+        let ttl = self.config.unmatched_ttl;
+        self.waiting.retain_mut(|queued| {
+            if matches(&queued.event) {
+                return true;
+            }
+            queued.skipped += 1;
+            if queued.skipped > ttl {
+                expired.push(queued.event.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        while self.waiting.len() < self.config.max_depth.max(1) {
+            let Some(event) = self.held.pop_front() else {
+                break;
+            };
+            self.waiting.push_back(QueuedEvent { event, skipped: 0 });
+        }
+
+        Release { event, expired }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_depth: usize, on_overflow: QueueOverflowPolicy) -> EventQueue {
+        EventQueue::new(EventQueueConfig {
+            max_depth,
+            on_overflow,
+            unmatched_ttl: 1,
+        })
+    }
+
+    fn topics(events: &[Event]) -> Vec<&str> {
+        events.iter().map(|event| event.topic.as_str()).collect()
+    }
+
+    fn is_build(event: &Event) -> bool {
+        event.topic.as_str().starts_with("build.")
+    }
+
+    #[test]
+    fn test_releases_matching_events_in_order_before_unmatched() {
+        let mut queue = queue(10, QueueOverflowPolicy::DropOldest);
+        for topic in ["note.one", "build.task", "build.done"] {
+            assert!(queue.push(Event::new(topic, "")).is_none());
+        }
+
+        let release = queue.release(is_build);
+        assert_eq!(release.event.unwrap().topic.as_str(), "build.task");
+        assert!(release.expired.is_empty());
+
+        let release = queue.release(is_build);
+        assert_eq!(release.event.unwrap().topic.as_str(), "build.done");
+        assert_eq!(topics(&release.expired), ["note.one"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_event_is_released_when_nothing_else_waits() {
+        let mut queue = queue(10, QueueOverflowPolicy::DropOldest);
+        queue.push(Event::new("note.one", ""));
+
+        let release = queue.release(is_build);
+        assert_eq!(release.event.unwrap().topic.as_str(), "note.one");
+        assert!(queue.release(is_build).event.is_none());
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let mut drop_oldest = queue(2, QueueOverflowPolicy::DropOldest);
+        drop_oldest.push(Event::new("build.one", ""));
+        drop_oldest.push(Event::new("build.two", ""));
+        let overflow = drop_oldest.push(Event::new("build.three", ""));
+        assert!(matches!(overflow, Some(Overflow::Dropped(e)) if e.topic.as_str() == "build.one"));
+        assert_eq!(drop_oldest.len(), 2);
+
+        let mut block = queue(1, QueueOverflowPolicy::Block);
+        block.push(Event::new("build.one", ""));
+        assert!(block.push(Event::new("build.two", "")).is_none());
+        assert_eq!(block.len(), 2);
+        assert_eq!(
+            block.release(is_build).event.unwrap().topic.as_str(),
+            "build.one"
+        );
+        assert_eq!(
+            block.release(is_build).event.unwrap().topic.as_str(),
+            "build.two"
+        );
+
+        let mut fail = queue(1, QueueOverflowPolicy::Fail);
+        fail.push(Event::new("build.one", ""));
+        let overflow = fail.push(Event::new("build.two", ""));
+        assert!(matches!(overflow, Some(Overflow::Rejected(e)) if e.topic.as_str() == "build.two"));
+        assert_eq!(fail.len(), 1);
+    }
+}
@@ -4,8 +4,13 @@
 //! state of the orchestration loop including iteration count, failures,
 //! timing, and hat activation tracking.
 
-use ralph_proto::HatId;
+use chrono::{DateTime, Utc};
+use ralph_proto::{Event, HatId};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 /// Current state of the event loop.
@@ -35,6 +40,25 @@ pub struct LoopState {
     pub consecutive_malformed_events: u32,
     /// Whether a completion event has been observed in JSONL.
     pub completion_requested: bool,
+    /// Completion topic that matched (recorded in the loop.terminate event).
+    pub completion_topic: Option<String>,
+    /// Topic of the first `fail_on_event` gate event observed in JSONL.
+    pub gate_failed_topic: Option<String>,
+    /// Topic of the last event read from JSONL.
+    pub last_event_topic: Option<String>,
+    /// Topic of the `until_event` early-stop event, once observed in JSONL.
+    pub until_event_topic: Option<String>,
+    /// `until_event` occurrences already in the events file when the loop
+    /// resumed; they are re-read but don't stop the loop.
+    pub stale_until_events: usize,
+    /// Completions rejected by `event_loop.completion_check`.
+    pub completion_rejections: u32,
+    /// Topic of the first event rejected by a full queue (`on_overflow: fail`).
+    pub queue_overflow_topic: Option<String>,
+    /// Topic of an event no hat triggers on, once the loop has stalled on it.
+    pub no_matching_hat_topic: Option<String>,
+    /// Consecutive iterations whose events all went unmatched.
+    pub unmatched_event_iterations: u32,
 
     /// Per-hat activation counts (used for max_activations).
     pub hat_activation_counts: HashMap<HatId, u32>,
@@ -42,6 +66,12 @@ pub struct LoopState {
     /// Hats for which `<hat_id>.exhausted` has been emitted.
     pub exhausted_hats: HashSet<HatId>,
 
+    /// Hats for which `hat.budget_exceeded` has been emitted.
+    pub budget_exceeded_hats: HashSet<HatId>,
+
+    /// Hat whose iteration budget ran out with no `hat.budget_exceeded` subscriber.
+    pub budget_exceeded_hat: Option<HatId>,
+
     /// When the last Telegram check-in message was sent.
     /// `None` means no check-in has been sent yet.
     pub last_checkin_at: Option<Instant>,
@@ -66,8 +96,19 @@ impl Default for LoopState {
             abandoned_task_redispatches: 0,
             consecutive_malformed_events: 0,
             completion_requested: false,
+            completion_topic: None,
+            gate_failed_topic: None,
+            last_event_topic: None,
+            until_event_topic: None,
+            stale_until_events: 0,
+            completion_rejections: 0,
+            queue_overflow_topic: None,
+            no_matching_hat_topic: None,
+            unmatched_event_iterations: 0,
             hat_activation_counts: HashMap::new(),
             exhausted_hats: HashSet::new(),
+            budget_exceeded_hats: HashSet::new(),
+            budget_exceeded_hat: None,
             last_checkin_at: None,
             last_active_hat_ids: Vec::new(),
         }
@@ -84,4 +125,134 @@ impl LoopState {
     pub fn elapsed(&self) -> Duration {
         self.started_at.elapsed()
     }
+
+    /// Restores the counters saved in `snapshot`.
+    ///
+    /// Timing and stop requests (completion, gates, `until_event`) are not
+    /// restored: they belong to the run that wrote the snapshot.
+    pub fn restore(&mut self, snapshot: &LoopStateSnapshot) {
+        self.iteration = snapshot.iteration;
+        self.consecutive_failures = snapshot.consecutive_failures;
+        self.cumulative_cost = snapshot.cumulative_cost;
+        self.last_hat.clone_from(&snapshot.last_hat);
+        self.last_event_topic.clone_from(&snapshot.last_event);
+        self.last_active_hat_ids
+            .clone_from(&snapshot.last_active_hat_ids);
+        self.task_block_counts
+            .clone_from(&snapshot.task_block_counts);
+        self.abandoned_tasks.clone_from(&snapshot.abandoned_tasks);
+        self.abandoned_task_redispatches = snapshot.abandoned_task_redispatches;
+        self.completion_rejections = snapshot.completion_rejections;
+        self.hat_activation_counts
+            .clone_from(&snapshot.hat_activation_counts);
+        self.exhausted_hats.clone_from(&snapshot.exhausted_hats);
+        self.budget_exceeded_hats
+            .clone_from(&snapshot.budget_exceeded_hats);
+    }
+}
+
+/// Loop state saved to `.ralph/agent/loop-state.json` after every iteration,
+/// so `ralph run --continue` resumes with the same iteration count and
+/// pending events instead of starting over from the scratchpad.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoopStateSnapshot {
+    /// When the snapshot was written.
+    pub saved_at: DateTime<Utc>,
+    /// Events file the loop was reading.
+    pub events_file: PathBuf,
+    /// Byte offset the events file had been read up to.
+    pub events_position: u64,
+    /// Iterations completed.
+    pub iteration: u32,
+    /// Number of consecutive failures.
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Cumulative cost in USD (if tracked).
+    #[serde(default)]
+    pub cumulative_cost: f64,
+    /// The last hat that executed.
+    #[serde(default)]
+    pub last_hat: Option<HatId>,
+    /// Topic of the last event read from the events file.
+    #[serde(default)]
+    pub last_event: Option<String>,
+    /// Hat IDs that were active in the last iteration.
+    #[serde(default)]
+    pub last_active_hat_ids: Vec<HatId>,
+    /// Per-task block counts.
+    #[serde(default)]
+    pub task_block_counts: HashMap<String, u32>,
+    /// Tasks that have been abandoned.
+    #[serde(default)]
+    pub abandoned_tasks: Vec<String>,
+    /// Count of times an abandoned task was dispatched again.
+    #[serde(default)]
+    pub abandoned_task_redispatches: u32,
+    /// Completions rejected by `event_loop.completion_check`.
+    #[serde(default)]
+    pub completion_rejections: u32,
+    /// Per-hat activation counts.
+    #[serde(default)]
+    pub hat_activation_counts: HashMap<HatId, u32>,
+    /// Hats for which `<hat_id>.exhausted` has been emitted.
+    #[serde(default)]
+    pub exhausted_hats: HashSet<HatId>,
+    /// Hats for which `hat.budget_exceeded` has been emitted.
+    #[serde(default)]
+    pub budget_exceeded_hats: HashSet<HatId>,
+    /// Events published for the next iteration.
+    #[serde(default)]
+    pub pending_events: Vec<Event>,
+    /// Events read from the events file that are still waiting in the queue.
+    #[serde(default)]
+    pub queued_events: Vec<Event>,
+}
+
+impl LoopStateSnapshot {
+    /// Captures `state` with the events file read up to `events_position`.
+    /// Pending and queued events are left for the caller to fill in.
+    pub fn capture(
+        state: &LoopState,
+        events_file: impl Into<PathBuf>,
+        events_position: u64,
+    ) -> Self {
+        Self {
+            saved_at: Utc::now(),
+            events_file: events_file.into(),
+            events_position,
+            iteration: state.iteration,
+            consecutive_failures: state.consecutive_failures,
+            cumulative_cost: state.cumulative_cost,
+            last_hat: state.last_hat.clone(),
+            last_event: state.last_event_topic.clone(),
+            last_active_hat_ids: state.last_active_hat_ids.clone(),
+            task_block_counts: state.task_block_counts.clone(),
+            abandoned_tasks: state.abandoned_tasks.clone(),
+            abandoned_task_redispatches: state.abandoned_task_redispatches,
+            completion_rejections: state.completion_rejections,
+            hat_activation_counts: state.hat_activation_counts.clone(),
+            exhausted_hats: state.exhausted_hats.clone(),
+            budget_exceeded_hats: state.budget_exceeded_hats.clone(),
+            pending_events: Vec::new(),
+            queued_events: Vec::new(),
+        }
+    }
+
+    /// Reads a snapshot. Fails if the file is missing or not a valid snapshot.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the snapshot as pretty-printed JSON, replacing the previous
+    /// one atomically (temp file + rename).
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json + "\n")?;
+        fs::rename(&tmp_path, path)
+    }
 }
@@ -2,29 +2,79 @@
 //!
 //! The event loop coordinates the execution of hats via pub/sub messaging.
 
+mod event_dedup;
+mod event_queue;
 mod loop_state;
 #[cfg(test)]
 mod tests;
 
-pub use loop_state::LoopState;
+use event_dedup::{EventDedup, Suppressed};
+use event_queue::{EventQueue, Overflow};
+pub use loop_state::{LoopState, LoopStateSnapshot};
 
-use crate::config::{HatBackend, InjectMode, RalphConfig};
+use crate::config::{CompletionMatcher, HatBackend, InjectMode, RalphConfig, SchemaMismatchAction};
+use crate::event_logger::EventHistory;
 use crate::event_parser::{EventParser, MutationEvidence, MutationStatus};
 use crate::event_reader::EventReader;
+use crate::event_schema::EventSchemas;
 use crate::hat_registry::HatRegistry;
 use crate::hatless_ralph::HatlessRalph;
-use crate::instructions::InstructionBuilder;
+use crate::instructions::{InstructionBuilder, IterationContext};
 use crate::loop_context::LoopContext;
-use crate::memory_store::{MarkdownMemoryStore, format_memories_as_markdown, truncate_to_budget};
+use crate::memory_store::{
+    MarkdownMemoryStore, evict_to_budget, format_memories_as_markdown, truncate_to_budget,
+};
+use crate::ralph_ignore::RalphIgnore;
+use crate::run_metrics::{IterationMetrics, RunMetrics, duration_ms};
 use crate::skill_registry::SkillRegistry;
-use crate::text::floor_char_boundary;
-use ralph_proto::{CheckinContext, Event, EventBus, Hat, HatId, RobotService};
-use std::path::PathBuf;
+use crate::text::{floor_char_boundary, truncate_with_ellipsis};
+use ralph_proto::{
+    CheckinContext, Event, EventBus, Hat, HatId, LifecycleNotification, RobotService,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+/// Topic published when a hat uses up its `max_iterations` budget.
+pub const HAT_BUDGET_EXCEEDED_TOPIC: &str = "hat.budget_exceeded";
+
+/// Topic recorded when a failed iteration attempt is retried.
+pub const ITERATION_RETRIED_TOPIC: &str = "iteration.retried";
+
+/// Topic recorded when the loop is held between iterations.
+pub const LOOP_PAUSED_TOPIC: &str = "loop.paused";
+
+/// Topic recorded when a paused loop resumes or single-steps.
+pub const LOOP_RESUMED_TOPIC: &str = "loop.resumed";
+
+/// Topic recorded when a queued event is dropped without being handled.
+pub const EVENT_UNHANDLED_TOPIC: &str = "event.unhandled";
+
+/// Topic recorded when a repeated event is suppressed by `event_loop.dedup`.
+pub const EVENT_DUPLICATE_TOPIC: &str = "event.duplicate";
+
+/// Topic recorded when `event_loop.completion_check` accepts a completion.
+pub const COMPLETION_VERIFIED_TOPIC: &str = "completion.verified";
+
+/// Topic published when `event_loop.completion_check` rejects a completion.
+pub const COMPLETION_REJECTED_TOPIC: &str = "completion.rejected";
+
+/// Topics the loop records for observers only; never routed to hats.
+const OBSERVER_ONLY_TOPICS: [&str; 9] = [
+    ITERATION_RETRIED_TOPIC,
+    LOOP_PAUSED_TOPIC,
+    LOOP_RESUMED_TOPIC,
+    EVENT_UNHANDLED_TOPIC,
+    EVENT_DUPLICATE_TOPIC,
+    COMPLETION_VERIFIED_TOPIC,
+    crate::event_hooks::HOOK_COMPLETED_TOPIC,
+    crate::event_hooks::HOOK_FAILED_TOPIC,
+    "loop.terminate",
+];
+
 /// Reason the event loop terminated.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TerminationReason {
@@ -48,29 +98,55 @@ pub enum TerminationReason {
     Interrupted,
     /// Restart requested via Telegram `/restart` command.
     RestartRequested,
+    /// A hat published an event listed in `event_loop.fail_on_event`.
+    GateFailed,
+    /// A hat published the `event_loop.until_event` topic
+    /// (`ralph run --until-event`).
+    UntilEventReached,
+    /// A hat used up its `max_iterations` budget and no hat handles
+    /// `hat.budget_exceeded`.
+    HatBudgetExceeded,
+    /// An event arrived at a full queue with `on_overflow: fail`.
+    EventQueueOverflow,
+    /// `event_loop.completion_check` rejected the completion promise
+    /// `max_rejections` times.
+    CompletionRejected,
+    /// Hats kept publishing events that no hat triggers on, so the loop
+    /// could not make progress. Usually a typo between one hat's
+    /// `publishes` and another's `triggers`.
+    NoMatchingHat {
+        /// The topic no hat subscribed to.
+        topic: String,
+    },
 }
 
 impl TerminationReason {
     /// Returns the exit code for this termination reason per spec.
     ///
     /// Per spec "Loop Termination" section:
-    /// - 0: Completion promise detected (success)
+    /// - 0: Completion promise detected (success), or `--until-event` reached
     /// - 1: Consecutive failures or unrecoverable error (failure)
     /// - 2: Max iterations, max runtime, or max cost exceeded (limit)
+    /// - 4: No hat matched a published event (configuration mismatch)
     /// - 130: User interrupt (SIGINT = 128 + 2)
     pub fn exit_code(&self) -> i32 {
         match self {
-            TerminationReason::CompletionPromise => 0,
+            TerminationReason::CompletionPromise | TerminationReason::UntilEventReached => 0,
             TerminationReason::ConsecutiveFailures
             | TerminationReason::LoopThrashing
             | TerminationReason::ValidationFailure
+            | TerminationReason::GateFailed
+            | TerminationReason::EventQueueOverflow
+            | TerminationReason::CompletionRejected
             | TerminationReason::Stopped => 1,
             TerminationReason::MaxIterations
             | TerminationReason::MaxRuntime
-            | TerminationReason::MaxCost => 2,
+            | TerminationReason::MaxCost
+            | TerminationReason::HatBudgetExceeded => 2,
             TerminationReason::Interrupted => 130,
             // Restart uses exit code 3 to signal the caller to exec-replace
             TerminationReason::RestartRequested => 3,
+            TerminationReason::NoMatchingHat { .. } => 4,
         }
     }
 
@@ -90,10 +166,19 @@ impl TerminationReason {
             TerminationReason::Stopped => "stopped",
             TerminationReason::Interrupted => "interrupted",
             TerminationReason::RestartRequested => "restart_requested",
+            TerminationReason::GateFailed => "gate_failed",
+            TerminationReason::UntilEventReached => "until_event_reached",
+            TerminationReason::HatBudgetExceeded => "hat_budget_exceeded",
+            TerminationReason::EventQueueOverflow => "event_queue_overflow",
+            TerminationReason::CompletionRejected => "completion_rejected",
+            TerminationReason::NoMatchingHat { .. } => "no_matching_hat",
         }
     }
 
     /// Returns true if this is a successful completion (not an error or limit).
+    ///
+    /// Stopping at `--until-event` exits 0 but is not a completion: the work
+    /// is meant to be resumed with `--continue`.
     pub fn is_success(&self) -> bool {
         matches!(self, TerminationReason::CompletionPromise)
     }
@@ -120,6 +205,25 @@ pub struct EventLoop {
     /// Robot service for human-in-the-loop communication.
     /// Injected externally when `human.enabled` is true and this is the primary loop.
     robot_service: Option<Box<dyn RobotService>>,
+    /// Completion promise matcher (literal topics plus optional regex).
+    completion_matcher: CompletionMatcher,
+    /// Payload schemas from `event_schemas`.
+    event_schemas: EventSchemas,
+    /// Events read from the events file, waiting to be released to the bus.
+    event_queue: EventQueue,
+    /// Filter for repeated events from the events file.
+    event_dedup: EventDedup,
+    /// `event.unhandled` and `event.duplicate` records not yet taken by the
+    /// caller.
+    unhandled_events: Vec<Event>,
+    /// Topic of the start event, already on the bus when the loop logs it.
+    start_topic: Option<String>,
+    /// When the prompt for the current iteration was built.
+    iteration_started_at: Option<Instant>,
+    /// Metrics for each processed iteration, for `run-summary.json`.
+    iteration_metrics: Vec<IterationMetrics>,
+    /// `.ralphignore` rules for what Ralph injects into prompts.
+    ralph_ignore: RalphIgnore,
 }
 
 impl EventLoop {
@@ -127,14 +231,17 @@ impl EventLoop {
     pub fn new(config: RalphConfig) -> Self {
         // Try to create diagnostics collector, but fall back to disabled if it fails
         // (e.g., in tests without proper directory setup)
-        let diagnostics = crate::diagnostics::DiagnosticsCollector::new(std::path::Path::new("."))
-            .unwrap_or_else(|e| {
-                debug!(
-                    "Failed to initialize diagnostics: {}, using disabled collector",
-                    e
-                );
-                crate::diagnostics::DiagnosticsCollector::disabled()
-            });
+        let diagnostics = crate::diagnostics::DiagnosticsCollector::with_config(
+            std::path::Path::new("."),
+            &config.features.diagnostics,
+        )
+        .unwrap_or_else(|e| {
+            debug!(
+                "Failed to initialize diagnostics: {}, using disabled collector",
+                e
+            );
+            crate::diagnostics::DiagnosticsCollector::disabled()
+        });
 
         Self::with_diagnostics(config, diagnostics)
     }
@@ -145,14 +252,17 @@ impl EventLoop {
     /// are located. Use this for multi-loop scenarios where each loop runs
     /// in an isolated workspace (git worktree).
     pub fn with_context(config: RalphConfig, context: LoopContext) -> Self {
-        let diagnostics = crate::diagnostics::DiagnosticsCollector::new(context.workspace())
-            .unwrap_or_else(|e| {
-                debug!(
-                    "Failed to initialize diagnostics: {}, using disabled collector",
-                    e
-                );
-                crate::diagnostics::DiagnosticsCollector::disabled()
-            });
+        let diagnostics = crate::diagnostics::DiagnosticsCollector::with_config(
+            context.workspace(),
+            &config.features.diagnostics,
+        )
+        .unwrap_or_else(|e| {
+            debug!(
+                "Failed to initialize diagnostics: {}, using disabled collector",
+                e
+            );
+            crate::diagnostics::DiagnosticsCollector::disabled()
+        });
 
         Self::with_context_and_diagnostics(config, context, diagnostics)
     }
@@ -164,8 +274,14 @@ impl EventLoop {
         diagnostics: crate::diagnostics::DiagnosticsCollector,
     ) -> Self {
         let registry = HatRegistry::from_config(&config);
+        // Template variables in hat instructions point into this loop's workspace
+        let mut instruction_core = config.core.clone();
+        instruction_core.workspace_root = context.workspace().to_path_buf();
+        instruction_core.scratchpad = context.scratchpad_path().display().to_string();
         let instruction_builder =
-            InstructionBuilder::with_events(config.core.clone(), config.events.clone());
+            InstructionBuilder::with_events(instruction_core, config.events.clone())
+                .with_context(config.event_loop.context.clone())
+                .with_preamble(config.include_preamble.clone());
 
         let mut bus = EventBus::new();
 
@@ -214,15 +330,19 @@ impl EventLoop {
             String::new()
         };
 
+        let ralph_ignore = RalphIgnore::load(context.workspace());
+
         // When memories are enabled, add tasks CLI instructions alongside scratchpad
         let ralph = HatlessRalph::new(
-            config.event_loop.completion_promise.clone(),
+            config.event_loop.completion_promise.primary(),
             config.core.clone(),
             &registry,
             config.event_loop.starting_event.clone(),
         )
         .with_memories_enabled(config.memories.enabled)
-        .with_skill_index(skill_index);
+        .with_skill_index(skill_index)
+        .with_ignore(ralph_ignore.clone())
+        .with_instructions_preamble(config.include_preamble.clone());
 
         // Read timestamped events path from marker file, fall back to default
         // The marker file contains a relative path like ".ralph/events-20260127-123456.jsonl"
@@ -234,6 +354,10 @@ impl EventLoop {
             })
             .unwrap_or_else(|_| context.events_path());
         let event_reader = EventReader::new(&events_path);
+        let completion_matcher = CompletionMatcher::from_config(&config.event_loop);
+        let event_schemas = compile_event_schemas(&config);
+        let event_queue = EventQueue::new(config.event_loop.queue.clone());
+        let event_dedup = EventDedup::new(config.event_loop.dedup.clone());
 
         Self {
             config,
@@ -248,6 +372,15 @@ impl EventLoop {
             loop_context: Some(context),
             skill_registry,
             robot_service: None,
+            completion_matcher,
+            event_schemas,
+            event_queue,
+            event_dedup,
+            unhandled_events: Vec::new(),
+            start_topic: None,
+            iteration_started_at: None,
+            iteration_metrics: Vec::new(),
+            ralph_ignore,
         }
     }
 
@@ -258,7 +391,9 @@ impl EventLoop {
     ) -> Self {
         let registry = HatRegistry::from_config(&config);
         let instruction_builder =
-            InstructionBuilder::with_events(config.core.clone(), config.events.clone());
+            InstructionBuilder::with_events(config.core.clone(), config.events.clone())
+                .with_context(config.event_loop.context.clone())
+                .with_preamble(config.include_preamble.clone());
 
         let mut bus = EventBus::new();
 
@@ -308,15 +443,19 @@ impl EventLoop {
             String::new()
         };
 
+        let ralph_ignore = RalphIgnore::load(&config.core.workspace_root);
+
         // When memories are enabled, add tasks CLI instructions alongside scratchpad
         let ralph = HatlessRalph::new(
-            config.event_loop.completion_promise.clone(),
+            config.event_loop.completion_promise.primary(),
             config.core.clone(),
             &registry,
             config.event_loop.starting_event.clone(),
         )
         .with_memories_enabled(config.memories.enabled)
-        .with_skill_index(skill_index);
+        .with_skill_index(skill_index)
+        .with_ignore(ralph_ignore.clone())
+        .with_instructions_preamble(config.include_preamble.clone());
 
         // Read events path from marker file, fall back to default if not present
         // The marker file is written by run_loop_impl() at run startup
@@ -324,6 +463,10 @@ impl EventLoop {
             .map(|s| s.trim().to_string())
             .unwrap_or_else(|_| ".ralph/events.jsonl".to_string());
         let event_reader = EventReader::new(&events_path);
+        let completion_matcher = CompletionMatcher::from_config(&config.event_loop);
+        let event_schemas = compile_event_schemas(&config);
+        let event_queue = EventQueue::new(config.event_loop.queue.clone());
+        let event_dedup = EventDedup::new(config.event_loop.dedup.clone());
 
         Self {
             config,
@@ -338,6 +481,15 @@ impl EventLoop {
             loop_context: None,
             skill_registry,
             robot_service: None,
+            completion_matcher,
+            event_schemas,
+            event_queue,
+            event_dedup,
+            unhandled_events: Vec::new(),
+            start_topic: None,
+            iteration_started_at: None,
+            iteration_metrics: Vec::new(),
+            ralph_ignore,
         }
     }
 
@@ -398,6 +550,11 @@ impl EventLoop {
             .and_then(|config| config.backend.as_ref())
     }
 
+    /// Gets the environment variables configured for a hat's backend.
+    pub fn get_hat_env(&self, hat_id: &HatId) -> Option<&BTreeMap<String, String>> {
+        self.registry.get_config(hat_id).map(|config| &config.env)
+    }
+
     /// Adds an observer that receives all published events.
     ///
     /// Multiple observers can be added (e.g., session recorder + TUI).
@@ -443,6 +600,40 @@ impl EventLoop {
             return Some(TerminationReason::ConsecutiveFailures);
         }
 
+        // Check for failure gate: a hat published a `fail_on_event` topic
+        if self.state.gate_failed_topic.is_some() {
+            return Some(TerminationReason::GateFailed);
+        }
+
+        // Check for the early-stop topic from `--until-event`
+        if self.state.until_event_topic.is_some() {
+            return Some(TerminationReason::UntilEventReached);
+        }
+
+        // Check for an unhandled per-hat budget overrun
+        if self.state.budget_exceeded_hat.is_some() {
+            return Some(TerminationReason::HatBudgetExceeded);
+        }
+
+        // Check for an event rejected by a full queue (`on_overflow: fail`)
+        if self.state.queue_overflow_topic.is_some() {
+            return Some(TerminationReason::EventQueueOverflow);
+        }
+
+        // Check for a completion check that keeps rejecting the work
+        if let Some(check) = &cfg.completion_check
+            && self.state.completion_rejections >= check.max_rejections
+        {
+            return Some(TerminationReason::CompletionRejected);
+        }
+
+        // Check for events that no hat triggers on
+        if let Some(topic) = &self.state.no_matching_hat_topic {
+            return Some(TerminationReason::NoMatchingHat {
+                topic: topic.clone(),
+            });
+        }
+
         // Check for loop thrashing: planner keeps dispatching abandoned tasks
         if self.state.abandoned_task_redispatches >= 3 {
             return Some(TerminationReason::LoopThrashing);
@@ -471,6 +662,65 @@ impl EventLoop {
         None
     }
 
+    /// Returns true if a stop has been requested (`.ralph/stop-requested`).
+    ///
+    /// Unlike [`check_termination`](Self::check_termination), this leaves the
+    /// marker in place so the stop still takes effect.
+    pub fn stop_requested(&self) -> bool {
+        std::path::Path::new(&self.config.core.workspace_root)
+            .join(".ralph/stop-requested")
+            .exists()
+    }
+
+    /// Returns true if the agent wrote events to JSONL that are not read yet.
+    pub fn has_unread_events(&self) -> bool {
+        self.state.completion_requested || self.event_reader.has_unread()
+    }
+
+    /// Records an `iteration.retried` event for a failed attempt.
+    ///
+    /// The event goes to observers (e.g., the session recorder) only; it is
+    /// not routed to hats, so it never triggers an iteration of its own.
+    pub fn notify_iteration_retried(&self, attempt: u32, failure: &str) -> Event {
+        let max_attempts = self.config.event_loop.retry.max_attempts;
+        let payload = format!(
+            "Iteration {iteration} attempt {attempt} of {max_attempts} failed; retrying.\n- iteration: {iteration}\n- attempt: {attempt}\n- max_attempts: {max_attempts}\n- failure: {failure}",
+            iteration = self.state.iteration + 1,
+        );
+        let event = Event::new(ITERATION_RETRIED_TOPIC, payload);
+        self.bus.notify_observers(&event);
+        event
+    }
+
+    /// Notifies observers that the loop is paused before the next iteration.
+    ///
+    /// Observer-only, like `iteration.retried`: no hat can trigger on it.
+    pub fn notify_loop_paused(&self) -> Event {
+        let payload = format!(
+            "Loop paused before iteration {next}.\n- iteration: {iteration}",
+            next = self.state.iteration + 1,
+            iteration = self.state.iteration,
+        );
+        let event = Event::new(LOOP_PAUSED_TOPIC, payload);
+        self.bus.notify_observers(&event);
+        event
+    }
+
+    /// Notifies observers that a paused loop is continuing.
+    ///
+    /// `step` is true when only a single iteration was released.
+    pub fn notify_loop_resumed(&self, step: bool, paused_for: Duration) -> Event {
+        let mode = if step { "step" } else { "resume" };
+        let payload = format!(
+            "Loop resumed ({mode}) after {secs}s paused.\n- iteration: {iteration}\n- mode: {mode}\n- paused_secs: {secs}",
+            secs = paused_for.as_secs(),
+            iteration = self.state.iteration,
+        );
+        let event = Event::new(LOOP_RESUMED_TOPIC, payload);
+        self.bus.notify_observers(&event);
+        event
+    }
+
     /// Checks if a completion event was received and returns termination reason.
     ///
     /// Completion is only accepted via JSONL events (e.g., `ralph emit`).
@@ -481,6 +731,12 @@ impl EventLoop {
 
         self.state.completion_requested = false;
 
+        // A failure gate outranks completion; check_termination reports it
+        if let Some(topic) = &self.state.gate_failed_topic {
+            warn!(topic = %topic, "Completion event ignored - failure gate event was published");
+            return None;
+        }
+
         // In persistent mode, suppress completion and keep the loop alive
         if self.config.event_loop.persistent {
             info!("Completion event suppressed - persistent mode active, loop staying alive");
@@ -532,6 +788,61 @@ impl EventLoop {
         Some(TerminationReason::CompletionPromise)
     }
 
+    /// Records the result of `event_loop.completion_check` for an accepted
+    /// completion event and returns the recorded event.
+    ///
+    /// A passing check is recorded as observer-only `completion.verified`.
+    /// A failing one publishes `completion.rejected` with the command's
+    /// output tail, so the hats (or Ralph) pick the work back up;
+    /// [`check_termination`](Self::check_termination) stops the loop once
+    /// `max_rejections` is reached.
+    pub fn record_completion_check(
+        &mut self,
+        outcome: &crate::completion_check::CompletionCheckOutcome,
+    ) -> Event {
+        let topic = self.state.completion_topic.clone().unwrap_or_default();
+        if outcome.passed() {
+            let payload = format!(
+                "Completion check passed ({status}).\n- command: {command}\n- completion: {topic}",
+                status = outcome.status(),
+                command = outcome.command,
+            );
+            let event = Event::new(COMPLETION_VERIFIED_TOPIC, payload);
+            self.bus.notify_observers(&event);
+            return event;
+        }
+
+        self.state.completion_rejections += 1;
+        self.state.completion_topic = None;
+        let max_rejections = self
+            .config
+            .event_loop
+            .completion_check
+            .as_ref()
+            .map_or(0, |check| check.max_rejections);
+        warn!(
+            command = %outcome.command,
+            status = %outcome.status(),
+            rejections = self.state.completion_rejections,
+            max_rejections,
+            "Completion check failed - completion rejected"
+        );
+
+        let payload = format!(
+            "Completion ({topic}) rejected: the completion check failed ({status}). \
+             Fix the failures below, then emit {topic} again.\n\
+             - command: {command}\n- rejection: {rejections}/{max_rejections}\n\n\
+             ## Output (tail)\n```\n{tail}\n```",
+            status = outcome.status(),
+            command = outcome.command,
+            rejections = self.state.completion_rejections,
+            tail = outcome.output_tail,
+        );
+        let event = Event::new(COMPLETION_REJECTED_TOPIC, payload);
+        self.bus.publish(event.clone());
+        event
+    }
+
     /// Initializes the loop by publishing the start event.
     pub fn initialize(&mut self, prompt_content: &str) {
         // Use configured starting_event or default to task.start for backward compatibility
@@ -541,26 +852,120 @@ impl EventLoop {
             .starting_event
             .clone()
             .unwrap_or_else(|| "task.start".to_string());
-        self.initialize_with_topic(&topic, prompt_content);
+        let payload = self
+            .config
+            .event_loop
+            .starting_payload
+            .clone()
+            .unwrap_or_else(|| prompt_content.to_string());
+        self.initialize_with_topic(&topic, prompt_content, &payload);
     }
 
     /// Initializes the loop for resume mode by publishing task.resume.
     ///
     /// Per spec: "User can run `ralph resume` to restart reading existing scratchpad."
     /// The planner should read the existing scratchpad rather than doing fresh gap analysis.
+    ///
+    /// With a loop state snapshot for the current events file, the
+    /// iteration count, counters and pending events are restored from it and
+    /// reading continues where the previous run left off. Otherwise the loop
+    /// starts over from `task.resume` and the scratchpad.
     pub fn initialize_resume(&mut self, prompt_content: &str) {
+        if let Some(snapshot) = self.load_state_snapshot() {
+            self.resume_from_snapshot(prompt_content, snapshot);
+            return;
+        }
+
         // Resume always uses task.resume regardless of starting_event config
-        self.initialize_with_topic("task.resume", prompt_content);
+        self.initialize_with_topic("task.resume", prompt_content, prompt_content);
+
+        // The events file is read again from the start, so `--until-event`
+        // must not stop on the occurrences that ended the previous run
+        if let Some(topic) = self.config.event_loop.until_event.as_deref() {
+            self.state.stale_until_events = self
+                .event_reader
+                .peek_new_events()
+                .map(|result| result.events.iter().filter(|e| e.topic == topic).count())
+                .unwrap_or(0);
+        }
+    }
+
+    /// Loads the loop state snapshot if it belongs to the events file being
+    /// read. A missing, corrupt or stale snapshot is ignored.
+    fn load_state_snapshot(&self) -> Option<LoopStateSnapshot> {
+        let path = self.state_snapshot_path();
+        let snapshot = match LoopStateSnapshot::load(&path) {
+            Ok(snapshot) => snapshot,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("No loop state snapshot at {}", path.display());
+                return None;
+            }
+            Err(e) => {
+                warn!(
+                    "Ignoring unreadable loop state snapshot {}: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        if snapshot.events_file != self.event_reader.path() {
+            warn!(
+                "Ignoring loop state snapshot for another events file ({})",
+                snapshot.events_file.display()
+            );
+            return None;
+        }
+        let events_len = std::fs::metadata(&snapshot.events_file).map_or(0, |meta| meta.len());
+        if events_len < snapshot.events_position {
+            warn!(
+                "Ignoring loop state snapshot: events file is shorter than the saved position ({} < {})",
+                events_len, snapshot.events_position
+            );
+            return None;
+        }
+        Some(snapshot)
+    }
+
+    /// Restores a snapshot written by a previous run of this loop.
+    ///
+    /// The snapshot's pending events are published in place of `task.resume`;
+    /// with none pending, `task.resume` is published as usual.
+    fn resume_from_snapshot(&mut self, prompt_content: &str, snapshot: LoopStateSnapshot) {
+        self.ralph.set_objective(prompt_content.to_string());
+        self.start_topic = Some("task.resume".to_string());
+        self.state.restore(&snapshot);
+        self.event_reader.seek(snapshot.events_position);
+
+        let pending = snapshot.pending_events.len();
+        let queued = snapshot.queued_events.len();
+        for event in snapshot.queued_events {
+            self.enqueue_event(event);
+        }
+        if snapshot.pending_events.is_empty() {
+            self.bus.publish(Event::new("task.resume", prompt_content));
+        } else {
+            for event in snapshot.pending_events {
+                self.bus.publish(event);
+            }
+        }
+
+        info!(
+            iteration = snapshot.iteration,
+            pending, queued, "Resumed from loop state snapshot saved at {}", snapshot.saved_at
+        );
     }
 
-    /// Common initialization logic with configurable topic.
-    fn initialize_with_topic(&mut self, topic: &str, prompt_content: &str) {
+    /// Common initialization logic with configurable topic and payload.
+    fn initialize_with_topic(&mut self, topic: &str, prompt_content: &str, payload: &str) {
         // Store the objective so it persists across all iterations.
         // After iteration 1, bus.take_pending() consumes the start event,
         // so without this the objective would be invisible to later hats.
         self.ralph.set_objective(prompt_content.to_string());
 
-        let start_event = Event::new(topic, prompt_content);
+        self.start_topic = Some(topic.to_string());
+        let start_event = Event::new(topic, payload);
         self.bus.publish(start_event);
         debug!(topic = topic, "Published {} event", topic);
     }
@@ -601,7 +1006,49 @@ impl EventLoop {
     /// Use this after `process_output` to detect if the LLM failed to publish an event.
     /// If false after processing, the loop will terminate on the next iteration.
     pub fn has_pending_events(&self) -> bool {
-        self.bus.next_hat_with_pending().is_some() || self.bus.has_human_pending()
+        self.bus.next_hat_with_pending().is_some()
+            || self.bus.has_human_pending()
+            || !self.event_queue.is_empty()
+    }
+
+    /// Returns the distinct topics the next `build_prompt(hat_id)` will
+    /// consume.
+    ///
+    /// For `ralph` that is every hat's pending events, since Ralph
+    /// coordinates them all in one iteration.
+    pub fn pending_topics(&self, hat_id: &HatId) -> Vec<String> {
+        let mut hat_ids: Vec<&HatId> = if hat_id.as_str() == "ralph" {
+            self.bus.hat_ids().collect()
+        } else {
+            vec![hat_id]
+        };
+        hat_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut topics: Vec<String> = Vec::new();
+        let pending = hat_ids
+            .into_iter()
+            .filter_map(|id| self.bus.peek_pending(id))
+            .flatten()
+            .chain(self.bus.peek_human_pending());
+        for event in pending {
+            if !topics.iter().any(|topic| topic == event.topic.as_str()) {
+                topics.push(event.topic.as_str().to_string());
+            }
+        }
+        topics
+    }
+
+    /// Returns the number of events waiting in the queue.
+    pub fn queue_depth(&self) -> usize {
+        self.event_queue.len()
+    }
+
+    /// Takes the `event.unhandled` and `event.duplicate` records produced
+    /// since the last call.
+    ///
+    /// Observers have already seen them; this lets the caller log them.
+    pub fn take_unhandled_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.unhandled_events)
     }
 
     /// Checks if any pending events are human-related (human.response, human.guidance).
@@ -669,6 +1116,8 @@ impl EventLoop {
     /// primed memories to the prompt context. If a scratchpad file exists and is
     /// non-empty, its content is also prepended (before memories).
     pub fn build_prompt(&mut self, hat_id: &HatId) -> Option<String> {
+        self.iteration_started_at = Some(Instant::now());
+
         // Handle "ralph" hat - the constant coordinator
         // Per spec: "Hatless Ralph is constant — Cannot be replaced, overwritten, or configured away"
         if hat_id.as_str() == "ralph" {
@@ -698,7 +1147,8 @@ impl EventLoop {
                 self.ralph.clear_robot_guidance();
                 let with_skills = self.prepend_auto_inject_skills(base_prompt);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
-                let final_prompt = self.prepend_ready_tasks(with_scratchpad);
+                let with_tasks = self.prepend_ready_tasks(with_scratchpad);
+                let final_prompt = self.prepend_iteration_context(with_tasks);
 
                 debug!("build_prompt: routing to HatlessRalph (solo mode)");
                 return Some(final_prompt);
@@ -753,7 +1203,13 @@ impl EventLoop {
                 let active_hat_ids = self.determine_active_hat_ids(&regular_events);
                 self.record_hat_activations(&active_hat_ids);
                 self.state.last_active_hat_ids = active_hat_ids.clone();
-                let active_hats = self.determine_active_hats(&regular_events);
+                let iteration = self.state.iteration + 1;
+                let rendered_hats: Vec<Hat> = self
+                    .determine_active_hats(&regular_events)
+                    .into_iter()
+                    .map(|hat| self.instruction_builder.render_hat(hat, iteration))
+                    .collect();
+                let active_hats: Vec<&Hat> = rendered_hats.iter().collect();
 
                 // Format events for context
                 let events_context = regular_events
@@ -778,7 +1234,8 @@ impl EventLoop {
                 self.ralph.clear_robot_guidance();
                 let with_skills = self.prepend_auto_inject_skills(base_prompt);
                 let with_scratchpad = self.prepend_scratchpad(with_skills);
-                let final_prompt = self.prepend_ready_tasks(with_scratchpad);
+                let with_tasks = self.prepend_ready_tasks(with_scratchpad);
+                let final_prompt = self.prepend_iteration_context(with_tasks);
 
                 return Some(final_prompt);
             }
@@ -808,10 +1265,13 @@ impl EventLoop {
             "build_prompt: routing to build_custom_hat() for '{}'",
             hat_id.as_str()
         );
-        Some(
-            self.instruction_builder
-                .build_custom_hat(hat, &events_context),
-        )
+        let hat = self
+            .instruction_builder
+            .render_hat(hat, self.state.iteration + 1);
+        let prompt = self
+            .instruction_builder
+            .build_custom_hat(&hat, &events_context);
+        Some(self.prepend_iteration_context(prompt))
     }
 
     /// Stores guidance payloads, persists them to scratchpad, and prepares them for prompt injection.
@@ -924,8 +1384,9 @@ impl EventLoop {
 
     /// Injects memory data and the ralph-tools skill into the prefix.
     ///
-    /// Special case: loads memory entries from the store, applies budget
-    /// truncation, then appends the ralph-tools skill content (which covers
+    /// Special case: loads memory entries from the store, evicts memories
+    /// that don't fit the budget (per `features.memory.eviction`) and
+    /// truncates what remains, then appends the ralph-tools skill content (which covers
     /// both tasks and memories CLI usage).
     /// Memory data is gated by `memories.enabled && memories.inject == Auto`.
     /// The ralph-tools skill is injected when either memories or tasks are enabled.
@@ -933,7 +1394,16 @@ impl EventLoop {
         let memories_config = &self.config.memories;
 
         // Inject memory DATA if memories are enabled with auto-inject
-        if memories_config.enabled && memories_config.inject == InjectMode::Auto {
+        let memories_ignored = self
+            .ralph_ignore
+            .is_ignored(Path::new(".ralph/agent/memories.md"));
+        if memories_ignored {
+            debug!("Memories file is in .ralphignore - not injecting memories");
+        }
+        if memories_config.enabled
+            && memories_config.inject == InjectMode::Auto
+            && !memories_ignored
+        {
             info!(
                 "Memory injection check: enabled={}, inject={:?}, workspace_root={:?}",
                 memories_config.enabled, memories_config.inject, self.config.core.workspace_root
@@ -949,7 +1419,7 @@ impl EventLoop {
                 memories_path.exists()
             );
 
-            let memories = match store.load() {
+            let mut memories = match store.load() {
                 Ok(memories) => {
                     info!("Successfully loaded {} memories from store", memories.len());
                     memories
@@ -963,12 +1433,34 @@ impl EventLoop {
                 }
             };
 
+            let loaded = memories.len();
+            memories.retain(|memory| !self.ralph_ignore.mentions_ignored(&memory.content));
+            if memories.len() < loaded {
+                debug!(
+                    "Skipped {} memories that mention .ralphignore paths",
+                    loaded - memories.len()
+                );
+            }
+
             if memories.is_empty() {
                 info!("Memory store is empty - no memories to inject");
             } else {
+                let eviction = self.config.features.memory.eviction;
+                let result = evict_to_budget(memories, memories_config.budget, eviction);
+                if !result.evicted.is_empty() {
+                    debug!(
+                        "Evicted {} memories to fit budget {} ({}): {:?}",
+                        result.evicted.len(),
+                        memories_config.budget,
+                        eviction,
+                        result.evicted.iter().map(|m| &m.id).collect::<Vec<_>>()
+                    );
+                }
+                let memories = result.kept;
                 let mut memories_content = format_memories_as_markdown(&memories);
 
                 if memories_config.budget > 0 {
+                    // A single memory can still exceed the budget on its own
                     let original_len = memories_content.len();
                     memories_content =
                         truncate_to_budget(&memories_content, memories_config.budget);
@@ -1056,6 +1548,14 @@ impl EventLoop {
     fn prepend_scratchpad(&self, prompt: String) -> String {
         let scratchpad_path = self.scratchpad_path();
 
+        if self.ralph_ignore.is_ignored(&scratchpad_path) {
+            debug!(
+                "Scratchpad {:?} is in .ralphignore, skipping injection",
+                scratchpad_path
+            );
+            return prompt;
+        }
+
         let resolved_path = if scratchpad_path.is_relative() {
             self.config.core.workspace_root.join(&scratchpad_path)
         } else {
@@ -1221,6 +1721,75 @@ impl EventLoop {
         final_prompt
     }
 
+    /// Prepends the `<iteration-context>` block when `event_loop.context` is
+    /// enabled: iteration budget, recent events, and open tasks.
+    fn prepend_iteration_context(&self, prompt: String) -> String {
+        let settings = self.instruction_builder.context_config();
+        if !settings.enabled {
+            return prompt;
+        }
+
+        let recent_events = if settings.events && settings.recent_events > 0 {
+            EventHistory::new(self.event_reader.path())
+                .read_last(settings.recent_events)
+                .unwrap_or_else(|e| {
+                    info!("Failed to read recent events for context: {}", e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        let open_tasks = if settings.tasks {
+            self.open_tasks()
+        } else {
+            Vec::new()
+        };
+        let context = IterationContext {
+            iteration: self.state.iteration + 1,
+            max_iterations: self.config.event_loop.max_iterations,
+            recent_events,
+            open_tasks,
+        };
+
+        match self.instruction_builder.build_iteration_context(&context) {
+            Some(block) => {
+                debug!("Injecting iteration context ({} chars)", block.len());
+                block + &prompt
+            }
+            None => prompt,
+        }
+    }
+
+    /// Open tasks from the task store, highest priority first.
+    fn open_tasks(&self) -> Vec<crate::task::Task> {
+        let tasks_path = self.tasks_path();
+        let resolved_path = if tasks_path.is_relative() {
+            self.config.core.workspace_root.join(&tasks_path)
+        } else {
+            tasks_path
+        };
+        if !resolved_path.exists() {
+            return Vec::new();
+        }
+
+        match crate::task_store::TaskStore::load(&resolved_path) {
+            Ok(store) => {
+                let mut tasks: Vec<_> = store
+                    .open()
+                    .into_iter()
+                    .filter(|task| !task.status.is_terminal())
+                    .cloned()
+                    .collect();
+                tasks.sort_by_key(|task| task.priority);
+                tasks
+            }
+            Err(e) => {
+                info!("Failed to load task store for context: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Builds the Ralph prompt (coordination mode).
     pub fn build_ralph_prompt(&self, prompt_content: &str) -> String {
         self.ralph.build_prompt(prompt_content, &[])
@@ -1317,6 +1886,65 @@ impl EventLoop {
         )
     }
 
+    /// Drops pending events for hats that have used up their `max_iterations`.
+    ///
+    /// The first time a hat's budget runs out, publishes `hat.budget_exceeded`.
+    /// If no hat subscribes to that topic, records the hat so that
+    /// [`check_termination`](Self::check_termination) ends the loop.
+    pub fn enforce_hat_budgets(&mut self) {
+        let mut hat_ids: Vec<HatId> = self.bus.hat_ids().cloned().collect();
+        hat_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        for hat_id in hat_ids {
+            let Some(max) = self
+                .registry
+                .get_config(&hat_id)
+                .and_then(|config| config.max_iterations)
+            else {
+                continue;
+            };
+            let count = *self.state.hat_activation_counts.get(&hat_id).unwrap_or(&0);
+            if count < max
+                || self
+                    .bus
+                    .peek_pending(&hat_id)
+                    .is_none_or(|pending| pending.is_empty())
+            {
+                continue;
+            }
+
+            let dropped = self.bus.take_pending(&hat_id);
+            if !self.state.budget_exceeded_hats.insert(hat_id.clone()) {
+                continue;
+            }
+
+            let mut dropped_topics: Vec<String> =
+                dropped.iter().map(|e| e.topic.to_string()).collect();
+            dropped_topics.sort();
+
+            warn!(
+                hat = %hat_id.as_str(),
+                max_iterations = max,
+                iterations = count,
+                "Hat exceeded its iteration budget"
+            );
+
+            let payload = format!(
+                "Hat '{hat}' exceeded its iteration budget.\n- hat: {hat}\n- max_iterations: {max}\n- iterations: {count}\n- dropped_topics:\n  - {topics}",
+                hat = hat_id.as_str(),
+                topics = dropped_topics.join("\n  - ")
+            );
+
+            if !self.registry.has_subscriber(HAT_BUDGET_EXCEEDED_TOPIC)
+                && self.state.budget_exceeded_hat.is_none()
+            {
+                self.state.budget_exceeded_hat = Some(hat_id.clone());
+            }
+            self.bus
+                .publish(Event::new(HAT_BUDGET_EXCEEDED_TOPIC, payload));
+        }
+    }
+
     fn record_hat_activations(&mut self, active_hat_ids: &[HatId]) {
         for hat_id in active_hat_ids {
             *self
@@ -1386,6 +2014,22 @@ impl EventLoop {
         self.state.iteration += 1;
         self.state.last_hat = Some(hat_id.clone());
 
+        let hat = self
+            .state
+            .last_active_hat_ids
+            .first()
+            .filter(|_| hat_id.as_str() == "ralph")
+            .unwrap_or(hat_id);
+        self.iteration_metrics.push(IterationMetrics {
+            iteration: self.state.iteration,
+            hat: hat.to_string(),
+            duration_ms: self
+                .iteration_started_at
+                .take()
+                .map_or(0, |started| duration_ms(started.elapsed())),
+            events_emitted: 0,
+        });
+
         // Periodic robot check-in
         if let Some(interval_secs) = self.config.robot.checkin_interval_seconds
             && let Some(ref robot_service) = self.robot_service
@@ -1416,7 +2060,9 @@ impl EventLoop {
         self.diagnostics.log_orchestration(
             self.state.iteration,
             "loop",
-            crate::diagnostics::OrchestrationEvent::IterationStarted,
+            crate::diagnostics::OrchestrationEvent::IterationStarted {
+                queue_depth: self.event_queue.len(),
+            },
         );
 
         // Log hat selected
@@ -1631,7 +2277,8 @@ impl EventLoop {
     ///
     /// Returns true if Ralph should be invoked to handle orphaned events.
     pub fn process_events_from_jsonl(&mut self) -> std::io::Result<bool> {
-        let result = self.event_reader.read_new_events()?;
+        let mut result = self.event_reader.read_new_events()?;
+        result.events.retain(|event| !self.is_own_record(event));
 
         // Handle malformed lines with backpressure
         for malformed in &result.malformed {
@@ -1655,6 +2302,8 @@ impl EventLoop {
         }
 
         if result.events.is_empty() && result.malformed.is_empty() {
+            self.track_unmatched_events(&[]);
+            self.release_queued_event();
             return Ok(false);
         }
 
@@ -1662,14 +2311,81 @@ impl EventLoop {
 
         // Validate and transform events (apply backpressure for build.done)
         let mut validated_events = Vec::new();
-        let completion_topic = self.config.event_loop.completion_promise.as_str();
         let total_events = result.events.len();
+        if let Some(metrics) = self
+            .iteration_metrics
+            .last_mut()
+            .filter(|metrics| metrics.iteration == self.state.iteration)
+        {
+            metrics.events_emitted += total_events;
+        }
         for (index, event) in result.events.into_iter().enumerate() {
+            self.state.last_event_topic = Some(event.topic.clone());
+
+            if !self.completion_matcher.matches(&event.topic)
+                && let Err(suppressed) = self.event_dedup.check(
+                    self.state.iteration,
+                    &event.topic,
+                    event.payload.as_deref(),
+                )
+            {
+                warn!(topic = %event.topic, "Suppressing repeated event: {}", suppressed);
+                self.record_duplicate(&event, &suppressed);
+                continue;
+            }
+
+            if let Err(violation) = self
+                .event_schemas
+                .check(&event.topic, event.payload.as_deref())
+            {
+                if self.config.event_loop.schema_mismatch == SchemaMismatchAction::Reject {
+                    warn!(topic = %event.topic, "Event rejected: {}", violation);
+                    self.bus.publish(Event::new(
+                        "event.malformed",
+                        format!("Rejected event '{}': {}", event.topic, violation),
+                    ));
+                    continue;
+                }
+                warn!(topic = %event.topic, "{}", violation);
+            }
+
             let payload = event.payload.clone().unwrap_or_default();
 
-            if event.topic == completion_topic {
+            if self.state.gate_failed_topic.is_none()
+                && self.config.event_loop.fail_on_event.contains(&event.topic)
+            {
+                warn!(
+                    topic = %event.topic,
+                    "Failure gate event detected in JSONL - loop will terminate"
+                );
+                self.diagnostics.log_orchestration(
+                    self.state.iteration,
+                    "jsonl",
+                    crate::diagnostics::OrchestrationEvent::LoopTerminated {
+                        reason: format!("gate_failed: {}", event.topic),
+                    },
+                );
+                self.state.gate_failed_topic = Some(event.topic.clone());
+            }
+
+            if self.state.until_event_topic.is_none()
+                && self.config.event_loop.until_event.as_deref() == Some(event.topic.as_str())
+            {
+                if self.state.stale_until_events > 0 {
+                    self.state.stale_until_events -= 1;
+                } else {
+                    info!(
+                        topic = %event.topic,
+                        "Until-event topic detected in JSONL - loop will stop"
+                    );
+                    self.state.until_event_topic = Some(event.topic.clone());
+                }
+            }
+
+            if self.completion_matcher.matches(&event.topic) {
                 if index + 1 == total_events {
                     self.state.completion_requested = true;
+                    self.state.completion_topic = Some(event.topic.clone());
                     self.diagnostics.log_orchestration(
                         self.state.iteration,
                         "jsonl",
@@ -1681,6 +2397,9 @@ impl EventLoop {
                         topic = %event.topic,
                         "Completion event detected in JSONL"
                     );
+                    // Never routed to a hat, but observers (hooks, event streams) see it
+                    self.bus
+                        .notify_observers(&Event::new(event.topic.as_str(), payload.as_str()));
                 } else {
                     warn!(
                         topic = %event.topic,
@@ -1977,7 +2696,7 @@ impl EventLoop {
                     }
                 };
 
-                // Block: poll events file for human.response
+                // Block: wait for human.response in the events file
                 // Per spec, even on send failure we treat as timeout (continue without blocking)
                 if send_ok {
                     // Read the active events path from the current-events marker,
@@ -2032,10 +2751,12 @@ impl EventLoop {
             }
         }
 
-        // Publish validated events to the bus.
+        self.track_unmatched_events(&validated_events);
+
+        // Queue validated events; one is released to the bus per iteration.
         // Ralph is always registered with subscribe("*"), so every event has at least
         // one subscriber. Events without a specific hat subscriber are "orphaned" —
-        // Ralph handles them as the universal fallback.
+        // Ralph handles them as the universal fallback once nothing else is waiting.
         for event in validated_events {
             self.diagnostics.log_orchestration(
                 self.state.iteration,
@@ -2049,11 +2770,12 @@ impl EventLoop {
                 has_orphans = true;
             }
 
-            debug!(
-                topic = %event.topic,
-                "Publishing event from JSONL"
-            );
-            self.bus.publish(event);
+            if event.topic.as_str().starts_with("human.") {
+                // Human interactions have their own queue on the bus
+                self.bus.publish(event);
+            } else {
+                self.enqueue_event(event);
+            }
         }
 
         // Publish human.response event if one was received during blocking
@@ -2065,9 +2787,162 @@ impl EventLoop {
             self.bus.publish(response);
         }
 
+        self.release_queued_event();
         Ok(has_orphans)
     }
 
+    /// Stops the loop once it stalls on events that no hat triggers on.
+    ///
+    /// Ralph gets one iteration to handle unmatched events as the universal
+    /// fallback. If that iteration again publishes only unmatched events, and
+    /// nothing else is waiting for a hat, the loop can't make progress.
+    fn track_unmatched_events(&mut self, events: &[Event]) {
+        let hat_events: Vec<&Event> = events
+            .iter()
+            .filter(|event| !event.topic.as_str().starts_with("human."))
+            .collect();
+        let registry = &self.registry;
+        let matched = |event: &Event| registry.has_subscriber(event.topic.as_str());
+        if registry.is_empty()
+            || hat_events.is_empty()
+            || hat_events.iter().any(|event| matched(event))
+            || self.event_queue.any(matched)
+        {
+            self.state.unmatched_event_iterations = 0;
+            return;
+        }
+
+        self.state.unmatched_event_iterations += 1;
+        if self.state.unmatched_event_iterations < 2 || self.state.no_matching_hat_topic.is_some() {
+            return;
+        }
+
+        let topic = hat_events[0].topic.to_string();
+        warn!(
+            topic = %topic,
+            triggers = %self.configured_triggers().join(", "),
+            "No hat triggers on '{}' - loop will terminate",
+            topic
+        );
+        self.diagnostics.log_orchestration(
+            self.state.iteration,
+            "jsonl",
+            crate::diagnostics::OrchestrationEvent::LoopTerminated {
+                reason: format!("no_matching_hat: {topic}"),
+            },
+        );
+        self.state.no_matching_hat_topic = Some(topic);
+    }
+
+    /// Trigger patterns of every configured hat, sorted and deduplicated.
+    fn configured_triggers(&self) -> Vec<String> {
+        let mut triggers: Vec<String> = self
+            .registry
+            .all()
+            .flat_map(|hat| hat.subscriptions.iter().map(|t| t.as_str().to_string()))
+            .collect();
+        triggers.sort();
+        triggers.dedup();
+        triggers
+    }
+
+    /// Returns true for records the loop itself wrote to the events file.
+    ///
+    /// Its start event and `completion.rejected` are already on the bus, and
+    /// observer-only records must never reach a hat. Other loop records
+    /// (e.g. `event.orphaned`) are meant for Ralph and pass through.
+    fn is_own_record(&self, event: &crate::event_reader::Event) -> bool {
+        event.hat.as_deref() == Some("loop")
+            && (OBSERVER_ONLY_TOPICS.contains(&event.topic.as_str())
+                || event.topic == COMPLETION_REJECTED_TOPIC
+                || self.start_topic.as_deref() == Some(event.topic.as_str()))
+    }
+
+    /// Adds an event from the events file to the queue.
+    fn enqueue_event(&mut self, event: Event) {
+        debug!(
+            topic = %event.topic,
+            depth = self.event_queue.len(),
+            "Queueing event from JSONL"
+        );
+        match self.event_queue.push(event) {
+            None => {}
+            Some(Overflow::Dropped(dropped)) => {
+                warn!(topic = %dropped.topic, "Event queue full, dropping oldest event");
+                self.record_unhandled(&dropped, "queue full (on_overflow: drop_oldest)");
+            }
+            Some(Overflow::Rejected(rejected)) => {
+                warn!(topic = %rejected.topic, "Event queue full - loop will terminate");
+                self.record_unhandled(&rejected, "queue full (on_overflow: fail)");
+                if self.state.queue_overflow_topic.is_none() {
+                    self.state.queue_overflow_topic = Some(rejected.topic.to_string());
+                }
+            }
+        }
+    }
+
+    /// Publishes the next queued event for the coming iteration.
+    ///
+    /// Unmatched events passed over for longer than `unmatched_ttl`
+    /// iterations are dropped and recorded as `event.unhandled`.
+    fn release_queued_event(&mut self) {
+        let registry = &self.registry;
+        let release = self
+            .event_queue
+            .release(|event| registry.is_empty() || registry.has_subscriber(event.topic.as_str()));
+
+        for expired in &release.expired {
+            warn!(topic = %expired.topic, "No hat handled queued event, dropping it");
+            self.record_unhandled(
+                expired,
+                &format!(
+                    "no hat subscribed within {} iterations",
+                    self.config.event_loop.queue.unmatched_ttl
+                ),
+            );
+        }
+
+        if let Some(event) = release.event {
+            debug!(
+                topic = %event.topic,
+                remaining = self.event_queue.len(),
+                "Publishing queued event"
+            );
+            self.bus.publish(event);
+        }
+    }
+
+    /// Records an `event.unhandled` event for a dropped event.
+    ///
+    /// Observer-only, like `iteration.retried`: no hat can trigger on it.
+    fn record_unhandled(&mut self, dropped: &Event, reason: &str) {
+        let payload = format!(
+            "Dropped event '{topic}': {reason}.\n- topic: {topic}\n- reason: {reason}\n- iteration: {iteration}\n- payload: {payload}",
+            topic = dropped.topic,
+            iteration = self.state.iteration,
+            payload = truncate_with_ellipsis(&dropped.payload, 200),
+        );
+        let event = Event::new(EVENT_UNHANDLED_TOPIC, payload);
+        self.bus.notify_observers(&event);
+        self.unhandled_events.push(event);
+    }
+
+    /// Records an `event.duplicate` event for a suppressed event.
+    ///
+    /// Observer-only, like `event.unhandled`: the history shows the repeat
+    /// without it triggering hats again.
+    fn record_duplicate(&mut self, event: &crate::event_reader::Event, suppressed: &Suppressed) {
+        let payload = format!(
+            "Suppressed event '{topic}': {suppressed}.\n- topic: {topic}\n- reason: {suppressed}\n- iteration: {iteration}\n- payload: {payload}",
+            topic = event.topic,
+            iteration = self.state.iteration,
+            payload = truncate_with_ellipsis(event.payload.as_deref().unwrap_or_default(), 200),
+        );
+        let record = Event::new(EVENT_DUPLICATE_TOPIC, payload);
+        self.bus.notify_observers(&record);
+        self.unhandled_events.push(record);
+    }
+
     /// Checks if output contains a completion event from Ralph.
     ///
     /// Completion must be emitted as an `<event>` tag, not plain text.
@@ -2075,7 +2950,7 @@ impl EventLoop {
         let events = EventParser::new().parse(output);
         events
             .iter()
-            .any(|event| event.topic.as_str() == self.config.event_loop.completion_promise)
+            .any(|event| self.completion_matcher.matches(event.topic.as_str()))
     }
 
     /// Publishes the loop.terminate system event to observers.
@@ -2085,13 +2960,22 @@ impl EventLoop {
     ///
     /// Returns the event for logging purposes.
     pub fn publish_terminate_event(&mut self, reason: &TerminationReason) -> Event {
+        if self.config.robot.notifications.notifies_termination(reason) {
+            self.notify_robot(&LifecycleNotification::Terminated {
+                reason: reason.as_str().to_string(),
+                success: reason.is_success(),
+                iterations: self.state.iteration,
+                elapsed: self.state.elapsed(),
+            });
+        }
+
         // Stop the robot service if it was running
         self.stop_robot_service();
 
         let elapsed = self.state.elapsed();
         let duration_str = format_duration(elapsed);
 
-        let payload = format!(
+        let mut payload = format!(
             "## Reason\n{}\n\n## Status\n{}\n\n## Summary\n- Iterations: {}\n- Duration: {}\n- Exit code: {}",
             reason.as_str(),
             termination_status_text(reason),
@@ -2099,6 +2983,17 @@ impl EventLoop {
             duration_str,
             reason.exit_code()
         );
+        if *reason == TerminationReason::CompletionPromise
+            && let Some(topic) = &self.state.completion_topic
+        {
+            payload.push_str(&format!("\n- Completion promise: {topic}"));
+        }
+        if let TerminationReason::NoMatchingHat { topic } = reason {
+            payload.push_str(&format!(
+                "\n- Unmatched topic: {topic}\n- Configured triggers: {}",
+                self.configured_triggers().join(", ")
+            ));
+        }
 
         let event = Event::new("loop.terminate", &payload);
 
@@ -2118,6 +3013,165 @@ impl EventLoop {
         event
     }
 
+    /// Publishes the `event_loop.on_failure_emit` event, if configured and
+    /// the loop stopped for any reason other than completion.
+    ///
+    /// Returns the event for logging purposes.
+    pub fn publish_failure_event(&mut self, reason: &TerminationReason) -> Option<Event> {
+        if reason.is_success() || *reason == TerminationReason::UntilEventReached {
+            return None;
+        }
+        let topic = self.config.event_loop.on_failure_emit.as_deref()?;
+
+        let payload = serde_json::json!({
+            "reason": reason.as_str(),
+            "exit_code": reason.exit_code(),
+            "iterations": self.state.iteration,
+            "elapsed_secs": self.state.elapsed().as_secs(),
+        });
+        let event = Event::new(topic, payload.to_string());
+        self.bus.publish(event.clone());
+        Some(event)
+    }
+
+    /// Per-iteration metrics recorded so far.
+    pub fn iteration_metrics(&self) -> &[IterationMetrics] {
+        &self.iteration_metrics
+    }
+
+    /// Path of the JSON run summary: `event_loop.summary_out` (relative to
+    /// the workspace root) or `.ralph/agent/run-summary.json`.
+    pub fn run_summary_path(&self) -> PathBuf {
+        let workspace = self
+            .loop_context
+            .as_ref()
+            .map_or(self.config.core.workspace_root.as_path(), |ctx| {
+                ctx.workspace()
+            });
+        match &self.config.event_loop.summary_out {
+            Some(path) => workspace.join(path),
+            None => self.loop_context.as_ref().map_or_else(
+                || workspace.join(".ralph/agent/run-summary.json"),
+                LoopContext::run_summary_path,
+            ),
+        }
+    }
+
+    /// Writes `run-summary.json` for a run that stopped with `reason`.
+    ///
+    /// Events the last iteration wrote but the loop stopped before reading
+    /// still count toward that iteration.
+    pub fn write_run_summary(&self, reason: &TerminationReason) -> std::io::Result<PathBuf> {
+        let mut iterations = self.iteration_metrics.clone();
+        if let Some(last) = iterations
+            .last_mut()
+            .filter(|metrics| metrics.iteration == self.state.iteration)
+            && let Ok(unread) = self.event_reader.peek_new_events()
+        {
+            last.events_emitted += unread
+                .events
+                .iter()
+                .filter(|event| !self.is_own_record(event))
+                .count();
+        }
+
+        let path = self.run_summary_path();
+        RunMetrics::new(
+            reason,
+            iterations,
+            self.state.elapsed(),
+            self.state.cumulative_cost,
+        )
+        .write(&path)?;
+        Ok(path)
+    }
+
+    /// Path of the loop state snapshot: `.ralph/agent/loop-state.json`.
+    pub fn state_snapshot_path(&self) -> PathBuf {
+        self.loop_context.as_ref().map_or_else(
+            || {
+                self.config
+                    .core
+                    .workspace_root
+                    .join(".ralph/agent/loop-state.json")
+            },
+            LoopContext::loop_state_path,
+        )
+    }
+
+    /// Captures the loop state, how far the events file has been read, and
+    /// the events waiting for the next iteration.
+    pub fn state_snapshot(&self) -> LoopStateSnapshot {
+        let mut snapshot = LoopStateSnapshot::capture(
+            &self.state,
+            self.event_reader.path(),
+            self.event_reader.position(),
+        );
+        snapshot.pending_events = self.pending_bus_events();
+        snapshot.queued_events = self.event_queue.events().cloned().collect();
+        snapshot
+    }
+
+    /// Writes the snapshot `ralph run --continue` resumes from.
+    pub fn write_state_snapshot(&self) -> std::io::Result<PathBuf> {
+        let path = self.state_snapshot_path();
+        self.state_snapshot().write(&path)?;
+        Ok(path)
+    }
+
+    /// Events on the bus awaiting the next iteration, each listed once even
+    /// when several hats subscribe to it.
+    fn pending_bus_events(&self) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+        let hat_events = self
+            .bus
+            .hat_ids()
+            .filter_map(|id| self.bus.peek_pending(id))
+            .flatten();
+        for event in hat_events.chain(self.bus.peek_human_pending()) {
+            let seen = events.iter().any(|other| {
+                other.topic == event.topic
+                    && other.payload == event.payload
+                    && other.source == event.source
+                    && other.target == event.target
+            });
+            if !seen {
+                events.push(event.clone());
+            }
+        }
+        events
+    }
+
+    /// Announces the loop start through the robot service, if enabled.
+    ///
+    /// The prompt is summarized to its first non-empty line.
+    pub fn notify_loop_started(&self, prompt: &str) {
+        if !self.config.robot.notifications.start {
+            return;
+        }
+
+        let first_line = prompt
+            .lines()
+            .map(|l| l.trim().trim_start_matches('#').trim())
+            .find(|l| !l.is_empty())
+            .unwrap_or("");
+        self.notify_robot(&LifecycleNotification::Started {
+            backend: self.config.cli.backend.clone(),
+            prompt_summary: truncate_with_ellipsis(first_line, 120),
+        });
+    }
+
+    /// Sends a lifecycle notification through the robot service, if any.
+    ///
+    /// Send failures are logged and never interrupt the loop.
+    pub fn notify_robot(&self, notification: &LifecycleNotification) {
+        if let Some(ref robot_service) = self.robot_service
+            && let Err(e) = robot_service.send_notification(notification)
+        {
+            warn!(error = %e, "Failed to send lifecycle notification");
+        }
+    }
+
     /// Returns the robot service's shutdown flag, if active.
     ///
     /// Signal handlers can set this flag to interrupt `wait_for_response()`
@@ -2192,6 +3246,16 @@ pub struct UserPrompt {
 }
 
 /// Formats a duration as human-readable string.
+/// Compiles `event_schemas`, skipping validation if a schema is invalid.
+///
+/// Config validation rejects invalid schemas before a loop starts.
+fn compile_event_schemas(config: &RalphConfig) -> EventSchemas {
+    EventSchemas::compile(&config.event_schemas).unwrap_or_else(|e| {
+        warn!("Event payload schemas disabled: {}", e);
+        EventSchemas::default()
+    })
+}
+
 fn format_duration(d: Duration) -> String {
     let total_secs = d.as_secs();
     let hours = total_secs / 3600;
@@ -2222,5 +3286,13 @@ fn termination_status_text(reason: &TerminationReason) -> &'static str {
         TerminationReason::Stopped => "Manually stopped.",
         TerminationReason::Interrupted => "Interrupted by signal.",
         TerminationReason::RestartRequested => "Restarting by human request.",
+        TerminationReason::GateFailed => "Failure gate event published.",
+        TerminationReason::UntilEventReached => {
+            "Stopped at the --until-event topic. Resume with `ralph run --continue`."
+        }
+        TerminationReason::HatBudgetExceeded => "A hat exceeded its iteration budget.",
+        TerminationReason::EventQueueOverflow => "Event queue overflowed.",
+        TerminationReason::CompletionRejected => "Completion check kept failing.",
+        TerminationReason::NoMatchingHat { .. } => "No hat triggers on the published event.",
     }
 }
@@ -35,7 +35,9 @@ hats:
 
 #[test]
 fn test_guidance_persists_across_iterations_solo_mode() {
-    let config = RalphConfig::default();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
     let mut event_loop = EventLoop::new(config);
     let ralph_id = HatId::new("ralph");
 
@@ -73,7 +75,9 @@ hats:
     triggers: ["task.start"]
     publishes: ["task.plan"]
 "#;
-    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
     let mut event_loop = EventLoop::new(config);
     let ralph_id = HatId::new("ralph");
 
@@ -281,6 +285,98 @@ hats:
     );
 }
 
+#[test]
+fn test_hat_budget_exceeded_routes_to_supervisor() {
+    let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.blocked"]
+    max_iterations: 2
+  supervisor:
+    name: "Supervisor"
+    triggers: ["hat.budget_exceeded"]
+    publishes: ["build.task"]
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let ralph = HatId::new("ralph");
+    let builder = HatId::new("builder");
+    let supervisor = HatId::new("supervisor");
+
+    for _ in 0..2 {
+        event_loop.bus.publish(Event::new("build.task", "retry"));
+        event_loop.enforce_hat_budgets();
+        assert_eq!(event_loop.check_termination(), None);
+        let _ = event_loop.build_prompt(&ralph).unwrap();
+    }
+
+    // A third activation would exceed the budget
+    event_loop.bus.publish(Event::new("build.task", "retry"));
+    event_loop.enforce_hat_budgets();
+
+    assert!(
+        event_loop
+            .bus
+            .peek_pending(&builder)
+            .is_none_or(Vec::is_empty)
+    );
+    let pending = event_loop.bus.peek_pending(&supervisor).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].topic.as_str(), HAT_BUDGET_EXCEEDED_TOPIC);
+    assert!(pending[0].payload.contains("- hat: builder"));
+    assert!(pending[0].payload.contains("- max_iterations: 2"));
+    assert!(pending[0].payload.contains("- iterations: 2"));
+    assert_eq!(
+        event_loop.check_termination(),
+        None,
+        "A subscribed supervisor should keep the loop running"
+    );
+
+    // Later events for the builder are dropped without another notification
+    event_loop.bus.publish(Event::new("build.task", "again"));
+    event_loop.enforce_hat_budgets();
+    assert!(
+        event_loop
+            .bus
+            .peek_pending(&builder)
+            .is_none_or(Vec::is_empty)
+    );
+    assert_eq!(event_loop.bus.peek_pending(&supervisor).unwrap().len(), 1);
+}
+
+#[test]
+fn test_hat_budget_exceeded_without_subscriber_terminates() {
+    let yaml = r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+    max_iterations: 1
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let ralph = HatId::new("ralph");
+
+    event_loop.bus.publish(Event::new("build.task", "first"));
+    event_loop.enforce_hat_budgets();
+    assert_eq!(event_loop.check_termination(), None);
+    let _ = event_loop.build_prompt(&ralph).unwrap();
+
+    event_loop.bus.publish(Event::new("build.task", "second"));
+    event_loop.enforce_hat_budgets();
+
+    let reason = event_loop.check_termination();
+    assert_eq!(reason, Some(TerminationReason::HatBudgetExceeded));
+    assert_eq!(reason.unwrap().exit_code(), 2);
+    assert_eq!(
+        event_loop.state().budget_exceeded_hat,
+        Some(HatId::new("builder"))
+    );
+}
+
 #[test]
 fn test_termination_max_iterations() {
     let yaml = r"
@@ -438,6 +534,427 @@ fn test_completion_promise_requires_last_event() {
     );
 }
 
+#[test]
+fn test_completion_event_reaches_observers() {
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    let mut event_loop = EventLoop::new(config);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_clone = Arc::clone(&seen);
+    event_loop.add_observer(move |event| {
+        seen_clone
+            .lock()
+            .unwrap()
+            .push((event.topic.to_string(), event.payload.clone()));
+    });
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(&events_path, "LOOP_COMPLETE", "Done");
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert!(
+        seen.lock()
+            .unwrap()
+            .contains(&("LOOP_COMPLETE".to_string(), "Done".to_string()))
+    );
+}
+
+#[test]
+fn test_loop_records_are_not_routed_back_to_hats() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test objective");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+    let ralph = HatId::new("ralph");
+    let _ = event_loop.build_prompt(&ralph).unwrap();
+
+    let loop_record = |topic: &str| {
+        format!(
+            r#"{{"ts":"2026-01-01T00:00:00Z","iteration":0,"hat":"loop","topic":"{topic}","payload":"from the loop"}}"#
+        )
+    };
+    let lines = [
+        loop_record("task.start"),
+        loop_record(crate::LOOP_PAUSED_TOPIC),
+        loop_record(crate::EVENT_UNHANDLED_TOPIC),
+        loop_record(crate::HOOK_FAILED_TOPIC),
+        loop_record("event.orphaned"),
+    ];
+    std::fs::write(&events_path, lines.join("\n") + "\n").unwrap();
+
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.queue_depth(), 0);
+    let pending = event_loop.bus.peek_pending(&ralph).unwrap();
+    let topics: Vec<_> = pending.iter().map(|e| e.topic.as_str()).collect();
+    assert_eq!(topics, vec!["event.orphaned"]);
+}
+
+#[test]
+fn test_fail_on_event_terminates_with_gate_failed() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.fail_on_event = vec!["review.critical".to_string()];
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(&events_path, "review.section", "Looks fine");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.check_termination(), None);
+
+    write_event_to_jsonl(&events_path, "review.critical", "SQL injection in login");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(
+        event_loop.check_termination(),
+        Some(TerminationReason::GateFailed)
+    );
+    assert_eq!(
+        event_loop.state().gate_failed_topic.as_deref(),
+        Some("review.critical")
+    );
+}
+
+#[test]
+fn test_until_event_stops_at_non_terminal_event() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.until_event = Some("design.approved".to_string());
+    config.event_loop.on_failure_emit = Some("loop.failed".to_string());
+    let mut event_loop = EventLoop::new(config.clone());
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(&events_path, "design.draft", "First pass");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.check_termination(), None);
+
+    write_event_to_jsonl(&events_path, "design.approved", "Ship the design");
+    let _ = event_loop.process_events_from_jsonl();
+    let reason = event_loop.check_termination().unwrap();
+    assert_eq!(reason, TerminationReason::UntilEventReached);
+    assert_eq!(reason.exit_code(), 0);
+    assert!(event_loop.publish_failure_event(&reason).is_none());
+
+    // Resuming re-reads the old occurrence without stopping again
+    let mut resumed = EventLoop::new(config);
+    resumed.event_reader = crate::event_reader::EventReader::new(&events_path);
+    resumed.initialize_resume("Test");
+    let _ = resumed.process_events_from_jsonl();
+    assert_eq!(resumed.check_termination(), None);
+
+    write_event_to_jsonl(&events_path, "design.approved", "Second design");
+    let _ = resumed.process_events_from_jsonl();
+    assert_eq!(
+        resumed.check_termination(),
+        Some(TerminationReason::UntilEventReached)
+    );
+}
+
+fn snapshot_test_config(workspace: &std::path::Path) -> RalphConfig {
+    let mut config = RalphConfig::parse_yaml(
+        r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["review.request"]
+  reviewer:
+    name: "Reviewer"
+    triggers: ["review.request"]
+    publishes: ["build.task"]
+"#,
+    )
+    .unwrap();
+    config.core.workspace_root = workspace.to_path_buf();
+    config
+}
+
+#[test]
+fn test_state_snapshot_round_trip_restores_iteration_and_queue() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+    let config = snapshot_test_config(temp_dir.path());
+
+    let mut event_loop = EventLoop::new(config.clone());
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+    event_loop.bus().take_pending(&HatId::new("ralph"));
+    let ralph = HatId::new("ralph");
+    event_loop.process_output(&ralph, "", true);
+    event_loop.process_output(&ralph, "", false);
+    event_loop.add_cost(0.25);
+
+    // One event is released to the bus, the other waits in the queue
+    write_event_to_jsonl(&events_path, "build.task", "Implement parser");
+    write_event_to_jsonl(&events_path, "review.request", "Review parser");
+    event_loop.process_events_from_jsonl().unwrap();
+    assert_eq!(event_loop.queue_depth(), 1);
+
+    let path = event_loop.write_state_snapshot().unwrap();
+    assert_eq!(path, temp_dir.path().join(".ralph/agent/loop-state.json"));
+    let snapshot = LoopStateSnapshot::load(&path).unwrap();
+    assert_eq!(snapshot.iteration, 2);
+    assert_eq!(snapshot.consecutive_failures, 1);
+    assert_eq!(snapshot.last_event.as_deref(), Some("review.request"));
+    assert_eq!(snapshot.events_position, event_loop.event_reader.position());
+    let pending: Vec<_> = snapshot
+        .pending_events
+        .iter()
+        .map(|e| e.topic.as_str())
+        .collect();
+    assert_eq!(pending, vec!["build.task"]);
+    let queued: Vec<_> = snapshot
+        .queued_events
+        .iter()
+        .map(|e| e.topic.as_str())
+        .collect();
+    assert_eq!(queued, vec!["review.request"]);
+
+    let mut resumed = EventLoop::new(config);
+    resumed.event_reader = crate::event_reader::EventReader::new(&events_path);
+    resumed.initialize_resume("Test");
+
+    assert_eq!(resumed.state().iteration, 2);
+    assert_eq!(resumed.state().consecutive_failures, 1);
+    assert!((resumed.state().cumulative_cost - 0.25).abs() < f64::EPSILON);
+    assert_eq!(resumed.state().last_hat, Some(ralph.clone()));
+    assert_eq!(resumed.queue_depth(), 1);
+    assert_eq!(
+        resumed.event_reader.position(),
+        event_loop.event_reader.position()
+    );
+    let builder_pending = resumed.bus().peek_pending(&HatId::new("builder")).cloned();
+    assert_eq!(builder_pending.unwrap()[0].payload, "Implement parser");
+    assert!(
+        resumed
+            .bus()
+            .peek_pending(&ralph)
+            .is_none_or(|events| events.iter().all(|e| e.topic.as_str() != "task.resume")),
+        "pending events replace task.resume"
+    );
+
+    // Reading continues after the saved position, and the queue drains in order
+    resumed.bus().take_pending(&HatId::new("builder"));
+    resumed.process_events_from_jsonl().unwrap();
+    assert_eq!(resumed.queue_depth(), 0);
+    let reviewer_pending = resumed.bus().peek_pending(&HatId::new("reviewer")).cloned();
+    assert_eq!(
+        reviewer_pending.unwrap()[0].topic.as_str(),
+        "review.request"
+    );
+}
+
+#[test]
+fn test_unusable_state_snapshot_falls_back_to_task_resume() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+    write_event_to_jsonl(&events_path, "build.task", "Implement parser");
+    let config = snapshot_test_config(temp_dir.path());
+    let snapshot_path = temp_dir.path().join(".ralph/agent/loop-state.json");
+    std::fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+
+    let snapshot_for = |events_file: &std::path::Path, position: u64| {
+        let state = LoopState {
+            iteration: 7,
+            ..LoopState::default()
+        };
+        serde_json::to_string(&LoopStateSnapshot::capture(&state, events_file, position)).unwrap()
+    };
+    let cases = [
+        "{\"iteration\": ".to_string(),
+        snapshot_for(&temp_dir.path().join("events-other.jsonl"), 0),
+        snapshot_for(&events_path, 1 << 20),
+    ];
+
+    for contents in cases {
+        std::fs::write(&snapshot_path, &contents).unwrap();
+
+        let mut resumed = EventLoop::new(config.clone());
+        resumed.event_reader = crate::event_reader::EventReader::new(&events_path);
+        resumed.initialize_resume("Test");
+
+        assert_eq!(resumed.state().iteration, 0, "snapshot used: {contents}");
+        let ralph_pending = resumed.bus().peek_pending(&HatId::new("ralph")).cloned();
+        assert_eq!(ralph_pending.unwrap()[0].topic.as_str(), "task.resume");
+        assert_eq!(resumed.event_reader.position(), 0);
+    }
+}
+
+#[test]
+fn test_unmatched_events_terminate_with_no_matching_hat() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::parse_yaml(
+        r#"
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["review.requst"]
+  reviewer:
+    name: "Reviewer"
+    triggers: ["review.request"]
+    publishes: ["build.task"]
+"#,
+    )
+    .unwrap();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    // Ralph gets a turn to handle an unmatched event, and can route it on
+    write_event_to_jsonl(&events_path, "build.blocked", "Missing dependency");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.check_termination(), None);
+    write_event_to_jsonl(&events_path, "build.task", "Retry with the dependency");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.check_termination(), None);
+
+    // A typo'd topic that Ralph can't route either stalls the loop
+    write_event_to_jsonl(&events_path, "review.requst", "Please review");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(event_loop.check_termination(), None);
+    write_event_to_jsonl(&events_path, "review.requst", "Please review");
+    let _ = event_loop.process_events_from_jsonl();
+
+    let reason = event_loop.check_termination().unwrap();
+    assert_eq!(
+        reason,
+        TerminationReason::NoMatchingHat {
+            topic: "review.requst".to_string()
+        }
+    );
+    let event = event_loop.publish_terminate_event(&reason);
+    assert!(
+        event.payload.contains("no_matching_hat"),
+        "{}",
+        event.payload
+    );
+    assert!(
+        event.payload.contains("- Unmatched topic: review.requst"),
+        "{}",
+        event.payload
+    );
+    assert!(
+        event
+            .payload
+            .contains("- Configured triggers: build.task, review.request"),
+        "{}",
+        event.payload
+    );
+}
+
+#[test]
+fn test_fail_on_event_overrides_completion() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.fail_on_event = vec!["review.critical".to_string()];
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    // Critical finding followed by completion in the same batch - gate wins
+    write_event_to_jsonl(&events_path, "review.critical", "Data loss on retry");
+    write_event_to_jsonl(&events_path, "LOOP_COMPLETE", "Done");
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert_eq!(event_loop.check_completion_event(), None);
+    assert_eq!(
+        event_loop.check_termination(),
+        Some(TerminationReason::GateFailed)
+    );
+}
+
+#[test]
+fn test_completion_promise_list_and_regex_record_matched_topic() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.completion_promise =
+        crate::config::CompletionPromises::parse_list("LOOP_COMPLETE,REVIEW_DONE");
+    config.event_loop.completion_promise_regex = Some("(?i)^loop_complete\\W*$".to_string());
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(&events_path, "Loop_Complete.", "Done");
+    let _ = event_loop.process_events_from_jsonl();
+    let reason = event_loop.check_completion_event();
+    assert_eq!(reason, Some(TerminationReason::CompletionPromise));
+
+    let terminate = event_loop.publish_terminate_event(&TerminationReason::CompletionPromise);
+    assert!(
+        terminate
+            .payload
+            .contains("Completion promise: Loop_Complete."),
+        "terminate payload should record the matched promise: {}",
+        terminate.payload
+    );
+}
+
+#[test]
+fn test_completion_promise_secondary_literal() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.completion_promise =
+        crate::config::CompletionPromises::parse_list("LOOP_COMPLETE,REVIEW_DONE");
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+
+    write_event_to_jsonl(&events_path, "REVIEW_DONE", "Done");
+    let _ = event_loop.process_events_from_jsonl();
+    assert_eq!(
+        event_loop.check_completion_event(),
+        Some(TerminationReason::CompletionPromise)
+    );
+}
+
 #[test]
 fn test_builder_cannot_terminate_loop() {
     // Per spec: completion requires an emitted event; output-only tokens are ignored
@@ -570,10 +1087,18 @@ fn test_exit_codes_per_spec() {
     assert_eq!(TerminationReason::ConsecutiveFailures.exit_code(), 1);
     assert_eq!(TerminationReason::LoopThrashing.exit_code(), 1);
     assert_eq!(TerminationReason::Stopped.exit_code(), 1);
+    assert_eq!(TerminationReason::GateFailed.exit_code(), 1);
     assert_eq!(TerminationReason::MaxIterations.exit_code(), 2);
     assert_eq!(TerminationReason::MaxRuntime.exit_code(), 2);
     assert_eq!(TerminationReason::MaxCost.exit_code(), 2);
     assert_eq!(TerminationReason::Interrupted.exit_code(), 130);
+    assert_eq!(
+        TerminationReason::NoMatchingHat {
+            topic: "review.requst".to_string()
+        }
+        .exit_code(),
+        4
+    );
 }
 
 /// Helper to write an event to a JSONL file for testing.
@@ -834,6 +1359,33 @@ hats:
     );
 }
 
+#[test]
+fn test_active_hat_instructions_render_template_variables() {
+    let yaml = r#"
+hats:
+  deployer:
+    name: "Deployment Manager"
+    triggers: ["deploy.request"]
+    publishes: ["deploy.done"]
+    instructions: "Iteration {{iteration}}: notes go in {{scratchpad}}; keep {{ticket}}."
+"#;
+    let mut config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    config.core.workspace_root = std::path::PathBuf::from("/repo");
+    let mut event_loop = EventLoop::new(config);
+
+    event_loop
+        .bus
+        .publish(Event::new("deploy.request", "Deploy to staging"));
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+
+    assert!(
+        prompt.contains(
+            "Iteration 1: notes go in /repo/.ralph/agent/scratchpad.md; keep {{ticket}}."
+        ),
+        "Should render known variables and keep unknown ones: {prompt}"
+    );
+}
+
 #[test]
 fn test_default_hat_with_custom_instructions_uses_build_custom_hat() {
     // Test that even default hats (planner/builder) use build_custom_hat when they have custom instructions
@@ -1040,10 +1592,15 @@ fn test_default_publishes_injects_when_no_events() {
             triggers: vec!["task.start".to_string()],
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
+            instructions_file: None,
             extra_instructions: vec![],
             backend: None,
+            env: std::collections::BTreeMap::new(),
+            allowed_tools: vec![],
+            disallowed_tools: vec![],
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            max_iterations: None,
         },
     );
     config.hats = hats;
@@ -1086,10 +1643,15 @@ fn test_default_publishes_not_injected_when_events_written() {
             triggers: vec!["task.start".to_string()],
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
+            instructions_file: None,
             extra_instructions: vec![],
             backend: None,
+            env: std::collections::BTreeMap::new(),
+            allowed_tools: vec![],
+            disallowed_tools: vec![],
             default_publishes: Some("task.done".to_string()),
             max_activations: None,
+            max_iterations: None,
         },
     );
     config.hats = hats;
@@ -1140,10 +1702,15 @@ fn test_default_publishes_not_injected_when_not_configured() {
             triggers: vec!["task.start".to_string()],
             publishes: vec!["task.done".to_string()],
             instructions: "Test hat".to_string(),
+            instructions_file: None,
             extra_instructions: vec![],
             backend: None,
+            env: std::collections::BTreeMap::new(),
+            allowed_tools: vec![],
+            disallowed_tools: vec![],
             default_publishes: None, // No default configured
             max_activations: None,
+            max_iterations: None,
         },
     );
     config.hats = hats;
@@ -1473,6 +2040,37 @@ hats:
     );
 }
 
+#[test]
+fn test_pending_topics_lists_what_build_prompt_consumes() {
+    let yaml = r#"
+hats:
+  planner:
+    name: "Planner"
+    triggers: ["task.start"]
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+"#;
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.bus.publish(Event::new("task.start", "Start"));
+    event_loop.bus.publish(Event::new("build.task", "First"));
+    event_loop.bus.publish(Event::new("build.task", "Second"));
+
+    assert_eq!(
+        event_loop.pending_topics(&HatId::new("builder")),
+        vec!["build.task"]
+    );
+    let ralph = HatId::new("ralph");
+    assert_eq!(
+        event_loop.pending_topics(&ralph),
+        vec!["build.task", "task.start"]
+    );
+
+    event_loop.build_prompt(&ralph).unwrap();
+    assert!(event_loop.pending_topics(&ralph).is_empty());
+}
+
 // === Phase 2: Active Hat Detection Tests ===
 
 #[test]
@@ -2020,6 +2618,59 @@ fn test_scratchpad_injection_with_content() {
     );
 }
 
+#[test]
+fn test_iteration_context_injected_at_top_of_prompt() {
+    use crate::task::{Task, TaskStatus};
+    use crate::task_store::TaskStore;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut store = TaskStore::load(&temp_dir.path().join(".ralph/agent/tasks.jsonl")).unwrap();
+    let mut closed = Task::new("Ship the parser".to_string(), 1);
+    closed.status = TaskStatus::Closed;
+    store.add(closed);
+    store.add(Task::new("Wire up the CLI".to_string(), 2));
+    store.save().unwrap();
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+    config.event_loop.context.enabled = true;
+    config.event_loop.context.recent_events = 1;
+
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test prompt");
+    let events_path = temp_dir.path().join("events.jsonl");
+    event_loop.event_reader = crate::event_reader::EventReader::new(&events_path);
+    write_event_to_jsonl(&events_path, "build.task", "Parse the config");
+    write_event_to_jsonl(&events_path, "build.done", "Parser tests pass");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+
+    assert!(prompt.starts_with("<iteration-context>\n"), "{prompt}");
+    let block = &prompt[..prompt.find("</iteration-context>").unwrap()];
+    assert!(block.contains("1 of 100 (99 remaining)"), "{block}");
+    assert!(block.contains("build.done: Parser tests pass"), "{block}");
+    assert!(!block.contains("build.task"), "{block}");
+    assert!(block.contains("[ ] [P2] Wire up the CLI"), "{block}");
+    assert!(!block.contains("Ship the parser"), "{block}");
+}
+
+#[test]
+fn test_iteration_context_off_by_default() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test prompt");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+
+    assert!(!prompt.contains("<iteration-context>"));
+}
+
 #[test]
 fn test_scratchpad_injection_no_file() {
     use tempfile::TempDir;
@@ -2094,6 +2745,48 @@ fn test_scratchpad_injection_ordering() {
     );
 }
 
+#[test]
+fn test_ralphignore_skips_scratchpad_and_memories() {
+    use crate::memory::{Memory, MemoryType};
+    use crate::memory_store::MarkdownMemoryStore;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let scratchpad_path = temp_dir.path().join(".ralph/agent/scratchpad.md");
+    std::fs::create_dir_all(scratchpad_path.parent().unwrap()).unwrap();
+    std::fs::write(&scratchpad_path, "scratchpad marker content").unwrap();
+    std::fs::write(
+        temp_dir.path().join(".ralphignore"),
+        ".ralph/agent/scratchpad.md\nfixtures/secrets/\n",
+    )
+    .unwrap();
+    let store = MarkdownMemoryStore::with_default_path(temp_dir.path());
+    for content in [
+        "Use cargo nextest for the suite",
+        "The staging key is in fixtures/secrets/staging.env",
+    ] {
+        store
+            .append(&Memory::new(
+                MemoryType::Pattern,
+                content.to_string(),
+                vec![],
+            ))
+            .unwrap();
+    }
+
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.path().to_path_buf();
+
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test prompt");
+
+    let prompt = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+
+    assert!(!prompt.contains("scratchpad marker content"), "{prompt}");
+    assert!(prompt.contains("cargo nextest"), "{prompt}");
+    assert!(!prompt.contains("staging.env"), "{prompt}");
+}
+
 #[test]
 fn test_scratchpad_injection_tail_truncation() {
     use tempfile::TempDir;
@@ -2729,6 +3422,12 @@ fn test_termination_reason_mappings() {
             3,
             false,
         ),
+        (
+            TerminationReason::UntilEventReached,
+            "until_event_reached",
+            0,
+            false,
+        ),
     ];
 
     for (reason, expected_str, expected_code, is_success) in cases {
@@ -3199,3 +3898,300 @@ hats:
     assert!(drop_again);
     assert!(event_again.is_none());
 }
+
+/// Robot service that records lifecycle notifications.
+struct RecordingRobot {
+    notifications: Arc<std::sync::Mutex<Vec<LifecycleNotification>>>,
+    fail: bool,
+}
+
+impl RobotService for RecordingRobot {
+    fn send_question(&self, _payload: &str) -> anyhow::Result<i32> {
+        Ok(0)
+    }
+
+    fn wait_for_response(&self, _events_path: &std::path::Path) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn send_checkin(
+        &self,
+        _iteration: u32,
+        _elapsed: Duration,
+        _context: Option<&CheckinContext>,
+    ) -> anyhow::Result<i32> {
+        Ok(0)
+    }
+
+    fn send_notification(&self, notification: &LifecycleNotification) -> anyhow::Result<i32> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .push(notification.clone());
+        if self.fail {
+            anyhow::bail!("network down");
+        }
+        Ok(1)
+    }
+
+    fn timeout_secs(&self) -> u64 {
+        0
+    }
+
+    fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        Arc::new(AtomicBool::new(false))
+    }
+
+    fn stop(self: Box<Self>) {}
+}
+
+fn loop_with_recording_robot(
+    yaml: &str,
+    fail: bool,
+) -> (EventLoop, Arc<std::sync::Mutex<Vec<LifecycleNotification>>>) {
+    let config: RalphConfig = serde_yaml::from_str(yaml).unwrap();
+    let mut event_loop = EventLoop::new(config);
+    let notifications = Arc::new(std::sync::Mutex::new(Vec::new()));
+    event_loop.set_robot_service(Box::new(RecordingRobot {
+        notifications: notifications.clone(),
+        fail,
+    }));
+    (event_loop, notifications)
+}
+
+#[test]
+fn test_lifecycle_notifications_for_start_and_termination() {
+    let (mut event_loop, notifications) = loop_with_recording_robot("{}", false);
+
+    event_loop.notify_loop_started("\n# Fix the flaky build\nMore detail");
+    event_loop.publish_terminate_event(&TerminationReason::MaxIterations);
+
+    let sent = notifications.lock().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert!(matches!(
+        &sent[0],
+        LifecycleNotification::Started { prompt_summary, .. } if prompt_summary == "Fix the flaky build"
+    ));
+    assert!(matches!(
+        &sent[1],
+        LifecycleNotification::Terminated { reason, success: false, .. } if reason == "max_iterations"
+    ));
+}
+
+#[test]
+fn test_lifecycle_notifications_respect_config() {
+    let yaml = "
+RObot:
+  notifications:
+    start: false
+";
+    let (mut event_loop, notifications) = loop_with_recording_robot(yaml, false);
+
+    event_loop.notify_loop_started("Prompt");
+    // Interrupts are off by default
+    event_loop.publish_terminate_event(&TerminationReason::Interrupted);
+
+    assert!(notifications.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_lifecycle_notification_failure_does_not_abort() {
+    let (mut event_loop, notifications) = loop_with_recording_robot("{}", true);
+
+    event_loop.notify_loop_started("Prompt");
+    let event = event_loop.publish_terminate_event(&TerminationReason::CompletionPromise);
+
+    assert_eq!(event.topic.as_str(), "loop.terminate");
+    assert_eq!(notifications.lock().unwrap().len(), 2);
+}
+
+fn schema_checked_loop(
+    temp_dir: &std::path::Path,
+    action: crate::config::SchemaMismatchAction,
+) -> EventLoop {
+    let mut config = RalphConfig::default();
+    config.core.workspace_root = temp_dir.to_path_buf();
+    config.event_loop.schema_mismatch = action;
+    config.event_schemas.insert(
+        "deploy.ready".to_string(),
+        serde_json::json!({
+            "type": "object",
+            "required": ["env"],
+            "properties": { "env": { "type": "string" } }
+        }),
+    );
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test objective");
+    event_loop.event_reader = crate::event_reader::EventReader::new(temp_dir.join("events.jsonl"));
+    let _ = event_loop.build_prompt(&HatId::new("ralph")).unwrap();
+    event_loop
+}
+
+fn pending_ralph_events(event_loop: &EventLoop) -> Vec<(String, String)> {
+    event_loop
+        .bus
+        .peek_pending(&HatId::new("ralph"))
+        .unwrap()
+        .iter()
+        .map(|e| (e.topic.as_str().to_string(), e.payload.clone()))
+        .collect()
+}
+
+#[test]
+fn test_event_schema_accepts_conforming_payload() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut event_loop =
+        schema_checked_loop(temp_dir.path(), crate::config::SchemaMismatchAction::Reject);
+
+    write_event_to_jsonl(
+        &temp_dir.path().join("events.jsonl"),
+        "deploy.ready",
+        r#"{"env":"staging"}"#,
+    );
+    let _ = event_loop.process_events_from_jsonl();
+
+    let pending = pending_ralph_events(&event_loop);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].0, "deploy.ready");
+}
+
+#[test]
+fn test_event_schema_rejects_non_conforming_payload() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut event_loop =
+        schema_checked_loop(temp_dir.path(), crate::config::SchemaMismatchAction::Reject);
+
+    write_event_to_jsonl(
+        &temp_dir.path().join("events.jsonl"),
+        "deploy.ready",
+        r#"{"env":{"name":"staging"}}"#,
+    );
+    let _ = event_loop.process_events_from_jsonl();
+
+    let pending = pending_ralph_events(&event_loop);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].0, "event.malformed");
+    assert!(
+        pending[0]
+            .1
+            .contains("Rejected event 'deploy.ready': payload for 'deploy.ready'"),
+        "{}",
+        pending[0].1
+    );
+    assert!(pending[0].1.contains("/env"), "{}", pending[0].1);
+}
+
+#[test]
+fn test_event_schema_warn_delivers_non_conforming_payload() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut event_loop =
+        schema_checked_loop(temp_dir.path(), crate::config::SchemaMismatchAction::Warn);
+
+    write_event_to_jsonl(
+        &temp_dir.path().join("events.jsonl"),
+        "deploy.ready",
+        "staging",
+    );
+    let _ = event_loop.process_events_from_jsonl();
+
+    let pending = pending_ralph_events(&event_loop);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].0, "deploy.ready");
+}
+
+fn dedup_event_loop(temp_dir: &std::path::Path, dedup_yaml: &str) -> EventLoop {
+    let mut config = RalphConfig::parse_yaml(&format!(
+        r#"
+event_loop:
+  dedup:
+{dedup_yaml}
+hats:
+  fixer:
+    name: "Fixer"
+    triggers: ["build.blocked"]
+    publishes: ["build.task"]
+"#
+    ))
+    .unwrap();
+    config.core.workspace_root = temp_dir.to_path_buf();
+    let mut event_loop = EventLoop::new(config);
+    event_loop.initialize("Test");
+    event_loop.event_reader = crate::event_reader::EventReader::new(temp_dir.join("events.jsonl"));
+    event_loop
+}
+
+/// Events waiting for the fixer: released to the bus plus still queued.
+fn fixer_triggers(event_loop: &EventLoop) -> usize {
+    let fixer = HatId::new("fixer");
+    event_loop.bus.peek_pending(&fixer).map_or(0, Vec::len) + event_loop.queue_depth()
+}
+
+#[test]
+fn test_dedup_suppresses_repeated_events_within_iteration() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+    let mut event_loop = dedup_event_loop(temp_dir.path(), "    enabled: true");
+
+    for _ in 0..3 {
+        write_event_to_jsonl(&events_path, "build.blocked", "Missing dependency");
+    }
+    write_event_to_jsonl(&events_path, "human.guidance", "Try vendoring it");
+    write_event_to_jsonl(&events_path, "human.guidance", "Try vendoring it");
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert_eq!(fixer_triggers(&event_loop), 1);
+    assert!(event_loop.bus.has_human_pending());
+    let records = event_loop.take_unhandled_events();
+    assert_eq!(records.len(), 2);
+    assert!(
+        records
+            .iter()
+            .all(|r| r.topic.as_str() == crate::EVENT_DUPLICATE_TOPIC)
+    );
+    assert!(records[0].payload.contains("Missing dependency"));
+
+    // The default window is one iteration: the next one may repeat it
+    let fixer = HatId::new("fixer");
+    let _ = event_loop.build_prompt(&fixer);
+    let _ = event_loop.process_output(&fixer, "", true);
+    write_event_to_jsonl(&events_path, "build.blocked", "Missing dependency");
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert!(event_loop.take_unhandled_events().is_empty());
+    assert_eq!(fixer_triggers(&event_loop), 1);
+}
+
+#[test]
+fn test_dedup_rate_limits_topic_per_iteration() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+    let mut event_loop = dedup_event_loop(
+        temp_dir.path(),
+        "    enabled: true\n    max_per_iteration:\n      build.blocked: 2",
+    );
+
+    for reason in ["one", "two", "three", "four"] {
+        write_event_to_jsonl(&events_path, "build.blocked", reason);
+    }
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert_eq!(fixer_triggers(&event_loop), 2);
+    let records = event_loop.take_unhandled_events();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].payload.contains("topic limit of 2"));
+}
+
+#[test]
+fn test_repeated_events_trigger_hats_without_dedup() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let events_path = temp_dir.path().join("events.jsonl");
+    let mut event_loop = dedup_event_loop(temp_dir.path(), "    enabled: false");
+
+    for _ in 0..3 {
+        write_event_to_jsonl(&events_path, "build.blocked", "Missing dependency");
+    }
+    let _ = event_loop.process_events_from_jsonl();
+
+    assert_eq!(fixer_triggers(&event_loop), 3);
+    assert!(event_loop.take_unhandled_events().is_empty());
+}
@@ -3,7 +3,7 @@
 use serde::{Deserialize, Deserializer, Serialize};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::warn;
 
 /// Result of parsing events from a JSONL file.
@@ -92,6 +92,9 @@ pub struct Event {
     )]
     pub payload: Option<String>,
     pub ts: String,
+    /// Hat that wrote the record (`"loop"` for the orchestrator's own records).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hat: Option<String>,
 }
 
 /// Reads new events from `.ralph/events.jsonl` since last read.
@@ -109,6 +112,20 @@ impl EventReader {
         }
     }
 
+    /// Returns the path of the events file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads new events without advancing past them.
+    pub fn peek_new_events(&self) -> std::io::Result<ParseResult> {
+        Self {
+            path: self.path.clone(),
+            position: self.position,
+        }
+        .read_new_events()
+    }
+
     /// Reads new events since the last read.
     ///
     /// Returns a `ParseResult` containing both successfully parsed events
@@ -191,10 +208,20 @@ impl EventReader {
         self.position
     }
 
+    /// Moves to `position`, e.g. one saved in a loop state snapshot.
+    pub fn seek(&mut self, position: u64) {
+        self.position = position;
+    }
+
     /// Resets the position to the start of the file.
     pub fn reset(&mut self) {
         self.position = 0;
     }
+
+    /// Returns true if the file has grown past the current position.
+    pub fn has_unread(&self) -> bool {
+        std::fs::metadata(&self.path).is_ok_and(|meta| meta.len() > self.position)
+    }
 }
 
 #[cfg(test)]
@@ -289,6 +316,21 @@ mod tests {
         assert!(result.malformed.is_empty());
     }
 
+    #[test]
+    fn test_has_unread() {
+        let mut file = NamedTempFile::new().unwrap();
+        let mut reader = EventReader::new(file.path());
+        assert!(!reader.has_unread());
+
+        writeln!(file, r#"{{"topic":"test","ts":"2024-01-01T00:00:00Z"}}"#).unwrap();
+        file.flush().unwrap();
+        assert!(reader.has_unread());
+
+        reader.read_new_events().unwrap();
+        assert!(!reader.has_unread());
+        assert!(!EventReader::new("/nonexistent/path.jsonl").has_unread());
+    }
+
     #[test]
     fn test_reset_position() {
         let mut file = NamedTempFile::new().unwrap();
@@ -0,0 +1,196 @@
+//! Per-topic JSON schemas for event payloads.
+//!
+//! `event_schemas` in config maps a topic to a JSON schema. Payloads that
+//! parse as JSON are validated as that value; any other payload is validated
+//! as a JSON string, and a missing payload as `null`.
+
+use crate::config::ConfigError;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Compiled payload schemas, keyed by topic.
+#[derive(Default)]
+pub struct EventSchemas {
+    validators: HashMap<String, jsonschema::Validator>,
+}
+
+impl fmt::Debug for EventSchemas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut topics: Vec<_> = self.validators.keys().collect();
+        topics.sort();
+        f.debug_struct("EventSchemas")
+            .field("topics", &topics)
+            .finish()
+    }
+}
+
+impl EventSchemas {
+    /// Compiles the configured schemas.
+    pub fn compile(schemas: &BTreeMap<String, Value>) -> Result<Self, ConfigError> {
+        let mut validators = HashMap::new();
+        for (topic, schema) in schemas {
+            let validator =
+                jsonschema::validator_for(schema).map_err(|e| ConfigError::InvalidEventSchema {
+                    topic: topic.clone(),
+                    message: e.to_string(),
+                })?;
+            validators.insert(topic.clone(), validator);
+        }
+        Ok(Self { validators })
+    }
+
+    /// Returns true if no topic has a schema.
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Validates an event payload as read from the events file.
+    ///
+    /// Topics without a schema always pass.
+    pub fn check(&self, topic: &str, payload: Option<&str>) -> Result<(), SchemaViolation> {
+        if !self.validators.contains_key(topic) {
+            return Ok(());
+        }
+        self.check_value(topic, &payload_value(payload))
+    }
+
+    /// Validates an already-parsed payload.
+    pub fn check_value(&self, topic: &str, payload: &Value) -> Result<(), SchemaViolation> {
+        let Some(validator) = self.validators.get(topic) else {
+            return Ok(());
+        };
+        let errors: Vec<String> = validator
+            .iter_errors(payload)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                if path.is_empty() {
+                    error.to_string()
+                } else {
+                    format!("{path}: {error}")
+                }
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaViolation {
+                topic: topic.to_string(),
+                errors,
+            })
+        }
+    }
+}
+
+/// Interprets a raw payload the way schemas see it.
+pub fn payload_value(payload: Option<&str>) -> Value {
+    match payload {
+        None => Value::Null,
+        Some(text) => {
+            serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+        }
+    }
+}
+
+/// A payload that does not match its topic's schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// The event topic.
+    pub topic: String,
+    /// One message per failed constraint, prefixed with the JSON pointer
+    /// of the offending value when it is not the payload itself.
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "payload for '{}' does not match its schema: {}",
+            self.topic,
+            self.errors.join("; ")
+        )
+    }
+}
+
+impl std::error::Error for SchemaViolation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn build_done_schemas() -> EventSchemas {
+        let mut schemas = BTreeMap::new();
+        schemas.insert(
+            "build.done".to_string(),
+            json!({
+                "type": "object",
+                "required": ["summary", "result"],
+                "properties": {
+                    "summary": { "type": "string" },
+                    "result": {
+                        "type": "object",
+                        "properties": { "tests": { "type": "string" } }
+                    }
+                }
+            }),
+        );
+        EventSchemas::compile(&schemas).unwrap()
+    }
+
+    #[test]
+    fn test_conforming_payload_passes() {
+        let schemas = build_done_schemas();
+
+        let payload = r#"{"summary":"all green","result":{"tests":"pass"}}"#;
+
+        assert_eq!(schemas.check("build.done", Some(payload)), Ok(()));
+    }
+
+    #[test]
+    fn test_stringly_typed_field_is_rejected() {
+        let schemas = build_done_schemas();
+
+        let payload = r#"{"summary":"all green","result":"{\"tests\":\"pass\"}"}"#;
+        let violation = schemas.check("build.done", Some(payload)).unwrap_err();
+
+        assert_eq!(violation.topic, "build.done");
+        assert_eq!(violation.errors.len(), 1);
+        assert!(
+            violation.errors[0].starts_with("/result: "),
+            "{}",
+            violation.errors[0]
+        );
+        assert!(violation.to_string().contains("'build.done'"));
+    }
+
+    #[test]
+    fn test_plain_text_and_missing_payloads_are_validated() {
+        let schemas = build_done_schemas();
+
+        assert!(schemas.check("build.done", Some("tests: pass")).is_err());
+        assert!(schemas.check("build.done", None).is_err());
+    }
+
+    #[test]
+    fn test_topics_without_schema_pass() {
+        let schemas = build_done_schemas();
+
+        assert_eq!(schemas.check("review.done", Some("anything")), Ok(()));
+        assert!(EventSchemas::default().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_schema_is_a_config_error() {
+        let mut schemas = BTreeMap::new();
+        schemas.insert("build.done".to_string(), json!({ "type": "objekt" }));
+
+        let err = EventSchemas::compile(&schemas).unwrap_err();
+
+        assert!(
+            matches!(&err, ConfigError::InvalidEventSchema { topic, .. } if topic == "build.done"),
+            "{err}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! File locking for shared resources in multi-loop scenarios.
 //!
-//! Provides fine-grained file locking using `flock()` for concurrent access
+//! Provides fine-grained file locking using `flock()` (`File::lock` on Windows) for concurrent access
 //! to shared resources like `.ralph/agent/tasks.jsonl` and `.ralph/agent/memories.md`.
 //! This enables multiple Ralph loops (in worktrees) to safely read and write
 //! shared state files.
@@ -122,7 +122,19 @@ impl FileLock {
             }
         }
 
-        #[cfg(not(unix))]
+        #[cfg(windows)]
+        {
+            match lock_type {
+                LockType::Shared => file.lock_shared()?,
+                LockType::Exclusive => file.lock()?,
+            }
+            Ok(LockGuard {
+                file,
+                _lock_type: lock_type,
+            })
+        }
+
+        #[cfg(not(any(unix, windows)))]
         {
             let _ = (file, lock_type);
             Err(io::Error::new(
@@ -161,7 +173,25 @@ impl FileLock {
             }
         }
 
-        #[cfg(not(unix))]
+        #[cfg(windows)]
+        {
+            use std::fs::TryLockError;
+
+            let locked = match lock_type {
+                LockType::Shared => file.try_lock_shared(),
+                LockType::Exclusive => file.try_lock(),
+            };
+            match locked {
+                Ok(()) => Ok(Some(LockGuard {
+                    file,
+                    _lock_type: lock_type,
+                })),
+                Err(TryLockError::WouldBlock) => Ok(None),
+                Err(TryLockError::Error(err)) => Err(err),
+            }
+        }
+
+        #[cfg(not(any(unix, windows)))]
         {
             let _ = (file, lock_type);
             Err(io::Error::new(
@@ -203,10 +233,22 @@ pub struct LockGuard {
     #[cfg(unix)]
     _flock: nix::fcntl::Flock<File>,
 
+    /// The locked file, unlocked on drop (Windows only).
+    #[cfg(windows)]
+    file: File,
+
     /// The type of lock held.
     _lock_type: LockType,
 }
 
+#[cfg(windows)]
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // Closing the handle releases the lock anyway, so a failure is harmless
+        let _ = self.file.unlock();
+    }
+}
+
 /// A locked file that provides safe read/write access.
 ///
 /// This is a convenience wrapper that combines file locking with
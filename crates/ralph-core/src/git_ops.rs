@@ -74,6 +74,32 @@ pub fn has_uncommitted_changes(path: impl AsRef<Path>) -> Result<bool, GitOpsErr
     Ok(!stdout.trim().is_empty())
 }
 
+/// List uncommitted changes as `git status --porcelain` lines
+/// (e.g. `" M src/lib.rs"`, `"?? notes.md"`).
+///
+/// # Arguments
+///
+/// * `path` - Path to the git repository (or worktree)
+pub fn uncommitted_files(path: impl AsRef<Path>) -> Result<Vec<String>, GitOpsError> {
+    let path = path.as_ref();
+
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitOpsError::Git(stderr.to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
 /// Auto-commit any uncommitted changes in the repository.
 ///
 /// This stages all changes (untracked, staged, unstaged) and creates a commit
@@ -408,6 +434,238 @@ pub fn get_recent_files(path: impl AsRef<Path>, limit: usize) -> Result<Vec<Stri
     Ok(files)
 }
 
+/// Outcome of simulating a merge without touching the working tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct MergePreview {
+    /// Common ancestor of the two branches.
+    pub merge_base: String,
+
+    /// Commits on the target branch that are not on the source branch.
+    pub target_ahead: usize,
+
+    /// Commits on the source branch that are not on the target branch.
+    pub source_ahead: usize,
+
+    /// Files changed on the source branch that would conflict.
+    pub conflicted: Vec<String>,
+
+    /// Files changed on the source branch that would merge cleanly.
+    pub clean: Vec<String>,
+
+    /// Tree of the simulated merge; conflicted files carry conflict markers.
+    pub tree: String,
+}
+
+impl MergePreview {
+    /// Returns true if the merge would produce conflicts.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicted.is_empty()
+    }
+}
+
+/// Simulate merging `source` into `target` without touching the working tree.
+///
+/// Uses `git merge-tree --write-tree` (git 2.38+), which performs the
+/// three-way merge in memory and only writes objects to the object store.
+///
+/// # Arguments
+///
+/// * `path` - Path to the git repository
+/// * `target` - Branch being merged into (e.g., `main`)
+/// * `source` - Branch being merged (e.g., `ralph/<loop_id>`)
+pub fn preview_merge(
+    path: impl AsRef<Path>,
+    target: &str,
+    source: &str,
+) -> Result<MergePreview, GitOpsError> {
+    let path = path.as_ref();
+
+    let merge_base = run_git(path, &["merge-base", target, source])?
+        .trim()
+        .to_string();
+
+    let range = format!("{}...{}", target, source);
+    let counts = run_git(path, &["rev-list", "--left-right", "--count", &range])?;
+    let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+    let target_ahead = counts.next().unwrap_or(0);
+    let source_ahead = counts.next().unwrap_or(0);
+
+    let output = Command::new("git")
+        .args([
+            "merge-tree",
+            "--write-tree",
+            "--name-only",
+            "--no-messages",
+            target,
+            source,
+        ])
+        .current_dir(path)
+        .output()?;
+
+    // First line is the resulting tree OID, followed by conflicted files
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let tree = lines.next().unwrap_or_default().trim().to_string();
+
+    // Exit code 1 means the merge has conflicts; anything else non-zero is an error
+    let conflicted: Vec<String> = match output.status.code() {
+        Some(0) => Vec::new(),
+        Some(1) => lines
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitOpsError::Git(format!(
+                "Failed to simulate merge: {}",
+                stderr
+            )));
+        }
+    };
+
+    let changed = run_git(path, &["diff", "--name-only", &merge_base, source, "--"])?;
+    let clean = changed
+        .lines()
+        .filter(|line| !line.is_empty() && !conflicted.iter().any(|c| c == line))
+        .map(String::from)
+        .collect();
+
+    Ok(MergePreview {
+        merge_base,
+        target_ahead,
+        source_ahead,
+        conflicted,
+        clean,
+        tree,
+    })
+}
+
+/// Read `file` as it stands in the tree of a simulated merge.
+///
+/// For a file listed in [`MergePreview::conflicted`] this is the content
+/// with conflict markers, as `git merge` would leave it in the working tree.
+pub fn merged_file_content(
+    path: impl AsRef<Path>,
+    preview: &MergePreview,
+    file: &str,
+) -> Result<String, GitOpsError> {
+    run_git(
+        path.as_ref(),
+        &["show", &format!("{}:{}", preview.tree, file)],
+    )
+}
+
+/// Outcome of bringing a branch into the checked-out target branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrationOutcome {
+    /// The branch landed; `commit` is the new head of the target branch.
+    Landed { commit: String },
+
+    /// The operation stopped on conflicts and was rolled back.
+    Conflicted { files: Vec<String> },
+}
+
+/// Squash `source` into a single commit on the branch checked out at `path`.
+///
+/// On conflict the squash is rolled back with `git reset --merge`, leaving
+/// the checkout and `source` as they were.
+///
+/// # Arguments
+///
+/// * `path` - Path to the checkout of the target branch
+/// * `source` - Branch being squashed (e.g., `ralph/<loop_id>`)
+/// * `message` - Message for the squash commit
+pub fn squash_merge(
+    path: impl AsRef<Path>,
+    source: &str,
+    message: &str,
+) -> Result<IntegrationOutcome, GitOpsError> {
+    let path = path.as_ref();
+
+    let output = Command::new("git")
+        .args(["merge", "--squash", source])
+        .current_dir(path)
+        .output()?;
+
+    if !output.status.success() {
+        let files = conflicted_files(path)?;
+        run_git(path, &["reset", "--merge"])?;
+        if files.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitOpsError::Git(stderr.to_string()));
+        }
+        return Ok(IntegrationOutcome::Conflicted { files });
+    }
+
+    run_git(path, &["commit", "-m", message])?;
+    Ok(IntegrationOutcome::Landed {
+        commit: get_head_sha(path)?,
+    })
+}
+
+/// Rebase `source` onto `target`, then fast-forward `target` to it.
+///
+/// The rebase runs in `worktree`, where `source` is checked out. On conflict
+/// it is aborted, leaving the worktree on its original commits.
+///
+/// # Arguments
+///
+/// * `path` - Path to the checkout of `target`
+/// * `worktree` - Path to the worktree that has `source` checked out
+/// * `target` - Branch being rebased onto (e.g., `main`)
+/// * `source` - Branch being rebased (e.g., `ralph/<loop_id>`)
+pub fn rebase_and_fast_forward(
+    path: impl AsRef<Path>,
+    worktree: impl AsRef<Path>,
+    target: &str,
+    source: &str,
+) -> Result<IntegrationOutcome, GitOpsError> {
+    let path = path.as_ref();
+    let worktree = worktree.as_ref();
+
+    let output = Command::new("git")
+        .args(["rebase", target])
+        .current_dir(worktree)
+        .output()?;
+
+    if !output.status.success() {
+        let files = conflicted_files(worktree)?;
+        run_git(worktree, &["rebase", "--abort"])?;
+        if files.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitOpsError::Git(stderr.to_string()));
+        }
+        return Ok(IntegrationOutcome::Conflicted { files });
+    }
+
+    run_git(path, &["merge", "--ff-only", source])?;
+    Ok(IntegrationOutcome::Landed {
+        commit: get_head_sha(path)?,
+    })
+}
+
+/// List files with unresolved conflicts in the checkout at `path`.
+fn conflicted_files(path: &Path) -> Result<Vec<String>, GitOpsError> {
+    let output = run_git(path, &["diff", "--name-only", "--diff-filter=U"])?;
+    Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Run a git command and return its stdout, or an error with its stderr.
+fn run_git(path: &Path, args: &[&str]) -> Result<String, GitOpsError> {
+    let output = Command::new("git").args(args).current_dir(path).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitOpsError::Git(stderr.to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -766,4 +1024,166 @@ mod tests {
             files
         );
     }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit_file(dir: &Path, file: &str, content: &str) {
+        fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-m", &format!("Update {}", file)]);
+    }
+
+    #[test]
+    fn test_preview_merge_reports_conflicts_and_clean_files() {
+        let temp = TempDir::new().unwrap();
+        init_git_repo(temp.path());
+
+        git(temp.path(), &["checkout", "-b", "ralph/test"]);
+        commit_file(temp.path(), "README.md", "# From loop");
+        commit_file(temp.path(), "feature.txt", "new feature");
+
+        git(temp.path(), &["checkout", "main"]);
+        commit_file(temp.path(), "README.md", "# From main");
+
+        let preview = preview_merge(temp.path(), "main", "ralph/test").unwrap();
+
+        assert!(preview.has_conflicts());
+        assert_eq!(preview.conflicted, vec!["README.md".to_string()]);
+        assert_eq!(preview.clean, vec!["feature.txt".to_string()]);
+        assert_eq!(preview.target_ahead, 1);
+        assert_eq!(preview.source_ahead, 2);
+        assert!(!preview.merge_base.is_empty());
+
+        let merged = merged_file_content(temp.path(), &preview, "README.md").unwrap();
+        assert!(merged.contains("<<<<<<<"), "{}", merged);
+        assert!(merged.contains("# From main") && merged.contains("# From loop"));
+
+        // Working tree untouched
+        assert!(is_working_tree_clean(temp.path()).unwrap());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("README.md")).unwrap(),
+            "# From main"
+        );
+    }
+
+    #[test]
+    fn test_preview_merge_clean() {
+        let temp = TempDir::new().unwrap();
+        init_git_repo(temp.path());
+
+        git(temp.path(), &["checkout", "-b", "ralph/test"]);
+        commit_file(temp.path(), "feature.txt", "new feature");
+        git(temp.path(), &["checkout", "main"]);
+
+        let preview = preview_merge(temp.path(), "main", "ralph/test").unwrap();
+
+        assert!(!preview.has_conflicts());
+        assert_eq!(preview.clean, vec!["feature.txt".to_string()]);
+        assert_eq!(preview.target_ahead, 0);
+        assert_eq!(preview.source_ahead, 1);
+    }
+
+    #[test]
+    fn test_preview_merge_unknown_branch_errors() {
+        let temp = TempDir::new().unwrap();
+        init_git_repo(temp.path());
+
+        assert!(preview_merge(temp.path(), "main", "ralph/missing").is_err());
+    }
+
+    /// Repo on `main` with `ralph/test` checked out in a worktree at `loop`.
+    fn init_repo_with_worktree(temp: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+        let repo = temp.path().join("repo");
+        let worktree = temp.path().join("loop");
+        fs::create_dir(&repo).unwrap();
+        init_git_repo(&repo);
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-b",
+                "ralph/test",
+                worktree.to_str().unwrap(),
+            ],
+        );
+        (repo, worktree)
+    }
+
+    #[test]
+    fn test_squash_merge_lands_single_commit() {
+        let temp = TempDir::new().unwrap();
+        let (repo, worktree) = init_repo_with_worktree(&temp);
+        commit_file(&worktree, "a.txt", "a");
+        commit_file(&worktree, "b.txt", "b");
+
+        let outcome = squash_merge(&repo, "ralph/test", "merge(ralph): add files").unwrap();
+
+        let IntegrationOutcome::Landed { commit } = outcome else {
+            panic!("expected squash to land: {:?}", outcome);
+        };
+        assert_eq!(commit, get_head_sha(&repo).unwrap());
+        assert!(
+            get_commit_summary(&repo)
+                .unwrap()
+                .ends_with(": merge(ralph): add files")
+        );
+        assert!(repo.join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_rebase_and_fast_forward_lands_linear_history() {
+        let temp = TempDir::new().unwrap();
+        let (repo, worktree) = init_repo_with_worktree(&temp);
+        commit_file(&worktree, "feature.txt", "feature");
+        commit_file(&repo, "main.txt", "main");
+
+        let outcome = rebase_and_fast_forward(&repo, &worktree, "main", "ralph/test").unwrap();
+
+        assert!(matches!(outcome, IntegrationOutcome::Landed { .. }));
+        assert_eq!(
+            get_head_sha(&repo).unwrap(),
+            get_head_sha(&worktree).unwrap()
+        );
+        assert!(repo.join("feature.txt").exists());
+    }
+
+    #[test]
+    fn test_conflicts_roll_back_and_keep_the_branch() {
+        let temp = TempDir::new().unwrap();
+        let (repo, worktree) = init_repo_with_worktree(&temp);
+        commit_file(&worktree, "README.md", "# From loop");
+        commit_file(&repo, "README.md", "# From main");
+        let loop_head = get_head_sha(&worktree).unwrap();
+        let main_head = get_head_sha(&repo).unwrap();
+
+        for outcome in [
+            rebase_and_fast_forward(&repo, &worktree, "main", "ralph/test").unwrap(),
+            squash_merge(&repo, "ralph/test", "merge(ralph): readme").unwrap(),
+        ] {
+            assert_eq!(
+                outcome,
+                IntegrationOutcome::Conflicted {
+                    files: vec!["README.md".to_string()]
+                }
+            );
+        }
+
+        assert_eq!(get_head_sha(&worktree).unwrap(), loop_head);
+        assert_eq!(get_head_sha(&repo).unwrap(), main_head);
+        assert!(is_working_tree_clean(&worktree).unwrap());
+        assert!(is_working_tree_clean(&repo).unwrap());
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::config::CoreConfig;
 use crate::hat_registry::HatRegistry;
+use crate::ralph_ignore::RalphIgnore;
 use ralph_proto::Topic;
 use std::collections::HashMap;
 use std::path::Path;
@@ -27,6 +28,10 @@ pub struct HatlessRalph {
     /// Collected robot guidance messages for injection into prompts.
     /// Set by EventLoop before build_prompt(), cleared after injection.
     robot_guidance: Vec<String>,
+    /// `.ralphignore` rules; ignored context files are not listed.
+    ignore: RalphIgnore,
+    /// Shared `includes` text shown ahead of each active hat's instructions.
+    instructions_preamble: String,
 }
 
 /// Hat topology for multi-hat mode prompt generation.
@@ -170,6 +175,8 @@ impl HatlessRalph {
             objective: None,
             skill_index: String::new(),
             robot_guidance: Vec::new(),
+            ignore: RalphIgnore::empty("."),
+            instructions_preamble: String::new(),
         }
     }
 
@@ -191,6 +198,21 @@ impl HatlessRalph {
         self
     }
 
+    /// Sets the `.ralphignore` rules applied to the context file listing.
+    pub fn with_ignore(mut self, ignore: RalphIgnore) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    /// Sets the shared preamble (from `includes`) shown with each active hat.
+    ///
+    /// It does not count as custom instructions, so a hat without its own
+    /// still gets the generic workflow.
+    pub fn with_instructions_preamble(mut self, preamble: String) -> Self {
+        self.instructions_preamble = preamble;
+        self
+    }
+
     /// Stores the user's original objective so it persists across all iterations.
     ///
     /// Called once during initialization. The objective is injected into every
@@ -439,6 +461,7 @@ Its content is auto-injected in `<scratchpad>` tags at the top of your context e
                     let path = e.path();
                     if path.extension().and_then(|s| s.to_str()) == Some("md")
                         && path.file_name().and_then(|s| s.to_str()) != Some("memories.md")
+                        && !self.ignore.is_ignored(&path)
                     {
                         path.file_name()
                             .and_then(|s| s.to_str())
@@ -667,10 +690,12 @@ You MUST continue until all tasks are `[x]` or `[~]`.
                 // Find matching HatInfo from topology to access event_receivers
                 let hat_info = topology.hats.iter().find(|h| h.name == active_hat.name);
 
-                if !active_hat.instructions.trim().is_empty() {
+                let instructions =
+                    format!("{}{}", self.instructions_preamble, active_hat.instructions);
+                if !instructions.trim().is_empty() {
                     section.push_str(&format!("### {} Instructions\n\n", active_hat.name));
-                    section.push_str(&active_hat.instructions);
-                    if !active_hat.instructions.ends_with('\n') {
+                    section.push_str(&instructions);
+                    if !instructions.ends_with('\n') {
                         section.push('\n');
                     }
                     section.push('\n');
@@ -872,6 +897,44 @@ You MUST NOT declare completion until this objective is fully satisfied.
     }
 }
 
+/// Prompt sections that change between iterations. The rest of a full
+/// prompt (objective, workflow, hat topology, event writing) repeats as is.
+const CONTINUATION_SECTIONS: &[&str] = &["## ROBOT GUIDANCE", "## PENDING EVENTS", "## ACTIVE HAT"];
+
+/// Reduces a full iteration prompt to what a backend resuming its previous
+/// session has not seen: the `<iteration-context>` block and the guidance,
+/// pending events and active hat sections.
+pub fn continuation_prompt(prompt: &str) -> String {
+    let mut delta = String::from(
+        "Continue from your previous iteration. Your objective and workflow are unchanged; \
+         below is what is new for this iteration.\n\n",
+    );
+    let mut in_context = false;
+    let mut keep = false;
+    for line in prompt.lines() {
+        if line == "<iteration-context>" {
+            in_context = true;
+        }
+        if in_context {
+            delta.push_str(line);
+            delta.push('\n');
+            if line == "</iteration-context>" {
+                in_context = false;
+                delta.push('\n');
+            }
+            continue;
+        }
+        if line.starts_with("## ") {
+            keep = CONTINUATION_SECTIONS.contains(&line.trim_end());
+        }
+        if keep {
+            delta.push_str(line);
+            delta.push('\n');
+        }
+    }
+    delta
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1159,6 +1222,39 @@ hats:
         );
     }
 
+    #[test]
+    fn test_includes_preamble_keeps_workflow_for_hat_without_instructions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("style.md"), "Keep it short.\n").unwrap();
+        std::fs::write(
+            dir.path().join("ralph.yml"),
+            r#"
+includes:
+  - style.md
+hats:
+  builder:
+    name: "Builder"
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+"#,
+        )
+        .unwrap();
+        let config = RalphConfig::from_file(dir.path().join("ralph.yml")).unwrap();
+        let registry = HatRegistry::from_config(&config);
+        let ralph = HatlessRalph::new("LOOP_COMPLETE", config.core.clone(), &registry, None)
+            .with_instructions_preamble(config.include_preamble.clone());
+
+        let builder = registry.get(&ralph_proto::HatId::new("builder")).unwrap();
+        assert!(builder.instructions.is_empty());
+        let prompt = ralph.build_prompt("", &[builder]);
+
+        assert!(prompt.contains("## WORKFLOW"), "{prompt}");
+        assert!(
+            prompt.contains("### Builder Instructions\n\nKeep it short.\n"),
+            "{prompt}"
+        );
+    }
+
     #[test]
     fn test_multiple_hats_with_instructions() {
         // When multiple hats have instructions, each should have its own section
@@ -2463,4 +2559,29 @@ hats:
             "Should NOT include ROBOT GUIDANCE when no guidance set"
         );
     }
+
+    #[test]
+    fn test_continuation_prompt_keeps_only_changing_sections() {
+        let config = RalphConfig::default();
+        let registry = HatRegistry::new();
+        let mut ralph = HatlessRalph::new("LOOP_COMPLETE", config.core.clone(), &registry, None);
+        ralph.set_objective("Ship the parser".to_string());
+        ralph.set_robot_guidance(vec!["Use the new API".to_string()]);
+        let full = format!(
+            "<iteration-context>\n## Iteration\n3 of 10 (7 remaining)\n</iteration-context>\n\n{}",
+            ralph.build_prompt("Event: build.task - Do the work", &[])
+        );
+
+        let delta = continuation_prompt(&full);
+
+        assert!(delta.contains("3 of 10 (7 remaining)"));
+        assert!(delta.contains("## ROBOT GUIDANCE"));
+        assert!(delta.contains("Use the new API"));
+        assert!(delta.contains("## PENDING EVENTS"));
+        assert!(delta.contains("Event: build.task - Do the work"));
+        assert!(!delta.contains("## OBJECTIVE"));
+        assert!(!delta.contains("## WORKFLOW"));
+        assert!(!delta.contains("## EVENT WRITING"));
+        assert!(delta.len() < full.len() / 2, "delta should be much shorter");
+    }
 }
@@ -4,10 +4,54 @@
 //! - 0a, 0b: Orientation (study specs, study context)
 //! - 1, 2, 3: Workflow phases
 //! - 999+: Guardrails (higher = more important)
-
-use crate::config::{CoreConfig, EventMetadata};
+//!
+//! Hat instructions may reference `{{workspace_root}}`, `{{scratchpad}}`,
+//! `{{iteration}}` and `{{specs_dir}}`; they are filled in when the prompt is
+//! built, so one instruction points at the right paths inside worktrees too.
+//!
+//! With `event_loop.context.enabled`, each iteration's prompt also starts
+//! with an `<iteration-context>` block (see [`IterationContext`]).
+
+use crate::config::{CoreConfig, EventMetadata, IterationContextConfig};
+use crate::event_logger::EventRecord;
+use crate::prompt_template::{PromptTemplateError, render_known, render_prompt};
+use crate::task::{Task, TaskStatus};
+use crate::text::truncate_with_ellipsis;
 use ralph_proto::Hat;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// Variables that hat instructions can reference as `{{name}}`.
+pub const INSTRUCTION_VARIABLES: [&str; 4] =
+    ["workspace_root", "scratchpad", "iteration", "specs_dir"];
+
+/// Names of the `{{name}}` placeholders in `instructions` that aren't
+/// [`INSTRUCTION_VARIABLES`], sorted and deduplicated.
+pub fn unknown_variables(instructions: &str) -> Vec<String> {
+    let known = INSTRUCTION_VARIABLES
+        .iter()
+        .map(|name| (name.to_string(), String::new()))
+        .collect();
+    match render_prompt(instructions, &known) {
+        Err(PromptTemplateError::MissingVars(names)) => names,
+        _ => Vec::new(),
+    }
+}
+
+/// Longest event payload shown in the iteration context, in characters.
+const CONTEXT_PAYLOAD_CHARS: usize = 200;
+
+/// What an iteration's context block can show.
+#[derive(Debug, Clone, Default)]
+pub struct IterationContext {
+    /// The iteration about to run.
+    pub iteration: u32,
+    /// `event_loop.max_iterations`.
+    pub max_iterations: u32,
+    /// Most recent events, oldest first.
+    pub recent_events: Vec<EventRecord>,
+    /// Open tasks, highest priority first.
+    pub open_tasks: Vec<Task>,
+}
 
 /// Builds instructions for custom hats.
 ///
@@ -18,20 +62,164 @@ pub struct InstructionBuilder {
     core: CoreConfig,
     /// Event metadata for deriving instructions from pub/sub contracts.
     events: HashMap<String, EventMetadata>,
+    /// Sections and budget of the iteration context block.
+    context: IterationContextConfig,
+    /// Shared `includes` text prepended to every hat's role instructions.
+    preamble: String,
 }
 
 impl InstructionBuilder {
     /// Creates a new instruction builder with core configuration.
     pub fn new(core: CoreConfig) -> Self {
+        Self::with_events(core, HashMap::new())
+    }
+
+    /// Creates a new instruction builder with event metadata for custom hats.
+    pub fn with_events(core: CoreConfig, events: HashMap<String, EventMetadata>) -> Self {
         Self {
             core,
-            events: HashMap::new(),
+            events,
+            context: IterationContextConfig::default(),
+            preamble: String::new(),
         }
     }
 
-    /// Creates a new instruction builder with event metadata for custom hats.
-    pub fn with_events(core: CoreConfig, events: HashMap<String, EventMetadata>) -> Self {
-        Self { core, events }
+    /// Sets which iteration context sections are rendered.
+    #[must_use]
+    pub fn with_context(mut self, context: IterationContextConfig) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Sets the shared preamble (from `includes`) prepended to hat instructions.
+    #[must_use]
+    pub fn with_preamble(mut self, preamble: String) -> Self {
+        self.preamble = preamble;
+        self
+    }
+
+    /// Returns the iteration context settings.
+    pub fn context_config(&self) -> &IterationContextConfig {
+        &self.context
+    }
+
+    /// Renders the `<iteration-context>` block, or `None` when it is
+    /// disabled or has nothing to show.
+    ///
+    /// Over `max_chars`, the oldest events are dropped first, then the
+    /// lowest-priority tasks. The iteration line is always kept.
+    pub fn build_iteration_context(&self, context: &IterationContext) -> Option<String> {
+        if !self.context.enabled {
+            return None;
+        }
+
+        let iteration = self.context.iteration.then(|| {
+            format!(
+                "## Iteration\n{} of {} ({} remaining)\n",
+                context.iteration,
+                context.max_iterations,
+                context.max_iterations.saturating_sub(context.iteration)
+            )
+        });
+        let mut events: Vec<String> = if self.context.events {
+            let skip = context
+                .recent_events
+                .len()
+                .saturating_sub(self.context.recent_events);
+            context.recent_events[skip..]
+                .iter()
+                .map(format_context_event)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut tasks: Vec<String> = if self.context.tasks {
+            context.open_tasks.iter().map(format_context_task).collect()
+        } else {
+            Vec::new()
+        };
+
+        let render = |events: &[String], tasks: &[String], omitted: (usize, usize)| {
+            let mut block = String::from("<iteration-context>\n");
+            if let Some(iteration) = &iteration {
+                block.push_str(iteration);
+            }
+            if !events.is_empty() || omitted.0 > 0 {
+                block.push_str("\n## Recent events\n");
+                for line in events {
+                    block.push_str(line);
+                }
+                if omitted.0 > 0 {
+                    block.push_str(&format!("- ... {} older omitted\n", omitted.0));
+                }
+            }
+            if !tasks.is_empty() || omitted.1 > 0 {
+                block.push_str("\n## Open tasks\n");
+                for line in tasks {
+                    block.push_str(line);
+                }
+                if omitted.1 > 0 {
+                    block.push_str(&format!("- ... {} more omitted\n", omitted.1));
+                }
+            }
+            block.push_str("</iteration-context>\n\n");
+            block
+        };
+
+        if iteration.is_none() && events.is_empty() && tasks.is_empty() {
+            return None;
+        }
+
+        let mut omitted = (0, 0);
+        let mut block = render(&events, &tasks, omitted);
+        while block.chars().count() > self.context.max_chars {
+            if !events.is_empty() {
+                events.remove(0);
+                omitted.0 += 1;
+            } else if !tasks.is_empty() {
+                tasks.pop();
+                omitted.1 += 1;
+            } else {
+                break;
+            }
+            block = render(&events, &tasks, omitted);
+        }
+        Some(block)
+    }
+
+    /// Values for [`INSTRUCTION_VARIABLES`] at `iteration`.
+    ///
+    /// Paths are absolute, resolved against the workspace root.
+    pub fn template_vars(&self, iteration: u32) -> BTreeMap<String, String> {
+        let root = &self.core.workspace_root;
+        BTreeMap::from([
+            ("workspace_root".to_string(), root.display().to_string()),
+            (
+                "scratchpad".to_string(),
+                root.join(&self.core.scratchpad).display().to_string(),
+            ),
+            ("iteration".to_string(), iteration.to_string()),
+            (
+                "specs_dir".to_string(),
+                root.join(&self.core.specs_dir).display().to_string(),
+            ),
+        ])
+    }
+
+    /// Fills the template variables in `instructions`.
+    ///
+    /// Unknown placeholders are left as written; strict mode rejects them
+    /// when the config is validated.
+    pub fn render_instructions(&self, instructions: &str, iteration: u32) -> String {
+        render_known(instructions, &self.template_vars(iteration))
+            .unwrap_or_else(|_| instructions.to_string())
+    }
+
+    /// Returns a copy of `hat` with its instructions rendered for `iteration`.
+    pub fn render_hat(&self, hat: &Hat, iteration: u32) -> Hat {
+        let mut hat = hat.clone();
+        hat.instructions = self.render_instructions(&hat.instructions, iteration);
+        hat
     }
 
     /// Derives instructions from a hat's pub/sub contract and event metadata.
@@ -148,6 +336,7 @@ impl InstructionBuilder {
         } else {
             hat.instructions.clone()
         };
+        let role_instructions = format!("{}{role_instructions}", self.preamble);
 
         let (publish_topics, must_publish) = if hat.publishes.is_empty() {
             (String::new(), String::new())
@@ -204,6 +393,39 @@ You MUST handle these events:
     }
 }
 
+/// One line per event: who wrote it, its topic, and a shortened,
+/// single-line payload.
+fn format_context_event(record: &EventRecord) -> String {
+    let mut line = String::from("- ");
+    if !record.hat.is_empty() {
+        line.push_str(&format!("[{}] ", record.hat));
+    }
+    line.push_str(&record.topic);
+    let payload = record
+        .payload
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !payload.is_empty() {
+        line.push_str(": ");
+        line.push_str(&truncate_with_ellipsis(&payload, CONTEXT_PAYLOAD_CHARS));
+    }
+    line.push('\n');
+    line
+}
+
+fn format_context_task(task: &Task) -> String {
+    let status = if task.status == TaskStatus::InProgress {
+        "[~]"
+    } else {
+        "[ ]"
+    };
+    format!(
+        "- {} [P{}] {} ({})\n",
+        status, task.priority, task.title, task.id
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,6 +478,8 @@ mod tests {
     fn test_custom_guardrails_injected() {
         let custom_core = CoreConfig {
             scratchpad: ".workspace/plan.md".to_string(),
+            scratchpad_archive_keep: 10,
+            runs_keep: 100,
             specs_dir: "./specifications/".to_string(),
             guardrails: vec!["Custom rule one".to_string(), "Custom rule two".to_string()],
             workspace_root: std::path::PathBuf::from("."),
@@ -326,4 +550,173 @@ mod tests {
         assert!(instructions.contains("Derived Behaviors"));
         assert!(instructions.contains("build.task"));
     }
+
+    #[test]
+    fn test_preamble_prepended_to_derived_instructions() {
+        let builder = default_builder().with_preamble("Keep it short.\n".to_string());
+        let hat = Hat::new("builder", "Builder").subscribe("build.task");
+
+        let instructions = builder.build_custom_hat(&hat, "Implement feature X");
+
+        let preamble = instructions.find("Keep it short.").unwrap();
+        let derived = instructions.find("Derived Behaviors").unwrap();
+        assert!(preamble < derived);
+    }
+
+    #[test]
+    fn test_template_variables_substituted() {
+        let builder = InstructionBuilder::new(CoreConfig {
+            workspace_root: std::path::PathBuf::from("/work/tree"),
+            scratchpad: ".ralph/agent/scratchpad.md".to_string(),
+            specs_dir: "specs".to_string(),
+            ..CoreConfig::default()
+        });
+        let hat = Hat::new("builder", "Builder").with_instructions(
+            "Read {{scratchpad}} and {{ specs_dir }} in {{workspace_root}} (iteration {{iteration}}).",
+        );
+
+        let rendered = builder.render_hat(&hat, 7);
+
+        assert_eq!(
+            rendered.instructions,
+            "Read /work/tree/.ralph/agent/scratchpad.md and /work/tree/specs in /work/tree (iteration 7)."
+        );
+        let prompt = builder.build_custom_hat(&rendered, "");
+        assert!(prompt.contains("(iteration 7)"));
+    }
+
+    #[test]
+    fn test_absolute_scratchpad_kept() {
+        let builder = InstructionBuilder::new(CoreConfig {
+            workspace_root: std::path::PathBuf::from("/work/tree"),
+            scratchpad: "/main/.ralph/worktrees/a/scratchpad.md".to_string(),
+            ..CoreConfig::default()
+        });
+
+        assert_eq!(
+            builder.render_instructions("{{scratchpad}}", 1),
+            "/main/.ralph/worktrees/a/scratchpad.md"
+        );
+    }
+
+    #[test]
+    fn test_unknown_template_variables_left_literal() {
+        let builder = default_builder();
+
+        assert_eq!(
+            builder.render_instructions(r"{{ticket}} at {{iteration}}, \{{iteration}}", 3),
+            "{{ticket}} at 3, {{iteration}}"
+        );
+        assert_eq!(
+            unknown_variables("{{zeta}} {{iteration}} {{alpha}} {{zeta}} ${{ secrets.TOKEN }}"),
+            vec!["alpha".to_string(), "zeta".to_string()]
+        );
+        assert!(unknown_variables(r"{{scratchpad}} \{{ticket}}").is_empty());
+    }
+
+    fn context_builder(configure: impl FnOnce(&mut IterationContextConfig)) -> InstructionBuilder {
+        let mut context = IterationContextConfig {
+            enabled: true,
+            ..IterationContextConfig::default()
+        };
+        configure(&mut context);
+        default_builder().with_context(context)
+    }
+
+    fn sample_context() -> IterationContext {
+        let event = |topic: &str, payload: &str| {
+            EventRecord::new(2, "builder", &ralph_proto::Event::new(topic, payload), None)
+        };
+        IterationContext {
+            iteration: 3,
+            max_iterations: 10,
+            recent_events: vec![
+                event("build.task", "Implement the parser"),
+                event("build.done", "Parser done\nall tests pass"),
+            ],
+            open_tasks: vec![
+                Task::new("Wire up the CLI".to_string(), 1),
+                Task::new("Write docs".to_string(), 3),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_iteration_context_renders_all_sections() {
+        let block = context_builder(|_| {})
+            .build_iteration_context(&sample_context())
+            .unwrap();
+
+        assert!(block.starts_with("<iteration-context>\n"));
+        assert!(block.ends_with("</iteration-context>\n\n"));
+        assert!(block.contains("3 of 10 (7 remaining)"));
+        assert!(block.contains("- [builder] build.task: Implement the parser\n"));
+        assert!(block.contains("- [builder] build.done: Parser done all tests pass\n"));
+        assert!(block.contains("[ ] [P1] Wire up the CLI"));
+        assert!(block.contains("[ ] [P3] Write docs"));
+    }
+
+    #[test]
+    fn test_iteration_context_sections_can_be_turned_off() {
+        let block = context_builder(|context| {
+            context.events = false;
+            context.tasks = false;
+        })
+        .build_iteration_context(&sample_context())
+        .unwrap();
+
+        assert!(block.contains("## Iteration"));
+        assert!(!block.contains("## Recent events"));
+        assert!(!block.contains("## Open tasks"));
+
+        let disabled = context_builder(|context| context.enabled = false);
+        assert_eq!(disabled.build_iteration_context(&sample_context()), None);
+    }
+
+    #[test]
+    fn test_iteration_context_truncates_events_before_tasks() {
+        let full = context_builder(|_| {})
+            .build_iteration_context(&sample_context())
+            .unwrap();
+        let event_line = "- [builder] build.task: Implement the parser\n";
+
+        // Room for everything except the oldest event
+        let block = context_builder(|context| {
+            context.max_chars = full.chars().count() - event_line.len() + 30;
+        })
+        .build_iteration_context(&sample_context())
+        .unwrap();
+        assert!(!block.contains("build.task"), "{block}");
+        assert!(block.contains("build.done"), "{block}");
+        assert!(block.contains("- ... 1 older omitted"), "{block}");
+        assert!(block.contains("Write docs"), "{block}");
+
+        // Too small for any events or tasks: only the iteration line is left
+        let block = context_builder(|context| context.max_chars = 10)
+            .build_iteration_context(&sample_context())
+            .unwrap();
+        assert!(block.contains("3 of 10"), "{block}");
+        assert!(block.contains("- ... 2 older omitted"), "{block}");
+        assert!(block.contains("- ... 2 more omitted"), "{block}");
+        assert!(!block.contains("Wire up the CLI"), "{block}");
+    }
+
+    #[test]
+    fn test_iteration_context_payloads_are_truncated() {
+        let mut context = sample_context();
+        context.recent_events = vec![EventRecord::new(
+            1,
+            "builder",
+            &ralph_proto::Event::new("build.done", "x".repeat(500)),
+            None,
+        )];
+
+        let block = context_builder(|_| {})
+            .build_iteration_context(&context)
+            .unwrap();
+
+        let line = block.lines().find(|l| l.contains("build.done")).unwrap();
+        assert!(line.ends_with("..."), "{line}");
+        assert!(line.len() < 250, "{line}");
+    }
 }
@@ -4,6 +4,7 @@
 //!
 //! This crate provides:
 //! - The main orchestration loop for coordinating multiple agents
+//! - An embeddable `Orchestrator` entrypoint for running loops programmatically
 //! - Configuration loading and management
 //! - State management for agent sessions
 //! - Message routing between agents
@@ -12,12 +13,16 @@
 
 #[cfg(feature = "recording")]
 mod cli_capture;
+mod completion_check;
 mod config;
 pub mod diagnostics;
+mod env_source;
+mod event_hooks;
 mod event_logger;
 mod event_loop;
 mod event_parser;
 mod event_reader;
+mod event_schema;
 pub mod file_lock;
 mod git_ops;
 mod handoff;
@@ -31,12 +36,26 @@ pub mod loop_history;
 pub mod loop_lock;
 mod loop_name;
 pub mod loop_registry;
+mod loop_status;
 mod memory;
+mod memory_export;
+pub mod memory_extraction;
 pub mod memory_parser;
 mod memory_store;
 pub mod merge_queue;
+mod orchestrator;
+mod pause;
 pub mod planning_session;
 pub mod preflight;
+mod prompt_source;
+mod prompt_template;
+mod ralph_ignore;
+mod redact;
+pub mod robot;
+mod robot_fanout;
+pub mod run_history;
+mod run_metrics;
+pub mod scratchpad_archive;
 #[cfg(feature = "recording")]
 mod session_player;
 #[cfg(feature = "recording")]
@@ -54,44 +73,73 @@ pub mod workspace;
 pub mod worktree;
 
 #[cfg(feature = "recording")]
-pub use cli_capture::{CliCapture, CliCapturePair};
+pub use cli_capture::{CAPTURE_CHANNEL_CAPACITY, CliCapture, CliCapturePair};
 pub use config::{
-    CliConfig, ConfigError, CoreConfig, EventLoopConfig, EventMetadata, FeaturesConfig, HatBackend,
-    HatConfig, InjectMode, MemoriesConfig, MemoriesFilter, RalphConfig, SkillOverride,
-    SkillsConfig,
+    AdapterSettings, AdaptersConfig, AutoMergeConflictPolicy, CaptureConfig, CliConfig,
+    CompletionCheckConfig, CompletionMatcher, CompletionPromises, ConfigError, CoreConfig,
+    CustomBackendConfig, CustomOutputFormat, CustomPromptMode, DiagnosticsConfig, EventDedupConfig,
+    EventHookConfig, EventLoopConfig, EventMetadata, EventQueueConfig, FeaturesConfig, HatBackend,
+    HatConfig, HookFailurePolicy, InjectMode, IterationContextConfig, MemoriesConfig,
+    MemoriesFilter, ModelPricing, QueueOverflowPolicy, RalphConfig, RecordingConfig, RedactRule,
+    RetryConfig, RobotNotificationsConfig, SchemaMismatchAction, SecurityConfig, SkillOverride,
+    SkillsConfig, SlackBotConfig, WebAuthConfig, WebAuthMode, WebConfig, WorktreeFeaturesConfig,
 };
 // Re-export loop_name types (also available via FeaturesConfig.loop_naming)
+pub use completion_check::{CompletionCheckOutcome, command_executable, run_completion_check};
 pub use diagnostics::DiagnosticsCollector;
-pub use event_logger::{EventHistory, EventLogger, EventRecord};
-pub use event_loop::{EventLoop, LoopState, TerminationReason, UserPrompt};
+pub use env_source::{
+    KEYCHAIN_PREFIX, KEYCHAIN_SERVICE, KeyringStore, ResolvedEnvValue, SecretStore,
+    expand_env_refs, missing_env_sources, resolve_env_value, secret_env_values,
+};
+pub use event_hooks::{
+    EventHookRunner, HOOK_COMPLETED_TOPIC, HOOK_FAILED_TOPIC, MAX_CONCURRENT_HOOKS,
+};
+pub use event_logger::{EventHistory, EventLogger, EventReadReport, EventRecord};
+pub use event_loop::{
+    COMPLETION_REJECTED_TOPIC, COMPLETION_VERIFIED_TOPIC, EVENT_DUPLICATE_TOPIC,
+    EVENT_UNHANDLED_TOPIC, EventLoop, HAT_BUDGET_EXCEEDED_TOPIC, ITERATION_RETRIED_TOPIC,
+    LOOP_PAUSED_TOPIC, LOOP_RESUMED_TOPIC, LoopState, LoopStateSnapshot, TerminationReason,
+    UserPrompt,
+};
 pub use event_parser::EventParser;
 pub use event_reader::{Event, EventReader, MalformedLine, ParseResult};
+pub use event_schema::{EventSchemas, SchemaViolation, payload_value};
 pub use file_lock::{FileLock, LockGuard as FileLockGuard, LockedFile};
 pub use git_ops::{
-    AutoCommitResult, GitOpsError, auto_commit_changes, clean_stashes, get_commit_summary,
-    get_current_branch, get_head_sha, get_recent_files, has_uncommitted_changes,
-    is_working_tree_clean, prune_remote_refs,
+    AutoCommitResult, GitOpsError, IntegrationOutcome, MergePreview, auto_commit_changes,
+    clean_stashes, get_commit_summary, get_current_branch, get_head_sha, get_recent_files,
+    has_uncommitted_changes, is_working_tree_clean, merged_file_content, preview_merge,
+    prune_remote_refs, rebase_and_fast_forward, squash_merge, uncommitted_files,
 };
 pub use handoff::{HandoffError, HandoffResult, HandoffWriter};
 pub use hat_registry::HatRegistry;
-pub use hatless_ralph::{HatInfo, HatTopology, HatlessRalph};
-pub use instructions::InstructionBuilder;
+pub use hatless_ralph::{HatInfo, HatTopology, HatlessRalph, continuation_prompt};
+pub use instructions::{INSTRUCTION_VARIABLES, InstructionBuilder, IterationContext};
 pub use landing::{LandingConfig, LandingError, LandingHandler, LandingResult};
 pub use loop_completion::{CompletionAction, CompletionError, LoopCompletionHandler};
 pub use loop_context::LoopContext;
 pub use loop_history::{HistoryError, HistoryEvent, HistoryEventType, HistorySummary, LoopHistory};
 pub use loop_lock::{LockError, LockGuard, LockMetadata, LoopLock};
-pub use loop_name::{LoopNameGenerator, LoopNamingConfig};
+pub use loop_name::{LOOP_NAME_SEED_ENV, LoopNameGenerator, LoopNamingConfig, sanitize_for_git};
 pub use loop_registry::{LoopEntry, LoopRegistry, RegistryError};
-pub use memory::{Memory, MemoryType};
+pub use loop_status::{
+    LOOP_STATUS_FILE, LoopStatus, LoopStatusWriter, ResourceUsage, STALE_AFTER_MINUTES,
+};
+pub use memory::{Memory, MemoryEviction, MemoryType, TAG_MATCH_WEIGHT, query_terms};
+pub use memory_export::{ExportedMemory, MEMORY_EXPORT_VERSION, MemoryExport, MemoryExportError};
 pub use memory_store::{
-    DEFAULT_MEMORIES_PATH, MarkdownMemoryStore, format_memories_as_markdown, truncate_to_budget,
+    DEFAULT_MEMORIES_PATH, EvictionResult, ImportOptions, ImportResult, MarkdownMemoryStore,
+    evict_to_budget, format_memories_as_markdown, rank_by_relevance, truncate_to_budget,
 };
 pub use merge_queue::{
     MergeButtonState, MergeEntry, MergeEvent, MergeEventType, MergeOption, MergeQueue,
     MergeQueueError, MergeState, SteeringDecision, merge_button_state, merge_execution_summary,
     merge_needs_steering, smart_merge_summary,
 };
+pub use orchestrator::{
+    IterationExecutor, IterationOutcome, IterationRequest, LoopHooks, Orchestrator, RunSummary,
+};
+pub use pause::{PauseControl, PauseState};
 pub use planning_session::{
     ConversationEntry, ConversationType, PlanningSession, PlanningSessionError, SessionMetadata,
     SessionStatus,
@@ -100,25 +148,37 @@ pub use preflight::{
     AcceptanceCriterion, CheckResult, CheckStatus, PreflightCheck, PreflightReport,
     PreflightRunner, extract_acceptance_criteria, extract_all_criteria, extract_criteria_from_file,
 };
+pub use prompt_source::{PromptSourceError, read_prompt_source};
+pub use prompt_template::{PromptTemplateError, render_prompt};
+pub use ralph_ignore::{IgnoreRule, RALPHIGNORE_FILE, RalphIgnore};
+pub use redact::{
+    DEFAULT_REDACT_PATTERNS, REDACTED, RedactingMakeWriter, RedactingWriter, Redactor,
+    install_redactor, installed_redactor, redacted_as,
+};
+pub use robot_fanout::RobotFanout;
+pub use run_history::{RunHistory, RunHistoryError, RunRecord};
+pub use run_metrics::{IterationMetrics, RunMetrics, RunTotals};
+pub use scratchpad_archive::{ArchivedScratchpad, ScratchpadArchive, ScratchpadArchiveError};
 #[cfg(feature = "recording")]
 pub use session_player::{PlayerConfig, ReplayMode, SessionPlayer, TimestampedRecord};
 #[cfg(feature = "recording")]
-pub use session_recorder::{Record, SessionRecorder};
+pub use session_recorder::{RECORD_SCHEMA_VERSION, Record, SessionRecorder};
 pub use skill::{SkillEntry, SkillFrontmatter, SkillSource, parse_frontmatter};
 pub use skill_registry::SkillRegistry;
 pub use summary_writer::SummaryWriter;
-pub use task::{Task, TaskStatus};
+pub use task::{Task, TaskPriority, TaskStatus, parse_priority};
 pub use task_definition::{
     TaskDefinition, TaskDefinitionError, TaskSetup, TaskSuite, Verification,
 };
-pub use task_store::TaskStore;
+pub use task_store::{TaskDependencyError, TaskStore};
 pub use text::{floor_char_boundary, truncate_with_ellipsis};
 pub use workspace::{
     CleanupPolicy, TaskWorkspace, VerificationResult, WorkspaceError, WorkspaceInfo,
     WorkspaceManager,
 };
 pub use worktree::{
-    SyncStats, Worktree, WorktreeConfig, WorktreeError, create_worktree, ensure_gitignore,
-    list_ralph_worktrees, list_worktrees, remove_worktree, sync_working_directory_to_worktree,
+    SyncStats, Worktree, WorktreeConfig, WorktreeError, create_worktree, delete_branch,
+    ensure_gitignore, ensure_worktree_dir_ignored, list_ralph_branches, list_ralph_worktrees,
+    list_worktrees, remove_worktree, rename_worktree, sync_working_directory_to_worktree,
     worktree_exists,
 };
@@ -20,6 +20,7 @@
 //! │   ├── memories.md           # Symlinked in worktrees
 //! │   ├── tasks.jsonl           # Isolated per worktree
 //! │   ├── scratchpad.md         # Isolated per worktree
+//! │   ├── archive/              # Archived scratchpads (isolated per worktree)
 //! │   └── context.md            # Worktree metadata (worktrees only)
 //! ├── specs/                    # Specification files (symlinked in worktrees)
 //! ├── tasks/                    # Code task files (symlinked in worktrees)
@@ -188,6 +189,14 @@ impl LoopContext {
         self.agent_dir().join("scratchpad.md")
     }
 
+    /// Path to the scratchpad archive directory.
+    ///
+    /// Previous runs' scratchpads are moved here on fresh starts. Never
+    /// symlinked: each loop keeps its own archive.
+    pub fn scratchpad_archive_dir(&self) -> PathBuf {
+        self.agent_dir().join("archive")
+    }
+
     /// Path to the memories markdown file.
     ///
     /// For primary loops, this is the actual memories file.
@@ -252,6 +261,18 @@ impl LoopContext {
         self.agent_dir().join("summary.md")
     }
 
+    /// Path to the machine-readable run summary.
+    pub fn run_summary_path(&self) -> PathBuf {
+        self.agent_dir().join("run-summary.json")
+    }
+
+    /// Path to the loop state snapshot used by `ralph run --continue`.
+    ///
+    /// Located at `.ralph/agent/loop-state.json`.
+    pub fn loop_state_path(&self) -> PathBuf {
+        self.agent_dir().join("loop-state.json")
+    }
+
     /// Path to the handoff markdown file.
     ///
     /// Generated on loop completion to provide context for the next session.
@@ -544,7 +565,7 @@ to the main repository:
 - `.ralph/specs/` → shared specifications
 - `.ralph/tasks/` → shared code task files
 
-Local state (scratchpad, runtime tasks, events) is isolated to this worktree.
+Local state (scratchpad, scratchpad archive, runtime tasks, events) is isolated to this worktree.
 "#,
             loop_id,
             self.workspace.display(),
@@ -627,6 +648,10 @@ mod tests {
             ctx.scratchpad_path(),
             PathBuf::from("/project/.ralph/agent/scratchpad.md")
         );
+        assert_eq!(
+            ctx.scratchpad_archive_dir(),
+            PathBuf::from("/project/.ralph/agent/archive")
+        );
         assert_eq!(
             ctx.memories_path(),
             PathBuf::from("/project/.ralph/agent/memories.md")
@@ -635,6 +660,10 @@ mod tests {
             ctx.summary_path(),
             PathBuf::from("/project/.ralph/agent/summary.md")
         );
+        assert_eq!(
+            ctx.run_summary_path(),
+            PathBuf::from("/project/.ralph/agent/run-summary.json")
+        );
         assert_eq!(
             ctx.handoff_path(),
             PathBuf::from("/project/.ralph/agent/handoff.md")
@@ -9,6 +9,13 @@
 //! - `refactor-api-calm-falcon`
 
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+
+/// Environment variable that seeds [`LoopNameGenerator::from_config`].
+///
+/// When set to an integer, generated names follow a fixed sequence, which
+/// keeps test assertions and demos reproducible.
+pub const LOOP_NAME_SEED_ENV: &str = "RALPH_LOOP_NAME_SEED";
 
 /// Configuration for loop naming.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,17 +49,43 @@ impl Default for LoopNamingConfig {
 /// Generator for human-readable loop names.
 pub struct LoopNameGenerator {
     config: LoopNamingConfig,
+    /// State of the seeded sequence; `None` draws suffixes from the clock.
+    seed_state: Option<Cell<u64>>,
 }
 
 impl LoopNameGenerator {
     /// Create a new generator with the given configuration.
     pub fn new(config: LoopNamingConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            seed_state: None,
+        }
     }
 
     /// Create a generator from config, using defaults if not configured.
+    ///
+    /// Seeded from `RALPH_LOOP_NAME_SEED` when it is set to an integer.
     pub fn from_config(config: &LoopNamingConfig) -> Self {
-        Self::new(config.clone())
+        match std::env::var(LOOP_NAME_SEED_ENV) {
+            Ok(value) => match value.trim().parse::<u64>() {
+                Ok(seed) => Self::from_config_with_seed(config, seed),
+                Err(_) => {
+                    tracing::warn!(value = %value, "Ignoring non-integer {}", LOOP_NAME_SEED_ENV);
+                    Self::new(config.clone())
+                }
+            },
+            Err(_) => Self::new(config.clone()),
+        }
+    }
+
+    /// Create a generator whose suffixes follow a fixed sequence for `seed`.
+    ///
+    /// Collision checks still apply: a taken name advances the sequence.
+    pub fn from_config_with_seed(config: &LoopNamingConfig, seed: u64) -> Self {
+        Self {
+            config: config.clone(),
+            seed_state: Some(Cell::new(seed)),
+        }
     }
 
     /// Generate a name from a prompt.
@@ -126,8 +159,10 @@ impl LoopNameGenerator {
             if !exists(&name) {
                 return name;
             }
-            // Small delay to get different nanosecond value
-            std::thread::sleep(std::time::Duration::from_micros(1));
+            if self.seed_state.is_none() {
+                // Small delay to get different nanosecond value
+                std::thread::sleep(std::time::Duration::from_micros(1));
+            }
         }
 
         // Fallback to timestamp format (very unlikely with 50*50 = 2500 combinations)
@@ -176,11 +211,14 @@ impl LoopNameGenerator {
     fn generate_suffix(&self) -> String {
         use std::time::SystemTime;
 
-        // Use nanoseconds for randomness
-        let nanos = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|d| d.as_nanos())
-            .unwrap_or(0);
+        let nanos = match &self.seed_state {
+            Some(state) => u128::from(next_seeded(state)),
+            // Use nanoseconds for randomness
+            None => SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0),
+        };
 
         let adj_idx = (nanos % ADJECTIVES.len() as u128) as usize;
         let noun_idx = ((nanos / 1000) % NOUNS.len() as u128) as usize;
@@ -219,6 +257,16 @@ impl LoopNameGenerator {
     }
 }
 
+/// Advances a seeded sequence (SplitMix64) and returns its next value.
+fn next_seeded(state: &Cell<u64>) -> u64 {
+    let next = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state.set(next);
+    let mut z = next;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 /// Generate a timestamp-based ID (legacy format).
 fn generate_timestamp_id() -> String {
     use std::time::SystemTime;
@@ -508,4 +556,28 @@ mod tests {
         // Should fall back to timestamp format
         assert!(name.starts_with("ralph-"));
     }
+
+    #[test]
+    fn test_same_seed_yields_same_first_name() {
+        let config = LoopNamingConfig::default();
+        let first = LoopNameGenerator::from_config_with_seed(&config, 42);
+        let second = LoopNameGenerator::from_config_with_seed(&config, 42);
+
+        let name = first.generate_memorable_unique(|_| false);
+
+        assert_eq!(name, second.generate_memorable_unique(|_| false));
+        assert_eq!(name.split('-').count(), 2, "{name}");
+    }
+
+    #[test]
+    fn test_seeded_generator_still_avoids_collisions() {
+        let config = LoopNamingConfig::default();
+        let taken = LoopNameGenerator::from_config_with_seed(&config, 7).generate_memorable();
+
+        let name = LoopNameGenerator::from_config_with_seed(&config, 7)
+            .generate_memorable_unique(|n| n == taken);
+
+        assert_ne!(name, taken);
+        assert!(!name.starts_with("ralph-"), "{name}");
+    }
 }
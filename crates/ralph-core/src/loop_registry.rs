@@ -233,10 +233,24 @@ impl LoopRegistry {
         Ok(result)
     }
 
+    /// Returns entries whose process is no longer running, without removing them.
+    pub fn stale_entries(&self) -> Result<Vec<LoopEntry>, RegistryError> {
+        let mut result = Vec::new();
+        self.with_lock_keep_stale(|data| {
+            result = data
+                .loops
+                .iter()
+                .filter(|e| !e.is_alive())
+                .cloned()
+                .collect();
+        })?;
+        Ok(result)
+    }
+
     /// Cleans stale entries (dead PIDs) and returns the number removed.
     pub fn clean_stale(&self) -> Result<usize, RegistryError> {
         let mut removed = 0;
-        self.with_lock(|data| {
+        self.with_lock_keep_stale(|data| {
             let original_len = data.loops.len();
             data.loops.retain(|e| e.is_alive());
             removed = original_len - data.loops.len();
@@ -259,9 +273,22 @@ impl LoopRegistry {
         Ok(found)
     }
 
-    /// Executes an operation with the registry file locked.
-    #[cfg(unix)]
+    /// Executes an operation with the registry file locked, after dropping
+    /// stale entries.
     fn with_lock<F>(&self, f: F) -> Result<(), RegistryError>
+    where
+        F: FnOnce(&mut RegistryData),
+    {
+        self.with_lock_keep_stale(|data| {
+            data.loops.retain(|e| e.is_alive());
+            f(data);
+        })
+    }
+
+    /// Executes an operation with the registry file locked, leaving stale
+    /// entries for `f` to inspect.
+    #[cfg(unix)]
+    fn with_lock_keep_stale<F>(&self, f: F) -> Result<(), RegistryError>
     where
         F: FnOnce(&mut RegistryData),
     {
@@ -291,9 +318,6 @@ impl LoopRegistry {
         // Read existing data using the locked file
         let mut data = self.read_data_from_file(&flock)?;
 
-        // Clean stale entries before any operation
-        data.loops.retain(|e| e.is_alive());
-
         // Execute the user function
         f(&mut data);
 
@@ -304,7 +328,7 @@ impl LoopRegistry {
     }
 
     #[cfg(not(unix))]
-    fn with_lock<F>(&self, _f: F) -> Result<(), RegistryError>
+    fn with_lock_keep_stale<F>(&self, _f: F) -> Result<(), RegistryError>
     where
         F: FnOnce(&mut RegistryData),
     {
@@ -647,4 +671,31 @@ mod tests {
         let found = registry.deregister_current_process().unwrap();
         assert!(!found);
     }
+
+    #[test]
+    fn test_clean_stale_counts_dead_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = LoopRegistry::new(temp_dir.path());
+
+        let alive = LoopEntry::new("alive", None::<String>);
+        let mut dead = LoopEntry::with_id("loop-dead", "dead", None::<String>, "/tmp");
+        dead.pid = 999_999_999;
+        let data = RegistryData {
+            loops: vec![alive.clone(), dead],
+        };
+        fs::create_dir_all(temp_dir.path().join(".ralph")).unwrap();
+        fs::write(
+            temp_dir.path().join(LoopRegistry::REGISTRY_FILE),
+            serde_json::to_string(&data).unwrap(),
+        )
+        .unwrap();
+
+        let stale = registry.stale_entries().unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].id, "loop-dead");
+
+        assert_eq!(registry.clean_stale().unwrap(), 1);
+        assert_eq!(registry.clean_stale().unwrap(), 0);
+        assert_eq!(registry.list().unwrap(), vec![alive]);
+    }
 }
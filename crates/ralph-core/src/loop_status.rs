@@ -0,0 +1,432 @@
+//! Per-loop status snapshots for `ralph loops list`.
+//!
+//! Each loop keeps a small sidecar file, `.ralph/loop-status.json` in its
+//! workspace, rewritten at iteration boundaries and every
+//! [`REFRESH_INTERVAL`] in between with the current iteration, the time of
+//! the last event it published, and the CPU time and memory of the running
+//! backend. The file is replaced atomically (write to a temp file, then
+//! rename), so readers such as `ralph loops list` or the web dashboard never
+//! see a torn write.
+
+use chrono::{DateTime, Duration, Utc};
+use ralph_proto::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Status file path, relative to the loop's workspace.
+pub const LOOP_STATUS_FILE: &str = ".ralph/loop-status.json";
+
+/// A status not rewritten for this long is flagged as stale.
+pub const STALE_AFTER_MINUTES: i64 = 30;
+
+/// How often a running loop rewrites its status between iteration boundaries.
+pub const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Snapshot of a running loop.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoopStatus {
+    /// Process ID of the loop.
+    pub pid: u32,
+
+    /// Current iteration (0 before the first one starts).
+    pub iteration: u32,
+
+    /// Hat worn in the current iteration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hat: Option<String>,
+
+    /// When this snapshot was written.
+    pub updated: DateTime<Utc>,
+
+    /// When the loop last published an event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event: Option<DateTime<Utc>>,
+
+    /// CPU time of the running backend process tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_ms: Option<u64>,
+
+    /// Resident memory of the running backend process tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_bytes: Option<u64>,
+}
+
+impl LoopStatus {
+    /// Status file path for the loop running in `workspace`.
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(LOOP_STATUS_FILE)
+    }
+
+    /// Reads the status of the loop running in `workspace`, if it wrote one.
+    pub fn read(workspace: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(workspace)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Writes the status atomically.
+    pub fn write(&self, workspace: &Path) -> io::Result<()> {
+        let path = Self::path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self).map_err(io::Error::other)?;
+
+        let tmp_path = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp_path);
+        })
+    }
+
+    /// When the loop last published an event, or the snapshot time if it
+    /// has published none yet.
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        self.last_event.unwrap_or(self.updated)
+    }
+
+    /// Returns true if the snapshot is older than [`STALE_AFTER_MINUTES`].
+    ///
+    /// A running loop refreshes its status every [`REFRESH_INTERVAL`], so a
+    /// stale status means the loop stopped updating it: check on this loop.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(self.updated) > Duration::minutes(STALE_AFTER_MINUTES)
+    }
+}
+
+/// Keeps the current loop's status file up to date.
+///
+/// Clones share state, so the copy driving [`Self::spawn_refresh`] writes the
+/// same iteration and hat as the loop's own copy.
+#[derive(Clone)]
+pub struct LoopStatusWriter {
+    workspace: PathBuf,
+    state: Arc<Mutex<WriterState>>,
+}
+
+#[derive(Default)]
+struct WriterState {
+    iteration: u32,
+    hat: Option<String>,
+    last_event: Option<DateTime<Utc>>,
+}
+
+impl LoopStatusWriter {
+    /// Creates a writer for the loop running in `workspace`.
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+            state: Arc::new(Mutex::new(WriterState::default())),
+        }
+    }
+
+    /// Returns a bus observer that records when each event is published.
+    pub fn observer(&self) -> impl Fn(&Event) + Send + 'static {
+        let state = Arc::clone(&self.state);
+        move |_event| {
+            if let Ok(mut state) = state.lock() {
+                state.last_event = Some(Utc::now());
+            }
+        }
+    }
+
+    /// Records the start of an iteration and writes the status.
+    pub fn start_iteration(&self, iteration: u32, hat: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.iteration = iteration;
+            state.hat = Some(hat.to_string());
+        }
+        self.write();
+    }
+
+    /// Writes a fresh snapshot. Failures are logged, never surfaced: the
+    /// status file is advisory and must not slow or stop the loop.
+    pub fn write(&self) {
+        let usage = ResourceUsage::sample();
+        // Held across the write so the refresh task and the loop never
+        // share a temp file
+        let Ok(state) = self.state.lock() else {
+            return;
+        };
+        let status = LoopStatus {
+            pid: std::process::id(),
+            iteration: state.iteration,
+            hat: state.hat.clone(),
+            updated: Utc::now(),
+            last_event: state.last_event,
+            cpu_ms: usage.cpu_ms,
+            rss_bytes: usage.rss_bytes,
+        };
+        if let Err(e) = status.write(&self.workspace) {
+            debug!(error = %e, "Failed to write loop status");
+        }
+    }
+
+    /// Rewrites the status every [`REFRESH_INTERVAL`] until the returned
+    /// task is aborted, so resource use stays current during long iterations.
+    pub fn spawn_refresh(&self) -> tokio::task::JoinHandle<()> {
+        let writer = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let writer = writer.clone();
+                // Sampling reads /proc for every process
+                let _ = tokio::task::spawn_blocking(move || writer.write()).await;
+            }
+        })
+    }
+}
+
+/// CPU and memory use of the loop's backend: every live descendant of the
+/// loop process, so tools the backend runs are counted too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// User and system CPU time of the backend processes, including
+    /// children they have already reaped.
+    pub cpu_ms: Option<u64>,
+
+    /// Resident memory of the backend processes.
+    pub rss_bytes: Option<u64>,
+}
+
+impl ResourceUsage {
+    /// Samples the descendants of the current process. Both values are
+    /// `None` when no backend is running.
+    pub fn sample() -> Self {
+        sample_descendants(std::process::id()).unwrap_or_default()
+    }
+}
+
+/// Live usage of one process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcessSample {
+    parent: u32,
+    cpu_ms: u64,
+    rss_bytes: u64,
+}
+
+/// Sums the samples of every descendant of `root`.
+fn sum_descendants(root: u32, processes: &HashMap<u32, ProcessSample>) -> Option<ResourceUsage> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (&pid, process) in processes {
+        children.entry(process.parent).or_default().push(pid);
+    }
+
+    let mut seen = HashSet::new();
+    let mut pending = children.get(&root).cloned().unwrap_or_default();
+    let (mut cpu_ms, mut rss_bytes) = (0, 0);
+    while let Some(pid) = pending.pop() {
+        if !seen.insert(pid) {
+            continue;
+        }
+        if let Some(process) = processes.get(&pid) {
+            cpu_ms += process.cpu_ms;
+            rss_bytes += process.rss_bytes;
+        }
+        pending.extend(children.get(&pid).into_iter().flatten());
+    }
+
+    (!seen.is_empty()).then_some(ResourceUsage {
+        cpu_ms: Some(cpu_ms),
+        rss_bytes: Some(rss_bytes),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn sample_descendants(root: u32) -> Option<ResourceUsage> {
+    use nix::unistd::{SysconfVar, sysconf};
+
+    let ticks_per_sec = sysconf(SysconfVar::CLK_TCK).ok()??;
+    let ticks_per_sec = u64::try_from(ticks_per_sec).ok().filter(|&t| t > 0)?;
+    let mut processes = HashMap::new();
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        // Processes can exit mid-scan; skip whatever is gone
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        let Some((parent, ticks)) = parse_stat(&stat) else {
+            continue;
+        };
+        let rss_bytes = std::fs::read_to_string(entry.path().join("status"))
+            .ok()
+            .and_then(|status| parse_vm_rss(&status))
+            .unwrap_or(0);
+        processes.insert(
+            pid,
+            ProcessSample {
+                parent,
+                cpu_ms: ticks * 1000 / ticks_per_sec,
+                rss_bytes,
+            },
+        );
+    }
+    sum_descendants(root, &processes)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sample_descendants(root: u32) -> Option<ResourceUsage> {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+    let processes = system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| {
+            let sample = ProcessSample {
+                parent: process.parent()?.as_u32(),
+                cpu_ms: process.accumulated_cpu_time(),
+                rss_bytes: process.memory(),
+            };
+            Some((pid.as_u32(), sample))
+        })
+        .collect();
+    sum_descendants(root, &processes)
+}
+
+/// Parses `/proc/<pid>/stat` into the parent PID and the CPU clock ticks of
+/// the process and its reaped children (`utime + stime + cutime + cstime`).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_stat(stat: &str) -> Option<(u32, u64)> {
+    // The command name is parenthesized and may itself contain spaces or ')'
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let parent = fields.get(1)?.parse().ok()?;
+    let ticks = fields
+        .get(11..15)?
+        .iter()
+        .map(|field| field.parse::<u64>().ok())
+        .sum::<Option<u64>>()?;
+    Some((parent, ticks))
+}
+
+/// Parses the `VmRSS:` line of `/proc/<pid>/status` into bytes.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line
+        .trim_start_matches("VmRSS:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_writer_round_trips_through_status_file() {
+        let temp = TempDir::new().unwrap();
+        let writer = LoopStatusWriter::new(temp.path());
+        assert_eq!(LoopStatus::read(temp.path()), None);
+
+        writer.start_iteration(3, "builder");
+        let status = LoopStatus::read(temp.path()).expect("status written");
+        assert_eq!(status.pid, std::process::id());
+        assert_eq!(status.iteration, 3);
+        assert_eq!(status.hat.as_deref(), Some("builder"));
+        assert_eq!(status.last_event, None);
+
+        writer.observer()(&Event::new("build.done", ""));
+        writer.write();
+        let status = LoopStatus::read(temp.path()).unwrap();
+        assert!(status.last_event.is_some());
+        assert_eq!(status.last_activity(), status.last_event.unwrap());
+
+        // No temp files left behind for readers to trip over
+        let entries: Vec<_> = std::fs::read_dir(temp.path().join(".ralph"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec!["loop-status.json"]);
+    }
+
+    #[test]
+    fn test_is_stale_after_threshold() {
+        let now = Utc::now();
+        let mut status = LoopStatus {
+            pid: 1,
+            iteration: 1,
+            hat: None,
+            updated: now - Duration::minutes(5),
+            last_event: None,
+            cpu_ms: None,
+            rss_bytes: None,
+        };
+        assert!(!status.is_stale(now));
+
+        status.updated = now - Duration::minutes(STALE_AFTER_MINUTES + 1);
+        assert!(status.is_stale(now));
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tralph\nVmPeak:\t  20000 kB\nVmRSS:\t   12345 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(12345 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tralph\n"), None);
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (node (worker) x) S 4100 4242 4100 0 -1 4194304 100 0 0 0 150 50 7 3 20 0 1 0 100 1000 200";
+        assert_eq!(parse_stat(stat), Some((4100, 150 + 50 + 7 + 3)));
+        assert_eq!(parse_stat("4242 (node) S"), None);
+    }
+
+    #[test]
+    fn test_sum_descendants_covers_grandchildren_only() {
+        let sample = |parent, cpu_ms, rss_bytes| ProcessSample {
+            parent,
+            cpu_ms,
+            rss_bytes,
+        };
+        let processes = HashMap::from([
+            (10, sample(1, 5, 100)),    // the loop itself
+            (11, sample(10, 20, 1000)), // backend
+            (12, sample(11, 30, 2000)), // tool run by the backend
+            (13, sample(1, 99, 9999)),  // unrelated
+        ]);
+
+        let usage = sum_descendants(10, &processes).unwrap();
+        assert_eq!(usage.cpu_ms, Some(50));
+        assert_eq!(usage.rss_bytes, Some(3000));
+        assert_eq!(sum_descendants(12, &processes), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sample_counts_running_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+
+        let usage = ResourceUsage::sample();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(usage.cpu_ms.is_some());
+        assert!(usage.rss_bytes.is_some_and(|rss| rss > 0));
+    }
+}
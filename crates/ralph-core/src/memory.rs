@@ -8,6 +8,7 @@
 //! that is both human-readable and machine-parseable.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Classification of a memory.
 ///
@@ -103,6 +104,63 @@ impl std::str::FromStr for MemoryType {
     }
 }
 
+/// Strategy for dropping memories when they exceed the injection token budget.
+///
+/// Eviction removes whole memories (never partial blocks) until the rest fit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MemoryEviction {
+    /// Drop the oldest memories first.
+    #[default]
+    Recent,
+    /// Drop lower-priority types first (context, then patterns, then
+    /// decisions, then fixes), oldest first within a type.
+    ByTypePriority,
+    /// Drop the largest memories first, so as many as possible survive.
+    LargestFirst,
+}
+
+impl MemoryEviction {
+    /// Returns memories sorted in eviction order: the first entry is the
+    /// first to be dropped.
+    #[must_use]
+    pub fn eviction_order<'a>(&self, memories: &'a [Memory]) -> Vec<&'a Memory> {
+        let mut order: Vec<&Memory> = memories.iter().collect();
+        // Oldest first is the base order; IDs embed the creation timestamp
+        order.sort_by(|a, b| (&a.created, &a.id).cmp(&(&b.created, &b.id)));
+
+        match self {
+            Self::Recent => {}
+            Self::ByTypePriority => order.sort_by_key(|m| type_priority(m.memory_type)),
+            Self::LargestFirst => {
+                order.sort_by_key(|m| std::cmp::Reverse(m.content.len()));
+            }
+        }
+
+        order
+    }
+}
+
+/// Priority used by [`MemoryEviction::ByTypePriority`]; lower is evicted first.
+fn type_priority(memory_type: MemoryType) -> u8 {
+    match memory_type {
+        MemoryType::Context => 0,
+        MemoryType::Pattern => 1,
+        MemoryType::Decision => 2,
+        MemoryType::Fix => 3,
+    }
+}
+
+impl std::fmt::Display for MemoryEviction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Recent => write!(f, "recent"),
+            Self::ByTypePriority => write!(f, "by-type-priority"),
+            Self::LargestFirst => write!(f, "largest-first"),
+        }
+    }
+}
+
 /// A single memory entry.
 ///
 /// Memories are stored in `.ralph/agent/memories.md` with the following format:
@@ -112,6 +170,8 @@ impl std::str::FromStr for MemoryType {
 /// > Can span multiple lines
 /// <!-- tags: tag1, tag2 | created: 2025-01-20 -->
 /// ```
+///
+/// Imported memories add `| provenance: ...` to the metadata comment.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memory {
     /// Unique identifier (format: `mem-{unix_timestamp}-{4_hex_chars}`)
@@ -128,6 +188,10 @@ pub struct Memory {
 
     /// Creation date (format: YYYY-MM-DD)
     pub created: String,
+
+    /// Where the memory came from (e.g., `imported from mem-1737372000-a1b2`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
 }
 
 impl Memory {
@@ -142,6 +206,7 @@ impl Memory {
             content,
             tags,
             created: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            provenance: None,
         }
     }
 
@@ -181,6 +246,45 @@ impl Memory {
                 .any(|tag| tag.to_lowercase().contains(&query_lower))
     }
 
+    /// Returns the token overlap (Jaccard index) between this memory's content
+    /// and `content`, from 0.0 (disjoint) to 1.0 (same words).
+    ///
+    /// Content is normalized to lowercase alphanumeric words, so punctuation,
+    /// casing, and word order don't affect the score.
+    #[must_use]
+    pub fn similarity(&self, content: &str) -> f64 {
+        let ours = normalized_tokens(&self.content);
+        let theirs = normalized_tokens(content);
+        if ours.is_empty() && theirs.is_empty() {
+            return 1.0;
+        }
+
+        let shared = ours.intersection(&theirs).count();
+        let total = ours.union(&theirs).count();
+        shared as f64 / total as f64
+    }
+
+    /// Scores how relevant this memory is to a tokenized search query.
+    ///
+    /// Each query term scores by how often it occurs in the content, with
+    /// whole-word hits counting double a hit inside a longer word. Tags are
+    /// curated keywords, so a tag hit is worth [`TAG_MATCH_WEIGHT`] body hits.
+    /// Returns 0.0 when no term matches.
+    #[must_use]
+    pub fn relevance(&self, terms: &[String]) -> f64 {
+        let content_tokens: Vec<String> = word_tokens(&self.content).collect();
+        let tag_tokens: Vec<String> = self.tags.iter().flat_map(|t| word_tokens(t)).collect();
+
+        terms
+            .iter()
+            .map(|term| {
+                let body = term_frequency(&content_tokens, term);
+                let tags = term_frequency(&tag_tokens, term);
+                body + tags * TAG_MATCH_WEIGHT
+            })
+            .sum()
+    }
+
     /// Returns true if this memory has any of the specified tags.
     #[must_use]
     pub fn has_any_tag(&self, tags: &[String]) -> bool {
@@ -191,6 +295,50 @@ impl Memory {
     }
 }
 
+/// How many body hits a single tag hit is worth when ranking search results.
+pub const TAG_MATCH_WEIGHT: f64 = 3.0;
+
+/// Splits a search query into distinct lowercase terms, in query order.
+#[must_use]
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for token in word_tokens(query) {
+        if !terms.contains(&token) {
+            terms.push(token);
+        }
+    }
+    terms
+}
+
+/// Splits text into a set of lowercase alphanumeric words.
+fn normalized_tokens(text: &str) -> HashSet<String> {
+    word_tokens(text).collect()
+}
+
+/// Splits text into lowercase alphanumeric words, keeping duplicates.
+fn word_tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Counts occurrences of `term` in `tokens`: 1.0 per whole-word match and
+/// 0.5 per word that merely contains it (so "export" still finds "exports").
+fn term_frequency(tokens: &[String], term: &str) -> f64 {
+    tokens
+        .iter()
+        .map(|token| {
+            if token == term {
+                1.0
+            } else if token.contains(term) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,6 +441,7 @@ mod tests {
             content: "Uses barrel exports for modules".to_string(),
             tags: vec!["imports".to_string(), "structure".to_string()],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
 
         // Match in content
@@ -315,6 +464,7 @@ mod tests {
             content: "Docker fix".to_string(),
             tags: vec!["docker".to_string(), "debugging".to_string()],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
 
         assert!(memory.has_any_tag(&["docker".to_string()]));
@@ -346,6 +496,7 @@ mod tests {
             content: "Chose Postgres".to_string(),
             tags: vec!["database".to_string()],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
 
         let json = serde_json::to_string(&memory).unwrap();
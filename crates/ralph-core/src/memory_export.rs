@@ -0,0 +1,222 @@
+//! Portable JSON format for `ralph tools memory export` and `import`.
+//!
+//! An export is a versioned document:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "memories": [
+//!     {
+//!       "id": "mem-1737372000-a1b2",
+//!       "type": "pattern",
+//!       "content": "Uses barrel exports",
+//!       "tags": ["structure"],
+//!       "created": "2025-01-20",
+//!       "provenance": "imported from mem-1737000000-c3d4"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Parsing also accepts the bare array of memories written by earlier
+//! versions of `memory export`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::{Memory, MemoryType};
+
+/// Current version of the export document.
+pub const MEMORY_EXPORT_VERSION: u32 = 1;
+
+/// Errors from reading an export document.
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryExportError {
+    /// The document is not valid JSON or doesn't match the schema.
+    #[error("Invalid memory export: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    /// The document was written by a newer version of Ralph.
+    #[error("Unsupported memory export version {0} (expected at most {MEMORY_EXPORT_VERSION})")]
+    UnsupportedVersion(u32),
+
+    /// A memory in the document is missing required data.
+    #[error("Invalid memory export: memories[{index}]: {reason}")]
+    InvalidMemory { index: usize, reason: String },
+}
+
+/// A versioned set of exported memories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryExport {
+    /// Document format version.
+    pub version: u32,
+
+    /// The exported memories.
+    pub memories: Vec<ExportedMemory>,
+}
+
+/// A memory as it appears in an export document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedMemory {
+    /// ID in the exporting project.
+    pub id: String,
+
+    /// Classification of the memory.
+    #[serde(rename = "type")]
+    pub memory_type: MemoryType,
+
+    /// The memory content.
+    pub content: String,
+
+    /// Tags for categorization and search.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Creation date (format: YYYY-MM-DD).
+    pub created: String,
+
+    /// Where the memory came from, if it was itself imported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+}
+
+impl From<Memory> for ExportedMemory {
+    fn from(memory: Memory) -> Self {
+        Self {
+            id: memory.id,
+            memory_type: memory.memory_type,
+            content: memory.content,
+            tags: memory.tags,
+            created: memory.created,
+            provenance: memory.provenance,
+        }
+    }
+}
+
+impl From<ExportedMemory> for Memory {
+    fn from(memory: ExportedMemory) -> Self {
+        Self {
+            id: memory.id,
+            memory_type: memory.memory_type,
+            content: memory.content,
+            tags: memory.tags,
+            created: memory.created,
+            provenance: memory.provenance,
+        }
+    }
+}
+
+/// Either document layout accepted on import.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExportLayout {
+    Document(MemoryExport),
+    Legacy(Vec<Memory>),
+}
+
+impl MemoryExport {
+    /// Builds an export document from stored memories.
+    #[must_use]
+    pub fn new(memories: Vec<Memory>) -> Self {
+        Self {
+            version: MEMORY_EXPORT_VERSION,
+            memories: memories.into_iter().map(ExportedMemory::from).collect(),
+        }
+    }
+
+    /// Parses and validates an export document, returning its memories.
+    ///
+    /// Every memory needs an ID, non-empty content, and a `YYYY-MM-DD`
+    /// creation date.
+    pub fn parse(json: &str) -> Result<Vec<Memory>, MemoryExportError> {
+        let memories: Vec<Memory> = match serde_json::from_str(json)? {
+            ExportLayout::Document(export) => {
+                if export.version > MEMORY_EXPORT_VERSION {
+                    return Err(MemoryExportError::UnsupportedVersion(export.version));
+                }
+                export.memories.into_iter().map(Memory::from).collect()
+            }
+            ExportLayout::Legacy(memories) => memories,
+        };
+
+        for (index, memory) in memories.iter().enumerate() {
+            let reason = if memory.id.trim().is_empty() {
+                "id is empty".to_string()
+            } else if memory.content.trim().is_empty() {
+                "content is empty".to_string()
+            } else if chrono::NaiveDate::parse_from_str(&memory.created, "%Y-%m-%d").is_err() {
+                format!("created '{}' is not a YYYY-MM-DD date", memory.created)
+            } else {
+                continue;
+            };
+            return Err(MemoryExportError::InvalidMemory { index, reason });
+        }
+
+        Ok(memories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(content: &str) -> Memory {
+        Memory {
+            id: "mem-1737372000-a1b2".to_string(),
+            memory_type: MemoryType::Decision,
+            content: content.to_string(),
+            tags: vec!["database".to_string()],
+            created: "2025-01-20".to_string(),
+            provenance: Some("imported from mem-1-abcd".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_document_round_trips() {
+        let json =
+            serde_json::to_string(&MemoryExport::new(vec![memory("Chose Postgres")])).unwrap();
+        assert!(json.contains(r#""type":"decision""#), "json: {json}");
+
+        let memories = MemoryExport::parse(&json).unwrap();
+        assert_eq!(
+            ExportedMemory::from(memories[0].clone()),
+            ExportedMemory::from(memory("Chose Postgres"))
+        );
+    }
+
+    #[test]
+    fn test_parse_accepts_legacy_array() {
+        let json = serde_json::to_string(&vec![memory("Chose Postgres")]).unwrap();
+
+        let memories = MemoryExport::parse(&json).unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].memory_type, MemoryType::Decision);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_documents() {
+        let err = MemoryExport::parse(r#"{"version": 2, "memories": []}"#).unwrap_err();
+        assert!(matches!(err, MemoryExportError::UnsupportedVersion(2)));
+
+        let mut undated = memory("Chose Postgres");
+        undated.created = "yesterday".to_string();
+        let json = serde_json::to_string(&MemoryExport::new(vec![memory("ok"), undated])).unwrap();
+        let err = MemoryExport::parse(&json).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid memory export: memories[1]: created 'yesterday' is not a YYYY-MM-DD date"
+        );
+
+        let json = serde_json::to_string(&MemoryExport::new(vec![memory("  ")])).unwrap();
+        assert!(
+            MemoryExport::parse(&json)
+                .unwrap_err()
+                .to_string()
+                .contains("content is empty")
+        );
+
+        assert!(matches!(
+            MemoryExport::parse(r#"{"memories": "nope"}"#),
+            Err(MemoryExportError::Parse(_))
+        ));
+    }
+}
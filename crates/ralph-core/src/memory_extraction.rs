@@ -0,0 +1,230 @@
+//! Automatic memory extraction from completed runs.
+//!
+//! When `features.memory.auto_extract` is enabled, a successfully completed
+//! loop gets one extra backend invocation that reviews the scratchpad and the
+//! final iteration's output and proposes candidate memories. Candidates are
+//! emitted as `<memory>` tags, in the same style as `<event>` tags:
+//!
+//! ```text
+//! <memory type="fix" tags="cargo, build">Run `cargo clean` after switching toolchains</memory>
+//! ```
+//!
+//! Each accepted candidate is stored with a provenance note naming the run.
+
+use crate::event_loop::TerminationReason;
+use crate::memory::{Memory, MemoryType};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Tag added to every auto-extracted memory.
+pub const AUTO_EXTRACTED_TAG: &str = "auto-extracted";
+
+/// Maximum characters of scratchpad or output included in the prompt.
+///
+/// The tail is kept, since the end of a run holds its conclusions.
+const MAX_SECTION_CHARS: usize = 16_000;
+
+/// Matches `<memory type="..." tags="...">content</memory>` (tags optional).
+static MEMORY_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<memory\s+type="([^"]*)"(?:\s+tags="([^"]*)")?\s*>(.*?)</memory>"#).unwrap()
+});
+
+/// A memory proposed by the extraction pass, not yet stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryCandidate {
+    /// Classification of the candidate.
+    pub memory_type: MemoryType,
+
+    /// The proposed memory content.
+    pub content: String,
+
+    /// Tags proposed by the agent.
+    pub tags: Vec<String>,
+}
+
+impl MemoryCandidate {
+    /// Converts the candidate into a memory, noting the run it came from.
+    #[must_use]
+    pub fn into_memory(self, run_id: &str) -> Memory {
+        let content = format!("{}\n(auto-extracted from run {})", self.content, run_id);
+        let mut tags = self.tags;
+        if !tags.iter().any(|t| t == AUTO_EXTRACTED_TAG) {
+            tags.push(AUTO_EXTRACTED_TAG.to_string());
+        }
+        Memory::new(self.memory_type, content, tags)
+    }
+}
+
+/// Returns true if memories should be extracted after a run ending with `reason`.
+///
+/// Only successful runs qualify: stalls, interruptions, and iteration limits
+/// (and every other safeguard) leave knowledge that may be half-formed.
+#[must_use]
+pub fn should_extract(reason: &TerminationReason) -> bool {
+    reason.is_success()
+}
+
+/// Builds the prompt for the extraction invocation.
+#[must_use]
+pub fn build_prompt(scratchpad: &str, final_output: &str, max_candidates: usize) -> String {
+    format!(
+        r#"A Ralph loop just completed successfully. Review its scratchpad and final output below and extract at most {max} memories worth keeping for future sessions.
+
+Good memories are durable, project-specific, and non-obvious:
+- pattern: how this codebase does things
+- decision: why something was chosen
+- fix: the solution to a problem likely to recur
+- context: project-specific knowledge
+
+Skip anything tied only to this task's progress. If nothing is worth keeping, output nothing.
+
+Emit each memory on its own as:
+<memory type="pattern|decision|fix|context" tags="tag1, tag2">One or two sentences.</memory>
+
+Do not modify any files.
+
+## Scratchpad
+
+{scratchpad}
+
+## Final Output
+
+{output}
+"#,
+        max = max_candidates,
+        scratchpad = tail(scratchpad, MAX_SECTION_CHARS),
+        output = tail(final_output, MAX_SECTION_CHARS),
+    )
+}
+
+/// Parses up to `max_candidates` memory candidates from extraction output.
+///
+/// Tags with an unknown type or empty content are skipped.
+#[must_use]
+pub fn parse_candidates(output: &str, max_candidates: usize) -> Vec<MemoryCandidate> {
+    MEMORY_TAG_RE
+        .captures_iter(output)
+        .filter_map(|caps| {
+            let memory_type = caps[1].trim().parse().ok()?;
+            // Blank lines would end the memory's quote block in memories.md
+            let content = caps[3]
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if content.is_empty() {
+                return None;
+            }
+            let tags = caps
+                .get(2)
+                .map(|t| {
+                    t.as_str()
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(MemoryCandidate {
+                memory_type,
+                content,
+                tags,
+            })
+        })
+        .take(max_candidates)
+        .collect()
+}
+
+/// Returns the last `max_chars` bytes of `s`, on a character boundary.
+fn tail(s: &str, max_chars: usize) -> &str {
+    if s.len() <= max_chars {
+        return s;
+    }
+    let mut start = s.len() - max_chars;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    &s[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_extract_only_on_success() {
+        assert!(should_extract(&TerminationReason::CompletionPromise));
+        assert!(!should_extract(&TerminationReason::MaxIterations));
+        assert!(!should_extract(&TerminationReason::Interrupted));
+        assert!(!should_extract(&TerminationReason::LoopThrashing));
+    }
+
+    #[test]
+    fn test_parse_candidates() {
+        let output = r#"Here is what I found:
+<memory type="fix" tags="cargo, build">Run cargo clean after switching toolchains</memory>
+<memory type="decision">Use JSONL for events
+
+because it appends atomically</memory>
+"#;
+
+        let candidates = parse_candidates(output, 5);
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].memory_type, MemoryType::Fix);
+        assert_eq!(
+            candidates[0].content,
+            "Run cargo clean after switching toolchains"
+        );
+        assert_eq!(candidates[0].tags, vec!["cargo", "build"]);
+        assert_eq!(candidates[1].memory_type, MemoryType::Decision);
+        assert_eq!(
+            candidates[1].content,
+            "Use JSONL for events\nbecause it appends atomically"
+        );
+        assert!(candidates[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_candidates_skips_invalid_and_caps_count() {
+        let output = r#"<memory type="opinion">Not a real type</memory>
+<memory type="pattern">  </memory>
+<memory type="pattern">First</memory>
+<memory type="context">Second</memory>
+<memory type="fix">Third</memory>"#;
+
+        let candidates = parse_candidates(output, 2);
+
+        let contents: Vec<&str> = candidates.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn test_into_memory_records_provenance() {
+        let candidate = MemoryCandidate {
+            memory_type: MemoryType::Pattern,
+            content: "Errors use thiserror".to_string(),
+            tags: vec!["errors".to_string()],
+        };
+
+        let memory = candidate.into_memory("primary-20250101-120000");
+
+        assert_eq!(memory.memory_type, MemoryType::Pattern);
+        assert!(memory.content.starts_with("Errors use thiserror"));
+        assert!(memory.content.contains("run primary-20250101-120000"));
+        assert_eq!(memory.tags, vec!["errors", AUTO_EXTRACTED_TAG]);
+    }
+
+    #[test]
+    fn test_build_prompt_keeps_tail_of_long_output() {
+        let output = format!("{}THE END", "x".repeat(MAX_SECTION_CHARS));
+
+        let prompt = build_prompt("scratch", &output, 3);
+
+        assert!(prompt.contains("at most 3 memories"));
+        assert!(prompt.contains("scratch"));
+        assert!(prompt.contains("THE END"));
+        assert!(prompt.len() < output.len() + 2_000);
+    }
+}
@@ -20,12 +20,14 @@ static SECTION_RE: LazyLock<Regex> =
 static MEMORY_ID_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^### (mem-\d+-[0-9a-f]{4})").unwrap());
 
-/// Regex to match blockquote content lines like `> content`
-static CONTENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^> (.+)$").unwrap());
+/// Regex to match blockquote content lines like `> content` (a bare `>` is a blank line)
+static CONTENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^>(?: (.*))?$").unwrap());
 
-/// Regex to match metadata HTML comments like `<!-- tags: a, b | created: 2025-01-20 -->`
+/// Regex to match metadata HTML comments like `<!-- tags: a, b | created: 2025-01-20 -->`,
+/// optionally followed by `| provenance: ...`
 static METADATA_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"<!-- tags: ([^|]*) \| created: (\d{4}-\d{2}-\d{2}) -->").unwrap()
+    Regex::new(r"<!-- tags: ([^|]*) \| created: (\d{4}-\d{2}-\d{2})(?: \| provenance: (.*?))? -->")
+        .unwrap()
 });
 
 /// Parse a memories markdown file into a vector of Memory structs.
@@ -53,6 +55,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
     let mut current_content: Vec<String> = Vec::new();
     let mut current_tags: Vec<String> = Vec::new();
     let mut current_created: Option<String> = None;
+    let mut current_provenance: Option<String> = None;
 
     for line in markdown.lines() {
         if let Some(caps) = SECTION_RE.captures(line) {
@@ -64,6 +67,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 &mut current_content,
                 &mut current_tags,
                 &mut current_created,
+                &mut current_provenance,
             );
             current_type = MemoryType::from_section(&caps[1]).unwrap_or(MemoryType::Pattern);
         } else if let Some(caps) = MEMORY_ID_RE.captures(line) {
@@ -75,10 +79,11 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 &mut current_content,
                 &mut current_tags,
                 &mut current_created,
+                &mut current_provenance,
             );
             current_id = Some(caps[1].to_string());
         } else if let Some(caps) = CONTENT_RE.captures(line) {
-            current_content.push(caps[1].to_string());
+            current_content.push(caps.get(1).map_or("", |m| m.as_str()).to_string());
         } else if let Some(caps) = METADATA_RE.captures(line) {
             current_tags = caps[1]
                 .split(',')
@@ -86,6 +91,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
                 .filter(|s| !s.is_empty())
                 .collect();
             current_created = Some(caps[2].to_string());
+            current_provenance = caps.get(3).map(|m| m.as_str().to_string());
         }
     }
 
@@ -97,6 +103,7 @@ pub fn parse_memories(markdown: &str) -> Vec<Memory> {
         &mut current_content,
         &mut current_tags,
         &mut current_created,
+        &mut current_provenance,
     );
 
     memories
@@ -110,6 +117,7 @@ fn flush_memory(
     current_content: &mut Vec<String>,
     current_tags: &mut Vec<String>,
     current_created: &mut Option<String>,
+    current_provenance: &mut Option<String>,
 ) {
     if let Some(id) = current_id.take()
         && !current_content.is_empty()
@@ -122,9 +130,11 @@ fn flush_memory(
             created: current_created
                 .take()
                 .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string()),
+            provenance: current_provenance.take(),
         });
     }
     current_content.clear();
+    *current_provenance = None;
 }
 
 #[cfg(test)]
@@ -301,4 +311,20 @@ mod tests {
         assert_eq!(memories.len(), 1);
         assert_eq!(memories[0].id, "mem-1737372100-c3d4");
     }
+
+    #[test]
+    fn test_parse_blank_content_lines_and_provenance() {
+        let markdown = "# Memories\n\n## Decisions\n\n### mem-1737372000-a1b2\n\
+> First paragraph\n>\n> Second paragraph\n\
+<!-- tags: docs | created: 2025-01-20 | provenance: imported from mem-1-abcd -->\n";
+
+        let memories = parse_memories(markdown);
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].content, "First paragraph\n\nSecond paragraph");
+        assert_eq!(memories[0].tags, vec!["docs"]);
+        assert_eq!(
+            memories[0].provenance.as_deref(),
+            Some("imported from mem-1-abcd")
+        );
+    }
 }
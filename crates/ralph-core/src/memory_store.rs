@@ -14,14 +14,16 @@
 //! The `MarkdownMemoryStore` is Clone because it doesn't hold the lock;
 //! locks are acquired for each operation.
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io;
 use std::path::{Path, PathBuf};
 
 use crate::text::floor_char_boundary;
 
 use crate::file_lock::FileLock;
-use crate::memory::{Memory, MemoryType};
+use crate::memory::{Memory, MemoryEviction, MemoryType, query_terms};
 use crate::memory_parser::parse_memories;
 
 /// Default path for the memories file relative to the workspace root.
@@ -170,6 +172,120 @@ impl MarkdownMemoryStore {
         Ok(true)
     }
 
+    /// Finds the existing memory most similar to `memory`, if any reaches `threshold`.
+    ///
+    /// Only memories of the same type are compared (see [`Memory::similarity`]).
+    /// Returns the match with its similarity score.
+    pub fn find_similar(
+        &self,
+        memory: &Memory,
+        threshold: f64,
+    ) -> io::Result<Option<(Memory, f64)>> {
+        let memories = self.load()?;
+        Ok(most_similar(&memories, memory, threshold).map(|(m, score)| (m.clone(), score)))
+    }
+
+    /// Imports memories (e.g., from `ralph tools memory export`).
+    ///
+    /// Every imported memory gets a fresh ID so it can't collide with existing
+    /// entries, and records the ID it was exported under in its provenance;
+    /// type, content, tags, and creation date are kept. Memories whose content
+    /// matches an existing (or already imported) memory are skipped, as are
+    /// memories reaching `dedup_threshold` similarity when one is set.
+    /// Uses an exclusive lock so the import is applied as a single write.
+    pub fn import(
+        &self,
+        memories: Vec<Memory>,
+        options: ImportOptions,
+    ) -> io::Result<ImportResult> {
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+
+        let existing = if self.exists() {
+            parse_memories(&fs::read_to_string(&self.path)?)
+        } else {
+            Vec::new()
+        };
+        let mut result = ImportResult::default();
+        let mut taken: HashSet<String> = existing.iter().map(|m| m.id.clone()).collect();
+        let mut all = if options.replace {
+            result.replaced = existing.len();
+            Vec::new()
+        } else {
+            existing
+        };
+        let mut hashes: HashMap<u64, String> = all
+            .iter()
+            .map(|m| (content_hash(&m.content), m.id.clone()))
+            .collect();
+
+        for mut memory in memories {
+            let hash = content_hash(&memory.content);
+            if let Some(id) = hashes.get(&hash) {
+                result.skipped.push((memory, id.clone()));
+                continue;
+            }
+            if let Some(threshold) = options.dedup_threshold
+                && let Some((existing, _)) = most_similar(&all, &memory, threshold)
+            {
+                result.skipped.push((memory, existing.id.clone()));
+                continue;
+            }
+
+            memory
+                .provenance
+                .get_or_insert_with(|| format!("imported from {}", memory.id));
+            memory.id = Memory::generate_id();
+            while taken.contains(&memory.id) {
+                memory.id = Memory::generate_id();
+            }
+            taken.insert(memory.id.clone());
+            hashes.insert(hash, memory.id.clone());
+
+            all.push(memory.clone());
+            result.imported.push(memory);
+        }
+
+        if !options.dry_run && (!result.imported.is_empty() || result.replaced > 0) {
+            self.write_all_internal(&all)?;
+        }
+        Ok(result)
+    }
+
+    /// Merges `memory` into the existing memory with ID `id`.
+    ///
+    /// The merged memory keeps the existing ID and creation date, takes the
+    /// longer of the two contents, and the union of both tag sets.
+    /// Returns the merged memory, or `None` if `id` was not found.
+    /// Uses an exclusive lock to prevent concurrent writes.
+    pub fn merge(&self, id: &str, memory: &Memory) -> io::Result<Option<Memory>> {
+        if !self.exists() {
+            return Ok(None);
+        }
+
+        let lock = FileLock::new(&self.path)?;
+        let _guard = lock.exclusive()?;
+
+        let content = fs::read_to_string(&self.path)?;
+        let mut memories = parse_memories(&content);
+
+        let Some(existing) = memories.iter_mut().find(|m| m.id == id) else {
+            return Ok(None);
+        };
+        if memory.content.len() > existing.content.len() {
+            existing.content.clone_from(&memory.content);
+        }
+        for tag in &memory.tags {
+            if !existing.tags.contains(tag) {
+                existing.tags.push(tag.clone());
+            }
+        }
+        let merged = existing.clone();
+
+        self.write_all_internal(&memories)?;
+        Ok(Some(merged))
+    }
+
     /// Returns the memory with the given ID, if it exists.
     pub fn get(&self, id: &str) -> io::Result<Option<Memory>> {
         let memories = self.load()?;
@@ -243,12 +359,24 @@ impl MarkdownMemoryStore {
             .map(|line| format!("> {}", line))
             .collect();
 
+        let provenance = memory
+            .provenance
+            .as_deref()
+            .map(|p| {
+                format!(
+                    " | provenance: {}",
+                    p.replace("-->", "->").replace('\n', " ")
+                )
+            })
+            .unwrap_or_default();
+
         format!(
-            "\n### {}\n{}\n<!-- tags: {} | created: {} -->\n",
+            "\n### {}\n{}\n<!-- tags: {} | created: {}{} -->\n",
             memory.id,
             content_lines.join("\n"),
             memory.tags.join(", "),
             memory.created,
+            provenance,
         )
     }
 
@@ -271,6 +399,55 @@ impl MarkdownMemoryStore {
     }
 }
 
+/// How [`MarkdownMemoryStore::import`] applies incoming memories.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Also skip memories at least this similar to a stored one.
+    pub dedup_threshold: Option<f64>,
+
+    /// Replace all stored memories instead of adding to them.
+    pub replace: bool,
+
+    /// Work out the result without writing anything.
+    pub dry_run: bool,
+}
+
+/// Outcome of [`MarkdownMemoryStore::import`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportResult {
+    /// Memories that were stored, with their newly assigned IDs.
+    pub imported: Vec<Memory>,
+
+    /// Memories skipped as duplicates, with the ID of the memory they matched.
+    pub skipped: Vec<(Memory, String)>,
+
+    /// Stored memories removed because the import replaced them.
+    pub replaced: usize,
+}
+
+/// Hashes memory content for exact-duplicate detection, ignoring surrounding
+/// whitespace.
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.trim().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the memory in `memories` most similar to `memory` (same type only),
+/// if any reaches `threshold`.
+fn most_similar<'a>(
+    memories: &'a [Memory],
+    memory: &Memory,
+    threshold: f64,
+) -> Option<(&'a Memory, f64)> {
+    memories
+        .iter()
+        .filter(|m| m.memory_type == memory.memory_type && m.id != memory.id)
+        .map(|m| (m, m.similarity(&memory.content)))
+        .filter(|(_, score)| *score >= threshold)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
 /// Formats memories as markdown for context injection.
 ///
 /// This produces a markdown document suitable for including in agent prompts:
@@ -367,6 +544,87 @@ pub fn truncate_to_budget(content: &str, budget: usize) -> String {
     }
 }
 
+/// Memories split by [`evict_to_budget`] into those that fit and those dropped.
+#[derive(Debug, Clone, Default)]
+pub struct EvictionResult {
+    /// Memories that fit within the budget, in their original order.
+    pub kept: Vec<Memory>,
+
+    /// Memories dropped to fit the budget, in the order they were evicted.
+    pub evicted: Vec<Memory>,
+}
+
+/// Drops whole memories until their markdown fits within a token budget.
+///
+/// Uses the same ~4 characters per token heuristic as [`truncate_to_budget`].
+/// Memories are evicted one at a time in the order given by `strategy`.
+///
+/// # Arguments
+/// * `memories` - The memories to fit
+/// * `budget` - Maximum tokens (0 = unlimited)
+/// * `strategy` - Which memories to drop first
+#[must_use]
+pub fn evict_to_budget(
+    memories: Vec<Memory>,
+    budget: usize,
+    strategy: MemoryEviction,
+) -> EvictionResult {
+    let char_budget = budget * 4;
+    if budget == 0 || format_memories_as_markdown(&memories).len() <= char_budget {
+        return EvictionResult {
+            kept: memories,
+            evicted: Vec::new(),
+        };
+    }
+
+    let order: Vec<String> = strategy
+        .eviction_order(&memories)
+        .into_iter()
+        .map(|m| m.id.clone())
+        .collect();
+
+    let mut kept = memories;
+    let mut evicted = Vec::new();
+    for id in order {
+        if format_memories_as_markdown(&kept).len() <= char_budget {
+            break;
+        }
+        if let Some(pos) = kept.iter().position(|m| m.id == id) {
+            evicted.push(kept.remove(pos));
+        }
+    }
+
+    EvictionResult { kept, evicted }
+}
+
+/// Ranks memories by relevance to a free-text query, best match first.
+///
+/// Memories matching no query term are dropped. Ties keep the newer memory
+/// first. A query with no searchable words falls back to substring matching.
+#[must_use]
+pub fn rank_by_relevance(memories: Vec<Memory>, query: &str) -> Vec<(Memory, f64)> {
+    let terms = query_terms(query);
+    let mut ranked: Vec<(Memory, f64)> = memories
+        .into_iter()
+        .map(|m| {
+            let score = if terms.is_empty() {
+                if m.matches_query(query) { 1.0 } else { 0.0 }
+            } else {
+                m.relevance(&terms)
+            };
+            (m, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+
+    ranked.sort_by(|(a, a_score), (b, b_score)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.created.cmp(&a.created))
+    });
+    ranked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -691,6 +949,7 @@ mod tests {
             content: "Use barrel exports".to_string(),
             tags: vec!["imports".to_string()],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
 
         let output = format_memories_as_markdown(&[memory]);
@@ -710,6 +969,7 @@ mod tests {
             content: "A pattern".to_string(),
             tags: vec![],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
         let decision = Memory {
             id: "mem-2-d".to_string(),
@@ -717,6 +977,7 @@ mod tests {
             content: "A decision".to_string(),
             tags: vec![],
             created: "2025-01-20".to_string(),
+            provenance: None,
         };
 
         let output = format_memories_as_markdown(&[pattern, decision]);
@@ -753,4 +1014,379 @@ mod tests {
         assert!(result.len() < content.len());
         assert!(result.contains("<!-- truncated:"));
     }
+
+    fn budget_memories() -> Vec<Memory> {
+        let memory = |id: &str, memory_type, content: &str, created: &str| Memory {
+            id: id.to_string(),
+            memory_type,
+            content: content.to_string(),
+            tags: vec![],
+            created: created.to_string(),
+            provenance: None,
+        };
+        vec![
+            memory(
+                "mem-1-a",
+                MemoryType::Pattern,
+                "Oldest pattern",
+                "2025-01-01",
+            ),
+            memory(
+                "mem-2-b",
+                MemoryType::Fix,
+                &"A long fix description ".repeat(8),
+                "2025-01-02",
+            ),
+            memory(
+                "mem-3-c",
+                MemoryType::Context,
+                "Newest context",
+                "2025-01-03",
+            ),
+        ]
+    }
+
+    /// Smallest budget (in tokens) that fits `memories` exactly.
+    fn budget_for(memories: &[Memory]) -> usize {
+        format_memories_as_markdown(memories).len().div_ceil(4)
+    }
+
+    fn ids(memories: &[Memory]) -> Vec<&str> {
+        memories.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_evict_to_budget_within_budget_keeps_all() {
+        let memories = budget_memories();
+        let budget = budget_for(&memories);
+
+        let result = evict_to_budget(memories, budget, MemoryEviction::Recent);
+        assert_eq!(result.kept.len(), 3);
+        assert!(result.evicted.is_empty());
+
+        let result = evict_to_budget(budget_memories(), 0, MemoryEviction::Recent);
+        assert_eq!(result.kept.len(), 3);
+    }
+
+    #[test]
+    fn test_evict_to_budget_recent_drops_oldest() {
+        let memories = budget_memories();
+        let budget = budget_for(&memories[1..]);
+
+        let result = evict_to_budget(memories, budget, MemoryEviction::Recent);
+        assert_eq!(ids(&result.kept), vec!["mem-2-b", "mem-3-c"]);
+        assert_eq!(ids(&result.evicted), vec!["mem-1-a"]);
+    }
+
+    #[test]
+    fn test_evict_to_budget_by_type_priority_drops_context_first() {
+        let memories = budget_memories();
+        let budget = budget_for(&memories[..2]);
+
+        let result = evict_to_budget(memories, budget, MemoryEviction::ByTypePriority);
+        assert_eq!(ids(&result.kept), vec!["mem-1-a", "mem-2-b"]);
+        assert_eq!(ids(&result.evicted), vec!["mem-3-c"]);
+    }
+
+    #[test]
+    fn test_evict_to_budget_largest_first_drops_largest() {
+        let memories = budget_memories();
+        let budget = budget_for(&[memories[0].clone(), memories[2].clone()]);
+
+        let result = evict_to_budget(memories, budget, MemoryEviction::LargestFirst);
+        assert_eq!(ids(&result.kept), vec!["mem-1-a", "mem-3-c"]);
+        assert_eq!(ids(&result.evicted), vec!["mem-2-b"]);
+    }
+
+    #[test]
+    fn test_evict_to_budget_continues_until_fit() {
+        let memories = budget_memories();
+        let budget = budget_for(&memories[2..]);
+
+        let result = evict_to_budget(memories, budget, MemoryEviction::Recent);
+        assert_eq!(ids(&result.kept), vec!["mem-3-c"]);
+        assert_eq!(ids(&result.evicted), vec!["mem-1-a", "mem-2-b"]);
+    }
+
+    #[test]
+    fn test_rank_by_relevance_prefers_tag_matches() {
+        let memory = |id: &str, content: &str, tags: &[&str], created: &str| Memory {
+            id: id.to_string(),
+            memory_type: MemoryType::Fix,
+            content: content.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            created: created.to_string(),
+            provenance: None,
+        };
+        let memories = vec![
+            memory(
+                "mem-1-a",
+                "Restart docker when the daemon hangs",
+                &[],
+                "2025-01-03",
+            ),
+            memory(
+                "mem-2-b",
+                "Clear the build cache before retrying",
+                &["docker"],
+                "2025-01-01",
+            ),
+            memory("mem-3-c", "Unrelated note", &["git"], "2025-01-02"),
+        ];
+
+        let ranked = rank_by_relevance(memories, "Docker");
+
+        let ranked_ids: Vec<&str> = ranked.iter().map(|(m, _)| m.id.as_str()).collect();
+        assert_eq!(ranked_ids, vec!["mem-2-b", "mem-1-a"]);
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+
+    #[test]
+    fn test_rank_by_relevance_scores_term_frequency() {
+        let memory = |id: &str, content: &str, created: &str| Memory {
+            id: id.to_string(),
+            memory_type: MemoryType::Pattern,
+            content: content.to_string(),
+            tags: vec![],
+            created: created.to_string(),
+            provenance: None,
+        };
+        let memories = vec![
+            memory("mem-1-a", "Barrel exports in src", "2025-01-03"),
+            memory(
+                "mem-2-b",
+                "Barrel files re-export modules; keep barrel files thin",
+                "2025-01-01",
+            ),
+            memory("mem-3-c", "Uses barrels everywhere", "2025-01-02"),
+        ];
+
+        let ranked = rank_by_relevance(memories, "barrel");
+
+        let ranked_ids: Vec<&str> = ranked.iter().map(|(m, _)| m.id.as_str()).collect();
+        // Two whole-word hits beat one; a partial-word hit ranks last
+        assert_eq!(ranked_ids, vec!["mem-2-b", "mem-1-a", "mem-3-c"]);
+    }
+
+    #[test]
+    fn test_find_similar_exact_duplicate() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(
+            MemoryType::Pattern,
+            "Use barrel exports for every module".to_string(),
+            vec![],
+        );
+        store.append(&existing).unwrap();
+
+        let candidate = Memory::new(
+            MemoryType::Pattern,
+            "Use barrel exports for every module".to_string(),
+            vec![],
+        );
+        let (found, score) = store.find_similar(&candidate, 0.8).unwrap().unwrap();
+
+        assert_eq!(found.id, existing.id);
+        assert!((score - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_find_similar_near_duplicate() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(
+            MemoryType::Fix,
+            "Use barrel exports for every module in src".to_string(),
+            vec![],
+        );
+        store.append(&existing).unwrap();
+
+        // Reworded: casing, punctuation, and one extra word
+        let candidate = Memory::new(
+            MemoryType::Fix,
+            "use barrel exports, for every module in the src!".to_string(),
+            vec![],
+        );
+        let (found, score) = store.find_similar(&candidate, 0.8).unwrap().unwrap();
+
+        assert_eq!(found.id, existing.id);
+        assert!((0.8..1.0).contains(&score));
+
+        // Same content under a different type is not a duplicate
+        let other_type = Memory::new(MemoryType::Pattern, candidate.content.clone(), vec![]);
+        assert!(store.find_similar(&other_type, 0.8).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_similar_distinct_memory() {
+        let (_temp, store) = create_temp_store();
+        store
+            .append(&Memory::new(
+                MemoryType::Pattern,
+                "Use barrel exports for every module".to_string(),
+                vec![],
+            ))
+            .unwrap();
+
+        let candidate = Memory::new(
+            MemoryType::Pattern,
+            "Integration tests live under tests/ with one file per command".to_string(),
+            vec![],
+        );
+        assert!(store.find_similar(&candidate, 0.8).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_keeps_id_and_unions_tags() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(
+            MemoryType::Decision,
+            "Chose Postgres".to_string(),
+            vec!["database".to_string()],
+        );
+        store.append(&existing).unwrap();
+
+        let update = Memory::new(
+            MemoryType::Decision,
+            "Chose Postgres for JSONB support".to_string(),
+            vec!["database".to_string(), "postgres".to_string()],
+        );
+        let merged = store.merge(&existing.id, &update).unwrap().unwrap();
+
+        assert_eq!(merged.id, existing.id);
+        assert_eq!(merged.content, "Chose Postgres for JSONB support");
+        assert_eq!(merged.tags, vec!["database", "postgres"]);
+
+        let memories = store.load().unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].content, "Chose Postgres for JSONB support");
+        assert!(store.merge("mem-0-0000", &update).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_import_regenerates_ids_and_keeps_fields() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(MemoryType::Pattern, "Existing".to_string(), vec![]);
+        store.append(&existing).unwrap();
+
+        // Exported from another project, reusing the same ID
+        let incoming = Memory {
+            id: existing.id.clone(),
+            memory_type: MemoryType::Decision,
+            content: "Chose Postgres".to_string(),
+            tags: vec!["database".to_string()],
+            created: "2024-06-01".to_string(),
+            provenance: None,
+        };
+        let result = store
+            .import(vec![incoming], ImportOptions::default())
+            .unwrap();
+
+        assert_eq!(result.imported.len(), 1);
+        let imported = &result.imported[0];
+        assert_ne!(imported.id, existing.id);
+
+        let loaded = store.get(&imported.id).unwrap().unwrap();
+        assert_eq!(loaded.memory_type, MemoryType::Decision);
+        assert_eq!(loaded.content, "Chose Postgres");
+        assert_eq!(loaded.tags, vec!["database"]);
+        assert_eq!(loaded.created, "2024-06-01");
+        assert_eq!(
+            loaded.provenance,
+            Some(format!("imported from {}", existing.id))
+        );
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_skips_exact_duplicates_by_content() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(MemoryType::Fix, "Pin the toolchain".to_string(), vec![]);
+        store.append(&existing).unwrap();
+
+        let incoming = vec![
+            Memory::new(
+                MemoryType::Context,
+                " Pin the toolchain\n".to_string(),
+                vec![],
+            ),
+            Memory::new(MemoryType::Fix, "Use sccache".to_string(), vec![]),
+            Memory::new(MemoryType::Fix, "Use sccache".to_string(), vec![]),
+        ];
+        let result = store.import(incoming, ImportOptions::default()).unwrap();
+
+        assert_eq!(result.imported.len(), 1);
+        assert_eq!(result.skipped[0].1, existing.id);
+        assert_eq!(result.skipped[1].1, result.imported[0].id);
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_replace_and_dry_run() {
+        let (_temp, store) = create_temp_store();
+        store
+            .append(&Memory::new(MemoryType::Fix, "Old".to_string(), vec![]))
+            .unwrap();
+        let incoming = || vec![Memory::new(MemoryType::Pattern, "New".to_string(), vec![])];
+
+        let dry_run = ImportOptions {
+            replace: true,
+            dry_run: true,
+            ..ImportOptions::default()
+        };
+        let result = store.import(incoming(), dry_run).unwrap();
+        assert_eq!((result.imported.len(), result.replaced), (1, 1));
+        assert_eq!(store.load().unwrap()[0].content, "Old");
+
+        let replace = ImportOptions {
+            replace: true,
+            ..ImportOptions::default()
+        };
+        store.import(incoming(), replace).unwrap();
+        let memories = store.load().unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].content, "New");
+    }
+
+    #[test]
+    fn test_import_dedup_skips_similar() {
+        let (_temp, store) = create_temp_store();
+        let existing = Memory::new(
+            MemoryType::Fix,
+            "Run cargo clean after switching toolchains".to_string(),
+            vec![],
+        );
+        store.append(&existing).unwrap();
+
+        let incoming = vec![
+            Memory::new(
+                MemoryType::Fix,
+                "run cargo clean after switching toolchains".to_string(),
+                vec![],
+            ),
+            Memory::new(
+                MemoryType::Fix,
+                "Pin the nightly toolchain".to_string(),
+                vec![],
+            ),
+            Memory::new(
+                MemoryType::Fix,
+                "Pin the nightly toolchain!".to_string(),
+                vec![],
+            ),
+        ];
+        let result = store
+            .import(
+                incoming,
+                ImportOptions {
+                    dedup_threshold: Some(0.8),
+                    ..ImportOptions::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(result.imported.len(), 1);
+        assert_eq!(result.skipped.len(), 2);
+        assert_eq!(result.skipped[0].1, existing.id);
+        assert_eq!(result.skipped[1].1, result.imported[0].id);
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
 }
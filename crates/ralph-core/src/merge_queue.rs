@@ -39,8 +39,9 @@
 use crate::loop_lock::LoopLock;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
-use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -90,6 +91,12 @@ pub enum MergeEventType {
         /// Reason for discarding (optional).
         reason: Option<String>,
     },
+
+    /// Loop was renamed; later events use the new ID.
+    Renamed {
+        /// The loop's new ID.
+        to: String,
+    },
 }
 
 /// State of the merge button for a loop.
@@ -159,6 +166,9 @@ pub struct MergeEntry {
     /// When the loop was queued.
     pub queued_at: DateTime<Utc>,
 
+    /// When the entry last changed state.
+    pub updated_at: DateTime<Utc>,
+
     /// PID of merge-ralph if merging.
     pub merge_pid: Option<u32>,
 
@@ -324,6 +334,27 @@ impl MergeQueue {
         self.append_event(&event)
     }
 
+    /// Records that a loop was renamed, carrying its entry over to `new_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `loop_id` - The current loop identifier
+    /// * `new_id` - The new loop identifier
+    pub fn rename(&self, loop_id: &str, new_id: &str) -> Result<(), MergeQueueError> {
+        if self.get_entry(loop_id)?.is_none() {
+            return Err(MergeQueueError::NotFound(loop_id.to_string()));
+        }
+
+        let event = MergeEvent {
+            ts: Utc::now(),
+            loop_id: loop_id.to_string(),
+            event: MergeEventType::Renamed {
+                to: new_id.to_string(),
+            },
+        };
+        self.append_event(&event)
+    }
+
     /// Marks a loop as discarded.
     ///
     /// # Arguments
@@ -355,6 +386,71 @@ impl MergeQueue {
         self.append_event(&event)
     }
 
+    /// Removes entries from the queue, dropping every event recorded for them
+    /// (including events under a loop's earlier names).
+    ///
+    /// Only entries in a terminal state are removed; other IDs are ignored.
+    /// Returns the number of entries removed.
+    pub fn remove_terminal(&self, loop_ids: &[&str]) -> Result<usize, MergeQueueError> {
+        if !self.queue_path.exists() {
+            return Ok(0);
+        }
+
+        self.with_exclusive_lock(|mut file| {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            let events = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(line_num, line)| {
+                    serde_json::from_str::<MergeEvent>(line).map_err(|e| {
+                        MergeQueueError::ParseError(format!("Line {}: {}", line_num + 1, e))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let removable: HashSet<String> = Self::derive_state(&events)
+                .into_iter()
+                .filter(|entry| {
+                    entry.state.is_terminal() && loop_ids.contains(&entry.loop_id.as_str())
+                })
+                .map(|entry| entry.loop_id)
+                .collect();
+            if removable.is_empty() {
+                return Ok(0);
+            }
+
+            // Walk backwards so each event maps to the name its loop ends up with
+            let mut final_ids: HashMap<&str, String> = HashMap::new();
+            let mut keep = vec![true; events.len()];
+            for (index, event) in events.iter().enumerate().rev() {
+                let id = match &event.event {
+                    MergeEventType::Renamed { to } => to,
+                    _ => &event.loop_id,
+                };
+                let final_id = final_ids
+                    .get(id.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| id.clone());
+                if matches!(event.event, MergeEventType::Renamed { .. }) {
+                    final_ids.insert(&event.loop_id, final_id.clone());
+                }
+                keep[index] = !removable.contains(&final_id);
+            }
+
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            for (event, _) in events.iter().zip(&keep).filter(|(_, keep)| **keep) {
+                let json = serde_json::to_string(event)
+                    .map_err(|e| MergeQueueError::ParseError(e.to_string()))?;
+                writeln!(file, "{}", json)?;
+            }
+            file.sync_all()?;
+            Ok(removable.len())
+        })
+    }
+
     /// Gets the next pending loop ready for merge (FIFO order).
     ///
     /// Returns the oldest loop in `Queued` state.
@@ -411,12 +507,18 @@ impl MergeQueue {
 
     /// Derives the current state of all loops from the event history.
     fn derive_state(events: &[MergeEvent]) -> Vec<MergeEntry> {
-        use std::collections::HashMap;
-
         // Build up state for each loop
         let mut loop_states: HashMap<String, MergeEntry> = HashMap::new();
 
         for event in events {
+            if let MergeEventType::Renamed { to } = &event.event {
+                if let Some(mut entry) = loop_states.remove(&event.loop_id) {
+                    entry.loop_id = to.clone();
+                    loop_states.insert(to.clone(), entry);
+                }
+                continue;
+            }
+
             let entry = loop_states
                 .entry(event.loop_id.clone())
                 .or_insert_with(|| MergeEntry {
@@ -424,12 +526,14 @@ impl MergeQueue {
                     prompt: String::new(),
                     state: MergeState::Queued,
                     queued_at: event.ts,
+                    updated_at: event.ts,
                     merge_pid: None,
                     merge_commit: None,
                     failure_reason: None,
                     discard_reason: None,
                 });
 
+            entry.updated_at = event.ts;
             match &event.event {
                 MergeEventType::Queued { prompt } => {
                     entry.prompt = prompt.clone();
@@ -452,6 +556,8 @@ impl MergeQueue {
                     entry.state = MergeState::Discarded;
                     entry.discard_reason = reason.clone();
                 }
+                // Applied before the entry lookup
+                MergeEventType::Renamed { .. } => {}
             }
         }
 
@@ -853,6 +959,63 @@ mod tests {
         assert_eq!(entry.discard_reason, Some("No longer needed".to_string()));
     }
 
+    #[test]
+    fn test_rename_carries_entry_to_new_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp_dir.path());
+
+        queue.enqueue("able-raven", "test").unwrap();
+        queue.mark_merging("able-raven", 12345).unwrap();
+        queue.mark_needs_review("able-raven", "conflicts").unwrap();
+        queue.rename("able-raven", "auth-rework").unwrap();
+
+        assert!(queue.get_entry("able-raven").unwrap().is_none());
+        let entry = queue.get_entry("auth-rework").unwrap().unwrap();
+        assert_eq!(entry.state, MergeState::NeedsReview);
+        assert_eq!(entry.prompt, "test");
+
+        // Later events use the new ID
+        queue.mark_merging("auth-rework", 12346).unwrap();
+        assert_eq!(
+            queue.get_entry("auth-rework").unwrap().unwrap().state,
+            MergeState::Merging
+        );
+        assert!(matches!(
+            queue.rename("able-raven", "other"),
+            Err(MergeQueueError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_remove_terminal_drops_history_and_keeps_active() {
+        let temp_dir = TempDir::new().unwrap();
+        let queue = MergeQueue::new(temp_dir.path());
+
+        queue.enqueue("able-raven", "renamed then merged").unwrap();
+        queue.rename("able-raven", "auth-rework").unwrap();
+        queue.mark_merging("auth-rework", 12345).unwrap();
+        queue.mark_merged("auth-rework", "abc123").unwrap();
+        queue.enqueue("brave-otter", "discarded").unwrap();
+        queue.discard("brave-otter", None).unwrap();
+        queue.enqueue("calm-heron", "still queued").unwrap();
+
+        let removed = queue
+            .remove_terminal(&["auth-rework", "brave-otter", "calm-heron"])
+            .unwrap();
+        assert_eq!(removed, 2);
+
+        let ids: Vec<_> = queue
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.loop_id)
+            .collect();
+        assert_eq!(ids, vec!["calm-heron"]);
+        let log = fs::read_to_string(temp_dir.path().join(MergeQueue::QUEUE_FILE)).unwrap();
+        assert_eq!(log.lines().count(), 1, "{log}");
+        assert!(!log.contains("able-raven"), "{log}");
+    }
+
     #[test]
     fn test_discard_from_needs_review() {
         let temp_dir = TempDir::new().unwrap();
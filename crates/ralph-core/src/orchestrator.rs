@@ -0,0 +1,1457 @@
+//! Library-level orchestration entrypoint.
+//!
+//! `Orchestrator` drives an [`EventLoop`] to termination: it picks the next
+//! hat, builds its prompt, hands the prompt to an [`IterationExecutor`], and
+//! feeds the output and emitted JSONL events back into the loop.
+//!
+//! Everything that is specific to a front end (PTY handling, TUI, console
+//! output, signal handling) lives behind the executor and [`LoopHooks`], so
+//! the same loop can be driven by the `ralph` binary or embedded in another
+//! program.
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use ralph_core::{
+//!     IterationExecutor, IterationOutcome, IterationRequest, LoopContext, Orchestrator,
+//!     RalphConfig, TerminationReason,
+//! };
+//! use std::io::Write;
+//!
+//! /// Backend that completes the loop on its first iteration.
+//! struct CompletingBackend {
+//!     events_path: std::path::PathBuf,
+//! }
+//!
+//! #[async_trait]
+//! impl IterationExecutor for CompletingBackend {
+//!     type Error = std::io::Error;
+//!
+//!     async fn execute(
+//!         &mut self,
+//!         _request: &IterationRequest,
+//!     ) -> Result<IterationOutcome, Self::Error> {
+//!         // Agents signal completion by emitting an event to the JSONL file
+//!         let mut events = std::fs::OpenOptions::new()
+//!             .create(true)
+//!             .append(true)
+//!             .open(&self.events_path)?;
+//!         writeln!(
+//!             events,
+//!             r#"{{"topic":"LOOP_COMPLETE","payload":"done","ts":"2026-01-01T00:00:00Z"}}"#
+//!         )?;
+//!         Ok(IterationOutcome::completed("All done"))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let workspace = tempfile::tempdir().unwrap();
+//! let context = LoopContext::primary(workspace.path().to_path_buf());
+//! context.ensure_directories().unwrap();
+//! let mut backend = CompletingBackend {
+//!     events_path: context.events_path(),
+//! };
+//!
+//! let mut orchestrator = Orchestrator::with_context(RalphConfig::default(), context);
+//! orchestrator.initialize("Say hello", false);
+//! let summary = orchestrator.run(&mut backend, &mut ()).await.unwrap();
+//!
+//! assert_eq!(summary.reason, TerminationReason::CompletionPromise);
+//! assert_eq!(summary.iterations, 1);
+//! assert_eq!(summary.exit_code(), 0);
+//! assert_eq!(summary.completion_topic.as_deref(), Some("LOOP_COMPLETE"));
+//! # });
+//! ```
+
+use crate::config::{HatBackend, RalphConfig};
+use crate::event_loop::{EventLoop, LoopState, TerminationReason};
+use crate::loop_context::LoopContext;
+use crate::pause::{PauseControl, PauseState};
+use async_trait::async_trait;
+use ralph_proto::{Event, HatId};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, Span, debug, error, field, info, info_span, warn};
+
+/// Maximum consecutive fallback events before the loop gives up.
+const MAX_FALLBACK_ATTEMPTS: u32 = 3;
+
+/// Span that records its `duration_ms` field when dropped, however the
+/// scope that owns it ends.
+struct TimedSpan {
+    span: Span,
+    started: Instant,
+}
+
+impl TimedSpan {
+    fn new(span: Span) -> Self {
+        Self {
+            span,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for TimedSpan {
+    fn drop(&mut self) {
+        let millis = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.span.record("duration_ms", millis);
+    }
+}
+
+/// A single iteration handed to an [`IterationExecutor`].
+#[derive(Debug, Clone)]
+pub struct IterationRequest {
+    /// Iteration number about to run (1-indexed).
+    pub iteration: u32,
+
+    /// Hat that received the pending events (`ralph` when coordinating).
+    pub hat_id: HatId,
+
+    /// Hat persona being worn for this iteration.
+    pub active_hat: HatId,
+
+    /// Display name of the active hat.
+    pub hat_name: String,
+
+    /// Hat-level backend override, if the active hat configures one.
+    pub backend: Option<HatBackend>,
+
+    /// Hat-level environment variables for the backend process.
+    pub env: BTreeMap<String, String>,
+
+    /// Tools the active hat allows (empty = no allowlist).
+    pub allowed_tools: Vec<String>,
+
+    /// Tools the active hat forbids.
+    pub disallowed_tools: Vec<String>,
+
+    /// Fully built prompt for this iteration.
+    pub prompt: String,
+}
+
+/// Result of executing one iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationOutcome {
+    /// Output text used for event parsing and logging.
+    pub output: String,
+
+    /// Whether the backend reported success.
+    pub success: bool,
+
+    /// Set when execution itself ended the loop (e.g., interrupt).
+    pub termination: Option<TerminationReason>,
+
+    /// Why the backend failed (exit code, signal, spawn error), if known.
+    ///
+    /// Failures with a summary and no events are retried per
+    /// `event_loop.retry`.
+    pub failure: Option<String>,
+
+    /// Estimated spend of the backend session in USD (0 when unknown),
+    /// counted against `event_loop.max_cost_usd`.
+    pub cost_usd: f64,
+}
+
+impl IterationOutcome {
+    /// Creates a successful outcome with the given output.
+    pub fn completed(output: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            success: true,
+            termination: None,
+            failure: None,
+            cost_usd: 0.0,
+        }
+    }
+
+    /// Creates a failed outcome with the given output.
+    pub fn failed(output: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            success: false,
+            termination: None,
+            failure: None,
+            cost_usd: 0.0,
+        }
+    }
+
+    /// Creates an outcome that terminates the loop.
+    pub fn terminated(reason: TerminationReason) -> Self {
+        Self {
+            output: String::new(),
+            success: false,
+            termination: Some(reason),
+            failure: None,
+            cost_usd: 0.0,
+        }
+    }
+
+    /// Sets the estimated spend of the backend session.
+    pub fn with_cost(mut self, cost_usd: f64) -> Self {
+        self.cost_usd = cost_usd;
+        self
+    }
+
+    /// Creates a failed outcome for a backend that crashed or could not start.
+    pub fn crashed(output: impl Into<String>, failure: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            success: false,
+            termination: None,
+            failure: Some(failure.into()),
+            cost_usd: 0.0,
+        }
+    }
+}
+
+/// Runs a prompt against an agent backend.
+#[async_trait]
+pub trait IterationExecutor: Send {
+    /// Error returned when execution fails outright (the loop stops).
+    type Error: Send;
+
+    /// Executes one iteration's prompt and returns its outcome.
+    async fn execute(
+        &mut self,
+        request: &IterationRequest,
+    ) -> Result<IterationOutcome, Self::Error>;
+}
+
+/// Callbacks for observing and steering a run.
+///
+/// All methods default to no-ops; `()` implements the trait for runs that
+/// need no hooks. Published events can also be streamed with
+/// [`Orchestrator::add_observer`].
+pub trait LoopHooks: Send {
+    /// Called at the start of every iteration, before termination checks.
+    ///
+    /// Returning a reason stops the loop (e.g., on a pending interrupt).
+    fn before_iteration(&mut self, _event_loop: &mut EventLoop) -> Option<TerminationReason> {
+        None
+    }
+
+    /// Called once the prompt is built, right before execution.
+    fn on_iteration_start(&mut self, _request: &IterationRequest, _event_loop: &EventLoop) {}
+
+    /// Called with the executor's output, before it is processed.
+    fn on_output(&mut self, _request: &IterationRequest, _output: &str, _event_loop: &EventLoop) {}
+
+    /// Called after output is processed, before JSONL events are read.
+    fn after_output(&mut self, _event_loop: &mut EventLoop) {}
+
+    /// Called with each `loop.paused` / `loop.resumed` event.
+    fn on_pause_changed(&mut self, _event: &Event, _event_loop: &EventLoop) {}
+
+    /// Called with the `completion.verified` or `completion.rejected` event
+    /// recorded for an `event_loop.completion_check` run.
+    fn on_completion_check(&mut self, _event: &Event, _event_loop: &EventLoop) {}
+
+    /// Called with each `event.unhandled` record for a dropped queued event,
+    /// and each `event.duplicate` record for a suppressed repeat.
+    fn on_event_unhandled(&mut self, _event: &Event, _event_loop: &EventLoop) {}
+
+    /// Called once with the published `loop.terminate` event.
+    fn on_terminate(
+        &mut self,
+        _reason: &TerminationReason,
+        _terminate_event: &Event,
+        _event_loop: &EventLoop,
+    ) {
+    }
+
+    /// Called after `on_terminate` with the `event_loop.on_failure_emit`
+    /// event, when the loop stopped without completing.
+    fn on_failure_event(&mut self, _event: &Event, _event_loop: &EventLoop) {}
+}
+
+impl LoopHooks for () {}
+
+/// Summary of a finished run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    /// Why the loop stopped.
+    pub reason: TerminationReason,
+
+    /// Number of iterations executed.
+    pub iterations: u32,
+
+    /// Wall-clock duration of the run.
+    pub elapsed: Duration,
+
+    /// Cumulative cost in USD (if tracked).
+    pub cumulative_cost: f64,
+
+    /// Completion topic that ended the loop, if any.
+    pub completion_topic: Option<String>,
+}
+
+impl RunSummary {
+    fn new(reason: TerminationReason, state: &LoopState) -> Self {
+        Self {
+            reason,
+            iterations: state.iteration,
+            elapsed: state.elapsed(),
+            cumulative_cost: state.cumulative_cost,
+            completion_topic: state.completion_topic.clone(),
+        }
+    }
+
+    /// Returns the process exit code for this run.
+    pub fn exit_code(&self) -> i32 {
+        self.reason.exit_code()
+    }
+}
+
+/// Drives an [`EventLoop`] until it terminates.
+pub struct Orchestrator {
+    event_loop: EventLoop,
+    pause: Option<PauseControl>,
+}
+
+impl Orchestrator {
+    /// Creates an orchestrator for the current directory.
+    pub fn new(config: RalphConfig) -> Self {
+        Self::from_event_loop(EventLoop::new(config))
+    }
+
+    /// Creates an orchestrator using the loop context for path resolution.
+    pub fn with_context(config: RalphConfig, context: LoopContext) -> Self {
+        Self::from_event_loop(EventLoop::with_context(config, context))
+    }
+
+    /// Wraps an already configured event loop.
+    pub fn from_event_loop(event_loop: EventLoop) -> Self {
+        Self {
+            event_loop,
+            pause: None,
+        }
+    }
+
+    /// Gates each iteration start on a shared [`PauseControl`].
+    pub fn with_pause_control(mut self, control: PauseControl) -> Self {
+        self.pause = Some(control);
+        self
+    }
+
+    /// Returns the underlying event loop.
+    pub fn event_loop(&self) -> &EventLoop {
+        &self.event_loop
+    }
+
+    /// Executes a request, retrying crashed attempts per `event_loop.retry`.
+    ///
+    /// An attempt is retried only if the backend failed with a known cause,
+    /// wrote no events, and no stop was requested. The iteration counter
+    /// advances once however many attempts it takes, but every attempt's
+    /// spend counts towards `max_cost_usd`.
+    async fn execute_with_retry<E>(
+        &mut self,
+        executor: &mut E,
+        request: &IterationRequest,
+    ) -> Result<IterationOutcome, E::Error>
+    where
+        E: IterationExecutor + ?Sized,
+    {
+        let retry = self.event_loop.config().event_loop.retry.clone();
+        let mut attempt = 1;
+        loop {
+            let backend_span = TimedSpan::new(info_span!(
+                "ralph.backend",
+                hat = %request.active_hat,
+                attempt,
+                success = field::Empty,
+                duration_ms = field::Empty,
+            ));
+            let outcome = executor
+                .execute(request)
+                .instrument(backend_span.span.clone())
+                .await?;
+            backend_span.span.record("success", outcome.success);
+            drop(backend_span);
+            self.event_loop.add_cost(outcome.cost_usd);
+            let Some(failure) = outcome.failure.as_deref() else {
+                return Ok(outcome);
+            };
+            if outcome.success
+                || outcome.termination.is_some()
+                || attempt >= retry.max_attempts
+                || self.event_loop.has_unread_events()
+                || self.event_loop.stop_requested()
+            {
+                return Ok(outcome);
+            }
+
+            let backoff = retry.backoff(attempt);
+            warn!(
+                hat = %request.hat_id,
+                attempt,
+                max_attempts = retry.max_attempts,
+                failure,
+                backoff_secs = backoff.as_secs(),
+                "Iteration attempt failed, retrying"
+            );
+            self.event_loop.notify_iteration_retried(attempt, failure);
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    /// Runs `event_loop.completion_check`, if configured, for a completion
+    /// event the loop just accepted.
+    ///
+    /// Returns true if the completion stands. A rejection has already been
+    /// published as `completion.rejected`, so the loop simply carries on.
+    async fn completion_verified<H>(&mut self, hooks: &mut H) -> bool
+    where
+        H: LoopHooks + ?Sized,
+    {
+        let Some(check) = self.event_loop.config().event_loop.completion_check.clone() else {
+            return true;
+        };
+        // Worktree loops check their own checkout
+        let workspace = self.event_loop.loop_context().map_or_else(
+            || self.event_loop.config().core.workspace_root.clone(),
+            |context| context.workspace().to_path_buf(),
+        );
+
+        info!(command = %check.command, "Running completion check");
+        // Boxed so the check doesn't grow every caller's `run` future
+        let outcome = Box::pin(crate::completion_check::run_completion_check(
+            &check, &workspace,
+        ))
+        .await;
+        let event = self.event_loop.record_completion_check(&outcome);
+        hooks.on_completion_check(&event, &self.event_loop);
+        outcome.passed()
+    }
+
+    /// Holds the loop while its pause control is paused.
+    ///
+    /// Records `loop.paused` when the wait starts and `loop.resumed` (with
+    /// the time spent paused) when it ends, so the event history explains
+    /// the gap.
+    async fn wait_while_paused<H>(&mut self, hooks: &mut H)
+    where
+        H: LoopHooks + ?Sized,
+    {
+        let Some(control) = self.pause.clone() else {
+            return;
+        };
+        if control.state() != PauseState::Paused {
+            return;
+        }
+
+        info!("Loop paused before next iteration");
+        let event = self.event_loop.notify_loop_paused();
+        hooks.on_pause_changed(&event, &self.event_loop);
+
+        let paused_at = Instant::now();
+        let released = control.wait_until_released().await;
+        let step = released == PauseState::Step;
+        info!(step, "Loop resumed");
+        let event = self
+            .event_loop
+            .notify_loop_resumed(step, paused_at.elapsed());
+        hooks.on_pause_changed(&event, &self.event_loop);
+    }
+
+    /// Returns the underlying event loop mutably.
+    pub fn event_loop_mut(&mut self) -> &mut EventLoop {
+        &mut self.event_loop
+    }
+
+    /// Adds an observer that receives every published event.
+    pub fn add_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(&Event) + Send + 'static,
+    {
+        self.event_loop.add_observer(observer);
+    }
+
+    /// Publishes the starting event (`task.resume` when `resume` is true).
+    pub fn initialize(&mut self, prompt: &str, resume: bool) {
+        if resume {
+            self.event_loop.initialize_resume(prompt);
+        } else {
+            self.event_loop.initialize(prompt);
+        }
+    }
+
+    /// Runs the loop until a termination condition is met.
+    ///
+    /// Returns an error only if the executor fails; every other stop is
+    /// reported through [`RunSummary::reason`].
+    pub async fn run<E, H>(
+        &mut self,
+        executor: &mut E,
+        hooks: &mut H,
+    ) -> Result<RunSummary, E::Error>
+    where
+        E: IterationExecutor + ?Sized,
+        H: LoopHooks + ?Sized,
+    {
+        let mut consecutive_fallbacks: u32 = 0;
+
+        loop {
+            // Pausing waits here, after the previous iteration finished, so
+            // guidance typed while paused is flushed by before_iteration
+            self.wait_while_paused(hooks).await;
+
+            if let Some(reason) = hooks.before_iteration(&mut self.event_loop) {
+                return Ok(self.terminate(reason, hooks));
+            }
+
+            // Check termination before execution
+            self.event_loop.enforce_hat_budgets();
+            if let Some(reason) = self.event_loop.check_termination() {
+                return Ok(self.terminate(reason, hooks));
+            }
+
+            // Get next hat to execute, with fallback recovery if no pending events
+            let hat_id = if let Some(id) = self.event_loop.next_hat() {
+                consecutive_fallbacks = 0;
+                id.clone()
+            } else {
+                consecutive_fallbacks += 1;
+
+                if consecutive_fallbacks > MAX_FALLBACK_ATTEMPTS {
+                    warn!(
+                        attempts = consecutive_fallbacks,
+                        "Fallback recovery exhausted after {} attempts, terminating",
+                        MAX_FALLBACK_ATTEMPTS
+                    );
+                    return Ok(self.terminate(TerminationReason::Stopped, hooks));
+                }
+
+                // The planner picks up task.resume and either dispatches more
+                // work, completes the loop, or works out what went wrong
+                if self.event_loop.inject_fallback_event() {
+                    continue;
+                }
+
+                warn!("No hats with pending events and fallback not available, terminating");
+                return Ok(self.terminate(TerminationReason::Stopped, hooks));
+            };
+
+            let iteration = self.event_loop.state().iteration + 1;
+
+            // When Ralph is coordinating, the active hat is the one being worked on
+            let active_hat = if hat_id.as_str() == "ralph" {
+                self.event_loop.get_active_hat_id()
+            } else {
+                hat_id.clone()
+            };
+
+            // Building the prompt consumes the pending events, so read their
+            // topics first
+            let iteration_span = TimedSpan::new(info_span!(
+                "ralph.iteration",
+                iteration,
+                hat = %active_hat,
+                hat_name = field::Empty,
+                topic = %self.event_loop.pending_topics(&hat_id).join(","),
+                duration_ms = field::Empty,
+            ));
+
+            let Some(prompt) = self.event_loop.build_prompt(&hat_id) else {
+                error!("Failed to build prompt for hat '{}'", hat_id);
+                continue;
+            };
+
+            let hat_config = self.event_loop.registry().get_config(&active_hat);
+            let (allowed_tools, disallowed_tools) = hat_config.map_or_else(Default::default, |c| {
+                (c.allowed_tools.clone(), c.disallowed_tools.clone())
+            });
+
+            let request = IterationRequest {
+                iteration,
+                hat_name: self
+                    .event_loop
+                    .registry()
+                    .get(&active_hat)
+                    .map_or_else(|| active_hat.as_str().to_string(), |hat| hat.name.clone()),
+                backend: self.event_loop.get_hat_backend(&active_hat).cloned(),
+                env: self
+                    .event_loop
+                    .get_hat_env(&active_hat)
+                    .cloned()
+                    .unwrap_or_default(),
+                allowed_tools,
+                disallowed_tools,
+                hat_id,
+                active_hat,
+                prompt,
+            };
+
+            if let Some(control) = &self.pause {
+                control.finish_step();
+            }
+
+            iteration_span
+                .span
+                .record("hat_name", request.hat_name.as_str());
+            hooks.on_iteration_start(&request, &self.event_loop);
+            let outcome = self
+                .execute_with_retry(executor, &request)
+                .instrument(iteration_span.span.clone())
+                .await?;
+
+            if let Some(reason) = outcome.termination {
+                return Ok(self.terminate(reason, hooks));
+            }
+
+            hooks.on_output(&request, &outcome.output, &self.event_loop);
+
+            if let Some(reason) =
+                self.event_loop
+                    .process_output(&request.hat_id, &outcome.output, outcome.success)
+            {
+                // Per spec: Log "All done! {promise} detected." when completion promise found
+                if reason == TerminationReason::CompletionPromise {
+                    info!(
+                        "All done! {} detected.",
+                        self.event_loop.config().event_loop.completion_promise
+                    );
+                }
+                return Ok(self.terminate(reason, hooks));
+            }
+
+            hooks.after_output(&mut self.event_loop);
+
+            // Read events from JSONL that the agent may have written
+            let agent_wrote_events = matches!(
+                self.event_loop
+                    .process_events_from_jsonl()
+                    .inspect_err(|e| warn!(error = %e, "Failed to read events from JSONL")),
+                Ok(true)
+            );
+            for event in self.event_loop.take_unhandled_events() {
+                hooks.on_event_unhandled(&event, &self.event_loop);
+            }
+
+            // Inject default_publishes for active hats only when agent wrote no events
+            if !agent_wrote_events {
+                let active_hats = self.event_loop.state().last_active_hat_ids.clone();
+                for active_hat_id in &active_hats {
+                    self.event_loop.check_default_publishes(active_hat_id);
+                    if self.event_loop.has_pending_events() {
+                        break; // One default is sufficient
+                    }
+                }
+            }
+
+            self.save_state_snapshot();
+
+            if let Some(reason) = self.event_loop.check_completion_event()
+                && self
+                    .completion_verified(hooks)
+                    .instrument(iteration_span.span.clone())
+                    .await
+            {
+                info!(
+                    "Completion event {} detected.",
+                    self.event_loop
+                        .state()
+                        .completion_topic
+                        .as_deref()
+                        .unwrap_or_default()
+                );
+                return Ok(self.terminate(reason, hooks));
+            }
+
+            // Per EventLoop doc: "Use has_pending_events after process_output to detect
+            // if the LLM failed to publish an event."
+            if !self.event_loop.has_pending_events() {
+                let expected = self.event_loop.get_hat_publishes(&request.hat_id);
+                debug!(
+                    hat = %request.hat_id.as_str(),
+                    expected_topics = ?expected,
+                    "No pending events after iteration. Agent may have failed to publish a valid event. \
+                     Expected one of: {:?}. Loop will terminate on next iteration.",
+                    expected
+                );
+            }
+
+            // The cooldown belongs to neither iteration
+            drop(iteration_span);
+
+            // Cooldown delay between iterations (skip for human events)
+            let cooldown = self.event_loop.config().event_loop.cooldown_delay_seconds;
+            if cooldown > 0 && !self.event_loop.has_pending_human_events() {
+                debug!(
+                    delay_seconds = cooldown,
+                    "Cooldown delay before next iteration"
+                );
+                tokio::time::sleep(Duration::from_secs(cooldown)).await;
+            }
+        }
+    }
+
+    /// Writes the loop state snapshot for `ralph run --continue`.
+    fn save_state_snapshot(&self) {
+        if let Err(e) = self.event_loop.write_state_snapshot() {
+            warn!("Failed to write loop state snapshot: {}", e);
+        }
+    }
+
+    /// Publishes `loop.terminate` (and the failure event, if configured),
+    /// writes `run-summary.json`, notifies hooks, and builds the summary.
+    fn terminate<H>(&mut self, reason: TerminationReason, hooks: &mut H) -> RunSummary
+    where
+        H: LoopHooks + ?Sized,
+    {
+        // Saved before loop.terminate is published so it isn't left pending
+        self.save_state_snapshot();
+        let terminate_event = self.event_loop.publish_terminate_event(&reason);
+        let failure_event = self.event_loop.publish_failure_event(&reason);
+        match self.event_loop.write_run_summary(&reason) {
+            Ok(path) => debug!("Wrote run summary to {}", path.display()),
+            Err(e) => warn!("Failed to write run summary: {}", e),
+        }
+        hooks.on_terminate(&reason, &terminate_event, &self.event_loop);
+        if let Some(event) = failure_event {
+            hooks.on_failure_event(&event, &self.event_loop);
+        }
+        RunSummary::new(reason, self.event_loop.state())
+    }
+}
+
+#[async_trait]
+impl IterationExecutor for crate::testing::MockBackend {
+    type Error = std::convert::Infallible;
+
+    async fn execute(
+        &mut self,
+        request: &IterationRequest,
+    ) -> Result<IterationOutcome, Self::Error> {
+        Ok(IterationOutcome::completed(
+            crate::testing::MockBackend::execute(self, &request.prompt),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBackend;
+    use tempfile::TempDir;
+
+    struct RecordingHooks {
+        started: Vec<u32>,
+        terminated: Option<TerminationReason>,
+    }
+
+    impl LoopHooks for RecordingHooks {
+        fn on_iteration_start(&mut self, request: &IterationRequest, _event_loop: &EventLoop) {
+            self.started.push(request.iteration);
+        }
+
+        fn on_terminate(
+            &mut self,
+            reason: &TerminationReason,
+            terminate_event: &Event,
+            _event_loop: &EventLoop,
+        ) {
+            assert_eq!(terminate_event.topic.as_str(), "loop.terminate");
+            self.terminated = Some(reason.clone());
+        }
+    }
+
+    fn orchestrator(temp: &TempDir, max_iterations: u32) -> Orchestrator {
+        orchestrator_with(temp, max_iterations, |_| {})
+    }
+
+    fn orchestrator_with(
+        temp: &TempDir,
+        max_iterations: u32,
+        configure: impl FnOnce(&mut RalphConfig),
+    ) -> Orchestrator {
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+        config.event_loop.max_iterations = max_iterations;
+        configure(&mut config);
+        let context = LoopContext::primary(temp.path().to_path_buf());
+        let mut orchestrator = Orchestrator::with_context(config, context);
+        orchestrator.initialize("Test objective", false);
+        orchestrator
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_at_max_iterations_with_mock_backend() {
+        let temp = TempDir::new().unwrap();
+        let mut backend = MockBackend::new(vec!["working".to_string(); 5]);
+        let mut hooks = RecordingHooks {
+            started: Vec::new(),
+            terminated: None,
+        };
+
+        let summary = orchestrator(&temp, 2)
+            .run(&mut backend, &mut hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxIterations);
+        assert_eq!(summary.iterations, 2);
+        assert_eq!(summary.exit_code(), 2);
+        assert_eq!(hooks.started, vec![1, 2]);
+        assert_eq!(hooks.terminated, Some(TerminationReason::MaxIterations));
+        assert_eq!(backend.execution_count(), 2);
+    }
+
+    /// Emits the completion promise every iteration, creating `done` in the
+    /// workspace from iteration `fixed_at` on.
+    struct CompletingBackend {
+        workspace: std::path::PathBuf,
+        fixed_at: u32,
+        prompts: Vec<String>,
+    }
+
+    #[async_trait]
+    impl IterationExecutor for CompletingBackend {
+        type Error = std::convert::Infallible;
+
+        async fn execute(
+            &mut self,
+            request: &IterationRequest,
+        ) -> Result<IterationOutcome, Self::Error> {
+            self.prompts.push(request.prompt.clone());
+            if request.iteration >= self.fixed_at {
+                std::fs::write(self.workspace.join("done"), "").unwrap();
+            }
+            let context = LoopContext::primary(self.workspace.clone());
+            let line = serde_json::json!({
+                "topic": "LOOP_COMPLETE",
+                "payload": "done",
+                "ts": chrono::Utc::now().to_rfc3339(),
+            })
+            .to_string();
+            crate::EventHistory::new(context.events_path())
+                .append(&line, false)
+                .unwrap();
+            Ok(IterationOutcome::completed("claimed done"))
+        }
+    }
+
+    #[derive(Default)]
+    struct CheckHooks(Vec<Event>);
+
+    impl LoopHooks for CheckHooks {
+        fn on_completion_check(&mut self, event: &Event, _event_loop: &EventLoop) {
+            self.0.push(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completion_check_rejects_until_command_passes() {
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator_with(&temp, 5, |config| {
+            config.event_loop.completion_check = Some(crate::CompletionCheckConfig::new(
+                "echo 'missing done'; test -f done",
+            ));
+        });
+        let mut backend = CompletingBackend {
+            workspace: temp.path().to_path_buf(),
+            fixed_at: 2,
+            prompts: Vec::new(),
+        };
+        let mut hooks = CheckHooks::default();
+
+        let summary = orchestrator.run(&mut backend, &mut hooks).await.unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::CompletionPromise);
+        assert_eq!(summary.iterations, 2);
+        let topics: Vec<_> = hooks.0.iter().map(|e| e.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            vec![
+                crate::COMPLETION_REJECTED_TOPIC,
+                crate::COMPLETION_VERIFIED_TOPIC
+            ]
+        );
+        assert!(hooks.0[0].payload.contains("missing done"));
+        assert!(hooks.0[0].payload.contains("rejection: 1/3"));
+        assert!(backend.prompts[1].contains("missing done"));
+    }
+
+    #[tokio::test]
+    async fn test_completion_check_stops_after_max_rejections() {
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator_with(&temp, 10, |config| {
+            let mut check = crate::CompletionCheckConfig::new("exit 1");
+            check.max_rejections = 2;
+            config.event_loop.completion_check = Some(check);
+        });
+        let mut backend = CompletingBackend {
+            workspace: temp.path().to_path_buf(),
+            fixed_at: u32::MAX,
+            prompts: Vec::new(),
+        };
+        let mut hooks = CheckHooks::default();
+
+        let summary = orchestrator.run(&mut backend, &mut hooks).await.unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::CompletionRejected);
+        assert_eq!(summary.exit_code(), 1);
+        assert_eq!(summary.iterations, 2);
+        assert_eq!(summary.completion_topic, None);
+        assert_eq!(hooks.0.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failure_event_is_written_on_max_iterations() {
+        struct LoggingHooks(crate::EventLogger);
+        impl LoopHooks for LoggingHooks {
+            fn on_failure_event(&mut self, event: &Event, event_loop: &EventLoop) {
+                let record =
+                    crate::EventRecord::new(event_loop.state().iteration, "loop", event, None);
+                self.0.log(&record).unwrap();
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let events_path = temp.path().join("events.jsonl");
+        let mut orchestrator = orchestrator_with(&temp, 1, |config| {
+            config.event_loop.on_failure_emit = Some("loop.terminated".to_string());
+        });
+        let mut backend = MockBackend::new(vec!["working".to_string(); 2]);
+        let mut hooks = LoggingHooks(crate::EventLogger::new(&events_path));
+
+        let summary = orchestrator.run(&mut backend, &mut hooks).await.unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxIterations);
+        let records = crate::EventHistory::new(&events_path).read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].topic, "loop.terminated");
+        let payload: serde_json::Value = serde_json::from_str(&records[0].payload).unwrap();
+        assert_eq!(payload["reason"], "max_iterations");
+        assert_eq!(payload["exit_code"], 2);
+        assert_eq!(payload["iterations"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_summary_json_written_on_termination() {
+        /// Writes one event to the events file per iteration.
+        struct EmittingBackend {
+            events_path: std::path::PathBuf,
+        }
+
+        #[async_trait]
+        impl IterationExecutor for EmittingBackend {
+            type Error = std::convert::Infallible;
+
+            async fn execute(
+                &mut self,
+                request: &IterationRequest,
+            ) -> Result<IterationOutcome, Self::Error> {
+                let line = serde_json::json!({
+                    "topic": "build.progress",
+                    "payload": format!("step {}", request.iteration),
+                    "ts": chrono::Utc::now().to_rfc3339(),
+                })
+                .to_string();
+                crate::EventHistory::new(&self.events_path)
+                    .append(&line, false)
+                    .unwrap();
+                Ok(IterationOutcome::completed("working"))
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator(&temp, 2);
+        let mut backend = EmittingBackend {
+            events_path: LoopContext::primary(temp.path().to_path_buf()).events_path(),
+        };
+
+        orchestrator.run(&mut backend, &mut ()).await.unwrap();
+
+        let path = temp.path().join(".ralph/agent/run-summary.json");
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["reason"], "max_iterations");
+        assert_eq!(json["exit_code"], 2);
+        assert_eq!(json["success"], false);
+        let iterations = json["iterations"].as_array().unwrap();
+        assert_eq!(iterations.len(), 2);
+        for (index, iteration) in iterations.iter().enumerate() {
+            assert_eq!(iteration["iteration"], index + 1);
+            assert_eq!(iteration["hat"], "ralph");
+            assert_eq!(iteration["events_emitted"], 1);
+            assert!(iteration["duration_ms"].is_u64());
+        }
+        assert_eq!(json["totals"]["iterations"], 2);
+        assert_eq!(json["totals"]["events_emitted"], 2);
+        assert!(json["totals"]["elapsed_ms"].is_u64());
+        assert!(json["totals"]["cost_usd"].is_f64());
+
+        // The final state snapshot doesn't hold the loop.terminate event
+        let snapshot =
+            crate::LoopStateSnapshot::load(&temp.path().join(".ralph/agent/loop-state.json"))
+                .unwrap();
+        assert_eq!(snapshot.iteration, 2);
+        assert_eq!(snapshot.last_event.as_deref(), Some("build.progress"));
+        assert!(
+            snapshot
+                .pending_events
+                .iter()
+                .all(|event| event.topic.as_str() != "loop.terminate")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usage_crossing_max_cost_stops_loop() {
+        /// Reports 20k input tokens per iteration ($0.06 at $3/M).
+        struct SpendingBackend;
+
+        #[async_trait]
+        impl IterationExecutor for SpendingBackend {
+            type Error = std::convert::Infallible;
+
+            async fn execute(
+                &mut self,
+                _request: &IterationRequest,
+            ) -> Result<IterationOutcome, Self::Error> {
+                let pricing = crate::ModelPricing {
+                    input: 3.0,
+                    ..crate::ModelPricing::default()
+                };
+                Ok(IterationOutcome::completed("working").with_cost(pricing.cost(20_000, 0, 0, 0)))
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator_with(&temp, 10, |config| {
+            config.event_loop.max_cost_usd = Some(0.10);
+        });
+
+        let summary = orchestrator
+            .run(&mut SpendingBackend, &mut ())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxCost);
+        assert_eq!(summary.iterations, 2);
+        assert!((summary.cumulative_cost - 0.12).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_summary_out_redirects_run_summary() {
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator_with(&temp, 1, |config| {
+            config.event_loop.summary_out = Some("reports/run.json".into());
+        });
+        let mut backend = MockBackend::new(vec!["working".to_string()]);
+
+        orchestrator.run(&mut backend, &mut ()).await.unwrap();
+
+        assert!(temp.path().join("reports/run.json").exists());
+        assert!(!temp.path().join(".ralph/agent/run-summary.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_completed_run_emits_no_failure_event() {
+        let temp = TempDir::new().unwrap();
+        let mut orchestrator = orchestrator_with(&temp, 1, |config| {
+            config.event_loop.on_failure_emit = Some("loop.terminated".to_string());
+        });
+
+        let event = orchestrator
+            .event_loop
+            .publish_failure_event(&TerminationReason::CompletionPromise);
+
+        assert!(event.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_before_iteration_hook_can_stop_run() {
+        struct InterruptHooks;
+        impl LoopHooks for InterruptHooks {
+            fn before_iteration(
+                &mut self,
+                _event_loop: &mut EventLoop,
+            ) -> Option<TerminationReason> {
+                Some(TerminationReason::Interrupted)
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let mut backend = MockBackend::new(Vec::new());
+
+        let summary = orchestrator(&temp, 10)
+            .run(&mut backend, &mut InterruptHooks)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::Interrupted);
+        assert_eq!(summary.iterations, 0);
+        assert_eq!(backend.execution_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_executor_termination_ends_run() {
+        struct StoppingBackend;
+
+        #[async_trait]
+        impl IterationExecutor for StoppingBackend {
+            type Error = std::convert::Infallible;
+
+            async fn execute(
+                &mut self,
+                _request: &IterationRequest,
+            ) -> Result<IterationOutcome, Self::Error> {
+                Ok(IterationOutcome::terminated(TerminationReason::Stopped))
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let summary = orchestrator(&temp, 10)
+            .run(&mut StoppingBackend, &mut ())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::Stopped);
+        assert_eq!(summary.iterations, 0);
+    }
+
+    /// Crashes for the first `crashes` attempts, then succeeds.
+    struct FlakyBackend {
+        crashes: u32,
+        attempts: u32,
+    }
+
+    #[async_trait]
+    impl IterationExecutor for FlakyBackend {
+        type Error = std::convert::Infallible;
+
+        async fn execute(
+            &mut self,
+            _request: &IterationRequest,
+        ) -> Result<IterationOutcome, Self::Error> {
+            self.attempts += 1;
+            if self.attempts <= self.crashes {
+                Ok(IterationOutcome::crashed(
+                    "boom",
+                    "backend exited with code 137",
+                ))
+            } else {
+                Ok(IterationOutcome::completed("working"))
+            }
+        }
+    }
+
+    fn retrying_orchestrator(
+        temp: &TempDir,
+        max_iterations: u32,
+        max_attempts: u32,
+    ) -> (Orchestrator, std::sync::Arc<std::sync::Mutex<Vec<Event>>>) {
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+        config.event_loop.max_iterations = max_iterations;
+        config.event_loop.retry.max_attempts = max_attempts;
+        config.event_loop.retry.backoff_secs = 0;
+        let context = LoopContext::primary(temp.path().to_path_buf());
+        let mut orchestrator = Orchestrator::with_context(config, context);
+        orchestrator.initialize("Test objective", false);
+
+        let retried = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = retried.clone();
+        orchestrator.add_observer(move |event| {
+            if event.topic.as_str() == crate::ITERATION_RETRIED_TOPIC {
+                sink.lock().unwrap().push(event.clone());
+            }
+        });
+        (orchestrator, retried)
+    }
+
+    #[tokio::test]
+    async fn test_crashed_attempts_are_retried_within_one_iteration() {
+        let temp = TempDir::new().unwrap();
+        let (mut orchestrator, retried) = retrying_orchestrator(&temp, 1, 3);
+        let mut backend = FlakyBackend {
+            crashes: 2,
+            attempts: 0,
+        };
+        let mut hooks = RecordingHooks {
+            started: Vec::new(),
+            terminated: None,
+        };
+
+        let summary = orchestrator.run(&mut backend, &mut hooks).await.unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxIterations);
+        assert_eq!(summary.iterations, 1);
+        assert_eq!(hooks.started, vec![1]);
+        assert_eq!(backend.attempts, 3);
+        assert_eq!(orchestrator.event_loop().state().consecutive_failures, 0);
+
+        let retried = retried.lock().unwrap();
+        assert_eq!(retried.len(), 2);
+        let payload = &retried[1].payload;
+        assert!(payload.contains("- attempt: 2"), "{payload}");
+        assert!(payload.contains("- max_attempts: 3"), "{payload}");
+        assert!(
+            payload.contains("- failure: backend exited with code 137"),
+            "{payload}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let temp = TempDir::new().unwrap();
+        let (mut orchestrator, retried) = retrying_orchestrator(&temp, 1, 2);
+        let mut backend = FlakyBackend {
+            crashes: 5,
+            attempts: 0,
+        };
+
+        let summary = orchestrator.run(&mut backend, &mut ()).await.unwrap();
+
+        assert_eq!(summary.iterations, 1);
+        assert_eq!(backend.attempts, 2);
+        assert_eq!(retried.lock().unwrap().len(), 1);
+        assert_eq!(orchestrator.event_loop().state().consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_by_default() {
+        let temp = TempDir::new().unwrap();
+        let (mut orchestrator, retried) = retrying_orchestrator(&temp, 1, 1);
+        let mut backend = FlakyBackend {
+            crashes: 1,
+            attempts: 0,
+        };
+
+        orchestrator.run(&mut backend, &mut ()).await.unwrap();
+
+        assert_eq!(backend.attempts, 1);
+        assert!(retried.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_when_stop_requested() {
+        let temp = TempDir::new().unwrap();
+        let (mut orchestrator, retried) = retrying_orchestrator(&temp, 10, 3);
+        std::fs::create_dir_all(temp.path().join(".ralph")).unwrap();
+        let mut backend = FlakyBackend {
+            crashes: 5,
+            attempts: 0,
+        };
+
+        /// Requests a stop once the first attempt has started.
+        struct StopHooks(std::path::PathBuf);
+        impl LoopHooks for StopHooks {
+            fn on_iteration_start(&mut self, _request: &IterationRequest, _event_loop: &EventLoop) {
+                std::fs::write(&self.0, "").unwrap();
+            }
+        }
+        let mut hooks = StopHooks(temp.path().join(".ralph/stop-requested"));
+
+        let summary = orchestrator.run(&mut backend, &mut hooks).await.unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::Stopped);
+        assert_eq!(backend.attempts, 1);
+        assert!(retried.lock().unwrap().is_empty());
+    }
+
+    /// Appends scripted JSONL events to the events file, one batch per iteration.
+    struct EmittingBackend {
+        events_path: std::path::PathBuf,
+        batches: Vec<Vec<(&'static str, &'static str)>>,
+        prompts: Vec<String>,
+    }
+
+    #[async_trait]
+    impl IterationExecutor for EmittingBackend {
+        type Error = std::convert::Infallible;
+
+        async fn execute(
+            &mut self,
+            request: &IterationRequest,
+        ) -> Result<IterationOutcome, Self::Error> {
+            self.prompts.push(request.prompt.clone());
+            let batch = if self.batches.is_empty() {
+                Vec::new()
+            } else {
+                self.batches.remove(0)
+            };
+            for (topic, payload) in batch {
+                let line = serde_json::json!({
+                    "topic": topic,
+                    "payload": payload,
+                    "ts": "2026-01-01T00:00:00Z",
+                });
+                crate::EventHistory::new(&self.events_path)
+                    .append(&line.to_string(), false)
+                    .unwrap();
+            }
+            Ok(IterationOutcome::completed("done".to_string()))
+        }
+    }
+
+    #[derive(Default)]
+    struct QueueHooks {
+        depths: Vec<usize>,
+        unhandled: Vec<Event>,
+    }
+
+    impl LoopHooks for QueueHooks {
+        fn on_iteration_start(&mut self, _request: &IterationRequest, event_loop: &EventLoop) {
+            self.depths.push(event_loop.queue_depth());
+        }
+
+        fn on_event_unhandled(&mut self, event: &Event, _event_loop: &EventLoop) {
+            self.unhandled.push(event.clone());
+        }
+    }
+
+    fn queue_orchestrator(temp: &TempDir, queue_yaml: &str) -> Orchestrator {
+        let yaml = format!(
+            r#"
+event_loop:
+  max_iterations: 3
+  queue:
+{queue_yaml}
+hats:
+  builder:
+    name: Builder
+    description: Builds one task
+    triggers: ["build.task"]
+    publishes: ["build.done"]
+"#
+        );
+        let mut config: RalphConfig = serde_yaml::from_str(&yaml).unwrap();
+        config.core.workspace_root = temp.path().to_path_buf();
+        let context = LoopContext::primary(temp.path().to_path_buf());
+        let mut orchestrator = Orchestrator::with_context(config, context);
+        orchestrator.initialize("Test objective", false);
+        orchestrator
+    }
+
+    #[tokio::test]
+    async fn test_queued_events_are_released_one_per_iteration() {
+        let temp = TempDir::new().unwrap();
+        let mut backend = EmittingBackend {
+            events_path: temp.path().join(".ralph/events.jsonl"),
+            batches: vec![vec![
+                ("build.task", "task-alpha"),
+                ("note.aside", "nobody listens"),
+                ("build.task", "task-beta"),
+            ]],
+            prompts: Vec::new(),
+        };
+        let mut hooks = QueueHooks::default();
+
+        let summary = queue_orchestrator(&temp, "    unmatched_ttl: 1")
+            .run(&mut backend, &mut hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxIterations);
+        assert_eq!(hooks.depths, vec![0, 2, 0]);
+
+        assert!(backend.prompts[1].contains("task-alpha"));
+        assert!(!backend.prompts[1].contains("task-beta"));
+        assert!(backend.prompts[2].contains("task-beta"));
+        assert!(!backend.prompts[2].contains("task-alpha"));
+
+        // The unmatched event was passed over twice, then dropped
+        assert_eq!(hooks.unhandled.len(), 1);
+        let unhandled = &hooks.unhandled[0];
+        assert_eq!(unhandled.topic.as_str(), crate::EVENT_UNHANDLED_TOPIC);
+        assert!(unhandled.payload.contains("- topic: note.aside"));
+        assert!(!backend.prompts.iter().any(|p| p.contains("nobody listens")));
+    }
+
+    #[tokio::test]
+    async fn test_queue_overflow_fail_stops_run() {
+        let temp = TempDir::new().unwrap();
+        let mut backend = EmittingBackend {
+            events_path: temp.path().join(".ralph/events.jsonl"),
+            batches: vec![vec![
+                ("build.task", "one"),
+                ("build.task", "two"),
+                ("build.task", "three"),
+            ]],
+            prompts: Vec::new(),
+        };
+        let mut hooks = QueueHooks::default();
+
+        let summary = queue_orchestrator(&temp, "    max_depth: 2\n    on_overflow: fail")
+            .run(&mut backend, &mut hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::EventQueueOverflow);
+        assert_eq!(summary.iterations, 1);
+        assert!(hooks.unhandled[0].payload.contains("on_overflow: fail"));
+    }
+
+    #[tokio::test]
+    async fn test_pause_control_gates_and_steps_iterations() {
+        /// Steps twice from the paused state, then resumes.
+        struct SteppingHooks {
+            control: PauseControl,
+            started: Vec<u32>,
+            pause_events: Vec<Event>,
+        }
+        impl LoopHooks for SteppingHooks {
+            fn on_iteration_start(&mut self, request: &IterationRequest, _event_loop: &EventLoop) {
+                self.started.push(request.iteration);
+            }
+
+            fn on_pause_changed(&mut self, event: &Event, _event_loop: &EventLoop) {
+                self.pause_events.push(event.clone());
+                if event.topic.as_str() == crate::LOOP_PAUSED_TOPIC {
+                    let pauses = self
+                        .pause_events
+                        .iter()
+                        .filter(|e| e.topic.as_str() == crate::LOOP_PAUSED_TOPIC)
+                        .count();
+                    if pauses < 3 {
+                        assert!(self.control.step());
+                    } else {
+                        self.control.resume();
+                    }
+                }
+            }
+        }
+
+        let temp = TempDir::new().unwrap();
+        let control = PauseControl::new();
+        control.pause();
+        let mut hooks = SteppingHooks {
+            control: control.clone(),
+            started: Vec::new(),
+            pause_events: Vec::new(),
+        };
+        let mut backend = MockBackend::new(vec!["working".to_string(); 5]);
+
+        let summary = orchestrator(&temp, 3)
+            .with_pause_control(control.clone())
+            .run(&mut backend, &mut hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.reason, TerminationReason::MaxIterations);
+        assert_eq!(hooks.started, vec![1, 2, 3]);
+        let topics: Vec<_> = hooks
+            .pause_events
+            .iter()
+            .map(|e| e.topic.as_str())
+            .collect();
+        assert_eq!(
+            topics,
+            vec![
+                "loop.paused",
+                "loop.resumed",
+                "loop.paused",
+                "loop.resumed",
+                "loop.paused",
+                "loop.resumed",
+            ]
+        );
+        assert!(hooks.pause_events[1].payload.contains("- mode: step"));
+        assert!(hooks.pause_events[5].payload.contains("- mode: resume"));
+        assert!(hooks.pause_events[2].payload.contains("- iteration: 1"));
+        assert_eq!(control.state(), PauseState::Running);
+    }
+}
@@ -0,0 +1,158 @@
+//! Pause, resume, and single-step control for a running loop.
+//!
+//! A [`PauseControl`] is shared between a front end (the TUI) and the
+//! [`Orchestrator`](crate::Orchestrator). Pausing never suspends the backend
+//! process: the iteration in flight runs to completion and the orchestrator
+//! waits before starting the next one.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::watch;
+
+/// Requested run state of the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PauseState {
+    /// Iterations run back to back.
+    #[default]
+    Running,
+    /// The loop waits before starting the next iteration.
+    Paused,
+    /// Run exactly one more iteration, then pause again.
+    Step,
+}
+
+/// Shared handle for pausing and stepping the loop.
+#[derive(Debug, Clone)]
+pub struct PauseControl {
+    state: Arc<watch::Sender<PauseState>>,
+    waiting: Arc<AtomicBool>,
+}
+
+impl Default for PauseControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PauseControl {
+    /// Creates a control in the running state.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(watch::Sender::new(PauseState::Running)),
+            waiting: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the requested state.
+    pub fn state(&self) -> PauseState {
+        *self.state.borrow()
+    }
+
+    /// Returns true once the loop is actually held between iterations.
+    ///
+    /// A pause requested mid-iteration is not waiting until that iteration
+    /// finishes.
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.load(Ordering::SeqCst)
+    }
+
+    /// Requests a pause before the next iteration.
+    pub fn pause(&self) {
+        self.state.send_replace(PauseState::Paused);
+    }
+
+    /// Resumes normal operation.
+    pub fn resume(&self) {
+        self.state.send_replace(PauseState::Running);
+    }
+
+    /// Pauses a running loop, or resumes a paused one.
+    pub fn toggle(&self) {
+        self.state.send_modify(|state| {
+            *state = match state {
+                PauseState::Running => PauseState::Paused,
+                PauseState::Paused | PauseState::Step => PauseState::Running,
+            };
+        });
+    }
+
+    /// Lets a paused loop run exactly one iteration.
+    ///
+    /// Returns false (and does nothing) unless the loop is paused.
+    pub fn step(&self) -> bool {
+        self.state.send_if_modified(|state| {
+            if *state == PauseState::Paused {
+                *state = PauseState::Step;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Waits while the loop is paused and returns the state that released it.
+    pub(crate) async fn wait_until_released(&self) -> PauseState {
+        let mut rx = self.state.subscribe();
+        self.waiting.store(true, Ordering::SeqCst);
+        let released = rx
+            .wait_for(|state| *state != PauseState::Paused)
+            .await
+            .map_or(PauseState::Running, |state| *state);
+        self.waiting.store(false, Ordering::SeqCst);
+        released
+    }
+
+    /// Re-pauses after a single step, right before its iteration executes.
+    pub(crate) fn finish_step(&self) {
+        self.state.send_if_modified(|state| {
+            if *state == PauseState::Step {
+                *state = PauseState::Paused;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_toggle_and_step_transitions() {
+        let control = PauseControl::new();
+        assert_eq!(control.state(), PauseState::Running);
+        assert!(!control.step(), "step only applies while paused");
+
+        control.toggle();
+        assert_eq!(control.state(), PauseState::Paused);
+        assert!(control.step());
+        assert_eq!(control.state(), PauseState::Step);
+
+        control.finish_step();
+        assert_eq!(control.state(), PauseState::Paused);
+
+        control.toggle();
+        assert_eq!(control.state(), PauseState::Running);
+        control.finish_step();
+        assert_eq!(control.state(), PauseState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_released_reports_step() {
+        let control = PauseControl::new();
+        control.pause();
+
+        let waiter = control.clone();
+        let handle = tokio::spawn(async move { waiter.wait_until_released().await });
+        while !control.is_waiting() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        assert!(control.step());
+        assert_eq!(handle.await.unwrap(), PauseState::Step);
+        assert!(!control.is_waiting());
+    }
+}
@@ -1,14 +1,17 @@
 //! Preflight checks for validating environment and configuration before running.
 
 use crate::config::ConfigWarning;
+use crate::env_source::{KeyringStore, SecretStore, missing_env_sources};
 use crate::{RalphConfig, git_ops};
 use async_trait::async_trait;
+use futures::future::join_all;
 use serde::Serialize;
 use std::env;
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
 
 /// Status of a preflight check.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -27,6 +30,8 @@ pub struct CheckResult {
     pub status: CheckStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// How long the check took, set by [`PreflightRunner`].
+    pub duration_ms: u64,
 }
 
 impl CheckResult {
@@ -36,6 +41,7 @@ impl CheckResult {
             label: label.into(),
             status: CheckStatus::Pass,
             message: None,
+            duration_ms: 0,
         }
     }
 
@@ -45,6 +51,7 @@ impl CheckResult {
             label: label.into(),
             status: CheckStatus::Warn,
             message: Some(message.into()),
+            duration_ms: 0,
         }
     }
 
@@ -54,6 +61,7 @@ impl CheckResult {
             label: label.into(),
             status: CheckStatus::Fail,
             message: Some(message.into()),
+            duration_ms: 0,
         }
     }
 }
@@ -63,6 +71,14 @@ impl CheckResult {
 pub trait PreflightCheck: Send + Sync {
     fn name(&self) -> &'static str;
     async fn run(&self, config: &RalphConfig) -> CheckResult;
+
+    /// Whether this check mutates shared state (e.g. creates directories).
+    ///
+    /// Sequential checks run one at a time, in order, before the concurrent
+    /// batch so other checks observe their effects.
+    fn sequential(&self) -> bool {
+        false
+    }
 }
 
 /// Aggregated preflight report.
@@ -107,8 +123,12 @@ impl PreflightRunner {
                 Box::new(ConfigValidCheck),
                 Box::new(BackendAvailableCheck),
                 Box::new(TelegramTokenCheck),
+                Box::new(EnvSourcesCheck::default()),
                 Box::new(GitCleanCheck),
+                Box::new(DiskSpaceCheck),
                 Box::new(PathsExistCheck),
+                Box::new(PromptCheck),
+                Box::new(CompletionCheckCommand),
                 Box::new(ToolsInPathCheck::default()),
                 Box::new(SpecCompletenessCheck),
             ],
@@ -133,16 +153,60 @@ impl PreflightRunner {
         Self::run_checks(checks, config).await
     }
 
+    /// Runs checks concurrently (capped by `features.preflight.max_concurrency`),
+    /// reporting results in the order the checks were given.
     async fn run_checks<'a, I>(checks: I, config: &RalphConfig) -> PreflightReport
     where
         I: IntoIterator<Item = &'a Box<dyn PreflightCheck>>,
     {
-        let mut results = Vec::new();
-        for check in checks {
-            results.push(check.run(config).await);
+        let checks: Vec<_> = checks.into_iter().collect();
+        let mut results: Vec<Option<CheckResult>> = vec![None; checks.len()];
+
+        for (index, check) in checks.iter().enumerate() {
+            if check.sequential() {
+                results[index] = Some(Self::run_timed(check.as_ref(), config).await);
+            }
         }
 
-        PreflightReport::from_results(results)
+        let permits = Semaphore::new(config.features.preflight.max_concurrency.max(1));
+        let concurrent = checks
+            .iter()
+            .enumerate()
+            .filter(|(_, check)| !check.sequential())
+            .map(|(index, check)| {
+                let permits = &permits;
+                async move {
+                    let _permit = permits.acquire().await.expect("semaphore is never closed");
+                    (index, Self::run_timed(check.as_ref(), config).await)
+                }
+            });
+        for (index, result) in join_all(concurrent).await {
+            results[index] = Some(result);
+        }
+
+        PreflightReport::from_results(results.into_iter().flatten().collect())
+    }
+
+    /// Runs one check, recording its duration and warning if it exceeds
+    /// `features.preflight.check_timeout_secs` (0 disables the timeout).
+    async fn run_timed(check: &dyn PreflightCheck, config: &RalphConfig) -> CheckResult {
+        let started = Instant::now();
+        let timeout_secs = config.features.preflight.check_timeout_secs;
+        let mut result = if timeout_secs == 0 {
+            check.run(config).await
+        } else {
+            tokio::time::timeout(Duration::from_secs(timeout_secs), check.run(config))
+                .await
+                .unwrap_or_else(|_| {
+                    CheckResult::warn(
+                        check.name(),
+                        "Check timed out",
+                        format!("Did not finish within {timeout_secs}s"),
+                    )
+                })
+        };
+        result.duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        result
     }
 }
 
@@ -184,10 +248,10 @@ impl PreflightCheck for BackendAvailableCheck {
     async fn run(&self, config: &RalphConfig) -> CheckResult {
         let backend = config.cli.backend.trim();
         if backend.eq_ignore_ascii_case("auto") {
-            return check_auto_backend(self.name(), config);
+            return check_auto_backend(self.name(), config).await;
         }
 
-        check_named_backend(self.name(), config, backend)
+        check_named_backend(self.name(), config, backend).await
     }
 }
 
@@ -221,6 +285,64 @@ impl PreflightCheck for TelegramTokenCheck {
     }
 }
 
+/// Verifies that `${VAR}` references and `keychain:` entries in `cli.env`
+/// and hat `env` resolve.
+struct EnvSourcesCheck {
+    store: Box<dyn SecretStore>,
+}
+
+impl Default for EnvSourcesCheck {
+    fn default() -> Self {
+        Self {
+            store: Box::new(KeyringStore),
+        }
+    }
+}
+
+#[async_trait]
+impl PreflightCheck for EnvSourcesCheck {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    async fn run(&self, config: &RalphConfig) -> CheckResult {
+        let mut hats: Vec<_> = config.hats.iter().collect();
+        hats.sort_by_key(|(id, _)| id.as_str());
+        let scopes = std::iter::once(("cli.env".to_string(), &config.cli.env)).chain(
+            hats.into_iter()
+                .map(|(id, hat)| (format!("hats.{id}.env"), &hat.env)),
+        );
+
+        let mut checked = 0;
+        let mut missing = Vec::new();
+        for (scope, env) in scopes {
+            for (key, value) in env {
+                checked += 1;
+                missing.extend(
+                    missing_env_sources(value, self.store.as_ref())
+                        .into_iter()
+                        .map(|problem| format!("{scope}.{key}: {problem}")),
+                );
+            }
+        }
+
+        if checked == 0 {
+            CheckResult::pass(self.name(), "No backend env configured")
+        } else if missing.is_empty() {
+            CheckResult::pass(self.name(), format!("{checked} env value(s) resolve"))
+        } else {
+            CheckResult::fail(
+                self.name(),
+                format!("{} env source(s) missing", missing.len()),
+                missing.join("; "),
+            )
+        }
+    }
+}
+
+/// Warns when the working tree has uncommitted changes, so the agent's first
+/// commit doesn't pick up unrelated local edits. Fails instead with
+/// `features.preflight.require_clean_tree`.
 struct GitCleanCheck;
 
 #[async_trait]
@@ -230,36 +352,120 @@ impl PreflightCheck for GitCleanCheck {
     }
 
     async fn run(&self, config: &RalphConfig) -> CheckResult {
-        let root = &config.core.workspace_root;
-        if !is_git_workspace(root) {
-            return CheckResult::pass(self.name(), "Not a git repository (skipping)");
+        let root = config.core.workspace_root.clone();
+        let require_clean = config.features.preflight.require_clean_tree;
+        let name = self.name();
+        // git_ops shells out synchronously; keep it off the runtime thread so
+        // the per-check timeout can fire if git hangs.
+        tokio::task::spawn_blocking(move || git_clean_result(name, &root, require_clean))
+            .await
+            .unwrap_or_else(|err| {
+                CheckResult::fail(name, "Git check did not complete", format!("{err}"))
+            })
+    }
+}
+
+fn git_clean_result(name: &'static str, root: &Path, require_clean: bool) -> CheckResult {
+    if !is_git_workspace(root) {
+        return CheckResult::pass(name, "Not a git repository (skipping)");
+    }
+
+    let branch = match git_ops::get_current_branch(root) {
+        Ok(branch) => branch,
+        Err(err) => {
+            return CheckResult::fail(name, "Git repository unavailable", format!("{err}"));
         }
+    };
+
+    let changes = match git_ops::uncommitted_files(root) {
+        Ok(changes) => changes,
+        Err(err) => {
+            return CheckResult::fail(name, "Unable to read git status", format!("{err}"));
+        }
+    };
+    if changes.is_empty() {
+        return CheckResult::pass(name, format!("Working tree clean ({branch})"));
+    }
+
+    let label = format!(
+        "Working tree has {} uncommitted change(s) ({branch})",
+        changes.len()
+    );
+    let listed = truncate_list(&changes, MAX_LISTED_CHANGES);
+    if require_clean {
+        CheckResult::fail(
+            name,
+            label,
+            format!("Commit or stash before running (require_clean_tree): {listed}"),
+        )
+    } else {
+        CheckResult::warn(
+            name,
+            label,
+            format!("Commit or stash changes before running for clean diffs: {listed}"),
+        )
+    }
+}
+
+/// How many changed files the git check names before summarizing the rest.
+const MAX_LISTED_CHANGES: usize = 10;
+
+/// Checks free space at the workspace, which diagnostics, worktrees and
+/// event files all grow during a run.
+struct DiskSpaceCheck;
+
+#[async_trait]
+impl PreflightCheck for DiskSpaceCheck {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
 
-        let branch = match git_ops::get_current_branch(root) {
-            Ok(branch) => branch,
+    async fn run(&self, config: &RalphConfig) -> CheckResult {
+        let root = &config.core.workspace_root;
+        let available = match available_disk_space(root) {
+            Ok(Some(available)) => available,
+            Ok(None) => {
+                return CheckResult::pass(
+                    self.name(),
+                    "Free space unknown on this platform (skipping)",
+                );
+            }
             Err(err) => {
-                return CheckResult::fail(
+                return CheckResult::warn(
                     self.name(),
-                    "Git repository unavailable",
-                    format!("{err}"),
+                    "Unable to read free disk space",
+                    format!("{}: {err}", root.display()),
                 );
             }
         };
 
-        match git_ops::is_working_tree_clean(root) {
-            Ok(true) => CheckResult::pass(self.name(), format!("Working tree clean ({branch})")),
-            Ok(false) => CheckResult::warn(
+        let preflight = &config.features.preflight;
+        let warn_below = preflight.min_free_disk_mb.saturating_mul(MIB);
+        let fail_below = preflight.disk_floor_mb.saturating_mul(MIB);
+        let free = format_size(available);
+        let detail = format!(
+            "{free} free at {} (warns below {}, fails below {})",
+            root.display(),
+            format_size(warn_below),
+            format_size(fail_below)
+        );
+
+        if available < fail_below {
+            CheckResult::fail(
                 self.name(),
-                "Working tree has uncommitted changes",
-                "Commit or stash changes before running for clean diffs",
-            ),
-            Err(err) => {
-                CheckResult::fail(self.name(), "Unable to read git status", format!("{err}"))
-            }
+                format!("Disk almost full ({free} free)"),
+                detail,
+            )
+        } else if available < warn_below {
+            CheckResult::warn(self.name(), format!("Low disk space ({free} free)"), detail)
+        } else {
+            CheckResult::pass(self.name(), format!("{free} free"))
         }
     }
 }
 
+const MIB: u64 = 1024 * 1024;
+
 struct PathsExistCheck;
 
 #[async_trait]
@@ -268,6 +474,11 @@ impl PreflightCheck for PathsExistCheck {
         "paths"
     }
 
+    fn sequential(&self) -> bool {
+        // Creates missing directories that the specs check then reads
+        true
+    }
+
     async fn run(&self, config: &RalphConfig) -> CheckResult {
         let mut created = Vec::new();
 
@@ -303,6 +514,68 @@ impl PreflightCheck for PathsExistCheck {
     }
 }
 
+struct PromptCheck;
+
+#[async_trait]
+impl PreflightCheck for PromptCheck {
+    fn name(&self) -> &'static str {
+        "prompt"
+    }
+
+    async fn run(&self, config: &RalphConfig) -> CheckResult {
+        match crate::read_prompt_source(&config.event_loop, &config.core.workspace_root) {
+            Ok(_) if config.event_loop.prompt.is_some() => {
+                CheckResult::pass(self.name(), "Inline prompt set")
+            }
+            Ok(_) => CheckResult::pass(
+                self.name(),
+                format!("Prompt file {} readable", config.event_loop.prompt_file),
+            ),
+            Err(err) => CheckResult::fail(self.name(), "Prompt unavailable", format!("{err}")),
+        }
+    }
+}
+
+struct CompletionCheckCommand;
+
+#[async_trait]
+impl PreflightCheck for CompletionCheckCommand {
+    fn name(&self) -> &'static str {
+        "completion_check"
+    }
+
+    async fn run(&self, config: &RalphConfig) -> CheckResult {
+        let Some(check) = &config.event_loop.completion_check else {
+            return CheckResult::pass(self.name(), "No completion check configured");
+        };
+        let Some(program) = crate::completion_check::command_executable(&check.command) else {
+            return CheckResult::pass(
+                self.name(),
+                format!("Completion check set ({})", check.command),
+            );
+        };
+
+        // Relative paths like ./scripts/verify.sh run from the workspace
+        let resolved = if Path::new(program).components().count() > 1 {
+            let path = config.core.workspace_root.join(program);
+            path.is_file().then_some(path)
+        } else {
+            find_executable(program)
+        };
+        match resolved {
+            Some(_) => CheckResult::pass(
+                self.name(),
+                format!("Completion check executable found ({program})"),
+            ),
+            None => CheckResult::fail(
+                self.name(),
+                "Completion check executable missing",
+                format!("`{program}` from `{}` was not found", check.command),
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ToolsInPathCheck {
     required: Vec<String>,
@@ -720,7 +993,7 @@ async fn telegram_get_me(token: &str) -> anyhow::Result<TelegramBotInfo> {
     Ok(TelegramBotInfo { username })
 }
 
-fn check_auto_backend(name: &str, config: &RalphConfig) -> CheckResult {
+async fn check_auto_backend(name: &str, config: &RalphConfig) -> CheckResult {
     let priority = config.get_agent_priority();
     if priority.is_empty() {
         return CheckResult::fail(
@@ -742,7 +1015,7 @@ fn check_auto_backend(name: &str, config: &RalphConfig) -> CheckResult {
         };
         checked.push(format!("{backend} ({command})"));
         if command_supports_version(backend) {
-            if command_available(&command) {
+            if command_available(&command).await {
                 return CheckResult::pass(name, format!("Auto backend available ({backend})"));
             }
         } else if find_executable(&command).is_some() {
@@ -765,7 +1038,7 @@ fn check_auto_backend(name: &str, config: &RalphConfig) -> CheckResult {
     )
 }
 
-fn check_named_backend(name: &str, config: &RalphConfig, backend: &str) -> CheckResult {
+async fn check_named_backend(name: &str, config: &RalphConfig, backend: &str) -> CheckResult {
     let command_override = config.cli.command.as_deref();
     let Some(command) = backend_command(backend, command_override) else {
         return CheckResult::fail(
@@ -787,7 +1060,7 @@ fn check_named_backend(name: &str, config: &RalphConfig, backend: &str) -> Check
         );
     }
 
-    if command_available(&command) {
+    if command_available(&command).await {
         CheckResult::pass(name, format!("Backend CLI available ({})", command))
     } else {
         CheckResult::fail(
@@ -820,10 +1093,11 @@ fn command_supports_version(backend: &str) -> bool {
     !backend.eq_ignore_ascii_case("custom")
 }
 
-fn command_available(command: &str) -> bool {
-    Command::new(command)
+async fn command_available(command: &str) -> bool {
+    tokio::process::Command::new(command)
         .arg("--version")
         .output()
+        .await
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
@@ -883,6 +1157,50 @@ fn executable_extensions() -> Vec<OsString> {
     }
 }
 
+/// Bytes available to unprivileged users on the filesystem holding `path`
+/// (or its nearest existing ancestor). `None` where this isn't supported.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // statvfs field widths vary by platform
+fn available_disk_space(path: &Path) -> std::io::Result<Option<u64>> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+    let stats = nix::sys::statvfs::statvfs(existing).map_err(std::io::Error::from)?;
+    Ok(Some(
+        (stats.blocks_available() as u64).saturating_mul(stats.fragment_size() as u64),
+    ))
+}
+
+#[cfg(not(unix))]
+fn available_disk_space(_path: &Path) -> std::io::Result<Option<u64>> {
+    Ok(None)
+}
+
+/// Formats a byte count as MB below 1 GB and as GB with one decimal above.
+fn format_size(bytes: u64) -> String {
+    const GIB: u64 = 1024 * MIB;
+    if bytes >= GIB {
+        format!("{:.1} GB", bytes as f64 / GIB as f64)
+    } else {
+        format!("{} MB", bytes / MIB)
+    }
+}
+
+/// Joins `items` with commas, naming at most `max` and counting the rest.
+fn truncate_list(items: &[String], max: usize) -> String {
+    let mut listed = items
+        .iter()
+        .take(max)
+        .map(|item| item.trim())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > max {
+        listed.push_str(&format!(", ... and {} more", items.len() - max));
+    }
+    listed
+}
+
 fn is_git_workspace(path: &Path) -> bool {
     let git_dir = path.join(".git");
     git_dir.is_dir() || git_dir.is_file()
@@ -900,6 +1218,166 @@ fn format_config_warnings(warnings: &[ConfigWarning]) -> String {
 mod tests {
     use super::*;
 
+    /// Check that sleeps before passing and tracks how many checks run at once.
+    struct DelayedCheck {
+        name: &'static str,
+        delay_ms: u64,
+        active: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        peak: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PreflightCheck for DelayedCheck {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn run(&self, _config: &RalphConfig) -> CheckResult {
+            use std::sync::atomic::Ordering;
+
+            let now_active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now_active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            CheckResult::pass(self.name, "done")
+        }
+    }
+
+    fn delayed_runner(
+        delays: &[(&'static str, u64)],
+    ) -> (
+        PreflightRunner,
+        std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    ) {
+        let active = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let checks = delays
+            .iter()
+            .map(|&(name, delay_ms)| {
+                Box::new(DelayedCheck {
+                    name,
+                    delay_ms,
+                    active: active.clone(),
+                    peak: peak.clone(),
+                }) as Box<dyn PreflightCheck>
+            })
+            .collect();
+        (PreflightRunner { checks }, peak)
+    }
+
+    #[tokio::test]
+    async fn completion_check_fails_when_executable_missing() {
+        let mut config = RalphConfig::default();
+        config.event_loop.completion_check = Some(crate::CompletionCheckConfig::new(
+            "definitely-not-a-real-ralph-checker --all",
+        ));
+
+        let result = CompletionCheckCommand.run(&config).await;
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(
+            result
+                .message
+                .as_deref()
+                .unwrap_or_default()
+                .contains("definitely-not-a-real-ralph-checker")
+        );
+    }
+
+    #[tokio::test]
+    async fn completion_check_passes_for_workspace_script() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("verify.sh"), "exit 0").unwrap();
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+        config.event_loop.completion_check =
+            Some(crate::CompletionCheckConfig::new("./verify.sh --quick"));
+
+        let result = CompletionCheckCommand.run(&config).await;
+
+        assert_eq!(result.status, CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn run_all_preserves_order_regardless_of_completion_order() {
+        // Earlier checks finish last
+        let (runner, _peak) =
+            delayed_runner(&[("first", 60), ("second", 40), ("third", 20), ("fourth", 0)]);
+
+        let report = runner.run_all(&RalphConfig::default()).await;
+
+        let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["first", "second", "third", "fourth"]);
+    }
+
+    #[tokio::test]
+    async fn run_all_respects_max_concurrency() {
+        let (runner, peak) = delayed_runner(&[("a", 20), ("b", 20), ("c", 20), ("d", 20)]);
+        let mut config = RalphConfig::default();
+        config.features.preflight.max_concurrency = 2;
+
+        let report = runner.run_all(&config).await;
+
+        assert_eq!(report.checks.len(), 4);
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_check_times_out_with_warning() {
+        let (runner, _peak) = delayed_runner(&[("slow", 5_000), ("fast", 0)]);
+        let mut config = RalphConfig::default();
+        config.features.preflight.check_timeout_secs = 1;
+
+        let report = runner.run_all(&config).await;
+
+        let slow = &report.checks[0];
+        assert_eq!(slow.name, "slow");
+        assert_eq!(slow.status, CheckStatus::Warn);
+        assert_eq!(slow.message.as_deref(), Some("Did not finish within 1s"));
+        assert_eq!(slow.duration_ms, 1_000);
+        assert_eq!(report.checks[1].status, CheckStatus::Pass);
+        assert_eq!(report.checks[1].duration_ms, 0);
+    }
+
+    /// Check that blocks its thread the way a hung git subprocess would.
+    struct BlockingCheck;
+
+    #[async_trait]
+    impl PreflightCheck for BlockingCheck {
+        fn name(&self) -> &'static str {
+            "blocking"
+        }
+
+        async fn run(&self, _config: &RalphConfig) -> CheckResult {
+            tokio::task::spawn_blocking(|| std::thread::sleep(Duration::from_secs(3)))
+                .await
+                .unwrap();
+            CheckResult::pass("blocking", "done")
+        }
+    }
+
+    #[tokio::test]
+    async fn blocking_check_times_out_without_stalling_others() {
+        let (delayed, _peak) = delayed_runner(&[("fast", 0)]);
+        let mut checks: Vec<Box<dyn PreflightCheck>> = vec![Box::new(BlockingCheck)];
+        checks.extend(delayed.checks);
+        let runner = PreflightRunner { checks };
+        let mut config = RalphConfig::default();
+        config.features.preflight.check_timeout_secs = 1;
+
+        let started = std::time::Instant::now();
+        let report = runner.run_all(&config).await;
+
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert_eq!(report.checks[0].name, "blocking");
+        assert_eq!(report.checks[0].status, CheckStatus::Warn);
+        assert_eq!(
+            report.checks[0].message.as_deref(),
+            Some("Did not finish within 1s")
+        );
+        assert_eq!(report.checks[1].status, CheckStatus::Pass);
+    }
+
     #[tokio::test]
     async fn report_counts_statuses() {
         let checks = vec![
@@ -928,6 +1406,40 @@ mod tests {
         assert!(message.contains("archive_prompts"));
     }
 
+    #[tokio::test]
+    async fn env_check_reports_missing_sources() {
+        use crate::env_source::tests::MockStore;
+
+        let mut config = RalphConfig::default();
+        config
+            .cli
+            .env
+            .insert("NPM_TOKEN".to_string(), "keychain:npm-token".to_string());
+        let check = EnvSourcesCheck {
+            store: Box::new(MockStore(
+                [("npm-token".to_string(), "npm_s3cr3t".to_string())].into(),
+            )),
+        };
+        let result = check.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+
+        config.cli.env.insert(
+            "REGISTRY".to_string(),
+            "${RALPH_TEST_SURELY_UNSET_VAR}".to_string(),
+        );
+        config
+            .cli
+            .env
+            .insert("PYPI_TOKEN".to_string(), "keychain:pypi-token".to_string());
+        let result = check.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(
+            result.message.unwrap_or_default(),
+            "cli.env.PYPI_TOKEN: keychain entry 'pypi-token' not found (service 'ralph'); \
+             cli.env.REGISTRY: ${RALPH_TEST_SURELY_UNSET_VAR} is not set"
+        );
+    }
+
     #[tokio::test]
     async fn tools_check_reports_missing_tools() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -977,6 +1489,28 @@ mod tests {
         assert_eq!(result.status, CheckStatus::Warn);
     }
 
+    #[tokio::test]
+    async fn prompt_check_fails_for_missing_or_blank_prompt() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+        config.event_loop.prompt = None;
+        config.event_loop.prompt_file = "PROMPT.md".to_string();
+
+        let result = PromptCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.unwrap_or_default().contains("not found"));
+
+        std::fs::write(temp.path().join("PROMPT.md"), "\n\n").unwrap();
+        let result = PromptCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.unwrap_or_default().contains("is empty"));
+
+        std::fs::write(temp.path().join("PROMPT.md"), "Ship it").unwrap();
+        let result = PromptCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+    }
+
     #[tokio::test]
     async fn telegram_check_skips_when_disabled() {
         let config = RalphConfig::default();
@@ -1001,6 +1535,114 @@ mod tests {
         assert!(result.label.contains("skipping"));
     }
 
+    /// Creates a repo with one committed file, `README.md`.
+    fn init_git_repo(dir: &Path) {
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .expect("git")
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--initial-branch=main"]);
+        git(&["config", "user.email", "test@test.local"]);
+        git(&["config", "user.name", "Test User"]);
+        std::fs::write(dir.join("README.md"), "# Test").unwrap();
+        git(&["add", "README.md"]);
+        git(&["commit", "-m", "Initial commit"]);
+    }
+
+    #[tokio::test]
+    async fn git_check_lists_dirty_files_and_fails_when_clean_tree_required() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        init_git_repo(temp.path());
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+
+        let result = GitCleanCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.label.contains("clean (main)"));
+
+        std::fs::write(temp.path().join("README.md"), "# Changed").unwrap();
+        std::fs::write(temp.path().join("notes.md"), "scratch").unwrap();
+
+        let result = GitCleanCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.label.contains("2 uncommitted change(s)"));
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("M README.md"), "message: {message}");
+        assert!(message.contains("?? notes.md"), "message: {message}");
+
+        config.features.preflight.require_clean_tree = true;
+        let result = GitCleanCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(
+            result
+                .message
+                .unwrap_or_default()
+                .contains("require_clean_tree")
+        );
+    }
+
+    #[tokio::test]
+    async fn git_check_truncates_long_file_lists() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        init_git_repo(temp.path());
+        for i in 0..MAX_LISTED_CHANGES + 3 {
+            std::fs::write(temp.path().join(format!("file{i:02}.txt")), "x").unwrap();
+        }
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().to_path_buf();
+
+        let result = GitCleanCheck.run(&config).await;
+
+        assert_eq!(result.status, CheckStatus::Warn);
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("file00.txt"), "message: {message}");
+        assert!(!message.contains("file12.txt"), "message: {message}");
+        assert!(message.ends_with("... and 3 more"), "message: {message}");
+    }
+
+    #[tokio::test]
+    async fn disk_check_warns_and_fails_below_thresholds() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let mut config = RalphConfig::default();
+        config.core.workspace_root = temp.path().join("not/created/yet");
+        config.features.preflight.min_free_disk_mb = 0;
+        config.features.preflight.disk_floor_mb = 0;
+
+        let result = DiskSpaceCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Pass);
+        let Some(available) = available_disk_space(temp.path()).unwrap() else {
+            assert!(result.label.contains("skipping"));
+            return;
+        };
+
+        // Leave a wide margin so other writers can't flip the outcome
+        let above_free_mb = available / MIB + 1024 * 1024;
+        config.features.preflight.min_free_disk_mb = above_free_mb;
+        let result = DiskSpaceCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.label.starts_with("Low disk space"));
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("fails below 0 MB"), "message: {message}");
+        assert!(message.contains(&temp.path().display().to_string()));
+
+        config.features.preflight.disk_floor_mb = above_free_mb;
+        let result = DiskSpaceCheck.run(&config).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.label.starts_with("Disk almost full"));
+    }
+
+    #[test]
+    fn format_size_switches_to_gb() {
+        assert_eq!(format_size(512 * MIB), "512 MB");
+        assert_eq!(format_size(2048 * MIB), "2.0 GB");
+        assert_eq!(format_size(1536 * MIB), "1.5 GB");
+    }
+
     #[tokio::test]
     async fn tools_check_skips_outside_repo() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -0,0 +1,189 @@
+//! Locating the prompt for a run.
+//!
+//! The prompt is `event_loop.prompt` (inline text, from config or `-p`) or
+//! the contents of `event_loop.prompt_file` (from config or `-P`). A missing,
+//! unreadable, or blank prompt is reported up front instead of handing the
+//! agent an empty task.
+
+use crate::config::EventLoopConfig;
+use std::path::{Path, PathBuf};
+
+const PROMPT_HINT: &str = "Pass the prompt inline with -p \"text\", point at a file with -P <path>, \
+or fix event_loop.prompt_file in ralph.yml.";
+
+/// Why no usable prompt could be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum PromptSourceError {
+    /// Neither an inline prompt nor a prompt file is configured.
+    #[error("No prompt specified (working directory: {}). {}", .working_dir.display(), PROMPT_HINT)]
+    Missing { working_dir: PathBuf },
+
+    /// The inline prompt is empty or whitespace.
+    #[error("Inline prompt is empty. {}", PROMPT_HINT)]
+    EmptyInline,
+
+    /// The prompt file does not exist.
+    #[error(
+        "Prompt file '{}' not found (working directory: {}). {}",
+        .path.display(), .working_dir.display(), PROMPT_HINT
+    )]
+    NotFound { path: PathBuf, working_dir: PathBuf },
+
+    /// The prompt path exists but is a directory.
+    #[error(
+        "Prompt file '{}' is a directory, not a file (working directory: {}). {}",
+        .path.display(), .working_dir.display(), PROMPT_HINT
+    )]
+    NotAFile { path: PathBuf, working_dir: PathBuf },
+
+    /// The prompt file is empty or whitespace.
+    #[error(
+        "Prompt file '{}' is empty (working directory: {}). {}",
+        .path.display(), .working_dir.display(), PROMPT_HINT
+    )]
+    Empty { path: PathBuf, working_dir: PathBuf },
+
+    /// The prompt file could not be read.
+    #[error(
+        "Failed to read prompt file '{}' (working directory: {}): {source}. {}",
+        .path.display(), .working_dir.display(), PROMPT_HINT
+    )]
+    Unreadable {
+        path: PathBuf,
+        working_dir: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Reads the raw prompt text, preferring the inline prompt over the file.
+///
+/// Relative prompt file paths are resolved against `working_dir`. Blank
+/// prompts are rejected; placeholders are left for
+/// [`render_prompt`](crate::render_prompt).
+pub fn read_prompt_source(
+    config: &EventLoopConfig,
+    working_dir: &Path,
+) -> Result<String, PromptSourceError> {
+    if let Some(inline) = &config.prompt {
+        if inline.trim().is_empty() {
+            return Err(PromptSourceError::EmptyInline);
+        }
+        return Ok(inline.clone());
+    }
+
+    if config.prompt_file.is_empty() {
+        return Err(PromptSourceError::Missing {
+            working_dir: working_dir.to_path_buf(),
+        });
+    }
+
+    let path = working_dir.join(&config.prompt_file);
+    let working_dir = working_dir.to_path_buf();
+    match std::fs::metadata(&path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(PromptSourceError::NotFound { path, working_dir });
+        }
+        Ok(metadata) if metadata.is_dir() => {
+            return Err(PromptSourceError::NotAFile { path, working_dir });
+        }
+        _ => {}
+    }
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(source) => {
+            return Err(PromptSourceError::Unreadable {
+                path,
+                working_dir,
+                source,
+            });
+        }
+    };
+    if content.trim().is_empty() {
+        return Err(PromptSourceError::Empty { path, working_dir });
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file_config(prompt_file: &str) -> EventLoopConfig {
+        EventLoopConfig {
+            prompt: None,
+            prompt_file: prompt_file.to_string(),
+            ..EventLoopConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_reads_relative_prompt_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("TASK.md"), "Build it").unwrap();
+
+        let prompt = read_prompt_source(&file_config("TASK.md"), temp.path()).unwrap();
+        assert_eq!(prompt, "Build it");
+    }
+
+    #[test]
+    fn test_missing_file_names_path_and_working_dir() {
+        let temp = TempDir::new().unwrap();
+
+        let err = read_prompt_source(&file_config("PROMTP.md"), temp.path()).unwrap_err();
+        assert!(matches!(err, PromptSourceError::NotFound { .. }));
+        let message = err.to_string();
+        assert!(
+            message.contains(&temp.path().join("PROMTP.md").display().to_string()),
+            "{message}"
+        );
+        assert!(
+            message.contains(&format!("working directory: {}", temp.path().display())),
+            "{message}"
+        );
+        assert!(
+            message.contains("-p") && message.contains("-P"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_only_file_is_empty() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("PROMPT.md"), " \n\t\n").unwrap();
+
+        let err = read_prompt_source(&file_config("PROMPT.md"), temp.path()).unwrap_err();
+        assert!(matches!(err, PromptSourceError::Empty { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_directory_is_not_a_prompt_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("prompts")).unwrap();
+
+        let err = read_prompt_source(&file_config("prompts"), temp.path()).unwrap_err();
+        assert!(matches!(err, PromptSourceError::NotAFile { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_inline_prompt_wins_and_must_not_be_blank() {
+        let temp = TempDir::new().unwrap();
+        let mut config = file_config("missing.md");
+        config.prompt = Some("Inline task".to_string());
+        assert_eq!(
+            read_prompt_source(&config, temp.path()).unwrap(),
+            "Inline task"
+        );
+
+        config.prompt = Some("   ".to_string());
+        assert!(matches!(
+            read_prompt_source(&config, temp.path()),
+            Err(PromptSourceError::EmptyInline)
+        ));
+
+        let err = read_prompt_source(&file_config(""), temp.path()).unwrap_err();
+        assert!(matches!(err, PromptSourceError::Missing { .. }));
+    }
+}
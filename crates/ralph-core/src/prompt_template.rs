@@ -0,0 +1,209 @@
+//! Variable substitution for prompts.
+//!
+//! Prompts may contain `{{name}}` placeholders that are filled from the
+//! `vars:` map in `ralph.yml` and `--var name=value` CLI flags, so one prompt
+//! file can serve many services. Variable values may themselves contain
+//! placeholders. A placeholder is only recognised when its name is an
+//! identifier (letters, digits, `_`, `-`, not starting with a digit), so
+//! text like `${{ secrets.TOKEN }}` passes through untouched. `\{{` produces
+//! a literal `{{`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Errors from rendering a prompt template.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PromptTemplateError {
+    /// The prompt references variables that have no value.
+    #[error("Prompt has unresolved placeholders: {}", format_names(.0))]
+    MissingVars(Vec<String>),
+
+    /// A variable's value refers back to itself.
+    #[error("Prompt variable '{0}' refers to itself ({1})")]
+    Cycle(String, String),
+}
+
+fn format_names(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{{{{{name}}}}}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Fills `{{name}}` placeholders in `template` from `vars`.
+///
+/// Fails with every missing name at once rather than stopping at the first.
+pub fn render_prompt(
+    template: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<String, PromptTemplateError> {
+    let mut missing = BTreeSet::new();
+    let rendered = expand(template, vars, &mut Vec::new(), &mut missing)?;
+    if missing.is_empty() {
+        Ok(rendered)
+    } else {
+        Err(PromptTemplateError::MissingVars(
+            missing.into_iter().collect(),
+        ))
+    }
+}
+
+/// Fills the `{{name}}` placeholders that `vars` defines, leaving any
+/// others as written.
+pub fn render_known(
+    template: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<String, PromptTemplateError> {
+    expand(template, vars, &mut Vec::new(), &mut BTreeSet::new())
+}
+
+fn expand(
+    text: &str,
+    vars: &BTreeMap<String, String>,
+    stack: &mut Vec<String>,
+    missing: &mut BTreeSet<String>,
+) -> Result<String, PromptTemplateError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("\\{{") {
+            out.push_str("{{");
+            rest = after;
+            continue;
+        }
+
+        if let Some((name, len)) = placeholder(rest) {
+            if stack.iter().any(|seen| seen == name) {
+                let mut chain = stack.clone();
+                chain.push(name.to_string());
+                return Err(PromptTemplateError::Cycle(
+                    name.to_string(),
+                    chain.join(" -> "),
+                ));
+            }
+            match vars.get(name) {
+                Some(value) => {
+                    stack.push(name.to_string());
+                    out.push_str(&expand(value, vars, stack, missing)?);
+                    stack.pop();
+                }
+                None => {
+                    missing.insert(name.to_string());
+                    out.push_str(&rest[..len]);
+                }
+            }
+            rest = &rest[len..];
+            continue;
+        }
+
+        out.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+
+    Ok(out)
+}
+
+/// Parses a placeholder at the start of `text`, returning its name and length.
+fn placeholder(text: &str) -> Option<(&str, usize)> {
+    let inner_start = text.strip_prefix("{{")?;
+    let end = inner_start.find("}}")?;
+    let name = inner_start[..end].trim();
+    is_identifier(name).then_some((name, end + 4))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn substitutes_placeholders() {
+        let vars = vars(&[("service", "billing"), ("ticket", "OPS-42")]);
+        let rendered =
+            render_prompt("Fix {{service}} for {{ ticket }} in {{service}}/src", &vars).unwrap();
+        assert_eq!(rendered, "Fix billing for OPS-42 in billing/src");
+    }
+
+    #[test]
+    fn text_without_placeholders_is_unchanged() {
+        let text = "Plain prompt with { braces } and ${{ secrets.TOKEN }}";
+        assert_eq!(render_prompt(text, &BTreeMap::new()).unwrap(), text);
+    }
+
+    #[test]
+    fn values_can_nest_placeholders() {
+        let vars = vars(&[
+            ("path", "services/{{service}}"),
+            ("service", "billing"),
+            ("task", "Refactor {{path}}"),
+        ]);
+        assert_eq!(
+            render_prompt("{{task}} now", &vars).unwrap(),
+            "Refactor services/billing now"
+        );
+        // A variable used in two places is not a cycle
+        assert_eq!(
+            render_prompt("{{path}} {{path}}", &vars).unwrap(),
+            "services/billing services/billing"
+        );
+    }
+
+    #[test]
+    fn nested_cycles_are_rejected() {
+        let vars = vars(&[("a", "{{b}}"), ("b", "x {{a}}")]);
+        let err = render_prompt("{{a}}", &vars).unwrap_err();
+        assert_eq!(
+            err,
+            PromptTemplateError::Cycle("a".to_string(), "a -> b -> a".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_vars_are_all_reported() {
+        let vars = vars(&[("nested", "{{inner}}")]);
+        let err = render_prompt("{{zeta}} {{alpha}} {{nested}} {{alpha}}", &vars).unwrap_err();
+        assert_eq!(
+            err,
+            PromptTemplateError::MissingVars(vec![
+                "alpha".to_string(),
+                "inner".to_string(),
+                "zeta".to_string()
+            ])
+        );
+        assert_eq!(
+            err.to_string(),
+            "Prompt has unresolved placeholders: {{alpha}}, {{inner}}, {{zeta}}"
+        );
+    }
+
+    #[test]
+    fn escaped_braces_stay_literal() {
+        let vars = vars(&[("name", "ralph")]);
+        assert_eq!(
+            render_prompt(r"Use \{{name}} for {{name}}", &vars).unwrap(),
+            "Use {{name}} for ralph"
+        );
+        // Escapes don't need the variable to exist
+        assert_eq!(
+            render_prompt(r"\{{undefined}}", &BTreeMap::new()).unwrap(),
+            "{{undefined}}"
+        );
+        // Extra braces around a placeholder are kept
+        assert_eq!(render_prompt("{{{name}}}", &vars).unwrap(), "{ralph}");
+    }
+}
@@ -0,0 +1,243 @@
+//! `.ralphignore`: paths Ralph never shows the agent.
+//!
+//! The file sits at the workspace root and uses gitignore syntax. Ignored
+//! files are left out of everything Ralph itself puts into a prompt: the
+//! injected scratchpad, primed memories that mention them, and the context
+//! file listing. The agent can still open them with its own tools; this
+//! only keeps Ralph from volunteering their contents.
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Name of the ignore file at the workspace root.
+pub const RALPHIGNORE_FILE: &str = ".ralphignore";
+
+/// Characters stripped from words before they are treated as paths.
+const PATH_DELIMITERS: &[char] = &[
+    '`', '\'', '"', '(', ')', '[', ']', '{', '}', '<', '>', ',', ';', ':', '!', '?',
+];
+
+/// Compiled `.ralphignore` rules for one workspace.
+#[derive(Debug, Clone)]
+pub struct RalphIgnore {
+    root: PathBuf,
+    matcher: Gitignore,
+    /// Lines of the ignore file, for reporting which line a rule came from.
+    lines: Vec<String>,
+}
+
+/// The `.ralphignore` rule that decides whether a path is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreRule {
+    /// The pattern as written.
+    pub pattern: String,
+
+    /// 1-indexed line of the pattern in `.ralphignore`.
+    pub line: Option<usize>,
+
+    /// True for a `!pattern` that re-includes the path.
+    pub negated: bool,
+}
+
+impl RalphIgnore {
+    /// Rules that ignore nothing.
+    pub fn empty(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            matcher: Gitignore::empty(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Loads `.ralphignore` from `root`.
+    ///
+    /// A missing file ignores nothing. Invalid patterns are logged and
+    /// skipped so one typo doesn't disable the whole file.
+    pub fn load(root: &Path) -> Self {
+        let path = root.join(RALPHIGNORE_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(root, &content, Some(&path)),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!(path = %path.display(), error = %e, "Failed to read .ralphignore");
+                }
+                Self::empty(root)
+            }
+        }
+    }
+
+    /// Builds the rules from `content` in gitignore syntax.
+    pub fn parse(root: &Path, content: &str, source: Option<&Path>) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for (index, line) in content.lines().enumerate() {
+            if let Err(e) = builder.add_line(source.map(Path::to_path_buf), line) {
+                warn!(line = index + 1, error = %e, "Skipping invalid .ralphignore pattern");
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to compile .ralphignore, ignoring nothing");
+            Gitignore::empty()
+        });
+        Self {
+            root: root.to_path_buf(),
+            matcher,
+            lines: content.lines().map(str::to_string).collect(),
+        }
+    }
+
+    /// Returns true if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_empty()
+    }
+
+    /// Returns true if `path` or one of its parent directories is ignored.
+    ///
+    /// Relative paths are taken relative to the workspace root. Paths
+    /// outside the workspace are never ignored.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.rule_for(path).is_some_and(|rule| !rule.negated)
+    }
+
+    /// Returns the rule that decides `path`, if any rule matches it or one
+    /// of its parent directories.
+    pub fn rule_for(&self, path: &Path) -> Option<IgnoreRule> {
+        if self.is_empty() {
+            return None;
+        }
+        let relative = self.relative(path)?;
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        let is_dir = self.root.join(relative).is_dir();
+        let (glob, negated) = match self.matcher.matched_path_or_any_parents(relative, is_dir) {
+            Match::None => return None,
+            Match::Ignore(glob) => (glob, false),
+            Match::Whitelist(glob) => (glob, true),
+        };
+        let pattern = glob.original().to_string();
+        // Later lines take precedence, so report the last occurrence
+        let line = self
+            .lines
+            .iter()
+            .rposition(|line| line.trim() == pattern)
+            .map(|index| index + 1);
+        Some(IgnoreRule {
+            pattern,
+            line,
+            negated,
+        })
+    }
+
+    /// Returns true if `text` mentions an ignored path.
+    ///
+    /// Words containing `/` or `.` are checked as paths, after stripping
+    /// surrounding quotes, brackets and punctuation. URLs are skipped.
+    pub fn mentions_ignored(&self, text: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        text.split_whitespace()
+            .map(|word| {
+                word.trim_start_matches(PATH_DELIMITERS)
+                    .trim_end_matches(|c| c == '.' || PATH_DELIMITERS.contains(&c))
+            })
+            .filter(|word| !word.is_empty() && !word.contains("://"))
+            .filter(|word| word.contains('/') || word.contains('.'))
+            .any(|word| self.is_ignored(Path::new(word)))
+    }
+
+    /// `path` relative to the root, or `None` if it lies outside it.
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if !path.has_root() {
+            return Some(path.strip_prefix(".").unwrap_or(path));
+        }
+        let root = std::path::absolute(&self.root).ok()?;
+        path.strip_prefix(root).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const RULES: &str =
+        "# secrets\n*.pem\nfixtures/secrets/\n!fixtures/secrets/README.md\nCargo.lock\n";
+
+    fn rules(root: &Path) -> RalphIgnore {
+        RalphIgnore::parse(root, RULES, None)
+    }
+
+    #[test]
+    fn test_missing_file_ignores_nothing() {
+        let temp = TempDir::new().unwrap();
+        let ignore = RalphIgnore::load(temp.path());
+
+        assert!(ignore.is_empty());
+        assert!(!ignore.is_ignored(Path::new("Cargo.lock")));
+    }
+
+    #[test]
+    fn test_load_reads_workspace_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join(RALPHIGNORE_FILE), RULES).unwrap();
+
+        let ignore = RalphIgnore::load(temp.path());
+
+        assert!(ignore.is_ignored(Path::new("Cargo.lock")));
+        assert!(ignore.is_ignored(&temp.path().join("keys/server.pem")));
+        assert!(!ignore.is_ignored(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn test_rule_for_reports_pattern_and_line() {
+        let temp = TempDir::new().unwrap();
+        let ignore = rules(temp.path());
+
+        let rule = ignore
+            .rule_for(Path::new("fixtures/secrets/token.txt"))
+            .unwrap();
+        assert_eq!(rule.pattern, "fixtures/secrets/");
+        assert_eq!(rule.line, Some(3));
+        assert!(!rule.negated);
+
+        let rule = ignore
+            .rule_for(Path::new("fixtures/secrets/README.md"))
+            .unwrap();
+        assert!(rule.negated);
+        assert_eq!(rule.pattern, "!fixtures/secrets/README.md");
+        assert_eq!(rule.line, Some(4));
+        assert!(!ignore.is_ignored(Path::new("fixtures/secrets/README.md")));
+
+        assert_eq!(ignore.rule_for(Path::new("src/lib.rs")), None);
+    }
+
+    #[test]
+    fn test_paths_outside_workspace_are_not_ignored() {
+        let temp = TempDir::new().unwrap();
+        let ignore = rules(temp.path());
+
+        assert!(!ignore.is_ignored(Path::new("/elsewhere/Cargo.lock")));
+    }
+
+    #[test]
+    fn test_mentions_ignored() {
+        let temp = TempDir::new().unwrap();
+        let ignore = rules(temp.path());
+
+        assert!(ignore.mentions_ignored("Never hand-edit `Cargo.lock`."));
+        assert!(ignore.mentions_ignored("The key lives in (certs/dev.pem)"));
+        assert!(!ignore.mentions_ignored("Run cargo test before committing"));
+        assert!(!ignore.mentions_ignored("See https://example.com/dev.pem"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_skipped() {
+        let temp = TempDir::new().unwrap();
+        let ignore = RalphIgnore::parse(temp.path(), "a[\n*.pem\n", None);
+
+        assert!(ignore.is_ignored(Path::new("dev.pem")));
+    }
+}
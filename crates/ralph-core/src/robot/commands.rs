@@ -1,7 +1,21 @@
-use std::path::Path;
+//! Bot commands shared by the Telegram and Slack bots.
+//!
+//! Responses are HTML in Telegram's subset (`<b>`, `<i>`, `<code>`, `<pre>`);
+//! other transports convert them for display.
 
-use crate::bot::escape_html;
-use crate::loop_lock::{LockState, lock_path, lock_state};
+use std::path::{Path, PathBuf};
+
+use ralph_proto::Topic;
+
+use super::loop_state::{LockState, lock_path, lock_state};
+use super::prompt_queue::PromptQueue;
+use crate::{TaskPriority, TaskStatus, TaskStore};
+
+/// Events shown by `/events` when no count is given.
+const DEFAULT_EVENTS_COUNT: usize = 20;
+
+/// Upper bound on the count accepted by `/events`.
+const MAX_EVENTS_COUNT: usize = 200;
 
 /// Check if a message is a bot command (starts with `/`).
 pub fn is_command(text: &str) -> bool {
@@ -14,21 +28,24 @@ pub fn is_command(text: &str) -> bool {
 /// or `None` if the command was not recognized (so the caller can
 /// treat it as a regular message).
 pub fn handle_command(text: &str, workspace_root: &Path) -> Option<String> {
-    let (command, _args) = parse_command(text);
+    let (command, args) = parse_command(text);
     match command {
         "/help" => Some(cmd_help()),
         "/status" => Some(cmd_status(workspace_root)),
         "/tasks" => Some(cmd_tasks(workspace_root)),
         "/memories" => Some(cmd_memories(workspace_root)),
         "/tail" => Some(cmd_tail(workspace_root)),
+        "/events" => Some(cmd_events(workspace_root, args)),
         "/restart" => Some(cmd_restart(workspace_root)),
         "/stop" => Some(cmd_stop(workspace_root)),
+        "/queue" => Some(cmd_queue(workspace_root, args)),
+        "/cancel" => Some(cmd_cancel(workspace_root, args)),
         _ => None,
     }
 }
 
 /// Split a command string into the command name and optional arguments.
-fn parse_command(text: &str) -> (&str, &str) {
+pub fn parse_command(text: &str) -> (&str, &str) {
     // Handle @bot suffix: /status@ralph_bot -> /status
     if let Some((first, rest)) = text.split_once(char::is_whitespace) {
         let cmd = first.split('@').next().unwrap_or(first);
@@ -39,6 +56,55 @@ fn parse_command(text: &str) -> (&str, &str) {
     }
 }
 
+/// Split a response into chunks of at most `limit` characters.
+///
+/// Splits on line boundaries so HTML tags (which never span lines in command
+/// responses) stay balanced; a single over-long line is split mid-line.
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for line in text.split('\n') {
+        let line_len = line.chars().count();
+        let separator = usize::from(!current.is_empty());
+
+        if current_len + separator + line_len > limit && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        if line_len > limit {
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(limit) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push('\n');
+            current_len += 1;
+        }
+        current.push_str(line);
+        current_len += line_len;
+    }
+
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Escape special HTML characters for Telegram's HTML parse mode.
+///
+/// Telegram requires `<`, `>`, and `&` to be escaped in HTML-formatted messages.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 fn truncate_with_ellipsis(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         input.to_string()
@@ -58,8 +124,11 @@ fn cmd_help() -> String {
         "/tasks — Open tasks",
         "/memories — Recent memories",
         "/tail — Last 20 events",
+        "/events [pattern] [count] — Recent events, optionally filtered by topic (e.g. /events build.* 10)",
         "/restart — Restart the orchestration loop",
         "/stop — Stop the orchestration loop",
+        "/queue [prompt] — List queued prompts, or queue one to run after this loop",
+        "/cancel &lt;n&gt; — Drop queued prompt n",
         "/help — This message",
     ]
     .join("\n")
@@ -78,11 +147,17 @@ fn cmd_status(workspace_root: &Path) -> String {
     };
 
     if state == LockState::Inactive {
-        return "No active loop (no lock file found).".to_string();
+        return with_queue_depth(
+            "No active loop (no lock file found).".to_string(),
+            workspace_root,
+        );
     }
 
     if state == LockState::Stale {
-        return "No active loop (stale lock file found).".to_string();
+        return with_queue_depth(
+            "No active loop (stale lock file found).".to_string(),
+            workspace_root,
+        );
     }
 
     let lock_path = lock_path(workspace_root);
@@ -145,9 +220,79 @@ fn cmd_status(workspace_root: &Path) -> String {
     lines.push(String::new());
     lines.push(format!("Prompt: {}", escape_html(&prompt_preview)));
 
+    with_queue_depth(lines.join("\n"), workspace_root)
+}
+
+/// Appends the number of queued prompts to a status message, if any.
+pub fn with_queue_depth(status: String, workspace_root: &Path) -> String {
+    let depth = PromptQueue::new(workspace_root).map_or(0, |queue| queue.len());
+    if depth == 0 {
+        status
+    } else {
+        format!("{}\nQueued prompts: <code>{}</code>", status, depth)
+    }
+}
+
+/// `/queue [prompt]` — List queued prompts, or queue a new one.
+pub fn cmd_queue(workspace_root: &Path, args: &str) -> String {
+    let queue = match PromptQueue::new(workspace_root) {
+        Ok(queue) => queue,
+        Err(e) => return format!("Failed to open queue: {}", escape_html(&e.to_string())),
+    };
+
+    if !args.is_empty() {
+        return match queue.push(args) {
+            Ok(position) => format!(
+                "Queued as #{}. It starts when the loops ahead of it finish.",
+                position
+            ),
+            Err(e) => format!("Failed to queue prompt: {}", escape_html(&e.to_string())),
+        };
+    }
+
+    let prompts = match queue.list() {
+        Ok(prompts) => prompts,
+        Err(e) => return format!("Failed to read queue: {}", escape_html(&e.to_string())),
+    };
+    if prompts.is_empty() {
+        return "No queued prompts.".to_string();
+    }
+
+    let mut lines = vec![
+        format!("<b>Queued Prompts ({})</b>", prompts.len()),
+        String::new(),
+    ];
+    for (i, queued) in prompts.iter().enumerate() {
+        lines.push(format!(
+            "{}. {} <i>({})</i>",
+            i + 1,
+            escape_html(&truncate_with_ellipsis(&queued.prompt, 100)),
+            queued.queued_at.format("%H:%M")
+        ));
+    }
     lines.join("\n")
 }
 
+/// `/cancel <n>` — Drop the queued prompt at position `n`.
+pub fn cmd_cancel(workspace_root: &Path, args: &str) -> String {
+    let Ok(position) = args.parse::<usize>() else {
+        return "Usage: /cancel &lt;n&gt; (see /queue for positions)".to_string();
+    };
+    let queue = match PromptQueue::new(workspace_root) {
+        Ok(queue) => queue,
+        Err(e) => return format!("Failed to open queue: {}", escape_html(&e.to_string())),
+    };
+    match queue.cancel(position) {
+        Ok(Some(cancelled)) => format!(
+            "Cancelled #{}: {}",
+            position,
+            escape_html(&truncate_with_ellipsis(&cancelled.prompt, 100))
+        ),
+        Ok(None) => format!("No queued prompt #{}.", position),
+        Err(e) => format!("Failed to update queue: {}", escape_html(&e.to_string())),
+    }
+}
+
 /// Count iterations from the current events file.
 fn count_iterations(workspace_root: &Path) -> usize {
     // Read current-events pointer
@@ -193,41 +338,20 @@ fn cmd_tasks(workspace_root: &Path) -> String {
         return "No tasks file found.".to_string();
     }
 
-    let content = match std::fs::read_to_string(&tasks_path) {
-        Ok(c) => c,
+    let store = match TaskStore::load(&tasks_path) {
+        Ok(store) => store,
         Err(e) => return format!("Failed to read tasks: {}", escape_html(&e.to_string())),
     };
 
-    let mut open_tasks: Vec<(String, String, u64)> = Vec::new(); // (id, title, priority)
-    let mut closed_count = 0u32;
+    let mut open_tasks = store.by_status(TaskStatus::Open);
+    let closed_count = store.by_status(TaskStatus::Closed).len();
 
-    for line in content.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        if let Ok(task) = serde_json::from_str::<serde_json::Value>(line) {
-            let status = task.get("status").and_then(|v| v.as_str()).unwrap_or("");
-            if status == "open" {
-                let id = task
-                    .get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("?")
-                    .to_string();
-                let title = task
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("untitled")
-                    .to_string();
-                let priority = task.get("priority").and_then(|v| v.as_u64()).unwrap_or(3);
-                open_tasks.push((id, title, priority));
-            } else if status == "closed" {
-                closed_count += 1;
-            }
-        }
-    }
-
-    // Sort by priority (lower = higher priority)
-    open_tasks.sort_by_key(|t| t.2);
+    // Same order as `ralph tools task ready`: priority (lower = higher), then oldest
+    open_tasks.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.created.cmp(&b.created))
+    });
 
     if open_tasks.is_empty() {
         return format!("No open tasks. ({} completed)", closed_count);
@@ -240,17 +364,18 @@ fn cmd_tasks(workspace_root: &Path) -> String {
     )];
     lines.push(String::new());
 
-    for (id, title, priority) in &open_tasks {
-        let priority_label = match priority {
-            1 => "P1",
-            2 => "P2",
-            _ => "P3",
+    for task in &open_tasks {
+        let priority_label = match task.priority_level() {
+            TaskPriority::High => "🔴 high",
+            TaskPriority::Normal => "⚪ normal",
+            TaskPriority::Low => "🔵 low",
         };
         lines.push(format!(
-            "{}  <code>{}</code>\n    {}",
+            "P{} {}  <code>{}</code>\n    {}",
+            task.priority,
             priority_label,
-            escape_html(id),
-            escape_html(title)
+            escape_html(&task.id),
+            escape_html(&task.title)
         ));
     }
 
@@ -399,31 +524,41 @@ fn cmd_stop(workspace_root: &Path) -> String {
     }
 }
 
-/// `/tail` — Last 20 lines of the current events file.
-fn cmd_tail(workspace_root: &Path) -> String {
-    // Find current events file
+/// Path of the active loop's events file.
+///
+/// Follows the `.ralph/current-events` pointer when present.
+fn current_events_path(workspace_root: &Path) -> Result<PathBuf, String> {
     let pointer_path = workspace_root.join(".ralph/current-events");
-    let events_path = if pointer_path.exists() {
+    if pointer_path.exists() {
         match std::fs::read_to_string(&pointer_path) {
-            Ok(p) => workspace_root.join(p.trim()),
-            Err(e) => {
-                return format!(
-                    "Failed to read current-events pointer: {}",
-                    escape_html(&e.to_string())
-                );
-            }
+            Ok(p) => Ok(workspace_root.join(p.trim())),
+            Err(e) => Err(format!(
+                "Failed to read current-events pointer: {}",
+                escape_html(&e.to_string())
+            )),
         }
     } else {
-        workspace_root.join(".ralph/events.jsonl")
-    };
+        Ok(workspace_root.join(".ralph/events.jsonl"))
+    }
+}
+
+/// Read the active loop's events file, or a user-facing message on failure.
+fn read_events(workspace_root: &Path) -> Result<String, String> {
+    let events_path = current_events_path(workspace_root)?;
 
     if !events_path.exists() {
-        return "No events file found.".to_string();
+        return Err("No events file found.".to_string());
     }
 
-    let content = match std::fs::read_to_string(&events_path) {
+    std::fs::read_to_string(&events_path)
+        .map_err(|e| format!("Failed to read events: {}", escape_html(&e.to_string())))
+}
+
+/// `/tail` — Last 20 lines of the current events file.
+fn cmd_tail(workspace_root: &Path) -> String {
+    let content = match read_events(workspace_root) {
         Ok(c) => c,
-        Err(e) => return format!("Failed to read events: {}", escape_html(&e.to_string())),
+        Err(message) => return message,
     };
 
     let all_lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
@@ -483,6 +618,178 @@ fn cmd_tail(workspace_root: &Path) -> String {
     lines.join("\n")
 }
 
+/// Arguments accepted by `/events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EventsQuery {
+    /// Topic pattern to filter by (`None` shows every topic).
+    pattern: Option<String>,
+
+    /// Number of most recent matching events to show.
+    count: usize,
+}
+
+/// Parse `/events [pattern] [count]` arguments, in either order.
+fn parse_events_args(args: &str) -> Result<EventsQuery, String> {
+    let mut pattern = None;
+    let mut count = None;
+
+    for arg in args.split_whitespace() {
+        if let Ok(n) = arg.parse::<usize>() {
+            if count.is_some() {
+                return Err(events_usage("Only one count is allowed."));
+            }
+            if n == 0 || n > MAX_EVENTS_COUNT {
+                return Err(events_usage(&format!(
+                    "Count must be between 1 and {}.",
+                    MAX_EVENTS_COUNT
+                )));
+            }
+            count = Some(n);
+        } else {
+            if pattern.is_some() {
+                return Err(events_usage("Only one topic pattern is allowed."));
+            }
+            if !is_valid_topic_pattern(arg) {
+                return Err(events_usage(&format!(
+                    "Invalid topic pattern <code>{}</code>. Patterns are dot-separated words where <code>*</code> matches one whole segment, e.g. <code>review.*</code>.",
+                    escape_html(arg)
+                )));
+            }
+            pattern = Some(arg.to_string());
+        }
+    }
+
+    Ok(EventsQuery {
+        pattern,
+        count: count.unwrap_or(DEFAULT_EVENTS_COUNT),
+    })
+}
+
+/// Returns true if `pattern` is a topic or a glob the topic matcher understands.
+///
+/// Wildcards only match whole segments, so `build*` is rejected in favour of
+/// `build.*`.
+fn is_valid_topic_pattern(pattern: &str) -> bool {
+    pattern.split('.').all(|segment| {
+        segment == "*"
+            || (!segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-'))
+    })
+}
+
+fn events_usage(problem: &str) -> String {
+    format!(
+        "{}\n\nUsage: <code>/events [pattern] [count]</code>\nExample: <code>/events build.* 10</code>",
+        problem
+    )
+}
+
+/// `/events [pattern] [count]` — Recent events, optionally filtered by topic.
+fn cmd_events(workspace_root: &Path, args: &str) -> String {
+    let query = match parse_events_args(args) {
+        Ok(q) => q,
+        Err(message) => return message,
+    };
+
+    let content = match read_events(workspace_root) {
+        Ok(c) => c,
+        Err(message) => return message,
+    };
+
+    let events: Vec<serde_json::Value> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+
+    if events.is_empty() {
+        return "Events file is empty.".to_string();
+    }
+
+    let topic_of = |event: &serde_json::Value| -> String {
+        event
+            .get("topic")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string()
+    };
+
+    let matcher = query.pattern.as_deref().map(Topic::new);
+    let matching: Vec<&serde_json::Value> = events
+        .iter()
+        .filter(|e| matcher.as_ref().is_none_or(|m| m.matches_str(&topic_of(e))))
+        .collect();
+
+    if matching.is_empty() {
+        let mut topics: Vec<String> = events.iter().map(topic_of).collect();
+        topics.sort();
+        topics.dedup();
+        let pattern = query.pattern.as_deref().unwrap_or("*");
+        return format!(
+            "No events matching <code>{}</code> among {} events.\n\nTopics seen: {}",
+            escape_html(pattern),
+            events.len(),
+            escape_html(&truncate_with_ellipsis(&topics.join(", "), 500))
+        );
+    }
+
+    let start = matching.len().saturating_sub(query.count);
+    let shown = &matching[start..];
+
+    let title = match query.pattern {
+        Some(ref pattern) => format!("<b>Events matching {}</b>", escape_html(pattern)),
+        None => "<b>Recent Events</b>".to_string(),
+    };
+    let mut lines = vec![format!(
+        "{} (showing {} of {})",
+        title,
+        shown.len(),
+        matching.len()
+    )];
+    lines.push(String::new());
+
+    for event in shown {
+        let time = event
+            .get("ts")
+            .and_then(|v| v.as_str())
+            .map(format_event_time)
+            .unwrap_or_default();
+        let iteration = event
+            .get("iteration")
+            .and_then(|v| v.as_u64())
+            .map(|i| format!(" #{}", i))
+            .unwrap_or_default();
+        let payload = match event.get("payload") {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        let payload_preview = truncate_with_ellipsis(&payload.replace('\n', " "), 80);
+
+        let mut line = format!(
+            "{} <code>{}</code>{}",
+            escape_html(&time),
+            escape_html(&topic_of(event)),
+            iteration
+        );
+        if !payload_preview.is_empty() {
+            line.push_str(&format!("\n  {}", escape_html(&payload_preview)));
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+/// Format an event timestamp as `HH:MM:SS`, falling back to the raw value.
+fn format_event_time(ts: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|_| ts.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -493,6 +800,13 @@ mod tests {
         std::fs::create_dir_all(dir.path().join(".ralph/agent")).unwrap();
     }
 
+    #[test]
+    fn escape_html_handles_special_chars() {
+        assert_eq!(escape_html("a < b & c > d"), "a &lt; b &amp; c &gt; d");
+        assert_eq!(escape_html("no specials"), "no specials");
+        assert_eq!(escape_html(""), "");
+    }
+
     #[test]
     fn is_command_recognizes_slash() {
         assert!(is_command("/help"));
@@ -633,6 +947,36 @@ mod tests {
         assert!(!result.contains("Fix bug")); // closed tasks not listed
     }
 
+    #[test]
+    fn cmd_tasks_orders_by_priority_and_shows_level() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+
+        let tasks_path = dir.path().join(".ralph/agent/tasks.jsonl");
+        std::fs::write(
+            &tasks_path,
+            concat!(
+                r#"{"id":"task-1","title":"Later","status":"open","priority":5,"created":"2025-01-01T00:00:00Z"}"#,
+                "\n",
+                r#"{"id":"task-2","title":"Second","status":"open","priority":3,"created":"2025-01-03T00:00:00Z"}"#,
+                "\n",
+                r#"{"id":"task-3","title":"First","status":"open","priority":3,"created":"2025-01-02T00:00:00Z"}"#,
+                "\n",
+                r#"{"id":"task-4","title":"Urgent","status":"open","priority":1,"created":"2025-01-04T00:00:00Z"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let result = cmd_tasks(dir.path());
+        let position = |title: &str| result.find(title).unwrap();
+        assert!(position("Urgent") < position("First"));
+        assert!(position("First") < position("Second"));
+        assert!(position("Second") < position("Later"));
+        assert!(result.contains("P1 🔴 high"));
+        assert!(result.contains("P5 🔵 low"));
+    }
+
     #[test]
     fn cmd_tasks_all_closed() {
         let dir = TempDir::new().unwrap();
@@ -751,6 +1095,133 @@ mod tests {
         assert!(result.len() < 300); // Truncated, not full 200 chars
     }
 
+    fn write_mixed_events(dir: &TempDir) {
+        let events_path = dir.path().join(".ralph/events.jsonl");
+        let mut f = std::fs::File::create(&events_path).unwrap();
+        for (i, topic) in ["build.start", "review.done", "build.done", "build.blocked"]
+            .iter()
+            .enumerate()
+        {
+            writeln!(
+                f,
+                r#"{{"topic":"{}","iteration":{},"payload":"step {}","ts":"2026-01-30T10:00:0{}Z"}}"#,
+                topic, i, i, i
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn parse_events_args_defaults() {
+        assert_eq!(
+            parse_events_args(""),
+            Ok(EventsQuery {
+                pattern: None,
+                count: DEFAULT_EVENTS_COUNT
+            })
+        );
+    }
+
+    #[test]
+    fn parse_events_args_pattern_and_count_in_any_order() {
+        let expected = Ok(EventsQuery {
+            pattern: Some("build.*".to_string()),
+            count: 10,
+        });
+        assert_eq!(parse_events_args("build.* 10"), expected);
+        assert_eq!(parse_events_args("10 build.*"), expected);
+        assert_eq!(
+            parse_events_args("5"),
+            Ok(EventsQuery {
+                pattern: None,
+                count: 5
+            })
+        );
+        assert_eq!(
+            parse_events_args("review.done"),
+            Ok(EventsQuery {
+                pattern: Some("review.done".to_string()),
+                count: DEFAULT_EVENTS_COUNT
+            })
+        );
+    }
+
+    #[test]
+    fn parse_events_args_rejects_invalid_input() {
+        assert!(parse_events_args("build*").unwrap_err().contains("build.*"));
+        assert!(parse_events_args("build..done").is_err());
+        assert!(parse_events_args("<b>").is_err());
+        assert!(parse_events_args("0").unwrap_err().contains("between 1"));
+        assert!(parse_events_args("1000").is_err());
+        assert!(parse_events_args("5 10").is_err());
+        assert!(parse_events_args("build.* review.*").is_err());
+    }
+
+    #[test]
+    fn cmd_events_filters_by_pattern_and_count() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+        write_mixed_events(&dir);
+
+        let result = cmd_events(dir.path(), "build.* 2");
+
+        assert!(result.contains("showing 2 of 3"));
+        assert!(!result.contains("build.start"));
+        assert!(result.contains("build.done"));
+        assert!(result.contains("build.blocked"));
+        assert!(!result.contains("review.done"));
+        assert!(result.contains("10:00:03"));
+    }
+
+    #[test]
+    fn cmd_events_no_matches_lists_topics() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+        write_mixed_events(&dir);
+
+        let result = cmd_events(dir.path(), "deploy.*");
+
+        assert!(result.contains("No events matching"));
+        assert!(result.contains("review.done"));
+    }
+
+    #[test]
+    fn cmd_events_no_events_file() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+        assert!(cmd_events(dir.path(), "").contains("No events file"));
+    }
+
+    #[test]
+    fn handle_command_recognizes_events() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+        assert!(handle_command("/events build.*", dir.path()).is_some());
+    }
+
+    #[test]
+    fn split_message_respects_limit_on_line_boundaries() {
+        let text = (0..10)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let chunks = split_message(&text, 20);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 20));
+        assert_eq!(chunks.join("\n"), text);
+    }
+
+    #[test]
+    fn split_message_short_text_and_long_line() {
+        assert_eq!(split_message("hello", 4096), vec!["hello"]);
+
+        let chunks = split_message(&"x".repeat(25), 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+    }
+
     #[test]
     fn cmd_restart_no_active_loop() {
         let dir = TempDir::new().unwrap();
@@ -856,4 +1327,56 @@ mod tests {
         let result = cmd_help();
         assert!(result.contains("/stop"));
     }
+
+    #[test]
+    fn cmd_queue_adds_lists_and_cancels() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+
+        assert_eq!(
+            handle_command("/queue", dir.path()).unwrap(),
+            "No queued prompts."
+        );
+        assert!(
+            handle_command("/queue Fix the <flaky> test", dir.path())
+                .unwrap()
+                .contains("Queued as #1")
+        );
+        handle_command("/queue Write docs", dir.path()).unwrap();
+
+        let listing = handle_command("/queue", dir.path()).unwrap();
+        assert!(listing.contains("Queued Prompts (2)"));
+        assert!(listing.contains("1. Fix the &lt;flaky&gt; test"));
+        assert!(listing.contains("2. Write docs"));
+
+        assert!(
+            handle_command("/cancel 1", dir.path())
+                .unwrap()
+                .contains("Cancelled #1")
+        );
+        assert!(
+            handle_command("/cancel 5", dir.path())
+                .unwrap()
+                .contains("No queued prompt #5")
+        );
+        assert!(
+            handle_command("/cancel x", dir.path())
+                .unwrap()
+                .starts_with("Usage")
+        );
+        let listing = handle_command("/queue", dir.path()).unwrap();
+        assert!(listing.contains("1. Write docs"));
+    }
+
+    #[test]
+    fn cmd_status_reports_queue_depth() {
+        let dir = TempDir::new().unwrap();
+        setup_workspace(&dir);
+        assert!(!cmd_status(dir.path()).contains("Queued prompts"));
+
+        cmd_queue(dir.path(), "next task");
+        cmd_queue(dir.path(), "task after that");
+
+        assert!(cmd_status(dir.path()).contains("Queued prompts: <code>2</code>"));
+    }
 }
@@ -1,22 +1,34 @@
+//! Whether a loop is running in a workspace, as seen by the bots.
+//!
+//! Probes the `flock` on `.ralph/loop.lock` that [`crate::LoopLock`] holds,
+//! so a lock file left behind by a crashed loop reads as stale.
+
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 
-pub(crate) const LOOP_LOCK_FILE: &str = ".ralph/loop.lock";
+/// Lock file held by the primary loop.
+pub const LOOP_LOCK_FILE: &str = ".ralph/loop.lock";
 
+/// State of a workspace's loop lock.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum LockState {
+pub enum LockState {
+    /// A loop holds the lock.
     Active,
+    /// No lock file exists.
     Inactive,
+    /// The lock file exists but nobody holds the lock.
     Stale,
 }
 
-pub(crate) fn lock_path(workspace_root: &Path) -> PathBuf {
+/// Path of the loop lock file in `workspace_root`.
+pub fn lock_path(workspace_root: &Path) -> PathBuf {
     workspace_root.join(LOOP_LOCK_FILE)
 }
 
-pub(crate) fn lock_state(workspace_root: &Path) -> anyhow::Result<LockState> {
+/// Probes the loop lock of `workspace_root` without taking it.
+pub fn lock_state(workspace_root: &Path) -> anyhow::Result<LockState> {
     let path = lock_path(workspace_root);
 
     if !path.exists() {
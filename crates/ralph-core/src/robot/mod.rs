@@ -0,0 +1,77 @@
+//! Transport-independent pieces of the human-in-the-loop bots.
+//!
+//! The Telegram and Slack crates implement [`ralph_proto::RobotService`] on
+//! top of this module: bot commands, question parsing, the prompt queue,
+//! and reading `human.response` events back from a loop's events file.
+
+pub mod commands;
+mod loop_state;
+pub mod prompt_queue;
+pub mod questions;
+mod responses;
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+pub use commands::{escape_html, handle_command, is_command, split_message};
+pub use loop_state::{LOOP_LOCK_FILE, LockState, lock_path, lock_state};
+pub use prompt_queue::{PromptQueue, QueuedPrompt};
+pub use questions::{Question, parse_question};
+pub use responses::{
+    QuestionThread, RESPONSE_POLL_INTERVAL, block_on, check_for_response, loop_events_path,
+    wait_for_response,
+};
+
+/// Maximum number of retry attempts for sending messages.
+pub const MAX_SEND_RETRIES: u32 = 3;
+
+/// Base delay for exponential backoff (1 second).
+pub const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Default cap on lifecycle notifications sent per hour.
+pub const DEFAULT_NOTIFICATIONS_PER_HOUR: u32 = 20;
+
+/// Masks a bot token for display, keeping its first and last 4 characters.
+pub fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() > 8 {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        "****".to_string()
+    }
+}
+
+/// Records a notification at `now` if fewer than `limit` were sent in the
+/// past hour, returning whether it may be sent. `limit == 0` never limits.
+pub fn allow_notification(sent: &mut Vec<DateTime<Utc>>, now: DateTime<Utc>, limit: u32) -> bool {
+    let window_start = now - chrono::Duration::hours(1);
+    sent.retain(|t| *t > window_start);
+
+    if limit > 0 && sent.len() >= limit as usize {
+        return false;
+    }
+    sent.push(now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_notification_caps_per_hour() {
+        let now = Utc::now();
+        let mut sent = vec![now - chrono::Duration::minutes(90)];
+
+        assert!(allow_notification(&mut sent, now, 2));
+        assert!(allow_notification(&mut sent, now, 2));
+        assert!(!allow_notification(&mut sent, now, 2));
+        // The entry older than an hour was pruned
+        assert_eq!(sent.len(), 2);
+
+        assert!(allow_notification(&mut sent, now, 0));
+    }
+}
@@ -0,0 +1,157 @@
+//! Prompts waiting for the daemon to start their loop.
+//!
+//! Prompts sent while a loop is running are queued in
+//! `.ralph/telegram-queue.json` and started one at a time as loops finish.
+//! The queue lives on disk so both the daemon and the running loop's
+//! `/queue` and `/cancel` commands see the same list, and it survives a
+//! daemon restart.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::file_lock::LockedFile;
+
+/// A prompt waiting for its loop to start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedPrompt {
+    /// The prompt to start the loop with.
+    pub prompt: String,
+
+    /// When the prompt was queued.
+    pub queued_at: DateTime<Utc>,
+}
+
+/// File-backed FIFO of prompts. Positions are 1-based, as shown to users.
+pub struct PromptQueue {
+    path: PathBuf,
+    file: LockedFile,
+}
+
+impl PromptQueue {
+    /// Opens the queue of the workspace at `workspace_root`.
+    pub fn new(workspace_root: &Path) -> io::Result<Self> {
+        let path = workspace_root.join(".ralph/telegram-queue.json");
+        let file = LockedFile::new(&path)?;
+        Ok(Self { path, file })
+    }
+
+    /// Returns the queued prompts, oldest first.
+    pub fn list(&self) -> io::Result<Vec<QueuedPrompt>> {
+        let content = self.file.read(&self.path)?;
+        Ok(parse(&content)?)
+    }
+
+    /// Number of queued prompts (0 if the queue can't be read).
+    pub fn len(&self) -> usize {
+        self.list().map(|prompts| prompts.len()).unwrap_or(0)
+    }
+
+    /// Returns true if no prompts are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a prompt and returns its position.
+    pub fn push(&self, prompt: &str) -> io::Result<usize> {
+        self.update(|prompts| {
+            prompts.push(QueuedPrompt {
+                prompt: prompt.to_string(),
+                queued_at: Utc::now(),
+            });
+            prompts.len()
+        })
+    }
+
+    /// Removes and returns the oldest prompt.
+    pub fn pop_front(&self) -> io::Result<Option<QueuedPrompt>> {
+        self.update(|prompts| (!prompts.is_empty()).then(|| prompts.remove(0)))
+    }
+
+    /// Removes the prompt at `position`, returning it if it existed.
+    pub fn cancel(&self, position: usize) -> io::Result<Option<QueuedPrompt>> {
+        self.update(|prompts| {
+            (position >= 1 && position <= prompts.len()).then(|| prompts.remove(position - 1))
+        })
+    }
+
+    /// Applies `f` to the queue under an exclusive lock and saves it.
+    fn update<T>(&self, f: impl FnOnce(&mut Vec<QueuedPrompt>) -> T) -> io::Result<T> {
+        self.file.with_exclusive_lock(|| {
+            let content = if self.path.exists() {
+                std::fs::read_to_string(&self.path)?
+            } else {
+                String::new()
+            };
+            let mut prompts = parse(&content)?;
+            let result = f(&mut prompts);
+            std::fs::write(&self.path, serde_json::to_string_pretty(&prompts)?)?;
+            Ok(result)
+        })
+    }
+}
+
+fn parse(content: &str) -> serde_json::Result<Vec<QueuedPrompt>> {
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn prompts(queue: &PromptQueue) -> Vec<String> {
+        queue
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|q| q.prompt)
+            .collect()
+    }
+
+    #[test]
+    fn push_pop_in_order() {
+        let dir = TempDir::new().unwrap();
+        let queue = PromptQueue::new(dir.path()).unwrap();
+        assert!(queue.is_empty());
+
+        assert_eq!(queue.push("first").unwrap(), 1);
+        assert_eq!(queue.push("second").unwrap(), 2);
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.pop_front().unwrap().unwrap().prompt, "first");
+        assert_eq!(queue.pop_front().unwrap().unwrap().prompt, "second");
+        assert!(queue.pop_front().unwrap().is_none());
+    }
+
+    #[test]
+    fn cancel_by_position() {
+        let dir = TempDir::new().unwrap();
+        let queue = PromptQueue::new(dir.path()).unwrap();
+        for prompt in ["a", "b", "c"] {
+            queue.push(prompt).unwrap();
+        }
+
+        assert_eq!(queue.cancel(2).unwrap().unwrap().prompt, "b");
+        assert!(queue.cancel(0).unwrap().is_none());
+        assert!(queue.cancel(3).unwrap().is_none());
+        assert_eq!(prompts(&queue), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn queue_is_shared_through_the_file() {
+        let dir = TempDir::new().unwrap();
+        PromptQueue::new(dir.path())
+            .unwrap()
+            .push("queued")
+            .unwrap();
+
+        let reopened = PromptQueue::new(dir.path()).unwrap();
+        assert_eq!(prompts(&reopened), vec!["queued"]);
+    }
+}
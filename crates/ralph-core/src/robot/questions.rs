@@ -0,0 +1,127 @@
+//! Parsing agent questions into text and answer choices.
+//!
+//! Questions with a small, fixed set of answers are offered to the human
+//! as choices (buttons on Telegram, a list of replies on Slack). Choices
+//! come from the `choices` field of a JSON payload:
+//!
+//! ```json
+//! {"question": "Which database?", "choices": ["Postgres", "SQLite"]}
+//! ```
+//!
+//! Plain-text questions that look like yes/no questions get Yes/No choices.
+//! Anything else is sent as free text.
+
+use serde::Deserialize;
+
+/// Maximum number of choices offered for a question.
+const MAX_CHOICES: usize = 8;
+
+/// Words that open a yes/no question.
+const BINARY_OPENERS: &[&str] = &[
+    "am", "are", "can", "could", "did", "do", "does", "has", "have", "is", "may", "shall",
+    "should", "was", "were", "will", "would",
+];
+
+/// A question ready to send, with the choices to offer (if any).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    /// The question text.
+    pub text: String,
+
+    /// Answer choices; empty for free-text questions.
+    pub choices: Vec<String>,
+}
+
+/// Structured `human.interact` payload.
+#[derive(Deserialize)]
+struct QuestionPayload {
+    question: String,
+    #[serde(default)]
+    choices: Vec<String>,
+}
+
+/// Parses a `human.interact` payload into question text and choices.
+pub fn parse_question(payload: &str) -> Question {
+    let (text, choices) = match serde_json::from_str::<QuestionPayload>(payload) {
+        Ok(parsed) => (parsed.question, parsed.choices),
+        Err(_) => (payload.to_string(), Vec::new()),
+    };
+
+    let mut choices: Vec<String> = choices
+        .into_iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .take(MAX_CHOICES)
+        .collect();
+    if choices.is_empty() && looks_binary(&text) {
+        choices = vec!["Yes".to_string(), "No".to_string()];
+    }
+
+    Question { text, choices }
+}
+
+/// Returns true if the question's last sentence reads as a yes/no question.
+///
+/// "Should I run the migration?" qualifies; "Should I use A or B?" and
+/// "Which database should I use?" don't.
+fn looks_binary(text: &str) -> bool {
+    let Some(last_line) = text.lines().map(str::trim).rfind(|l| !l.is_empty()) else {
+        return false;
+    };
+    let line = last_line.to_lowercase();
+    if ["(y/n)", "[y/n]", "yes/no"]
+        .iter()
+        .any(|m| line.contains(m))
+    {
+        return true;
+    }
+
+    let sentence = line.rsplit(". ").next().unwrap_or(&line);
+    let Some(question) = sentence.strip_suffix('?') else {
+        return false;
+    };
+    let mut words = question.split_whitespace();
+    words
+        .next()
+        .is_some_and(|first| BINARY_OPENERS.contains(&first))
+        && !words.any(|w| w == "or")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_payload_with_choices() {
+        let question =
+            parse_question(r#"{"question":"Which DB?","choices":["Postgres"," SQLite ",""]}"#);
+        assert_eq!(question.text, "Which DB?");
+        assert_eq!(question.choices, vec!["Postgres", "SQLite"]);
+    }
+
+    #[test]
+    fn binary_questions_get_yes_no() {
+        for text in [
+            "Should I run the migration?",
+            "Tests fail on main.\nIs it OK to skip them?",
+            "I found 3 stale branches. Can I delete them?",
+            "Proceed with deploy (y/n)",
+            r#"{"question":"Do you want a backup first?"}"#,
+        ] {
+            assert_eq!(parse_question(text).choices, vec!["Yes", "No"], "{text}");
+        }
+    }
+
+    #[test]
+    fn open_questions_stay_free_text() {
+        for text in [
+            "Which database should I use?",
+            "Should I use Postgres or SQLite?",
+            "Should I run the migration",
+            "Here is the plan.",
+            "",
+        ] {
+            assert!(parse_question(text).choices.is_empty(), "{text}");
+        }
+    }
+}
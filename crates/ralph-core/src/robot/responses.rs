@@ -0,0 +1,443 @@
+//! Reading human responses back from a loop's events file.
+//!
+//! The bots write `human.response` events into the events file of the loop
+//! that asked; the waiting loop reads them from there.
+
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tracing::{debug, info, warn};
+
+/// Fallback poll interval while waiting for a human response.
+///
+/// The events file watcher normally wakes the wait immediately; polling
+/// covers filesystems without change notifications and the shutdown flag.
+pub const RESPONSE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Get the active events file path for a given loop.
+///
+/// Reads the `current-events` marker to find the timestamped events file.
+/// Falls back to the default `events.jsonl` if the marker doesn't exist.
+/// Loops other than `main` live in `.worktrees/<loop_id>`.
+pub fn loop_events_path(workspace_root: &Path, loop_id: &str) -> PathBuf {
+    let ralph_dir = if loop_id == "main" {
+        workspace_root.join(".ralph")
+    } else {
+        workspace_root
+            .join(".worktrees")
+            .join(loop_id)
+            .join(".ralph")
+    };
+
+    let marker_path = ralph_dir.join("current-events");
+    if let Ok(contents) = std::fs::read_to_string(&marker_path) {
+        let relative = contents.trim();
+        if !relative.is_empty() {
+            // Marker contains a path relative to workspace root
+            // (e.g., ".ralph/events-20260201-210033.jsonl")
+            if loop_id == "main" {
+                return workspace_root.join(relative);
+            } else {
+                return workspace_root
+                    .join(".worktrees")
+                    .join(loop_id)
+                    .join(relative);
+            }
+        }
+    }
+
+    ralph_dir.join("events.jsonl")
+}
+
+/// A pending question that can tell replies to it apart from other responses.
+pub trait QuestionThread: Sync {
+    /// Returns true if the `human.response` `event` replies to this question.
+    fn is_reply(&self, event: &serde_json::Value) -> bool;
+}
+
+/// Wait for a `human.response` event in `events_path` until one arrives,
+/// `timeout` expires or `shutdown` is set.
+///
+/// Only responses written after the call count. Watches the events file for
+/// changes so responses are picked up as soon as they are written, and polls
+/// every [`RESPONSE_POLL_INTERVAL`] as a fallback (and to notice `shutdown`).
+/// A response that replies to `question` is preferred over others that
+/// arrived at the same time. Returns `None` on timeout or shutdown.
+pub async fn wait_for_response(
+    events_path: &Path,
+    question: Option<&dyn QuestionThread>,
+    timeout: Duration,
+    shutdown: &AtomicBool,
+) -> io::Result<Option<String>> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    // Track file position to only read new lines
+    let mut file_pos = std::fs::metadata(events_path).map(|m| m.len()).unwrap_or(0);
+
+    // `changes` never closes while `changes_tx` is alive, even if
+    // watching fails and only the poll interval remains
+    let (changes_tx, mut changes) = tokio::sync::mpsc::unbounded_channel();
+    let _watcher = watch_events_file(events_path, changes_tx.clone());
+    let mut poll = tokio::time::interval(RESPONSE_POLL_INTERVAL);
+    poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        // Check if we've been interrupted (Ctrl+C / SIGTERM / SIGHUP)
+        if shutdown.load(Ordering::Relaxed) {
+            info!(events_path = %events_path.display(), "Interrupted while waiting for human.response");
+            return Ok(None);
+        }
+
+        if let Some(response) = check_for_response(events_path, &mut file_pos, question)? {
+            info!(events_path = %events_path.display(), "Received human.response: {}", response);
+            return Ok(Some(response));
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep_until(deadline) => {
+                warn!(
+                    events_path = %events_path.display(),
+                    timeout_secs = timeout.as_secs(),
+                    "Timed out waiting for human.response"
+                );
+                return Ok(None);
+            }
+            _ = changes.recv() => {}
+            _ = poll.tick() => {}
+        }
+    }
+}
+
+/// Runs `future` to completion from synchronous code, such as the event
+/// loop waiting for a response.
+///
+/// Inside a multi-threaded tokio runtime, uses `block_in_place` so other
+/// tasks keep running on the remaining workers. Outside any runtime, a
+/// temporary current-thread runtime drives it.
+pub fn block_on<F: Future>(future: F) -> io::Result<F::Output> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => Ok(tokio::task::block_in_place(|| handle.block_on(future))),
+        Err(_) => Ok(tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(future)),
+    }
+}
+
+/// Check the events file for a `human.response` event starting from
+/// `file_pos`. Updates `file_pos` to the new end of file.
+///
+/// A response that replies to `question` wins over earlier responses in the
+/// same batch; otherwise the first response is returned.
+pub fn check_for_response(
+    events_path: &Path,
+    file_pos: &mut u64,
+    question: Option<&dyn QuestionThread>,
+) -> io::Result<Option<String>> {
+    if !events_path.exists() {
+        return Ok(None);
+    }
+
+    let mut file = std::fs::File::open(events_path)?;
+    file.seek(SeekFrom::Start(*file_pos))?;
+
+    let mut unreferenced = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        *file_pos += line.len() as u64 + 1; // +1 for newline
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Try to parse as JSON event
+        if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line)
+            && event.get("topic").and_then(|t| t.as_str()) == Some("human.response")
+        {
+            let message = event
+                .get("payload")
+                .and_then(|p| p.as_str())
+                .unwrap_or("")
+                .to_string();
+            match question {
+                None => return Ok(Some(message)),
+                Some(q) if q.is_reply(&event) => return Ok(Some(message)),
+                Some(_) => {
+                    unreferenced.get_or_insert(message);
+                    continue;
+                }
+            }
+        }
+
+        // Also check pipe-separated format:
+        // EVENT: human.response | message: "..." | timestamp: "..."
+        if line.contains("EVENT: human.response") {
+            let message = line
+                .split('|')
+                .find(|part| part.trim().starts_with("message:"))
+                .and_then(|part| {
+                    let value = part.trim().strip_prefix("message:")?;
+                    let trimmed = value.trim().trim_matches('"');
+                    Some(trimmed.to_string())
+                })
+                .unwrap_or_default();
+            if question.is_none() {
+                return Ok(Some(message));
+            }
+            unreferenced.get_or_insert(message);
+        }
+    }
+
+    Ok(unreferenced)
+}
+
+/// Watches the directory holding `events_path` and signals `changes`
+/// whenever the events file is touched.
+///
+/// Returns `None` (leaving the caller to poll) if the platform watcher
+/// can't be set up, e.g. when the directory doesn't exist yet.
+fn watch_events_file(
+    events_path: &Path,
+    changes: tokio::sync::mpsc::UnboundedSender<()>,
+) -> Option<notify::RecommendedWatcher> {
+    use notify::Watcher;
+
+    let dir = events_path.parent()?;
+    let file_name = events_path.file_name()?.to_os_string();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && event
+                .paths
+                .iter()
+                .any(|p| p.file_name() == Some(file_name.as_os_str()))
+        {
+            let _ = changes.send(());
+        }
+    });
+
+    match watcher.and_then(|mut w| {
+        w.watch(dir, notify::RecursiveMode::NonRecursive)?;
+        Ok(w)
+    }) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            debug!(error = %e, "Could not watch events file — falling back to polling");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn check_for_response_json_format() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+
+        // Write a non-response event first
+        let mut file = std::fs::File::create(&events_path).unwrap();
+        writeln!(
+            file,
+            r#"{{"topic":"build.done","payload":"tests: pass, lint: pass, typecheck: pass, audit: pass, coverage: pass","ts":"2026-01-30T00:00:00Z"}}"#
+        )
+        .unwrap();
+        // Write a human.response event
+        writeln!(
+            file,
+            r#"{{"topic":"human.response","payload":"Use async","ts":"2026-01-30T00:01:00Z"}}"#
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, Some("Use async".to_string()));
+    }
+
+    #[test]
+    fn check_for_response_pipe_format() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+
+        let mut file = std::fs::File::create(&events_path).unwrap();
+        writeln!(
+            file,
+            r#"EVENT: human.response | message: "Use sync" | timestamp: "2026-01-30T00:01:00Z""#
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, Some("Use sync".to_string()));
+    }
+
+    #[test]
+    fn check_for_response_skips_non_response_events() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+
+        let mut file = std::fs::File::create(&events_path).unwrap();
+        writeln!(
+            file,
+            r#"{{"topic":"build.done","payload":"done","ts":"2026-01-30T00:00:00Z"}}"#
+        )
+        .unwrap();
+        writeln!(
+            file,
+            r#"{{"topic":"human.guidance","payload":"check errors","ts":"2026-01-30T00:01:00Z"}}"#
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn check_for_response_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("does-not-exist.jsonl");
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn check_for_response_tracks_position() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+
+        // Write one event
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&events_path)
+            .unwrap();
+        writeln!(
+            file,
+            r#"{{"topic":"build.done","payload":"done","ts":"2026-01-30T00:00:00Z"}}"#
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, None);
+        assert!(pos > 0, "position should advance after reading");
+
+        let pos_after_first = pos;
+
+        // Append a human.response
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&events_path)
+            .unwrap();
+        writeln!(
+            file,
+            r#"{{"topic":"human.response","payload":"yes","ts":"2026-01-30T00:02:00Z"}}"#
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        // Should find the response starting from where we left off
+        let result = check_for_response(&events_path, &mut pos, None).unwrap();
+        assert_eq!(result, Some("yes".to_string()));
+        assert!(pos > pos_after_first, "position should advance further");
+    }
+
+    struct ReplyTo(i64);
+
+    impl QuestionThread for ReplyTo {
+        fn is_reply(&self, event: &serde_json::Value) -> bool {
+            event.get("reply_to").and_then(serde_json::Value::as_i64) == Some(self.0)
+        }
+    }
+
+    #[test]
+    fn check_for_response_prefers_reply_to_question() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+        std::fs::write(
+            &events_path,
+            concat!(
+                r#"EVENT: human.response | message: "piped" | timestamp: "2026-01-30T00:00:00Z""#,
+                "\n",
+                r#"{"topic":"human.response","payload":"mine","reply_to":42,"ts":"2026-01-30T00:01:00Z"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, Some(&ReplyTo(42))).unwrap();
+        assert_eq!(result, Some("mine".to_string()));
+
+        let mut pos = 0;
+        let result = check_for_response(&events_path, &mut pos, Some(&ReplyTo(7))).unwrap();
+        assert_eq!(result, Some("piped".to_string()));
+    }
+
+    #[test]
+    fn loop_events_path_follows_marker() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            loop_events_path(dir.path(), "main"),
+            dir.path().join(".ralph/events.jsonl")
+        );
+
+        let ralph_dir = dir.path().join(".worktrees/feature/.ralph");
+        std::fs::create_dir_all(&ralph_dir).unwrap();
+        std::fs::write(
+            ralph_dir.join("current-events"),
+            ".ralph/events-20260201-210033.jsonl\n",
+        )
+        .unwrap();
+        assert_eq!(
+            loop_events_path(dir.path(), "feature"),
+            dir.path()
+                .join(".worktrees/feature/.ralph/events-20260201-210033.jsonl")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_response_ignores_earlier_responses_and_times_out() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+        std::fs::write(
+            &events_path,
+            r#"{"topic":"human.response","payload":"old","ts":"2026-01-30T00:00:00Z"}"#.to_owned()
+                + "\n",
+        )
+        .unwrap();
+
+        let start = tokio::time::Instant::now();
+        let shutdown = AtomicBool::new(false);
+        let result = wait_for_response(&events_path, None, Duration::from_mins(5), &shutdown)
+            .await
+            .unwrap();
+        assert_eq!(result, None);
+        assert!(start.elapsed() >= Duration::from_mins(5));
+    }
+
+    #[tokio::test]
+    async fn wait_for_response_stops_on_shutdown() {
+        let dir = TempDir::new().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+        let shutdown = AtomicBool::new(true);
+
+        let result = wait_for_response(&events_path, None, Duration::from_mins(5), &shutdown)
+            .await
+            .unwrap();
+        assert_eq!(result, None);
+    }
+}
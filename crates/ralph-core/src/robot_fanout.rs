@@ -0,0 +1,268 @@
+//! Running several RObot transports at once.
+//!
+//! [`RobotFanout`] wraps the configured [`RobotService`]s (e.g. Telegram and
+//! Slack) behind a single service. Questions, check-ins and notifications go
+//! to every transport, and the first answer from any of them wins: every
+//! transport writes answers into the same events file, so each one sees the
+//! winning `human.response` and settles its own pending question.
+
+use ralph_proto::{CheckinContext, LifecycleNotification, RobotService};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use tracing::warn;
+
+/// How often a wait checks whether the fanout was asked to shut down.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Several robot services acting as one.
+pub struct RobotFanout {
+    services: Vec<Box<dyn RobotService>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl RobotFanout {
+    /// Wraps `services`; messages go to them in order.
+    pub fn new(services: Vec<Box<dyn RobotService>>) -> Self {
+        Self {
+            services,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Sends through every service, succeeding if any of them did.
+    ///
+    /// Returns the first service's message ID that succeeded; failures of
+    /// the others are logged.
+    fn send_all(
+        &self,
+        what: &str,
+        send: impl Fn(&dyn RobotService) -> anyhow::Result<i32>,
+    ) -> anyhow::Result<i32> {
+        let mut sent = None;
+        let mut last_error = None;
+        for service in &self.services {
+            match send(service.as_ref()) {
+                Ok(message_id) => {
+                    sent.get_or_insert(message_id);
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to send {what} through one robot transport");
+                    last_error = Some(e);
+                }
+            }
+        }
+        match (sent, last_error) {
+            (Some(message_id), _) => Ok(message_id),
+            (None, Some(e)) => Err(e),
+            (None, None) => Ok(0),
+        }
+    }
+
+    fn relay_shutdown(&self) {
+        for service in &self.services {
+            service.shutdown_flag().store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl RobotService for RobotFanout {
+    fn send_question(&self, payload: &str) -> anyhow::Result<i32> {
+        self.send_all("question", |service| service.send_question(payload))
+    }
+
+    /// Waits on every service at once and returns the first answer.
+    ///
+    /// The answer lands in the shared events file, so the other services
+    /// return it too within their poll interval; the wait ends once all have
+    /// returned. A shutdown of the fanout is relayed to every service.
+    fn wait_for_response(&self, events_path: &Path) -> anyhow::Result<Option<String>> {
+        let (results_tx, results) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for service in &self.services {
+                let results_tx = results_tx.clone();
+                scope.spawn(move || {
+                    let _ = results_tx.send(service.wait_for_response(events_path));
+                });
+            }
+            drop(results_tx);
+
+            let mut answer = None;
+            let mut error = None;
+            let mut relayed = false;
+            loop {
+                match results.recv_timeout(SHUTDOWN_POLL_INTERVAL) {
+                    Ok(Ok(Some(response))) => {
+                        answer.get_or_insert(response);
+                    }
+                    Ok(Ok(None)) | Err(RecvTimeoutError::Timeout) => {}
+                    Ok(Err(e)) => {
+                        warn!(error = %e, "Robot transport failed while waiting for a response");
+                        error.get_or_insert(e);
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if !relayed && self.shutdown.load(Ordering::Relaxed) {
+                    self.relay_shutdown();
+                    relayed = true;
+                }
+            }
+
+            match (answer, error) {
+                (Some(response), _) => Ok(Some(response)),
+                (None, Some(e)) => Err(e),
+                (None, None) => Ok(None),
+            }
+        })
+    }
+
+    fn send_checkin(
+        &self,
+        iteration: u32,
+        elapsed: Duration,
+        context: Option<&CheckinContext>,
+    ) -> anyhow::Result<i32> {
+        self.send_all("check-in", |service| {
+            service.send_checkin(iteration, elapsed, context)
+        })
+    }
+
+    fn send_notification(&self, notification: &LifecycleNotification) -> anyhow::Result<i32> {
+        self.send_all("notification", |service| {
+            service.send_notification(notification)
+        })
+    }
+
+    fn timeout_secs(&self) -> u64 {
+        self.services
+            .iter()
+            .map(|service| service.timeout_secs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
+    fn stop(self: Box<Self>) {
+        for service in self.services {
+            service.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    /// Answers after `delay` with `answer`, or waits until shut down.
+    struct FakeService {
+        answer: Option<&'static str>,
+        delay: Duration,
+        fail_sends: bool,
+        questions: Arc<Mutex<Vec<String>>>,
+        shutdown: Arc<AtomicBool>,
+    }
+
+    impl FakeService {
+        fn new(answer: Option<&'static str>, delay: Duration) -> Self {
+            Self {
+                answer,
+                delay,
+                fail_sends: false,
+                questions: Arc::default(),
+                shutdown: Arc::default(),
+            }
+        }
+    }
+
+    impl RobotService for FakeService {
+        fn send_question(&self, payload: &str) -> anyhow::Result<i32> {
+            if self.fail_sends {
+                anyhow::bail!("offline");
+            }
+            self.questions.lock().unwrap().push(payload.to_string());
+            Ok(7)
+        }
+
+        fn wait_for_response(&self, _events_path: &Path) -> anyhow::Result<Option<String>> {
+            let deadline = Instant::now() + self.delay;
+            while Instant::now() < deadline {
+                if self.shutdown.load(Ordering::Relaxed) {
+                    return Ok(None);
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(self.answer.map(String::from))
+        }
+
+        fn send_checkin(
+            &self,
+            _iteration: u32,
+            _elapsed: Duration,
+            _context: Option<&CheckinContext>,
+        ) -> anyhow::Result<i32> {
+            Ok(0)
+        }
+
+        fn send_notification(&self, _notification: &LifecycleNotification) -> anyhow::Result<i32> {
+            Ok(0)
+        }
+
+        fn timeout_secs(&self) -> u64 {
+            self.delay.as_secs()
+        }
+
+        fn shutdown_flag(&self) -> Arc<AtomicBool> {
+            self.shutdown.clone()
+        }
+
+        fn stop(self: Box<Self>) {}
+    }
+
+    #[test]
+    fn test_question_goes_to_every_service_and_survives_one_failure() {
+        let first = FakeService::new(None, Duration::ZERO);
+        let mut second = FakeService::new(None, Duration::ZERO);
+        second.fail_sends = true;
+        let questions = first.questions.clone();
+        let fanout = RobotFanout::new(vec![Box::new(second), Box::new(first)]);
+
+        assert_eq!(fanout.send_question("Ship it?").unwrap(), 7);
+        assert_eq!(*questions.lock().unwrap(), vec!["Ship it?"]);
+    }
+
+    #[test]
+    fn test_first_answer_wins() {
+        let fanout = RobotFanout::new(vec![
+            Box::new(FakeService::new(
+                Some("from slack"),
+                Duration::from_millis(200),
+            )),
+            Box::new(FakeService::new(Some("from telegram"), Duration::ZERO)),
+        ]);
+
+        let answer = fanout.wait_for_response(Path::new("events.jsonl")).unwrap();
+        assert_eq!(answer.as_deref(), Some("from telegram"));
+    }
+
+    #[test]
+    fn test_shutdown_is_relayed_to_every_service() {
+        let fanout = RobotFanout::new(vec![
+            Box::new(FakeService::new(Some("late"), Duration::from_secs(30))),
+            Box::new(FakeService::new(Some("later"), Duration::from_mins(1))),
+        ]);
+        fanout.shutdown_flag().store(true, Ordering::Relaxed);
+
+        let started = Instant::now();
+        let answer = fanout.wait_for_response(Path::new("events.jsonl")).unwrap();
+        assert_eq!(answer, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(fanout.timeout_secs(), 60);
+    }
+}
@@ -0,0 +1,388 @@
+//! Per-run records for `ralph runs`.
+//!
+//! Every loop run gets a directory at `.ralph/runs/<run-id>/` holding a
+//! `meta.json` written when the run starts and updated when it terminates.
+//! Records live in the repository's `.ralph/` (not a worktree's) so they
+//! outlive the worktrees of parallel loops.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::loop_context::LoopContext;
+use crate::scratchpad_archive::{ArchivedScratchpad, ScratchpadArchive};
+
+/// Metadata file inside each run directory.
+const META_FILE: &str = "meta.json";
+
+/// Errors from reading or writing run records.
+#[derive(Debug, thiserror::Error)]
+pub enum RunHistoryError {
+    /// No run exists with the given ID.
+    #[error("No run found with ID '{0}'")]
+    NotFound(String),
+
+    /// IO error reading or writing run records.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// A `meta.json` file could not be parsed or written.
+    #[error("Invalid run metadata: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// What is known about a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Run ID (start timestamp, e.g. `20250101-120000`).
+    pub id: String,
+
+    /// When the run started.
+    pub started: DateTime<Utc>,
+
+    /// Short summary of the prompt.
+    pub prompt: String,
+
+    /// Backend the run used.
+    pub backend: String,
+
+    /// Config sources the run was started with (files, presets, overrides).
+    #[serde(default)]
+    pub config_sources: Vec<String>,
+
+    /// ID of the loop that executed the run.
+    pub loop_id: String,
+
+    /// Workspace the loop ran in (the worktree for parallel loops).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+
+    /// Events file the run wrote to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events_file: Option<PathBuf>,
+
+    /// Session recording written with `--record-session`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_recording: Option<PathBuf>,
+
+    /// When the run terminated (None while running or after a crash).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<DateTime<Utc>>,
+
+    /// Why the run terminated (see `TerminationReason::as_str`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub termination_reason: Option<String>,
+
+    /// Iterations executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+}
+
+impl RunRecord {
+    /// Creates a record for a run starting now.
+    pub fn new(
+        id: impl Into<String>,
+        prompt: impl Into<String>,
+        backend: impl Into<String>,
+        loop_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            started: Utc::now(),
+            prompt: prompt.into(),
+            backend: backend.into(),
+            config_sources: Vec::new(),
+            loop_id: loop_id.into(),
+            workspace: None,
+            events_file: None,
+            session_recording: None,
+            finished: None,
+            termination_reason: None,
+            iterations: None,
+        }
+    }
+
+    /// How long the run took, if it has finished.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        self.finished.map(|finished| finished - self.started)
+    }
+
+    /// The archived scratchpad left behind by this run.
+    ///
+    /// A run's scratchpad is archived when the next fresh run starts in the
+    /// same workspace, so this is the first archive taken after the run
+    /// finished. Returns `None` while the scratchpad hasn't been archived yet.
+    pub fn scratchpad_archive(&self) -> Option<ArchivedScratchpad> {
+        let workspace = self.workspace.as_ref()?;
+        let finished = self
+            .finished?
+            .with_timezone(&Local)
+            .format("%Y%m%d-%H%M%S")
+            .to_string();
+        let archive = ScratchpadArchive::from_context(&LoopContext::primary(workspace.clone()));
+        // Archives are listed newest first; the last one at or after `finished` is the earliest
+        archive
+            .list()
+            .ok()?
+            .into_iter()
+            .rev()
+            .find(|a| a.timestamp.as_str() >= finished.as_str())
+    }
+}
+
+/// The `.ralph/runs/` directory of a repository.
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    dir: PathBuf,
+}
+
+impl RunHistory {
+    /// The relative path to the runs directory within the workspace.
+    pub const RUNS_DIR: &'static str = ".ralph/runs";
+
+    /// Creates a run history for the workspace at `workspace_root`.
+    pub fn new(workspace_root: impl AsRef<Path>) -> Self {
+        Self {
+            dir: workspace_root.as_ref().join(Self::RUNS_DIR),
+        }
+    }
+
+    /// Creates a run history in the loop's repository root, shared by the
+    /// primary loop and worktree loops.
+    pub fn from_context(context: &LoopContext) -> Self {
+        Self::new(context.repo_root())
+    }
+
+    /// Returns the runs directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Directory of the run with the given ID.
+    pub fn run_dir(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    /// Records the start of a run.
+    ///
+    /// If another run already uses `record.id` (two loops starting in the same
+    /// second), a numeric suffix is added. Returns the ID actually used.
+    pub fn start(&self, mut record: RunRecord) -> Result<String, RunHistoryError> {
+        fs::create_dir_all(&self.dir)?;
+        let base = record.id.clone();
+        let mut suffix = 1;
+        loop {
+            match fs::create_dir(self.run_dir(&record.id)) {
+                Ok(()) => break,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    suffix += 1;
+                    record.id = format!("{}-{}", base, suffix);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.write(&record)?;
+        Ok(record.id)
+    }
+
+    /// Records how a run ended.
+    pub fn finish(
+        &self,
+        id: &str,
+        termination_reason: &str,
+        iterations: u32,
+    ) -> Result<RunRecord, RunHistoryError> {
+        let mut record = self.get(id)?;
+        record.finished = Some(Utc::now());
+        record.termination_reason = Some(termination_reason.to_string());
+        record.iterations = Some(iterations);
+        self.write(&record)?;
+        Ok(record)
+    }
+
+    /// Loads the record of a run.
+    pub fn get(&self, id: &str) -> Result<RunRecord, RunHistoryError> {
+        let path = self.run_dir(id).join(META_FILE);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(RunHistoryError::NotFound(id.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Lists all runs, newest first. Unreadable records are skipped.
+    pub fn list(&self) -> Result<Vec<RunRecord>, RunHistoryError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut runs = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let id = entry.file_name().to_string_lossy().to_string();
+            match self.get(&id) {
+                Ok(record) => runs.push(record),
+                Err(e) => tracing::debug!("Skipping run record {}: {}", id, e),
+            }
+        }
+        runs.sort_by(|a, b| b.started.cmp(&a.started).then_with(|| b.id.cmp(&a.id)));
+        Ok(runs)
+    }
+
+    /// Runs that fall outside the newest `keep` (0 keeps everything).
+    pub fn expired(&self, keep: usize) -> Result<Vec<RunRecord>, RunHistoryError> {
+        if keep == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(self.list()?.into_iter().skip(keep).collect())
+    }
+
+    /// Deletes runs beyond the newest `keep` and returns their IDs.
+    pub fn prune(&self, keep: usize) -> Result<Vec<String>, RunHistoryError> {
+        let mut removed = Vec::new();
+        for record in self.expired(keep)? {
+            fs::remove_dir_all(self.run_dir(&record.id))?;
+            removed.push(record.id);
+        }
+        Ok(removed)
+    }
+
+    fn write(&self, record: &RunRecord) -> Result<(), RunHistoryError> {
+        let path = self.run_dir(&record.id).join(META_FILE);
+        fs::write(path, serde_json::to_string_pretty(record)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(id: &str, started: &str) -> RunRecord {
+        let mut record = RunRecord::new(id, "prompt", "claude", "primary");
+        record.started = started.parse().unwrap();
+        record
+    }
+
+    #[test]
+    fn start_and_finish_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = RunHistory::new(temp_dir.path());
+
+        let mut start = RunRecord::new("20250101-120000", "Fix tests", "claude", "primary-1");
+        start.config_sources = vec!["ralph.yml".to_string()];
+        start.events_file = Some(PathBuf::from(".ralph/events-20250101-120000.jsonl"));
+        let id = history.start(start.clone()).unwrap();
+        assert_eq!(id, "20250101-120000");
+        assert_eq!(history.get(&id).unwrap(), start);
+        assert!(history.get(&id).unwrap().duration().is_none());
+
+        let finished = history.finish(&id, "completed", 4).unwrap();
+        assert_eq!(finished.termination_reason.as_deref(), Some("completed"));
+        assert_eq!(finished.iterations, Some(4));
+        assert!(finished.duration().is_some());
+        assert_eq!(history.get(&id).unwrap(), finished);
+    }
+
+    #[test]
+    fn colliding_ids_get_a_suffix() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = RunHistory::new(temp_dir.path());
+
+        let first = history
+            .start(record("20250101-120000", "2025-01-01T12:00:00Z"))
+            .unwrap();
+        let second = history
+            .start(record("20250101-120000", "2025-01-01T12:00:00Z"))
+            .unwrap();
+        assert_eq!(first, "20250101-120000");
+        assert_eq!(second, "20250101-120000-2");
+    }
+
+    #[test]
+    fn list_is_newest_first_and_skips_garbage() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = RunHistory::new(temp_dir.path());
+        history.start(record("a", "2025-01-01T00:00:00Z")).unwrap();
+        history.start(record("c", "2025-01-03T00:00:00Z")).unwrap();
+        history.start(record("b", "2025-01-02T00:00:00Z")).unwrap();
+        fs::create_dir_all(history.run_dir("broken")).unwrap();
+        fs::write(history.run_dir("broken").join(META_FILE), "{").unwrap();
+
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["c", "b", "a"]);
+        assert!(matches!(
+            history.get("missing"),
+            Err(RunHistoryError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn prune_keeps_newest_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = RunHistory::new(temp_dir.path());
+        for (id, started) in [
+            ("a", "2025-01-01T00:00:00Z"),
+            ("b", "2025-01-02T00:00:00Z"),
+            ("c", "2025-01-03T00:00:00Z"),
+        ] {
+            history.start(record(id, started)).unwrap();
+        }
+
+        assert!(history.prune(0).unwrap().is_empty());
+        assert_eq!(history.prune(2).unwrap(), vec!["a"]);
+        assert!(!history.run_dir("a").exists());
+        assert_eq!(history.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn scratchpad_archive_is_first_archive_after_finish() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_dir =
+            LoopContext::primary(temp_dir.path().to_path_buf()).scratchpad_archive_dir();
+        fs::create_dir_all(&archive_dir).unwrap();
+        for timestamp in ["20250101-110000", "20250101-130000", "20250101-150000"] {
+            fs::write(
+                archive_dir.join(format!("scratchpad-{timestamp}.md")),
+                "notes",
+            )
+            .unwrap();
+        }
+
+        let finished = chrono::NaiveDateTime::parse_from_str("20250101-120000", "%Y%m%d-%H%M%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut run = record("run", "2025-01-01T00:00:00Z");
+        assert!(run.scratchpad_archive().is_none());
+
+        run.workspace = Some(temp_dir.path().to_path_buf());
+        run.finished = Some(finished);
+        assert_eq!(
+            run.scratchpad_archive().unwrap().timestamp,
+            "20250101-130000"
+        );
+
+        run.finished = Some(finished + chrono::Duration::hours(4));
+        assert!(run.scratchpad_archive().is_none());
+    }
+
+    #[test]
+    fn empty_history_lists_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(RunHistory::new(temp_dir.path()).list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+//! Machine-readable run summary.
+//!
+//! On termination the loop writes `.ralph/agent/run-summary.json` (or the
+//! path in `event_loop.summary_out`) with per-iteration metrics and totals.
+//! It complements the human-readable `summary.md`.
+
+use crate::event_loop::TerminationReason;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Metrics for one iteration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IterationMetrics {
+    /// Iteration number (1-indexed).
+    pub iteration: u32,
+    /// Hat that was active.
+    pub hat: String,
+    /// Wall time from building the prompt to processing the output.
+    pub duration_ms: u64,
+    /// Events the agent wrote to the events file.
+    pub events_emitted: usize,
+}
+
+/// Totals across the run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunTotals {
+    /// Iterations executed.
+    pub iterations: u32,
+    /// Sum of the iterations' wall time.
+    pub iteration_ms: u64,
+    /// Wall time of the whole run, including time between iterations.
+    pub elapsed_ms: u64,
+    /// Events written by agents.
+    pub events_emitted: usize,
+    /// Cumulative cost in USD, if tracked.
+    pub cost_usd: f64,
+}
+
+/// Contents of `run-summary.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Termination reason (see [`TerminationReason::as_str`]).
+    pub reason: String,
+    /// Process exit code for the reason.
+    pub exit_code: i32,
+    /// Whether the loop completed.
+    pub success: bool,
+    /// One entry per iteration, in order.
+    pub iterations: Vec<IterationMetrics>,
+    /// Totals across the run.
+    pub totals: RunTotals,
+}
+
+impl RunMetrics {
+    /// Builds the summary for a finished run.
+    pub fn new(
+        reason: &TerminationReason,
+        iterations: Vec<IterationMetrics>,
+        elapsed: Duration,
+        cost_usd: f64,
+    ) -> Self {
+        let totals = RunTotals {
+            iterations: u32::try_from(iterations.len()).unwrap_or(u32::MAX),
+            iteration_ms: iterations.iter().map(|i| i.duration_ms).sum(),
+            elapsed_ms: duration_ms(elapsed),
+            events_emitted: iterations.iter().map(|i| i.events_emitted).sum(),
+            cost_usd,
+        };
+        Self {
+            reason: reason.as_str().to_string(),
+            exit_code: reason.exit_code(),
+            success: reason.is_success(),
+            iterations,
+            totals,
+        }
+    }
+
+    /// Writes the summary as pretty-printed JSON, creating parent
+    /// directories as needed.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+}
+
+/// Milliseconds in `duration`, saturating.
+pub(crate) fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totals_sum_iterations() {
+        let iterations = vec![
+            IterationMetrics {
+                iteration: 1,
+                hat: "planner".to_string(),
+                duration_ms: 1200,
+                events_emitted: 1,
+            },
+            IterationMetrics {
+                iteration: 2,
+                hat: "builder".to_string(),
+                duration_ms: 3400,
+                events_emitted: 2,
+            },
+        ];
+
+        let metrics = RunMetrics::new(
+            &TerminationReason::CompletionPromise,
+            iterations,
+            Duration::from_secs(5),
+            0.25,
+        );
+
+        assert_eq!(metrics.reason, "completed");
+        assert_eq!(metrics.exit_code, 0);
+        assert!(metrics.success);
+        assert_eq!(metrics.totals.iterations, 2);
+        assert_eq!(metrics.totals.iteration_ms, 4600);
+        assert_eq!(metrics.totals.elapsed_ms, 5000);
+        assert_eq!(metrics.totals.events_emitted, 3);
+    }
+
+    #[test]
+    fn test_write_round_trips() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("agent/run-summary.json");
+        let metrics = RunMetrics::new(
+            &TerminationReason::MaxIterations,
+            Vec::new(),
+            Duration::ZERO,
+            0.0,
+        );
+
+        metrics.write(&path).unwrap();
+
+        let read: RunMetrics = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read, metrics);
+    }
+}
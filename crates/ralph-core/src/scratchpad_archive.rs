@@ -0,0 +1,295 @@
+//! Scratchpad archival between runs.
+//!
+//! A fresh `ralph run` starts with an empty scratchpad. Rather than deleting
+//! the previous run's scratchpad, it is moved into `.ralph/agent/archive/`
+//! as `scratchpad-<timestamp>.md` so it can be inspected or restored later.
+//!
+//! Only the most recent archives are kept (see `core.scratchpad_archive_keep`).
+//! The archive directory is loop-local: it is never symlinked into worktrees
+//! and is skipped when syncing the working directory to a new worktree.
+
+use crate::loop_context::LoopContext;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name prefix for archived scratchpads.
+const ARCHIVE_PREFIX: &str = "scratchpad-";
+
+/// File name suffix for archived scratchpads.
+const ARCHIVE_SUFFIX: &str = ".md";
+
+/// A scratchpad that was archived by a previous run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedScratchpad {
+    /// Timestamp identifier (e.g., `20250101-120000`).
+    pub timestamp: String,
+
+    /// Path to the archived file.
+    pub path: PathBuf,
+
+    /// Size of the archived file in bytes.
+    pub size: u64,
+}
+
+/// Errors that can occur while restoring an archived scratchpad.
+#[derive(Debug, thiserror::Error)]
+pub enum ScratchpadArchiveError {
+    /// No archive exists with the given timestamp.
+    #[error("No archived scratchpad found for timestamp '{0}'")]
+    NotFound(String),
+
+    /// IO error reading or writing archive files.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Manages the scratchpad archive directory for a loop.
+#[derive(Debug, Clone)]
+pub struct ScratchpadArchive {
+    dir: PathBuf,
+}
+
+impl ScratchpadArchive {
+    /// Creates an archive manager for the given archive directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates an archive manager for the loop's archive directory.
+    pub fn from_context(context: &LoopContext) -> Self {
+        Self::new(context.scratchpad_archive_dir())
+    }
+
+    /// Returns the archive directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Moves a non-empty scratchpad into the archive.
+    ///
+    /// Returns the archive path, or `None` if the scratchpad was missing or
+    /// blank (a blank scratchpad is removed without archiving). After
+    /// archiving, only the newest `keep` archives are retained; `keep == 0`
+    /// retains all of them.
+    pub fn archive(&self, scratchpad: &Path, keep: usize) -> io::Result<Option<PathBuf>> {
+        let content = match fs::read_to_string(scratchpad) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if content.trim().is_empty() {
+            fs::remove_file(scratchpad)?;
+            return Ok(None);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+
+        // Same-second archives get an increasing suffix. Pick it from the highest
+        // existing one (not the first free name) so pruning can't reorder archives.
+        let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let last_suffix = self
+            .list()?
+            .iter()
+            .map(|a| sort_key(&a.timestamp))
+            .filter(|(b, _)| *b == base)
+            .map(|(_, n)| n)
+            .max();
+        let timestamp = match last_suffix {
+            Some(n) => format!("{}-{}", base, n + 1),
+            None => base,
+        };
+
+        let target = self.path_for(&timestamp);
+        if fs::rename(scratchpad, &target).is_err() {
+            // Rename fails across filesystems; fall back to copy + remove
+            fs::copy(scratchpad, &target)?;
+            fs::remove_file(scratchpad)?;
+        }
+
+        self.prune(keep)?;
+
+        Ok(Some(target))
+    }
+
+    /// Lists archived scratchpads, newest first.
+    pub fn list(&self) -> io::Result<Vec<ArchivedScratchpad>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut archives = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(timestamp) = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(ARCHIVE_PREFIX))
+                .and_then(|n| n.strip_suffix(ARCHIVE_SUFFIX))
+            else {
+                continue;
+            };
+
+            archives.push(ArchivedScratchpad {
+                timestamp: timestamp.to_string(),
+                path: entry.path(),
+                size: entry.metadata()?.len(),
+            });
+        }
+
+        archives.sort_by(|a, b| sort_key(&b.timestamp).cmp(&sort_key(&a.timestamp)));
+        Ok(archives)
+    }
+
+    /// Copies an archived scratchpad back to `scratchpad`.
+    ///
+    /// A non-empty current scratchpad is archived first (with no pruning) so
+    /// restoring never destroys content.
+    pub fn restore(
+        &self,
+        timestamp: &str,
+        scratchpad: &Path,
+    ) -> Result<PathBuf, ScratchpadArchiveError> {
+        let source = self.path_for(timestamp);
+        if !source.is_file() {
+            return Err(ScratchpadArchiveError::NotFound(timestamp.to_string()));
+        }
+
+        // Read first so archiving the current scratchpad can't race the source
+        let content = fs::read(&source)?;
+        self.archive(scratchpad, 0)?;
+
+        if let Some(parent) = scratchpad.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(scratchpad, content)?;
+
+        Ok(source)
+    }
+
+    /// Deletes all but the newest `keep` archives (`keep == 0` keeps all).
+    fn prune(&self, keep: usize) -> io::Result<()> {
+        if keep == 0 {
+            return Ok(());
+        }
+
+        for stale in self.list()?.into_iter().skip(keep) {
+            fs::remove_file(&stale.path)?;
+        }
+        Ok(())
+    }
+
+    fn path_for(&self, timestamp: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}{}{}", ARCHIVE_PREFIX, timestamp, ARCHIVE_SUFFIX))
+    }
+}
+
+/// Chronological sort key for an archive timestamp.
+///
+/// Archives created within the same second get a `-N` suffix, which must be
+/// compared numerically so `-10` sorts after `-9`.
+fn sort_key(timestamp: &str) -> (&str, u32) {
+    match timestamp.split_at_checked(15) {
+        Some((base, rest)) => (
+            base,
+            rest.strip_prefix('-')
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0),
+        ),
+        None => (timestamp, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, ScratchpadArchive, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let ctx = LoopContext::primary(temp.path().to_path_buf());
+        ctx.ensure_agent_dir().unwrap();
+        let archive = ScratchpadArchive::from_context(&ctx);
+        (temp, archive, ctx.scratchpad_path())
+    }
+
+    #[test]
+    fn test_archive_missing_scratchpad_is_noop() {
+        let (_temp, archive, scratchpad) = setup();
+
+        assert!(archive.archive(&scratchpad, 10).unwrap().is_none());
+        assert!(archive.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_blank_scratchpad_is_removed_not_archived() {
+        let (_temp, archive, scratchpad) = setup();
+        fs::write(&scratchpad, "  \n").unwrap();
+
+        assert!(archive.archive(&scratchpad, 10).unwrap().is_none());
+        assert!(!scratchpad.exists());
+        assert!(archive.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_moves_scratchpad() {
+        let (_temp, archive, scratchpad) = setup();
+        fs::write(&scratchpad, "# Plan\n- [x] done").unwrap();
+
+        let archived = archive.archive(&scratchpad, 10).unwrap().unwrap();
+
+        assert!(!scratchpad.exists());
+        assert!(archived.starts_with(archive.dir()));
+        assert_eq!(fs::read_to_string(&archived).unwrap(), "# Plan\n- [x] done");
+        assert_eq!(archive.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_archive_prunes_to_retention() {
+        let (_temp, archive, scratchpad) = setup();
+
+        for i in 0..4 {
+            fs::write(&scratchpad, format!("run {}", i)).unwrap();
+            archive.archive(&scratchpad, 2).unwrap();
+        }
+
+        let archives = archive.list().unwrap();
+        assert_eq!(archives.len(), 2);
+        // Newest first
+        assert_eq!(fs::read_to_string(&archives[0].path).unwrap(), "run 3");
+        assert_eq!(fs::read_to_string(&archives[1].path).unwrap(), "run 2");
+    }
+
+    #[test]
+    fn test_restore_copies_archive_and_preserves_current() {
+        let (_temp, archive, scratchpad) = setup();
+        fs::write(&scratchpad, "old run").unwrap();
+        archive.archive(&scratchpad, 10).unwrap();
+        let timestamp = archive.list().unwrap()[0].timestamp.clone();
+
+        fs::write(&scratchpad, "current run").unwrap();
+        archive.restore(&timestamp, &scratchpad).unwrap();
+
+        assert_eq!(fs::read_to_string(&scratchpad).unwrap(), "old run");
+        let contents: Vec<String> = archive
+            .list()
+            .unwrap()
+            .iter()
+            .map(|a| fs::read_to_string(&a.path).unwrap())
+            .collect();
+        assert!(contents.contains(&"old run".to_string()));
+        assert!(contents.contains(&"current run".to_string()));
+    }
+
+    #[test]
+    fn test_restore_unknown_timestamp() {
+        let (_temp, archive, scratchpad) = setup();
+
+        let err = archive.restore("19700101-000000", &scratchpad).unwrap_err();
+        assert!(matches!(err, ScratchpadArchiveError::NotFound(_)));
+    }
+}
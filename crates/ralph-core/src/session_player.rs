@@ -5,11 +5,16 @@
 //! plain text mode (ANSI stripped), and step-through debugging.
 
 use ralph_proto::{TerminalWrite, UxEvent};
+use std::fs::File;
 use std::io::{self, BufRead, Write};
+use std::path::Path;
 use std::time::Duration;
 
 use crate::session_recorder::Record;
 
+/// Leading bytes of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// Replay mode for session playback.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReplayMode {
@@ -154,6 +159,19 @@ impl SessionPlayer {
         })
     }
 
+    /// Creates a player from a session file.
+    ///
+    /// Gzip-compressed recordings (as written for `*.gz` paths) are
+    /// decompressed transparently.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = io::BufReader::new(File::open(path)?);
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            Self::from_reader(io::BufReader::new(flate2::bufread::GzDecoder::new(reader)))
+        } else {
+            Self::from_reader(reader)
+        }
+    }
+
     /// Creates a player from raw JSONL bytes.
     pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
         Self::from_reader(io::BufReader::new(bytes))
@@ -175,6 +193,17 @@ impl SessionPlayer {
         &self.records
     }
 
+    /// Returns the record schema version from the `_meta.header` record.
+    ///
+    /// Recordings made before the header existed report version 1.
+    pub fn schema_version(&self) -> u32 {
+        self.records
+            .iter()
+            .find(|r| r.record.event == "_meta.header")
+            .and_then(|r| r.record.data["schema_version"].as_u64())
+            .map_or(1, |v| v as u32)
+    }
+
     /// Returns records filtered by event type.
     pub fn filter_by_event(&self, event_prefix: &str) -> Vec<&TimestampedRecord> {
         self.records
@@ -515,6 +544,29 @@ mod tests {
         assert_eq!(config.event_filter, vec!["ux."]);
     }
 
+    #[test]
+    fn test_schema_version_defaults_to_one_without_header() {
+        let jsonl = r#"{"ts":1000,"event":"_meta.loop_start","data":{"prompt_file":"PROMPT.md"}}
+{"ts":1100,"event":"bus.publish","data":{"topic":"task.start","payload":"go"}}"#;
+
+        let player = SessionPlayer::from_bytes(jsonl.as_bytes()).unwrap();
+
+        assert_eq!(player.schema_version(), 1);
+        assert_eq!(player.record_count(), 2);
+    }
+
+    #[test]
+    fn test_schema_version_from_header() {
+        let jsonl = r#"{"ts":1000,"event":"_meta.header","data":{"schema_version":2,"ralph_version":"2.0.0"}}
+{"ts":1050,"event":"backend.stderr","data":{"n":1,"text":"warning\n"}}
+{"ts":1100,"event":"ux.terminal.write","data":{"bytes":"SGVsbG8=","stdout":true,"offset_ms":0}}"#;
+
+        let player = SessionPlayer::from_bytes(jsonl.as_bytes()).unwrap();
+
+        assert_eq!(player.schema_version(), 2);
+        assert_eq!(player.collect_text_output().unwrap(), "Hello");
+    }
+
     #[test]
     fn test_empty_input() {
         let player = SessionPlayer::from_bytes(b"").unwrap();
@@ -3,13 +3,27 @@
 //! `SessionRecorder` captures events from both the EventBus (routing events)
 //! and UX captures (terminal output) into a unified JSONL format for replay
 //! and analysis.
+//!
+//! Files written by [`SessionRecorder::create`] start with a `_meta.header`
+//! record carrying [`RECORD_SCHEMA_VERSION`]. Recordings from before the
+//! header existed have no such record and are treated as version 1.
 
-use ralph_proto::{Event, UxEvent};
+use crate::redact::Redactor;
+use ralph_proto::{Event, TerminalWrite, UxEvent};
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
+/// Version of the record schema written by this build.
+///
+/// Version 2 added the header, prompt, stderr, tool result, iteration
+/// boundary and truncation records.
+pub const RECORD_SCHEMA_VERSION: u32 = 2;
+
 /// A timestamped record in the JSONL session file.
 ///
 /// Records use internal tagging to distinguish event types while maintaining
@@ -58,6 +72,17 @@ impl Record {
         Self::new(event_type, ux_event)
     }
 
+    /// Creates the header record that opens a recording.
+    pub fn header() -> Self {
+        Self::new(
+            "_meta.header",
+            serde_json::json!({
+                "schema_version": RECORD_SCHEMA_VERSION,
+                "ralph_version": env!("CARGO_PKG_VERSION"),
+            }),
+        )
+    }
+
     /// Creates a metadata record for loop start.
     pub fn meta_loop_start(prompt_file: &str, max_iterations: u32, ux_mode: Option<&str>) -> Self {
         Self::new(
@@ -82,6 +107,75 @@ impl Record {
         )
     }
 
+    /// Creates a metadata record marking the start of an iteration.
+    pub fn meta_iteration_start(iteration: u32, hat: &str) -> Self {
+        Self::new(
+            "_meta.iteration_start",
+            serde_json::json!({
+                "n": iteration,
+                "hat": hat,
+            }),
+        )
+    }
+
+    /// Creates a metadata record marking the end of an iteration.
+    pub fn meta_iteration_end(iteration: u32, hat: &str, elapsed_ms: u64) -> Self {
+        Self::new(
+            "_meta.iteration_end",
+            serde_json::json!({
+                "n": iteration,
+                "hat": hat,
+                "elapsed_ms": elapsed_ms,
+            }),
+        )
+    }
+
+    /// Creates a metadata record for the rendered prompt sent to the backend.
+    pub fn meta_prompt(iteration: u32, hat: &str, prompt: &str) -> Self {
+        Self::new(
+            "_meta.prompt",
+            serde_json::json!({
+                "n": iteration,
+                "hat": hat,
+                "prompt": prompt,
+            }),
+        )
+    }
+
+    /// Creates a record for a chunk of backend stderr.
+    pub fn backend_stderr(iteration: u32, text: &str) -> Self {
+        Self::new(
+            "backend.stderr",
+            serde_json::json!({
+                "n": iteration,
+                "text": text,
+            }),
+        )
+    }
+
+    /// Creates a record for a tool result reported by the backend stream.
+    pub fn tool_result(iteration: u32, id: &str, output: &str) -> Self {
+        Self::new(
+            "backend.tool_result",
+            serde_json::json!({
+                "n": iteration,
+                "id": id,
+                "output": output,
+            }),
+        )
+    }
+
+    /// Creates the final record written when the size cap is reached.
+    pub fn meta_truncated(max_bytes: u64, bytes_written: u64) -> Self {
+        Self::new(
+            "_meta.truncated",
+            serde_json::json!({
+                "max_bytes": max_bytes,
+                "bytes_written": bytes_written,
+            }),
+        )
+    }
+
     /// Creates a metadata record for termination.
     pub fn meta_termination(
         reason: &str,
@@ -105,6 +199,8 @@ impl Record {
 ///
 /// The recorder is thread-safe and can be used as an EventBus observer.
 /// It writes each event as a JSON line immediately for crash resilience.
+/// With [`with_max_bytes`](Self::with_max_bytes), recording stops once the
+/// cap would be exceeded, after writing a single `_meta.truncated` record.
 ///
 /// # Example
 ///
@@ -133,6 +229,18 @@ pub struct SessionRecorder<W> {
 
     /// Counter for UX write events recorded.
     ux_write_count: Mutex<u32>,
+
+    /// Masks secrets before records are written.
+    redactor: Redactor,
+
+    /// Size cap in bytes of JSONL written; 0 means unlimited.
+    max_bytes: u64,
+
+    /// Bytes of JSONL written so far, before any compression.
+    bytes_written: AtomicU64,
+
+    /// Set once the size cap was hit and the truncation record written.
+    truncated: AtomicBool,
 }
 
 impl<W: Write> SessionRecorder<W> {
@@ -142,12 +250,33 @@ impl<W: Write> SessionRecorder<W> {
             writer: Mutex::new(writer),
             start_time: Instant::now(),
             ux_write_count: Mutex::new(0),
+            redactor: Redactor::default(),
+            max_bytes: 0,
+            bytes_written: AtomicU64::new(0),
+            truncated: AtomicBool::new(false),
         }
     }
 
+    /// Masks secrets matched by `redactor` in everything recorded.
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Stops recording once `max_bytes` of JSONL have been written.
+    ///
+    /// The record that would cross the cap is replaced by a final
+    /// `_meta.truncated` record, and everything after it is dropped.
+    /// 0 disables the cap.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
     /// Records an EventBus event.
     pub fn record_bus_event(&self, event: &Event) {
-        let record = Record::from_bus_event(event);
+        let mut record = Record::from_bus_event(event);
+        self.redactor.redact_json(&mut record.data);
         self.write_record(&record);
     }
 
@@ -158,7 +287,22 @@ impl<W: Write> SessionRecorder<W> {
         {
             *count += 1;
         }
-        let record = Record::from_ux_event(ux_event);
+        let record = match ux_event {
+            // Terminal bytes are base64-encoded, so redact the decoded output
+            UxEvent::TerminalWrite(write) if !self.redactor.is_empty() => {
+                match write.decode_bytes() {
+                    Ok(bytes) => {
+                        Record::from_ux_event(&UxEvent::TerminalWrite(TerminalWrite::new(
+                            &self.redactor.redact_bytes(&bytes),
+                            write.stdout,
+                            write.offset_ms,
+                        )))
+                    }
+                    Err(_) => Record::from_ux_event(ux_event),
+                }
+            }
+            _ => Record::from_ux_event(ux_event),
+        };
         self.write_record(&record);
     }
 
@@ -170,10 +314,26 @@ impl<W: Write> SessionRecorder<W> {
     }
 
     /// Records a metadata event.
-    pub fn record_meta(&self, record: Record) {
+    pub fn record_meta(&self, mut record: Record) {
+        self.redactor.redact_json(&mut record.data);
         self.write_record(&record);
     }
 
+    /// Records a chunk of backend stderr for `iteration`.
+    pub fn record_stderr(&self, iteration: u32, text: &str) {
+        self.record_meta(Record::backend_stderr(iteration, text));
+    }
+
+    /// Records a tool result for `iteration`.
+    pub fn record_tool_result(&self, iteration: u32, id: &str, output: &str) {
+        self.record_meta(Record::tool_result(iteration, id, output));
+    }
+
+    /// Returns true once the size cap stopped recording.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+
     /// Returns the number of UX write events recorded.
     pub fn ux_write_count(&self) -> u32 {
         self.ux_write_count.lock().map(|g| *g).unwrap_or(0)
@@ -184,13 +344,33 @@ impl<W: Write> SessionRecorder<W> {
         self.start_time.elapsed()
     }
 
-    /// Writes a record to the output.
+    /// Writes a record to the output, enforcing the size cap.
     fn write_record(&self, record: &Record) {
-        if let Ok(mut writer) = self.writer.lock() {
-            // Ignore write errors - recording should not interrupt execution
-            if let Ok(json) = serde_json::to_string(record) {
+        let Ok(mut writer) = self.writer.lock() else {
+            return;
+        };
+        // Counters are only touched while the writer lock is held
+        if self.truncated.load(Ordering::Relaxed) {
+            return;
+        }
+        // Ignore write errors - recording should not interrupt execution
+        let Ok(json) = serde_json::to_string(record) else {
+            return;
+        };
+        let len = json.len() as u64 + 1;
+        let written = self.bytes_written.load(Ordering::Relaxed);
+        if self.max_bytes > 0 && written + len > self.max_bytes {
+            self.truncated.store(true, Ordering::Relaxed);
+            if let Ok(json) =
+                serde_json::to_string(&Record::meta_truncated(self.max_bytes, written))
+            {
                 let _ = writeln!(writer, "{}", json);
             }
+            let _ = writer.flush();
+            return;
+        }
+        if writeln!(writer, "{}", json).is_ok() {
+            self.bytes_written.store(written + len, Ordering::Relaxed);
         }
     }
 
@@ -203,6 +383,35 @@ impl<W: Write> SessionRecorder<W> {
     }
 }
 
+/// Returns true if `path` names a gzip-compressed recording (`*.gz`).
+fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+impl SessionRecorder<Box<dyn Write + Send>> {
+    /// Creates a recorder writing to the file at `path`.
+    ///
+    /// Paths ending in `.gz` (e.g. `session.jsonl.gz`) are gzip-compressed.
+    /// The gzip stream is finished when the recorder is dropped. The file
+    /// starts with a `_meta.header` record.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        let writer: Box<dyn Write + Send> = if is_gzip_path(path) {
+            Box::new(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            ))
+        } else {
+            Box::new(file)
+        };
+        let recorder = Self::new(writer);
+        recorder.write_record(&Record::header());
+        Ok(recorder)
+    }
+}
+
 impl<W: Write + Send + 'static> SessionRecorder<W> {
     /// Creates an observer closure suitable for EventBus::set_observer.
     ///
@@ -258,6 +467,200 @@ mod tests {
         assert!(output_str.contains("SGVsbG8=")); // "Hello" in base64
     }
 
+    #[test]
+    fn test_redacts_recorded_output() {
+        use crate::redact::{DEFAULT_REDACT_PATTERNS, REDACTED, Redactor};
+
+        let secret = "sk-ant-REDACTED";
+        let mut output = Vec::new();
+        {
+            let recorder = SessionRecorder::new(&mut output)
+                .with_redactor(Redactor::new(DEFAULT_REDACT_PATTERNS).unwrap());
+            recorder.record_bus_event(&Event::new("build.done", format!("key={secret}")));
+            recorder.record_ux_event(&UxEvent::TerminalWrite(TerminalWrite::new(
+                format!("export ANTHROPIC_API_KEY={secret}\n").as_bytes(),
+                true,
+                10,
+            )));
+        }
+
+        let output_str = String::from_utf8_lossy(&output);
+        assert!(!output_str.contains(secret));
+        assert!(output_str.contains(&format!("key={REDACTED}")));
+
+        let write_line = output_str.lines().nth(1).unwrap();
+        let record: Record = serde_json::from_str(write_line).unwrap();
+        let UxEvent::TerminalWrite(write) = serde_json::from_value(record.data).unwrap() else {
+            panic!("Expected TerminalWrite event");
+        };
+        assert_eq!(
+            String::from_utf8(write.decode_bytes().unwrap()).unwrap(),
+            format!("export ANTHROPIC_API_KEY={REDACTED}\n")
+        );
+        assert_eq!(write.offset_ms, 10);
+    }
+
+    #[test]
+    fn test_gzip_recording_round_trip() {
+        use crate::session_player::SessionPlayer;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl.gz");
+        {
+            let recorder = SessionRecorder::create(&path).unwrap();
+            recorder.record_meta(Record::meta_loop_start("PROMPT.md", 10, Some("cli")));
+            recorder.record_bus_event(&Event::new("task.start", "Begin work"));
+            recorder.record_ux_event(&UxEvent::TerminalWrite(TerminalWrite::new(
+                b"\x1b[32mHello\x1b[0m",
+                true,
+                5,
+            )));
+        }
+
+        let raw = std::fs::read(&path).unwrap();
+        assert_eq!(&raw[..2], &[0x1f, 0x8b], "expected a gzip stream");
+
+        let player = SessionPlayer::from_file(&path).unwrap();
+        let events: Vec<&str> = player
+            .records()
+            .iter()
+            .map(|r| r.record.event.as_str())
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                "_meta.header",
+                "_meta.loop_start",
+                "bus.publish",
+                "ux.terminal.write"
+            ]
+        );
+        assert_eq!(player.records()[2].record.data["payload"], "Begin work");
+        let UxEvent::TerminalWrite(write) =
+            serde_json::from_value(player.records()[3].record.data.clone()).unwrap()
+        else {
+            panic!("Expected TerminalWrite event");
+        };
+        assert_eq!(write.decode_bytes().unwrap(), b"\x1b[32mHello\x1b[0m");
+    }
+
+    #[test]
+    fn test_plain_recording_is_not_compressed() {
+        use crate::session_player::SessionPlayer;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        {
+            let recorder = SessionRecorder::create(&path).unwrap();
+            recorder.record_bus_event(&Event::new("task.start", "Begin work"));
+        }
+
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("task.start")
+        );
+        assert_eq!(SessionPlayer::from_file(&path).unwrap().record_count(), 2);
+    }
+
+    #[test]
+    fn test_created_recording_starts_with_header() {
+        use crate::session_player::SessionPlayer;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        drop(SessionRecorder::create(&path).unwrap());
+
+        let player = SessionPlayer::from_file(&path).unwrap();
+        let header = &player.records()[0].record;
+        assert_eq!(header.event, "_meta.header");
+        assert_eq!(header.data["ralph_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(player.schema_version(), RECORD_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_records_iteration_prompt_stderr_and_tool_results() {
+        use crate::redact::{DEFAULT_REDACT_PATTERNS, REDACTED, Redactor};
+
+        let secret = "sk-ant-REDACTED";
+        let mut output = Vec::new();
+        {
+            let recorder = SessionRecorder::new(&mut output)
+                .with_redactor(Redactor::new(DEFAULT_REDACT_PATTERNS).unwrap());
+            recorder.record_meta(Record::meta_iteration_start(1, "builder"));
+            recorder.record_meta(Record::meta_prompt(1, "builder", "Build the thing"));
+            recorder.record_stderr(1, &format!("warning: key {secret}\n"));
+            recorder.record_tool_result(1, "toolu_1", "src/lib.rs");
+            recorder.record_meta(Record::meta_iteration_end(1, "builder", 1200));
+        }
+
+        let records: Vec<Record> = String::from_utf8_lossy(&output)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let events: Vec<&str> = records.iter().map(|r| r.event.as_str()).collect();
+        assert_eq!(
+            events,
+            vec![
+                "_meta.iteration_start",
+                "_meta.prompt",
+                "backend.stderr",
+                "backend.tool_result",
+                "_meta.iteration_end"
+            ]
+        );
+        assert_eq!(records[1].data["prompt"], "Build the thing");
+        assert_eq!(
+            records[2].data["text"],
+            format!("warning: key {REDACTED}\n")
+        );
+        assert_eq!(records[3].data["id"], "toolu_1");
+        assert_eq!(records[3].data["output"], "src/lib.rs");
+        assert_eq!(records[4].data["elapsed_ms"], 1200);
+        assert!(records.iter().all(|r| r.data["n"] == 1));
+    }
+
+    #[test]
+    fn test_size_cap_stops_recording_with_truncation_record() {
+        let mut output = Vec::new();
+        let recorder = SessionRecorder::new(&mut output).with_max_bytes(300);
+        for i in 0..20 {
+            recorder.record_bus_event(&Event::new("build.progress", format!("step {i}")));
+        }
+        assert!(recorder.is_truncated());
+        drop(recorder);
+
+        let output_str = String::from_utf8_lossy(&output);
+        let records: Vec<Record> = output_str
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let (last, kept) = records.split_last().unwrap();
+        assert_eq!(last.event, "_meta.truncated");
+        assert_eq!(last.data["max_bytes"], 300);
+        assert!(!kept.is_empty());
+        assert!(kept.iter().all(|r| r.event == "bus.publish"));
+
+        let kept_bytes: usize = output_str.lines().map(|line| line.len() + 1).sum::<usize>()
+            - output_str.lines().last().unwrap().len()
+            - 1;
+        assert!(kept_bytes <= 300);
+        assert_eq!(last.data["bytes_written"], kept_bytes as u64);
+    }
+
+    #[test]
+    fn test_zero_max_bytes_is_unlimited() {
+        let mut output = Vec::new();
+        let recorder = SessionRecorder::new(&mut output).with_max_bytes(0);
+        for i in 0..100 {
+            recorder.record_bus_event(&Event::new("build.progress", format!("step {i}")));
+        }
+        assert!(!recorder.is_truncated());
+        drop(recorder);
+
+        assert_eq!(String::from_utf8_lossy(&output).lines().count(), 100);
+    }
+
     #[test]
     fn test_record_metadata() {
         let mut output = Vec::new();
@@ -220,6 +220,12 @@ impl SummaryWriter {
             TerminationReason::Stopped => "Stopped manually",
             TerminationReason::Interrupted => "Interrupted by signal",
             TerminationReason::RestartRequested => "Restarting by human request",
+            TerminationReason::GateFailed => "Failed: failure gate event published",
+            TerminationReason::UntilEventReached => "Stopped: --until-event topic reached",
+            TerminationReason::HatBudgetExceeded => "Hat iteration budget exceeded",
+            TerminationReason::EventQueueOverflow => "Failed: event queue overflowed",
+            TerminationReason::CompletionRejected => "Failed: completion check kept failing",
+            TerminationReason::NoMatchingHat { .. } => "Failed: no hat matched a published event",
         }
     }
 
@@ -316,8 +322,19 @@ mod tests {
             abandoned_task_redispatches: 0,
             consecutive_malformed_events: 0,
             completion_requested: false,
+            completion_topic: None,
+            gate_failed_topic: None,
+            last_event_topic: None,
+            until_event_topic: None,
+            stale_until_events: 0,
+            completion_rejections: 0,
+            queue_overflow_topic: None,
+            no_matching_hat_topic: None,
+            unmatched_event_iterations: 0,
             hat_activation_counts: std::collections::HashMap::new(),
             exhausted_hats: std::collections::HashSet::new(),
+            budget_exceeded_hats: std::collections::HashSet::new(),
+            budget_exceeded_hat: None,
             last_checkin_at: None,
             last_active_hat_ids: Vec::new(),
         }
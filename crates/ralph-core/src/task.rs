@@ -26,6 +26,125 @@ impl TaskStatus {
     pub fn is_terminal(&self) -> bool {
         matches!(self, TaskStatus::Closed | TaskStatus::Failed)
     }
+
+    /// Returns the status name as stored in `tasks.jsonl`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Open => "open",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Closed => "closed",
+            TaskStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = String;
+
+    /// Parses a status name; `done` is accepted for `closed`, and dashes or
+    /// underscores in `in_progress` are optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['_', '-'], "").as_str() {
+            "open" => Ok(TaskStatus::Open),
+            "inprogress" => Ok(TaskStatus::InProgress),
+            "closed" | "done" => Ok(TaskStatus::Closed),
+            "failed" => Ok(TaskStatus::Failed),
+            _ => Err(format!(
+                "Invalid status '{}'. Valid statuses: open, in_progress, closed (done), failed",
+                s
+            )),
+        }
+    }
+}
+
+/// Named priority levels for tasks.
+///
+/// Tasks store priority as a number from 1 (highest) to 5 (lowest); the
+/// names map onto that scale so existing task files keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskPriority {
+    /// Work on this first (priority 1-2)
+    High,
+    /// The default (priority 3)
+    Normal,
+    /// Work on this when nothing else is ready (priority 4-5)
+    Low,
+}
+
+impl TaskPriority {
+    /// Returns the numeric priority stored for this level.
+    pub fn value(self) -> u8 {
+        match self {
+            TaskPriority::High => 1,
+            TaskPriority::Normal => 3,
+            TaskPriority::Low => 5,
+        }
+    }
+
+    /// Returns the level a numeric priority falls into.
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            0..=2 => TaskPriority::High,
+            3 => TaskPriority::Normal,
+            _ => TaskPriority::Low,
+        }
+    }
+
+    /// Returns the level name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskPriority::High => "high",
+            TaskPriority::Normal => "normal",
+            TaskPriority::Low => "low",
+        }
+    }
+}
+
+impl std::fmt::Display for TaskPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TaskPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high" => Ok(TaskPriority::High),
+            "normal" => Ok(TaskPriority::Normal),
+            "low" => Ok(TaskPriority::Low),
+            _ => Err(format!(
+                "Invalid priority '{}'. Valid levels: high, normal, low",
+                s
+            )),
+        }
+    }
+}
+
+/// Parses a priority given as a level name or a number from 1 to 5.
+pub fn parse_priority(s: &str) -> Result<u8, String> {
+    if let Ok(value) = s.parse::<u8>() {
+        return if (1..=5).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!(
+                "Invalid priority '{}'. Numeric priorities run from 1 to 5",
+                s
+            ))
+        };
+    }
+    s.parse::<TaskPriority>().map(TaskPriority::value)
+}
+
+fn default_priority() -> u8 {
+    TaskPriority::Normal.value()
 }
 
 /// A task in the task tracking system.
@@ -44,21 +163,35 @@ pub struct Task {
     /// Current state
     pub status: TaskStatus,
 
-    /// Priority 1-5 (1 = highest)
+    /// Priority 1-5 (1 = highest, 3 = normal)
+    #[serde(default = "default_priority")]
     pub priority: u8,
 
-    /// Tasks that must complete before this one
-    #[serde(default)]
+    /// Tasks that must complete before this one (also read as `depends_on`)
+    #[serde(default, alias = "depends_on")]
     pub blocked_by: Vec<String>,
 
+    /// Free-form labels for grouping and filtering
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// ID of the task this one was split out of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
     /// Loop ID that created this task (from RALPH_LOOP_ID env var).
     /// Used to filter tasks by ownership when multiple loops share a task list.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub loop_id: Option<String>,
 
-    /// Creation timestamp (ISO 8601)
+    /// Creation timestamp (ISO 8601); empty in hand-written records without one
+    #[serde(default)]
     pub created: String,
 
+    /// Last modification timestamp (ISO 8601); absent until first changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+
     /// Completion timestamp (ISO 8601), if closed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub closed: Option<String>,
@@ -74,12 +207,35 @@ impl Task {
             status: TaskStatus::Open,
             priority: priority.clamp(1, 5),
             blocked_by: Vec::new(),
+            tags: Vec::new(),
+            parent: None,
             loop_id: None,
             created: chrono::Utc::now().to_rfc3339(),
+            updated: None,
             closed: None,
         }
     }
 
+    /// Returns when the task last changed (its creation time if never updated).
+    pub fn updated_at(&self) -> &str {
+        self.updated.as_deref().unwrap_or(&self.created)
+    }
+
+    /// Returns true if the task has the given tag (case-insensitive).
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Records the current time as the task's last modification.
+    pub fn touch(&mut self) {
+        self.updated = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    /// Returns the named level of this task's priority.
+    pub fn priority_level(&self) -> TaskPriority {
+        TaskPriority::from_value(self.priority)
+    }
+
     /// Sets the loop ID for this task.
     pub fn with_loop_id(mut self, loop_id: Option<String>) -> Self {
         self.loop_id = loop_id;
@@ -121,6 +277,23 @@ impl Task {
         self.blocked_by.push(task_id);
         self
     }
+
+    /// Adds tags, skipping blanks and duplicates.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = String>) -> Self {
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !self.has_tag(tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+        self
+    }
+
+    /// Sets the parent task ID.
+    pub fn with_parent(mut self, parent: Option<String>) -> Self {
+        self.parent = parent;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -145,6 +318,34 @@ mod tests {
         assert_eq!(task_high.priority, 5);
     }
 
+    #[test]
+    fn test_parse_priority_accepts_names_and_numbers() {
+        assert_eq!(parse_priority("high"), Ok(1));
+        assert_eq!(parse_priority("Normal"), Ok(3));
+        assert_eq!(parse_priority("low"), Ok(5));
+        assert_eq!(parse_priority("2"), Ok(2));
+        assert!(parse_priority("0").is_err());
+        assert!(parse_priority("urgent").is_err());
+    }
+
+    #[test]
+    fn test_priority_level_and_default() {
+        assert_eq!(
+            Task::new("t".to_string(), 2).priority_level(),
+            TaskPriority::High
+        );
+        assert_eq!(
+            Task::new("t".to_string(), 4).priority_level(),
+            TaskPriority::Low
+        );
+
+        let task: Task = serde_json::from_str(
+            r#"{"id":"task-1","title":"t","status":"open","created":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(task.priority_level(), TaskPriority::Normal);
+    }
+
     #[test]
     fn test_task_id_format() {
         let task = Task::new("Test".to_string(), 1);
@@ -192,6 +393,44 @@ mod tests {
         assert!(!task.is_ready(&[]));
     }
 
+    #[test]
+    fn test_status_parses_names_and_done_alias() {
+        assert_eq!("open".parse(), Ok(TaskStatus::Open));
+        assert_eq!("in-progress".parse(), Ok(TaskStatus::InProgress));
+        assert_eq!("In_Progress".parse(), Ok(TaskStatus::InProgress));
+        assert_eq!("done".parse(), Ok(TaskStatus::Closed));
+        assert_eq!("closed".parse(), Ok(TaskStatus::Closed));
+        assert_eq!("failed".parse(), Ok(TaskStatus::Failed));
+        assert!("finished".parse::<TaskStatus>().is_err());
+        assert_eq!(TaskStatus::InProgress.to_string(), "in_progress");
+    }
+
+    #[test]
+    fn test_tags_parent_and_updated_round_trip() {
+        let mut task = Task::new("Tagged".to_string(), 3)
+            .with_tags(vec!["ui".to_string(), " UI ".to_string(), String::new()])
+            .with_parent(Some("task-1-abcd".to_string()));
+        assert_eq!(task.tags, vec!["ui"]);
+        assert!(task.has_tag("UI"));
+        assert_eq!(task.updated_at(), task.created);
+
+        task.touch();
+        let json = serde_json::to_string(&task).unwrap();
+        let parsed: Task = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tags, vec!["ui"]);
+        assert_eq!(parsed.parent.as_deref(), Some("task-1-abcd"));
+        assert_eq!(parsed.updated, task.updated);
+
+        // Older records without the new fields still load
+        let old: Task = serde_json::from_str(
+            r#"{"id":"task-1","title":"t","status":"open","created":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(old.tags.is_empty());
+        assert_eq!(old.parent, None);
+        assert_eq!(old.updated_at(), "2025-01-01T00:00:00Z");
+    }
+
     #[test]
     fn test_is_terminal() {
         assert!(!TaskStatus::Open.is_terminal());
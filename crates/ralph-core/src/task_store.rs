@@ -13,13 +13,33 @@
 //!
 //! Use `load()` and `save()` for simple single-operation access, or use
 //! `with_exclusive_lock()` for read-modify-write operations that need atomicity.
+//!
+//! Writes go to a temporary file that is renamed over `tasks.jsonl`, so a
+//! reader never sees a half-written file.
 
 use crate::file_lock::FileLock;
 use crate::task::{Task, TaskStatus};
+use std::collections::HashSet;
 use std::io;
 use std::path::Path;
 use tracing::warn;
 
+/// Errors from editing task dependencies.
+#[derive(Debug, thiserror::Error)]
+pub enum TaskDependencyError {
+    /// No task exists with the given ID.
+    #[error("Task {0} not found")]
+    NotFound(String),
+
+    /// A task cannot depend on itself.
+    #[error("Task {0} cannot be blocked by itself")]
+    SelfDependency(String),
+
+    /// The dependency would close a cycle; holds the cycle's task IDs.
+    #[error("Dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
 /// A store for managing tasks with JSONL persistence and file locking.
 pub struct TaskStore {
     path: std::path::PathBuf,
@@ -27,6 +47,43 @@ pub struct TaskStore {
     lock: FileLock,
 }
 
+/// Reads all tasks from `path`, or none if the file doesn't exist.
+fn read_tasks(path: &Path) -> io::Result<Vec<Task>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(parse_task_line)
+        .collect())
+}
+
+/// Writes all tasks to `path` via a temporary file and rename.
+fn write_tasks(path: &Path, tasks: &[Task]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for task in tasks {
+        let line = serde_json::to_string(task).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("task serialization failed: {e}"),
+            )
+        })?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    let tmp_path = path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })
+}
+
 /// Parses a JSONL line into a Task, logging a warning on failure.
 fn parse_task_line(line: &str) -> Option<Task> {
     match serde_json::from_str(line) {
@@ -52,17 +109,7 @@ impl TaskStore {
     pub fn load(path: &Path) -> io::Result<Self> {
         let lock = FileLock::new(path)?;
         let _guard = lock.shared()?;
-
-        let tasks = if path.exists() {
-            let content = std::fs::read_to_string(path)?;
-            content
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .filter_map(|line| parse_task_line(line))
-                .collect()
-        } else {
-            Vec::new()
-        };
+        let tasks = read_tasks(path)?;
 
         Ok(Self {
             path: path.to_path_buf(),
@@ -77,31 +124,7 @@ impl TaskStore {
     /// Uses an exclusive lock to prevent concurrent writes.
     pub fn save(&self) -> io::Result<()> {
         let _guard = self.lock.exclusive()?;
-
-        if let Some(parent) = self.path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        let content: String = self
-            .tasks
-            .iter()
-            .map(|t| {
-                serde_json::to_string(t).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("task serialization failed: {e}"),
-                    )
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-        std::fs::write(
-            &self.path,
-            if content.is_empty() {
-                String::new()
-            } else {
-                content + "\n"
-            },
-        )
+        write_tasks(&self.path, &self.tasks)
     }
 
     /// Reloads tasks from disk, useful after external modifications.
@@ -110,18 +133,7 @@ impl TaskStore {
     /// Uses a shared lock to allow concurrent reads.
     pub fn reload(&mut self) -> io::Result<()> {
         let _guard = self.lock.shared()?;
-
-        self.tasks = if self.path.exists() {
-            let content = std::fs::read_to_string(&self.path)?;
-            content
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .filter_map(|line| parse_task_line(line))
-                .collect()
-        } else {
-            Vec::new()
-        };
-
+        self.tasks = read_tasks(&self.path)?;
         Ok(())
     }
 
@@ -146,46 +158,11 @@ impl TaskStore {
         let _guard = self.lock.exclusive()?;
 
         // Reload to get latest changes from other loops
-        self.tasks = if self.path.exists() {
-            let content = std::fs::read_to_string(&self.path)?;
-            content
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .filter_map(|line| parse_task_line(line))
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        // Execute the user function
-        let result = f(self);
+        self.tasks = read_tasks(&self.path)?;
 
-        // Save changes
-        if let Some(parent) = self.path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        let content: String = self
-            .tasks
-            .iter()
-            .map(|t| {
-                serde_json::to_string(t).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("task serialization failed: {e}"),
-                    )
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-        std::fs::write(
-            &self.path,
-            if content.is_empty() {
-                String::new()
-            } else {
-                content + "\n"
-            },
-        )?;
+        let result = f(self);
 
+        write_tasks(&self.path, &self.tasks)?;
         Ok(result)
     }
 
@@ -207,22 +184,24 @@ impl TaskStore {
 
     /// Closes a task by ID and returns a reference to it.
     pub fn close(&mut self, id: &str) -> Option<&Task> {
-        if let Some(task) = self.get_mut(id) {
-            task.status = TaskStatus::Closed;
-            task.closed = Some(chrono::Utc::now().to_rfc3339());
-            return self.get(id);
-        }
-        None
+        self.set_status(id, TaskStatus::Closed)
     }
 
     /// Fails a task by ID and returns a reference to it.
     pub fn fail(&mut self, id: &str) -> Option<&Task> {
-        if let Some(task) = self.get_mut(id) {
-            task.status = TaskStatus::Failed;
-            task.closed = Some(chrono::Utc::now().to_rfc3339());
-            return self.get(id);
-        }
-        None
+        self.set_status(id, TaskStatus::Failed)
+    }
+
+    /// Sets a task's status and returns a reference to it.
+    ///
+    /// Terminal statuses record the completion time; reopening a task
+    /// clears it.
+    pub fn set_status(&mut self, id: &str, status: TaskStatus) -> Option<&Task> {
+        let task = self.get_mut(id)?;
+        task.status = status;
+        task.touch();
+        task.closed = status.is_terminal().then(|| task.updated_at().to_string());
+        Some(task)
     }
 
     /// Returns all tasks as a slice.
@@ -230,6 +209,33 @@ impl TaskStore {
         &self.tasks
     }
 
+    /// Returns tasks with the given status.
+    pub fn by_status(&self, status: TaskStatus) -> Vec<&Task> {
+        self.tasks.iter().filter(|t| t.status == status).collect()
+    }
+
+    /// Returns tasks carrying the given tag (case-insensitive).
+    pub fn by_tag(&self, tag: &str) -> Vec<&Task> {
+        self.tasks.iter().filter(|t| t.has_tag(tag)).collect()
+    }
+
+    /// Returns unfinished tasks still waiting on at least one blocker.
+    ///
+    /// A blocker counts as pending until it is closed; unknown blocker IDs
+    /// also keep a task blocked, matching [`Task::is_ready`].
+    pub fn blocked(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| {
+                !t.status.is_terminal()
+                    && t.blocked_by.iter().any(|blocker| {
+                        self.get(blocker)
+                            .is_none_or(|b| b.status != TaskStatus::Closed)
+                    })
+            })
+            .collect()
+    }
+
     /// Returns all open tasks (not closed).
     pub fn open(&self) -> Vec<&Task> {
         self.tasks
@@ -239,11 +245,145 @@ impl TaskStore {
     }
 
     /// Returns all ready tasks (open with no pending blockers).
+    ///
+    /// Sorted by priority (highest first), then by creation time (oldest
+    /// first), so the first task is the one to pick up next.
     pub fn ready(&self) -> Vec<&Task> {
-        self.tasks
+        let mut ready: Vec<&Task> = self
+            .tasks
             .iter()
             .filter(|t| t.is_ready(&self.tasks))
-            .collect()
+            .collect();
+        ready.sort_by(|a, b| {
+            a.priority
+                .cmp(&b.priority)
+                .then_with(|| a.created.cmp(&b.created))
+        });
+        ready
+    }
+
+    /// Sets a task's priority (clamped to 1-5) and returns a reference to it.
+    pub fn set_priority(&mut self, id: &str, priority: u8) -> Option<&Task> {
+        let task = self.get_mut(id)?;
+        task.priority = priority.clamp(1, 5);
+        task.touch();
+        Some(task)
+    }
+
+    /// Makes task `id` wait for task `on` to complete.
+    ///
+    /// Adding a dependency that already exists is a no-op. Fails without
+    /// changing anything if either task is missing or if `on` already depends
+    /// (directly or transitively) on `id`, since that would create a cycle.
+    pub fn block(&mut self, id: &str, on: &str) -> Result<&Task, TaskDependencyError> {
+        if self.get(id).is_none() {
+            return Err(TaskDependencyError::NotFound(id.to_string()));
+        }
+        if self.get(on).is_none() {
+            return Err(TaskDependencyError::NotFound(on.to_string()));
+        }
+        if id == on {
+            return Err(TaskDependencyError::SelfDependency(id.to_string()));
+        }
+        if let Some(mut path) = self.dependency_path(on, id) {
+            // on -> ... -> id, closed by the new edge id -> on
+            path.push(on.to_string());
+            return Err(TaskDependencyError::Cycle(path));
+        }
+
+        let task = self.get_mut(id).expect("task existence checked above");
+        if !task.blocked_by.iter().any(|b| b == on) {
+            task.blocked_by.push(on.to_string());
+            task.touch();
+        }
+        Ok(task)
+    }
+
+    /// Removes task `on` from task `id`'s blockers.
+    ///
+    /// Returns `Ok(false)` if `id` wasn't blocked by `on`.
+    pub fn unblock(&mut self, id: &str, on: &str) -> Result<bool, TaskDependencyError> {
+        let task = self
+            .get_mut(id)
+            .ok_or_else(|| TaskDependencyError::NotFound(id.to_string()))?;
+        let before = task.blocked_by.len();
+        task.blocked_by.retain(|b| b != on);
+        let changed = task.blocked_by.len() != before;
+        if changed {
+            task.touch();
+        }
+        Ok(changed)
+    }
+
+    /// Returns a dependency cycle among the stored tasks, if there is one.
+    ///
+    /// The cycle is returned as task IDs with the first ID repeated at the
+    /// end (e.g., `[a, b, a]`). Tasks in a cycle can never become ready.
+    /// Dependencies on unknown task IDs are ignored.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut done: HashSet<&str> = HashSet::new();
+        for task in &self.tasks {
+            let mut stack: Vec<&str> = Vec::new();
+            if let Some(cycle) = self.visit(&task.id, &mut stack, &mut done) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    /// Depth-first search for a cycle reachable from `id`.
+    ///
+    /// `stack` holds the current path; `done` holds tasks already known to be
+    /// cycle-free, so shared dependencies (diamonds) are only walked once.
+    fn visit<'a>(
+        &'a self,
+        id: &'a str,
+        stack: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = stack.iter().position(|s| *s == id) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(ToString::to_string).collect();
+            cycle.push(id.to_string());
+            return Some(cycle);
+        }
+        if done.contains(id) {
+            return None;
+        }
+
+        let task = self.get(id)?;
+        stack.push(id);
+        for blocker in &task.blocked_by {
+            if let Some(cycle) = self.visit(blocker, stack, done) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        done.insert(id);
+        None
+    }
+
+    /// Returns the chain of task IDs from `from` to `to` along `blocked_by`
+    /// edges, if `from` depends on `to`.
+    fn dependency_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<Vec<&str>> = vec![vec![from]];
+        while let Some(path) = stack.pop() {
+            let current = *path.last().expect("paths are never empty");
+            if current == to {
+                return Some(path.iter().map(ToString::to_string).collect());
+            }
+            if !seen.insert(current) {
+                continue;
+            }
+            if let Some(task) = self.get(current) {
+                for blocker in &task.blocked_by {
+                    let mut next = path.clone();
+                    next.push(blocker);
+                    stack.push(next);
+                }
+            }
+        }
+        None
     }
 
     /// Returns true if there are any open tasks.
@@ -352,6 +492,113 @@ mod tests {
         assert_eq!(ready[0].title, "Ready");
     }
 
+    #[test]
+    fn test_ready_sorted_by_priority_then_creation() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        let mut store = TaskStore::load(&path).unwrap();
+
+        for (title, priority, created) in [
+            ("old low", 5, "2025-01-01T00:00:00Z"),
+            ("new normal", 3, "2025-01-03T00:00:00Z"),
+            ("new high", 1, "2025-01-04T00:00:00Z"),
+            ("old normal", 3, "2025-01-02T00:00:00Z"),
+        ] {
+            let mut task = Task::new(title.to_string(), priority);
+            task.created = created.to_string();
+            store.add(task);
+        }
+
+        let titles: Vec<&str> = store.ready().iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["new high", "old normal", "new normal", "old low"]
+        );
+    }
+
+    #[test]
+    fn test_set_priority() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        let mut store = TaskStore::load(&path).unwrap();
+        let task = Task::new("Test".to_string(), 3);
+        let id = task.id.clone();
+        store.add(task);
+
+        assert_eq!(store.set_priority(&id, 1).unwrap().priority, 1);
+        assert_eq!(store.set_priority(&id, 9).unwrap().priority, 5);
+        assert!(store.set_priority("task-missing", 1).is_none());
+    }
+
+    #[test]
+    fn test_set_status_tracks_updated_and_closed() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        let mut store = TaskStore::load(&path).unwrap();
+        let task = Task::new("Test".to_string(), 3);
+        let id = task.id.clone();
+        store.add(task);
+
+        let task = store.set_status(&id, TaskStatus::InProgress).unwrap();
+        assert!(task.updated.is_some());
+        assert!(task.closed.is_none());
+
+        let task = store.set_status(&id, TaskStatus::Closed).unwrap();
+        assert_eq!(task.closed, task.updated);
+
+        // Reopening clears the completion time
+        let task = store.set_status(&id, TaskStatus::Open).unwrap();
+        assert!(task.closed.is_none());
+        assert!(store.set_status("task-missing", TaskStatus::Open).is_none());
+    }
+
+    #[test]
+    fn test_query_by_status_tag_and_blocked() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        let mut store = TaskStore::load(&path).unwrap();
+
+        let blocker = Task::new("Schema".to_string(), 3).with_tags(vec!["db".to_string()]);
+        let blocker_id = blocker.id.clone();
+        store.add(blocker);
+        let waiting = Task::new("Migrate".to_string(), 3)
+            .with_tags(vec!["DB".to_string(), "ops".to_string()])
+            .with_blocker(blocker_id.clone());
+        store.add(waiting);
+        let mut done = Task::new("Docs".to_string(), 3).with_blocker("task-gone".to_string());
+        done.status = TaskStatus::Closed;
+        store.add(done);
+
+        let titles = |tasks: Vec<&Task>| tasks.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(store.by_tag("db")), vec!["Schema", "Migrate"]);
+        assert_eq!(titles(store.by_tag("ops")), vec!["Migrate"]);
+        assert_eq!(titles(store.by_status(TaskStatus::Closed)), vec!["Docs"]);
+        assert_eq!(titles(store.blocked()), vec!["Migrate"]);
+
+        store.close(&blocker_id);
+        assert!(store.blocked().is_empty());
+        assert_eq!(titles(store.ready()), vec!["Migrate"]);
+    }
+
+    #[test]
+    fn test_save_replaces_file_without_leaving_temp_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        let mut store = TaskStore::load(&path).unwrap();
+        store.add(Task::new("One".to_string(), 3));
+        store.save().unwrap();
+        store.add(Task::new("Two".to_string(), 3));
+        store.save().unwrap();
+
+        let files: Vec<std::path::PathBuf> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "tmp"))
+            .collect();
+        assert!(files.is_empty(), "leftover temp files: {files:?}");
+        assert_eq!(TaskStore::load(&path).unwrap().all().len(), 2);
+    }
+
     #[test]
     fn test_has_open_tasks() {
         let tmp = TempDir::new().unwrap();
@@ -511,4 +758,114 @@ mod tests {
         assert_eq!(loaded.all().len(), 1);
         assert_eq!(loaded.all()[0].title, "Valid task");
     }
+
+    /// Builds a store with tasks whose IDs are the given names.
+    fn store_with(tmp: &TempDir, ids: &[&str]) -> TaskStore {
+        let mut store = TaskStore::load(&tmp.path().join("tasks.jsonl")).unwrap();
+        for id in ids {
+            let mut task = Task::new(format!("Task {}", id), 3);
+            task.id = id.to_string();
+            store.add(task);
+        }
+        store
+    }
+
+    fn ready_ids(store: &TaskStore) -> Vec<&str> {
+        store.ready().iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_diamond_dependencies_ready_in_order() {
+        let tmp = TempDir::new().unwrap();
+        // b and c depend on a; d depends on both b and c
+        let mut store = store_with(&tmp, &["a", "b", "c", "d"]);
+        store.block("b", "a").unwrap();
+        store.block("c", "a").unwrap();
+        store.block("d", "b").unwrap();
+        store.block("d", "c").unwrap();
+
+        assert!(store.find_cycle().is_none());
+        assert_eq!(ready_ids(&store), vec!["a"]);
+
+        store.close("a");
+        assert_eq!(ready_ids(&store), vec!["b", "c"]);
+
+        store.close("b");
+        assert_eq!(ready_ids(&store), vec!["c"]);
+
+        store.close("c");
+        assert_eq!(ready_ids(&store), vec!["d"]);
+    }
+
+    #[test]
+    fn test_block_rejects_cycles() {
+        let tmp = TempDir::new().unwrap();
+        let mut store = store_with(&tmp, &["a", "b", "c"]);
+        store.block("b", "a").unwrap();
+        store.block("c", "b").unwrap();
+
+        let err = store.block("a", "c").unwrap_err();
+        match err {
+            TaskDependencyError::Cycle(path) => assert_eq!(path, vec!["c", "b", "a", "c"]),
+            other => panic!("expected cycle, got {other:?}"),
+        }
+        assert!(store.get("a").unwrap().blocked_by.is_empty());
+
+        assert!(matches!(
+            store.block("a", "a"),
+            Err(TaskDependencyError::SelfDependency(_))
+        ));
+        assert!(matches!(
+            store.block("a", "missing"),
+            Err(TaskDependencyError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_block_is_idempotent_and_unblock_removes() {
+        let tmp = TempDir::new().unwrap();
+        let mut store = store_with(&tmp, &["a", "b"]);
+        store.block("b", "a").unwrap();
+        store.block("b", "a").unwrap();
+        assert_eq!(store.get("b").unwrap().blocked_by, vec!["a"]);
+
+        assert!(store.unblock("b", "a").unwrap());
+        assert!(!store.unblock("b", "a").unwrap());
+        assert_eq!(ready_ids(&store), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_find_cycle_in_existing_tasks() {
+        let tmp = TempDir::new().unwrap();
+        let mut store = store_with(&tmp, &["a", "b", "c"]);
+        // Cycles can't be created through block(), but tasks.jsonl may be hand-edited
+        store.get_mut("a").unwrap().blocked_by.push("b".to_string());
+        store.get_mut("b").unwrap().blocked_by.push("c".to_string());
+        store.get_mut("c").unwrap().blocked_by.push("a".to_string());
+
+        assert_eq!(
+            store.find_cycle(),
+            Some(
+                vec!["a", "b", "c", "a"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            )
+        );
+        assert!(store.ready().is_empty());
+    }
+
+    #[test]
+    fn test_depends_on_alias_is_read_as_blocked_by() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tasks.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"id":"task-1","title":"t","status":"open","priority":3,"depends_on":["task-0"],"created":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+
+        let store = TaskStore::load(&path).unwrap();
+        assert_eq!(store.get("task-1").unwrap().blocked_by, vec!["task-0"]);
+    }
 }
@@ -21,7 +21,7 @@
 
 use crate::session_player::SessionPlayer;
 use ralph_proto::UxEvent;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead};
 use std::path::Path;
 use std::time::Duration;
 
@@ -55,15 +55,13 @@ pub struct ReplayBackend {
 }
 
 impl ReplayBackend {
-    /// Creates a replay backend from a JSONL file.
+    /// Creates a replay backend from a JSONL file (optionally gzipped).
     ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be opened or contains invalid JSON.
     pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
-        let file = std::fs::File::open(path.as_ref())?;
-        let reader = BufReader::new(file);
-        Self::from_reader(reader)
+        Ok(Self::from_player(SessionPlayer::from_file(path)?))
     }
 
     /// Creates a replay backend from a JSONL reader.
@@ -72,8 +70,10 @@ impl ReplayBackend {
     ///
     /// Returns an error if the JSONL data is malformed.
     pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
-        let player = SessionPlayer::from_reader(reader)?;
+        Ok(Self::from_player(SessionPlayer::from_reader(reader)?))
+    }
 
+    fn from_player(player: SessionPlayer) -> Self {
         // Pre-compute indices of terminal write records for efficient iteration
         let terminal_write_indices: Vec<usize> = player
             .records()
@@ -83,13 +83,13 @@ impl ReplayBackend {
             .map(|(i, _)| i)
             .collect();
 
-        Ok(Self {
+        Self {
             player,
             position: 0,
             timing_mode: ReplayTimingMode::default(),
             terminal_write_indices,
             last_offset_ms: 0,
-        })
+        }
     }
 
     /// Creates a replay backend from raw JSONL bytes.
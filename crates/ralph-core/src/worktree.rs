@@ -30,20 +30,27 @@
 
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufRead, BufReader, Write};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
 /// Configuration for worktree operations.
 #[derive(Debug, Clone)]
 pub struct WorktreeConfig {
     /// Directory where worktrees are created (default: `.worktrees`).
+    ///
+    /// Relative paths resolve against the repo root; absolute paths may point
+    /// outside the repo.
     pub worktree_dir: PathBuf,
+
+    /// Ref new worktree branches are created from (default: current HEAD).
+    pub base_branch: Option<String>,
 }
 
 impl Default for WorktreeConfig {
     fn default() -> Self {
         Self {
             worktree_dir: PathBuf::from(".worktrees"),
+            base_branch: None,
         }
     }
 }
@@ -53,9 +60,17 @@ impl WorktreeConfig {
     pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
         Self {
             worktree_dir: dir.into(),
+            ..Self::default()
         }
     }
 
+    /// Branch new worktrees from `base` instead of the current HEAD.
+    #[must_use]
+    pub fn with_base_branch(mut self, base: impl Into<String>) -> Self {
+        self.base_branch = Some(base.into());
+        self
+    }
+
     /// Get the absolute path to worktree directory relative to repo root.
     pub fn worktree_path(&self, repo_root: &Path) -> PathBuf {
         if self.worktree_dir.is_absolute() {
@@ -64,6 +79,59 @@ impl WorktreeConfig {
             repo_root.join(&self.worktree_dir)
         }
     }
+
+    /// The worktree directory relative to `repo_root`, or `None` when it
+    /// lies outside the repo.
+    pub fn repo_relative_dir(&self, repo_root: &Path) -> Option<PathBuf> {
+        let relative = if self.worktree_dir.is_absolute() {
+            let dir = normalize_lexically(&self.worktree_dir);
+            match dir.strip_prefix(normalize_lexically(repo_root)) {
+                Ok(relative) => relative.to_path_buf(),
+                // Either side may be behind a symlink (e.g. /tmp on macOS)
+                Err(_) => {
+                    let root = repo_root.canonicalize().ok()?;
+                    let dir = canonicalize_existing_prefix(&dir);
+                    dir.strip_prefix(root).ok()?.to_path_buf()
+                }
+            }
+        } else {
+            normalize_lexically(&self.worktree_dir)
+        };
+        let inside = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        (inside && !relative.as_os_str().is_empty()).then_some(relative)
+    }
+}
+
+/// Resolves `.` and `..` components without touching the filesystem.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => out.push(".."),
+            },
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Canonicalizes the longest existing ancestor of `path`, keeping the rest.
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return canonical.join(rest);
+        }
+    }
+    path.to_path_buf()
 }
 
 /// Information about a git worktree.
@@ -121,12 +189,32 @@ pub enum WorktreeError {
     /// Branch already exists.
     #[error("Branch already exists: {0}")]
     BranchExists(String),
+
+    /// The configured base branch doesn't resolve to a commit.
+    #[error("Base branch not found: {0}")]
+    BaseBranchNotFound(String),
+
+    /// The branch has commits not merged into HEAD.
+    #[error("Branch has unmerged commits: {0}")]
+    UnmergedBranch(String),
+}
+
+/// Returns true if `reference` resolves to a commit in the repository.
+fn ref_exists(repo_root: &Path, reference: &str) -> Result<bool, WorktreeError> {
+    let status = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{reference}^{{commit}}"))
+        .current_dir(repo_root)
+        .stdout(std::process::Stdio::null())
+        .status()?;
+    Ok(status.success())
 }
 
 /// Create a new worktree for a parallel Ralph loop.
 ///
 /// Creates a new branch and worktree at `{config.worktree_dir}/{loop_id}`.
-/// The branch is created from HEAD of the current branch.
+/// The branch is created from `config.base_branch`, or from HEAD of the
+/// current branch when no base is set.
 ///
 /// # Arguments
 ///
@@ -162,14 +250,21 @@ pub fn create_worktree(
         ));
     }
 
+    if let Some(base) = &config.base_branch
+        && !ref_exists(repo_root, base)?
+    {
+        return Err(WorktreeError::BaseBranchNotFound(base.clone()));
+    }
+
     // Ensure worktree directory exists
     fs::create_dir_all(&worktree_base)?;
 
     // Create worktree with new branch
-    // git worktree add -b <branch> <path>
+    // git worktree add -b <branch> <path> [<base>]
     let output = Command::new("git")
         .args(["worktree", "add", "-b", &branch_name])
         .arg(&worktree_path)
+        .args(config.base_branch.as_deref())
         .current_dir(repo_root)
         .output()?;
 
@@ -227,7 +322,7 @@ pub fn create_worktree(
 ///
 /// # Note
 ///
-/// This also deletes the associated branch if it exists.
+/// The worktree's branch is kept; see [`delete_branch`].
 pub fn remove_worktree(
     repo_root: impl AsRef<Path>,
     worktree_path: impl AsRef<Path>,
@@ -241,9 +336,6 @@ pub fn remove_worktree(
         ));
     }
 
-    // Get the branch name before removing
-    let branch = get_worktree_branch(worktree_path);
-
     // Remove the worktree (--force handles uncommitted changes)
     let output = Command::new("git")
         .args(["worktree", "remove", "--force"])
@@ -256,22 +348,6 @@ pub fn remove_worktree(
         return Err(WorktreeError::Git(stderr.to_string()));
     }
 
-    // Delete the branch if it was a ralph/* branch
-    if let Some(branch) = branch
-        && branch.starts_with("ralph/")
-    {
-        let output = Command::new("git")
-            .args(["branch", "-D", &branch])
-            .current_dir(repo_root)
-            .output()?;
-
-        if !output.status.success() {
-            // Non-fatal: branch might already be deleted
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            tracing::debug!("Failed to delete branch {}: {}", branch, stderr);
-        }
-    }
-
     // Prune worktree refs
     let _ = Command::new("git")
         .args(["worktree", "prune"])
@@ -283,6 +359,151 @@ pub fn remove_worktree(
     Ok(())
 }
 
+/// Delete a local branch, returning `false` if it did not exist.
+///
+/// Without `force` the branch must be merged into HEAD; otherwise
+/// [`WorktreeError::UnmergedBranch`] is returned and the branch is kept.
+pub fn delete_branch(
+    repo_root: impl AsRef<Path>,
+    branch: &str,
+    force: bool,
+) -> Result<bool, WorktreeError> {
+    let repo_root = repo_root.as_ref();
+    if !ref_exists(repo_root, &format!("refs/heads/{branch}"))? {
+        return Ok(false);
+    }
+    if !force {
+        let merged = Command::new("git")
+            .args(["merge-base", "--is-ancestor", branch, "HEAD"])
+            .current_dir(repo_root)
+            .status()?;
+        if !merged.success() {
+            return Err(WorktreeError::UnmergedBranch(branch.to_string()));
+        }
+    }
+
+    let output = Command::new("git")
+        .args(["branch", "-D", branch])
+        .current_dir(repo_root)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WorktreeError::Git(stderr.to_string()));
+    }
+
+    tracing::debug!("Deleted branch {}", branch);
+    Ok(true)
+}
+
+/// List local `ralph/` branches, whether or not a worktree uses them.
+pub fn list_ralph_branches(repo_root: impl AsRef<Path>) -> Result<Vec<String>, WorktreeError> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname:short)",
+            "refs/heads/ralph/",
+        ])
+        .current_dir(repo_root.as_ref())
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WorktreeError::Git(stderr.to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Rename a loop's worktree and its `ralph/` branch.
+///
+/// Moves `{config.worktree_dir}/{loop_id}` to `{config.worktree_dir}/{new_id}`
+/// and renames `ralph/{loop_id}` to `ralph/{new_id}`. If the branch rename
+/// fails, the worktree is moved back.
+///
+/// # Arguments
+///
+/// * `repo_root` - Root of the git repository
+/// * `loop_id` - Current loop identifier
+/// * `new_id` - New loop identifier
+/// * `config` - Worktree configuration
+pub fn rename_worktree(
+    repo_root: impl AsRef<Path>,
+    loop_id: &str,
+    new_id: &str,
+    config: &WorktreeConfig,
+) -> Result<Worktree, WorktreeError> {
+    let repo_root = repo_root.as_ref();
+    let worktree_base = config.worktree_path(repo_root);
+    let old_path = worktree_base.join(loop_id);
+    let new_path = worktree_base.join(new_id);
+    let old_branch = format!("ralph/{loop_id}");
+    let new_branch = format!("ralph/{new_id}");
+
+    if !old_path.exists() {
+        return Err(WorktreeError::NotFound(
+            old_path.to_string_lossy().to_string(),
+        ));
+    }
+    if worktree_exists(repo_root, new_id, config) {
+        return Err(WorktreeError::AlreadyExists(
+            new_path.to_string_lossy().to_string(),
+        ));
+    }
+    let branch_exists = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{new_branch}"))
+        .current_dir(repo_root)
+        .output()?
+        .status
+        .success();
+    if branch_exists {
+        return Err(WorktreeError::BranchExists(new_branch));
+    }
+
+    let output = Command::new("git")
+        .args(["worktree", "move"])
+        .arg(&old_path)
+        .arg(&new_path)
+        .current_dir(repo_root)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WorktreeError::Git(stderr.to_string()));
+    }
+
+    let output = Command::new("git")
+        .args(["branch", "-m", &old_branch, &new_branch])
+        .current_dir(repo_root)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let _ = Command::new("git")
+            .args(["worktree", "move"])
+            .arg(&new_path)
+            .arg(&old_path)
+            .current_dir(repo_root)
+            .output();
+        return Err(WorktreeError::Git(stderr.to_string()));
+    }
+
+    tracing::debug!(
+        "Renamed worktree {} to {} (branch {})",
+        old_path.display(),
+        new_path.display(),
+        new_branch
+    );
+
+    Ok(Worktree {
+        head: get_head_commit(&new_path).ok(),
+        path: new_path,
+        branch: new_branch,
+        is_main: false,
+    })
+}
+
 /// List all git worktrees in the repository.
 ///
 /// # Arguments
@@ -428,21 +649,30 @@ pub fn ensure_gitignore(
     Ok(())
 }
 
-/// Get the branch name for a worktree.
-fn get_worktree_branch(worktree_path: &Path) -> Option<String> {
-    let output = Command::new("git")
-        .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .current_dir(worktree_path)
-        .output()
-        .ok()?;
-
-    if output.status.success() {
-        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if branch != "HEAD" {
-            return Some(branch);
-        }
-    }
-    None
+/// Ensure the configured worktree directory is in `.gitignore`.
+///
+/// A directory outside the repo is left alone. Returns whether the
+/// directory is inside the repo (and therefore ignored).
+pub fn ensure_worktree_dir_ignored(
+    repo_root: impl AsRef<Path>,
+    config: &WorktreeConfig,
+) -> Result<bool, WorktreeError> {
+    let repo_root = repo_root.as_ref();
+    let Some(relative) = config.repo_relative_dir(repo_root) else {
+        tracing::debug!(
+            "Worktree dir {} is outside the repo; leaving .gitignore untouched",
+            config.worktree_path(repo_root).display()
+        );
+        return Ok(false);
+    };
+    // .gitignore patterns always use forward slashes
+    let pattern = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    ensure_gitignore(repo_root, &pattern)?;
+    Ok(true)
 }
 
 /// Get the HEAD commit SHA for a worktree.
@@ -577,6 +807,7 @@ fn copy_file_with_structure(
 ///
 /// - `.git/` directory (never copied)
 /// - The worktree directory itself (e.g., `.worktrees/`)
+/// - The scratchpad archive (`.ralph/agent/archive/`), which is loop-local
 ///
 /// # Arguments
 ///
@@ -594,8 +825,9 @@ pub fn sync_working_directory_to_worktree(
 ) -> Result<SyncStats, WorktreeError> {
     let mut stats = SyncStats::default();
 
-    // Get the worktree directory name for exclusion
-    let worktree_dir = &config.worktree_dir;
+    // Get the worktree directory name for exclusion (nothing to exclude when
+    // it lives outside the repo)
+    let worktree_dir = config.repo_relative_dir(repo_root);
 
     // Helper to check if a path should be excluded
     let should_exclude = |path: &Path| -> bool {
@@ -605,12 +837,16 @@ pub fn sync_working_directory_to_worktree(
             return true;
         }
         // Exclude the worktree directory itself
-        let worktree_dir_str = worktree_dir.to_string_lossy();
-        if path_str.starts_with(&*worktree_dir_str)
-            || path_str.starts_with(&format!("{}/", worktree_dir_str))
+        if worktree_dir
+            .as_ref()
+            .is_some_and(|dir| path.starts_with(dir))
         {
             return true;
         }
+        // Archived scratchpads belong to the main loop only
+        if path_str.starts_with(".ralph/agent/archive/") {
+            return true;
+        }
         false
     };
 
@@ -636,8 +872,13 @@ pub fn sync_working_directory_to_worktree(
         }
     }
 
-    // Get unstaged modified files
-    let modified = get_unstaged_modified_files(repo_root)?;
+    // Unstaged changes are relative to HEAD, so they don't apply to a
+    // worktree branched from a different base
+    let modified = if config.base_branch.is_some() {
+        Vec::new()
+    } else {
+        get_unstaged_modified_files(repo_root)?
+    };
     for file in modified {
         if should_exclude(&file) {
             stats.skipped += 1;
@@ -729,6 +970,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_worktree_config_repo_relative_dir() {
+        let repo = Path::new("/repo");
+        let relative = |dir: &str| WorktreeConfig::with_dir(dir).repo_relative_dir(repo);
+
+        assert_eq!(relative(".worktrees"), Some(PathBuf::from(".worktrees")));
+        assert_eq!(relative("./build/wt/"), Some(PathBuf::from("build/wt")));
+        assert_eq!(relative("/repo/build/wt"), Some(PathBuf::from("build/wt")));
+        assert_eq!(relative("a/../wt"), Some(PathBuf::from("wt")));
+        assert_eq!(relative("../worktrees"), None);
+        assert_eq!(relative("/repo/../worktrees"), None);
+        assert_eq!(relative("/elsewhere/worktrees"), None);
+        assert_eq!(relative("."), None);
+    }
+
+    #[test]
+    fn test_worktree_dir_outside_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        init_git_repo(&repo);
+
+        let config = WorktreeConfig::with_dir(temp_dir.path().join("worktrees"));
+        assert!(!ensure_worktree_dir_ignored(&repo, &config).unwrap());
+        assert!(!repo.join(".gitignore").exists());
+
+        let worktree = create_worktree(&repo, "outside", &config).unwrap();
+        assert_eq!(worktree.path, temp_dir.path().join("worktrees/outside"));
+        assert!(worktree.path.join("README.md").exists());
+        assert!(worktree_exists(&repo, "outside", &config));
+        assert!(!worktree_exists(
+            &repo,
+            "outside",
+            &WorktreeConfig::default()
+        ));
+
+        remove_worktree(&repo, &worktree.path).unwrap();
+        assert!(!worktree_exists(&repo, "outside", &config));
+        assert!(!repo.join(".gitignore").exists());
+
+        // An in-repo custom dir is ignored by its repo-relative path
+        let config = WorktreeConfig::with_dir(repo.join("build/worktrees"));
+        assert!(ensure_worktree_dir_ignored(&repo, &config).unwrap());
+        let gitignore = fs::read_to_string(repo.join(".gitignore")).unwrap();
+        assert_eq!(gitignore, "build/worktrees/\n");
+    }
+
     #[test]
     fn test_create_and_remove_worktree() {
         let temp_dir = TempDir::new().unwrap();
@@ -751,6 +1039,89 @@ mod tests {
         // Remove worktree
         remove_worktree(temp_dir.path(), &worktree.path).unwrap();
         assert!(!worktree.path.exists());
+        assert_eq!(
+            list_ralph_branches(temp_dir.path()).unwrap(),
+            vec!["ralph/test-loop-123"],
+            "the branch outlives its worktree"
+        );
+    }
+
+    #[test]
+    fn test_delete_branch_requires_force_for_unmerged_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        init_git_repo(repo);
+        let config = WorktreeConfig::default();
+
+        let merged = create_worktree(repo, "merged-loop", &config).unwrap();
+        let unmerged = create_worktree(repo, "unmerged-loop", &config).unwrap();
+        fs::write(unmerged.path.join("work.txt"), "work").unwrap();
+        for args in [&["add", "work.txt"][..], &["commit", "-m", "work"]] {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(&unmerged.path)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        remove_worktree(repo, &merged.path).unwrap();
+        remove_worktree(repo, &unmerged.path).unwrap();
+
+        assert!(delete_branch(repo, "ralph/merged-loop", false).unwrap());
+        assert!(matches!(
+            delete_branch(repo, "ralph/unmerged-loop", false),
+            Err(WorktreeError::UnmergedBranch(_))
+        ));
+        assert_eq!(
+            list_ralph_branches(repo).unwrap(),
+            vec!["ralph/unmerged-loop"]
+        );
+        assert!(delete_branch(repo, "ralph/unmerged-loop", true).unwrap());
+        assert!(!delete_branch(repo, "ralph/unmerged-loop", true).unwrap());
+        assert!(list_ralph_branches(repo).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_create_worktree_from_base_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        init_git_repo(repo);
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["checkout", "-b", "develop"]);
+        fs::write(repo.join("develop.txt"), "develop only").unwrap();
+        git(&["add", "develop.txt"]);
+        git(&["commit", "-m", "Develop commit"]);
+        git(&["checkout", "main"]);
+
+        let config = WorktreeConfig::default().with_base_branch("develop");
+        let worktree = create_worktree(repo, "from-develop", &config).unwrap();
+        assert!(worktree.path.join("develop.txt").exists());
+        let develop = Command::new("git")
+            .args(["rev-parse", "develop"])
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert_eq!(
+            worktree.head.as_deref(),
+            Some(String::from_utf8_lossy(&develop.stdout).trim())
+        );
+
+        // The default still branches from the current HEAD
+        let worktree = create_worktree(repo, "from-main", &WorktreeConfig::default()).unwrap();
+        assert!(!worktree.path.join("develop.txt").exists());
+
+        let config = WorktreeConfig::default().with_base_branch("release");
+        let err = create_worktree(repo, "from-release", &config).unwrap_err();
+        assert!(matches!(err, WorktreeError::BaseBranchNotFound(ref base) if base == "release"));
+        assert_eq!(err.to_string(), "Base branch not found: release");
+        assert!(!repo.join(".worktrees/from-release").exists());
     }
 
     #[test]
@@ -877,6 +1248,46 @@ mod tests {
         assert!(worktree_exists(temp_dir.path(), loop_id, &config));
     }
 
+    #[test]
+    fn test_rename_worktree_moves_directory_and_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        init_git_repo(temp_dir.path());
+        let config = WorktreeConfig::default();
+        let _wt = create_worktree(temp_dir.path(), "able-raven", &config).unwrap();
+
+        let renamed =
+            rename_worktree(temp_dir.path(), "able-raven", "auth-rework", &config).unwrap();
+
+        assert_eq!(renamed.branch, "ralph/auth-rework");
+        assert!(renamed.path.ends_with(".worktrees/auth-rework"));
+        assert!(!worktree_exists(temp_dir.path(), "able-raven", &config));
+        let worktrees = list_ralph_worktrees(temp_dir.path()).unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].branch, "ralph/auth-rework");
+    }
+
+    #[test]
+    fn test_rename_worktree_refuses_collisions() {
+        let temp_dir = TempDir::new().unwrap();
+        init_git_repo(temp_dir.path());
+        let config = WorktreeConfig::default();
+        let _a = create_worktree(temp_dir.path(), "able-raven", &config).unwrap();
+        let _b = create_worktree(temp_dir.path(), "brave-otter", &config).unwrap();
+
+        let result = rename_worktree(temp_dir.path(), "able-raven", "brave-otter", &config);
+        assert!(matches!(result, Err(WorktreeError::AlreadyExists(_))));
+
+        Command::new("git")
+            .args(["branch", "ralph/calm-heron"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let result = rename_worktree(temp_dir.path(), "able-raven", "calm-heron", &config);
+        assert!(matches!(result, Err(WorktreeError::BranchExists(_))));
+
+        assert!(worktree_exists(temp_dir.path(), "able-raven", &config));
+    }
+
     #[test]
     fn test_not_a_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -1171,4 +1582,19 @@ branch refs/heads/ralph/loop-1
         assert_eq!(stats.modified_copied, 1);
         assert_eq!(stats.errors, 0);
     }
+
+    #[test]
+    fn test_sync_skips_scratchpad_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        init_git_repo(temp_dir.path());
+
+        let archive_dir = temp_dir.path().join(".ralph/agent/archive");
+        fs::create_dir_all(&archive_dir).unwrap();
+        fs::write(archive_dir.join("scratchpad-20250101-120000.md"), "old").unwrap();
+
+        let config = WorktreeConfig::default();
+        let worktree = create_worktree(temp_dir.path(), "sync-archive", &config).unwrap();
+
+        assert!(!worktree.path.join(".ralph/agent/archive").exists());
+    }
 }
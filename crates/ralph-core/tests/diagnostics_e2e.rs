@@ -18,7 +18,7 @@ fn test_diagnostics_creates_log_files() {
     collector.log_orchestration(
         1,
         "test-hat",
-        ralph_core::diagnostics::OrchestrationEvent::IterationStarted,
+        ralph_core::diagnostics::OrchestrationEvent::IterationStarted { queue_depth: 0 },
     );
 
     collector.log_performance(
@@ -63,7 +63,7 @@ fn test_diagnostics_files_contain_valid_jsonl() {
         collector.log_orchestration(
             i,
             "test-hat",
-            ralph_core::diagnostics::OrchestrationEvent::IterationStarted,
+            ralph_core::diagnostics::OrchestrationEvent::IterationStarted { queue_depth: 0 },
         );
 
         collector.log_performance(
@@ -121,7 +121,7 @@ fn test_diagnostics_disabled_when_env_not_set() {
     collector.log_orchestration(
         1,
         "test-hat",
-        ralph_core::diagnostics::OrchestrationEvent::IterationStarted,
+        ralph_core::diagnostics::OrchestrationEvent::IterationStarted { queue_depth: 0 },
     );
 
     // No files should be created
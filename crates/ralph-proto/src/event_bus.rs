@@ -64,6 +64,16 @@ impl EventBus {
         self.observers.clear();
     }
 
+    /// Sends an event to observers without routing it to any hat.
+    ///
+    /// For informational events (e.g. `iteration.retried`) that should be
+    /// recorded but must not trigger another iteration.
+    pub fn notify_observers(&self, event: &Event) {
+        for observer in &self.observers {
+            observer(event);
+        }
+    }
+
     /// Registers a hat with the event bus.
     pub fn register(&mut self, hat: Hat) {
         let id = hat.id.clone();
@@ -368,6 +378,24 @@ mod tests {
         assert_eq!(*count.lock().unwrap(), 1); // Still 1, observers cleared
     }
 
+    #[test]
+    fn test_notify_observers_does_not_route() {
+        use std::sync::{Arc, Mutex};
+
+        let mut bus = EventBus::new();
+        let count = Arc::new(Mutex::new(0));
+        let count_clone = Arc::clone(&count);
+        bus.add_observer(move |_| {
+            *count_clone.lock().unwrap() += 1;
+        });
+        bus.register(Hat::new("impl", "Implementer").subscribe("*"));
+
+        bus.notify_observers(&Event::new("iteration.retried", "attempt 1"));
+
+        assert_eq!(*count.lock().unwrap(), 1);
+        assert!(!bus.has_pending());
+    }
+
     #[test]
     fn test_peek_pending_does_not_consume() {
         let mut bus = EventBus::new();
@@ -23,7 +23,7 @@ pub use error::{Error, Result};
 pub use event::Event;
 pub use event_bus::EventBus;
 pub use hat::{Hat, HatId};
-pub use robot::{CheckinContext, RobotService};
+pub use robot::{CheckinContext, LifecycleNotification, RobotService};
 pub use topic::Topic;
 pub use ux_event::{
     FrameCapture, TerminalColorMode, TerminalResize, TerminalWrite, TuiFrame, UxEvent,
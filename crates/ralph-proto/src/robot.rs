@@ -27,6 +27,37 @@ pub struct CheckinContext {
     pub cumulative_cost: f64,
 }
 
+/// A loop lifecycle moment worth telling the human about.
+///
+/// Sent through [`RobotService::send_notification`] so the human learns about
+/// starts and endings without watching the terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LifecycleNotification {
+    /// The loop started working on a prompt.
+    Started {
+        /// Backend running the iterations (e.g., "claude").
+        backend: String,
+        /// First line of the prompt, shortened.
+        prompt_summary: String,
+    },
+    /// The loop terminated.
+    Terminated {
+        /// Termination reason (e.g., "completed", "max_iterations").
+        reason: String,
+        /// Whether the loop finished successfully.
+        success: bool,
+        /// Iterations run.
+        iterations: u32,
+        /// Wall-clock time since the loop started.
+        elapsed: Duration,
+    },
+    /// Preflight checks failed, so the loop never started.
+    PreflightFailed {
+        /// Summary of what failed.
+        detail: String,
+    },
+}
+
 /// A communication service for human-in-the-loop interaction.
 ///
 /// Implementors handle platform-specific concerns: sending messages,
@@ -40,9 +71,11 @@ pub trait RobotService: Send + Sync {
     /// no recipient is configured (question is logged but not sent).
     fn send_question(&self, payload: &str) -> anyhow::Result<i32>;
 
-    /// Poll the events file for a `human.response` event.
+    /// Wait for a `human.response` event in the events file.
     ///
-    /// Blocks until a response arrives or the configured timeout expires.
+    /// Blocks the calling thread until a response arrives or the configured
+    /// timeout expires; the event loop is synchronous, so async
+    /// implementations bridge to their runtime here.
     /// Returns `Ok(Some(response))` on response, `Ok(None)` on timeout.
     fn wait_for_response(&self, events_path: &Path) -> anyhow::Result<Option<String>>;
 
@@ -57,6 +90,12 @@ pub trait RobotService: Send + Sync {
         context: Option<&CheckinContext>,
     ) -> anyhow::Result<i32>;
 
+    /// Send a loop lifecycle notification.
+    ///
+    /// Returns `Ok(0)` if the notification was skipped (no recipient, or
+    /// rate-limited), or the message ID on success.
+    fn send_notification(&self, notification: &LifecycleNotification) -> anyhow::Result<i32>;
+
     /// Get the configured response timeout in seconds.
     fn timeout_secs(&self) -> u64;
 
@@ -0,0 +1,148 @@
+//! Minimal client for the Slack Web API.
+//!
+//! Only the three methods the RObot needs: `auth.test` to check a token,
+//! `chat.postMessage` to send, and `reactions.add` to acknowledge.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::error::{SlackError, SlackResult};
+
+/// Default Web API base URL.
+pub const SLACK_API_URL: &str = "https://slack.com/api/";
+
+/// Per-request timeout for Web API calls.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Identity of the bot a token belongs to, as reported by `auth.test`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthInfo {
+    /// Workspace (team) name.
+    pub team: String,
+
+    /// Bot user name.
+    pub user: String,
+
+    /// Bot user ID, used to ignore the bot's own messages.
+    pub user_id: String,
+}
+
+/// Sends requests to the Slack Web API with a bot token.
+#[derive(Clone)]
+pub struct SlackApi {
+    client: reqwest::Client,
+    token: String,
+    api_url: reqwest::Url,
+}
+
+impl SlackApi {
+    /// Create a client authenticating with `token` (`xoxb-...`).
+    pub fn new(token: &str) -> Self {
+        let builder = reqwest::Client::builder().timeout(REQUEST_TIMEOUT);
+        // Tests talk to a local mock server; keep proxies out of the way
+        let builder = if cfg!(test) {
+            builder.no_proxy()
+        } else {
+            builder
+        };
+        Self {
+            client: builder.build().expect("Client creation failed"),
+            token: token.to_string(),
+            api_url: SLACK_API_URL.parse().expect("valid Slack API URL"),
+        }
+    }
+
+    /// Sends all requests to `api_url` instead of slack.com. Used for tests.
+    pub fn with_api_url(mut self, api_url: reqwest::Url) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Checks the token and returns who it belongs to.
+    pub async fn auth_test(&self) -> SlackResult<AuthInfo> {
+        let response = self.call("auth.test", json!({})).await?;
+        let field = |name: &str| {
+            response
+                .get(name)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        Ok(AuthInfo {
+            team: field("team"),
+            user: field("user"),
+            user_id: field("user_id"),
+        })
+    }
+
+    /// Posts `text` (mrkdwn) to `channel`, in the thread of `thread_ts` if
+    /// given. Returns the new message's timestamp.
+    pub async fn post_message(
+        &self,
+        channel: &str,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> SlackResult<String> {
+        let mut body = json!({
+            "channel": channel,
+            "text": text,
+            "mrkdwn": true,
+        });
+        if let Some(thread_ts) = thread_ts {
+            body["thread_ts"] = thread_ts.into();
+        }
+        let response = self.call("chat.postMessage", body).await?;
+        response
+            .get("ts")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| SlackError::Api("chat.postMessage returned no ts".to_string()))
+    }
+
+    /// Adds the `name` emoji reaction (without colons) to a message.
+    pub async fn add_reaction(&self, channel: &str, ts: &str, name: &str) -> SlackResult<()> {
+        self.call(
+            "reactions.add",
+            json!({
+                "channel": channel,
+                "timestamp": ts,
+                "name": name,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Calls a Web API method and returns its response.
+    ///
+    /// Transport failures become [`SlackError::Request`]; a response with
+    /// `"ok": false` becomes [`SlackError::Api`] carrying Slack's error code.
+    async fn call(&self, method: &str, body: Value) -> SlackResult<Value> {
+        let url = self
+            .api_url
+            .join(method)
+            .map_err(|e| SlackError::Request(e.to_string()))?;
+        let response: Value = self
+            .client
+            .post(url)
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| SlackError::Request(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| SlackError::Request(e.to_string()))?;
+
+        if response.get("ok").and_then(Value::as_bool) == Some(true) {
+            Ok(response)
+        } else {
+            let error = response
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown_error");
+            Err(SlackError::Api(format!("{method}: {error}")))
+        }
+    }
+}
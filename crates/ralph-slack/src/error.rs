@@ -0,0 +1,55 @@
+use thiserror::Error;
+
+/// Result type alias for Slack operations.
+pub type SlackResult<T> = std::result::Result<T, SlackError>;
+
+/// Errors that can occur during Slack bot operations.
+#[derive(Debug, Error)]
+pub enum SlackError {
+    /// Bot token is missing from config, environment and keychain.
+    #[error(
+        "slack bot token not found: set RALPH_SLACK_BOT_TOKEN or configure RObot.slack.bot_token"
+    )]
+    MissingBotToken,
+
+    /// Signing secret is missing from config, environment and keychain.
+    #[error(
+        "slack signing secret not found: set RALPH_SLACK_SIGNING_SECRET or configure RObot.slack.signing_secret"
+    )]
+    MissingSigningSecret,
+
+    /// Failed to start the Events API listener.
+    #[error("failed to start slack listener: {0}")]
+    Startup(String),
+
+    /// Slack rejected the request (e.g., `channel_not_found`, `invalid_auth`).
+    ///
+    /// Holds the Web API's error code. Not retried, since resending the
+    /// same request fails the same way.
+    #[error("slack API error: {0}")]
+    Api(String),
+
+    /// A single Web API request failed in transport.
+    #[error("slack request failed: {0}")]
+    Request(String),
+
+    /// Failed to send a message after retries.
+    #[error("failed to send slack message after {attempts} attempts: {reason}")]
+    Send { attempts: u32, reason: String },
+
+    /// Failed to read or write state file.
+    #[error("state persistence error: {0}")]
+    State(#[from] std::io::Error),
+
+    /// Failed to parse state JSON.
+    #[error("state parse error: {0}")]
+    StateParse(#[from] serde_json::Error),
+
+    /// A message came from a channel other than the configured one.
+    #[error("channel {0} is not the configured RObot channel")]
+    UnauthorizedChannel(String),
+
+    /// Failed to write event to JSONL.
+    #[error("event write error: {0}")]
+    EventWrite(String),
+}
@@ -0,0 +1,195 @@
+//! Message formatting in Slack's mrkdwn.
+
+use std::time::Duration;
+
+use ralph_proto::{CheckinContext, LifecycleNotification};
+
+/// Escape the characters Slack treats as control characters in mrkdwn.
+///
+/// Slack uses the same three HTML entities as Telegram, so text escaped for
+/// one is escaped for the other.
+pub fn escape_mrkdwn(text: &str) -> String {
+    ralph_core::robot::escape_html(text)
+}
+
+/// Convert the Telegram HTML produced by the bot commands to mrkdwn.
+///
+/// Handles the tags the commands emit (`<b>`, `<i>`, `<code>`, `<pre>`,
+/// `<a href>`); other tags are dropped. Entities are kept, since Slack
+/// expects `&amp;`, `&lt;` and `&gt;` too.
+pub fn html_to_mrkdwn(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        match name {
+            "b" | "strong" => out.push('*'),
+            "i" | "em" => out.push('_'),
+            "s" | "del" => out.push('~'),
+            "code" => out.push('`'),
+            "pre" => out.push_str("```"),
+            "a" if tag.starts_with('/') => out.push('>'),
+            "a" => {
+                let href = tag
+                    .split_once("href=\"")
+                    .and_then(|(_, url)| url.split_once('"'))
+                    .map_or("", |(url, _)| url);
+                out.push('<');
+                out.push_str(href);
+                out.push('|');
+            }
+            _ => {}
+        }
+    }
+    out.push_str(rest);
+    out.replace("&quot;", "\"").replace("&#39;", "'")
+}
+
+/// Formats a periodic check-in message.
+pub fn format_checkin(
+    iteration: u32,
+    elapsed: Duration,
+    context: Option<&CheckinContext>,
+) -> String {
+    let mut lines = vec![format!(
+        "Still working — iteration *{}*, `{}` elapsed.",
+        iteration,
+        format_elapsed(elapsed)
+    )];
+
+    if let Some(ctx) = context {
+        if let Some(hat) = &ctx.current_hat {
+            lines.push(format!("Hat: `{}`", escape_mrkdwn(hat)));
+        }
+        if ctx.open_tasks > 0 || ctx.closed_tasks > 0 {
+            lines.push(format!(
+                "Tasks: *{}* open, {} closed",
+                ctx.open_tasks, ctx.closed_tasks
+            ));
+        }
+        if ctx.cumulative_cost > 0.0 {
+            lines.push(format!("Cost: `${:.4}`", ctx.cumulative_cost));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Formats a lifecycle notification.
+pub fn format_notification(loop_id: &str, notification: &LifecycleNotification) -> String {
+    let loop_id = escape_mrkdwn(loop_id);
+    match notification {
+        LifecycleNotification::Started {
+            backend,
+            prompt_summary,
+        } => format!(
+            ":rocket: Loop `{}` started on *{}*\n{}",
+            loop_id,
+            escape_mrkdwn(backend),
+            escape_mrkdwn(prompt_summary)
+        ),
+        LifecycleNotification::Terminated {
+            reason,
+            success,
+            iterations,
+            elapsed,
+        } => format!(
+            "{} Loop `{}` ended: *{}*\n{} iteration{} in `{}`",
+            if *success {
+                ":white_check_mark:"
+            } else {
+                ":octagonal_sign:"
+            },
+            loop_id,
+            escape_mrkdwn(reason),
+            iterations,
+            if *iterations == 1 { "" } else { "s" },
+            format_elapsed(*elapsed)
+        ),
+        LifecycleNotification::PreflightFailed { detail } => format!(
+            ":warning: Loop `{}` did not start — preflight failed\n{}",
+            loop_id,
+            escape_mrkdwn(detail)
+        ),
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_mrkdwn_converts_command_output() {
+        assert_eq!(
+            html_to_mrkdwn("<b>Status</b>: <code>running</code> &amp; <i>healthy</i>"),
+            "*Status*: `running` &amp; _healthy_"
+        );
+        assert_eq!(html_to_mrkdwn("<pre>a &lt; b</pre>"), "```a &lt; b```");
+        assert_eq!(
+            html_to_mrkdwn(r#"See <a href="https://example.com">docs</a>"#),
+            "See <https://example.com|docs>"
+        );
+        assert_eq!(html_to_mrkdwn("no tags"), "no tags");
+    }
+
+    #[test]
+    fn format_notification_covers_lifecycle() {
+        let started = format_notification(
+            "main",
+            &LifecycleNotification::Started {
+                backend: "claude".to_string(),
+                prompt_summary: "Fix <flaky> test".to_string(),
+            },
+        );
+        assert!(started.contains("`main` started on *claude*"));
+        assert!(started.contains("Fix &lt;flaky&gt; test"));
+
+        let ended = format_notification(
+            "main",
+            &LifecycleNotification::Terminated {
+                reason: "completed".to_string(),
+                success: true,
+                iterations: 1,
+                elapsed: Duration::from_secs(75),
+            },
+        );
+        assert!(ended.starts_with(":white_check_mark:"));
+        assert!(ended.contains("1 iteration in `1m 15s`"));
+    }
+
+    #[test]
+    fn format_checkin_includes_context() {
+        let context = CheckinContext {
+            current_hat: Some("builder".to_string()),
+            open_tasks: 2,
+            closed_tasks: 3,
+            cumulative_cost: 0.5,
+        };
+        let msg = format_checkin(4, Duration::from_secs(42), Some(&context));
+        assert!(msg.contains("iteration *4*, `42s` elapsed"));
+        assert!(msg.contains("Hat: `builder`"));
+        assert!(msg.contains("*2* open, 3 closed"));
+        assert!(msg.contains("Cost: `$0.5000`"));
+    }
+}
@@ -0,0 +1,200 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use ralph_core::robot::loop_events_path;
+
+use crate::error::{SlackError, SlackResult};
+use crate::state::{SlackState, StateManager};
+
+/// Processes incoming Slack messages and writes events to the correct loop's events file.
+///
+/// Routing matches the Telegram bot, with threads standing in for replies.
+pub struct MessageHandler {
+    state_manager: StateManager,
+    workspace_root: PathBuf,
+    channel_id: String,
+}
+
+impl MessageHandler {
+    /// Create a new message handler for `channel_id`, rooted at the given workspace.
+    pub fn new(
+        state_manager: StateManager,
+        workspace_root: impl Into<PathBuf>,
+        channel_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            state_manager,
+            workspace_root: workspace_root.into(),
+            channel_id: channel_id.into(),
+        }
+    }
+
+    /// Handle a message posted in `channel`.
+    ///
+    /// Rejects messages from any channel but the configured one. Determines
+    /// the target loop, classifies the message as response or guidance, and
+    /// appends the event to the loop's events file.
+    ///
+    /// Returns the event topic that was written (`"human.response"` or `"human.guidance"`).
+    pub fn handle_message(
+        &self,
+        state: &mut SlackState,
+        text: &str,
+        channel: &str,
+        thread_ts: Option<&str>,
+    ) -> SlackResult<String> {
+        if channel != self.channel_id {
+            tracing::warn!(channel, "rejected message from another channel");
+            return Err(SlackError::UnauthorizedChannel(channel.to_string()));
+        }
+
+        let target_loop = self.determine_target_loop(state, text, thread_ts);
+        let events_path = loop_events_path(&self.workspace_root, &target_loop);
+        let is_response = state.pending_questions.contains_key(&target_loop);
+
+        let topic = if is_response {
+            "human.response"
+        } else {
+            "human.guidance"
+        };
+
+        let mut event_json = serde_json::json!({
+            "topic": topic,
+            "payload": text,
+            "ts": Utc::now().to_rfc3339(),
+        });
+        // Lets the waiting loop tell which question a response answers
+        if let Some(thread_ts) = thread_ts {
+            event_json["thread_ts"] = thread_ts.into();
+        }
+        let event_line = serde_json::to_string(&event_json)?;
+
+        append_event(&events_path, &event_line)?;
+
+        if is_response {
+            self.state_manager
+                .remove_pending_question(state, &target_loop)?;
+        }
+
+        tracing::info!(
+            topic,
+            target_loop,
+            "wrote {} event for loop {}",
+            topic,
+            target_loop
+        );
+
+        Ok(topic.to_string())
+    }
+
+    /// Determine which loop a message is targeted at.
+    ///
+    /// Priority:
+    /// 1. Reply in the thread of a pending question → that loop
+    /// 2. `@loop-id` prefix → extracted loop ID
+    /// 3. Default → "main"
+    fn determine_target_loop(
+        &self,
+        state: &SlackState,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> String {
+        if let Some(thread_ts) = thread_ts
+            && let Some(loop_id) = self.state_manager.get_loop_for_thread(state, thread_ts)
+        {
+            return loop_id;
+        }
+
+        if let Some(loop_id) = text.strip_prefix('@')
+            && let Some(id) = loop_id.split_whitespace().next()
+            && !id.is_empty()
+        {
+            return id.to_string();
+        }
+
+        "main".to_string()
+    }
+}
+
+/// Append an event line to the given file, locked against concurrent writers.
+fn append_event(path: &Path, event_line: &str) -> SlackResult<()> {
+    ralph_core::EventHistory::new(path)
+        .append(event_line, false)
+        .map_err(|e| {
+            SlackError::EventWrite(format!("failed to write to {}: {}", path.display(), e))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (MessageHandler, TempDir, SlackState) {
+        let dir = TempDir::new().unwrap();
+        let state_manager = StateManager::new(dir.path().join(".ralph/slack-state.json"));
+        let handler = MessageHandler::new(state_manager, dir.path(), "C123");
+        (handler, dir, SlackState::default())
+    }
+
+    fn read_event(path: &Path) -> serde_json::Value {
+        let contents = std::fs::read_to_string(path).unwrap();
+        serde_json::from_str(contents.lines().last().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn writes_guidance_event_to_main() {
+        let (handler, dir, mut state) = setup();
+        let topic = handler
+            .handle_message(&mut state, "don't forget logging", "C123", None)
+            .unwrap();
+
+        assert_eq!(topic, "human.guidance");
+        let event = read_event(&dir.path().join(".ralph/events.jsonl"));
+        assert_eq!(event["topic"], "human.guidance");
+        assert_eq!(event["payload"], "don't forget logging");
+    }
+
+    #[test]
+    fn thread_reply_answers_that_loops_question() {
+        let (handler, dir, mut state) = setup();
+        let mgr = StateManager::new(dir.path().join(".ralph/slack-state.json"));
+        mgr.add_pending_question(&mut state, "feature-a", &["171.000100".to_string()])
+            .unwrap();
+        mgr.add_pending_question(&mut state, "main", &["172.000100".to_string()])
+            .unwrap();
+
+        let topic = handler
+            .handle_message(&mut state, "Use Postgres", "C123", Some("171.000100"))
+            .unwrap();
+
+        assert_eq!(topic, "human.response");
+        let event = read_event(&dir.path().join(".worktrees/feature-a/.ralph/events.jsonl"));
+        assert_eq!(event["payload"], "Use Postgres");
+        assert_eq!(event["thread_ts"], "171.000100");
+        assert!(!state.pending_questions.contains_key("feature-a"));
+        assert!(state.pending_questions.contains_key("main"));
+    }
+
+    #[test]
+    fn loop_prefix_routes_guidance() {
+        let (handler, dir, mut state) = setup();
+        handler
+            .handle_message(&mut state, "@feature-b add tests", "C123", None)
+            .unwrap();
+
+        let event = read_event(&dir.path().join(".worktrees/feature-b/.ralph/events.jsonl"));
+        assert_eq!(event["topic"], "human.guidance");
+    }
+
+    #[test]
+    fn rejects_other_channels() {
+        let (handler, dir, mut state) = setup();
+        let err = handler
+            .handle_message(&mut state, "hello", "C999", None)
+            .unwrap_err();
+
+        assert!(matches!(err, SlackError::UnauthorizedChannel(ref c) if c == "C999"));
+        assert!(!dir.path().join(".ralph/events.jsonl").exists());
+    }
+}
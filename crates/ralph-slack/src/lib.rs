@@ -0,0 +1,35 @@
+//! # ralph-slack
+//!
+//! Slack integration for human-in-the-loop orchestration in Ralph.
+//!
+//! A second RObot transport next to Telegram, for teams that live in Slack:
+//!
+//! - **AI → Human**: questions, check-ins and lifecycle notifications are
+//!   posted to one configured channel through the Web API
+//! - **Human → AI**: replies in a question's thread answer it; other channel
+//!   messages become guidance. Slack delivers them to an Events API
+//!   listener, which also serves the bot commands as slash commands
+//!
+//! ## Key Components
+//!
+//! - [`SlackService`] — Lifecycle management for the bot within the event loop
+//! - [`SlackApi`] — Web API client (`auth.test`, `chat.postMessage`, `reactions.add`)
+//! - [`MessageHandler`] — Routes incoming messages and writes events to JSONL
+//! - [`StateManager`] — Persists pending questions for thread routing
+//! - [`verify_signature`] — Checks Slack's request signatures
+
+mod api;
+mod error;
+mod format;
+mod handler;
+mod listener;
+mod service;
+mod state;
+
+pub use api::{AuthInfo, SLACK_API_URL, SlackApi};
+pub use error::{SlackError, SlackResult};
+pub use format::{escape_mrkdwn, html_to_mrkdwn};
+pub use handler::MessageHandler;
+pub use listener::{MAX_REQUEST_AGE_SECS, SlackRequest, parse_request, verify_signature};
+pub use service::{SLACK_MESSAGE_LIMIT, SlackService};
+pub use state::{PendingQuestion, SlackState, StateManager};
@@ -0,0 +1,545 @@
+//! HTTP endpoint for the Slack Events API and slash commands.
+//!
+//! Slack delivers channel messages and slash commands as signed POST
+//! requests to the app's request URL. The listener verifies each request's
+//! signature, routes messages through the [`MessageHandler`], and answers
+//! slash commands with the same commands the Telegram bot offers.
+
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use hyper::{Method, Request, Response, StatusCode};
+use ring::hmac;
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+use crate::api::SlackApi;
+use crate::error::SlackError;
+use crate::format::{escape_mrkdwn, html_to_mrkdwn};
+use crate::handler::MessageHandler;
+use crate::state::StateManager;
+
+/// Requests signed longer ago than this are rejected as possible replays.
+pub const MAX_REQUEST_AGE_SECS: i64 = 300;
+
+/// Largest request body accepted.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// How often the accept loop checks the shutdown flag.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The single slash command that takes a bot command as its argument,
+/// e.g. `/ralph status`.
+const RALPH_COMMAND: &str = "/ralph";
+
+/// A request from Slack, parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlackRequest {
+    /// Slack checking the request URL while the app is set up.
+    UrlVerification { challenge: String },
+
+    /// A person posted a message in a channel the app is in.
+    Message {
+        channel: String,
+        text: String,
+        ts: String,
+        thread_ts: Option<String>,
+    },
+
+    /// A slash command.
+    Command {
+        command: String,
+        text: String,
+        channel: String,
+    },
+
+    /// Anything else: bot messages, edits, other event types.
+    Ignored,
+}
+
+/// Checks Slack's `X-Slack-Signature` for a request.
+///
+/// The signature is `v0=` followed by the hex HMAC-SHA256 of
+/// `v0:<timestamp>:<body>`, keyed with the app's signing secret. Requests
+/// whose timestamp is more than [`MAX_REQUEST_AGE_SECS`] away from `now`
+/// (Unix seconds) are rejected.
+pub fn verify_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now: i64,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > MAX_REQUEST_AGE_SECS {
+        return false;
+    }
+    let Some(tag) = signature.strip_prefix("v0=").and_then(decode_hex) else {
+        return false;
+    };
+
+    let mut base = format!("v0:{timestamp}:").into_bytes();
+    base.extend_from_slice(body);
+    let key = hmac::Key::new(hmac::HMAC_SHA256, signing_secret.as_bytes());
+    hmac::verify(&key, &base, &tag).is_ok()
+}
+
+/// Parses a request body by its content type.
+///
+/// Events arrive as JSON, slash commands as a form.
+pub fn parse_request(content_type: &str, body: &[u8]) -> SlackRequest {
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        return parse_command(body);
+    }
+
+    let Ok(payload) = serde_json::from_slice::<Value>(body) else {
+        return SlackRequest::Ignored;
+    };
+    let str_field =
+        |value: &Value, name: &str| value.get(name).and_then(Value::as_str).map(String::from);
+
+    match payload.get("type").and_then(Value::as_str) {
+        Some("url_verification") => SlackRequest::UrlVerification {
+            challenge: str_field(&payload, "challenge").unwrap_or_default(),
+        },
+        Some("event_callback") => {
+            let Some(event) = payload.get("event") else {
+                return SlackRequest::Ignored;
+            };
+            // Edits, joins and the bot's own posts come with a subtype or bot_id
+            if event.get("type").and_then(Value::as_str) != Some("message")
+                || event.get("subtype").is_some()
+                || event.get("bot_id").is_some()
+            {
+                return SlackRequest::Ignored;
+            }
+            match (
+                str_field(event, "channel"),
+                str_field(event, "text"),
+                str_field(event, "ts"),
+            ) {
+                (Some(channel), Some(text), Some(ts)) if !text.trim().is_empty() => {
+                    SlackRequest::Message {
+                        channel,
+                        text,
+                        ts,
+                        thread_ts: str_field(event, "thread_ts"),
+                    }
+                }
+                _ => SlackRequest::Ignored,
+            }
+        }
+        _ => SlackRequest::Ignored,
+    }
+}
+
+fn parse_command(body: &[u8]) -> SlackRequest {
+    let Ok(fields) = serde_urlencoded::from_bytes::<Vec<(String, String)>>(body) else {
+        return SlackRequest::Ignored;
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+    match (field("command"), field("channel_id")) {
+        (Some(command), Some(channel)) => SlackRequest::Command {
+            command,
+            text: field("text").unwrap_or_default(),
+            channel,
+        },
+        _ => SlackRequest::Ignored,
+    }
+}
+
+/// Everything the listener needs to answer requests.
+pub(crate) struct ListenerContext {
+    pub signing_secret: String,
+    pub workspace_root: PathBuf,
+    pub channel_id: String,
+    pub state_manager: StateManager,
+    pub handler: MessageHandler,
+    pub api: SlackApi,
+}
+
+/// Accepts connections on `listener` until `shutdown` is set.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    ctx: Arc<ListenerContext>,
+    shutdown: Arc<AtomicBool>,
+) {
+    let mut poll = tokio::time::interval(SHUTDOWN_POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let ctx = ctx.clone();
+                    tokio::spawn(async move {
+                        let service = hyper::service::service_fn(move |request| {
+                            let ctx = ctx.clone();
+                            async move { Ok::<_, Infallible>(handle_request(&ctx, request).await) }
+                        });
+                        if let Err(e) = hyper::server::conn::http1::Builder::new()
+                            .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                            .await
+                        {
+                            debug!(error = %e, "Slack connection closed with an error");
+                        }
+                    });
+                }
+                Err(e) => warn!(error = %e, "Failed to accept Slack connection"),
+            },
+            _ = poll.tick() => {
+                if shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+            }
+        }
+    }
+    info!("Slack listener stopped");
+}
+
+async fn handle_request(
+    ctx: &ListenerContext,
+    request: Request<hyper::body::Incoming>,
+) -> Response<Full<Bytes>> {
+    if request.method() != Method::POST {
+        return empty(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let timestamp = header("x-slack-request-timestamp");
+    let signature = header("x-slack-signature");
+    let content_type = header("content-type");
+    let is_retry = request.headers().contains_key("x-slack-retry-num");
+
+    let body = match Limited::new(request.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => return empty(StatusCode::PAYLOAD_TOO_LARGE),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    if !verify_signature(&ctx.signing_secret, &timestamp, &body, &signature, now) {
+        warn!("Rejected Slack request with a bad or stale signature");
+        return empty(StatusCode::UNAUTHORIZED);
+    }
+
+    // Slack redelivers events it thinks timed out; the first delivery was handled
+    if is_retry {
+        return empty(StatusCode::OK);
+    }
+
+    match parse_request(&content_type, &body) {
+        SlackRequest::UrlVerification { challenge } => {
+            json_response(&json!({ "challenge": challenge }))
+        }
+        SlackRequest::Message {
+            channel,
+            text,
+            ts,
+            thread_ts,
+        } => {
+            handle_message(ctx, channel, &text, ts, thread_ts);
+            empty(StatusCode::OK)
+        }
+        SlackRequest::Command {
+            command,
+            text,
+            channel,
+        } => json_response(&command_response(ctx, &command, &text, &channel)),
+        SlackRequest::Ignored => empty(StatusCode::OK),
+    }
+}
+
+/// Routes a channel message to its loop and acknowledges it in Slack.
+///
+/// The acknowledgement is sent in the background: Slack expects the HTTP
+/// response within three seconds.
+fn handle_message(
+    ctx: &ListenerContext,
+    channel: String,
+    text: &str,
+    ts: String,
+    thread_ts: Option<String>,
+) {
+    info!(channel = %channel, text = %text, "Received Slack message");
+
+    let mut state = match ctx.state_manager.load_or_default() {
+        Ok(state) => state,
+        Err(e) => {
+            warn!(error = %e, "Failed to load Slack state");
+            return;
+        }
+    };
+
+    let topic = match ctx
+        .handler
+        .handle_message(&mut state, text, &channel, thread_ts.as_deref())
+    {
+        Ok(topic) => topic,
+        // Already logged; strangers get no acknowledgement
+        Err(SlackError::UnauthorizedChannel(_)) => return,
+        Err(e) => {
+            warn!(error = %e, text = %text, "Failed to handle incoming Slack message");
+            return;
+        }
+    };
+
+    let api = ctx.api.clone();
+    tokio::spawn(async move {
+        let reaction = if topic == "human.response" {
+            "thumbsup"
+        } else {
+            "eyes"
+        };
+        if let Err(e) = api.add_reaction(&channel, &ts, reaction).await {
+            warn!(error = %e, "Failed to react to message");
+        }
+
+        if topic == "human.guidance" {
+            let thread = thread_ts.as_deref().unwrap_or(&ts);
+            let _ = api
+                .post_message(
+                    &channel,
+                    ":memo: *Guidance received* — will apply next iteration.",
+                    Some(thread),
+                )
+                .await;
+        }
+    });
+}
+
+/// Runs a slash command and builds Slack's response payload.
+///
+/// `/ralph <command> [args]` runs any bot command; commands registered
+/// on their own (e.g. `/status`) work as well.
+fn command_response(ctx: &ListenerContext, command: &str, text: &str, channel: &str) -> Value {
+    if channel != ctx.channel_id {
+        return json!({
+            "response_type": "ephemeral",
+            "text": "This channel is not connected to Ralph.",
+        });
+    }
+
+    let text = text.trim();
+    let command_line = if command == RALPH_COMMAND {
+        let text = text.trim_start_matches('/');
+        if text.is_empty() {
+            "/help".to_string()
+        } else {
+            format!("/{text}")
+        }
+    } else {
+        format!("{command} {text}").trim_end().to_string()
+    };
+
+    let reply = ralph_core::robot::handle_command(&command_line, &ctx.workspace_root).map_or_else(
+        || {
+            format!(
+                "Unknown command `{}`. Try `{RALPH_COMMAND} help`.",
+                escape_mrkdwn(&command_line)
+            )
+        },
+        |html| html_to_mrkdwn(&html),
+    );
+
+    json!({
+        "response_type": "in_channel",
+        "text": reply,
+    })
+}
+
+fn empty(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+fn json_response(body: &Value) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write as _;
+    use tempfile::TempDir;
+
+    fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        let mut base = format!("v0:{timestamp}:").into_bytes();
+        base.extend_from_slice(body);
+        let tag = hmac::sign(&key, &base);
+        let hex = tag.as_ref().iter().fold(String::new(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        });
+        format!("v0={hex}")
+    }
+
+    #[test]
+    fn verify_signature_accepts_valid_and_rejects_tampered() {
+        let body = br#"{"type":"url_verification","challenge":"abc"}"#;
+        let signature = sign("s3cret", "1700000000", body);
+
+        assert!(verify_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_060
+        ));
+        // Wrong secret, altered body, stale timestamp, malformed header
+        assert!(!verify_signature(
+            "other",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_060
+        ));
+        assert!(!verify_signature(
+            "s3cret",
+            "1700000000",
+            b"{}",
+            &signature,
+            1_700_000_060
+        ));
+        assert!(!verify_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_400
+        ));
+        assert!(!verify_signature(
+            "s3cret",
+            "1700000000",
+            body,
+            "v0=zz",
+            1_700_000_060
+        ));
+        assert!(!verify_signature(
+            "s3cret",
+            "later",
+            body,
+            &signature,
+            1_700_000_060
+        ));
+    }
+
+    #[test]
+    fn parse_request_reads_events() {
+        assert_eq!(
+            parse_request(
+                "application/json",
+                br#"{"type":"url_verification","challenge":"abc"}"#
+            ),
+            SlackRequest::UrlVerification {
+                challenge: "abc".to_string()
+            }
+        );
+
+        let message = br#"{"type":"event_callback","event":{"type":"message","channel":"C123","user":"U1","text":"Use Postgres","ts":"171.000200","thread_ts":"171.000100"}}"#;
+        assert_eq!(
+            parse_request("application/json", message),
+            SlackRequest::Message {
+                channel: "C123".to_string(),
+                text: "Use Postgres".to_string(),
+                ts: "171.000200".to_string(),
+                thread_ts: Some("171.000100".to_string()),
+            }
+        );
+
+        let from_bot = br#"{"type":"event_callback","event":{"type":"message","channel":"C123","bot_id":"B1","text":"hi","ts":"1.0"}}"#;
+        assert_eq!(
+            parse_request("application/json", from_bot),
+            SlackRequest::Ignored
+        );
+        let edit = br#"{"type":"event_callback","event":{"type":"message","subtype":"message_changed","channel":"C123","ts":"1.0"}}"#;
+        assert_eq!(
+            parse_request("application/json", edit),
+            SlackRequest::Ignored
+        );
+    }
+
+    #[test]
+    fn parse_request_reads_slash_commands() {
+        let body = b"token=x&team_id=T1&channel_id=C123&command=%2Fralph&text=events+build.%2A+5";
+        assert_eq!(
+            parse_request("application/x-www-form-urlencoded", body),
+            SlackRequest::Command {
+                command: "/ralph".to_string(),
+                text: "events build.* 5".to_string(),
+                channel: "C123".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn command_response_runs_bot_commands() {
+        let dir = TempDir::new().unwrap();
+        let state_path = dir.path().join(".ralph/slack-state.json");
+        let ctx = ListenerContext {
+            signing_secret: "s3cret".to_string(),
+            workspace_root: dir.path().to_path_buf(),
+            channel_id: "C123".to_string(),
+            state_manager: StateManager::new(&state_path),
+            handler: MessageHandler::new(StateManager::new(&state_path), dir.path(), "C123"),
+            api: SlackApi::new("xoxb-test"),
+        };
+
+        let help = command_response(&ctx, "/ralph", "", "C123");
+        assert_eq!(help["response_type"], "in_channel");
+        let text = help["text"].as_str().unwrap();
+        assert!(text.contains("/status"));
+        assert!(!text.contains("<b>"));
+
+        let status = command_response(&ctx, "/status", "", "C123");
+        assert_eq!(status["response_type"], "in_channel");
+
+        let unknown = command_response(&ctx, "/ralph", "frobnicate", "C123");
+        assert!(
+            unknown["text"]
+                .as_str()
+                .unwrap()
+                .contains("Unknown command")
+        );
+
+        let elsewhere = command_response(&ctx, "/ralph", "status", "C999");
+        assert_eq!(elsewhere["response_type"], "ephemeral");
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use ralph_core::robot::QuestionThread;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SlackResult;
+
+/// Persistent state for the Slack bot, stored at `.ralph/slack-state.json`.
+///
+/// Unlike Telegram, the channel is configured up front, so only question
+/// routing and notification rate limiting need to survive restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlackState {
+    /// Pending questions keyed by loop ID, tracking which thread awaits a reply.
+    #[serde(default)]
+    pub pending_questions: HashMap<String, PendingQuestion>,
+
+    /// When recent lifecycle notifications were sent, for rate limiting
+    /// across loop restarts.
+    #[serde(default)]
+    pub recent_notifications: Vec<DateTime<Utc>>,
+}
+
+/// A question posted to the channel that is awaiting a response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingQuestion {
+    /// When the question was sent.
+    pub asked_at: DateTime<Utc>,
+
+    /// Timestamp of the question's first message, which is also the
+    /// `thread_ts` of replies in its thread.
+    pub ts: String,
+
+    /// Timestamps of the further messages of a question that was split to
+    /// fit Slack's length limit.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub later_ts: Vec<String>,
+}
+
+impl PendingQuestion {
+    /// Returns true if `ts` is one of this question's messages.
+    pub fn has_message(&self, ts: &str) -> bool {
+        self.ts == ts || self.later_ts.iter().any(|t| t == ts)
+    }
+}
+
+impl QuestionThread for PendingQuestion {
+    /// Replies are `human.response` events posted in one of the question's
+    /// threads.
+    fn is_reply(&self, event: &serde_json::Value) -> bool {
+        event
+            .get("thread_ts")
+            .and_then(|t| t.as_str())
+            .is_some_and(|ts| self.has_message(ts))
+    }
+}
+
+/// Manages persistence of Slack bot state to disk.
+pub struct StateManager {
+    path: PathBuf,
+}
+
+impl StateManager {
+    /// Create a new StateManager that reads/writes to the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Load state from disk. Returns `None` if the file doesn't exist.
+    pub fn load(&self) -> SlackResult<Option<SlackState>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        let state: SlackState = serde_json::from_str(&contents)?;
+        Ok(Some(state))
+    }
+
+    /// Save state to disk using atomic write (temp file + rename).
+    pub fn save(&self, state: &SlackState) -> SlackResult<()> {
+        let json = serde_json::to_string_pretty(state)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&tmp_path, &json)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Load existing state or create a fresh empty state.
+    pub fn load_or_default(&self) -> SlackResult<SlackState> {
+        Ok(self.load()?.unwrap_or_default())
+    }
+
+    /// Add a pending question for a given loop, sent as the messages with
+    /// timestamps `message_ts` (in send order).
+    pub fn add_pending_question(
+        &self,
+        state: &mut SlackState,
+        loop_id: &str,
+        message_ts: &[String],
+    ) -> SlackResult<()> {
+        let (first, later) = message_ts
+            .split_first()
+            .map_or((String::new(), &[][..]), |(first, later)| {
+                (first.clone(), later)
+            });
+        state.pending_questions.insert(
+            loop_id.to_string(),
+            PendingQuestion {
+                asked_at: Utc::now(),
+                ts: first,
+                later_ts: later.to_vec(),
+            },
+        );
+        self.save(state)
+    }
+
+    /// Remove a pending question for a given loop.
+    pub fn remove_pending_question(
+        &self,
+        state: &mut SlackState,
+        loop_id: &str,
+    ) -> SlackResult<()> {
+        state.pending_questions.remove(loop_id);
+        self.save(state)
+    }
+
+    /// Given a message's `thread_ts`, find which loop's question it answers.
+    pub fn get_loop_for_thread(&self, state: &SlackState, thread_ts: &str) -> Option<String> {
+        state
+            .pending_questions
+            .iter()
+            .find(|(_, q)| q.has_message(thread_ts))
+            .map(|(loop_id, _)| loop_id.clone())
+    }
+
+    /// Return the path to the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn pending_question_round_trip_and_thread_lookup() {
+        let dir = TempDir::new().unwrap();
+        let mgr = StateManager::new(dir.path().join("slack-state.json"));
+        assert!(mgr.load().unwrap().is_none());
+
+        let mut state = mgr.load_or_default().unwrap();
+        mgr.add_pending_question(
+            &mut state,
+            "feature-a",
+            &[
+                "1700000000.000100".to_string(),
+                "1700000000.000200".to_string(),
+            ],
+        )
+        .unwrap();
+
+        let loaded = mgr.load().unwrap().unwrap();
+        let question = &loaded.pending_questions["feature-a"];
+        assert_eq!(question.ts, "1700000000.000100");
+        assert_eq!(question.later_ts, vec!["1700000000.000200"]);
+        assert_eq!(
+            mgr.get_loop_for_thread(&loaded, "1700000000.000200")
+                .as_deref(),
+            Some("feature-a")
+        );
+        assert_eq!(mgr.get_loop_for_thread(&loaded, "1.0"), None);
+
+        let mut state = loaded;
+        mgr.remove_pending_question(&mut state, "feature-a")
+            .unwrap();
+        assert!(mgr.load().unwrap().unwrap().pending_questions.is_empty());
+    }
+}
@@ -1,9 +1,13 @@
 use std::path::Path;
 
 use async_trait::async_trait;
+use ralph_core::robot::escape_html;
 
 use crate::error::{TelegramError, TelegramResult};
 
+/// Maximum length of a single Telegram message, in characters.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+
 /// Trait abstracting Telegram bot operations for testability.
 ///
 /// Production code uses [`TelegramBot`]; tests can provide a mock implementation.
@@ -14,6 +18,17 @@ pub trait BotApi: Send + Sync {
     /// Returns the Telegram message ID of the sent message.
     async fn send_message(&self, chat_id: i64, text: &str) -> TelegramResult<i32>;
 
+    /// Send a text message with one inline keyboard button per choice.
+    ///
+    /// Pressing button `i` sends a callback query whose data is
+    /// [`crate::choices::callback_data`]`(i)`. Returns the message ID.
+    async fn send_message_with_choices(
+        &self,
+        chat_id: i64,
+        text: &str,
+        choices: &[String],
+    ) -> TelegramResult<i32>;
+
     /// Send a document (file) to the given chat with an optional caption.
     ///
     /// Returns the Telegram message ID of the sent message.
@@ -58,6 +73,12 @@ impl TelegramBot {
         }
     }
 
+    /// Points the bot at a different Bot API server (e.g., a self-hosted one).
+    pub fn with_api_url(mut self, api_url: reqwest::Url) -> Self {
+        self.bot = self.bot.set_api_url(api_url);
+        self
+    }
+
     /// Format an outgoing question message using Telegram HTML.
     ///
     /// Includes emoji, hat name, iteration number, and the question text.
@@ -85,15 +106,6 @@ impl TelegramBot {
     }
 }
 
-/// Escape special HTML characters for Telegram's HTML parse mode.
-///
-/// Telegram requires `<`, `>`, and `&` to be escaped in HTML-formatted messages.
-pub fn escape_html(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-}
-
 /// Convert Ralph-generated markdown to Telegram HTML.
 ///
 /// Handles the subset of markdown that Ralph produces:
@@ -281,10 +293,40 @@ impl BotApi for TelegramBot {
             .send_message(teloxide::types::ChatId(chat_id), text)
             .parse_mode(ParseMode::Html)
             .await
-            .map_err(|e| TelegramError::Send {
-                attempts: 1,
-                reason: e.to_string(),
-            })?;
+            .map_err(request_error)?;
+
+        Ok(result.id.0)
+    }
+
+    async fn send_message_with_choices(
+        &self,
+        chat_id: i64,
+        text: &str,
+        choices: &[String],
+    ) -> TelegramResult<i32> {
+        use teloxide::payloads::SendMessageSetters;
+        use teloxide::prelude::*;
+        use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+
+        // Two buttons per row keeps Yes/No side by side and longer lists readable
+        let rows: Vec<Vec<InlineKeyboardButton>> = choices
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                InlineKeyboardButton::callback(label, crate::choices::callback_data(i))
+            })
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(<[InlineKeyboardButton]>::to_vec)
+            .collect();
+
+        let result = self
+            .bot
+            .send_message(teloxide::types::ChatId(chat_id), text)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(InlineKeyboardMarkup::new(rows))
+            .await
+            .map_err(request_error)?;
 
         Ok(result.id.0)
     }
@@ -308,10 +350,7 @@ impl BotApi for TelegramBot {
             request = request.caption(cap).parse_mode(ParseMode::Html);
         }
 
-        let result = request.await.map_err(|e| TelegramError::Send {
-            attempts: 1,
-            reason: e.to_string(),
-        })?;
+        let result = request.await.map_err(request_error)?;
 
         Ok(result.id.0)
     }
@@ -335,15 +374,26 @@ impl BotApi for TelegramBot {
             request = request.caption(cap).parse_mode(ParseMode::Html);
         }
 
-        let result = request.await.map_err(|e| TelegramError::Send {
-            attempts: 1,
-            reason: e.to_string(),
-        })?;
+        let result = request.await.map_err(request_error)?;
 
         Ok(result.id.0)
     }
 }
 
+/// Converts a failed Bot API request into a [`TelegramError`].
+///
+/// Rejections carry Telegram's description so callers can report it;
+/// everything else (network, timeouts) is a retryable send failure.
+fn request_error(error: teloxide::RequestError) -> TelegramError {
+    match error {
+        teloxide::RequestError::Api(api_error) => TelegramError::Api(api_error.to_string()),
+        other => TelegramError::Send {
+            attempts: 1,
+            reason: other.to_string(),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,6 +444,16 @@ mod tests {
             Ok(current)
         }
 
+        async fn send_message_with_choices(
+            &self,
+            chat_id: i64,
+            text: &str,
+            choices: &[String],
+        ) -> TelegramResult<i32> {
+            let labelled = format!("{} [{}]", text, choices.join(" | "));
+            self.send_message(chat_id, &labelled).await
+        }
+
         async fn send_document(
             &self,
             chat_id: i64,
@@ -485,16 +545,6 @@ mod tests {
         assert!(msg.contains("shutting down"));
     }
 
-    #[test]
-    fn escape_html_handles_special_chars() {
-        assert_eq!(
-            super::escape_html("a < b & c > d"),
-            "a &lt; b &amp; c &gt; d"
-        );
-        assert_eq!(super::escape_html("no specials"), "no specials");
-        assert_eq!(super::escape_html(""), "");
-    }
-
     // ---- markdown_to_telegram_html tests ----
 
     #[test]
@@ -0,0 +1,32 @@
+//! Answer choices for agent questions.
+//!
+//! Questions with a small, fixed set of answers (see
+//! [`ralph_core::robot::questions`]) are sent with inline keyboard buttons
+//! so they can be answered with one tap.
+
+pub use ralph_core::robot::questions::{Question, parse_question};
+
+/// Prefix of the callback data attached to choice buttons.
+const CALLBACK_PREFIX: &str = "choice:";
+
+/// Callback data for the button at `index`.
+pub fn callback_data(index: usize) -> String {
+    format!("{}{}", CALLBACK_PREFIX, index)
+}
+
+/// Extracts the choice index from a button's callback data.
+pub fn parse_callback_data(data: &str) -> Option<usize> {
+    data.strip_prefix(CALLBACK_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callback_data_round_trips() {
+        assert_eq!(parse_callback_data(&callback_data(3)), Some(3));
+        assert_eq!(parse_callback_data("choice:x"), None);
+        assert_eq!(parse_callback_data("other:1"), None);
+    }
+}
@@ -7,18 +7,27 @@
 //! stops polling when a loop starts — the loop's own [`TelegramService`]
 //! takes over for the full Telegram feature set (commands, guidance,
 //! responses, check-ins). When the loop finishes, the daemon resumes.
+//!
+//! Prompts that arrive while a loop is running (the daemon's own, or one
+//! started elsewhere) are queued in [`PromptQueue`] and started one at a time
+//! as loops finish. `/queue` lists them and `/cancel <n>` drops one.
+//!
+//! Polling survives Telegram outages: network errors reconnect with
+//! exponential backoff (see [`crate::reconnect`]), and the daemon announces
+//! when it comes back online. An invalid bot token is not retried forever.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use async_trait::async_trait;
 use tracing::{error, info, warn};
 
+use ralph_core::robot::{self, LockState, PromptQueue, escape_html, lock_path, lock_state};
 use ralph_proto::daemon::{DaemonAdapter, StartLoopFn};
 
-use crate::bot::{BotApi, TelegramBot, escape_html};
-use crate::loop_lock::{LockState, lock_path, lock_state};
+use crate::bot::{BotApi, TelegramBot};
+use crate::reconnect::{MAX_FATAL_ATTEMPTS, PollError, ReconnectBackoff};
 use crate::state::StateManager;
 
 async fn wait_for_shutdown(shutdown: Arc<AtomicBool>) {
@@ -30,11 +39,12 @@ async fn wait_for_shutdown(shutdown: Arc<AtomicBool>) {
 /// A Telegram-based daemon adapter.
 ///
 /// Polls Telegram for messages while idle and delegates loop execution
-/// to the provided [`StartLoopFn`] callback. Supports `/status` commands
-/// and graceful shutdown via `SIGINT`/`SIGTERM`.
+/// to the provided [`StartLoopFn`] callback. Supports `/status`, `/queue`
+/// and `/cancel` commands and graceful shutdown via `SIGINT`/`SIGTERM`.
 pub struct TelegramDaemon {
     bot_token: String,
     chat_id: i64,
+    status_events: bool,
 }
 
 impl TelegramDaemon {
@@ -43,7 +53,18 @@ impl TelegramDaemon {
     /// `bot_token` — Telegram Bot API token.
     /// `chat_id` — The Telegram chat to communicate with.
     pub fn new(bot_token: String, chat_id: i64) -> Self {
-        Self { bot_token, chat_id }
+        Self {
+            bot_token,
+            chat_id,
+            status_events: false,
+        }
+    }
+
+    /// Record offline/online transitions as `telegram.offline` and
+    /// `telegram.online` events in `.ralph/events.jsonl`.
+    pub fn with_status_events(mut self, enabled: bool) -> Self {
+        self.status_events = enabled;
+        self
     }
 }
 
@@ -88,25 +109,79 @@ impl DaemonAdapter for TelegramDaemon {
             });
         }
 
+        let queue = PromptQueue::new(&workspace_root)?;
+
         let mut offset: i32 = 0;
+        let mut backoff = ReconnectBackoff::default();
+        let mut fatal_failures: u32 = 0;
+        let mut offline = false;
 
         // Main daemon loop
         'daemon: while !shutdown.load(Ordering::Relaxed) {
             // ── Idle: poll Telegram for messages ──
-            let updates = match tokio::select! {
+            let result = tokio::select! {
                 _ = wait_for_shutdown(shutdown.clone()) => {
                     break 'daemon;
                 }
                 updates = poll_updates(&self.bot_token, 30, offset) => updates,
-            } {
+            };
+            let updates = match result {
                 Ok(u) => u,
                 Err(e) => {
-                    warn!(error = %e, "Telegram poll failed, retrying");
-                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    let delay = match &e {
+                        PollError::Fatal(reason) => {
+                            fatal_failures += 1;
+                            if fatal_failures >= MAX_FATAL_ATTEMPTS {
+                                error!(error = %reason, "Telegram rejected the bot token, giving up");
+                                anyhow::bail!(
+                                    "Telegram rejected the bot token ({}). Check RALPH_TELEGRAM_BOT_TOKEN or run `ralph bot onboard --telegram`.",
+                                    reason
+                                );
+                            }
+                            backoff.next_delay()
+                        }
+                        PollError::RetryAfter(delay) => *delay,
+                        PollError::Transient(reason) => {
+                            if !offline {
+                                offline = true;
+                                warn!(error = %reason, "Telegram unreachable, daemon offline");
+                                self.record_status_event(
+                                    &workspace_root,
+                                    "telegram.offline",
+                                    reason,
+                                );
+                            }
+                            backoff.next_delay()
+                        }
+                    };
+                    warn!(
+                        error = %e,
+                        attempt = backoff.failures(),
+                        retry_in_secs = delay.as_secs(),
+                        "Telegram poll failed, retrying"
+                    );
+                    tokio::select! {
+                        _ = wait_for_shutdown(shutdown.clone()) => break 'daemon,
+                        () = tokio::time::sleep(delay) => {}
+                    }
                     continue;
                 }
             };
 
+            fatal_failures = 0;
+            backoff.reset();
+            if offline {
+                offline = false;
+                info!("Telegram reachable again, daemon online");
+                self.record_status_event(&workspace_root, "telegram.online", "reconnected");
+                let _ = bot
+                    .send_message(
+                        chat_id,
+                        "Ralph daemon reconnected 🔌 Messages sent while it was offline may need resending.",
+                    )
+                    .await;
+            }
+
             for update in updates {
                 offset = update.update_id + 1;
 
@@ -115,26 +190,43 @@ impl DaemonAdapter for TelegramDaemon {
                     None => continue,
                 };
 
-                if let Ok(mut state) = state_manager.load_or_default() {
-                    if state.chat_id.is_none() {
-                        state.chat_id = Some(chat_id);
+                let reply_chat = update.chat_id.unwrap_or(chat_id);
+                let allowed = match state_manager.load_or_default() {
+                    Ok(mut state) => {
+                        if state.chat_id.is_none() && state.allowed_chat_ids.is_empty() {
+                            state.chat_id = Some(chat_id);
+                        }
+                        let allowed = state.is_chat_allowed(reply_chat);
+                        if allowed {
+                            state.last_seen = Some(chrono::Utc::now());
+                        }
+                        state.last_update_id = Some(update.update_id);
+                        if let Err(e) = state_manager.save(&state) {
+                            warn!(error = %e, "Failed to persist Telegram state");
+                        }
+                        allowed
                     }
-                    state.last_seen = Some(chrono::Utc::now());
-                    state.last_update_id = Some(update.update_id);
-                    if let Err(e) = state_manager.save(&state) {
-                        warn!(error = %e, "Failed to persist Telegram state");
+                    Err(e) => {
+                        warn!(error = %e, "Failed to load Telegram state");
+                        reply_chat == chat_id
                     }
-                } else {
-                    warn!("Failed to load Telegram state");
+                };
+                if !allowed {
+                    warn!(
+                        chat_id = reply_chat,
+                        "rejected message from chat not on the allowlist"
+                    );
+                    continue;
                 }
 
                 info!(text = %text, "Daemon received message");
 
                 // Handle daemon-only commands
                 if text.starts_with('/') {
-                    match text.split_whitespace().next().unwrap_or("") {
+                    let (command, args) = robot::commands::parse_command(text);
+                    let msg = match command {
                         "/status" => {
-                            let msg = match lock_state(&workspace_root) {
+                            let status = match lock_state(&workspace_root) {
                                 Ok(LockState::Active) => "A loop is running.".to_string(),
                                 Ok(LockState::Stale) => {
                                     "No active loop (stale lock file found).".to_string()
@@ -144,17 +236,14 @@ impl DaemonAdapter for TelegramDaemon {
                                 }
                                 Err(e) => format!("Failed to check lock state: {}", e),
                             };
-                            let _ = bot.send_message(chat_id, &msg).await;
-                        }
-                        _ => {
-                            let _ = bot
-                                .send_message(
-                                    chat_id,
-                                    "Unknown command. I only handle /status while idle.",
-                                )
-                                .await;
+                            robot::commands::with_queue_depth(status, &workspace_root)
                         }
-                    }
+                        "/queue" => robot::commands::cmd_queue(&workspace_root, args),
+                        "/cancel" => robot::commands::cmd_cancel(&workspace_root, args),
+                        _ => "Unknown command. While idle I handle /status, /queue and /cancel."
+                            .to_string(),
+                    };
+                    let _ = bot.send_message(reply_chat, &msg).await;
                     continue;
                 }
 
@@ -166,20 +255,27 @@ impl DaemonAdapter for TelegramDaemon {
                         warn!(error = %e, "Failed to check loop lock state");
                         let _ = bot
                             .send_message(
-                                chat_id,
+                                reply_chat,
                                 "Failed to check loop state; try again in a moment.",
                             )
                             .await;
                         continue;
                     }
                 };
-                if state == LockState::Active {
-                    let _ = bot
-                        .send_message(
-                            chat_id,
-                            "A loop is already running — it will receive your messages directly.",
-                        )
-                        .await;
+
+                // Busy, or others are waiting: queue behind them
+                if state == LockState::Active || !queue.is_empty() {
+                    let msg = match queue.push(text) {
+                        Ok(position) => format!(
+                            "A loop is already running. Queued as #{} — it starts when the loops ahead of it finish.",
+                            position
+                        ),
+                        Err(e) => {
+                            warn!(error = %e, "Failed to queue prompt");
+                            "A loop is already running, and queuing your prompt failed.".to_string()
+                        }
+                    };
+                    let _ = bot.send_message(reply_chat, &msg).await;
                     continue;
                 }
 
@@ -191,39 +287,32 @@ impl DaemonAdapter for TelegramDaemon {
                 }
 
                 // No loop running — start one with this message as prompt
-                let escaped = escape_html(text);
-                let ack = format!("Starting loop: <i>{}</i>", escaped);
-                let _ = bot.send_message(chat_id, &ack).await;
-
-                // ── Loop Running: hand off Telegram to the loop ──
-                // The loop's TelegramService polls getUpdates, handles commands,
-                // guidance, responses, check-ins. We just await completion.
-                let prompt = text.to_string();
-                let mut loop_handle = tokio::spawn(start_loop(prompt));
-                let result = tokio::select! {
-                    _ = wait_for_shutdown(shutdown.clone()) => {
-                        loop_handle.abort();
-                        let _ = loop_handle.await;
-                        break 'daemon;
-                    }
-                    result = &mut loop_handle => result,
-                };
+                let ack = format!("Starting loop: <i>{}</i>", escape_html(text));
+                if !run_loop(&bot, reply_chat, &ack, text, &start_loop, &shutdown).await {
+                    break 'daemon;
+                }
+            }
 
-                // Loop finished — daemon resumes polling.
-                match result {
-                    Ok(Ok(description)) => {
-                        let notification =
-                            format!("Loop complete ({}).", escape_html(&description));
-                        let _ = bot.send_message(chat_id, &notification).await;
-                    }
-                    Ok(Err(e)) => {
-                        let notification = format!("Loop failed: {}", escape_html(&e.to_string()));
-                        let _ = bot.send_message(chat_id, &notification).await;
-                    }
+            // Start queued prompts one by one once no loop is running
+            while !shutdown.load(Ordering::Relaxed)
+                && lock_state(&workspace_root).is_ok_and(|state| state != LockState::Active)
+            {
+                let next = match queue.pop_front() {
+                    Ok(Some(next)) => next,
+                    Ok(None) => break,
                     Err(e) => {
-                        let notification = format!("Loop failed: {}", escape_html(&e.to_string()));
-                        let _ = bot.send_message(chat_id, &notification).await;
+                        warn!(error = %e, "Failed to read prompt queue");
+                        break;
                     }
+                };
+                let remaining = queue.len();
+                let ack = format!(
+                    "Starting queued loop ({} more queued): <i>{}</i>",
+                    remaining,
+                    escape_html(&next.prompt)
+                );
+                if !run_loop(&bot, chat_id, &ack, &next.prompt, &start_loop, &shutdown).await {
+                    break 'daemon;
                 }
             }
         }
@@ -235,6 +324,62 @@ impl DaemonAdapter for TelegramDaemon {
     }
 }
 
+impl TelegramDaemon {
+    /// Appends a connectivity event to `.ralph/events.jsonl` when enabled.
+    fn record_status_event(&self, workspace_root: &Path, topic: &str, payload: &str) {
+        if !self.status_events {
+            return;
+        }
+        if let Err(e) = append_status_event(workspace_root, topic, payload) {
+            warn!(error = %e, topic, "Failed to record daemon status event");
+        }
+    }
+}
+
+fn append_status_event(workspace_root: &Path, topic: &str, payload: &str) -> std::io::Result<()> {
+    let line = serde_json::json!({
+        "topic": topic,
+        "payload": payload,
+        "ts": chrono::Utc::now().to_rfc3339(),
+    });
+    ralph_core::EventHistory::new(workspace_root.join(".ralph/events.jsonl"))
+        .append(&line.to_string(), false)
+}
+
+/// Runs one loop for `prompt` and reports the outcome to `chat`.
+///
+/// The loop's own `TelegramService` polls Telegram meanwhile (commands,
+/// guidance, responses, check-ins). Returns false if the daemon was asked
+/// to shut down before the loop finished.
+async fn run_loop(
+    bot: &TelegramBot,
+    chat: i64,
+    ack: &str,
+    prompt: &str,
+    start_loop: &StartLoopFn,
+    shutdown: &Arc<AtomicBool>,
+) -> bool {
+    let _ = bot.send_message(chat, ack).await;
+
+    let mut loop_handle = tokio::spawn(start_loop(prompt.to_string()));
+    let result = tokio::select! {
+        _ = wait_for_shutdown(shutdown.clone()) => {
+            loop_handle.abort();
+            let _ = loop_handle.await;
+            return false;
+        }
+        result = &mut loop_handle => result,
+    };
+
+    let notification = match result {
+        Ok(Ok(description)) => format!("Loop complete ({}).", escape_html(&description)),
+        Ok(Err(e)) => format!("Loop failed: {}", escape_html(&e.to_string())),
+        Err(e) => format!("Loop failed: {}", escape_html(&e.to_string())),
+    };
+    let _ = bot.send_message(chat, &notification).await;
+    true
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Lightweight Telegram polling (teloxide Bot client)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -242,6 +387,7 @@ impl DaemonAdapter for TelegramDaemon {
 /// A minimal parsed update for daemon idle polling.
 struct DaemonUpdate {
     update_id: i32,
+    chat_id: Option<i64>,
     text: Option<String>,
 }
 
@@ -253,7 +399,7 @@ async fn poll_updates(
     token: &str,
     timeout_secs: u64,
     offset: i32,
-) -> anyhow::Result<Vec<DaemonUpdate>> {
+) -> Result<Vec<DaemonUpdate>, PollError> {
     use teloxide::payloads::GetUpdatesSetters;
     use teloxide::requests::Requester;
 
@@ -263,22 +409,23 @@ async fn poll_updates(
         .offset(offset)
         .timeout(timeout_secs as u32);
 
-    let updates = request
-        .await
-        .map_err(|e| anyhow::anyhow!("Telegram getUpdates failed: {}", e))?;
+    let updates = request.await.map_err(|e| PollError::classify(&e))?;
 
     let mut results = Vec::new();
     for update in updates {
         #[allow(clippy::cast_possible_wrap)]
         let id = update.id.0 as i32;
 
-        let text = match update.kind {
-            teloxide::types::UpdateKind::Message(ref msg) => msg.text().map(String::from),
-            _ => None,
+        let (chat_id, text) = match update.kind {
+            teloxide::types::UpdateKind::Message(ref msg) => {
+                (Some(msg.chat.id.0), msg.text().map(String::from))
+            }
+            _ => (None, None),
         };
 
         results.push(DaemonUpdate {
             update_id: id,
+            chat_id,
             text,
         });
     }
@@ -295,5 +442,31 @@ mod tests {
         let daemon = TelegramDaemon::new("test-token".to_string(), 12345);
         assert_eq!(daemon.bot_token, "test-token");
         assert_eq!(daemon.chat_id, 12345);
+        assert!(!daemon.status_events);
+    }
+
+    #[test]
+    fn test_status_events_written_only_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let events_path = dir.path().join(".ralph/events.jsonl");
+
+        let daemon = TelegramDaemon::new("test-token".to_string(), 12345);
+        daemon.record_status_event(dir.path(), "telegram.offline", "network down");
+        assert!(!events_path.exists());
+
+        let daemon = daemon.with_status_events(true);
+        daemon.record_status_event(dir.path(), "telegram.offline", "network down");
+        daemon.record_status_event(dir.path(), "telegram.online", "reconnected");
+
+        let content = std::fs::read_to_string(&events_path).unwrap();
+        let events: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["topic"], "telegram.offline");
+        assert_eq!(events[0]["payload"], "network down");
+        assert!(events[0]["ts"].is_string());
+        assert_eq!(events[1]["topic"], "telegram.online");
     }
 }
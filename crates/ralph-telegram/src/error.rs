@@ -16,6 +16,13 @@ pub enum TelegramError {
     #[error("failed to start telegram bot: {0}")]
     Startup(String),
 
+    /// Telegram rejected the request (e.g., chat not found, malformed HTML).
+    ///
+    /// Holds the Bot API's error description. Not retried, since resending
+    /// the same request fails the same way.
+    #[error("telegram API error: {0}")]
+    Api(String),
+
     /// Failed to send a message after retries.
     #[error("failed to send telegram message after {attempts} attempts: {reason}")]
     Send { attempts: u32, reason: String },
@@ -36,6 +43,10 @@ pub enum TelegramError {
     #[error("state parse error: {0}")]
     StateParse(#[from] serde_json::Error),
 
+    /// A message came from a chat that isn't on the allowlist.
+    #[error("chat {0} is not on the allowlist")]
+    UnauthorizedChat(i64),
+
     /// Failed to write event to JSONL.
     #[error("event write error: {0}")]
     EventWrite(String),
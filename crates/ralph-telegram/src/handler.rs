@@ -1,8 +1,9 @@
 use std::path::{Path, PathBuf};
 
 use chrono::Utc;
+use ralph_core::robot::loop_events_path;
 
-use crate::error::TelegramResult;
+use crate::error::{TelegramError, TelegramResult};
 use crate::state::{StateManager, TelegramState};
 
 /// Processes incoming Telegram messages and writes events to the correct loop's events.jsonl.
@@ -20,9 +21,30 @@ impl MessageHandler {
         }
     }
 
+    /// Checks that `chat_id` may steer the bot.
+    ///
+    /// The first chat to write when none is configured is adopted as the
+    /// primary chat. After that, chats not on the allowlist are rejected
+    /// with [`TelegramError::UnauthorizedChat`].
+    pub fn authorize(&self, state: &mut TelegramState, chat_id: i64) -> TelegramResult<()> {
+        if state.chat_id.is_none() && state.allowed_chat_ids.is_empty() {
+            state.chat_id = Some(chat_id);
+            self.state_manager.save(state)?;
+            tracing::info!(chat_id, "auto-detected chat ID from first message");
+            return Ok(());
+        }
+
+        if state.is_chat_allowed(chat_id) {
+            Ok(())
+        } else {
+            tracing::warn!(chat_id, "rejected message from chat not on the allowlist");
+            Err(TelegramError::UnauthorizedChat(chat_id))
+        }
+    }
+
     /// Handle an incoming message from Telegram.
     ///
-    /// Determines target loop, classifies as response or guidance, and appends
+    /// Rejects chats not on the allowlist (see [`Self::authorize`]). Determines target loop, classifies as response or guidance, and appends
     /// the appropriate event to the loop's events.jsonl.
     ///
     /// Returns the event topic that was written (`"human.response"` or `"human.guidance"`).
@@ -33,12 +55,7 @@ impl MessageHandler {
         chat_id: i64,
         reply_to_message_id: Option<i32>,
     ) -> TelegramResult<String> {
-        // Auto-detect chat ID from first message
-        if state.chat_id.is_none() {
-            state.chat_id = Some(chat_id);
-            self.state_manager.save(state)?;
-            tracing::info!(chat_id, "auto-detected chat ID from first message");
-        }
+        self.authorize(state, chat_id)?;
 
         let target_loop = self.determine_target_loop(state, text, reply_to_message_id);
         let events_path = self.get_events_path(&target_loop);
@@ -51,11 +68,15 @@ impl MessageHandler {
         };
 
         let timestamp = Utc::now().to_rfc3339();
-        let event_json = serde_json::json!({
+        let mut event_json = serde_json::json!({
             "topic": topic,
             "payload": text,
             "ts": timestamp,
         });
+        // Lets the waiting loop tell which question a response answers
+        if let Some(reply_id) = reply_to_message_id {
+            event_json["reply_to"] = reply_id.into();
+        }
         let event_line = serde_json::to_string(&event_json)?;
 
         self.append_event(&events_path, &event_line)?;
@@ -76,6 +97,39 @@ impl MessageHandler {
         Ok(topic.to_string())
     }
 
+    /// Handle a press of a choice button attached to a question.
+    ///
+    /// `data` is the button's callback data and `message_id` the message the
+    /// buttons were attached to. The chosen label is written as the same
+    /// `human.response` event a typed reply would produce.
+    ///
+    /// Returns the chosen label, or `None` if the button doesn't belong to a
+    /// pending question (e.g., it was already answered or timed out).
+    pub fn handle_choice(
+        &self,
+        state: &mut TelegramState,
+        data: &str,
+        chat_id: i64,
+        message_id: i32,
+    ) -> TelegramResult<Option<String>> {
+        self.authorize(state, chat_id)?;
+        let Some(index) = crate::choices::parse_callback_data(data) else {
+            return Ok(None);
+        };
+        let Some(choice) = state
+            .pending_questions
+            .values()
+            .find(|q| q.has_message(message_id))
+            .and_then(|q| q.choices.get(index))
+            .cloned()
+        else {
+            return Ok(None);
+        };
+
+        self.handle_message(state, &choice, chat_id, Some(message_id))?;
+        Ok(Some(choice))
+    }
+
     /// Determine which loop a message is targeted at.
     ///
     /// Priority:
@@ -107,76 +161,17 @@ impl MessageHandler {
     }
 
     /// Get the active events file path for a given loop.
-    ///
-    /// Reads the `current-events` marker to find the timestamped events file.
-    /// Falls back to the default `events.jsonl` if the marker doesn't exist.
     fn get_events_path(&self, loop_id: &str) -> PathBuf {
-        let ralph_dir = if loop_id == "main" {
-            self.workspace_root.join(".ralph")
-        } else {
-            self.workspace_root
-                .join(".worktrees")
-                .join(loop_id)
-                .join(".ralph")
-        };
-
-        let marker_path = ralph_dir.join("current-events");
-        if let Ok(contents) = std::fs::read_to_string(&marker_path) {
-            let relative = contents.trim();
-            if !relative.is_empty() {
-                // Marker contains a path relative to workspace root
-                // (e.g., ".ralph/events-20260201-210033.jsonl")
-                if loop_id == "main" {
-                    return self.workspace_root.join(relative);
-                } else {
-                    return self
-                        .workspace_root
-                        .join(".worktrees")
-                        .join(loop_id)
-                        .join(relative);
-                }
-            }
-        }
-
-        ralph_dir.join("events.jsonl")
+        loop_events_path(&self.workspace_root, loop_id)
     }
 
-    /// Append an event line to the given file atomically.
+    /// Append an event line to the given file, locked against concurrent writers.
     fn append_event(&self, path: &Path, event_line: &str) -> TelegramResult<()> {
-        use std::fs::OpenOptions;
-        use std::io::Write;
-
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                crate::error::TelegramError::EventWrite(format!(
-                    "failed to create directory {}: {}",
-                    parent.display(),
-                    e
-                ))
-            })?;
-        }
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
+        ralph_core::EventHistory::new(path)
+            .append(event_line, false)
             .map_err(|e| {
-                crate::error::TelegramError::EventWrite(format!(
-                    "failed to open {}: {}",
-                    path.display(),
-                    e
-                ))
-            })?;
-
-        writeln!(file, "{}", event_line).map_err(|e| {
-            crate::error::TelegramError::EventWrite(format!(
-                "failed to write to {}: {}",
-                path.display(),
-                e
-            ))
-        })?;
-
-        Ok(())
+                TelegramError::EventWrite(format!("failed to write to {}: {}", path.display(), e))
+            })
     }
 }
 
@@ -194,9 +189,11 @@ mod tests {
         let handler = MessageHandler::new(state_manager, dir.path());
         let state = TelegramState {
             chat_id: None,
+            allowed_chat_ids: Vec::new(),
             last_seen: None,
             last_update_id: None,
             pending_questions: HashMap::new(),
+            recent_notifications: Vec::new(),
         };
         (handler, dir, state)
     }
@@ -225,6 +222,8 @@ mod tests {
             crate::state::PendingQuestion {
                 asked_at: chrono::Utc::now(),
                 message_id: 42,
+                earlier_message_ids: Vec::new(),
+                choices: Vec::new(),
             },
         );
 
@@ -269,6 +268,69 @@ mod tests {
         assert_eq!(state.chat_id, Some(999));
     }
 
+    #[test]
+    fn accepts_messages_from_allowlisted_chats() {
+        let (handler, dir, mut state) = setup();
+        state.allow_chat(111);
+        state.allow_chat(222);
+
+        handler
+            .handle_message(&mut state, "from primary", 111, None)
+            .unwrap();
+        handler
+            .handle_message(&mut state, "from second chat", 222, None)
+            .unwrap();
+
+        let events_path = dir.path().join(".ralph/events.jsonl");
+        let contents = std::fs::read_to_string(events_path).unwrap();
+        let payloads: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                let event: serde_json::Value = serde_json::from_str(line).unwrap();
+                event["payload"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(payloads, vec!["from primary", "from second chat"]);
+        assert_eq!(state.chat_id, Some(111));
+    }
+
+    #[test]
+    fn rejects_messages_from_chats_not_on_allowlist() {
+        let (handler, dir, mut state) = setup();
+        state.allow_chat(111);
+
+        let err = handler
+            .handle_message(&mut state, "let me in", 666, None)
+            .unwrap_err();
+
+        assert!(matches!(err, TelegramError::UnauthorizedChat(666)));
+        assert!(!dir.path().join(".ralph/events.jsonl").exists());
+        assert_eq!(state.allowed_chats(), vec![111]);
+    }
+
+    #[test]
+    fn rejected_chat_cannot_answer_choice_buttons() {
+        let (handler, dir, mut state) = setup();
+        state.allow_chat(111);
+        state.pending_questions.insert(
+            "main".to_string(),
+            crate::state::PendingQuestion {
+                asked_at: chrono::Utc::now(),
+                message_id: 42,
+                earlier_message_ids: Vec::new(),
+                choices: vec!["Yes".to_string(), "No".to_string()],
+            },
+        );
+
+        assert!(
+            handler
+                .handle_choice(&mut state, "choice:0", 666, 42)
+                .is_err()
+        );
+        assert!(!dir.path().join(".ralph/events.jsonl").exists());
+        assert!(state.pending_questions.contains_key("main"));
+    }
+
     #[test]
     fn writes_to_timestamped_events_file_when_marker_exists() {
         let (handler, dir, mut state) = setup();
@@ -305,4 +367,66 @@ mod tests {
             "event should NOT be written to default events.jsonl when marker exists"
         );
     }
+
+    #[test]
+    fn choice_button_writes_response_event() {
+        let (handler, dir, mut state) = setup();
+        state.pending_questions.insert(
+            "main".to_string(),
+            crate::state::PendingQuestion {
+                asked_at: chrono::Utc::now(),
+                message_id: 42,
+                earlier_message_ids: Vec::new(),
+                choices: vec!["Yes".to_string(), "No".to_string()],
+            },
+        );
+
+        let choice = handler
+            .handle_choice(&mut state, &crate::choices::callback_data(1), 123, 42)
+            .unwrap();
+
+        assert_eq!(choice.as_deref(), Some("No"));
+        let events_path = dir.path().join(".ralph/events.jsonl");
+        let contents = std::fs::read_to_string(events_path).unwrap();
+        let event: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(event["topic"], "human.response");
+        assert_eq!(event["payload"], "No");
+        assert_eq!(event["reply_to"], 42);
+        assert!(!state.pending_questions.contains_key("main"));
+    }
+
+    #[test]
+    fn stale_choice_button_is_ignored() {
+        let (handler, dir, mut state) = setup();
+        state.pending_questions.insert(
+            "main".to_string(),
+            crate::state::PendingQuestion {
+                asked_at: chrono::Utc::now(),
+                message_id: 42,
+                earlier_message_ids: Vec::new(),
+                choices: vec!["Yes".to_string(), "No".to_string()],
+            },
+        );
+
+        // Button on a message that is no longer pending, and an out-of-range index
+        assert_eq!(
+            handler
+                .handle_choice(&mut state, "choice:0", 123, 7)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            handler
+                .handle_choice(&mut state, "choice:5", 123, 42)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            handler.handle_choice(&mut state, "bogus", 123, 42).unwrap(),
+            None
+        );
+
+        assert!(!dir.path().join(".ralph/events.jsonl").exists());
+        assert!(state.pending_questions.contains_key("main"));
+    }
 }
@@ -16,19 +16,21 @@
 //! - [`error`] — Error types for startup, send, and receive failures
 
 mod bot;
-pub mod commands;
+pub mod choices;
 pub mod daemon;
 mod error;
 mod handler;
-mod loop_lock;
+pub mod reconnect;
 mod service;
 mod state;
 
-pub use bot::{BotApi, TelegramBot, escape_html, markdown_to_telegram_html};
+pub use bot::{BotApi, TELEGRAM_MESSAGE_LIMIT, TelegramBot, markdown_to_telegram_html};
 pub use daemon::TelegramDaemon;
 pub use error::{TelegramError, TelegramResult};
 pub use handler::MessageHandler;
-pub use service::{
-    BASE_RETRY_DELAY, CheckinContext, MAX_SEND_RETRIES, TelegramService, retry_with_backoff,
+pub use ralph_core::robot::{
+    BASE_RETRY_DELAY, DEFAULT_NOTIFICATIONS_PER_HOUR, MAX_SEND_RETRIES, escape_html,
+    loop_events_path, mask_token,
 };
+pub use service::{CheckinContext, TelegramService, retry_with_backoff};
 pub use state::{PendingQuestion, StateManager, TelegramState};
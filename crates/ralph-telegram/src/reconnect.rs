@@ -0,0 +1,179 @@
+//! Reconnect strategy for the daemon's Telegram polling loop.
+//!
+//! Network errors back off exponentially with jitter, capped at
+//! [`RECONNECT_MAX_DELAY`], so a Telegram outage neither kills the daemon nor
+//! floods the logs. Errors that retrying can't fix (an invalid bot token) are
+//! classified as fatal so the daemon can give up with a clear message.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use teloxide::{ApiError, RequestError};
+
+/// Delay after the first failed poll.
+pub const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between reconnect attempts.
+pub const RECONNECT_MAX_DELAY: Duration = Duration::from_mins(5);
+
+/// Consecutive fatal errors tolerated before the daemon exits.
+pub const MAX_FATAL_ATTEMPTS: u32 = 2;
+
+/// Why a poll failed, and how the daemon should react.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollError {
+    /// Retrying won't help (e.g., Telegram rejected the bot token).
+    Fatal(String),
+
+    /// Telegram asked us to wait before polling again (flood control).
+    RetryAfter(Duration),
+
+    /// Network blip or server error; reconnect with backoff.
+    Transient(String),
+}
+
+impl PollError {
+    /// Classifies a Telegram request error.
+    pub fn classify(error: &RequestError) -> Self {
+        match error {
+            RequestError::Api(ApiError::InvalidToken) => Self::Fatal(error.to_string()),
+            RequestError::RetryAfter(secs) => Self::RetryAfter(secs.duration()),
+            _ => Self::Transient(error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for PollError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fatal(reason) | Self::Transient(reason) => write!(f, "{}", reason),
+            Self::RetryAfter(delay) => write!(f, "rate limited, retry after {}s", delay.as_secs()),
+        }
+    }
+}
+
+/// Exponential backoff with jitter for reconnect attempts.
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    base: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl ReconnectBackoff {
+    /// Creates a backoff starting at `base` and capped at `max`.
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            failures: 0,
+        }
+    }
+
+    /// Number of consecutive failures since the last reset.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Records a failure and returns how long to wait before retrying.
+    pub fn next_delay(&mut self) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        backoff_delay(self.base, self.max, self.failures, jitter_fraction())
+    }
+
+    /// Clears the failure count after a successful poll.
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY)
+    }
+}
+
+/// Delay for the `attempt`th consecutive failure (1-based).
+///
+/// Uses "equal jitter": half of the capped exponential delay is fixed and the
+/// other half is scaled by `jitter` (0.0..=1.0), so reconnecting daemons
+/// spread out without ever waiting less than half the nominal delay.
+fn backoff_delay(base: Duration, max: Duration, attempt: u32, jitter: f64) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(31);
+    let nominal = base.saturating_mul(1u32 << exponent).min(max);
+    let half = nominal / 2;
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+/// Cheap jitter source in `0.0..1.0` from the clock's sub-second nanos.
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1_000) / 1_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_delay_doubles_until_cap() {
+        let base = Duration::from_secs(1);
+        let max = Duration::from_mins(5);
+
+        // At maximum jitter the delay is the nominal exponential delay
+        assert_eq!(backoff_delay(base, max, 1, 1.0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(base, max, 2, 1.0), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, max, 5, 1.0), Duration::from_secs(16));
+        assert_eq!(backoff_delay(base, max, 9, 1.0), Duration::from_secs(256));
+        assert_eq!(backoff_delay(base, max, 10, 1.0), max);
+        assert_eq!(backoff_delay(base, max, 1_000, 1.0), max);
+    }
+
+    #[test]
+    fn backoff_delay_jitter_stays_within_half_to_full() {
+        let base = Duration::from_secs(1);
+        let max = Duration::from_mins(5);
+
+        assert_eq!(backoff_delay(base, max, 5, 0.0), Duration::from_secs(8));
+        assert_eq!(backoff_delay(base, max, 5, 0.5), Duration::from_secs(12));
+        for attempt in 1..20 {
+            let delay = backoff_delay(base, max, attempt, jitter_fraction());
+            assert!(delay <= max);
+        }
+    }
+
+    #[test]
+    fn reconnect_backoff_counts_and_resets() {
+        let mut backoff = ReconnectBackoff::default();
+        backoff.next_delay();
+        backoff.next_delay();
+        assert_eq!(backoff.failures(), 2);
+
+        backoff.reset();
+        assert_eq!(backoff.failures(), 0);
+        assert!(backoff.next_delay() <= RECONNECT_BASE_DELAY);
+    }
+
+    #[test]
+    fn classify_invalid_token_is_fatal() {
+        let error = RequestError::Api(ApiError::InvalidToken);
+        assert!(matches!(PollError::classify(&error), PollError::Fatal(_)));
+    }
+
+    #[test]
+    fn classify_other_errors_are_retried() {
+        let error = RequestError::Api(ApiError::ChatNotFound);
+        assert!(matches!(
+            PollError::classify(&error),
+            PollError::Transient(_)
+        ));
+
+        let error = RequestError::RetryAfter(teloxide::types::Seconds::from_seconds(7));
+        assert_eq!(
+            PollError::classify(&error),
+            PollError::RetryAfter(Duration::from_secs(7))
+        );
+    }
+}
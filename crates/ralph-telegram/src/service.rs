@@ -2,27 +2,27 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use chrono::Utc;
+use ralph_core::robot::{
+    self, BASE_RETRY_DELAY, DEFAULT_NOTIFICATIONS_PER_HOUR, MAX_SEND_RETRIES, QuestionThread,
+    allow_notification, mask_token,
+};
+use ralph_proto::LifecycleNotification;
 use tracing::{debug, info, warn};
 
-use crate::bot::TelegramBot;
+use crate::bot::{TELEGRAM_MESSAGE_LIMIT, TelegramBot};
 use crate::error::{TelegramError, TelegramResult};
 use crate::handler::MessageHandler;
 use crate::state::StateManager;
 
-/// Maximum number of retry attempts for sending messages.
-pub const MAX_SEND_RETRIES: u32 = 3;
-
-/// Base delay for exponential backoff (1 second).
-pub const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
-
 /// Execute a fallible send operation with exponential backoff retry.
 ///
 /// Retries up to [`MAX_SEND_RETRIES`] times with delays of 1s, 2s, 4s.
 /// Returns the result on success, or `TelegramError::Send` after all
-/// retries are exhausted.
+/// retries are exhausted. `TelegramError::Api` errors are returned
+/// immediately without retrying.
 ///
 /// The `sleep_fn` parameter allows tests to substitute a no-op sleep.
 pub fn retry_with_backoff<F, S>(mut send_fn: F, mut sleep_fn: S) -> TelegramResult<i32>
@@ -35,6 +35,8 @@ where
     for attempt in 1..=MAX_SEND_RETRIES {
         match send_fn(attempt) {
             Ok(msg_id) => return Ok(msg_id),
+            // Telegram rejected the request itself; resending won't change that
+            Err(e @ TelegramError::Api(_)) => return Err(e),
             Err(e) => {
                 last_error = e.to_string();
                 warn!(
@@ -91,6 +93,9 @@ pub struct TelegramService {
     handler: MessageHandler,
     bot: TelegramBot,
     shutdown: Arc<AtomicBool>,
+    notifications_per_hour: u32,
+    presence_messages: bool,
+    api_url: Option<reqwest::Url>,
 }
 
 impl TelegramService {
@@ -123,9 +128,39 @@ impl TelegramService {
             handler,
             bot,
             shutdown,
+            notifications_per_hour: DEFAULT_NOTIFICATIONS_PER_HOUR,
+            presence_messages: true,
+            api_url: None,
         })
     }
 
+    /// Caps lifecycle notifications at `limit` per hour (0 = unlimited).
+    ///
+    /// The count is persisted in the state file, so a loop that keeps
+    /// restarting can't flood the chat.
+    pub fn with_notification_limit(mut self, limit: u32) -> Self {
+        self.notifications_per_hour = limit;
+        self
+    }
+
+    /// Enables or disables the "bot online" / "shutting down" messages.
+    ///
+    /// Turn these off when lifecycle notifications already announce the
+    /// loop's start and end.
+    pub fn with_presence_messages(mut self, enabled: bool) -> Self {
+        self.presence_messages = enabled;
+        self
+    }
+
+    /// Sends all Bot API requests to `api_url` instead of api.telegram.org.
+    ///
+    /// Used for self-hosted Bot API servers and for tests.
+    pub fn with_api_url(mut self, api_url: reqwest::Url) -> Self {
+        self.bot = self.bot.with_api_url(api_url.clone());
+        self.api_url = Some(api_url);
+        self
+    }
+
     /// Get a reference to the workspace root.
     pub fn workspace_root(&self) -> &PathBuf {
         &self.workspace_root
@@ -138,15 +173,7 @@ impl TelegramService {
 
     /// Get a reference to the bot token (masked for logging).
     pub fn bot_token_masked(&self) -> String {
-        if self.bot_token.len() > 8 {
-            format!(
-                "{}...{}",
-                &self.bot_token[..4],
-                &self.bot_token[self.bot_token.len() - 4..]
-            )
-        } else {
-            "****".to_string()
-        }
+        mask_token(&self.bot_token)
     }
 
     /// Get a reference to the state manager.
@@ -189,18 +216,30 @@ impl TelegramService {
             TelegramError::Startup("no tokio runtime available for polling".to_string())
         })?;
 
-        let raw_bot = teloxide::Bot::new(&self.bot_token);
+        let mut raw_bot = teloxide::Bot::new(&self.bot_token);
+        if let Some(api_url) = &self.api_url {
+            raw_bot = raw_bot.set_api_url(api_url.clone());
+        }
         let workspace_root = self.workspace_root.clone();
         let state_path = self.workspace_root.join(".ralph/telegram-state.json");
         let shutdown = self.shutdown.clone();
         let loop_id = self.loop_id.clone();
 
         handle.spawn(async move {
-            Self::poll_updates(raw_bot, workspace_root, state_path, shutdown, loop_id).await;
+            // Boxed: the polling future is too large to keep on the stack
+            Box::pin(Self::poll_updates(
+                raw_bot,
+                workspace_root,
+                state_path,
+                shutdown,
+                loop_id,
+            ))
+            .await;
         });
 
         // Send greeting if we already know the chat ID
-        if let Ok(state) = self.state_manager.load_or_default()
+        if self.presence_messages
+            && let Ok(state) = self.state_manager.load_or_default()
             && let Some(chat_id) = state.chat_id
         {
             let greeting = crate::bot::TelegramBot::format_greeting(&self.loop_id);
@@ -258,6 +297,17 @@ impl TelegramService {
                         // Extract message from update kind
                         let msg = match update.kind {
                             teloxide::types::UpdateKind::Message(msg) => msg,
+                            teloxide::types::UpdateKind::CallbackQuery(query) => {
+                                Self::handle_choice_query(
+                                    &bot,
+                                    &handler,
+                                    &state_manager,
+                                    &query,
+                                    offset.saturating_sub(1),
+                                )
+                                .await;
+                                continue;
+                            }
                             _ => continue,
                         };
 
@@ -275,22 +325,6 @@ impl TelegramService {
                             "Received Telegram message"
                         );
 
-                        // Handle bot commands before routing to handler
-                        if crate::commands::is_command(text)
-                            && let Some(response) =
-                                crate::commands::handle_command(text, &workspace_root)
-                        {
-                            use teloxide::payloads::SendMessageSetters;
-                            let send_result = bot
-                                .send_message(teloxide::types::ChatId(chat_id), &response)
-                                .parse_mode(teloxide::types::ParseMode::Html)
-                                .await;
-                            if let Err(e) = send_result {
-                                warn!(error = %e, "Failed to send command response");
-                            }
-                            continue;
-                        }
-
                         let mut state = match state_manager.load_or_default() {
                             Ok(s) => s,
                             Err(e) => {
@@ -299,6 +333,33 @@ impl TelegramService {
                             }
                         };
 
+                        // Only allowlisted chats may run commands or steer loops
+                        if handler.authorize(&mut state, chat_id).is_err() {
+                            state.last_update_id = Some(offset.saturating_sub(1));
+                            if let Err(e) = state_manager.save(&state) {
+                                warn!(error = %e, "Failed to persist Telegram state");
+                            }
+                            continue;
+                        }
+
+                        // Handle bot commands before routing to handler
+                        if robot::is_command(text)
+                            && let Some(response) = robot::handle_command(text, &workspace_root)
+                        {
+                            use teloxide::payloads::SendMessageSetters;
+                            for chunk in robot::split_message(&response, TELEGRAM_MESSAGE_LIMIT) {
+                                let send_result = bot
+                                    .send_message(teloxide::types::ChatId(chat_id), chunk)
+                                    .parse_mode(teloxide::types::ParseMode::Html)
+                                    .await;
+                                if let Err(e) = send_result {
+                                    warn!(error = %e, "Failed to send command response");
+                                    break;
+                                }
+                            }
+                            continue;
+                        }
+
                         match handler.handle_message(&mut state, text, chat_id, reply_to) {
                             Ok(topic) => {
                                 let emoji = if topic == "human.response" {
@@ -354,6 +415,77 @@ impl TelegramService {
         info!(loop_id = %loop_id, "Telegram polling task stopped");
     }
 
+    /// Handles a press of a question's choice button.
+    ///
+    /// Writes the chosen answer as a `human.response`, acknowledges the press
+    /// (Telegram shows a spinner until it is answered), and removes the
+    /// buttons so the question can't be answered twice.
+    async fn handle_choice_query(
+        bot: &teloxide::Bot,
+        handler: &MessageHandler,
+        state_manager: &StateManager,
+        query: &teloxide::types::CallbackQuery,
+        update_id: i32,
+    ) {
+        use teloxide::payloads::AnswerCallbackQuerySetters;
+        use teloxide::requests::Requester;
+
+        let (Some(data), Some(message)) = (query.data.as_deref(), query.message.as_ref()) else {
+            return;
+        };
+        let chat_id = message.chat().id;
+
+        let mut state = match state_manager.load_or_default() {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(error = %e, "Failed to load Telegram state");
+                return;
+            }
+        };
+
+        let acknowledgement =
+            match handler.handle_choice(&mut state, data, chat_id.0, message.id().0) {
+                Ok(Some(choice)) => {
+                    info!(chat_id = chat_id.0, choice = %choice, "Received Telegram choice");
+                    format!("Answered: {}", choice)
+                }
+                Ok(None) => "This question is no longer waiting for an answer.".to_string(),
+                Err(TelegramError::UnauthorizedChat(_)) => {
+                    // Leave the buttons in place for an allowed chat to answer
+                    let _ = bot
+                        .answer_callback_query(query.id.clone())
+                        .text("This chat isn't allowed to answer questions.")
+                        .await;
+                    state.last_update_id = Some(update_id);
+                    if let Err(e) = state_manager.save(&state) {
+                        warn!(error = %e, "Failed to persist Telegram state");
+                    }
+                    return;
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to handle Telegram choice");
+                    "Failed to record your answer.".to_string()
+                }
+            };
+
+        if let Err(e) = bot
+            .answer_callback_query(query.id.clone())
+            .text(acknowledgement)
+            .await
+        {
+            warn!(error = %e, "Failed to answer callback query");
+        }
+        if let Err(e) = bot.edit_message_reply_markup(chat_id, message.id()).await {
+            debug!(error = %e, "Failed to remove choice buttons");
+        }
+
+        state.last_seen = Some(Utc::now());
+        state.last_update_id = Some(update_id);
+        if let Err(e) = state_manager.save(&state) {
+            warn!(error = %e, "Failed to persist Telegram state");
+        }
+    }
+
     /// Register bot commands with the Telegram API so they appear in the menu.
     async fn register_commands(bot: &teloxide::Bot) {
         use teloxide::requests::Requester;
@@ -365,6 +497,8 @@ impl TelegramService {
             BotCommand::new("memories", "Recent memories"),
             BotCommand::new("tail", "Last 20 events"),
             BotCommand::new("stop", "Stop the loop"),
+            BotCommand::new("queue", "List or add queued prompts"),
+            BotCommand::new("cancel", "Drop a queued prompt"),
             BotCommand::new("help", "List available commands"),
         ];
 
@@ -379,7 +513,8 @@ impl TelegramService {
     /// Signals the background polling task to shut down.
     pub fn stop(self) {
         // Send farewell if we know the chat ID
-        if let Ok(state) = self.state_manager.load_or_default()
+        if self.presence_messages
+            && let Ok(state) = self.state_manager.load_or_default()
             && let Some(chat_id) = state.chat_id
         {
             let farewell = crate::bot::TelegramBot::format_farewell(&self.loop_id);
@@ -402,25 +537,49 @@ impl TelegramService {
     /// question is stored in the state manager so that incoming replies can be
     /// routed back to the correct loop.
     ///
+    /// Questions longer than Telegram's 4096-character limit are sent as
+    /// several messages; replies to any of them are routed to this loop.
+    ///
+    /// Yes/no questions, and JSON payloads with a `choices` list, get inline
+    /// buttons on the last message (see [`crate::choices`]).
+    ///
     /// On send failure, retries up to 3 times with exponential backoff (1s, 2s, 4s).
-    /// Returns the message ID of the sent Telegram message, or 0 if no chat ID
-    /// is configured (question is logged but not sent).
+    /// Returns the message ID of the (last) sent Telegram message, or 0 if no
+    /// chat ID is configured (question is logged but not sent).
     pub fn send_question(&self, payload: &str) -> TelegramResult<i32> {
         let mut state = self.state_manager.load_or_default()?;
-
-        let message_id = if let Some(chat_id) = state.chat_id {
-            self.send_with_retry(chat_id, payload)?
+        let question = crate::choices::parse_question(payload);
+
+        let message_ids = if let Some(chat_id) = state.chat_id {
+            let chunks = robot::split_message(&question.text, TELEGRAM_MESSAGE_LIMIT);
+            let last = chunks.len().saturating_sub(1);
+            chunks
+                .iter()
+                .enumerate()
+                .map(|(i, chunk)| {
+                    if i == last && !question.choices.is_empty() {
+                        self.send_choices_with_retry(chat_id, chunk, &question.choices)
+                    } else {
+                        self.send_with_retry(chat_id, chunk)
+                    }
+                })
+                .collect::<TelegramResult<Vec<_>>>()?
         } else {
             warn!(
                 loop_id = %self.loop_id,
                 "No chat ID configured — human.interact question logged but not sent: {}",
                 payload
             );
-            0
+            vec![0]
         };
+        let message_id = message_ids.last().copied().unwrap_or(0);
 
-        self.state_manager
-            .add_pending_question(&mut state, &self.loop_id, message_id)?;
+        self.state_manager.add_split_pending_question(
+            &mut state,
+            &self.loop_id,
+            &message_ids,
+            &question.choices,
+        )?;
 
         debug!(
             loop_id = %self.loop_id,
@@ -472,10 +631,7 @@ impl TelegramService {
                 )];
 
                 if let Some(hat) = &ctx.current_hat {
-                    lines.push(format!(
-                        "Hat: <code>{}</code>",
-                        crate::bot::escape_html(hat)
-                    ));
+                    lines.push(format!("Hat: <code>{}</code>", robot::escape_html(hat)));
                 }
 
                 if ctx.open_tasks > 0 || ctx.closed_tasks > 0 {
@@ -499,6 +655,38 @@ impl TelegramService {
         self.send_with_retry(chat_id, &msg)
     }
 
+    /// Send a loop lifecycle notification via Telegram.
+    ///
+    /// Skips (returning `Ok(0)`) when no chat ID is configured or when the
+    /// hourly notification limit has been reached.
+    pub fn send_notification(&self, notification: &LifecycleNotification) -> TelegramResult<i32> {
+        let mut state = self.state_manager.load_or_default()?;
+        let Some(chat_id) = state.chat_id else {
+            debug!(
+                loop_id = %self.loop_id,
+                "No chat ID configured — skipping lifecycle notification"
+            );
+            return Ok(0);
+        };
+
+        if !allow_notification(
+            &mut state.recent_notifications,
+            Utc::now(),
+            self.notifications_per_hour,
+        ) {
+            warn!(
+                loop_id = %self.loop_id,
+                limit = self.notifications_per_hour,
+                "Lifecycle notification rate limit reached — skipping"
+            );
+            return Ok(0);
+        }
+        self.state_manager.save(&state)?;
+
+        let msg = format_notification(&self.loop_id, notification);
+        self.send_with_retry(chat_id, &msg)
+    }
+
     /// Send a document (file) to the human via Telegram.
     ///
     /// Loads the chat ID from state and sends the file at `file_path` with an
@@ -557,6 +745,34 @@ impl TelegramService {
         )
     }
 
+    /// Attempt to send a message with choice buttons, with exponential
+    /// backoff retries.
+    fn send_choices_with_retry(
+        &self,
+        chat_id: i64,
+        payload: &str,
+        choices: &[String],
+    ) -> TelegramResult<i32> {
+        use crate::bot::BotApi;
+
+        let handle = tokio::runtime::Handle::try_current().map_err(|_| TelegramError::Send {
+            attempts: 0,
+            reason: "no tokio runtime available for sending".to_string(),
+        })?;
+
+        retry_with_backoff(
+            |_attempt| {
+                tokio::task::block_in_place(|| {
+                    handle.block_on(
+                        self.bot
+                            .send_message_with_choices(chat_id, payload, choices),
+                    )
+                })
+            },
+            |delay| std::thread::sleep(delay),
+        )
+    }
+
     /// Attempt to send a document with exponential backoff retries.
     fn send_document_with_retry(
         &self,
@@ -605,25 +821,19 @@ impl TelegramService {
         )
     }
 
-    /// Poll the events file for a `human.response` event, blocking until one
-    /// arrives or the configured timeout expires.
+    /// Wait for a `human.response` event in `events_path` until one arrives
+    /// or the configured timeout expires.
     ///
-    /// Polls the given `events_path` every second for new lines containing
-    /// `"human.response"`. On response, removes the pending question and
-    /// returns the response message. On timeout, removes the pending question
-    /// and returns `None`.
-    pub fn wait_for_response(&self, events_path: &Path) -> TelegramResult<Option<String>> {
-        let timeout = Duration::from_secs(self.timeout_secs);
-        let poll_interval = Duration::from_millis(250);
-        let deadline = Instant::now() + timeout;
-
-        // Track file position to only read new lines
-        let initial_pos = if events_path.exists() {
-            std::fs::metadata(events_path).map(|m| m.len()).unwrap_or(0)
-        } else {
-            0
-        };
-        let mut file_pos = initial_pos;
+    /// See [`robot::wait_for_response`]. A response that replies to this
+    /// loop's question is preferred over others that arrived at the same
+    /// time. The pending question is removed either way; returns `None` on
+    /// timeout or shutdown.
+    pub async fn wait_for_response(&self, events_path: &Path) -> TelegramResult<Option<String>> {
+        let question = self
+            .state_manager
+            .load_or_default()
+            .ok()
+            .and_then(|state| state.pending_questions.get(&self.loop_id).cloned());
 
         info!(
             loop_id = %self.loop_id,
@@ -632,112 +842,79 @@ impl TelegramService {
             "Waiting for human.response"
         );
 
-        loop {
-            if Instant::now() >= deadline {
-                warn!(
-                    loop_id = %self.loop_id,
-                    timeout_secs = self.timeout_secs,
-                    "Timed out waiting for human.response"
-                );
-
-                // Remove pending question on timeout
-                if let Ok(mut state) = self.state_manager.load_or_default() {
-                    let _ = self
-                        .state_manager
-                        .remove_pending_question(&mut state, &self.loop_id);
-                }
-
-                return Ok(None);
-            }
-
-            // Check if we've been interrupted (Ctrl+C / SIGTERM / SIGHUP)
-            if self.shutdown.load(Ordering::Relaxed) {
-                info!(loop_id = %self.loop_id, "Interrupted while waiting for human.response");
-                if let Ok(mut state) = self.state_manager.load_or_default() {
-                    let _ = self
-                        .state_manager
-                        .remove_pending_question(&mut state, &self.loop_id);
-                }
-                return Ok(None);
-            }
-
-            // Read new lines from the events file
-            if let Some(response) = Self::check_for_response(events_path, &mut file_pos)? {
-                info!(
-                    loop_id = %self.loop_id,
-                    "Received human.response: {}",
-                    response
-                );
-
-                // Remove pending question on response
-                if let Ok(mut state) = self.state_manager.load_or_default() {
-                    let _ = self
-                        .state_manager
-                        .remove_pending_question(&mut state, &self.loop_id);
-                }
-
-                return Ok(Some(response));
-            }
-
-            std::thread::sleep(poll_interval);
-        }
-    }
-
-    /// Check the events file for a `human.response` event starting from
-    /// `file_pos`. Updates `file_pos` to the new end of file.
-    fn check_for_response(
-        events_path: &Path,
-        file_pos: &mut u64,
-    ) -> TelegramResult<Option<String>> {
-        use std::io::{BufRead, BufReader, Seek, SeekFrom};
+        let response = robot::wait_for_response(
+            events_path,
+            question.as_ref().map(|q| q as &dyn QuestionThread),
+            Duration::from_secs(self.timeout_secs),
+            &self.shutdown,
+        )
+        .await?;
 
-        if !events_path.exists() {
-            return Ok(None);
+        // The question is settled either way
+        if let Ok(mut state) = self.state_manager.load_or_default() {
+            let _ = self
+                .state_manager
+                .remove_pending_question(&mut state, &self.loop_id);
         }
 
-        let mut file = std::fs::File::open(events_path)?;
-        file.seek(SeekFrom::Start(*file_pos))?;
-
-        let reader = BufReader::new(file);
-        for line in reader.lines() {
-            let line = line?;
-            let line_bytes = line.len() as u64 + 1; // +1 for newline
-            *file_pos += line_bytes;
-
-            if line.trim().is_empty() {
-                continue;
-            }
+        Ok(response)
+    }
 
-            // Try to parse as JSON event
-            if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line)
-                && event.get("topic").and_then(|t| t.as_str()) == Some("human.response")
-            {
-                let message = event
-                    .get("payload")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                return Ok(Some(message));
-            }
+    /// Blocking wrapper around [`wait_for_response`](Self::wait_for_response)
+    /// for the synchronous event loop.
+    ///
+    /// Inside a multi-threaded tokio runtime, uses `block_in_place` so other
+    /// tasks keep running on the remaining workers. Outside any runtime, a
+    /// temporary current-thread runtime drives the wait.
+    pub fn wait_for_response_blocking(&self, events_path: &Path) -> TelegramResult<Option<String>> {
+        robot::block_on(self.wait_for_response(events_path))
+            .map_err(|e| TelegramError::Receive(e.to_string()))?
+    }
+}
 
-            // Also check pipe-separated format (written by MessageHandler)
-            if line.contains("EVENT: human.response") {
-                // Extract message from pipe-separated format:
-                // EVENT: human.response | message: "..." | timestamp: "..."
-                let message = line
-                    .split('|')
-                    .find(|part| part.trim().starts_with("message:"))
-                    .and_then(|part| {
-                        let value = part.trim().strip_prefix("message:")?;
-                        let trimmed = value.trim().trim_matches('"');
-                        Some(trimmed.to_string())
-                    })
-                    .unwrap_or_default();
-                return Ok(Some(message));
-            }
+/// Formats a lifecycle notification as a Telegram HTML message.
+fn format_notification(loop_id: &str, notification: &LifecycleNotification) -> String {
+    use ralph_core::robot::escape_html;
+
+    let loop_id = escape_html(loop_id);
+    match notification {
+        LifecycleNotification::Started {
+            backend,
+            prompt_summary,
+        } => format!(
+            "🚀 Loop <code>{}</code> started on <b>{}</b>\n{}",
+            loop_id,
+            escape_html(backend),
+            escape_html(prompt_summary)
+        ),
+        LifecycleNotification::Terminated {
+            reason,
+            success,
+            iterations,
+            elapsed,
+        } => {
+            let emoji = if *success { "✅" } else { "🛑" };
+            let secs = elapsed.as_secs();
+            let elapsed_str = if secs >= 60 {
+                format!("{}m {}s", secs / 60, secs % 60)
+            } else {
+                format!("{}s", secs)
+            };
+            format!(
+                "{} Loop <code>{}</code> ended: <b>{}</b>\n{} iteration{} in <code>{}</code>",
+                emoji,
+                loop_id,
+                escape_html(reason),
+                iterations,
+                if *iterations == 1 { "" } else { "s" },
+                elapsed_str
+            )
         }
-
-        Ok(None)
+        LifecycleNotification::PreflightFailed { detail } => format!(
+            "⚠️ Loop <code>{}</code> did not start — preflight failed\n{}",
+            loop_id,
+            escape_html(detail)
+        ),
     }
 }
 
@@ -747,7 +924,7 @@ impl ralph_proto::RobotService for TelegramService {
     }
 
     fn wait_for_response(&self, events_path: &Path) -> anyhow::Result<Option<String>> {
-        Ok(TelegramService::wait_for_response(self, events_path)?)
+        Ok(self.wait_for_response_blocking(events_path)?)
     }
 
     fn send_checkin(
@@ -771,6 +948,10 @@ impl ralph_proto::RobotService for TelegramService {
         )?)
     }
 
+    fn send_notification(&self, notification: &LifecycleNotification) -> anyhow::Result<i32> {
+        Ok(TelegramService::send_notification(self, notification)?)
+    }
+
     fn timeout_secs(&self) -> u64 {
         self.timeout_secs
     }
@@ -797,9 +978,59 @@ impl fmt::Debug for TelegramService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::PendingQuestion;
     use std::io::Write;
+    use std::time::Instant;
     use tempfile::TempDir;
 
+    #[test]
+    fn format_notification_covers_lifecycle() {
+        let started = format_notification(
+            "main",
+            &LifecycleNotification::Started {
+                backend: "claude".to_string(),
+                prompt_summary: "Fix <the> build".to_string(),
+            },
+        );
+        assert!(started.contains("<code>main</code>"));
+        assert!(started.contains("claude"));
+        assert!(started.contains("Fix &lt;the&gt; build"));
+
+        let ended = format_notification(
+            "main",
+            &LifecycleNotification::Terminated {
+                reason: "max_iterations".to_string(),
+                success: false,
+                iterations: 3,
+                elapsed: Duration::from_secs(125),
+            },
+        );
+        assert!(ended.contains("🛑"));
+        assert!(ended.contains("max_iterations"));
+        assert!(ended.contains("3 iterations"));
+        assert!(ended.contains("2m 5s"));
+
+        let preflight = format_notification(
+            "main",
+            &LifecycleNotification::PreflightFailed {
+                detail: "1 failure".to_string(),
+            },
+        );
+        assert!(preflight.contains("preflight failed"));
+    }
+
+    #[test]
+    fn send_notification_without_chat_id_is_skipped() {
+        let dir = TempDir::new().unwrap();
+        let service = test_service(&dir);
+
+        let result = service.send_notification(&LifecycleNotification::PreflightFailed {
+            detail: "x".to_string(),
+        });
+
+        assert_eq!(result.unwrap(), 0);
+    }
+
     fn test_service(dir: &TempDir) -> TelegramService {
         TelegramService::new(
             dir.path().to_path_buf(),
@@ -851,6 +1082,7 @@ mod tests {
         .unwrap();
         let masked = service.bot_token_masked();
         assert_eq!(masked, "abcd...5678");
+        assert_eq!(mask_token("short"), "****");
     }
 
     #[test]
@@ -892,127 +1124,258 @@ mod tests {
     }
 
     #[test]
-    fn check_for_response_json_format() {
+    fn check_for_response_prefers_reply_to_question() {
         let dir = TempDir::new().unwrap();
         let events_path = dir.path().join("events.jsonl");
-
-        // Write a non-response event first
-        let mut file = std::fs::File::create(&events_path).unwrap();
-        writeln!(
-            file,
-            r#"{{"topic":"build.done","payload":"tests: pass, lint: pass, typecheck: pass, audit: pass, coverage: pass","ts":"2026-01-30T00:00:00Z"}}"#
-        )
-        .unwrap();
-        // Write a human.response event
-        writeln!(
-            file,
-            r#"{{"topic":"human.response","payload":"Use async","ts":"2026-01-30T00:01:00Z"}}"#
+        std::fs::write(
+            &events_path,
+            concat!(
+                r#"{"topic":"human.response","payload":"plain","ts":"2026-01-30T00:00:00Z"}"#,
+                "\n",
+                r#"{"topic":"human.response","payload":"other","reply_to":7,"ts":"2026-01-30T00:01:00Z"}"#,
+                "\n",
+                r#"{"topic":"human.response","payload":"mine","reply_to":42,"ts":"2026-01-30T00:02:00Z"}"#,
+                "\n",
+            ),
         )
         .unwrap();
-        file.flush().unwrap();
+        let question = PendingQuestion {
+            asked_at: Utc::now(),
+            message_id: 42,
+            earlier_message_ids: Vec::new(),
+            choices: Vec::new(),
+        };
+
+        let mut pos = 0;
+        let result = robot::check_for_response(&events_path, &mut pos, Some(&question)).unwrap();
+        assert_eq!(result, Some("mine".to_string()));
 
+        // Without a correlated reply, the first response still counts
+        let unrelated = PendingQuestion {
+            message_id: 99,
+            ..question
+        };
         let mut pos = 0;
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, Some("Use async".to_string()));
+        let result = robot::check_for_response(&events_path, &mut pos, Some(&unrelated)).unwrap();
+        assert_eq!(result, Some("plain".to_string()));
     }
 
-    #[test]
-    fn check_for_response_pipe_format() {
-        let dir = TempDir::new().unwrap();
-        let events_path = dir.path().join("events.jsonl");
+    /// Minimal stand-in for the Telegram Bot API.
+    ///
+    /// Answers each request with the next canned response (repeating the
+    /// last one) and records the request bodies.
+    struct MockBotApi {
+        url: reqwest::Url,
+        bodies: Arc<std::sync::Mutex<Vec<String>>>,
+    }
 
-        let mut file = std::fs::File::create(&events_path).unwrap();
-        writeln!(
-            file,
-            r#"EVENT: human.response | message: "Use sync" | timestamp: "2026-01-30T00:01:00Z""#
-        )
-        .unwrap();
-        file.flush().unwrap();
+    impl MockBotApi {
+        fn start(responses: Vec<String>) -> Self {
+            use std::io::{BufRead, BufReader, Read};
 
-        let mut pos = 0;
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, Some("Use sync".to_string()));
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap())
+                .parse()
+                .unwrap();
+            let bodies = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let recorded = bodies.clone();
+
+            std::thread::spawn(move || {
+                for (i, stream) in listener.incoming().enumerate() {
+                    let Ok(mut stream) = stream else { break };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut content_length = 0;
+                    loop {
+                        let mut header = String::new();
+                        if reader.read_line(&mut header).unwrap_or(0) == 0 || header == "\r\n" {
+                            break;
+                        }
+                        if let Some((name, value)) = header.split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push(String::from_utf8_lossy(&body).into_owned());
+
+                    let response = &responses[i.min(responses.len() - 1)];
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    );
+                }
+            });
+
+            Self { url, bodies }
+        }
+
+        fn message(message_id: i32) -> String {
+            format!(
+                r#"{{"ok":true,"result":{{"message_id":{},"date":1769731200,"chat":{{"id":123,"type":"private","first_name":"Ralph"}},"text":"ok"}}}}"#,
+                message_id
+            )
+        }
+
+        fn bodies(&self) -> Vec<String> {
+            self.bodies.lock().unwrap().clone()
+        }
     }
 
-    #[test]
-    fn check_for_response_skips_non_response_events() {
+    fn service_with_mock_api(dir: &TempDir, api: &MockBotApi) -> TelegramService {
+        let service = test_service(dir).with_api_url(api.url.clone());
+        let mut state = service.state_manager().load_or_default().unwrap();
+        state.chat_id = Some(123);
+        service.state_manager().save(&state).unwrap();
+        service
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_question_records_telegram_message_id() {
         let dir = TempDir::new().unwrap();
-        let events_path = dir.path().join("events.jsonl");
+        let api = MockBotApi::start(vec![MockBotApi::message(4711)]);
+        let service = service_with_mock_api(&dir, &api);
 
-        let mut file = std::fs::File::create(&events_path).unwrap();
-        writeln!(
-            file,
-            r#"{{"topic":"build.done","payload":"done","ts":"2026-01-30T00:00:00Z"}}"#
-        )
-        .unwrap();
-        writeln!(
-            file,
-            r#"{{"topic":"human.guidance","payload":"check errors","ts":"2026-01-30T00:01:00Z"}}"#
-        )
-        .unwrap();
-        file.flush().unwrap();
+        let msg_id = service.send_question("Which DB to use?").unwrap();
 
-        let mut pos = 0;
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, None);
+        assert_eq!(msg_id, 4711);
+        let state = service.state_manager().load_or_default().unwrap();
+        assert_eq!(state.pending_questions["main"].message_id, 4711);
+        let bodies = api.bodies();
+        assert_eq!(bodies.len(), 1);
+        let request: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(request["chat_id"], 123);
+        assert_eq!(request["text"], "Which DB to use?");
     }
 
-    #[test]
-    fn check_for_response_missing_file() {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_question_chunks_long_payloads() {
         let dir = TempDir::new().unwrap();
-        let events_path = dir.path().join("does-not-exist.jsonl");
-
-        let mut pos = 0;
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, None);
+        let api = MockBotApi::start(vec![MockBotApi::message(10), MockBotApi::message(11)]);
+        let service = service_with_mock_api(&dir, &api);
+        let line = "x".repeat(99);
+        let payload = vec![line.as_str(); 60].join("\n");
+
+        let msg_id = service.send_question(&payload).unwrap();
+
+        assert_eq!(msg_id, 11);
+        let bodies = api.bodies();
+        assert_eq!(bodies.len(), 2);
+        for body in &bodies {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let text = request["text"].as_str().unwrap();
+            assert!(text.chars().count() <= TELEGRAM_MESSAGE_LIMIT);
+        }
+        let state = service.state_manager().load_or_default().unwrap();
+        assert_eq!(
+            state.pending_questions["main"].earlier_message_ids,
+            vec![10]
+        );
+        assert_eq!(
+            service.state_manager().get_loop_for_reply(&state, 10),
+            Some("main".to_string())
+        );
     }
 
-    #[test]
-    fn check_for_response_tracks_position() {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_question_attaches_choice_buttons() {
         let dir = TempDir::new().unwrap();
-        let events_path = dir.path().join("events.jsonl");
+        let api = MockBotApi::start(vec![MockBotApi::message(7), MockBotApi::message(8)]);
+        let service = service_with_mock_api(&dir, &api);
 
-        // Write one event
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(&events_path)
+        service
+            .send_question("Should I run the migration?")
+            .unwrap();
+        service
+            .send_question("Which database should I use?")
             .unwrap();
-        writeln!(
-            file,
-            r#"{{"topic":"build.done","payload":"done","ts":"2026-01-30T00:00:00Z"}}"#
-        )
-        .unwrap();
-        file.flush().unwrap();
 
-        let mut pos = 0;
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, None);
-        assert!(pos > 0, "position should advance after reading");
+        let bodies = api.bodies();
+        let yes_no: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+        let buttons = &yes_no["reply_markup"]["inline_keyboard"][0];
+        assert_eq!(buttons[0]["text"], "Yes");
+        assert_eq!(
+            buttons[1]["callback_data"],
+            crate::choices::callback_data(1)
+        );
+        let free_text: serde_json::Value = serde_json::from_str(&bodies[1]).unwrap();
+        assert!(free_text.get("reply_markup").is_none());
+
+        let state = service.state_manager().load_or_default().unwrap();
+        assert!(state.pending_questions["main"].choices.is_empty());
+    }
 
-        let pos_after_first = pos;
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_question_uses_json_choices() {
+        let dir = TempDir::new().unwrap();
+        let api = MockBotApi::start(vec![MockBotApi::message(9)]);
+        let service = service_with_mock_api(&dir, &api);
 
-        // Append a human.response
-        let mut file = std::fs::OpenOptions::new()
-            .append(true)
-            .open(&events_path)
+        service
+            .send_question(r#"{"question":"Which DB?","choices":["Postgres","SQLite","Both"]}"#)
             .unwrap();
-        writeln!(
-            file,
-            r#"{{"topic":"human.response","payload":"yes","ts":"2026-01-30T00:02:00Z"}}"#
-        )
-        .unwrap();
-        file.flush().unwrap();
 
-        // Should find the response starting from where we left off
-        let result = TelegramService::check_for_response(&events_path, &mut pos).unwrap();
-        assert_eq!(result, Some("yes".to_string()));
-        assert!(pos > pos_after_first, "position should advance further");
+        let request: serde_json::Value = serde_json::from_str(&api.bodies()[0]).unwrap();
+        assert_eq!(request["text"], "Which DB?");
+        let rows = request["reply_markup"]["inline_keyboard"]
+            .as_array()
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][0]["text"], "Both");
+        let state = service.state_manager().load_or_default().unwrap();
+        assert_eq!(
+            state.pending_questions["main"].choices,
+            vec!["Postgres", "SQLite", "Both"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_question_surfaces_api_error_without_retrying() {
+        let dir = TempDir::new().unwrap();
+        let api = MockBotApi::start(vec![
+            r#"{"ok":false,"error_code":400,"description":"Bad Request: chat not found"}"#
+                .to_string(),
+        ]);
+        let service = service_with_mock_api(&dir, &api);
+
+        let err = service.send_question("Anyone there?").unwrap_err();
+
+        assert!(
+            matches!(&err, TelegramError::Api(description) if description.contains("chat not found")),
+            "unexpected error: {err}"
+        );
+        assert_eq!(api.bodies().len(), 1);
+        let state = service.state_manager().load_or_default().unwrap();
+        assert!(!state.pending_questions.contains_key("main"));
     }
 
     #[test]
-    fn wait_for_response_returns_on_response() {
+    fn retry_with_backoff_does_not_retry_api_errors() {
+        let mut attempts = 0;
+
+        let result = retry_with_backoff(
+            |_attempt| {
+                attempts += 1;
+                Err(TelegramError::Api(
+                    "Forbidden: bot was blocked by the user".to_string(),
+                ))
+            },
+            |_delay| panic!("API errors should not be retried"),
+        );
+
+        assert!(matches!(result, Err(TelegramError::Api(_))));
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_response_returns_on_response() {
         let dir = TempDir::new().unwrap();
         let service = TelegramService::new(
             dir.path().to_path_buf(),
@@ -1029,10 +1392,10 @@ mod tests {
         // Store a pending question first
         service.send_question("Which plan?").unwrap();
 
-        // Spawn a thread to write the response after a brief delay
+        // Spawn a task to write the response after a brief delay
         let writer_path = events_path.clone();
-        let writer = std::thread::spawn(move || {
-            std::thread::sleep(Duration::from_millis(200));
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
             let mut file = std::fs::OpenOptions::new()
                 .append(true)
                 .open(&writer_path)
@@ -1045,8 +1408,8 @@ mod tests {
             file.flush().unwrap();
         });
 
-        let result = service.wait_for_response(&events_path).unwrap();
-        writer.join().unwrap();
+        let result = service.wait_for_response(&events_path).await.unwrap();
+        writer.await.unwrap();
 
         assert_eq!(result, Some("Go with plan A".to_string()));
 
@@ -1058,13 +1421,13 @@ mod tests {
         );
     }
 
-    #[test]
-    fn wait_for_response_returns_none_on_timeout() {
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_response_returns_none_on_timeout() {
         let dir = TempDir::new().unwrap();
         let service = TelegramService::new(
             dir.path().to_path_buf(),
             Some("token".to_string()),
-            1, // 1 second timeout
+            300, // paused time makes the full timeout instant
             "main".to_string(),
         )
         .unwrap();
@@ -1076,8 +1439,10 @@ mod tests {
         // Store a pending question
         service.send_question("Will this timeout?").unwrap();
 
-        let result = service.wait_for_response(&events_path).unwrap();
+        let start = tokio::time::Instant::now();
+        let result = service.wait_for_response(&events_path).await.unwrap();
         assert_eq!(result, None, "should return None on timeout");
+        assert!(start.elapsed() >= Duration::from_mins(5));
 
         // Pending question should be removed even on timeout
         let state = service.state_manager().load_or_default().unwrap();
@@ -1258,8 +1623,8 @@ mod tests {
         assert!((ctx.cumulative_cost - 1.2345).abs() < f64::EPSILON);
     }
 
-    #[test]
-    fn wait_for_response_returns_none_on_shutdown() {
+    #[tokio::test]
+    async fn wait_for_response_returns_none_on_shutdown() {
         let dir = TempDir::new().unwrap();
         let service = TelegramService::new(
             dir.path().to_path_buf(),
@@ -1276,7 +1641,7 @@ mod tests {
         service.shutdown_flag().store(true, Ordering::Relaxed);
 
         let start = Instant::now();
-        let result = service.wait_for_response(&events_path).unwrap();
+        let result = service.wait_for_response(&events_path).await.unwrap();
         let elapsed = start.elapsed();
 
         assert_eq!(result, None, "should return None when shutdown flag is set");
@@ -1286,4 +1651,56 @@ mod tests {
             elapsed
         );
     }
+
+    #[tokio::test]
+    async fn wait_for_response_wakes_on_file_change() {
+        let dir = TempDir::new().unwrap();
+        let service = TelegramService::new(
+            dir.path().to_path_buf(),
+            Some("token".to_string()),
+            60,
+            "main".to_string(),
+        )
+        .unwrap();
+        let events_path = dir.path().join("events.jsonl");
+        std::fs::File::create(&events_path).unwrap();
+
+        let writer_path = events_path.clone();
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&writer_path)
+                .unwrap();
+            writeln!(
+                file,
+                r#"{{"topic":"human.response","payload":"now","ts":"2026-01-30T00:00:00Z"}}"#
+            )
+            .unwrap();
+        });
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            service.wait_for_response(&events_path),
+        )
+        .await
+        .expect("response should be picked up well before the timeout")
+        .unwrap();
+        writer.await.unwrap();
+
+        assert_eq!(result, Some("now".to_string()));
+    }
+
+    #[test]
+    fn wait_for_response_blocking_works_without_runtime() {
+        let dir = TempDir::new().unwrap();
+        let service = test_service(&dir);
+        let events_path = dir.path().join("events.jsonl");
+        std::fs::write(&events_path, "").unwrap();
+        service.shutdown_flag().store(true, Ordering::Relaxed);
+
+        let result = service.wait_for_response_blocking(&events_path).unwrap();
+
+        assert_eq!(result, None);
+    }
 }
@@ -2,16 +2,26 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use ralph_core::robot::QuestionThread;
 use serde::{Deserialize, Serialize};
 
 use crate::error::TelegramResult;
 
 /// Persistent state for the Telegram bot, stored at `.ralph/telegram-state.json`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TelegramState {
     /// The chat ID for the human operator (auto-detected from first message).
+    ///
+    /// Questions and notifications are sent here. This chat is always
+    /// allowed to steer the bot.
     pub chat_id: Option<i64>,
 
+    /// Further chats allowed to steer the bot, besides `chat_id`.
+    ///
+    /// Messages from any other chat are rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_chat_ids: Vec<i64>,
+
     /// Timestamp of the last message seen.
     pub last_seen: Option<DateTime<Utc>>,
 
@@ -22,6 +32,60 @@ pub struct TelegramState {
     /// Pending questions keyed by loop ID, tracking which message awaits a reply.
     #[serde(default)]
     pub pending_questions: HashMap<String, PendingQuestion>,
+
+    /// When recent lifecycle notifications were sent, for rate limiting
+    /// across loop restarts.
+    #[serde(default)]
+    pub recent_notifications: Vec<DateTime<Utc>>,
+}
+
+impl TelegramState {
+    /// Returns true if messages from `chat_id` may steer the bot.
+    pub fn is_chat_allowed(&self, chat_id: i64) -> bool {
+        self.chat_id == Some(chat_id) || self.allowed_chat_ids.contains(&chat_id)
+    }
+
+    /// All allowed chats, primary chat first.
+    pub fn allowed_chats(&self) -> Vec<i64> {
+        self.chat_id
+            .into_iter()
+            .chain(self.allowed_chat_ids.iter().copied())
+            .collect()
+    }
+
+    /// Adds `chat_id` to the allowlist. The first allowed chat becomes the
+    /// primary chat.
+    ///
+    /// Returns false if the chat was already allowed.
+    pub fn allow_chat(&mut self, chat_id: i64) -> bool {
+        if self.is_chat_allowed(chat_id) {
+            return false;
+        }
+        if self.chat_id.is_none() {
+            self.chat_id = Some(chat_id);
+        } else {
+            self.allowed_chat_ids.push(chat_id);
+        }
+        true
+    }
+
+    /// Removes `chat_id` from the allowlist. Denying the primary chat
+    /// promotes the next allowed chat in its place.
+    ///
+    /// Returns false if the chat wasn't allowed.
+    pub fn deny_chat(&mut self, chat_id: i64) -> bool {
+        if self.chat_id == Some(chat_id) {
+            self.chat_id = if self.allowed_chat_ids.is_empty() {
+                None
+            } else {
+                Some(self.allowed_chat_ids.remove(0))
+            };
+            return true;
+        }
+        let before = self.allowed_chat_ids.len();
+        self.allowed_chat_ids.retain(|&id| id != chat_id);
+        self.allowed_chat_ids.len() != before
+    }
 }
 
 /// A question sent to the human that is awaiting a response.
@@ -31,7 +95,38 @@ pub struct PendingQuestion {
     pub asked_at: DateTime<Utc>,
 
     /// The Telegram message ID, used to match reply-to routing.
+    ///
+    /// For questions split across several messages, this is the last one.
     pub message_id: i32,
+
+    /// IDs of the earlier messages of a question that was split to fit
+    /// Telegram's length limit. Replies to any of them are routed too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub earlier_message_ids: Vec<i32>,
+
+    /// Labels of the inline buttons offered with the question, in button
+    /// order. Empty for free-text questions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+}
+
+impl PendingQuestion {
+    /// Returns true if `message_id` is one of this question's messages.
+    pub fn has_message(&self, message_id: i32) -> bool {
+        self.message_id == message_id || self.earlier_message_ids.contains(&message_id)
+    }
+}
+
+impl QuestionThread for PendingQuestion {
+    /// Replies are `human.response` events whose `reply_to` is one of the
+    /// question's messages.
+    fn is_reply(&self, event: &serde_json::Value) -> bool {
+        event
+            .get("reply_to")
+            .and_then(serde_json::Value::as_i64)
+            .and_then(|id| i32::try_from(id).ok())
+            .is_some_and(|id| self.has_message(id))
+    }
 }
 
 /// Manages persistence of Telegram bot state to disk.
@@ -73,9 +168,11 @@ impl StateManager {
     pub fn load_or_default(&self) -> TelegramResult<TelegramState> {
         Ok(self.load()?.unwrap_or_else(|| TelegramState {
             chat_id: None,
+            allowed_chat_ids: Vec::new(),
             last_seen: None,
             last_update_id: None,
             pending_questions: HashMap::new(),
+            recent_notifications: Vec::new(),
         }))
     }
 
@@ -86,11 +183,26 @@ impl StateManager {
         loop_id: &str,
         message_id: i32,
     ) -> TelegramResult<()> {
+        self.add_split_pending_question(state, loop_id, &[message_id], &[])
+    }
+
+    /// Add a pending question sent as several messages (in send order),
+    /// along with the choices offered as buttons on the last message.
+    pub fn add_split_pending_question(
+        &self,
+        state: &mut TelegramState,
+        loop_id: &str,
+        message_ids: &[i32],
+        choices: &[String],
+    ) -> TelegramResult<()> {
+        let (message_id, earlier) = message_ids.split_last().unwrap_or((&0, &[]));
         state.pending_questions.insert(
             loop_id.to_string(),
             PendingQuestion {
                 asked_at: Utc::now(),
-                message_id,
+                message_id: *message_id,
+                earlier_message_ids: earlier.to_vec(),
+                choices: choices.to_vec(),
             },
         );
         self.save(state)
@@ -115,7 +227,7 @@ impl StateManager {
         state
             .pending_questions
             .iter()
-            .find(|(_, q)| q.message_id == reply_message_id)
+            .find(|(_, q)| q.has_message(reply_message_id))
             .map(|(loop_id, _)| loop_id.clone())
     }
 
@@ -147,14 +259,17 @@ mod tests {
         let (mgr, _dir) = test_manager();
         let state = TelegramState {
             chat_id: Some(123_456),
+            allowed_chat_ids: vec![789],
             last_seen: Some(Utc::now()),
             last_update_id: Some(101),
             pending_questions: HashMap::new(),
+            recent_notifications: Vec::new(),
         };
         mgr.save(&state).unwrap();
 
         let loaded = mgr.load().unwrap().unwrap();
         assert_eq!(loaded.chat_id, Some(123_456));
+        assert_eq!(loaded.allowed_chat_ids, vec![789]);
         assert_eq!(loaded.last_update_id, Some(101));
     }
 
@@ -194,4 +309,61 @@ mod tests {
         );
         assert_eq!(mgr.get_loop_for_reply(&state, 99), None);
     }
+
+    #[test]
+    fn reply_routing_matches_any_chunk_of_split_question() {
+        let (mgr, _dir) = test_manager();
+        let mut state = mgr.load_or_default().unwrap();
+
+        mgr.add_split_pending_question(&mut state, "main", &[10, 11, 12], &[])
+            .unwrap();
+
+        let question = &state.pending_questions["main"];
+        assert_eq!(question.message_id, 12);
+        assert_eq!(question.earlier_message_ids, vec![10, 11]);
+        assert_eq!(mgr.get_loop_for_reply(&state, 10), Some("main".to_string()));
+        assert_eq!(mgr.get_loop_for_reply(&state, 12), Some("main".to_string()));
+        assert_eq!(mgr.get_loop_for_reply(&state, 13), None);
+    }
+
+    #[test]
+    fn legacy_state_without_allowlist_loads() {
+        let (mgr, _dir) = test_manager();
+        std::fs::write(
+            mgr.path(),
+            r#"{"chat_id": 42, "last_seen": null, "pending_questions": {}}"#,
+        )
+        .unwrap();
+
+        let state = mgr.load().unwrap().unwrap();
+        assert_eq!(state.allowed_chats(), vec![42]);
+        assert!(state.is_chat_allowed(42));
+        assert!(!state.is_chat_allowed(43));
+    }
+
+    #[test]
+    fn allow_and_deny_chats() {
+        let (mgr, _dir) = test_manager();
+        let mut state = mgr.load_or_default().unwrap();
+
+        assert!(state.allow_chat(1));
+        assert!(state.allow_chat(2));
+        assert!(state.allow_chat(3));
+        assert!(!state.allow_chat(2));
+        assert_eq!(state.chat_id, Some(1));
+        assert_eq!(state.allowed_chats(), vec![1, 2, 3]);
+
+        assert!(state.deny_chat(2));
+        assert!(!state.deny_chat(2));
+        assert_eq!(state.allowed_chats(), vec![1, 3]);
+
+        // Denying the primary chat promotes the next one
+        assert!(state.deny_chat(1));
+        assert_eq!(state.chat_id, Some(3));
+        assert!(state.allowed_chat_ids.is_empty());
+
+        assert!(state.deny_chat(3));
+        assert_eq!(state.chat_id, None);
+        assert!(state.allowed_chats().is_empty());
+    }
 }
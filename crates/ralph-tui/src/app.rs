@@ -73,7 +73,10 @@ pub fn dispatch_action(action: Action, state: &mut TuiState, viewport_height: us
             state.search_state.search_mode = true;
         }
         Action::SearchNext => {
-            state.next_match();
+            // While paused, `n` single-steps unless a search is active
+            if state.search_state.query.is_some() || !state.step_loop() {
+                state.next_match();
+            }
         }
         Action::SearchPrev => {
             state.prev_match();
@@ -84,6 +87,12 @@ pub fn dispatch_action(action: Action, state: &mut TuiState, viewport_height: us
         Action::GuidanceNow => {
             state.start_guidance(crate::state::GuidanceMode::Now);
         }
+        Action::TogglePause => {
+            state.toggle_pause();
+        }
+        Action::Resume => {
+            state.resume_loop();
+        }
         Action::None => {}
     }
     false
@@ -478,6 +487,30 @@ mod tests {
         assert_eq!(state.search_state.current_match, 1);
     }
 
+    #[test]
+    fn dispatch_action_pause_step_and_resume() {
+        use ralph_core::{PauseControl, PauseState};
+
+        let control = PauseControl::new();
+        let mut state = TuiState::new();
+        state.pause_control = Some(control.clone());
+
+        // n does nothing to the loop while it is running
+        dispatch_action(Action::SearchNext, &mut state, 10);
+        assert_eq!(control.state(), PauseState::Running);
+
+        dispatch_action(Action::TogglePause, &mut state, 10);
+        assert_eq!(control.state(), PauseState::Paused);
+        assert!(state.is_paused());
+
+        dispatch_action(Action::SearchNext, &mut state, 10);
+        assert_eq!(control.state(), PauseState::Step);
+
+        dispatch_action(Action::Resume, &mut state, 10);
+        assert_eq!(control.state(), PauseState::Running);
+        assert!(!state.is_paused());
+    }
+
     #[test]
     fn dispatch_action_search_prev_calls_prev_match() {
         let mut state = TuiState::new();
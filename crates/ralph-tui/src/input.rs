@@ -40,6 +40,10 @@ pub enum Action {
     GuidanceNext,
     /// Open guidance input for current iteration (urgent)
     GuidanceNow,
+    /// Pause the loop before the next iteration, or resume it
+    TogglePause,
+    /// Resume a paused loop
+    Resume,
     /// Key not mapped to any action
     None,
 }
@@ -55,8 +59,10 @@ pub enum Action {
 /// - `g`: Scroll to top
 /// - `G`: Scroll to bottom
 /// - `/`: Start search
-/// - `n`: Next search match
+/// - `n`: Next search match (single-step when paused)
 /// - `N`: Previous search match
+/// - `p`: Pause/resume the loop
+/// - `r`: Resume the loop
 /// - `?`: Show help
 /// - `Esc`: Dismiss help/cancel search
 pub fn map_key(key: KeyEvent) -> Action {
@@ -83,6 +89,10 @@ pub fn map_key(key: KeyEvent) -> Action {
         KeyCode::Char(':') => Action::GuidanceNext,
         KeyCode::Char('!') => Action::GuidanceNow,
 
+        // Pause control
+        KeyCode::Char('p') => Action::TogglePause,
+        KeyCode::Char('r') => Action::Resume,
+
         // Help
         KeyCode::Char('?') => Action::ShowHelp,
         KeyCode::Esc => Action::DismissHelp,
@@ -209,6 +219,14 @@ mod tests {
         assert_eq!(map_key(key), Action::GuidanceNow);
     }
 
+    #[test]
+    fn p_returns_toggle_pause_and_r_returns_resume() {
+        let key = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::TogglePause);
+        let key = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert_eq!(map_key(key), Action::Resume);
+    }
+
     // AC17: Unknown Key Returns None
     #[test]
     fn unknown_key_returns_none() {
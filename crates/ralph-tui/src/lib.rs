@@ -84,6 +84,18 @@ impl Tui {
         self
     }
 
+    /// Enables the pause (`p`), single-step (`n`), and resume (`r`) keys.
+    ///
+    /// The same control must be given to the orchestrator, which waits on
+    /// it between iterations.
+    #[must_use]
+    pub fn with_pause_control(self, control: ralph_core::PauseControl) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.pause_control = Some(control);
+        }
+        self
+    }
+
     /// Returns the shared state for external updates.
     pub fn state(&self) -> Arc<Mutex<TuiState>> {
         Arc::clone(&self.state)
@@ -120,7 +132,15 @@ impl Tui {
             .terminated_rx
             .expect("Termination signal not set - call with_termination_signal() first");
         let app = App::new(Arc::clone(&self.state), terminated_rx, self.interrupt_tx);
-        app.run().await
+        let result = app.run().await;
+
+        // Nothing can resume a paused loop once the TUI is gone
+        if let Ok(state) = self.state.lock()
+            && let Some(control) = &state.pause_control
+        {
+            control.resume();
+        }
+        result
     }
 }
 
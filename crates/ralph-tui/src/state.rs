@@ -1,5 +1,6 @@
 //! State management for the TUI.
 
+use ralph_core::{PauseControl, PauseState};
 use ralph_proto::{Event, HatId};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -199,6 +200,29 @@ pub struct TuiState {
     /// Brief flash message after attempting to send guidance.
     /// (mode, result, when)
     pub guidance_flash: Option<(GuidanceMode, GuidanceResult, Instant)>,
+
+    // ========================================================================
+    // Pause Control
+    // ========================================================================
+    /// Shared pause/step control for the loop (None when not wired up).
+    pub pause_control: Option<PauseControl>,
+
+    // ========================================================================
+    // Event Queue
+    // ========================================================================
+    /// Events waiting in the loop's queue at the start of the iteration.
+    pub queue_depth: usize,
+}
+
+/// Pause status shown in the header and footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseIndicator {
+    /// Pause requested; the current iteration is still running.
+    Pausing,
+    /// Loop is held between iterations.
+    Paused,
+    /// A single step was released and is running.
+    Stepping,
 }
 
 impl TuiState {
@@ -240,6 +264,9 @@ impl TuiState {
             guidance_next_queue: Arc::new(Mutex::new(Vec::new())),
             events_path: None,
             guidance_flash: None,
+            // Pause control
+            pause_control: None,
+            queue_depth: 0,
         }
     }
 
@@ -282,6 +309,9 @@ impl TuiState {
             guidance_next_queue: Arc::new(Mutex::new(Vec::new())),
             events_path: None,
             guidance_flash: None,
+            // Pause control
+            pause_control: None,
+            queue_depth: 0,
         }
     }
 
@@ -317,6 +347,7 @@ impl TuiState {
                 let saved_pending_backend = self.pending_backend.clone();
                 let saved_guidance_next_queue = Arc::clone(&self.guidance_next_queue);
                 let saved_events_path = self.events_path.clone();
+                let saved_pause_control = self.pause_control.take();
                 *self = Self::new();
                 self.hat_map = saved_hat_map;
                 self.loop_started = saved_loop_started; // Keep original timer
@@ -328,6 +359,7 @@ impl TuiState {
                 self.pending_backend = saved_pending_backend;
                 self.guidance_next_queue = saved_guidance_next_queue;
                 self.events_path = saved_events_path;
+                self.pause_control = saved_pause_control;
                 if let Some((hat_id, hat_display)) = custom_hat.clone() {
                     self.pending_hat = Some((hat_id, hat_display));
                 } else {
@@ -366,6 +398,10 @@ impl TuiState {
                 self.finish_latest_iteration();
                 self.freeze_loop_elapsed();
             }
+            "loop.paused" => {
+                // The previous iteration is over; stop its timer during the pause
+                self.finish_latest_iteration();
+            }
             "loop.terminate" => {
                 self.pending_hat = None;
                 self.loop_completed = true;
@@ -786,17 +822,9 @@ impl TuiState {
             Err(_) => return false,
         };
 
-        use std::io::Write;
-        let mut file = match std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-        {
-            Ok(f) => f,
-            Err(_) => return false,
-        };
-
-        file.write_all(line.as_bytes()).is_ok() && file.write_all(b"\n").is_ok()
+        ralph_core::EventHistory::new(path)
+            .append(&line, false)
+            .is_ok()
     }
 
     /// Returns true if guidance input is currently active.
@@ -813,6 +841,52 @@ impl TuiState {
         }
     }
 
+    // ========================================================================
+    // Pause Methods
+    // ========================================================================
+
+    /// Pauses the loop before its next iteration, or resumes it.
+    pub fn toggle_pause(&mut self) {
+        if let Some(control) = &self.pause_control {
+            control.toggle();
+        }
+    }
+
+    /// Resumes a paused loop.
+    pub fn resume_loop(&mut self) {
+        if let Some(control) = &self.pause_control {
+            control.resume();
+        }
+    }
+
+    /// Runs a single iteration of a paused loop.
+    ///
+    /// Returns false when the loop is not paused.
+    pub fn step_loop(&mut self) -> bool {
+        self.pause_control.as_ref().is_some_and(PauseControl::step)
+    }
+
+    /// Returns true if the loop is paused or a pause was requested.
+    pub fn is_paused(&self) -> bool {
+        self.pause_control
+            .as_ref()
+            .is_some_and(|control| control.state() != PauseState::Running)
+    }
+
+    /// Returns the pause status to display, if any.
+    pub fn pause_indicator(&self) -> Option<PauseIndicator> {
+        if self.loop_completed {
+            return None;
+        }
+        let control = self.pause_control.as_ref()?;
+        match control.state() {
+            PauseState::Running => None,
+            PauseState::Step => Some(PauseIndicator::Stepping),
+            PauseState::Paused if control.is_waiting() => Some(PauseIndicator::Paused),
+            PauseState::Paused => Some(PauseIndicator::Pausing),
+        }
+    }
+
     /// Returns active guidance flash (mode + result) if still within display window (2 seconds).
     pub fn active_guidance_flash(&self) -> Option<(GuidanceMode, GuidanceResult)> {
         self.guidance_flash.and_then(|(mode, result, when)| {
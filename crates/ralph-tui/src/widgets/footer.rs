@@ -130,16 +130,24 @@ impl Widget for Footer<'_> {
         };
         left_spans.push(Span::raw(elapsed_display));
 
-        let indicator_text = if self.state.loop_completed {
-            "■ DONE"
-        } else {
-            "◉ ACTIVE"
-        };
+        if self.state.queue_depth > 0 {
+            left_spans.push(Span::raw(" │ "));
+            left_spans.push(Span::styled(
+                format!("Queue: {}", self.state.queue_depth),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
 
-        let indicator_style = if self.state.loop_completed {
-            Style::default().fg(Color::Blue)
+        let (indicator_text, indicator_style) = if self.state.loop_completed {
+            ("■ DONE", Style::default().fg(Color::Blue))
         } else {
-            Style::default().fg(Color::Green)
+            match self.state.pause_indicator() {
+                Some(crate::state::PauseIndicator::Paused) => {
+                    ("‖ PAUSED", Style::default().fg(Color::Yellow))
+                }
+                Some(_) => ("‖ PAUSING", Style::default().fg(Color::Yellow)),
+                None => ("◉ ACTIVE", Style::default().fg(Color::Green)),
+            }
         };
 
         // Calculate left content width for layout
@@ -241,6 +249,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footer_shows_queue_depth_when_events_wait() {
+        let mut state = TuiState::new();
+        assert!(!render_to_string(&state).contains("Queue:"));
+
+        state.queue_depth = 3;
+        let text = render_to_string(&state);
+        assert!(
+            text.contains("Queue: 3"),
+            "should show queue depth, got: {}",
+            text
+        );
+    }
+
     #[test]
     fn footer_shows_elapsed_time() {
         // Given loop_started is set (simulating 2 minutes 30 seconds elapsed)
@@ -1,4 +1,4 @@
-use crate::state::TuiState;
+use crate::state::{PauseIndicator, TuiState};
 use ratatui::{
     style::{Color, Style},
     text::{Line, Span},
@@ -13,6 +13,7 @@ use ratatui::{
 // Priority levels (lower number = more important, always shown):
 // - Priority 1: Iteration counter [iter N/M] - always shown (TUI pagination)
 // - Priority 2: Mode indicator [LIVE]/[REVIEW] (▶/◀ compressed) - always shown
+// - Priority 2: Pause banner [PAUSED] ([P] compressed) - shown while paused
 // - Priority 3: Hat display, Scroll indicator - compressed at 50
 // - Priority 4: Iteration elapsed time MM:SS - hidden at 50
 // - Priority 5: Idle countdown - hidden at 40
@@ -76,9 +77,12 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
         spans.push(Span::raw(emoji.to_string()));
     }
 
-    // Priority 5: Idle countdown - hidden at WIDTH_MINIMAL and below
+    // Priority 5: Idle countdown - hidden at WIDTH_MINIMAL and below, and
+    // suspended while the loop is held between iterations
+    let pause = state.pause_indicator();
     if let Some(idle) = state.idle_timeout_remaining
         && width > WIDTH_MINIMAL
+        && pause != Some(PauseIndicator::Paused)
     {
         spans.push(Span::raw(format!(" | idle: {}s", idle.as_secs())));
     }
@@ -99,6 +103,23 @@ pub fn render(state: &TuiState, width: u16) -> Paragraph<'static> {
     };
     spans.push(mode);
 
+    // Priority 2: Pause banner - ALWAYS shown while paused (compressed at WIDTH_COMPRESS and below)
+    if let Some(pause) = pause {
+        let label = if width <= WIDTH_COMPRESS {
+            " [P]"
+        } else {
+            match pause {
+                PauseIndicator::Pausing => " [PAUSING]",
+                PauseIndicator::Paused => " [PAUSED]",
+                PauseIndicator::Stepping => " [STEP]",
+            }
+        };
+        spans.push(Span::styled(
+            label,
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+
     // Priority 3: Scroll indicator - compressed at WIDTH_COMPRESS and below
     if state.in_scroll_mode {
         if width > WIDTH_COMPRESS {
@@ -381,6 +402,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_shows_pause_banner_and_suspends_idle_countdown() {
+        let control = ralph_core::PauseControl::new();
+        let mut state = TuiState::new();
+        state.idle_timeout_remaining = Some(Duration::from_secs(25));
+        state.pause_control = Some(control.clone());
+
+        let text = render_to_string(&state);
+        assert!(!text.contains("PAUS"), "running loop has no banner: {text}");
+
+        control.pause();
+        let text = render_to_string(&state);
+        assert!(text.contains("[PAUSING]"), "got: {text}");
+        assert!(text.contains("idle: 25s"), "got: {text}");
+    }
+
     #[test]
     fn header_shows_scroll_indicator() {
         let mut state = TuiState::new();
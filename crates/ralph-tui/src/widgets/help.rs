@@ -73,6 +73,20 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::raw("      Send guidance (now, current iteration)"),
         ]),
         Line::from(""),
+        Line::from(Span::styled("Loop:", Style::default().fg(Color::Yellow))),
+        Line::from(vec![
+            Span::styled("  p", Style::default().fg(Color::Cyan)),
+            Span::raw("      Pause after this iteration / resume"),
+        ]),
+        Line::from(vec![
+            Span::styled("  n", Style::default().fg(Color::Cyan)),
+            Span::raw("      Run one iteration (while paused)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  r", Style::default().fg(Color::Cyan)),
+            Span::raw("      Resume"),
+        ]),
+        Line::from(""),
         Line::from(Span::styled("Other:", Style::default().fg(Color::Yellow))),
         Line::from(vec![
             Span::styled("  q", Style::default().fg(Color::Cyan)),
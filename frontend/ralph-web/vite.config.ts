@@ -1,16 +1,82 @@
 /// <reference types="vitest" />
 import { resolve, dirname } from "path";
 import { fileURLToPath } from "url";
-import { defineConfig } from "vite";
+import { timingSafeEqual } from "crypto";
+import { defineConfig, type Plugin } from "vite";
 import react from "@vitejs/plugin-react";
 import tailwindcss from "@tailwindcss/vite";
 
 const __dirname = dirname(fileURLToPath(import.meta.url));
 const backendPort = process.env.RALPH_BACKEND_PORT || "3000";
 const backendTarget = `http://localhost:${backendPort}`;
+const authToken = process.env.RALPH_WEB_TOKEN || undefined;
+// With RALPH_WEB_AUTH_READ=none only mutations (checked by the backend) need the token
+const protectReads = process.env.RALPH_WEB_AUTH_READ !== "none";
+const TOKEN_COOKIE = "ralph_web_token";
+
+function tokensEqual(candidate: string | null | undefined, expected: string): boolean {
+  if (!candidate) return false;
+  const a = Buffer.from(candidate);
+  const b = Buffer.from(expected);
+  return a.length === b.length && timingSafeEqual(a, b);
+}
+
+/**
+ * Require RALPH_WEB_TOKEN for every dashboard request.
+ *
+ * Opening `/?token=<token>` stores the token in a cookie and redirects to the
+ * clean URL; the cookie is then forwarded to the API through the proxy, which
+ * checks it again (including WebSocket upgrades, which bypass this middleware).
+ * When reads are public, pages load without the token and only the backend
+ * rejects mutations.
+ */
+function tokenAuth(token: string, protectReads: boolean): Plugin {
+  return {
+    name: "ralph-token-auth",
+    configureServer(server) {
+      server.middlewares.use((req, res, next) => {
+        const url = new URL(req.url ?? "/", "http://localhost");
+        const queryToken = url.searchParams.get("token");
+        if (tokensEqual(queryToken, token)) {
+          url.searchParams.delete("token");
+          res.statusCode = 302;
+          res.setHeader(
+            "Set-Cookie",
+            `${TOKEN_COOKIE}=${encodeURIComponent(token)}; Path=/; HttpOnly; SameSite=Strict`
+          );
+          res.setHeader("Location", url.pathname + url.search);
+          res.end();
+          return;
+        }
+
+        if (!protectReads) {
+          next();
+          return;
+        }
+
+        const cookieToken = (req.headers.cookie ?? "")
+          .split(";")
+          .map((part) => part.trim().split("="))
+          .find(([key]) => key === TOKEN_COOKIE)?.[1];
+        const bearer = req.headers.authorization?.replace(/^Bearer /, "");
+        if (
+          tokensEqual(cookieToken && decodeURIComponent(cookieToken), token) ||
+          tokensEqual(bearer, token)
+        ) {
+          next();
+          return;
+        }
+
+        res.statusCode = 401;
+        res.setHeader("Content-Type", "text/plain");
+        res.end("Unauthorized: open the dashboard URL printed by `ralph web` (it includes ?token=...)");
+      });
+    },
+  };
+}
 
 export default defineConfig({
-  plugins: [react(), tailwindcss()],
+  plugins: [react(), tailwindcss(), ...(authToken ? [tokenAuth(authToken, protectReads)] : [])],
   resolve: {
     alias: {
       "@": resolve(__dirname, "./src"),
@@ -18,13 +84,18 @@ export default defineConfig({
   },
   server: {
     port: 5173,
-    host: true, // Listen on all interfaces (0.0.0.0)
-    allowedHosts: ["studio", "localhost"],
+    host: true, // Listen on all interfaces (0.0.0.0); `ralph web` passes --host to narrow this
+    // With a token, any Host header is accepted: the token guards the dashboard instead
+    allowedHosts: authToken ? true : ["studio", "localhost"],
     proxy: {
       "/trpc": {
         target: backendTarget,
         changeOrigin: true,
       },
+      "/events": {
+        target: backendTarget,
+        changeOrigin: true,
+      },
       "/ws": {
         target: backendTarget,
         ws: true,